@@ -0,0 +1,79 @@
+//! Login to servers that require an access token.
+
+use engawa_server::infrastructure::dto::http::{AccessTokenDto, LoginRequestDto};
+
+use super::{
+    domain::{exit_code_for, login_url},
+    error::{ClientError, ExitCode},
+    tls::TlsTrust,
+};
+
+/// Prompt for the password of the client ID and log in
+///
+/// Exits the process with the code of the error if reading the password or logging in fails.
+pub async fn prompt_login(ws_url: &str, client_id: &str, tls: &TlsTrust) -> String {
+    let password = match rpassword::prompt_password(format!("Password for {}: ", client_id)) {
+        Ok(password) => password,
+        Err(e) => {
+            eprintln!("Failed to read the password: {}", e);
+            std::process::exit(ExitCode::GeneralError.code());
+        }
+    };
+    match login(ws_url, client_id, &password, tls).await {
+        Ok(token) => token,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(exit_code_for(&e).code());
+        }
+    }
+}
+
+/// Log in with the client ID and password and get an access token
+///
+/// The login endpoint is derived from the WebSocket URL of the server; `https://` endpoints
+/// trust the certificate authorities of `tls`.
+pub async fn login(
+    ws_url: &str,
+    client_id: &str,
+    password: &str,
+    tls: &TlsTrust,
+) -> Result<String, ClientError> {
+    let url = login_url(ws_url).ok_or_else(|| {
+        ClientError::ConnectionError(format!("Cannot log in to '{}': not a ws:// URL", ws_url))
+    })?;
+    let request = LoginRequestDto {
+        client_id: client_id.to_string(),
+        password: password.to_string(),
+    };
+    let response = tls
+        .http_client()
+        .post(&url)
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| ClientError::ConnectionError(e.to_string()))?;
+    match response.status().as_u16() {
+        200 => {}
+        401 => {
+            return Err(ClientError::AuthenticationFailed(
+                "wrong client ID or password".to_string(),
+            ));
+        }
+        404 => {
+            return Err(ClientError::AuthenticationFailed(
+                "the server does not require logging in".to_string(),
+            ));
+        }
+        status => {
+            return Err(ClientError::ConnectionError(format!(
+                "Login rejected with HTTP {}",
+                status
+            )));
+        }
+    }
+    let token: AccessTokenDto = response
+        .json()
+        .await
+        .map_err(|e| ClientError::ConnectionError(e.to_string()))?;
+    Ok(token.access_token)
+}
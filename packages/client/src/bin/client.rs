@@ -1,43 +1,318 @@
 //! Simple WebSocket chat client with client ID and reconnection support.
 //!
-//! Connects to a WebSocket chat server and sends messages from stdin.
-//! Displays ">" prompt and waits for input, then sends with message type "chat".
-//! Automatically reconnects on disconnection (max 5 attempts with 5 second interval).
+//! Connects to a WebSocket chat server and sends the messages typed in a full-screen terminal
+//! UI (message pane, participant sidebar, input line and connection status bar). With
+//! `--ui plain`, `--accessible` or when stdin/stdout is not a terminal, it displays a ">"
+//! prompt instead and sends each line read from stdin with message type "chat".
+//! Automatically reconnects on disconnection with exponential backoff (5 retries in a row by
+//! default, see `--max-retries`); lines typed while disconnected are sent after reconnecting.
 //! Duplicate client_id connections are rejected by the server.
 //!
+//! Exit codes (stable, for scripting):
+//! - 0: session ended normally
+//! - 1: unexpected error
+//! - 2: client_id is already connected
+//! - 3: room is full
+//! - 4: authentication failed
+//! - 5: connection lost after all reconnect attempts
+//! - 6: session replaced by a newer connection
+//! - 7: no room with the requested slug
+//! - 8: kicked, banned or disconnected by an admin
+//! - 9: join request rejected by a moderator
+//!
 //! Run with:
 //! ```not_rust
 //! cargo run --bin client -- --client-id Alice
 //! cargo run --bin client -- -c Bob
+//! cargo run --bin client -- -c Carol --room general
+//! cargo run --bin client -- -c Dave --accessible
+//! cargo run --bin client -- -c Dave --ui plain
+//! cargo run --bin client -- -c Erin --config client.json
+//! cargo run --bin client -- -c Frank --login
+//! cargo run --bin client -- -c Grace --token eyJhbGciOi...
+//! cargo run --bin client -- -c Heidi --max-retries 20
+//! cargo run --bin client -- -c Ivan --wire-log wire.jsonl --wire-log-redact content
+//! cargo run --bin client -- -c Judy --history-cache ~/.cache/engawa
+//! cargo run --bin client -- replay wire.jsonl --speed 2
+//! cargo run --bin client -- history ~/.cache/engawa --room general --search deploy
 //! ```
 
-use clap::Parser;
-use engawa_client::run;
-use engawa_shared::logger::setup_logger;
+use std::{
+    io::IsTerminal,
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, Subcommand};
+use engawa_client::{
+    ClientConfig, Endpoint, ExitCode, OutputMode, TlsTrust, UiMode, load_recording, prompt_login,
+    read_cached_history, replay, run,
+};
+use engawa_server::{domain::Locale, infrastructure::dedup::DEFAULT_DEDUP_WINDOW};
+use engawa_shared::logger::{LogArgs, setup_logger, setup_logger_with_writer};
 
 #[derive(Parser, Debug)]
 #[command(name = "client")]
 #[command(about = "WebSocket chat client with broadcast support and unique client ID", long_about = None)]
+#[command(subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Client ID for identifying messages (must be unique)
-    #[arg(short = 'c', long)]
-    client_id: String,
+    #[arg(short = 'c', long, required = true)]
+    client_id: Option<String>,
 
-    /// WebSocket server URL
+    /// Access token for servers that require authentication (see --login)
+    #[arg(long, conflicts_with = "login")]
+    token: Option<String>,
+
+    /// Prompt for the password of the client ID and log in before connecting
+    #[arg(long)]
+    login: bool,
+
+    /// WebSocket server URL (ws:// or wss://)
     #[arg(short = 'u', long, default_value = "ws://127.0.0.1:8080/ws")]
     url: String,
+
+    /// PEM file of a CA certificate to trust on wss:// (e.g. of a self-signed server certificate)
+    #[arg(long, global = true)]
+    ca_cert: Option<PathBuf>,
+
+    /// Name to show the client as instead of its client ID (e.g. "Alice Smith"); change it with
+    /// /nick once connected [default: `display_name` in --config]
+    #[arg(long)]
+    display_name: Option<String>,
+
+    /// Slug of the room to join (e.g. general); exits with code 7 if no room has it
+    #[arg(short = 'r', long)]
+    room: Option<String>,
+
+    /// Language of system notices such as join/leave ("en", "ja"); defaults to the room's
+    #[arg(long)]
+    locale: Option<Locale>,
+
+    /// Screen-reader friendly output: one labeled line per event, no separators or prompt redraws
+    #[arg(long)]
+    accessible: bool,
+
+    /// Terminal UI: "tui" (panes, the default) or "plain" (printed lines and a prompt);
+    /// --accessible and non-terminal stdin/stdout always use plain [default: `ui` in --config]
+    #[arg(long)]
+    ui: Option<UiMode>,
+
+    /// JSON configuration file (e.g. `{"quiet_hours": ["22:00-07:00"]}` to mute mention bells)
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Reconnection attempts in a row before giving up [default: 5, or `max_retries` in --config]
+    #[arg(long)]
+    max_retries: Option<u32>,
+
+    /// Number of message sequence numbers remembered to skip messages re-sent after reconnecting
+    #[arg(long, default_value_t = DEFAULT_DEDUP_WINDOW)]
+    dedup_window: usize,
+
+    /// File (JSON Lines) recording every WebSocket frame sent and received, for protocol
+    /// debugging; print it with `engawa-server wire-log dump`
+    #[arg(long)]
+    wire_log: Option<PathBuf>,
+
+    /// JSON fields (comma separated, e.g. content) whose values are redacted in --wire-log
+    #[arg(long, value_delimiter = ',')]
+    wire_log_redact: Vec<String>,
+
+    /// Show the round trip to the server in the prompt (e.g. `alice [42ms]> `), measured every
+    /// 10 seconds; /ping measures it on demand either way
+    #[arg(long)]
+    show_latency: bool,
+
+    /// Directory caching the messages of each room, shown when joining it again and readable
+    /// while the server is unreachable (see the `history` subcommand) [default: `history_cache`
+    /// in --config]
+    #[arg(long)]
+    history_cache: Option<PathBuf>,
+
+    #[command(flatten)]
+    log: LogArgs,
+}
+
+/// Subcommands (the chat client runs when none is given)
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Send the frames of a wire log or an exported room history to a server again
+    Replay {
+        /// Wire log (--wire-log) or room history (GET /api/v1/rooms/{room_id}/messages)
+        path: PathBuf,
+
+        /// WebSocket server URL
+        #[arg(short = 'u', long, default_value = "ws://127.0.0.1:8080/ws")]
+        url: String,
+
+        /// Replay speed relative to the recording (2 is twice as fast, 0 sends without waiting)
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+    },
+
+    /// Print the messages of a room cached with --history-cache, without connecting
+    History {
+        /// Directory of the cache (--history-cache)
+        cache: PathBuf,
+
+        /// Slug of the room (the default room if neither --room nor --room-id is given)
+        #[arg(short = 'r', long, conflicts_with = "room_id")]
+        room: Option<String>,
+
+        /// ID of the room
+        #[arg(long)]
+        room_id: Option<String>,
+
+        /// Only print the messages containing this text, newest first
+        #[arg(long)]
+        search: Option<String>,
+    },
+}
+
+/// Run `replay` and return the exit code
+async fn run_replay(path: &Path, url: &str, speed: f64, tls: &TlsTrust) -> i32 {
+    let frames = match load_recording(path) {
+        Ok(frames) => frames,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::GeneralError.code();
+        }
+    };
+    match replay(url, frames, speed, tls).await {
+        Ok(summary) => {
+            println!(
+                "Replayed {} frames from {} clients in {:.1?} ({} errors)",
+                summary.frames, summary.clients, summary.elapsed, summary.errors
+            );
+            ExitCode::Success.code()
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::ConnectionLost.code()
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() {
-    // Initialize tracing
-    setup_logger(env!("CARGO_BIN_NAME"), "info");
-
     let args = Args::parse();
 
+    let mut config = match &args.config {
+        Some(path) if args.command.is_none() => match ClientConfig::load(path) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(ExitCode::GeneralError.code());
+            }
+        },
+        _ => ClientConfig::default(),
+    };
+    if let Some(ui) = args.ui {
+        config.ui = ui;
+    }
+    // The panes need a terminal to draw on, and screen readers follow printed lines better
+    let is_terminal = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+    if args.accessible || !is_terminal || args.command.is_some() {
+        config.ui = UiMode::Plain;
+    }
+
+    // Initialize tracing (the terminal UI owns the screen, so logs are dropped there)
+    let logger = match config.ui {
+        UiMode::Tui => {
+            setup_logger_with_writer(env!("CARGO_BIN_NAME"), "info", &args.log, std::io::sink)
+        }
+        UiMode::Plain => setup_logger(env!("CARGO_BIN_NAME"), "info", &args.log),
+    };
+    if let Err(e) = logger {
+        eprintln!("invalid --log-filter: {}", e);
+        std::process::exit(ExitCode::GeneralError.code());
+    }
+
+    let tls = match TlsTrust::load(args.ca_cert.as_deref()) {
+        Ok(tls) => tls,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(ExitCode::GeneralError.code());
+        }
+    };
+
+    match args.command {
+        Some(Command::Replay { path, url, speed }) => {
+            std::process::exit(run_replay(&path, &url, speed, &tls).await);
+        }
+        Some(Command::History {
+            cache,
+            room,
+            room_id,
+            search,
+        }) => match read_cached_history(&cache, room, room_id, search.as_deref()) {
+            Ok(history) => {
+                println!("{}", history);
+                std::process::exit(ExitCode::Success.code());
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(ExitCode::GeneralError.code());
+            }
+        },
+        None => {}
+    }
+    let client_id = args
+        .client_id
+        .expect("--client-id is required without a subcommand");
+
+    if let Some(max_retries) = args.max_retries {
+        config.max_retries = max_retries;
+    }
+    if args.wire_log.is_some() {
+        config.wire_log = args.wire_log;
+    }
+    if !args.wire_log_redact.is_empty() {
+        config.wire_log_redact = args.wire_log_redact;
+    }
+    if args.show_latency {
+        config.show_latency = true;
+    }
+    if args.history_cache.is_some() {
+        config.history_cache = args.history_cache;
+    }
+    if args.display_name.is_some() {
+        config.display_name = args.display_name;
+    }
+
+    // Log in as the client ID when asked to; the token is reused when reconnecting
+    let token = if args.login {
+        Some(prompt_login(&args.url, &client_id, &tls).await)
+    } else {
+        args.token
+    };
+
+    let mode = if args.accessible {
+        OutputMode::Accessible
+    } else {
+        OutputMode::Standard
+    };
+
     // Run the client
-    if let Err(e) = run(args.url, args.client_id).await {
+    if let Err(e) = run(
+        Endpoint {
+            url: args.url,
+            token,
+            tls,
+        },
+        client_id,
+        args.room,
+        args.locale,
+        mode,
+        args.dedup_window,
+        config,
+    )
+    .await
+    {
         tracing::error!("Client error: {}", e);
-        std::process::exit(1);
+        std::process::exit(ExitCode::GeneralError.code());
     }
 }
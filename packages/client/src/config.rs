@@ -0,0 +1,162 @@
+//! Client configuration file.
+//!
+//! Settings that are awkward to pass as flags are read from a JSON file given with `--config`:
+//!
+//! ```json
+//! { "quiet_hours": ["22:00-07:00", "12:00-13:00"], "max_retries": 10 }
+//! ```
+//!
+//! The prompt and the colors of the terminal UI are customized there too:
+//!
+//! ```json
+//! { "prompt": "[{room}] {client_id} ({unread})> ", "theme": "light" }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::{
+    domain::{PromptTemplate, QuietHours},
+    error::ConfigError,
+    ui::{Theme, UiMode},
+};
+
+/// Reconnection attempts after losing the connection before the client gives up
+pub const DEFAULT_MAX_RETRIES: u32 = 5;
+
+/// Settings read from the client configuration file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientConfig {
+    /// Daily windows (JST) during which mention bells are muted
+    pub quiet_hours: Vec<QuietHours>,
+    /// Reconnection attempts in a row before giving up (`--max-retries` overrides it)
+    pub max_retries: u32,
+    /// File recording the WebSocket frames sent and received (`--wire-log` overrides it)
+    pub wire_log: Option<PathBuf>,
+    /// JSON fields whose values are redacted in the wire log (`--wire-log-redact` overrides it)
+    pub wire_log_redact: Vec<String>,
+    /// Whether the prompt shows the latest round trip to the server (`--show-latency` enables it)
+    pub show_latency: bool,
+    /// How the chat uses the terminal (`--ui` overrides it)
+    pub ui: UiMode,
+    /// Name to show the client as instead of its client ID (`--display-name` overrides it)
+    pub display_name: Option<String>,
+    /// Directory caching the messages received in each room (`--history-cache` overrides it)
+    pub history_cache: Option<PathBuf>,
+    /// Template of the prompt in plain mode, with the placeholders `{client_id}`, `{room}`,
+    /// `{unread}` and `{latency}` (`{client_id}> ` if unset)
+    pub prompt: Option<PromptTemplate>,
+    /// Colors of the terminal UI: `dark` (default), `light`, `mono` or custom colors
+    pub theme: Theme,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            quiet_hours: Vec::new(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            wire_log: None,
+            wire_log_redact: Vec::new(),
+            show_latency: false,
+            ui: UiMode::default(),
+            display_name: None,
+            history_cache: None,
+            prompt: None,
+            theme: Theme::default(),
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Load the configuration from a JSON file
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let json = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.display().to_string(),
+            source,
+        })?;
+        serde_json::from_str(&json).map_err(|source| ConfigError::Parse {
+            path: path.display().to_string(),
+            source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Color;
+
+    use super::*;
+
+    #[test]
+    fn test_load_config_file() {
+        // テスト項目: 設定ファイルの quiet_hours と max_retries と ui が読み込まれ、不正な時間帯はエラーになる
+        // given (前提条件):
+        let dir = std::env::temp_dir().join(format!("engawa-client-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let valid = dir.join("valid.json");
+        let invalid = dir.join("invalid.json");
+        std::fs::write(
+            &valid,
+            r#"{"quiet_hours": ["22:00-07:00"], "max_retries": 10, "ui": "plain"}"#,
+        )
+        .unwrap();
+        std::fs::write(&invalid, r#"{"quiet_hours": ["22:00"]}"#).unwrap();
+
+        // when (操作):
+        let config = ClientConfig::load(&valid).unwrap();
+        let error = ClientConfig::load(&invalid).unwrap_err();
+        let missing = ClientConfig::load(&dir.join("missing.json")).unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // then (期待する結果):
+        assert_eq!(config.quiet_hours, vec!["22:00-07:00".parse().unwrap()]);
+        assert_eq!(config.max_retries, 10);
+        assert_eq!(config.ui, UiMode::Plain);
+        assert!(matches!(error, ConfigError::Parse { .. }));
+        assert!(error.to_string().contains("Invalid quiet hours '22:00'"));
+        assert!(matches!(missing, ConfigError::Read { .. }));
+    }
+
+    #[test]
+    fn test_load_prompt_and_theme() {
+        // テスト項目: 設定ファイルのプロンプトとテーマ（組み込みのテーマと色の上書き）が読み込まれ、不正なテーマはエラーになる
+        // given (前提条件):
+        let dir = std::env::temp_dir().join(format!("engawa-client-theme-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let named = dir.join("named.json");
+        let custom = dir.join("custom.json");
+        let invalid = dir.join("invalid.json");
+        std::fs::write(
+            &named,
+            r#"{"prompt": "{client_id}@{room}> ", "theme": "mono"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            &custom,
+            r##"{"theme": {"base": "light", "sender": "#005f87"}}"##,
+        )
+        .unwrap();
+        std::fs::write(&invalid, r#"{"theme": "solarized"}"#).unwrap();
+
+        // when (操作):
+        let named = ClientConfig::load(&named).unwrap();
+        let custom = ClientConfig::load(&custom).unwrap();
+        let error = ClientConfig::load(&invalid).unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // then (期待する結果):
+        assert_eq!(named.prompt, Some("{client_id}@{room}> ".parse().unwrap()));
+        assert_eq!(named.theme, Theme::MONO);
+        assert_eq!(custom.prompt, None);
+        assert_eq!(
+            custom.theme,
+            Theme {
+                sender: Color::Rgb(0x00, 0x5f, 0x87),
+                ..Theme::LIGHT
+            }
+        );
+        assert!(error.to_string().contains("unknown theme 'solarized'"));
+    }
+}
@@ -5,7 +5,23 @@
 
 #![allow(dead_code)]
 
-use super::error::ClientError;
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use chrono::NaiveTime;
+use engawa_server::{
+    domain::Locale,
+    infrastructure::{dedup::DedupWindow, i18n::SystemText},
+};
+use serde::Deserialize;
+
+use super::{
+    error::{ClientError, ConfigError, ExitCode},
+    tls::TlsTrust,
+};
 
 /// Check if the client should exit immediately based on the error type.
 ///
@@ -18,7 +34,210 @@ use super::error::ClientError;
 /// `true` if the error requires immediate exit (e.g., DuplicateClientId),
 /// `false` otherwise
 pub fn should_exit_immediately(error: &ClientError) -> bool {
-    matches!(error, ClientError::DuplicateClientId(_))
+    matches!(
+        error,
+        ClientError::DuplicateClientId(_)
+            | ClientError::RoomFull
+            | ClientError::AuthenticationFailed(_)
+            | ClientError::SessionReplaced
+            | ClientError::Removed { .. }
+            | ClientError::JoinRejected
+            | ClientError::RoomNotFound(_)
+    )
+}
+
+/// Map a client error to the process exit code.
+///
+/// # Arguments
+///
+/// * `error` - The client error that terminated the client
+///
+/// # Returns
+///
+/// The documented exit code for the error
+pub fn exit_code_for(error: &ClientError) -> ExitCode {
+    match error {
+        ClientError::DuplicateClientId(_) => ExitCode::DuplicateClientId,
+        ClientError::RoomFull => ExitCode::RoomFull,
+        ClientError::AuthenticationFailed(_) => ExitCode::AuthenticationFailed,
+        ClientError::SessionReplaced => ExitCode::SessionReplaced,
+        ClientError::Removed { .. } => ExitCode::Removed,
+        ClientError::JoinRejected => ExitCode::JoinRejected,
+        ClientError::RoomNotFound(_) => ExitCode::RoomNotFound,
+        ClientError::InvalidRecording(_)
+        | ClientError::HistoryCache(_)
+        | ClientError::SwitchingRoom(_) => ExitCode::GeneralError,
+        ClientError::ConnectionError(_)
+        | ClientError::ConnectionLost
+        | ClientError::ServerRestarting(_) => ExitCode::ConnectionLost,
+    }
+}
+
+/// Classify the HTTP status of a rejected WebSocket handshake.
+///
+/// # Arguments
+///
+/// * `status` - HTTP status code returned by the server
+/// * `client_id` - The client ID used for the connection attempt
+/// * `room_slug` - The room slug used for the connection attempt, if any
+///
+/// # Returns
+///
+/// The client error corresponding to the status code
+pub fn classify_handshake_status(
+    status: u16,
+    client_id: &str,
+    room_slug: Option<&str>,
+) -> ClientError {
+    match (status, room_slug) {
+        (404, Some(slug)) => ClientError::RoomNotFound(slug.to_string()),
+        (409, _) => ClientError::DuplicateClientId(client_id.to_string()),
+        (503, _) => ClientError::RoomFull,
+        (401 | 403, _) => ClientError::AuthenticationFailed(format!("HTTP {}", status)),
+        _ => ClientError::ConnectionError(format!("Handshake rejected with HTTP {}", status)),
+    }
+}
+
+/// Server the client connects to.
+#[derive(Debug, Clone)]
+pub struct Endpoint {
+    /// WebSocket URL of the server
+    pub url: String,
+    /// Access token sent as `Authorization: Bearer <token>` to servers requiring one
+    pub token: Option<String>,
+    /// Certificate authorities trusted by `wss://` URLs
+    pub tls: TlsTrust,
+}
+
+/// Room the client connects to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RoomTarget {
+    /// Default room of the server
+    #[default]
+    Default,
+    /// Room with the slug given with `--room`
+    Slug(String),
+    /// Room with the ID chosen with `/join` or `/create`
+    Id(String),
+}
+
+impl RoomTarget {
+    /// Query parameter selecting the room in the connection URL (empty for the default room).
+    pub fn query(&self) -> String {
+        match self {
+            RoomTarget::Default => String::new(),
+            RoomTarget::Slug(slug) => format!("&room_slug={}", slug),
+            RoomTarget::Id(room_id) => format!("&room_id={}", room_id),
+        }
+    }
+
+    /// Slug of the room, reported when no room has it.
+    pub fn slug(&self) -> Option<&str> {
+        match self {
+            RoomTarget::Slug(slug) => Some(slug),
+            _ => None,
+        }
+    }
+}
+
+/// Derive the login endpoint of the server from its WebSocket URL.
+///
+/// # Arguments
+///
+/// * `ws_url` - WebSocket URL of the server (e.g. `ws://127.0.0.1:8080/ws`)
+///
+/// # Returns
+///
+/// The URL of `POST /api/v1/auth/login` on the same host, or `None` if the URL is not a
+/// `ws://` or `wss://` URL
+pub fn login_url(ws_url: &str) -> Option<String> {
+    Some(format!("{}/api/v1/auth/login", http_origin(ws_url)?))
+}
+
+/// Derive the endpoint listing the messages a client starred from the server's WebSocket URL.
+///
+/// # Returns
+///
+/// The URL of `GET /api/v1/users/{client_id}/starred` on the same host, or `None` if the URL
+/// is not a `ws://` or `wss://` URL
+pub fn starred_url(ws_url: &str, client_id: &str) -> Option<String> {
+    Some(format!(
+        "{}/api/v1/users/{}/starred",
+        http_origin(ws_url)?,
+        client_id
+    ))
+}
+
+/// Derive the endpoint searching the messages of a room from the server's WebSocket URL.
+///
+/// # Returns
+///
+/// The URL of `GET /api/v1/rooms/{room_id}/search` on the same host, or `None` if the URL is
+/// not a `ws://` or `wss://` URL
+pub fn room_search_url(ws_url: &str, room_id: &str) -> Option<String> {
+    Some(format!(
+        "{}/api/v1/rooms/{}/search",
+        http_origin(ws_url)?,
+        room_id
+    ))
+}
+
+/// Query parameter asking the server to show the client under a display name.
+///
+/// # Arguments
+///
+/// * `display_name` - Name to show the client as, if any
+///
+/// # Returns
+///
+/// `&display_name=<name>` with the name percent-encoded, or an empty string without a name
+pub fn display_name_query(display_name: Option<&str>) -> String {
+    let Some(name) = display_name else {
+        return String::new();
+    };
+    let mut query = String::from("&display_name=");
+    for byte in name.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            query.push(byte as char);
+        } else {
+            query.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    query
+}
+
+/// Derive the endpoint creating rooms (`POST`) from the server's WebSocket URL.
+///
+/// # Returns
+///
+/// The URL of `/api/v1/rooms` on the same host, or `None` if the URL is not a `ws://` or
+/// `wss://` URL
+pub fn rooms_url(ws_url: &str) -> Option<String> {
+    Some(format!("{}/api/v1/rooms", http_origin(ws_url)?))
+}
+
+/// Derive the endpoint of a room's detail from the server's WebSocket URL.
+///
+/// # Returns
+///
+/// The URL of `GET /api/v1/rooms/{room_id}` on the same host, or `None` if the URL is not a
+/// `ws://` or `wss://` URL
+pub fn room_url(ws_url: &str, room_id: &str) -> Option<String> {
+    Some(format!("{}/api/v1/rooms/{}", http_origin(ws_url)?, room_id))
+}
+
+/// `http://host` or `https://host` of a `ws://` or `wss://` URL
+fn http_origin(ws_url: &str) -> Option<String> {
+    let (scheme, rest) = if let Some(rest) = ws_url.strip_prefix("ws://") {
+        ("http", rest)
+    } else {
+        ("https", ws_url.strip_prefix("wss://")?)
+    };
+    let host = rest
+        .split(['/', '?'])
+        .next()
+        .filter(|host| !host.is_empty())?;
+    Some(format!("{}://{}", scheme, host))
 }
 
 /// Check if the client should attempt to reconnect.
@@ -46,6 +265,785 @@ pub fn should_attempt_reconnect(
     current_attempt < max_attempts
 }
 
+/// Delay before the first reconnection attempt.
+pub const RECONNECT_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Upper bound of the delay between reconnection attempts.
+pub const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Delay before a reconnection attempt (exponential backoff with jitter).
+///
+/// The backoff doubles with each attempt up to [`RECONNECT_MAX_DELAY`], and `jitter` picks the
+/// delay within its upper half so that clients dropped at the same time do not all reconnect
+/// at the same time.
+///
+/// # Arguments
+///
+/// * `attempt` - The reconnection attempt count (0-indexed)
+/// * `jitter` - Random value between 0.0 and 1.0
+///
+/// # Returns
+///
+/// The time to wait before the attempt
+pub fn reconnect_delay(attempt: u32, jitter: f64) -> Duration {
+    let backoff = RECONNECT_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(RECONNECT_MAX_DELAY);
+    backoff / 2 + backoff.mul_f64(jitter.clamp(0.0, 1.0) / 2.0)
+}
+
+/// Pick the text of a system notice (join/leave notices etc.).
+///
+/// # Arguments
+///
+/// * `locale` - Locale chosen on the client; overrides the room's locale
+/// * `text` - The system text to show
+/// * `server_notice` - Notice the server rendered in the room's locale
+///
+/// # Returns
+///
+/// The localized text, or `None` to use the default format
+pub fn localized_notice(
+    locale: Option<Locale>,
+    text: SystemText<'_>,
+    server_notice: Option<String>,
+) -> Option<String> {
+    match locale {
+        Some(locale) => Some(text.localize(locale)),
+        None => server_notice,
+    }
+}
+
+/// Command typed at the prompt to list the rooms on the server.
+pub const LIST_ROOMS_COMMAND: &str = "/rooms";
+
+/// Command typed at the prompt to measure the round trip to the server.
+pub const PING_COMMAND: &str = "/ping";
+
+/// Command typed at the prompt to post a poll: `/poll "Question" option1 option2 ...`.
+pub const POLL_COMMAND: &str = "/poll";
+
+/// Command typed at the prompt to vote on a poll: `/vote <seq> <option number>`.
+pub const VOTE_COMMAND: &str = "/vote";
+
+/// Command typed at the prompt to copy a message into another room: `/forward <seq> <room>`.
+pub const FORWARD_COMMAND: &str = "/forward";
+
+/// Command typed at the prompt to star a message of the room: `/star <seq>`.
+pub const STAR_COMMAND: &str = "/star";
+
+/// Command typed at the prompt to remove the star of a message: `/unstar <seq>`.
+pub const UNSTAR_COMMAND: &str = "/unstar";
+
+/// Command typed at the prompt to list the messages the client starred.
+pub const STARRED_COMMAND: &str = "/starred";
+
+/// Command typed at the prompt to search the messages of the room: `/search <text>`.
+pub const SEARCH_COMMAND: &str = "/search";
+
+/// Command typed at the prompt to scroll back to a message found with `/search`:
+/// `/jump <seq>`.
+pub const JUMP_COMMAND: &str = "/jump";
+
+/// Command typed at the prompt to show what the client is doing: `/activity <text>` (clears it
+/// without text).
+pub const ACTIVITY_COMMAND: &str = "/activity";
+
+/// Command typed at the prompt to create a room and join it: `/create <name>`.
+pub const CREATE_ROOM_COMMAND: &str = "/create";
+
+/// Command typed at the prompt to leave the room and join another one: `/join <room ID>`.
+pub const JOIN_ROOM_COMMAND: &str = "/join";
+
+/// Command typed at the prompt to change the name the client is shown as: `/nick <name>`
+/// (shows the client ID again without a name).
+pub const NICK_COMMAND: &str = "/nick";
+
+/// Line typed at the prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    /// Chat message to send to the room
+    Chat(String),
+    /// Request for the rooms on the server
+    ListRooms,
+    /// Measurement of the round trip to the server
+    Ping,
+    /// Poll to post to the room
+    Poll {
+        question: String,
+        options: Vec<String>,
+    },
+    /// Vote on a poll (`option` starts from 0, while the prompt counts from 1)
+    Vote { seq: u64, option: usize },
+    /// Copy of a message of the room into another room the client is in
+    Forward { seq: u64, room_id: String },
+    /// Star (`starred: true`) or unstar a message of the room
+    Star { seq: u64, starred: bool },
+    /// Request for the messages the client starred
+    ListStarred,
+    /// Search of the messages of the room containing the text
+    Search(String),
+    /// Scroll back to a message of the room
+    Jump { seq: u64 },
+    /// Activity to show next to the client in participant listings (`None` clears it)
+    SetActivity(Option<String>),
+    /// Name to show the client as instead of its client ID (`None` clears it)
+    SetDisplayName(Option<String>),
+    /// Creation of a room named with the slug, to join it once created
+    CreateRoom(String),
+    /// Switch to the room with the ID
+    JoinRoom(String),
+    /// Command typed with wrong arguments, with how to use it
+    Usage(&'static str),
+}
+
+impl Input {
+    /// Parse a (trimmed) line typed at the prompt.
+    ///
+    /// Lines other than known commands are sent as chat messages. Arguments of `/poll` are
+    /// separated by spaces; wrap one in double quotes to keep its spaces.
+    pub fn parse(line: &str) -> Self {
+        match line {
+            LIST_ROOMS_COMMAND => return Input::ListRooms,
+            PING_COMMAND => return Input::Ping,
+            STARRED_COMMAND => return Input::ListStarred,
+            _ => {}
+        }
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+        match command {
+            POLL_COMMAND => match split_quoted(args).split_first() {
+                Some((question, options)) if options.len() >= 2 => Input::Poll {
+                    question: question.clone(),
+                    options: options.to_vec(),
+                },
+                _ => Input::Usage("/poll \"Question\" option1 option2 ..."),
+            },
+            VOTE_COMMAND => {
+                let mut args = args.split_whitespace();
+                let seq = args
+                    .next()
+                    .and_then(|seq| seq.trim_start_matches('#').parse().ok());
+                let option = args.next().and_then(|option| option.parse::<usize>().ok());
+                match (seq, option, args.next()) {
+                    (Some(seq), Some(option), None) if option > 0 => Input::Vote {
+                        seq,
+                        option: option - 1,
+                    },
+                    _ => Input::Usage("/vote <message number> <option number>"),
+                }
+            }
+            FORWARD_COMMAND => {
+                let mut args = args.split_whitespace();
+                let seq = args
+                    .next()
+                    .and_then(|seq| seq.trim_start_matches('#').parse().ok());
+                match (seq, args.next(), args.next()) {
+                    (Some(seq), Some(room_id), None) => Input::Forward {
+                        seq,
+                        room_id: room_id.to_string(),
+                    },
+                    _ => Input::Usage("/forward <message number> <room ID>"),
+                }
+            }
+            STAR_COMMAND | UNSTAR_COMMAND => {
+                let mut args = args.split_whitespace();
+                let seq = args
+                    .next()
+                    .and_then(|seq| seq.trim_start_matches('#').parse().ok());
+                match (seq, args.next()) {
+                    (Some(seq), None) => Input::Star {
+                        seq,
+                        starred: command == STAR_COMMAND,
+                    },
+                    _ if command == STAR_COMMAND => Input::Usage("/star <message number>"),
+                    _ => Input::Usage("/unstar <message number>"),
+                }
+            }
+            SEARCH_COMMAND => {
+                let query = args.trim();
+                if query.is_empty() {
+                    Input::Usage("/search <text>")
+                } else {
+                    Input::Search(query.to_string())
+                }
+            }
+            JUMP_COMMAND => {
+                let mut args = args.split_whitespace();
+                let seq = args
+                    .next()
+                    .and_then(|seq| seq.trim_start_matches('#').parse().ok());
+                match (seq, args.next()) {
+                    (Some(seq), None) => Input::Jump { seq },
+                    _ => Input::Usage("/jump <message number>"),
+                }
+            }
+            ACTIVITY_COMMAND => {
+                let activity = args.trim();
+                Input::SetActivity((!activity.is_empty()).then(|| activity.to_string()))
+            }
+            CREATE_ROOM_COMMAND | JOIN_ROOM_COMMAND => {
+                let mut args = args.split_whitespace();
+                match (args.next(), args.next()) {
+                    (Some(name), None) if command == CREATE_ROOM_COMMAND => {
+                        Input::CreateRoom(name.to_string())
+                    }
+                    (Some(room_id), None) => Input::JoinRoom(room_id.to_string()),
+                    _ if command == CREATE_ROOM_COMMAND => Input::Usage("/create <name>"),
+                    _ => Input::Usage("/join <room ID>"),
+                }
+            }
+            NICK_COMMAND => {
+                let name = args.trim();
+                Input::SetDisplayName((!name.is_empty()).then(|| name.to_string()))
+            }
+            _ => Input::Chat(line.to_string()),
+        }
+    }
+}
+
+/// Split arguments at spaces, keeping the spaces of those wrapped in double quotes
+fn split_quoted(args: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    let mut started = false;
+    for c in args.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    words.push(std::mem::take(&mut word));
+                    started = false;
+                }
+            }
+            c => {
+                word.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        words.push(word);
+    }
+    words
+}
+
+/// Resume position kept across reconnections.
+///
+/// The server gives a resume token in `room-connected`; sending it back with the latest
+/// received sequence number lets the server skip messages the client already has, and the
+/// dedup window drops any that are still re-sent.
+#[derive(Debug, Clone)]
+pub struct ResumeState {
+    token: Option<String>,
+    window: DedupWindow,
+}
+
+impl ResumeState {
+    /// Create a resume state remembering `window_size` sequence numbers.
+    pub fn new(window_size: usize) -> Self {
+        Self {
+            token: None,
+            window: DedupWindow::new(window_size),
+        }
+    }
+
+    /// Query parameters to append to the connection URL (empty before the first connection).
+    pub fn query(&self) -> String {
+        match &self.token {
+            Some(token) => format!(
+                "&resume_token={}&last_seq={}",
+                token,
+                self.window.last_seq()
+            ),
+            None => String::new(),
+        }
+    }
+
+    /// Record the resume token of a new connection.
+    ///
+    /// A different token means the room was recreated, so the sequence numbers start over.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Resume token of the new connection
+    /// * `last_seq` - Sequence number of the latest message in the room
+    ///
+    /// # Returns
+    ///
+    /// The sequence number to request a backfill from, if messages were sent to the same room
+    /// while the client was disconnected
+    pub fn on_room_connected(
+        &mut self,
+        token: Option<String>,
+        last_seq: Option<u64>,
+    ) -> Option<u64> {
+        let resumed = token.is_some() && token == self.token;
+        if !resumed {
+            self.window.reset();
+        }
+        self.token = token;
+
+        let received = self.window.last_seq();
+        (resumed && last_seq.is_some_and(|last_seq| last_seq > received)).then_some(received)
+    }
+
+    /// Latest sequence number received (0 before any message of the room).
+    pub fn last_seq(&self) -> u64 {
+        self.window.last_seq()
+    }
+
+    /// Check whether a chat message should be rendered.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the message was already rendered; messages without a sequence number are
+    /// always rendered
+    pub fn accept(&mut self, seq: Option<u64>) -> bool {
+        seq.is_none_or(|seq| self.window.insert(seq))
+    }
+}
+
+/// Check whether a chat message mentions the client as `@client_id`.
+///
+/// The mention must not be followed by another character of a client ID, so that `@al` does
+/// not match a message for `@alice`.
+pub fn mentions(content: &str, client_id: &str) -> bool {
+    let mention = format!("@{}", client_id);
+    content.match_indices(&mention).any(|(index, _)| {
+        content[index + mention.len()..]
+            .chars()
+            .next()
+            .is_none_or(|next| !(next.is_alphanumeric() || next == '-' || next == '_'))
+    })
+}
+
+/// Participants currently typing, in the order they started.
+#[derive(Debug, Clone, Default)]
+pub struct TypingIndicators {
+    typing: Vec<String>,
+}
+
+impl TypingIndicators {
+    /// Record that a participant started typing.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the participant was not typing already (the indicator should be shown)
+    pub fn start(&mut self, client_id: &str) -> bool {
+        if self.typing.iter().any(|id| id == client_id) {
+            return false;
+        }
+        self.typing.push(client_id.to_string());
+        true
+    }
+
+    /// Record that a participant stopped typing, sent a message or left.
+    pub fn stop(&mut self, client_id: &str) {
+        self.typing.retain(|id| id != client_id);
+    }
+
+    /// Participants currently typing.
+    pub fn participants(&self) -> &[String] {
+        &self.typing
+    }
+}
+
+/// Pace of the messages sent to the server, slowed down when the server asks.
+///
+/// The server sends `slow-down` with the minimum interval between messages when this client's
+/// send queue backs up, and again with an interval of zero once it has drained.
+#[derive(Debug, Clone, Default)]
+pub struct SendThrottle {
+    interval: Duration,
+    last_sent: Option<Instant>,
+}
+
+impl SendThrottle {
+    /// Apply a `slow-down` advice (`Duration::ZERO` lifts it).
+    ///
+    /// # Returns
+    ///
+    /// `true` if the pace changed (the change should be shown)
+    pub fn advise(&mut self, interval: Duration) -> bool {
+        let changed = self.interval != interval;
+        self.interval = interval;
+        changed
+    }
+
+    /// Time to wait at `now` before sending the next message.
+    pub fn wait(&self, now: Instant) -> Duration {
+        self.last_sent
+            .map(|sent_at| (sent_at + self.interval).saturating_duration_since(now))
+            .unwrap_or_default()
+    }
+
+    /// Record that a message was sent at `now`.
+    pub fn sent(&mut self, now: Instant) {
+        self.last_sent = Some(now);
+    }
+}
+
+/// Round trip to the server, measured with WebSocket Ping frames.
+///
+/// Each probe carries its own number as the Ping payload, so that a late Pong answering an
+/// earlier probe is not taken for the answer to the latest one.
+#[derive(Debug, Clone, Default)]
+pub struct LatencyMeter {
+    next_probe: u64,
+    pending: Option<(u64, Instant)>,
+    requested: bool,
+    latest: Option<Duration>,
+}
+
+impl LatencyMeter {
+    /// Start a probe sent at `now`; `requested` marks a probe asked for with `/ping`.
+    ///
+    /// # Returns
+    ///
+    /// The payload of the Ping frame to send
+    pub fn probe(&mut self, now: Instant, requested: bool) -> Vec<u8> {
+        let probe = self.next_probe;
+        self.next_probe += 1;
+        self.pending = Some((probe, now));
+        // A /ping still unanswered is answered by the new probe
+        self.requested |= requested;
+        probe.to_be_bytes().to_vec()
+    }
+
+    /// Record a Pong frame received at `now`.
+    ///
+    /// # Returns
+    ///
+    /// The round-trip time and whether `/ping` asked for it, if the Pong answers the latest
+    /// probe
+    pub fn on_pong(&mut self, payload: &[u8], now: Instant) -> Option<(Duration, bool)> {
+        let (probe, sent_at) = self.pending?;
+        if payload != probe.to_be_bytes() {
+            return None;
+        }
+        let rtt = now.saturating_duration_since(sent_at);
+        self.pending = None;
+        self.latest = Some(rtt);
+        Some((rtt, std::mem::take(&mut self.requested)))
+    }
+
+    /// Latest round-trip time measured, if any.
+    pub fn latest(&self) -> Option<Duration> {
+        self.latest
+    }
+}
+
+/// Template of the prompt, with the placeholders `{client_id}`, `{room}`, `{unread}` and
+/// `{latency}` (e.g. `"[{room}] {client_id} ({unread})> "`).
+///
+/// `{{` and `}}` stand for literal braces.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct PromptTemplate {
+    parts: Vec<PromptPart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PromptPart {
+    Text(String),
+    ClientId,
+    Room,
+    Unread,
+    Latency,
+}
+
+/// Values filled into a [`PromptTemplate`].
+#[derive(Debug, Clone, Copy)]
+pub struct PromptValues<'a> {
+    pub client_id: &'a str,
+    /// Slug of the room, or its ID when joined without one (empty until joined)
+    pub room: &'a str,
+    /// Messages received since the last line was sent
+    pub unread: usize,
+    /// Latest round trip to the server (shown as e.g. `42ms`, empty until measured)
+    pub latency: Option<Duration>,
+}
+
+impl PromptTemplate {
+    /// Check whether the template shows the latency, which then needs to be measured
+    /// periodically.
+    pub fn shows_latency(&self) -> bool {
+        self.parts.contains(&PromptPart::Latency)
+    }
+
+    /// Fill the placeholders with the values.
+    pub fn render(&self, values: &PromptValues) -> String {
+        let mut prompt = String::new();
+        for part in &self.parts {
+            match part {
+                PromptPart::Text(text) => prompt.push_str(text),
+                PromptPart::ClientId => prompt.push_str(values.client_id),
+                PromptPart::Room => prompt.push_str(values.room),
+                PromptPart::Unread => prompt.push_str(&values.unread.to_string()),
+                PromptPart::Latency => {
+                    if let Some(rtt) = values.latency {
+                        prompt.push_str(&format!("{}ms", rtt.as_millis()));
+                    }
+                }
+            }
+        }
+        prompt
+    }
+}
+
+impl FromStr for PromptTemplate {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ConfigError::InvalidPrompt(s.to_string());
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let (name, rest) = chars.as_str().split_once('}').ok_or_else(invalid)?;
+                    let part = match name {
+                        "client_id" => PromptPart::ClientId,
+                        "room" => PromptPart::Room,
+                        "unread" => PromptPart::Unread,
+                        "latency" => PromptPart::Latency,
+                        _ => return Err(invalid()),
+                    };
+                    chars = rest.chars();
+                    if !text.is_empty() {
+                        parts.push(PromptPart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(part);
+                }
+                '}' => return Err(invalid()),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(PromptPart::Text(text));
+        }
+        Ok(Self { parts })
+    }
+}
+
+impl TryFrom<String> for PromptTemplate {
+    type Error = ConfigError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Skew from which the client tells the user that its clock disagrees with the server's.
+pub const CLOCK_SKEW_NOTICE_THRESHOLD_MS: i64 = 2000;
+
+/// Difference between the server's clock and the local clock.
+///
+/// Estimated from the `server_time` of `room-connected` and of each `heartbeat`, so that the
+/// times the client stamps on what it sends agree with the times the server stamps on other
+/// messages even when the local clock is wrong. The network delay is not subtracted; it makes
+/// the estimate lag behind the server by half a round trip at most.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClockSkew {
+    /// Milliseconds to add to the local clock to get the server's (`None` until estimated)
+    offset_ms: Option<i64>,
+}
+
+impl ClockSkew {
+    /// Record the server's clock `server_time` received when the local clock read `local_now`.
+    ///
+    /// # Returns
+    ///
+    /// The new offset if the clocks now disagree by [`CLOCK_SKEW_NOTICE_THRESHOLD_MS`] or more
+    /// while they did not before, so that the user is told once rather than on every heartbeat
+    pub fn observe(&mut self, server_time: i64, local_now: i64) -> Option<i64> {
+        let offset = server_time - local_now;
+        let was_skewed = self.offset_ms.is_some_and(is_skewed);
+        self.offset_ms = Some(offset);
+        (!was_skewed && is_skewed(offset)).then_some(offset)
+    }
+
+    /// Milliseconds to add to the local clock to get the server's (0 until estimated).
+    pub fn offset_ms(&self) -> i64 {
+        self.offset_ms.unwrap_or_default()
+    }
+
+    /// Convert a local timestamp (milliseconds) to the server's clock.
+    pub fn to_server_time(self, local: i64) -> i64 {
+        local + self.offset_ms()
+    }
+}
+
+fn is_skewed(offset_ms: i64) -> bool {
+    offset_ms.abs() >= CLOCK_SKEW_NOTICE_THRESHOLD_MS
+}
+
+/// Split a text frame into the messages it carries.
+///
+/// Servers batching broadcasts send several messages as one JSON array frame; any other frame
+/// carries a single message and is returned as is.
+pub fn unbatch(text: &str) -> Vec<String> {
+    if !text.trim_start().starts_with('[') {
+        return vec![text.to_string()];
+    }
+    match serde_json::from_str::<Vec<serde_json::Value>>(text) {
+        Ok(messages) => messages.iter().map(ToString::to_string).collect(),
+        Err(_) => vec![text.to_string()],
+    }
+}
+
+/// Daily window in JST during which mention bells are muted, written as `HH:MM-HH:MM`.
+///
+/// A window whose end is earlier than its start spans midnight (e.g. `22:00-07:00`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct QuietHours {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Time the window ends.
+    pub fn end(&self) -> NaiveTime {
+        self.end
+    }
+
+    /// Check whether `time` falls in the window (the end is exclusive).
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ConfigError::InvalidQuietHours(s.to_string());
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").map_err(|_| invalid())?;
+        let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").map_err(|_| invalid())?;
+        if start == end {
+            return Err(invalid());
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl TryFrom<String> for QuietHours {
+    type Error = ConfigError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+/// Mention received while quiet hours muted the bell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissedMention {
+    pub from: String,
+    pub content: String,
+    pub sent_at: i64,
+}
+
+/// Change of the do-not-disturb state noticed by [`DoNotDisturb::tick`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuietHoursEvent {
+    /// A window started; bells are muted until `until`
+    Started { until: NaiveTime },
+    /// The window ended with the mentions received during it
+    Ended { missed: Vec<MissedMention> },
+}
+
+/// Do-not-disturb state kept across reconnections.
+///
+/// Mentions ring the bell outside quiet hours; inside them they are collected and handed back
+/// as a summary when the window ends.
+#[derive(Debug, Clone, Default)]
+pub struct DoNotDisturb {
+    windows: Vec<QuietHours>,
+    active: bool,
+    missed: Vec<MissedMention>,
+}
+
+impl DoNotDisturb {
+    /// Create a do-not-disturb state with the configured windows.
+    pub fn new(windows: Vec<QuietHours>) -> Self {
+        Self {
+            windows,
+            active: false,
+            missed: Vec::new(),
+        }
+    }
+
+    /// The window containing `time`, if any.
+    fn window_at(&self, time: NaiveTime) -> Option<&QuietHours> {
+        self.windows.iter().find(|window| window.contains(time))
+    }
+
+    /// Record a mention received at `time` (JST).
+    ///
+    /// # Returns
+    ///
+    /// `true` if the bell should ring; `false` if the mention was kept for the summary
+    pub fn on_mention(&mut self, mention: MissedMention, time: NaiveTime) -> bool {
+        if self.window_at(time).is_some() {
+            self.missed.push(mention);
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Check for a window starting or ending at `time` (JST).
+    ///
+    /// # Returns
+    ///
+    /// The event to report, if the state changed since the last call
+    pub fn tick(&mut self, time: NaiveTime) -> Option<QuietHoursEvent> {
+        match self.window_at(time).map(QuietHours::end) {
+            Some(until) if !self.active => {
+                self.active = true;
+                Some(QuietHoursEvent::Started { until })
+            }
+            None if self.active || !self.missed.is_empty() => {
+                self.active = false;
+                Some(QuietHoursEvent::Ended {
+                    missed: std::mem::take(&mut self.missed),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,4 +1138,596 @@ mod tests {
         // then (期待する結果):
         assert!(result);
     }
+
+    #[test]
+    fn test_reconnect_delay_backs_off_exponentially() {
+        // テスト項目: 再接続の待ち時間は試行ごとに倍になって上限で止まり、ジッターでバックオフの後半に散らばる
+        // when (操作):
+        let first = reconnect_delay(0, 0.0);
+        let second = reconnect_delay(1, 0.0);
+        let third_max_jitter = reconnect_delay(2, 1.0);
+        let capped = reconnect_delay(20, 0.0);
+        let overflow = reconnect_delay(u32::MAX, 1.0);
+
+        // then (期待する結果):
+        assert_eq!(first, Duration::from_millis(250));
+        assert_eq!(second, Duration::from_millis(500));
+        assert_eq!(third_max_jitter, Duration::from_secs(2));
+        assert_eq!(capped, RECONNECT_MAX_DELAY / 2);
+        assert_eq!(overflow, RECONNECT_MAX_DELAY);
+    }
+
+    #[test]
+    fn test_should_exit_immediately_with_room_full() {
+        // テスト項目: RoomFull エラーの場合、即座に終了すべきと判定される
+        // given (前提条件):
+        let error = ClientError::RoomFull;
+
+        // when (操作):
+        let result = should_exit_immediately(&error);
+
+        // then (期待する結果):
+        assert!(result);
+    }
+
+    #[test]
+    fn test_exit_code_for_each_error() {
+        // テスト項目: 各エラーが文書化された終了コードに対応付けられる
+        // given (前提条件):
+        let cases = [
+            (ClientError::DuplicateClientId("alice".to_string()), 2),
+            (ClientError::RoomFull, 3),
+            (ClientError::AuthenticationFailed("HTTP 401".to_string()), 4),
+            (ClientError::ConnectionError("network error".to_string()), 5),
+            (ClientError::SessionReplaced, 6),
+            (ClientError::RoomNotFound("general".to_string()), 7),
+            (ClientError::Removed { banned: true }, 8),
+            (ClientError::JoinRejected, 9),
+        ];
+
+        for (error, expected) in cases {
+            // when (操作):
+            let code = exit_code_for(&error).code();
+
+            // then (期待する結果):
+            assert_eq!(code, expected, "unexpected exit code for {:?}", error);
+        }
+    }
+
+    #[test]
+    fn test_exit_code_success_is_zero() {
+        // テスト項目: 正常終了の終了コードは 0 である
+        // when (操作):
+        let code = ExitCode::Success.code();
+
+        // then (期待する結果):
+        assert_eq!(code, 0);
+    }
+
+    #[test]
+    fn test_classify_handshake_status() {
+        // テスト項目: ハンドシェイクの HTTP ステータスがエラー種別に分類される
+        // when (操作):
+        let conflict = classify_handshake_status(409, "alice", None);
+        let unavailable = classify_handshake_status(503, "alice", None);
+        let unauthorized = classify_handshake_status(401, "alice", None);
+        let room_not_found = classify_handshake_status(404, "alice", Some("general"));
+        let not_found = classify_handshake_status(404, "alice", None);
+        let other = classify_handshake_status(500, "alice", None);
+
+        // then (期待する結果):
+        assert!(matches!(conflict, ClientError::DuplicateClientId(id) if id == "alice"));
+        assert!(matches!(unavailable, ClientError::RoomFull));
+        assert!(matches!(unauthorized, ClientError::AuthenticationFailed(_)));
+        assert!(matches!(room_not_found, ClientError::RoomNotFound(slug) if slug == "general"));
+        assert!(matches!(not_found, ClientError::ConnectionError(_)));
+        assert!(matches!(other, ClientError::ConnectionError(_)));
+    }
+
+    #[test]
+    fn test_login_url() {
+        // テスト項目: WebSocket の URL から同じホストのログイン・スターの一覧・ルーム・検索のエンドポイントが導出される
+        // when (操作):
+        let plain = login_url("ws://127.0.0.1:8080/ws");
+        let secure = login_url("wss://chat.example.com/ws?x=1");
+        let other = login_url("http://127.0.0.1:8080/ws");
+
+        // then (期待する結果):
+        assert_eq!(
+            plain.as_deref(),
+            Some("http://127.0.0.1:8080/api/v1/auth/login")
+        );
+        assert_eq!(
+            secure.as_deref(),
+            Some("https://chat.example.com/api/v1/auth/login")
+        );
+        assert_eq!(other, None);
+        assert_eq!(
+            starred_url("ws://127.0.0.1:8080/ws", "alice").as_deref(),
+            Some("http://127.0.0.1:8080/api/v1/users/alice/starred")
+        );
+        assert_eq!(
+            rooms_url("ws://127.0.0.1:8080/ws").as_deref(),
+            Some("http://127.0.0.1:8080/api/v1/rooms")
+        );
+        assert_eq!(
+            room_url("wss://chat.example.com/ws", "room-1").as_deref(),
+            Some("https://chat.example.com/api/v1/rooms/room-1")
+        );
+        assert_eq!(
+            room_search_url("ws://127.0.0.1:8080/ws", "room-1").as_deref(),
+            Some("http://127.0.0.1:8080/api/v1/rooms/room-1/search")
+        );
+    }
+
+    #[test]
+    fn test_display_name_query() {
+        // テスト項目: 表示名は英数字と記号の一部以外がパーセントエンコードされてクエリに含まれる
+        // when (操作):
+        let name = display_name_query(Some("Alice Smith"));
+        let unicode = display_name_query(Some("あ&b"));
+        let none = display_name_query(None);
+
+        // then (期待する結果):
+        assert_eq!(name, "&display_name=Alice%20Smith");
+        assert_eq!(unicode, "&display_name=%E3%81%82%26b");
+        assert_eq!(none, "");
+    }
+
+    #[test]
+    fn test_resume_state_skips_duplicates_across_reconnects() {
+        // テスト項目: 再接続後に重なって再送されたメッセージは表示されず、再開位置がクエリに含まれる
+        // given (前提条件):
+        let mut resume = ResumeState::new(16);
+        assert_eq!(resume.query(), "");
+        resume.on_room_connected(Some("room-1".to_string()), Some(0));
+        assert!(resume.accept(Some(1)));
+        assert!(resume.accept(Some(2)));
+
+        // when (操作): 同じルームに再接続し、1〜3 が再送される
+        resume.on_room_connected(Some("room-1".to_string()), Some(2));
+        let rendered: Vec<u64> = (1..=3).filter(|seq| resume.accept(Some(*seq))).collect();
+
+        // then (期待する結果):
+        assert_eq!(rendered, vec![3]);
+        assert_eq!(resume.query(), "&resume_token=room-1&last_seq=3");
+        assert!(resume.accept(None));
+    }
+
+    #[test]
+    fn test_resume_state_resets_when_room_is_recreated() {
+        // テスト項目: 再接続先のルームが作り直されていた場合はシーケンス番号を忘れる
+        // given (前提条件):
+        let mut resume = ResumeState::new(16);
+        resume.on_room_connected(Some("room-1".to_string()), Some(0));
+        assert!(resume.accept(Some(1)));
+
+        // when (操作):
+        let backfill = resume.on_room_connected(Some("room-2".to_string()), Some(5));
+
+        // then (期待する結果): 別のルームの履歴は要求しない
+        assert_eq!(backfill, None);
+        assert!(resume.accept(Some(1)));
+    }
+
+    #[test]
+    fn test_resume_state_requests_backfill_for_missed_messages() {
+        // テスト項目: 同じルームに再接続し、切断中に送信されたメッセージがある場合は
+        //             受信済みの最新の番号からバックフィルを要求する
+        // given (前提条件):
+        let mut resume = ResumeState::new(16);
+        let first = resume.on_room_connected(Some("room-1".to_string()), Some(4));
+        assert!(resume.accept(Some(5)));
+
+        // when (操作):
+        let missed = resume.on_room_connected(Some("room-1".to_string()), Some(8));
+        let up_to_date = resume.on_room_connected(Some("room-1".to_string()), Some(5));
+
+        // then (期待する結果): 初回接続では過去の履歴を要求しない
+        assert_eq!(first, None);
+        assert_eq!(missed, Some(5));
+        assert_eq!(up_to_date, None);
+    }
+
+    #[test]
+    fn test_localized_notice_prefers_client_locale() {
+        // テスト項目: クライアントのロケールが指定された場合はサーバの通知文より優先される
+        // given (前提条件):
+        let text = SystemText::ParticipantJoined { client_id: "bob" };
+        let server_notice = Some("bob joined the room".to_string());
+
+        // when (操作):
+        let overridden = localized_notice(Some(Locale::Ja), text, server_notice.clone());
+        let from_server = localized_notice(None, text, server_notice);
+        let missing = localized_notice(None, text, None);
+
+        // then (期待する結果):
+        assert_eq!(overridden.as_deref(), Some("bob さんが入室しました"));
+        assert_eq!(from_server.as_deref(), Some("bob joined the room"));
+        assert_eq!(missing, None);
+    }
+
+    #[test]
+    fn test_parse_input() {
+        // テスト項目: /rooms はルーム一覧の要求、/ping は往復時間の計測、それ以外の行はチャットメッセージとして解析される
+        // when (操作):
+        let list_rooms = Input::parse("/rooms");
+        let ping = Input::parse("/ping");
+        let chat = Input::parse("hello");
+        let other = Input::parse("/roomsx");
+
+        // then (期待する結果):
+        assert_eq!(list_rooms, Input::ListRooms);
+        assert_eq!(ping, Input::Ping);
+        assert_eq!(chat, Input::Chat("hello".to_string()));
+        assert_eq!(other, Input::Chat("/roomsx".to_string()));
+    }
+
+    #[test]
+    fn test_parse_poll_and_vote() {
+        // テスト項目: /poll は引用符で囲んだ空白を含む質問と選択肢に、/vote は 0 始まりの選択肢に解析され、引数が足りない場合は使い方になる
+        // when (操作):
+        let poll = Input::parse(r#"/poll "Where to eat?" Ramen "Sushi bar""#);
+        let one_option = Input::parse("/poll Lunch? Ramen");
+        let vote = Input::parse("/vote #12 2");
+        let zero = Input::parse("/vote 12 0");
+        let pollster = Input::parse("/pollster");
+
+        // then (期待する結果):
+        assert_eq!(
+            poll,
+            Input::Poll {
+                question: "Where to eat?".to_string(),
+                options: vec!["Ramen".to_string(), "Sushi bar".to_string()],
+            }
+        );
+        assert!(matches!(one_option, Input::Usage(_)));
+        assert_eq!(vote, Input::Vote { seq: 12, option: 1 });
+        assert!(matches!(zero, Input::Usage(_)));
+        assert_eq!(pollster, Input::Chat("/pollster".to_string()));
+    }
+
+    #[test]
+    fn test_parse_forward() {
+        // テスト項目: /forward はメッセージ番号と転送先のルームに解析され、引数が足りない・多い場合は使い方になる
+        // when (操作):
+        let forward = Input::parse("/forward #12 room-2");
+        let missing_room = Input::parse("/forward 12");
+        let extra = Input::parse("/forward 12 room-2 room-3");
+
+        // then (期待する結果):
+        assert_eq!(
+            forward,
+            Input::Forward {
+                seq: 12,
+                room_id: "room-2".to_string(),
+            }
+        );
+        assert!(matches!(missing_room, Input::Usage(_)));
+        assert!(matches!(extra, Input::Usage(_)));
+    }
+
+    #[test]
+    fn test_parse_star() {
+        // テスト項目: /star・/unstar はメッセージ番号に、/starred はスターの一覧の要求に解析され、番号が無い場合は使い方になる
+        // when (操作):
+        let star = Input::parse("/star #12");
+        let unstar = Input::parse("/unstar 12");
+        let starred = Input::parse("/starred");
+        let missing = Input::parse("/unstar");
+
+        // then (期待する結果):
+        assert_eq!(
+            star,
+            Input::Star {
+                seq: 12,
+                starred: true
+            }
+        );
+        assert_eq!(
+            unstar,
+            Input::Star {
+                seq: 12,
+                starred: false
+            }
+        );
+        assert_eq!(starred, Input::ListStarred);
+        assert_eq!(missing, Input::Usage("/unstar <message number>"));
+    }
+
+    #[test]
+    fn test_parse_search() {
+        // テスト項目: /search は続く文字列を検索語に、/jump はメッセージ番号に解析され、引数が無い場合は使い方になる
+        // when (操作):
+        let search = Input::parse("/search deploy failed");
+        let jump = Input::parse("/jump #12");
+        let missing_query = Input::parse("/search");
+        let missing_seq = Input::parse("/jump");
+
+        // then (期待する結果):
+        assert_eq!(search, Input::Search("deploy failed".to_string()));
+        assert_eq!(jump, Input::Jump { seq: 12 });
+        assert_eq!(missing_query, Input::Usage("/search <text>"));
+        assert_eq!(missing_seq, Input::Usage("/jump <message number>"));
+    }
+
+    #[test]
+    fn test_parse_activity() {
+        // テスト項目: /activity は続く文字列をアクティビティに、文字列が無い場合はアクティビティの消去に解析される
+        // when (操作):
+        let activity = Input::parse("/activity reviewing PR #42");
+        let clear = Input::parse("/activity");
+        let other = Input::parse("/activityx");
+
+        // then (期待する結果):
+        assert_eq!(
+            activity,
+            Input::SetActivity(Some("reviewing PR #42".to_string()))
+        );
+        assert_eq!(clear, Input::SetActivity(None));
+        assert_eq!(other, Input::Chat("/activityx".to_string()));
+    }
+
+    #[test]
+    fn test_parse_create_and_join() {
+        // テスト項目: /create はルームの名前に、/join はルーム ID に解析され、引数が 1 つでなければ使い方になる
+        // when (操作):
+        let create = Input::parse("/create design");
+        let join = Input::parse("/join 550e8400-e29b-41d4-a716-446655440000");
+        let spaced = Input::parse("/create design review");
+        let missing = Input::parse("/join");
+
+        // then (期待する結果):
+        assert_eq!(create, Input::CreateRoom("design".to_string()));
+        assert_eq!(
+            join,
+            Input::JoinRoom("550e8400-e29b-41d4-a716-446655440000".to_string())
+        );
+        assert_eq!(spaced, Input::Usage("/create <name>"));
+        assert_eq!(missing, Input::Usage("/join <room ID>"));
+    }
+
+    #[test]
+    fn test_parse_nick() {
+        // テスト項目: /nick は続く文字列を表示名に、文字列が無い場合は表示名の消去に解析される
+        // when (操作):
+        let nick = Input::parse("/nick Alice Smith");
+        let clear = Input::parse("/nick");
+
+        // then (期待する結果):
+        assert_eq!(nick, Input::SetDisplayName(Some("Alice Smith".to_string())));
+        assert_eq!(clear, Input::SetDisplayName(None));
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn mention(from: &str) -> MissedMention {
+        MissedMention {
+            from: from.to_string(),
+            content: "@alice ping".to_string(),
+            sent_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_mentions_matches_whole_client_id() {
+        // テスト項目: `@client_id` がメンションとして判定され、前方一致の ID は判定されない
+        // when (操作) / then (期待する結果):
+        assert!(mentions("@alice hi", "alice"));
+        assert!(mentions("hi @alice, are you there?", "alice"));
+        assert!(mentions("hi @alice", "alice"));
+        assert!(!mentions("hi @alice2", "alice"));
+        assert!(!mentions("hi @alice-bot", "alice"));
+        assert!(!mentions("hi alice", "alice"));
+    }
+
+    #[test]
+    fn test_typing_indicators_track_typing_participants() {
+        // テスト項目: 入力を始めた参加者が順に記録され、繰り返しの開始は表示対象にならない
+        // given (前提条件):
+        let mut typing = TypingIndicators::default();
+
+        // when (操作):
+        let alice = typing.start("alice");
+        let bob = typing.start("bob");
+        let alice_again = typing.start("alice");
+        typing.stop("alice");
+
+        // then (期待する結果):
+        assert!(alice);
+        assert!(bob);
+        assert!(!alice_again);
+        assert_eq!(typing.participants(), ["bob".to_string()]);
+    }
+
+    #[test]
+    fn test_send_throttle_spaces_out_messages_while_slowed_down() {
+        // テスト項目: 減速を要請されている間は前回の送信から間隔を空け、解除されると待たずに送信する
+        // given (前提条件):
+        let mut throttle = SendThrottle::default();
+        let start = Instant::now();
+        throttle.sent(start);
+
+        // when (操作):
+        let before_advice = throttle.wait(start);
+        let slowed = throttle.advise(Duration::from_millis(500));
+        let repeated = throttle.advise(Duration::from_millis(500));
+        let soon_after = throttle.wait(start + Duration::from_millis(200));
+        let long_after = throttle.wait(start + Duration::from_secs(1));
+        let lifted = throttle.advise(Duration::ZERO);
+        let after_lifted = throttle.wait(start);
+
+        // then (期待する結果):
+        assert_eq!(before_advice, Duration::ZERO);
+        assert!(slowed);
+        assert!(!repeated);
+        assert_eq!(soon_after, Duration::from_millis(300));
+        assert_eq!(long_after, Duration::ZERO);
+        assert!(lifted);
+        assert_eq!(after_lifted, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_latency_meter_matches_pongs_to_the_latest_probe() {
+        // テスト項目: 最新のプローブへの Pong だけが往復時間になり、/ping の要求は後続のプローブに引き継がれる
+        // given (前提条件):
+        let mut meter = LatencyMeter::default();
+        let start = Instant::now();
+        let requested = meter.probe(start, true);
+        let periodic = meter.probe(start + Duration::from_millis(10), false);
+
+        // when (操作):
+        let stale = meter.on_pong(&requested, start + Duration::from_millis(20));
+        let answered = meter.on_pong(&periodic, start + Duration::from_millis(52));
+        let duplicate = meter.on_pong(&periodic, start + Duration::from_millis(60));
+
+        // then (期待する結果):
+        assert_eq!(stale, None);
+        assert_eq!(answered, Some((Duration::from_millis(42), true)));
+        assert_eq!(duplicate, None);
+        assert_eq!(meter.latest(), Some(Duration::from_millis(42)));
+    }
+
+    #[test]
+    fn test_prompt_template_fills_placeholders() {
+        // テスト項目: プロンプトのテンプレートのプレースホルダーが値に置き換えられ、未知のプレースホルダーはエラーになる
+        // given (前提条件):
+        let template: PromptTemplate = "[{room}] {client_id} ({unread}) {{{latency}}}> "
+            .parse()
+            .unwrap();
+        let values = PromptValues {
+            client_id: "alice",
+            room: "general",
+            unread: 3,
+            latency: Some(Duration::from_millis(42)),
+        };
+
+        // when (操作):
+        let measured = template.render(&values);
+        let unmeasured = template.render(&PromptValues {
+            latency: None,
+            ..values
+        });
+        let unknown = "{user}> ".parse::<PromptTemplate>();
+        let unclosed = "{room> ".parse::<PromptTemplate>();
+
+        // then (期待する結果):
+        assert_eq!(measured, "[general] alice (3) {42ms}> ");
+        assert_eq!(unmeasured, "[general] alice (3) {}> ");
+        assert!(template.shows_latency());
+        assert!(matches!(unknown, Err(ConfigError::InvalidPrompt(_))));
+        assert!(matches!(unclosed, Err(ConfigError::InvalidPrompt(_))));
+    }
+
+    #[test]
+    fn test_clock_skew_converts_to_server_time_and_notifies_once() {
+        // テスト項目: サーバーの時刻との差で時刻を変換し、しきい値を超えた時だけ一度通知する
+        // given (前提条件):
+        let mut skew = ClockSkew::default();
+        let local = 1_000_000;
+
+        // when (操作):
+        let close = skew.observe(local + 300, local);
+        let behind = skew.observe(local + 5_000, local);
+        let still_behind = skew.observe(local + 5_200, local + 100);
+
+        // then (期待する結果):
+        assert_eq!(close, None);
+        assert_eq!(behind, Some(5_000));
+        assert_eq!(still_behind, None);
+        assert_eq!(skew.to_server_time(local), local + 5_100);
+        assert_eq!(ClockSkew::default().to_server_time(local), local);
+    }
+
+    #[test]
+    fn test_unbatch_splits_array_frames() {
+        // テスト項目: JSON 配列のフレームはメッセージごとに分割され、それ以外のフレームはそのまま返される
+        // when (操作):
+        let batched = unbatch(r#"[{"seq":1},{"seq":2}]"#);
+        let single = unbatch(r#"{"seq":3}"#);
+        let invalid = unbatch("[not json");
+
+        // then (期待する結果):
+        assert_eq!(batched, vec![r#"{"seq":1}"#, r#"{"seq":2}"#]);
+        assert_eq!(single, vec![r#"{"seq":3}"#]);
+        assert_eq!(invalid, vec!["[not json"]);
+    }
+
+    #[test]
+    fn test_quiet_hours_parse() {
+        // テスト項目: `HH:MM-HH:MM` 形式のみ受け付け、開始と終了が同じものは拒否する
+        // when (操作) / then (期待する結果):
+        let window: QuietHours = "22:00-07:00".parse().unwrap();
+        assert_eq!(window.to_string(), "22:00-07:00");
+        assert!("22:00".parse::<QuietHours>().is_err());
+        assert!("25:00-07:00".parse::<QuietHours>().is_err());
+        assert!("09:00-09:00".parse::<QuietHours>().is_err());
+    }
+
+    #[test]
+    fn test_quiet_hours_contains_across_midnight() {
+        // テスト項目: 日付をまたぐ時間帯と日中の時間帯の判定（終了時刻は含まない）
+        // given (前提条件):
+        let night: QuietHours = "22:00-07:00".parse().unwrap();
+        let lunch: QuietHours = "12:00-13:00".parse().unwrap();
+
+        // when (操作) / then (期待する結果):
+        assert!(night.contains(time(23, 30)));
+        assert!(night.contains(time(6, 59)));
+        assert!(!night.contains(time(7, 0)));
+        assert!(!night.contains(time(21, 59)));
+        assert!(lunch.contains(time(12, 0)));
+        assert!(!lunch.contains(time(13, 0)));
+    }
+
+    #[test]
+    fn test_do_not_disturb_collects_mentions_until_window_ends() {
+        // テスト項目: 時間帯中のメンションはベルを鳴らさずに保持され、終了時にまとめて返される
+        // given (前提条件):
+        let mut dnd = DoNotDisturb::new(vec!["22:00-07:00".parse().unwrap()]);
+
+        // when (操作):
+        let started = dnd.tick(time(22, 0));
+        let rang_during = dnd.on_mention(mention("bob"), time(23, 0));
+        let still_quiet = dnd.tick(time(23, 0));
+        let ended = dnd.tick(time(7, 0));
+        let rang_after = dnd.on_mention(mention("carol"), time(8, 0));
+
+        // then (期待する結果):
+        assert_eq!(
+            started,
+            Some(QuietHoursEvent::Started { until: time(7, 0) })
+        );
+        assert!(!rang_during);
+        assert_eq!(still_quiet, None);
+        assert_eq!(
+            ended,
+            Some(QuietHoursEvent::Ended {
+                missed: vec![mention("bob")]
+            })
+        );
+        assert!(rang_after);
+        assert_eq!(dnd.tick(time(8, 0)), None);
+    }
+
+    #[test]
+    fn test_do_not_disturb_reports_mentions_missed_before_first_tick() {
+        // テスト項目: 開始の通知前に保持されたメンションも、時間帯の外になった時点で返される
+        // given (前提条件):
+        let mut dnd = DoNotDisturb::new(vec!["12:00-13:00".parse().unwrap()]);
+        dnd.on_mention(mention("bob"), time(12, 59));
+
+        // when (操作):
+        let event = dnd.tick(time(13, 0));
+
+        // then (期待する結果):
+        assert_eq!(
+            event,
+            Some(QuietHoursEvent::Ended {
+                missed: vec![mention("bob")]
+            })
+        );
+    }
 }
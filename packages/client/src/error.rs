@@ -9,7 +9,140 @@ pub enum ClientError {
     #[error("Client ID '{0}' is already connected")]
     DuplicateClientId(String),
 
+    /// Room has reached its participant capacity
+    #[error("Room is full")]
+    RoomFull,
+
+    /// Server rejected the credentials
+    #[error("Authentication failed: {0}")]
+    AuthenticationFailed(String),
+
     /// Connection error
     #[error("Connection error: {0}")]
     ConnectionError(String),
+
+    /// An established connection was lost
+    #[error("Connection lost")]
+    ConnectionLost,
+
+    /// Server is restarting and asked the client to reconnect after the delay
+    #[error("Server is restarting")]
+    ServerRestarting(std::time::Duration),
+
+    /// A newer connection with the same client ID replaced this session
+    #[error("Session was replaced by a newer connection")]
+    SessionReplaced,
+
+    /// An admin kicked the client or disconnected everyone in its room (`banned` if its
+    /// future connections are also rejected)
+    #[error("Removed from the server by an admin")]
+    Removed { banned: bool },
+
+    /// A moderator rejected the client waiting to join a room requiring approval
+    #[error("Join request was rejected by a moderator")]
+    JoinRejected,
+
+    /// No room has the requested slug
+    #[error("No room with slug '{0}'")]
+    RoomNotFound(String),
+
+    /// The user asked to join the room with the ID, so the session reconnects to it
+    #[error("Switching to room {0}")]
+    SwitchingRoom(String),
+
+    /// A recording to replay could not be read
+    #[error("Invalid recording {0}")]
+    InvalidRecording(String),
+
+    /// The cached messages could not be read
+    #[error("Cannot read history cache {0}")]
+    HistoryCache(String),
+}
+
+/// Errors in the client configuration file
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// The file could not be read
+    #[error("Failed to read config file '{path}': {source}")]
+    Read {
+        path: String,
+        source: std::io::Error,
+    },
+
+    /// The file is not a valid configuration
+    #[error("Invalid config file '{path}': {source}")]
+    Parse {
+        path: String,
+        source: serde_json::Error,
+    },
+
+    /// A quiet-hours window is not in the `HH:MM-HH:MM` format
+    #[error("Invalid quiet hours '{0}': expected HH:MM-HH:MM with different start and end")]
+    InvalidQuietHours(String),
+
+    /// The prompt template has an unknown or unclosed placeholder
+    #[error(
+        "Invalid prompt '{0}': placeholders are {{client_id}}, {{room}}, {{unread}} and {{latency}}"
+    )]
+    InvalidPrompt(String),
+
+    /// The theme is not a built-in theme or has an invalid color
+    #[error("Invalid theme: {0}")]
+    InvalidTheme(String),
+
+    /// The CA certificate given with --ca-cert could not be loaded
+    #[error("Failed to load CA certificate '{path}': {reason}")]
+    CaCert { path: String, reason: String },
+
+    /// The history cache directory could not be created
+    #[error("Failed to open history cache '{path}': {source}")]
+    HistoryCache {
+        path: String,
+        source: std::io::Error,
+    },
+
+    /// The wire log could not be opened
+    #[error("Failed to open wire log '{path}': {source}")]
+    WireLog {
+        path: String,
+        source: engawa_server::infrastructure::error::WireLogError,
+    },
+}
+
+/// Process exit codes of the client binary.
+///
+/// These values are stable so that wrapper scripts can branch on the failure cause
+/// without parsing stderr.
+///
+/// | Code | Meaning                                    |
+/// |------|--------------------------------------------|
+/// | 0    | Session ended normally                     |
+/// | 1    | Unexpected error                           |
+/// | 2    | Client ID is already connected             |
+/// | 3    | Room is full                               |
+/// | 4    | Authentication failed                      |
+/// | 5    | Connection lost (reconnect attempts used)  |
+/// | 6    | Session replaced by a newer connection     |
+/// | 7    | No room with the requested slug            |
+/// | 8    | Kicked, banned or disconnected by an admin |
+/// | 9    | Join request rejected by a moderator       |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    Success = 0,
+    GeneralError = 1,
+    DuplicateClientId = 2,
+    RoomFull = 3,
+    AuthenticationFailed = 4,
+    ConnectionLost = 5,
+    SessionReplaced = 6,
+    RoomNotFound = 7,
+    Removed = 8,
+    JoinRejected = 9,
+}
+
+impl ExitCode {
+    /// Get the numeric exit code passed to `std::process::exit`.
+    pub fn code(self) -> i32 {
+        self as i32
+    }
 }
@@ -2,13 +2,41 @@
 
 #![allow(dead_code)]
 
-use engawa_server::infrastructure::dto::websocket::ParticipantInfo;
-use engawa_shared::time::timestamp_to_jst_rfc3339;
+use std::time::Duration;
+
+use chrono::NaiveTime;
+use engawa_server::infrastructure::dto::{
+    http::{MessageDto, StarredMessageDto},
+    websocket::{
+        ChatMessage, ForwardedFromInfo, ParticipantInfo, PollInfo, PollOptionInfo, RoomInfo,
+    },
+};
+use engawa_shared::time::{timestamp_to_jst_clock, timestamp_to_jst_rfc3339};
+
+use super::domain::MissedMention;
+
+/// How incoming messages are laid out on the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputMode {
+    /// Separators, arrows and a redrawn prompt
+    #[default]
+    Standard,
+    /// One clearly labeled line per event and no prompt redraws, for screen readers
+    Accessible,
+}
 
 /// Message formatter for client display
-pub struct MessageFormatter;
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessageFormatter {
+    mode: OutputMode,
+}
 
 impl MessageFormatter {
+    /// Create a formatter for the given output mode
+    pub fn new(mode: OutputMode) -> Self {
+        Self { mode }
+    }
+
     /// Format the room-connected message showing all participants
     ///
     /// # Arguments
@@ -20,9 +48,45 @@ impl MessageFormatter {
     ///
     /// A formatted string with participant list
     pub fn format_room_connected(
+        &self,
         participants: &[ParticipantInfo],
         current_client_id: &str,
     ) -> String {
+        if self.mode == OutputMode::Accessible {
+            let mut output = format!("Participants: {}\n", participants.len());
+            for participant in participants {
+                let mut labels = Vec::new();
+                if participant.client_id == current_client_id {
+                    labels.push("you");
+                }
+                if participant.guest {
+                    labels.push("guest");
+                }
+                let labels = if labels.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", labels.join(", "))
+                };
+                let display_name = match &participant.display_name {
+                    Some(display_name) => format!(", shown as {}", display_name),
+                    None => String::new(),
+                };
+                let activity = match &participant.activity {
+                    Some(activity) => format!(", activity: {}", activity),
+                    None => String::new(),
+                };
+                output.push_str(&format!(
+                    "Participant: {}{}{}, entered at {}{}\n",
+                    participant.client_id,
+                    labels,
+                    display_name,
+                    timestamp_to_jst_clock(participant.connected_at),
+                    activity
+                ));
+            }
+            return output;
+        }
+
         let mut output = String::new();
         output.push_str("\n\n============================================================\n");
         output.push_str("Participants:\n");
@@ -33,82 +97,935 @@ impl MessageFormatter {
             for participant in participants {
                 let is_me = participant.client_id == current_client_id;
                 let me_suffix = if is_me { " (me)" } else { "" };
+                let guest_suffix = if participant.guest { " (guest)" } else { "" };
                 let timestamp_str = timestamp_to_jst_rfc3339(participant.connected_at);
+                let display_name = match &participant.display_name {
+                    Some(display_name) => format!(" \"{}\"", display_name),
+                    None => String::new(),
+                };
+                let activity = match &participant.activity {
+                    Some(activity) => format!(" | {}", activity),
+                    None => String::new(),
+                };
                 output.push_str(&format!(
-                    "{}{} - entered at {}\n",
-                    participant.client_id, me_suffix, timestamp_str
+                    "{}{}{}{} - entered at {}{}\n",
+                    participant.client_id,
+                    display_name,
+                    me_suffix,
+                    guest_suffix,
+                    timestamp_str,
+                    activity
                 ));
             }
         }
 
-        output.push_str("============================================================\n\n");
-        output
+        output.push_str("============================================================\n\n");
+        output
+    }
+
+    /// Format the rooms available on the server
+    ///
+    /// # Arguments
+    ///
+    /// * `rooms` - Rooms listed in the room-list message
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with one line per room
+    pub fn format_room_list(&self, rooms: &[RoomInfo]) -> String {
+        if self.mode == OutputMode::Accessible {
+            let mut output = format!("Rooms: {}\n", rooms.len());
+            for room in rooms {
+                let topic = room
+                    .topic
+                    .as_ref()
+                    .map(|topic| format!(", topic: {}", topic))
+                    .unwrap_or_default();
+                output.push_str(&format!(
+                    "Room {}{}, {} participants, ID {}\n",
+                    room.name, topic, room.participant_count, room.room_id
+                ));
+            }
+            return output;
+        }
+
+        let mut output = String::new();
+        output.push_str("\n\n============================================================\n");
+        output.push_str("Rooms:\n");
+
+        if rooms.is_empty() {
+            output.push_str("(No rooms)\n");
+        } else {
+            for room in rooms {
+                let topic = room
+                    .topic
+                    .as_ref()
+                    .map(|topic| format!(" - {}", topic))
+                    .unwrap_or_default();
+                output.push_str(&format!(
+                    "{}{} ({} participants) [{}]\n",
+                    room.name, topic, room.participant_count, room.room_id
+                ));
+            }
+        }
+
+        output.push_str("============================================================\n\n");
+        output
+    }
+
+    /// Format the notice shown when leaving the room for another one
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the room (its slug, or its ID without one)
+    /// * `room_id` - The ID of the room
+    /// * `created` - Whether the client has just created the room with `/create`
+    pub fn format_joining_room(&self, name: &str, room_id: &str, created: bool) -> String {
+        let action = if created {
+            "Created room"
+        } else {
+            "Switching to room"
+        };
+        if self.mode == OutputMode::Accessible {
+            return format!("{} {}, ID {}\n", action, name, room_id);
+        }
+
+        format!("\n> {} {} [{}]\n", action, name, room_id)
+    }
+
+    /// Format a participant-joined notification
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - The ID of the participant who joined
+    /// * `connected_at` - Unix timestamp when the participant connected (milliseconds)
+    /// * `guest` - Whether the participant connected as a guest
+    /// * `notice` - Localized text of the notification, if any
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the join notification
+    pub fn format_participant_joined(
+        &self,
+        client_id: &str,
+        connected_at: i64,
+        guest: bool,
+        notice: Option<&str>,
+    ) -> String {
+        let guest_suffix = if guest { " (guest)" } else { "" };
+        if self.mode == OutputMode::Accessible {
+            let time = timestamp_to_jst_clock(connected_at);
+            return match notice {
+                Some(notice) => format!("Joined at {}: {}{}\n", time, notice, guest_suffix),
+                None => format!("Joined: {}{} at {}\n", client_id, guest_suffix, time),
+            };
+        }
+
+        let timestamp_str = timestamp_to_jst_rfc3339(connected_at);
+        match notice {
+            Some(notice) => format!("\n+ {}{} ({})\n", notice, guest_suffix, timestamp_str),
+            None => format!(
+                "\n+ {}{} entered at {}\n",
+                client_id, guest_suffix, timestamp_str
+            ),
+        }
+    }
+
+    /// Format a participant-left notification
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - The ID of the participant who left
+    /// * `disconnected_at` - Unix timestamp when the participant disconnected (milliseconds)
+    /// * `notice` - Localized text of the notification, if any
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the leave notification
+    pub fn format_participant_left(
+        &self,
+        client_id: &str,
+        disconnected_at: i64,
+        notice: Option<&str>,
+    ) -> String {
+        if self.mode == OutputMode::Accessible {
+            let time = timestamp_to_jst_clock(disconnected_at);
+            return match notice {
+                Some(notice) => format!("Left at {}: {}\n", time, notice),
+                None => format!("Left: {} at {}\n", client_id, time),
+            };
+        }
+
+        let timestamp_str = timestamp_to_jst_rfc3339(disconnected_at);
+        match notice {
+            Some(notice) => format!("\n- {} ({})\n", notice, timestamp_str),
+            None => format!("\n- {} left at {}\n", client_id, timestamp_str),
+        }
+    }
+
+    /// Format the activity a participant set or cleared
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - The ID of the participant
+    /// * `activity` - The new activity (`None` when cleared)
+    pub fn format_activity_updated(&self, client_id: &str, activity: Option<&str>) -> String {
+        if self.mode == OutputMode::Accessible {
+            return match activity {
+                Some(activity) => format!("Activity of {}: {}\n", client_id, activity),
+                None => format!("Activity of {} cleared\n", client_id),
+            };
+        }
+
+        match activity {
+            Some(activity) => format!("\n~ {}: {}\n", client_id, activity),
+            None => format!("\n~ {} cleared the activity\n", client_id),
+        }
+    }
+
+    /// Format the display name a participant set or cleared
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - The ID of the participant
+    /// * `display_name` - The new display name (`None` when cleared)
+    pub fn format_participant_renamed(
+        &self,
+        client_id: &str,
+        display_name: Option<&str>,
+    ) -> String {
+        if self.mode == OutputMode::Accessible {
+            return match display_name {
+                Some(display_name) => format!("Display name of {}: {}\n", client_id, display_name),
+                None => format!("Display name of {} cleared\n", client_id),
+            };
+        }
+
+        match display_name {
+            Some(display_name) => format!("\n* {} is now known as {}\n", client_id, display_name),
+            None => format!("\n* {} cleared the display name\n", client_id),
+        }
+    }
+
+    /// Format a chat message
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The client ID of the sender
+    /// * `content` - The message content
+    /// * `sent_at` - Unix timestamp when the message was sent (milliseconds)
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the chat message
+    pub fn format_chat_message(&self, from: &str, content: &str, sent_at: i64) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!(
+                "Message from {} at {}: {}\n",
+                from,
+                timestamp_to_jst_clock(sent_at),
+                content
+            );
+        }
+
+        let timestamp_str = timestamp_to_jst_rfc3339(sent_at);
+        format!(
+            "\n\n------------------------------------------------------------\n\
+             @{}: {}\n\
+             sent at {}\n\
+             ------------------------------------------------------------\n\n",
+            from, content, timestamp_str
+        )
+    }
+
+    /// Format a chat message forwarded from another room, with where it was first posted
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Copy of the message, sent by the client who forwarded it
+    /// * `origin` - Room, author and timestamp of the original message
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the provenance followed by the chat message
+    pub fn format_forwarded_chat(
+        &self,
+        message: &ChatMessage,
+        origin: &ForwardedFromInfo,
+    ) -> String {
+        let provenance = self.format_forwarded_from(origin);
+        let chat =
+            self.format_chat_message(&message.client_id, &message.content, message.timestamp);
+        if self.mode == OutputMode::Accessible {
+            return format!("{}\n{}", provenance, chat);
+        }
+        // Keep the blank lines above the separator of the chat message
+        format!("\n\n{}{}", provenance, chat.trim_start_matches('\n'))
+    }
+
+    /// Where a forwarded message was first posted, on one line
+    pub fn format_forwarded_from(&self, origin: &ForwardedFromInfo) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!(
+                "Forwarded from {} in room {}, sent at {}",
+                origin.client_id,
+                origin.room_id,
+                timestamp_to_jst_clock(origin.timestamp)
+            );
+        }
+
+        format!(
+            "» forwarded from @{} in room {} (sent at {})\n",
+            origin.client_id,
+            origin.room_id,
+            timestamp_to_jst_rfc3339(origin.timestamp)
+        )
+    }
+
+    /// Format the latest messages of the room sent when connecting
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - Messages of the room-history message, oldest first
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the messages between a header and a footer
+    pub fn format_room_history(&self, messages: &[ChatMessage]) -> String {
+        let mut output = if self.mode == OutputMode::Accessible {
+            format!("Recent messages: {}\n", messages.len())
+        } else {
+            format!("\n=== {} recent messages ===\n", messages.len())
+        };
+        for message in messages {
+            match (&message.poll, &message.forwarded_from) {
+                (Some(poll), _) => output.push_str(&self.format_poll(message, poll)),
+                (None, Some(origin)) => {
+                    output.push_str(&self.format_forwarded_chat(message, origin))
+                }
+                (None, None) => output.push_str(&self.format_chat_message(
+                    &message.client_id,
+                    &message.content,
+                    message.timestamp,
+                )),
+            }
+        }
+        if self.mode == OutputMode::Standard {
+            output.push_str("=== end of recent messages ===\n\n");
+        }
+        output
+    }
+
+    /// Format a confirmation message after sending
+    ///
+    /// # Arguments
+    ///
+    /// * `sent_at` - Unix timestamp when the message was sent (milliseconds)
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the sent confirmation
+    pub fn format_sent_confirmation(&self, sent_at: i64) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!("Sent at {}\n", timestamp_to_jst_clock(sent_at));
+        }
+
+        let timestamp_str = timestamp_to_jst_rfc3339(sent_at);
+        format!("sent at {}\n\n", timestamp_str)
+    }
+
+    /// Format the notice sent before the server restarts
+    ///
+    /// # Arguments
+    ///
+    /// * `reconnect_after_ms` - Milliseconds to wait before reconnecting
+    /// * `notice` - Localized text of the notice, if any
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the restart notice
+    pub fn format_server_shutdown(&self, reconnect_after_ms: u64, notice: Option<&str>) -> String {
+        let seconds = reconnect_after_ms as f64 / 1000.0;
+        if self.mode == OutputMode::Accessible {
+            let notice = notice.unwrap_or("Server is restarting");
+            return format!(
+                "Server notice: {}, reconnecting in {:.1} seconds\n",
+                notice, seconds
+            );
+        }
+
+        match notice {
+            Some(notice) => format!("\n! {} ({:.1}s)\n", notice, seconds),
+            None => format!(
+                "\n! Server is restarting, reconnecting in {:.1}s...\n",
+                seconds
+            ),
+        }
+    }
+
+    /// Format the notice shown when a newer connection with the same client ID took over
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the notice
+    pub fn format_session_replaced(&self) -> String {
+        if self.mode == OutputMode::Accessible {
+            return "Session notice: this session was replaced by a newer connection with the same client ID\n"
+                .to_string();
+        }
+
+        "\n! This session was replaced by a newer connection with the same client ID\n".to_string()
+    }
+
+    /// Format the notice shown when an admin kicked or banned the client
+    ///
+    /// # Arguments
+    ///
+    /// * `banned` - Whether the client's future connections are rejected
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the notice
+    pub fn format_removed(&self, banned: bool) -> String {
+        let what = if banned {
+            "banned from the server by an admin"
+        } else {
+            "kicked from the server by an admin"
+        };
+        if self.mode == OutputMode::Accessible {
+            return format!("Session notice: you were {}\n", what);
+        }
+
+        format!("\n! You were {}\n", what)
+    }
+
+    /// Format the notice shown while waiting for a moderator to approve joining the room
+    ///
+    /// # Arguments
+    ///
+    /// * `notice` - Waiting notice sent by the server
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the notice
+    pub fn format_join_pending(&self, notice: &str) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!("Join notice: {}\n", notice);
+        }
+
+        format!("\n… {}\n", notice)
+    }
+
+    /// Format the notice shown when a client asks to join a room requiring approval
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - Client waiting for approval
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the notice
+    pub fn format_join_requested(&self, client_id: &str) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!("Join request: {} is waiting for approval\n", client_id);
+        }
+
+        format!("\n? {} is waiting for approval to join\n", client_id)
+    }
+
+    /// Format the notice shown when a moderator rejected joining the room
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the notice
+    pub fn format_join_rejected(&self) -> String {
+        if self.mode == OutputMode::Accessible {
+            return "Session notice: a moderator rejected your request to join the room\n"
+                .to_string();
+        }
+
+        "\n! A moderator rejected your request to join the room\n".to_string()
+    }
+
+    /// Format the notice shown when an admin froze or unfroze the room
+    ///
+    /// # Arguments
+    ///
+    /// * `frozen` - Whether messages sent to the room are now rejected
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the notice
+    pub fn format_room_frozen(&self, frozen: bool) -> String {
+        let what = if frozen {
+            "froze the room; messages are not accepted until it is unfrozen"
+        } else {
+            "unfroze the room; messages are accepted again"
+        };
+        if self.mode == OutputMode::Accessible {
+            return format!("Room notice: an admin {}\n", what);
+        }
+
+        format!("\n! An admin {}\n", what)
+    }
+
+    /// Format the notice shown when an admin disconnected everyone in the room
+    ///
+    /// # Arguments
+    ///
+    /// * `reason` - Why the admin disconnected the participants
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the notice
+    pub fn format_disconnected(&self, reason: &str) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!(
+                "Session notice: an admin disconnected everyone in the room: {}\n",
+                reason
+            );
+        }
+
+        format!(
+            "\n! An admin disconnected everyone in the room: {}\n",
+            reason
+        )
+    }
+
+    /// Format the notice shown when a message was deleted from the room history
+    ///
+    /// # Arguments
+    ///
+    /// * `seq` - Sequence number of the deleted message
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the notice
+    pub fn format_message_deleted(&self, seq: u64) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!("Deletion notice: message {} was deleted\n", seq);
+        }
+
+        format!("\n- Message #{} was deleted\n", seq)
+    }
+
+    /// Format a poll posted to the room, numbering its options from 1 as `/vote` expects
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - `poll` message whose content is the question
+    /// * `poll` - Options of the poll with their votes
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the question and one line per option
+    pub fn format_poll(&self, message: &ChatMessage, poll: &PollInfo) -> String {
+        let seq = message.seq.unwrap_or_default();
+        let options = self.format_poll_options(&poll.options);
+        if self.mode == OutputMode::Accessible {
+            return format!(
+                "Poll {} from {} at {}: {}\n{}",
+                seq,
+                message.client_id,
+                timestamp_to_jst_clock(message.timestamp),
+                message.content,
+                options
+            );
+        }
+
+        format!(
+            "\n\n------------------------------------------------------------\n\
+             @{} asks (poll #{}): {}\n\
+             {}\
+             vote with /vote {} <option number>\n\
+             ------------------------------------------------------------\n\n",
+            message.client_id, seq, message.content, options, seq
+        )
+    }
+
+    /// Format the new tally of a poll after a vote
+    ///
+    /// # Arguments
+    ///
+    /// * `seq` - Sequence number of the poll
+    /// * `options` - Options of the poll with their votes
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with one line per option
+    pub fn format_poll_updated(&self, seq: u64, options: &[PollOptionInfo]) -> String {
+        let options = self.format_poll_options(options);
+        if self.mode == OutputMode::Accessible {
+            return format!("Poll {} results:\n{}", seq, options);
+        }
+
+        format!("\n# Poll #{} results:\n{}", seq, options)
+    }
+
+    /// Format the confirmation of a star added to or removed from a message
+    ///
+    /// # Arguments
+    ///
+    /// * `seq` - Sequence number of the message
+    /// * `starred` - Whether the message is starred now
+    pub fn format_star_updated(&self, seq: u64, starred: bool) -> String {
+        let action = if starred { "Starred" } else { "Unstarred" };
+        if self.mode == OutputMode::Accessible {
+            return format!("{} message {}\n", action, seq);
+        }
+
+        let mark = if starred { "★" } else { "☆" };
+        format!("\n{} {} message #{}\n", mark, action, seq)
+    }
+
+    /// Format the messages the client starred, in the order they were starred
+    pub fn format_starred_messages(&self, messages: &[StarredMessageDto]) -> String {
+        if self.mode == OutputMode::Accessible {
+            let mut output = format!("Starred messages: {}\n", messages.len());
+            for starred in messages {
+                output.push_str(&format!(
+                    "Message {} in room {} from {}: {}\n",
+                    starred.message.seq,
+                    starred.room_id,
+                    starred.message.client_id,
+                    starred.message.content
+                ));
+            }
+            return output;
+        }
+
+        let mut output = String::new();
+        output.push_str("\n\n============================================================\n");
+        output.push_str("Starred messages:\n");
+
+        if messages.is_empty() {
+            output.push_str("(No starred messages)\n");
+        } else {
+            for starred in messages {
+                output.push_str(&format!(
+                    "★ #{} [{}] @{}: {} (sent at {})\n",
+                    starred.message.seq,
+                    starred.room_id,
+                    starred.message.client_id,
+                    starred.message.content,
+                    starred.message.timestamp
+                ));
+            }
+        }
+
+        output.push_str("============================================================\n\n");
+        output
+    }
+
+    /// Format the messages of the room found with `/search`, newest first
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Text searched for
+    /// * `messages` - Messages containing the text
+    pub fn format_search_results(&self, query: &str, messages: &[MessageDto]) -> String {
+        if self.mode == OutputMode::Accessible {
+            let mut output = format!("Messages matching \"{}\": {}\n", query, messages.len());
+            for message in messages {
+                output.push_str(&format!(
+                    "Message {} from {} at {}: {}\n",
+                    message.seq, message.client_id, message.timestamp, message.content
+                ));
+            }
+            return output;
+        }
+
+        let mut output = String::new();
+        output.push_str("\n\n============================================================\n");
+        output.push_str(&format!("Messages matching \"{}\":\n", query));
+
+        if messages.is_empty() {
+            output.push_str("(No matching messages)\n");
+        } else {
+            for message in messages {
+                output.push_str(&format!(
+                    "#{} [{}] @{}: {}\n",
+                    message.seq, message.timestamp, message.client_id, message.content
+                ));
+            }
+            output.push_str("(Type /jump <message number> to scroll back to a message)\n");
+        }
+
+        output.push_str("============================================================\n\n");
+        output
+    }
+
+    /// Format the notice shown before the cached messages of the room while the server cannot
+    /// be reached
+    ///
+    /// # Arguments
+    ///
+    /// * `reason` - Why the connection failed
+    /// * `count` - Number of cached messages shown
+    pub fn format_offline_history(&self, reason: &str, count: usize) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!(
+                "Cannot reach the server ({}), cached messages: {}\n",
+                reason, count
+            );
+        }
+
+        format!(
+            "\n! Cannot reach the server ({}); showing {} cached messages\n",
+            reason, count
+        )
+    }
+
+    /// Format the notice shown before the results of `/search` found in the cached messages
+    /// when the server could not search
+    ///
+    /// # Arguments
+    ///
+    /// * `reason` - Why the search on the server failed
+    pub fn format_cache_fallback(&self, reason: &str) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!("Searching the cached messages: {}\n", reason);
+        }
+
+        format!(
+            "\n! Cannot search on the server ({}); searching the cached messages\n",
+            reason
+        )
+    }
+
+    /// Format the notice shown for `/jump` outside the terminal UI, which has no scrollback
+    /// to jump in
+    pub fn format_jump_unavailable(&self) -> String {
+        if self.mode == OutputMode::Accessible {
+            return "Jumping to a message needs the terminal UI\n".to_string();
+        }
+
+        "\n! Jumping to a message needs the terminal UI (--ui tui)\n".to_string()
+    }
+
+    /// One line per option of a poll, numbered from 1
+    fn format_poll_options(&self, options: &[PollOptionInfo]) -> String {
+        options
+            .iter()
+            .enumerate()
+            .map(|(i, option)| {
+                let votes = match option.votes {
+                    1 => "1 vote".to_string(),
+                    votes => format!("{} votes", votes),
+                };
+                format!("  {}. {} ({})\n", i + 1, option.label, votes)
+            })
+            .collect()
+    }
+
+    /// Format how to use a command typed with wrong arguments
+    ///
+    /// # Arguments
+    ///
+    /// * `usage` - Command with its arguments
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the usage
+    pub fn format_usage(&self, usage: &str) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!("Usage: {}\n", usage);
+        }
+
+        format!("\n! Usage: {}\n", usage)
+    }
+
+    /// Format the welcome message of the room sent to this client after joining
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - Text of the welcome message
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the welcome message
+    pub fn format_welcome(&self, content: &str) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!(
+                "Welcome message: {}
+",
+                content
+            );
+        }
+
+        format!("\n* {}\n", content)
+    }
+
+    /// Format the server asking this client to slow down, or lifting the request
+    ///
+    /// # Arguments
+    ///
+    /// * `send_interval_ms` - Minimum milliseconds between sent messages (`0` when lifted)
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the notice
+    pub fn format_slow_down(&self, send_interval_ms: u64) -> String {
+        let seconds = send_interval_ms as f64 / 1000.0;
+        if self.mode == OutputMode::Accessible {
+            return match send_interval_ms {
+                0 => "Server notice: sending messages at normal speed again\n".to_string(),
+                _ => format!(
+                    "Server notice: server is busy, sending one message every {:.1} seconds\n",
+                    seconds
+                ),
+            };
+        }
+
+        match send_interval_ms {
+            0 => "\n! Server caught up, sending normally\n".to_string(),
+            _ => format!(
+                "\n! Server is busy, sending one message every {:.1}s\n",
+                seconds
+            ),
+        }
+    }
+
+    /// Format the round trip to the server measured with `/ping`
+    ///
+    /// # Arguments
+    ///
+    /// * `rtt` - Time between sending the Ping and receiving its Pong
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the round-trip time
+    pub fn format_latency(&self, rtt: Duration) -> String {
+        let millis = rtt.as_secs_f64() * 1000.0;
+        if self.mode == OutputMode::Accessible {
+            return format!(
+                "Latency: round trip to the server took {:.1} milliseconds\n",
+                millis
+            );
+        }
+
+        format!("\n~ Pong from the server in {:.1}ms\n", millis)
     }
 
-    /// Format a participant-joined notification
+    /// Format the notice shown when the local clock disagrees with the server's
     ///
     /// # Arguments
     ///
-    /// * `client_id` - The ID of the participant who joined
-    /// * `connected_at` - Unix timestamp when the participant connected (milliseconds)
+    /// * `offset_ms` - Milliseconds to add to the local clock to get the server's
     ///
     /// # Returns
     ///
-    /// A formatted string with the join notification
-    pub fn format_participant_joined(client_id: &str, connected_at: i64) -> String {
-        let timestamp_str = timestamp_to_jst_rfc3339(connected_at);
-        format!("\n+ {} entered at {}\n", client_id, timestamp_str)
+    /// A formatted string with the notice
+    pub fn format_clock_skew(&self, offset_ms: i64) -> String {
+        let seconds = offset_ms.unsigned_abs() as f64 / 1000.0;
+        let direction = if offset_ms > 0 { "behind" } else { "ahead of" };
+        if self.mode == OutputMode::Accessible {
+            return format!(
+                "Clock notice: your clock is {:.1} seconds {} the server; sent times follow the server's clock\n",
+                seconds, direction
+            );
+        }
+
+        format!(
+            "\n! Your clock is {:.1}s {} the server; sent times follow the server's clock\n",
+            seconds, direction
+        )
     }
 
-    /// Format a participant-left notification
+    /// Format the indicator of the participants typing
     ///
     /// # Arguments
     ///
-    /// * `client_id` - The ID of the participant who left
-    /// * `disconnected_at` - Unix timestamp when the participant disconnected (milliseconds)
+    /// * `client_ids` - Participants typing, in the order they started
     ///
     /// # Returns
     ///
-    /// A formatted string with the leave notification
-    pub fn format_participant_left(client_id: &str, disconnected_at: i64) -> String {
-        let timestamp_str = timestamp_to_jst_rfc3339(disconnected_at);
-        format!("\n- {} left at {}\n", client_id, timestamp_str)
+    /// A formatted string with the indicator (e.g. `alice is typing...`)
+    pub fn format_typing(&self, client_ids: &[String]) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!("Typing: {}\n", client_ids.join(", "));
+        }
+
+        let names = match client_ids {
+            [] => return String::new(),
+            [only] => return format!("\n... {} is typing...\n", only),
+            [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+        };
+        format!("\n... {} are typing...\n", names)
     }
 
-    /// Format a chat message
+    /// Format an error sent by the server for a rejected message
     ///
     /// # Arguments
     ///
-    /// * `from` - The client ID of the sender
-    /// * `content` - The message content
-    /// * `sent_at` - Unix timestamp when the message was sent (milliseconds)
+    /// * `code` - Error code (e.g. `invalid_message`)
+    /// * `message` - Description of the problem
     ///
     /// # Returns
     ///
-    /// A formatted string with the chat message
-    pub fn format_chat_message(from: &str, content: &str, sent_at: i64) -> String {
-        let timestamp_str = timestamp_to_jst_rfc3339(sent_at);
-        format!(
-            "\n\n------------------------------------------------------------\n\
-             @{}: {}\n\
-             sent at {}\n\
-             ------------------------------------------------------------\n\n",
-            from, content, timestamp_str
-        )
+    /// A formatted string with the error
+    pub fn format_error(&self, code: &str, message: &str) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!("Error from server ({}): {}\n", code, message);
+        }
+
+        format!("\n! Rejected by server ({}): {}\n", code, message)
     }
 
-    /// Format a confirmation message after sending
+    /// Format the notice shown when quiet hours start
     ///
     /// # Arguments
     ///
-    /// * `sent_at` - Unix timestamp when the message was sent (milliseconds)
+    /// * `until` - Time (JST) the quiet hours end
     ///
     /// # Returns
     ///
-    /// A formatted string with the sent confirmation
-    pub fn format_sent_confirmation(sent_at: i64) -> String {
-        let timestamp_str = timestamp_to_jst_rfc3339(sent_at);
-        format!("sent at {}\n", timestamp_str)
+    /// A formatted string with the notice
+    pub fn format_quiet_hours_started(&self, until: NaiveTime) -> String {
+        let until = until.format("%H:%M");
+        if self.mode == OutputMode::Accessible {
+            return format!("Quiet hours started: mention bells muted until {}\n", until);
+        }
+
+        format!("\n~ Quiet hours until {}: mention bells are muted\n", until)
+    }
+
+    /// Format the summary shown when quiet hours end
+    ///
+    /// # Arguments
+    ///
+    /// * `missed` - Mentions received during the quiet hours
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with one line per missed mention
+    pub fn format_quiet_hours_ended(&self, missed: &[MissedMention]) -> String {
+        if self.mode == OutputMode::Accessible {
+            let mut output = format!("Quiet hours ended: {} missed mentions\n", missed.len());
+            for mention in missed {
+                output.push_str(&format!(
+                    "Missed mention from {} at {}: {}\n",
+                    mention.from,
+                    timestamp_to_jst_clock(mention.sent_at),
+                    mention.content
+                ));
+            }
+            return output;
+        }
+
+        if missed.is_empty() {
+            return "\n~ Quiet hours ended: no missed mentions\n".to_string();
+        }
+        let mut output = format!("\n~ Quiet hours ended: {} missed mentions\n", missed.len());
+        for mention in missed {
+            output.push_str(&format!(
+                "  @{}: {} (sent at {})\n",
+                mention.from,
+                mention.content,
+                timestamp_to_jst_rfc3339(mention.sent_at)
+            ));
+        }
+        output
     }
 
     /// Format a binary message notification
@@ -120,7 +1037,11 @@ impl MessageFormatter {
     /// # Returns
     ///
     /// A formatted string with the binary data notification
-    pub fn format_binary_message(byte_count: usize) -> String {
+    pub fn format_binary_message(&self, byte_count: usize) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!("Binary message received: {} bytes\n", byte_count);
+        }
+
         format!("\n← Received {} bytes of binary data\n", byte_count)
     }
 
@@ -133,7 +1054,11 @@ impl MessageFormatter {
     /// # Returns
     ///
     /// A formatted string with the raw message
-    pub fn format_raw_message(text: &str) -> String {
+    pub fn format_raw_message(&self, text: &str) -> String {
+        if self.mode == OutputMode::Accessible {
+            return format!("Unrecognized message received: {}\n", text);
+        }
+
         format!("\n← Received: {}\n", text)
     }
 }
@@ -141,6 +1066,7 @@ impl MessageFormatter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use engawa_server::infrastructure::dto::websocket::MessageType;
 
     #[test]
     fn test_format_room_connected_with_empty_participants() {
@@ -150,7 +1076,8 @@ mod tests {
         let current_client_id = "alice";
 
         // when (操作):
-        let result = MessageFormatter::format_room_connected(&participants, current_client_id);
+        let result =
+            MessageFormatter::default().format_room_connected(&participants, current_client_id);
 
         // then (期待する結果):
         assert!(result.contains("Participants:"));
@@ -158,6 +1085,173 @@ mod tests {
         assert!(result.contains("============================================================"));
     }
 
+    #[test]
+    fn test_format_room_list() {
+        // テスト項目: ルームごとに名前・トピック・参加者数・ID が表示される
+        // given (前提条件):
+        let rooms = vec![
+            RoomInfo {
+                room_id: "room-1".to_string(),
+                name: "general".to_string(),
+                topic: Some("Daily chat".to_string()),
+                participant_count: 3,
+            },
+            RoomInfo {
+                room_id: "room-2".to_string(),
+                name: "random".to_string(),
+                topic: None,
+                participant_count: 0,
+            },
+        ];
+
+        // when (操作):
+        let result = MessageFormatter::default().format_room_list(&rooms);
+        let empty = MessageFormatter::default().format_room_list(&[]);
+
+        // then (期待する結果):
+        assert!(result.contains("general - Daily chat (3 participants) [room-1]"));
+        assert!(result.contains("random (0 participants) [room-2]"));
+        assert!(empty.contains("(No rooms)"));
+    }
+
+    #[test]
+    fn test_format_joining_room() {
+        // テスト項目: 作成したルームと切り替え先のルームの通知が通常モードとアクセシブルモードでフォーマットされる
+        // given (前提条件):
+        let formatter = MessageFormatter::default();
+        let accessible = MessageFormatter::new(OutputMode::Accessible);
+
+        // when (操作):
+        let created = formatter.format_joining_room("design", "room-1", true);
+        let switching = formatter.format_joining_room("room-2", "room-2", false);
+
+        // then (期待する結果):
+        assert_eq!(created, "\n> Created room design [room-1]\n");
+        assert_eq!(switching, "\n> Switching to room room-2 [room-2]\n");
+        assert_eq!(
+            accessible.format_joining_room("design", "room-1", true),
+            "Created room design, ID room-1\n"
+        );
+    }
+
+    #[test]
+    fn test_format_starred_messages() {
+        // テスト項目: スターを付けたメッセージごとに番号・ルーム・送信者・内容が表示される
+        // given (前提条件):
+        let messages = vec![StarredMessageDto {
+            room_id: "room-1".to_string(),
+            message: MessageDto {
+                seq: 12,
+                client_id: "bob".to_string(),
+                content: "Meeting at 3pm".to_string(),
+                timestamp: "2023-01-01T00:00:00.000+09:00".to_string(),
+                tags: Vec::new(),
+            },
+            starred_at: "2023-01-01T00:05:00.000+09:00".to_string(),
+        }];
+
+        // when (操作):
+        let result = MessageFormatter::default().format_starred_messages(&messages);
+        let accessible =
+            MessageFormatter::new(OutputMode::Accessible).format_starred_messages(&messages);
+        let empty = MessageFormatter::default().format_starred_messages(&[]);
+
+        // then (期待する結果):
+        assert!(result.contains(
+            "★ #12 [room-1] @bob: Meeting at 3pm (sent at 2023-01-01T00:00:00.000+09:00)"
+        ));
+        assert_eq!(
+            accessible,
+            "Starred messages: 1\nMessage 12 in room room-1 from bob: Meeting at 3pm\n"
+        );
+        assert!(empty.contains("(No starred messages)"));
+    }
+
+    #[test]
+    fn test_format_search_results() {
+        // テスト項目: 検索に一致したメッセージごとに番号・時刻・送信者・内容が表示される
+        // given (前提条件):
+        let messages = vec![MessageDto {
+            seq: 12,
+            client_id: "bob".to_string(),
+            content: "deploy done".to_string(),
+            timestamp: "2023-01-01T00:00:00.000+09:00".to_string(),
+            tags: Vec::new(),
+        }];
+
+        // when (操作):
+        let result = MessageFormatter::default().format_search_results("deploy", &messages);
+        let accessible = MessageFormatter::new(OutputMode::Accessible)
+            .format_search_results("deploy", &messages);
+        let empty = MessageFormatter::default().format_search_results("deploy", &[]);
+
+        // then (期待する結果):
+        assert!(result.contains("Messages matching \"deploy\":"));
+        assert!(result.contains("#12 [2023-01-01T00:00:00.000+09:00] @bob: deploy done"));
+        assert_eq!(
+            accessible,
+            "Messages matching \"deploy\": 1\nMessage 12 from bob at 2023-01-01T00:00:00.000+09:00: deploy done\n"
+        );
+        assert!(empty.contains("(No matching messages)"));
+        assert!(
+            MessageFormatter::default()
+                .format_jump_unavailable()
+                .contains("needs the terminal UI")
+        );
+        assert_eq!(
+            MessageFormatter::new(OutputMode::Accessible).format_cache_fallback("timed out"),
+            "Searching the cached messages: timed out\n"
+        );
+    }
+
+    #[test]
+    fn test_format_activity_updated() {
+        // テスト項目: アクティビティの設定と消去が通常モードとアクセシブルモードでフォーマットされる
+        // given (前提条件):
+        let formatter = MessageFormatter::default();
+        let accessible = MessageFormatter::new(OutputMode::Accessible);
+
+        // when (操作):
+        let set = formatter.format_activity_updated("bob", Some("reviewing PR #42"));
+        let cleared = formatter.format_activity_updated("bob", None);
+
+        // then (期待する結果):
+        assert_eq!(set, "\n~ bob: reviewing PR #42\n");
+        assert_eq!(cleared, "\n~ bob cleared the activity\n");
+        assert_eq!(
+            accessible.format_activity_updated("bob", Some("reviewing PR #42")),
+            "Activity of bob: reviewing PR #42\n"
+        );
+        assert_eq!(
+            accessible.format_activity_updated("bob", None),
+            "Activity of bob cleared\n"
+        );
+    }
+
+    #[test]
+    fn test_format_participant_renamed() {
+        // テスト項目: 表示名の設定と消去が通常モードとアクセシブルモードでフォーマットされる
+        // given (前提条件):
+        let formatter = MessageFormatter::default();
+        let accessible = MessageFormatter::new(OutputMode::Accessible);
+
+        // when (操作):
+        let set = formatter.format_participant_renamed("bob", Some("Bob Jones"));
+        let cleared = formatter.format_participant_renamed("bob", None);
+
+        // then (期待する結果):
+        assert_eq!(set, "\n* bob is now known as Bob Jones\n");
+        assert_eq!(cleared, "\n* bob cleared the display name\n");
+        assert_eq!(
+            accessible.format_participant_renamed("bob", Some("Bob Jones")),
+            "Display name of bob: Bob Jones\n"
+        );
+        assert_eq!(
+            accessible.format_participant_renamed("bob", None),
+            "Display name of bob cleared\n"
+        );
+    }
+
     #[test]
     fn test_format_room_connected_with_single_participant() {
         // テスト項目: 単一参加者の場合、正しくフォーマットされる
@@ -165,11 +1259,15 @@ mod tests {
         let participants = vec![ParticipantInfo {
             client_id: "alice".to_string(),
             connected_at: 1672498800000,
+            guest: false,
+            display_name: None,
+            activity: None,
         }];
         let current_client_id = "alice";
 
         // when (操作):
-        let result = MessageFormatter::format_room_connected(&participants, current_client_id);
+        let result =
+            MessageFormatter::default().format_room_connected(&participants, current_client_id);
 
         // then (期待する結果):
         assert!(result.contains("alice (me)"));
@@ -185,21 +1283,29 @@ mod tests {
             ParticipantInfo {
                 client_id: "alice".to_string(),
                 connected_at: 1672498800000,
+                guest: false,
+                display_name: None,
+                activity: None,
             },
             ParticipantInfo {
-                client_id: "bob".to_string(),
+                client_id: "guest-7f3a".to_string(),
                 connected_at: 1672498900000,
+                guest: true,
+                display_name: None,
+                activity: Some("reviewing PR #42".to_string()),
             },
         ];
         let current_client_id = "alice";
 
         // when (操作):
-        let result = MessageFormatter::format_room_connected(&participants, current_client_id);
+        let result =
+            MessageFormatter::default().format_room_connected(&participants, current_client_id);
 
         // then (期待する結果):
         assert!(result.contains("alice (me)"));
-        assert!(result.contains("bob - entered at"));
-        assert!(!result.contains("bob (me)"));
+        assert!(result.contains("guest-7f3a (guest) - entered at"));
+        assert!(result.contains("+09:00 | reviewing PR #42\n"));
+        assert!(!result.contains("guest-7f3a (me)"));
     }
 
     #[test]
@@ -210,10 +1316,15 @@ mod tests {
         let connected_at = 1672498800000;
 
         // when (操作):
-        let result = MessageFormatter::format_participant_joined(client_id, connected_at);
+        let result = MessageFormatter::default().format_participant_joined(
+            client_id,
+            connected_at,
+            false,
+            None,
+        );
 
         // then (期待する結果):
-        assert!(result.contains("+ bob"));
+        assert!(result.contains("+ bob entered"));
         assert!(result.contains("entered at"));
         assert!(result.contains("2023-01-01"));
     }
@@ -226,7 +1337,8 @@ mod tests {
         let disconnected_at = 1672498800000;
 
         // when (操作):
-        let result = MessageFormatter::format_participant_left(client_id, disconnected_at);
+        let result =
+            MessageFormatter::default().format_participant_left(client_id, disconnected_at, None);
 
         // then (期待する結果):
         assert!(result.contains("- charlie"));
@@ -234,6 +1346,33 @@ mod tests {
         assert!(result.contains("2023-01-01"));
     }
 
+    #[test]
+    fn test_format_localized_notices() {
+        // テスト項目: 通知文がある場合は通知文とタイムスタンプが表示される
+        // given (前提条件):
+        let at = 1672498800000;
+
+        // when (操作):
+        let joined = MessageFormatter::default().format_participant_joined(
+            "bob",
+            at,
+            true,
+            Some("bob さんが入室しました"),
+        );
+        let left = MessageFormatter::default().format_participant_left(
+            "bob",
+            at,
+            Some("bob さんが退室しました"),
+        );
+        let shutdown = MessageFormatter::default()
+            .format_server_shutdown(1500, Some("サーバを再起動しています。まもなく再接続します"));
+
+        // then (期待する結果):
+        assert!(joined.contains("+ bob さんが入室しました (guest) (2023-01-01"));
+        assert!(left.contains("- bob さんが退室しました (2023-01-01"));
+        assert!(shutdown.contains("! サーバを再起動しています。まもなく再接続します (1.5s)"));
+    }
+
     #[test]
     fn test_format_chat_message() {
         // テスト項目: チャットメッセージが正しくフォーマットされる
@@ -243,7 +1382,7 @@ mod tests {
         let sent_at = 1672498800000;
 
         // when (操作):
-        let result = MessageFormatter::format_chat_message(from, content, sent_at);
+        let result = MessageFormatter::default().format_chat_message(from, content, sent_at);
 
         // then (期待する結果):
         assert!(result.contains("@alice:"));
@@ -253,6 +1392,110 @@ mod tests {
         assert!(result.contains("------------------------------------------------------------"));
     }
 
+    #[test]
+    fn test_format_forwarded_chat() {
+        // テスト項目: 転送されたメッセージは転送元のルーム・送信者・送信日時の後に表示される
+        // given (前提条件):
+        let origin = ForwardedFromInfo {
+            room_id: "room-1".to_string(),
+            client_id: "bob".to_string(),
+            timestamp: 1672498800000,
+        };
+        let message = ChatMessage {
+            r#type: MessageType::Chat,
+            client_id: "alice".to_string(),
+            content: "Lunch at noon".to_string(),
+            timestamp: 1672502400000,
+            seq: Some(3),
+            poll: None,
+            forwarded_from: Some(origin.clone()),
+        };
+
+        // when (操作):
+        let result = MessageFormatter::default().format_forwarded_chat(&message, &origin);
+        let accessible =
+            MessageFormatter::new(OutputMode::Accessible).format_forwarded_chat(&message, &origin);
+
+        // then (期待する結果):
+        assert!(
+            result.starts_with(
+                "\n\n» forwarded from @bob in room room-1 (sent at 2023-01-01T00:00:00"
+            )
+        );
+        assert!(result.contains("@alice: Lunch at noon"));
+        assert_eq!(
+            accessible,
+            "Forwarded from bob in room room-1, sent at 00:00\n\
+             Message from alice at 01:00: Lunch at noon\n"
+        );
+    }
+
+    #[test]
+    fn test_format_poll() {
+        // テスト項目: 投票は選択肢を 1 始まりの番号と票数付きで表示し、集計も同じ形式で表示される
+        // given (前提条件):
+        let option = |label: &str, votes: usize| PollOptionInfo {
+            label: label.to_string(),
+            votes,
+        };
+        let poll = PollInfo {
+            options: vec![option("Ramen", 0), option("Sushi", 0)],
+        };
+        let message = ChatMessage {
+            r#type: MessageType::Poll,
+            client_id: "alice".to_string(),
+            content: "Lunch?".to_string(),
+            timestamp: 1672498800000,
+            seq: Some(7),
+            poll: Some(poll.clone()),
+            forwarded_from: None,
+        };
+
+        // when (操作):
+        let result = MessageFormatter::default().format_poll(&message, &poll);
+        let accessible = MessageFormatter::new(OutputMode::Accessible).format_poll(&message, &poll);
+        let updated = MessageFormatter::default()
+            .format_poll_updated(7, &[option("Ramen", 1), option("Sushi", 2)]);
+
+        // then (期待する結果):
+        assert!(result.contains(
+            "@alice asks (poll #7): Lunch?\n  1. Ramen (0 votes)\n  2. Sushi (0 votes)\n"
+        ));
+        assert!(result.contains("vote with /vote 7 <option number>"));
+        assert!(accessible.starts_with("Poll 7 from alice at "));
+        assert!(
+            updated.contains("# Poll #7 results:\n  1. Ramen (1 vote)\n  2. Sushi (2 votes)\n")
+        );
+    }
+
+    #[test]
+    fn test_format_room_history() {
+        // テスト項目: 接続時の履歴が見出しの後に古い順で表示される
+        // given (前提条件):
+        let message = |seq: u64, content: &str| ChatMessage {
+            r#type: MessageType::Chat,
+            client_id: "alice".to_string(),
+            content: content.to_string(),
+            timestamp: 1672498800000,
+            seq: Some(seq),
+            poll: None,
+            forwarded_from: None,
+        };
+        let messages = vec![message(1, "first"), message(2, "second")];
+
+        // when (操作):
+        let result = MessageFormatter::default().format_room_history(&messages);
+        let accessible =
+            MessageFormatter::new(OutputMode::Accessible).format_room_history(&messages);
+
+        // then (期待する結果):
+        assert!(result.starts_with("\n=== 2 recent messages ==="));
+        assert!(result.find("first").unwrap() < result.find("second").unwrap());
+        assert!(result.ends_with("=== end of recent messages ===\n\n"));
+        assert!(accessible.starts_with("Recent messages: 2\n"));
+        assert!(accessible.contains("Message from alice at "));
+    }
+
     #[test]
     fn test_format_sent_confirmation() {
         // テスト項目: 送信確認メッセージが正しくフォーマットされる
@@ -260,7 +1503,7 @@ mod tests {
         let sent_at = 1672498800000;
 
         // when (操作):
-        let result = MessageFormatter::format_sent_confirmation(sent_at);
+        let result = MessageFormatter::default().format_sent_confirmation(sent_at);
 
         // then (期待する結果):
         assert!(result.contains("sent at"));
@@ -274,13 +1517,130 @@ mod tests {
         let byte_count = 1024;
 
         // when (操作):
-        let result = MessageFormatter::format_binary_message(byte_count);
+        let result = MessageFormatter::default().format_binary_message(byte_count);
 
         // then (期待する結果):
         assert!(result.contains("1024 bytes"));
         assert!(result.contains("Received"));
     }
 
+    #[test]
+    fn test_format_error() {
+        // テスト項目: サーバーからのエラーがコードと説明付きでフォーマットされる
+        // when (操作):
+        let result = MessageFormatter::default()
+            .format_error("unknown_message_type", "Unknown message type 'x'");
+
+        // then (期待する結果):
+        assert!(result.contains("unknown_message_type"));
+        assert!(result.contains("Unknown message type 'x'"));
+    }
+
+    #[test]
+    fn test_format_message_deleted() {
+        // テスト項目: 削除されたメッセージの通知がシーケンス番号付きでフォーマットされる
+        // when (操作):
+        let result = MessageFormatter::default().format_message_deleted(7);
+
+        // then (期待する結果):
+        assert!(result.contains("#7 was deleted"));
+    }
+
+    #[test]
+    fn test_format_incident_notices() {
+        // テスト項目: ルームの凍結・解除と全員の切断の通知が、アクセシブル出力ではラベル付きの 1 行でフォーマットされる
+        // given (前提条件):
+        let standard = MessageFormatter::default();
+        let accessible = MessageFormatter::new(OutputMode::Accessible);
+
+        // when (操作):
+        let frozen = standard.format_room_frozen(true);
+        let unfrozen = accessible.format_room_frozen(false);
+        let disconnected = standard.format_disconnected("spam wave");
+
+        // then (期待する結果):
+        assert!(frozen.starts_with("\n! An admin froze the room"));
+        assert!(unfrozen.starts_with("Room notice: an admin unfroze the room"));
+        assert_eq!(
+            disconnected,
+            "\n! An admin disconnected everyone in the room: spam wave\n"
+        );
+    }
+
+    #[test]
+    fn test_format_join_approval_notices() {
+        // テスト項目: 参加の承認待ち・承認の要求・拒否の通知が、アクセシブル出力ではラベル付きの 1 行でフォーマットされる
+        // given (前提条件):
+        let standard = MessageFormatter::default();
+        let accessible = MessageFormatter::new(OutputMode::Accessible);
+
+        // when (操作):
+        let pending = standard.format_join_pending("Waiting for a moderator");
+        let requested = accessible.format_join_requested("bob");
+        let rejected = accessible.format_join_rejected();
+
+        // then (期待する結果):
+        assert_eq!(pending, "\n… Waiting for a moderator\n");
+        assert_eq!(requested, "Join request: bob is waiting for approval\n");
+        assert!(rejected.starts_with("Session notice: a moderator rejected"));
+    }
+
+    #[test]
+    fn test_format_welcome() {
+        // テスト項目: ウェルカムメッセージが本文付きで、アクセシブル出力ではラベル付きの 1 行でフォーマットされる
+        // when (操作):
+        let standard = MessageFormatter::default().format_welcome("Read the rules");
+        let accessible =
+            MessageFormatter::new(OutputMode::Accessible).format_welcome("Read the rules");
+
+        // then (期待する結果):
+        assert_eq!(standard, "\n* Read the rules\n");
+        assert_eq!(accessible, "Welcome message: Read the rules\n");
+    }
+
+    #[test]
+    fn test_format_slow_down() {
+        // テスト項目: 減速の要請は送信の間隔付きで、解除は通常の速度に戻ったことがフォーマットされる
+        // when (操作):
+        let slowed = MessageFormatter::default().format_slow_down(500);
+        let lifted = MessageFormatter::default().format_slow_down(0);
+
+        // then (期待する結果):
+        assert!(slowed.contains("every 0.5s"));
+        assert!(lifted.contains("sending normally"));
+    }
+
+    #[test]
+    fn test_format_latency() {
+        // テスト項目: /ping の往復時間がミリ秒でフォーマットされる
+        // when (操作):
+        let standard = MessageFormatter::default().format_latency(Duration::from_micros(42_500));
+        let accessible = MessageFormatter::new(OutputMode::Accessible)
+            .format_latency(Duration::from_micros(42_500));
+
+        // then (期待する結果):
+        assert!(standard.contains("42.5ms"));
+        assert_eq!(
+            accessible,
+            "Latency: round trip to the server took 42.5 milliseconds\n"
+        );
+    }
+
+    #[test]
+    fn test_format_clock_skew() {
+        // テスト項目: 時計のずれが向きと秒数でフォーマットされる
+        // when (操作):
+        let behind = MessageFormatter::default().format_clock_skew(5_500);
+        let ahead = MessageFormatter::new(OutputMode::Accessible).format_clock_skew(-3_000);
+
+        // then (期待する結果):
+        assert!(behind.contains("5.5s behind the server"));
+        assert_eq!(
+            ahead,
+            "Clock notice: your clock is 3.0 seconds ahead of the server; sent times follow the server's clock\n"
+        );
+    }
+
     #[test]
     fn test_format_raw_message() {
         // テスト項目: 生メッセージが正しくフォーマットされる
@@ -288,10 +1648,151 @@ mod tests {
         let text = "unknown message format";
 
         // when (操作):
-        let result = MessageFormatter::format_raw_message(text);
+        let result = MessageFormatter::default().format_raw_message(text);
 
         // then (期待する結果):
         assert!(result.contains("unknown message format"));
         assert!(result.contains("Received:"));
     }
+
+    #[test]
+    fn test_accessible_chat_message_is_a_single_labeled_line() {
+        // テスト項目: アクセシブルモードではチャットメッセージが区切り線なしの 1 行になる
+        // given (前提条件):
+        let formatter = MessageFormatter::new(OutputMode::Accessible);
+        // 2023-01-01 12:30:00 JST
+        let sent_at = 1672498800000 + (12 * 3600 + 30 * 60) * 1000;
+
+        // when (操作):
+        let result = formatter.format_chat_message("alice", "Hello", sent_at);
+
+        // then (期待する結果):
+        assert_eq!(result, "Message from alice at 12:30: Hello\n");
+    }
+
+    #[test]
+    fn test_accessible_room_connected_labels_each_participant() {
+        // テスト項目: アクセシブルモードでは参加者数と参加者ごとのラベル付きの行が出力される
+        // given (前提条件):
+        let formatter = MessageFormatter::new(OutputMode::Accessible);
+        let participants = vec![
+            ParticipantInfo {
+                client_id: "alice".to_string(),
+                connected_at: 1672498800000,
+                guest: false,
+                display_name: None,
+                activity: None,
+            },
+            ParticipantInfo {
+                client_id: "guest-7f3a".to_string(),
+                connected_at: 1672498800000,
+                guest: true,
+                display_name: Some("Gina".to_string()),
+                activity: Some("reviewing PR #42".to_string()),
+            },
+        ];
+
+        // when (操作):
+        let result = formatter.format_room_connected(&participants, "alice");
+
+        // then (期待する結果):
+        assert_eq!(
+            result,
+            "Participants: 2\n\
+             Participant: alice (you), entered at 00:00\n\
+             Participant: guest-7f3a (guest), shown as Gina, entered at 00:00, activity: reviewing PR #42\n"
+        );
+    }
+
+    #[test]
+    fn test_format_typing_lists_participants() {
+        // テスト項目: 入力中の参加者が 1 人なら単数形、複数なら列挙して表示される
+        // given (前提条件):
+        let formatter = MessageFormatter::default();
+        let names = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        // when (操作):
+        let one = formatter.format_typing(&names(&["alice"]));
+        let three = formatter.format_typing(&names(&["alice", "bob", "carol"]));
+        let none = formatter.format_typing(&[]);
+
+        // then (期待する結果):
+        assert_eq!(one, "\n... alice is typing...\n");
+        assert_eq!(three, "\n... alice, bob and carol are typing...\n");
+        assert_eq!(none, "");
+    }
+
+    #[test]
+    fn test_accessible_output_has_no_separators_or_arrows() {
+        // テスト項目: アクセシブルモードの出力には罫線・矢印・空行が含まれない
+        // given (前提条件):
+        let formatter = MessageFormatter::new(OutputMode::Accessible);
+        let rooms = vec![RoomInfo {
+            room_id: "room-1".to_string(),
+            name: "general".to_string(),
+            topic: None,
+            participant_count: 3,
+        }];
+
+        // when (操作):
+        let outputs = [
+            formatter.format_room_list(&rooms),
+            formatter.format_participant_joined("bob", 1672498800000, true, None),
+            formatter.format_participant_left("bob", 1672498800000, None),
+            formatter.format_server_shutdown(3000, None),
+            formatter.format_error("rate_limited", "Slow down"),
+            formatter.format_binary_message(16),
+            formatter.format_raw_message("???"),
+            formatter.format_typing(&["alice".to_string()]),
+            formatter.format_slow_down(500),
+            formatter.format_welcome("Read the rules"),
+            formatter.format_join_pending("Waiting for a moderator"),
+            formatter.format_join_requested("bob"),
+        ];
+
+        // then (期待する結果):
+        for output in &outputs {
+            assert!(!output.contains("===="), "{output}");
+            assert!(!output.contains("----"), "{output}");
+            assert!(!output.contains('←'), "{output}");
+            assert!(!output.starts_with('\n'), "{output}");
+        }
+        assert_eq!(
+            outputs[0],
+            "Rooms: 1\nRoom general, 3 participants, ID room-1\n"
+        );
+        assert_eq!(outputs[1], "Joined: bob (guest) at 00:00\n");
+        assert_eq!(
+            outputs[3],
+            "Server notice: Server is restarting, reconnecting in 3.0 seconds\n"
+        );
+    }
+
+    #[test]
+    fn test_format_quiet_hours_ended_lists_missed_mentions() {
+        // テスト項目: 時間帯の終了時に見逃したメンションが一覧表示される
+        // given (前提条件):
+        let missed = vec![MissedMention {
+            from: "bob".to_string(),
+            content: "@alice ping".to_string(),
+            // 2023-01-01 23:05:00 JST
+            sent_at: 1672498800000 + (23 * 3600 + 5 * 60) * 1000,
+        }];
+
+        // when (操作):
+        let standard = MessageFormatter::default().format_quiet_hours_ended(&missed);
+        let accessible =
+            MessageFormatter::new(OutputMode::Accessible).format_quiet_hours_ended(&missed);
+        let none = MessageFormatter::default().format_quiet_hours_ended(&[]);
+
+        // then (期待する結果):
+        assert!(standard.contains("1 missed mentions"));
+        assert!(standard.contains("@bob: @alice ping"));
+        assert_eq!(
+            accessible,
+            "Quiet hours ended: 1 missed mentions\n\
+             Missed mention from bob at 23:05: @alice ping\n"
+        );
+        assert!(none.contains("no missed mentions"));
+    }
 }
@@ -0,0 +1,363 @@
+//! Local cache of the messages received in each room.
+//!
+//! The messages are appended to one JSON Lines file per room (`<room_id>.jsonl`) in the cache
+//! directory, one `chat` message per line, so that the scrollback is shown at once when
+//! joining a room again, and can be read and searched while the server is unreachable.
+//! `rooms.json` remembers the room joined for the default room and for each slug, since the
+//! room ID is only known once connected.
+//!
+//! Only messages with a sequence number are cached: the server does not echo the messages
+//! this client sends, so they are cached once the room history sent on a later connection
+//! includes them.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use engawa_server::infrastructure::dto::{http::MessageDto, websocket::ChatMessage};
+use engawa_shared::time::timestamp_to_jst_rfc3339;
+
+use super::{domain::RoomTarget, error::ClientError, formatter::MessageFormatter};
+
+/// Messages kept per room; older ones are dropped when the cache of the room is loaded
+pub const DEFAULT_HISTORY_CACHE_CAPACITY: usize = 5000;
+
+/// File mapping the default room and the slugs to the room IDs joined for them
+const ROOMS_FILE: &str = "rooms.json";
+
+/// Messages received in each room, kept in a directory
+pub struct HistoryCache {
+    dir: PathBuf,
+    capacity: usize,
+    /// Serializes the reads and writes of the files
+    lock: Mutex<()>,
+}
+
+impl HistoryCache {
+    /// Open the cache in `dir`, creating the directory if needed
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory of the cache files
+    /// * `capacity` - Messages kept per room
+    pub fn open(dir: &Path, capacity: usize) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            capacity: capacity.max(1),
+            lock: Mutex::new(()),
+        })
+    }
+
+    /// Load the cached messages of a room, oldest first
+    ///
+    /// Messages beyond the capacity, repeated sequence numbers and unreadable lines (e.g. one
+    /// cut short by a crash) are dropped, and the file is rewritten without them.
+    pub fn load(&self, room_id: &str) -> io::Result<Vec<ChatMessage>> {
+        let _guard = self.lock.lock().unwrap();
+        let path = self.room_path(room_id);
+        let (messages, lines) = read_messages(&path)?;
+        let skip = messages.len().saturating_sub(self.capacity);
+        let messages: Vec<ChatMessage> = messages.into_values().skip(skip).collect();
+        if messages.len() != lines {
+            write_messages(&path, &messages)?;
+        }
+        Ok(messages)
+    }
+
+    /// Append messages received in a room (those without a sequence number are skipped)
+    pub fn append(&self, room_id: &str, messages: &[ChatMessage]) -> io::Result<()> {
+        let mut lines = String::new();
+        for message in messages.iter().filter(|message| message.seq.is_some()) {
+            lines.push_str(&serde_json::to_string(message)?);
+            lines.push('\n');
+        }
+        if lines.is_empty() {
+            return Ok(());
+        }
+        let _guard = self.lock.lock().unwrap();
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.room_path(room_id))?
+            .write_all(lines.as_bytes())
+    }
+
+    /// Remove a message deleted from a room
+    pub fn remove(&self, room_id: &str, seq: u64) -> io::Result<()> {
+        let _guard = self.lock.lock().unwrap();
+        let path = self.room_path(room_id);
+        let (mut messages, _) = read_messages(&path)?;
+        if messages.remove(&seq).is_some() {
+            let messages: Vec<ChatMessage> = messages.into_values().collect();
+            write_messages(&path, &messages)?;
+        }
+        Ok(())
+    }
+
+    /// Find the cached messages of a room containing the text (ignoring case), newest first
+    pub fn search(&self, room_id: &str, query: &str, limit: usize) -> io::Result<Vec<MessageDto>> {
+        let needle = query.trim().to_lowercase();
+        let messages = self.load(room_id)?;
+        Ok(messages
+            .into_iter()
+            .rev()
+            .filter(|message| message.content.to_lowercase().contains(&needle))
+            .take(limit)
+            .map(|message| MessageDto {
+                seq: message.seq.unwrap_or_default(),
+                client_id: message.client_id,
+                content: message.content,
+                timestamp: timestamp_to_jst_rfc3339(message.timestamp),
+                tags: Vec::new(),
+            })
+            .collect())
+    }
+
+    /// Remember the ID of the room joined for the target, to read its cache while offline
+    pub fn remember_room(&self, target: &RoomTarget, room_id: &str) -> io::Result<()> {
+        let Some(key) = target_key(target) else {
+            return Ok(());
+        };
+        let _guard = self.lock.lock().unwrap();
+        let mut rooms = self.read_rooms();
+        if rooms.get(&key).map(String::as_str) == Some(room_id) {
+            return Ok(());
+        }
+        rooms.insert(key, room_id.to_string());
+        fs::write(self.dir.join(ROOMS_FILE), serde_json::to_vec(&rooms)?)
+    }
+
+    /// ID of the room joined last for the target, if any
+    pub fn room_id(&self, target: &RoomTarget) -> Option<String> {
+        match target {
+            RoomTarget::Id(room_id) => Some(room_id.clone()),
+            target => {
+                let _guard = self.lock.lock().unwrap();
+                self.read_rooms().remove(&target_key(target)?)
+            }
+        }
+    }
+
+    fn read_rooms(&self) -> HashMap<String, String> {
+        fs::read(self.dir.join(ROOMS_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Cache file of a room (characters other than those of a room ID are replaced)
+    fn room_path(&self, room_id: &str) -> PathBuf {
+        let name: String = room_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{}.jsonl", name))
+    }
+}
+
+/// Format the cached messages of a room (oldest first), or those containing `query` (newest
+/// first), to read them without connecting to the server
+///
+/// # Arguments
+///
+/// * `dir` - Directory of the cache (`--history-cache`)
+/// * `room_slug` - Slug of the room, or the default room if neither it nor `room_id` is given
+/// * `room_id` - ID of the room
+/// * `query` - Text the messages contain, to search them
+pub fn read_cached_history(
+    dir: &Path,
+    room_slug: Option<String>,
+    room_id: Option<String>,
+    query: Option<&str>,
+) -> Result<String, ClientError> {
+    let invalid =
+        |reason: String| ClientError::HistoryCache(format!("'{}': {}", dir.display(), reason));
+    if !dir.is_dir() {
+        return Err(invalid("no such directory".to_string()));
+    }
+    let cache = HistoryCache::open(dir, usize::MAX).map_err(|e| invalid(e.to_string()))?;
+    let target = match (room_id, room_slug) {
+        (Some(room_id), _) => RoomTarget::Id(room_id),
+        (None, Some(slug)) => RoomTarget::Slug(slug),
+        (None, None) => RoomTarget::Default,
+    };
+    let room_id = cache.room_id(&target).ok_or_else(|| {
+        invalid(match &target {
+            RoomTarget::Slug(slug) => format!("room '{}' was never joined", slug),
+            _ => "the default room was never joined".to_string(),
+        })
+    })?;
+    let formatter = MessageFormatter::default();
+    match query {
+        Some(query) => {
+            let messages = cache
+                .search(&room_id, query, usize::MAX)
+                .map_err(|e| invalid(e.to_string()))?;
+            Ok(formatter.format_search_results(query, &messages))
+        }
+        None => {
+            let messages = cache.load(&room_id).map_err(|e| invalid(e.to_string()))?;
+            Ok(formatter.format_room_history(&messages))
+        }
+    }
+}
+
+/// Key of the target in `rooms.json` (`None` for a room given by its ID)
+fn target_key(target: &RoomTarget) -> Option<String> {
+    match target {
+        RoomTarget::Default => Some("default".to_string()),
+        RoomTarget::Slug(slug) => Some(format!("slug:{}", slug)),
+        RoomTarget::Id(_) => None,
+    }
+}
+
+/// Read the messages of a cache file by sequence number, and the number of lines read
+fn read_messages(path: &Path) -> io::Result<(BTreeMap<u64, ChatMessage>, usize)> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((BTreeMap::new(), 0)),
+        Err(e) => return Err(e),
+    };
+    let mut messages = BTreeMap::new();
+    let mut lines = 0;
+    for line in BufReader::new(file).lines() {
+        lines += 1;
+        let Ok(message) = serde_json::from_str::<ChatMessage>(&line?) else {
+            continue;
+        };
+        if let Some(seq) = message.seq {
+            messages.insert(seq, message);
+        }
+    }
+    Ok((messages, lines))
+}
+
+/// Replace a cache file with the messages, through a temporary file
+fn write_messages(path: &Path, messages: &[ChatMessage]) -> io::Result<()> {
+    let mut contents = String::new();
+    for message in messages {
+        contents.push_str(&serde_json::to_string(message)?);
+        contents.push('\n');
+    }
+    let temporary = path.with_extension("jsonl.tmp");
+    fs::write(&temporary, contents)?;
+    fs::rename(temporary, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use engawa_server::infrastructure::dto::websocket::MessageType;
+
+    use super::*;
+
+    fn chat(seq: u64, content: &str) -> ChatMessage {
+        ChatMessage {
+            r#type: MessageType::Chat,
+            client_id: "bob".to_string(),
+            content: content.to_string(),
+            timestamp: 1672498800000,
+            seq: Some(seq),
+            poll: None,
+            forwarded_from: None,
+        }
+    }
+
+    fn contents(messages: &[ChatMessage]) -> Vec<&str> {
+        messages
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_history_cache_keeps_latest_messages_per_room() {
+        // テスト項目: ルームごとに受信したメッセージが連番順に上限まで保存され、削除したメッセージと壊れた行は除かれる
+        // given (前提条件):
+        let dir = std::env::temp_dir().join(format!("engawa-client-cache-{}", std::process::id()));
+        let cache = HistoryCache::open(&dir, 3).unwrap();
+        let unsent = ChatMessage {
+            seq: None,
+            ..chat(0, "not sent yet")
+        };
+        cache
+            .append("room-1", &[chat(1, "first"), chat(2, "second"), unsent])
+            .unwrap();
+        cache.append("room-2", &[chat(1, "elsewhere")]).unwrap();
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(dir.join("room-1.jsonl"))
+            .unwrap();
+        file.write_all(b"{\"type\":\"chat\",\"cli").unwrap();
+        cache
+            .append(
+                "room-1",
+                &[chat(3, "third"), chat(2, "second"), chat(4, "fourth")],
+            )
+            .unwrap();
+
+        // when (操作):
+        cache.remove("room-1", 3).unwrap();
+        let room_1 = cache.load("room-1").unwrap();
+        let reloaded = cache.load("room-1").unwrap();
+        let room_2 = cache.load("room-2").unwrap();
+        let missing = cache.load("room-3").unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        // then (期待する結果):
+        assert_eq!(contents(&room_1), vec!["first", "second", "fourth"]);
+        assert_eq!(contents(&reloaded), contents(&room_1));
+        assert_eq!(contents(&room_2), vec!["elsewhere"]);
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn test_history_cache_search_and_rooms() {
+        // テスト項目: キャッシュを大文字と小文字を区別せずに新しい順で検索でき、既定のルームとスラッグに参加したルームの ID を覚える
+        // given (前提条件):
+        let dir = std::env::temp_dir().join(format!("engawa-client-rooms-{}", std::process::id()));
+        let cache = HistoryCache::open(&dir, DEFAULT_HISTORY_CACHE_CAPACITY).unwrap();
+        cache
+            .append(
+                "room-1",
+                &[
+                    chat(1, "Deploy at 10"),
+                    chat(2, "lunch?"),
+                    chat(3, "deploy done"),
+                ],
+            )
+            .unwrap();
+
+        // when (操作):
+        let found = cache.search("room-1", "DEPLOY", 10).unwrap();
+        cache.remember_room(&RoomTarget::Default, "room-1").unwrap();
+        cache
+            .remember_room(&RoomTarget::Slug("general".to_string()), "room-2")
+            .unwrap();
+        let reopened = HistoryCache::open(&dir, DEFAULT_HISTORY_CACHE_CAPACITY).unwrap();
+        let default = reopened.room_id(&RoomTarget::Default);
+        let general = reopened.room_id(&RoomTarget::Slug("general".to_string()));
+        let unknown = reopened.room_id(&RoomTarget::Slug("random".to_string()));
+        fs::remove_dir_all(&dir).unwrap();
+
+        // then (期待する結果):
+        let found: Vec<_> = found
+            .iter()
+            .map(|message| (message.seq, message.content.as_str()))
+            .collect();
+        assert_eq!(found, vec![(3, "deploy done"), (1, "Deploy at 10")]);
+        assert_eq!(default.as_deref(), Some("room-1"));
+        assert_eq!(general.as_deref(), Some("room-2"));
+        assert_eq!(unknown, None);
+    }
+}
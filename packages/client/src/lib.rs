@@ -1,8 +1,25 @@
+mod auth;
+mod config;
 mod domain;
 mod error;
 mod formatter;
+mod history_cache;
+mod replay;
+mod rooms;
 mod runner;
+mod search;
 mod session;
+mod starred;
+mod tls;
 mod ui;
 
+pub use auth::prompt_login;
+pub use config::ClientConfig;
+pub use domain::Endpoint;
+pub use error::ExitCode;
+pub use formatter::OutputMode;
+pub use history_cache::read_cached_history;
+pub use replay::{ReplaySummary, load_recording, replay};
 pub use runner::run;
+pub use tls::TlsTrust;
+pub use ui::{Theme, UiMode};
@@ -0,0 +1,305 @@
+//! Replay of recorded protocol traffic against a server.
+//!
+//! A recording is either a wire log (`--wire-log` of the server or the client) or the room
+//! history exported from `GET /api/v1/rooms/{room_id}/messages`. The frames the clients sent
+//! are sent again from one connection per client ID, keeping their original pacing scaled by
+//! the replay speed, to reproduce bug reports or to shape load tests.
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::DateTime;
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::{connect_async_tls_with_config, tungstenite::protocol::Message};
+
+use engawa_server::infrastructure::{
+    dto::{
+        http::RoomMessagesDto,
+        websocket::{ChatMessage, ErrorMessage, MessageType},
+        wire_log::{FrameKind, WireDirection, WireRecord},
+    },
+    wire_log::{REDACTED, read_records},
+};
+
+use super::{error::ClientError, tls::TlsTrust};
+
+/// Client ID used for frames of a client wire log with no chat message revealing the client
+const UNKNOWN_CLIENT_ID: &str = "replay";
+
+/// Time left for the server to answer the last frames before the connections are closed
+const REPLAY_GRACE_PERIOD: Duration = Duration::from_millis(500);
+
+/// Frame sent by a client in a recording
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayFrame {
+    /// Milliseconds since the first frame of the recording
+    pub at_ms: i64,
+    /// Client that sent the frame
+    pub client_id: String,
+    /// Text payload of the frame
+    pub payload: String,
+}
+
+/// Outcome of a replay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplaySummary {
+    /// Frames sent
+    pub frames: usize,
+    /// Connections opened (one per client ID)
+    pub clients: usize,
+    /// `error` frames the server answered with
+    pub errors: usize,
+    /// Time taken by the replay
+    pub elapsed: Duration,
+}
+
+/// Load the frames sent by clients from a wire log or an exported room history
+pub fn load_recording(path: &Path) -> Result<Vec<ReplayFrame>, ClientError> {
+    let invalid =
+        |reason: String| ClientError::InvalidRecording(format!("'{}': {}", path.display(), reason));
+    let contents = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+    // An exported history is a single JSON document; a wire log has one record per line
+    if let Ok(history) = serde_json::from_str::<RoomMessagesDto>(&contents) {
+        return frames_from_history(history).map_err(invalid);
+    }
+    let records = read_records(path).map_err(|e| invalid(e.to_string()))?;
+    Ok(frames_from_wire_log(records))
+}
+
+/// Chat frames of the messages in an exported room history
+fn frames_from_history(history: RoomMessagesDto) -> Result<Vec<ReplayFrame>, String> {
+    let mut frames = Vec::with_capacity(history.messages.len());
+    let mut first = None;
+    for message in history.messages {
+        let timestamp = DateTime::parse_from_rfc3339(&message.timestamp)
+            .map_err(|e| format!("invalid timestamp of message {}: {}", message.seq, e))?
+            .timestamp_millis();
+        let first = *first.get_or_insert(timestamp);
+        let chat = ChatMessage {
+            r#type: MessageType::Chat,
+            client_id: message.client_id.clone(),
+            content: message.content,
+            timestamp,
+            seq: None,
+            poll: None,
+            forwarded_from: None,
+        };
+        frames.push(ReplayFrame {
+            at_ms: timestamp - first,
+            client_id: message.client_id,
+            payload: serde_json::to_string(&chat).unwrap(),
+        });
+    }
+    Ok(frames)
+}
+
+/// Text frames sent by clients in a wire log
+///
+/// A server wire log records the frames received from each client (the peer is its client
+/// ID). A client wire log records the frames sent to the server (the peer is the server URL);
+/// they are sent as the client ID of its chat messages.
+fn frames_from_wire_log(records: Vec<WireRecord>) -> Vec<ReplayFrame> {
+    let is_server_url = |peer: &str| peer.contains("://");
+    let sent_by_client = |record: &WireRecord| match record.dir {
+        WireDirection::In => !is_server_url(&record.peer),
+        WireDirection::Out => is_server_url(&record.peer),
+    };
+    let records: Vec<WireRecord> = records
+        .into_iter()
+        .filter(|record| record.kind == FrameKind::Text && sent_by_client(record))
+        .filter(|record| record.payload.as_deref().is_some_and(|p| p != REDACTED))
+        .collect();
+
+    // Client ID of each server URL in a client wire log, from the chat messages it sent
+    let mut client_ids: HashMap<&str, String> = HashMap::new();
+    for record in records.iter().filter(|record| is_server_url(&record.peer)) {
+        if let Some(chat) = record
+            .payload
+            .as_deref()
+            .and_then(|payload| serde_json::from_str::<ChatMessage>(payload).ok())
+        {
+            client_ids.entry(&record.peer).or_insert(chat.client_id);
+        }
+    }
+
+    let first = records.first().map_or(0, |record| record.ts);
+    records
+        .iter()
+        .map(|record| ReplayFrame {
+            at_ms: record.ts - first,
+            client_id: if is_server_url(&record.peer) {
+                client_ids
+                    .get(record.peer.as_str())
+                    .cloned()
+                    .unwrap_or_else(|| UNKNOWN_CLIENT_ID.to_string())
+            } else {
+                record.peer.clone()
+            },
+            payload: record.payload.clone().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Delay from the start of the replay at which a frame recorded `at_ms` after the first is sent
+///
+/// A speed of 2.0 replays twice as fast; 0 sends every frame without waiting.
+pub fn replay_delay(at_ms: i64, speed: f64) -> Duration {
+    if speed <= 0.0 || at_ms <= 0 {
+        return Duration::ZERO;
+    }
+    Duration::from_secs_f64(at_ms as f64 / 1000.0 / speed)
+}
+
+/// Replay the frames against the server at `url`
+///
+/// Every client ID of the recording connects first; the frames are then sent in order at
+/// their scaled times. `error` frames answered by the server are logged and counted.
+/// `wss://` URLs trust the certificate authorities of `tls`.
+pub async fn replay(
+    url: &str,
+    frames: Vec<ReplayFrame>,
+    speed: f64,
+    tls: &TlsTrust,
+) -> Result<ReplaySummary, ClientError> {
+    let errors = Arc::new(AtomicUsize::new(0));
+    let mut writers = HashMap::new();
+    let mut readers = Vec::new();
+    for frame in &frames {
+        if writers.contains_key(&frame.client_id) {
+            continue;
+        }
+        let connect_url = format!("{}?client_id={}", url, frame.client_id);
+        let connector = Some(tls.connector());
+        let connected = connect_async_tls_with_config(connect_url, None, false, connector).await;
+        let (ws_stream, _response) = connected.map_err(|e| {
+            ClientError::ConnectionError(format!("Cannot connect as '{}': {}", frame.client_id, e))
+        })?;
+        let (write, mut read) = ws_stream.split();
+        let client_id = frame.client_id.clone();
+        let errors = errors.clone();
+        readers.push(tokio::spawn(async move {
+            while let Some(Ok(message)) = read.next().await {
+                if let Message::Text(text) = message
+                    && let Ok(error) = serde_json::from_str::<ErrorMessage>(&text)
+                    && matches!(error.r#type, MessageType::Error)
+                {
+                    tracing::warn!("'{}' got {}: {}", client_id, error.code, error.message);
+                    errors.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }));
+        writers.insert(frame.client_id.clone(), write);
+    }
+
+    let start = Instant::now();
+    let start_at = tokio::time::Instant::now();
+    let clients = writers.len();
+    let mut sent = 0;
+    for frame in frames {
+        tokio::time::sleep_until(start_at + replay_delay(frame.at_ms, speed)).await;
+        let write = writers.get_mut(&frame.client_id).expect("connected");
+        if let Err(e) = write.send(Message::Text(frame.payload.into())).await {
+            tracing::warn!("Failed to replay a frame of '{}': {}", frame.client_id, e);
+            continue;
+        }
+        sent += 1;
+    }
+
+    tokio::time::sleep(REPLAY_GRACE_PERIOD).await;
+    for write in writers.values_mut() {
+        let _ = write.send(Message::Close(None)).await;
+    }
+    for reader in readers {
+        reader.abort();
+    }
+    Ok(ReplaySummary {
+        frames: sent,
+        clients,
+        errors: errors.load(Ordering::Relaxed),
+        elapsed: start.elapsed(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(ts: i64, dir: WireDirection, peer: &str, payload: &str) -> WireRecord {
+        WireRecord {
+            ts,
+            dir,
+            peer: peer.to_string(),
+            kind: FrameKind::Text,
+            len: payload.len(),
+            payload: Some(payload.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_frames_from_wire_log_keep_client_frames() {
+        // テスト項目: サーバ・クライアントのワイヤーログからクライアントが送ったフレームだけを取り出し、送信者と経過時間が付く
+        // given (前提条件):
+        let chat = r#"{"type":"chat","client_id":"bob","content":"hi","timestamp":1}"#;
+        let records = vec![
+            record(
+                1000,
+                WireDirection::In,
+                "alice",
+                r#"{"type":"typing-started"}"#,
+            ),
+            record(
+                1100,
+                WireDirection::Out,
+                "alice",
+                r#"{"type":"room-connected"}"#,
+            ),
+            record(1500, WireDirection::Out, "ws://127.0.0.1:8080/ws", chat),
+            record(1600, WireDirection::In, "ws://127.0.0.1:8080/ws", chat),
+            record(1700, WireDirection::In, "alice", REDACTED),
+        ];
+
+        // when (操作):
+        let frames = frames_from_wire_log(records);
+
+        // then (期待する結果):
+        assert_eq!(
+            frames,
+            vec![
+                ReplayFrame {
+                    at_ms: 0,
+                    client_id: "alice".to_string(),
+                    payload: r#"{"type":"typing-started"}"#.to_string(),
+                },
+                ReplayFrame {
+                    at_ms: 500,
+                    client_id: "bob".to_string(),
+                    payload: chat.to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replay_delay_scales_with_speed() {
+        // テスト項目: 送信までの時間は速度で割った値になり、速度 0 では待たない
+        // given (前提条件):
+        let at_ms = 3000;
+
+        // when (操作):
+        let normal = replay_delay(at_ms, 1.0);
+        let fast = replay_delay(at_ms, 2.0);
+        let unpaced = replay_delay(at_ms, 0.0);
+
+        // then (期待する結果):
+        assert_eq!(normal, Duration::from_secs(3));
+        assert_eq!(fast, Duration::from_millis(1500));
+        assert_eq!(unpaced, Duration::ZERO);
+    }
+}
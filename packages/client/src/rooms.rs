@@ -0,0 +1,105 @@
+//! Creating and switching rooms over the REST API.
+
+use std::time::Duration;
+
+use engawa_server::infrastructure::dto::http::{RoomDetailDto, RoomSummaryDto};
+
+use super::{
+    domain::{Endpoint, room_url, rooms_url},
+    error::ClientError,
+};
+
+/// How long to wait for the REST API of the rooms
+const ROOMS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Create a room named with the slug
+///
+/// The endpoint is derived from the WebSocket URL of the server and sent the same access
+/// token as the connection.
+pub async fn create_room(server: &Endpoint, name: &str) -> Result<RoomSummaryDto, ClientError> {
+    let url = rooms_url(&server.url).ok_or_else(|| {
+        ClientError::ConnectionError(format!("Cannot create rooms on '{}'", server.url))
+    })?;
+    let mut request = server
+        .tls
+        .http_client()
+        .post(&url)
+        .query(&[("slug", name)])
+        .timeout(ROOMS_TIMEOUT);
+    if let Some(token) = &server.token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ClientError::ConnectionError(e.to_string()))?;
+    match response.status().as_u16() {
+        201 => {}
+        400 => {
+            return Err(ClientError::ConnectionError(format!(
+                "'{}' is not a valid room name (lowercase letters, digits and hyphens)",
+                name
+            )));
+        }
+        404 => {
+            return Err(ClientError::ConnectionError(
+                "the server does not allow creating rooms".to_string(),
+            ));
+        }
+        409 => {
+            return Err(ClientError::ConnectionError(format!(
+                "a room named '{}' already exists",
+                name
+            )));
+        }
+        status => {
+            return Err(ClientError::ConnectionError(format!(
+                "Creating the room failed with HTTP {}",
+                status
+            )));
+        }
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| ClientError::ConnectionError(e.to_string()))
+}
+
+/// Get the room with the ID, to check that it exists before switching to it
+pub async fn fetch_room(server: &Endpoint, room_id: &str) -> Result<RoomDetailDto, ClientError> {
+    let url = room_url(&server.url, room_id).ok_or_else(|| {
+        ClientError::ConnectionError(format!("Cannot look up rooms on '{}'", server.url))
+    })?;
+    let mut request = server
+        .tls
+        .http_client()
+        .get(&url)
+        .query(&[("include", "participants")])
+        .timeout(ROOMS_TIMEOUT);
+    if let Some(token) = &server.token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ClientError::ConnectionError(e.to_string()))?;
+    match response.status().as_u16() {
+        200 => {}
+        404 => {
+            return Err(ClientError::ConnectionError(format!(
+                "no room with ID '{}'",
+                room_id
+            )));
+        }
+        status => {
+            return Err(ClientError::ConnectionError(format!(
+                "Looking up the room failed with HTTP {}",
+                status
+            )));
+        }
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| ClientError::ConnectionError(e.to_string()))
+}
@@ -1,66 +1,243 @@
 //! Client execution logic with reconnection support.
 
-use std::time::Duration;
+use std::{
+    hash::{BuildHasher, RandomState},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
 
-use super::{error::ClientError, session::run_client_session};
+use engawa_server::{domain::Locale, infrastructure::wire_log::WireLog};
 
-const MAX_RECONNECT_ATTEMPTS: u32 = 5;
-const RECONNECT_INTERVAL_SECS: u64 = 5;
+use super::{
+    config::ClientConfig,
+    domain::{
+        ClockSkew, DoNotDisturb, Endpoint, LatencyMeter, PromptTemplate, ResumeState, RoomTarget,
+        exit_code_for, reconnect_delay, should_exit_immediately,
+    },
+    error::{ClientError, ConfigError, ExitCode},
+    formatter::OutputMode,
+    history_cache::{DEFAULT_HISTORY_CACHE_CAPACITY, HistoryCache},
+    session::{Outbox, SessionState, run_client_session, watch_quiet_hours},
+    ui::{ConnectionStatus, Prompt, Screen, UiMode, start_tui},
+};
 
 /// Run the WebSocket client with reconnection logic
-pub async fn run(url: String, client_id: String) -> Result<(), Box<dyn std::error::Error>> {
-    let mut reconnect_count = 0;
+///
+/// `server` is the URL of the server and the access token sent to it, if any.
+/// `room_slug` selects the room to join by its slug (until the user switches rooms with
+/// `/join` or `/create`), and `locale` overrides the room's locale
+/// for system notices. `mode` selects how incoming messages are laid out in plain mode.
+/// `dedup_window` is the number of message sequence numbers remembered across reconnections to
+/// avoid rendering re-sent messages twice. `config` holds the settings read from the
+/// configuration file, such as the terminal UI, the wire log to record the frames in and
+/// whether the prompt shows the latency.
+///
+/// A lost connection is retried with exponential backoff and jitter, up to
+/// `config.max_retries` attempts in a row; the count starts over once a connection is
+/// established again.
+pub async fn run(
+    server: Endpoint,
+    client_id: String,
+    room_slug: Option<String>,
+    locale: Option<Locale>,
+    mode: OutputMode,
+    dedup_window: usize,
+    config: ClientConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut retries = 0;
+    let has_quiet_hours = !config.quiet_hours.is_empty();
+    let wire_log = match &config.wire_log {
+        Some(path) => Some(Arc::new(
+            WireLog::open(path, config.wire_log_redact.clone()).map_err(|source| {
+                ConfigError::WireLog {
+                    path: path.display().to_string(),
+                    source,
+                }
+            })?,
+        )),
+        None => None,
+    };
+    let history_cache = match &config.history_cache {
+        Some(path) => Some(Arc::new(
+            HistoryCache::open(path, DEFAULT_HISTORY_CACHE_CAPACITY).map_err(|source| {
+                ConfigError::HistoryCache {
+                    path: path.display().to_string(),
+                    source,
+                }
+            })?,
+        )),
+        None => None,
+    };
+    let latency = Arc::new(Mutex::new(LatencyMeter::default()));
+    // A prompt showing the latency needs it to be measured periodically
+    let show_latency = config.show_latency
+        || config
+            .prompt
+            .as_ref()
+            .is_some_and(PromptTemplate::shows_latency);
+    let shown_latency = show_latency.then(|| latency.clone());
+    let (screen, outbox) = match config.ui {
+        UiMode::Tui => {
+            let (screen, lines) = start_tui(&client_id, shown_latency, config.theme)?;
+            (screen, Outbox::new(lines))
+        }
+        UiMode::Plain => {
+            let prompt = Prompt::new(&client_id, shown_latency).with_template(config.prompt);
+            let outbox = Outbox::from_stdin(prompt.clone(), mode);
+            (Screen::plain(prompt, mode), outbox)
+        }
+    };
+    let mut state = SessionState {
+        resume: Arc::new(Mutex::new(ResumeState::new(dedup_window))),
+        dnd: Arc::new(Mutex::new(DoNotDisturb::new(config.quiet_hours))),
+        outbox,
+        wire_log,
+        latency,
+        clock: Arc::new(Mutex::new(ClockSkew::default())),
+        screen: screen.clone(),
+        show_latency,
+        display_name: Arc::new(Mutex::new(config.display_name)),
+        history_cache,
+        shown_cache: Arc::new(Mutex::new(None)),
+    };
+    let mut room = match room_slug {
+        Some(slug) => RoomTarget::Slug(slug),
+        None => RoomTarget::Default,
+    };
+    let quiet_hours_watcher =
+        has_quiet_hours.then(|| tokio::spawn(watch_quiet_hours(state.dnd.clone(), screen.clone())));
 
     loop {
         tracing::info!(
-            "Attempting to connect to {} as '{}' (attempt {}/{})",
-            url,
+            "Attempting to connect to {} as '{}' (retry {}/{})",
+            server.url,
             client_id,
-            reconnect_count + 1,
-            MAX_RECONNECT_ATTEMPTS
+            retries,
+            config.max_retries
         );
+        screen.status(ConnectionStatus::Connecting);
 
-        match run_client_session(&url, &client_id).await {
+        match run_client_session(&server, &client_id, &room, locale, &mut state).await {
             Ok(_) => {
                 tracing::info!("Client session ended normally");
                 // If connection ended normally (user exit), don't reconnect
                 break;
             }
             Err(e) => {
-                // Check if it's a duplicate client_id error
-                if let Some(client_err) = e.downcast_ref::<ClientError>()
-                    && matches!(client_err, ClientError::DuplicateClientId(_))
+                let client_err = e.downcast_ref::<ClientError>();
+
+                // Errors such as a duplicate client_id cannot be fixed by reconnecting
+                if let Some(client_err) = client_err
+                    && should_exit_immediately(client_err)
                 {
                     tracing::error!("{}", e);
-                    tracing::error!(
-                        "Cannot connect with client_id '{}' as it is already in use. Exiting.",
-                        client_id
-                    );
-                    std::process::exit(1);
+                    if matches!(client_err, ClientError::DuplicateClientId(_)) {
+                        tracing::error!(
+                            "Cannot connect with client_id '{}' as it is already in use. Exiting.",
+                            client_id
+                        );
+                    }
+                    screen.close(Some(&e.to_string()));
+                    std::process::exit(exit_code_for(client_err).code());
                 }
 
-                tracing::warn!("Connection lost: {}", e);
-                reconnect_count += 1;
+                // The user chose another room with /join or /create
+                if let Some(ClientError::SwitchingRoom(room_id)) = client_err {
+                    tracing::info!("Switching to room {}", room_id);
+                    room = RoomTarget::Id(room_id.clone());
+                    retries = 0;
+                    continue;
+                }
+
+                // A restarting server tells when to reconnect; this is not a failed attempt
+                if let Some(ClientError::ServerRestarting(delay)) = client_err {
+                    tracing::info!("Server is restarting, reconnecting in {:?}...", delay);
+                    retries = 0;
+                    screen.status(ConnectionStatus::Reconnecting {
+                        delay: *delay,
+                        retry: retries,
+                        max_retries: config.max_retries,
+                    });
+                    tokio::time::sleep(*delay).await;
+                    continue;
+                }
 
-                if reconnect_count >= MAX_RECONNECT_ATTEMPTS {
-                    tracing::error!(
-                        "Failed to reconnect after {} attempts. Exiting.",
-                        MAX_RECONNECT_ATTEMPTS
-                    );
-                    std::process::exit(1);
+                // The session was connected, so the retries start over
+                if matches!(client_err, Some(ClientError::ConnectionLost)) {
+                    retries = 0;
                 }
 
+                tracing::warn!("{}", e);
+                show_offline_history(&state, &room, &e.to_string());
+
+                if retries >= config.max_retries {
+                    tracing::error!("Failed to reconnect after {} retries. Exiting.", retries);
+                    screen.close(Some(&format!(
+                        "Failed to reconnect after {} retries: {}",
+                        retries, e
+                    )));
+                    std::process::exit(ExitCode::ConnectionLost.code());
+                }
+
+                let delay = reconnect_delay(retries, jitter());
+                retries += 1;
                 tracing::info!(
-                    "Reconnecting in {} seconds... (attempt {}/{})",
-                    RECONNECT_INTERVAL_SECS,
-                    reconnect_count + 1,
-                    MAX_RECONNECT_ATTEMPTS
+                    "Reconnecting in {:.1?}... (retry {}/{})",
+                    delay,
+                    retries,
+                    config.max_retries
                 );
+                screen.status(ConnectionStatus::Reconnecting {
+                    delay,
+                    retry: retries,
+                    max_retries: config.max_retries,
+                });
 
-                tokio::time::sleep(Duration::from_secs(RECONNECT_INTERVAL_SECS)).await;
+                tokio::time::sleep(delay).await;
             }
         }
     }
 
+    if let Some(watcher) = quiet_hours_watcher {
+        watcher.abort();
+    }
+    screen.close(None);
     Ok(())
 }
+
+/// Show the cached messages of the room while the server cannot be reached, unless messages
+/// of a room were already shown
+fn show_offline_history(state: &SessionState, room: &RoomTarget, reason: &str) {
+    let Some(cache) = &state.history_cache else {
+        return;
+    };
+    let mut shown = state.shown_cache.lock().unwrap();
+    if shown.is_some() {
+        return;
+    }
+    let Some(room_id) = cache.room_id(room) else {
+        return;
+    };
+    match cache.load(&room_id) {
+        Ok(cached) if !cached.is_empty() => {
+            let formatter = state.screen.formatter();
+            state
+                .screen
+                .print(&formatter.format_offline_history(reason, cached.len()));
+            state.screen.history(&cached);
+            *shown = Some(room_id);
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!(
+            "Failed to read the history cache of room {}: {}",
+            room_id,
+            e
+        ),
+    }
+}
+
+/// Random value between 0.0 and 1.0 for the reconnection jitter
+fn jitter() -> f64 {
+    let random = RandomState::new().hash_one(SystemTime::now());
+    random as f64 / u64::MAX as f64
+}
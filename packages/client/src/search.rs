@@ -0,0 +1,59 @@
+//! Searching the messages of the room.
+
+use std::time::Duration;
+
+use engawa_server::infrastructure::dto::http::{MessageDto, MessageSearchDto};
+
+use super::{
+    domain::{Endpoint, room_search_url},
+    error::ClientError,
+};
+
+/// How long to wait for the search results
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Get the messages of the room containing the text, newest first
+///
+/// The endpoint is derived from the WebSocket URL of the server and sent the same access
+/// token as the connection.
+pub async fn fetch_search(
+    server: &Endpoint,
+    room_id: &str,
+    query: &str,
+) -> Result<Vec<MessageDto>, ClientError> {
+    let url = room_search_url(&server.url, room_id).ok_or_else(|| {
+        ClientError::ConnectionError(format!("Cannot search messages on '{}'", server.url))
+    })?;
+    let mut request = server
+        .tls
+        .http_client()
+        .get(&url)
+        .query(&[("q", query)])
+        .timeout(SEARCH_TIMEOUT);
+    if let Some(token) = &server.token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ClientError::ConnectionError(e.to_string()))?;
+    match response.status().as_u16() {
+        200 => {}
+        404 => {
+            return Err(ClientError::ConnectionError(
+                "the server does not search messages".to_string(),
+            ));
+        }
+        status => {
+            return Err(ClientError::ConnectionError(format!(
+                "Searching messages failed with HTTP {}",
+                status
+            )));
+        }
+    }
+    let search: MessageSearchDto = response
+        .json()
+        .await
+        .map_err(|e| ClientError::ConnectionError(e.to_string()))?;
+    Ok(search.messages)
+}
@@ -1,130 +1,649 @@
 //! WebSocket client session management.
 
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
 use futures_util::{SinkExt, StreamExt};
 use rustyline::DefaultEditor;
 use rustyline::error::ReadlineError;
 use tokio::sync::mpsc;
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tokio_tungstenite::{
+    connect_async_tls_with_config,
+    tungstenite::{
+        self,
+        client::IntoClientRequest,
+        http::{HeaderValue, header::AUTHORIZATION},
+        protocol::Message,
+    },
+};
 
-use engawa_server::infrastructure::dto::websocket::{
-    ChatMessage, MessageType, ParticipantJoinedMessage, ParticipantLeftMessage,
-    RoomConnectedMessage,
+use engawa_server::{
+    domain::Locale,
+    infrastructure::dto::websocket::{
+        ActivityUpdatedMessage, BackfillRequestMessage, ChatMessage, CreatePollMessage,
+        DisconnectedMessage, ErrorMessage, ForwardMessage, HeartbeatMessage, HelloAckMessage,
+        HelloMessage, JoinPendingMessage, JoinRequestedMessage, ListRoomsMessage,
+        MessageDeletedMessage, MessageType, ParticipantJoinedMessage, ParticipantLeftMessage,
+        ParticipantRenamedMessage, PollUpdatedMessage, RoomConnectedMessage, RoomFreezeMessage,
+        RoomHistoryMessage, RoomListMessage, ServerShutdownMessage, SetActivityMessage,
+        SetDisplayNameMessage, SlowDownMessage, StarMessage, StarUpdatedMessage, TypingMessage,
+        VoteMessage, WelcomeMessage,
+    },
+    infrastructure::dto::wire_log::{FrameKind, WireDirection},
+    infrastructure::i18n::SystemText,
+    infrastructure::protocol::{Feature, PROTOCOL_VERSION},
+    infrastructure::wire_log::WireLog,
+    ui::{
+        BANNED_CLOSE_CODE, DISCONNECTED_CLOSE_CODE, JOIN_REJECTED_CLOSE_CODE, KICKED_CLOSE_CODE,
+        SESSION_REPLACED_CLOSE_CODE,
+    },
 };
-use engawa_shared::time::get_jst_timestamp;
+use engawa_shared::time::{get_jst_timestamp, timestamp_to_jst_time};
+
+use super::{
+    domain::{
+        ClockSkew, DoNotDisturb, Endpoint, Input, JOIN_ROOM_COMMAND, LIST_ROOMS_COMMAND,
+        LatencyMeter, MissedMention, PING_COMMAND, POLL_COMMAND, QuietHoursEvent, ResumeState,
+        RoomTarget, SendThrottle, TypingIndicators, classify_handshake_status, display_name_query,
+        localized_notice, mentions, unbatch,
+    },
+    error::ClientError,
+    formatter::OutputMode,
+    history_cache::HistoryCache,
+    rooms::{create_room, fetch_room},
+    search::fetch_search,
+    starred::fetch_starred,
+    ui::{ConnectionStatus, Prompt, Screen},
+};
+
+/// Cached messages shown when searching while the server cannot search
+const CACHED_SEARCH_LIMIT: usize = 20;
+
+/// How often the quiet-hours watcher checks whether a window started or ended
+const QUIET_HOURS_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How often the latency is measured while the prompt shows it
+const LATENCY_PROBE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Lines typed at the prompt that are not sent yet
+///
+/// Stdin is read for the whole client run, so lines typed while disconnected wait here and
+/// are sent after reconnecting. A line stays queued until it was written to a connection.
+pub struct Outbox {
+    lines: mpsc::UnboundedReceiver<String>,
+    unsent: Option<String>,
+}
+
+impl Outbox {
+    /// Queue the lines received on `lines`, such as those typed in the terminal UI
+    pub fn new(lines: mpsc::UnboundedReceiver<String>) -> Self {
+        Self {
+            lines,
+            unsent: None,
+        }
+    }
+
+    /// Start reading lines from stdin on a separate thread
+    pub fn from_stdin(prompt: Prompt, mode: OutputMode) -> Self {
+        let (input_tx, lines) = mpsc::unbounded_channel::<String>();
+        // Spawn a blocking thread for reading stdin (synchronous readline)
+        std::thread::spawn(move || match mode {
+            OutputMode::Standard => read_lines_with_editor(&prompt, input_tx),
+            // Line editing redraws the prompt with ANSI escapes, so read plain lines instead
+            OutputMode::Accessible => read_plain_lines(input_tx),
+        });
+        Self::new(lines)
+    }
+
+    /// Number of lines waiting to be sent
+    pub fn queued(&self) -> usize {
+        self.lines.len() + usize::from(self.unsent.is_some())
+    }
+
+    /// Next line to send, or `None` once stdin is closed
+    async fn next(&mut self) -> Option<String> {
+        if self.unsent.is_none() {
+            self.unsent = Some(self.lines.recv().await?);
+        }
+        self.unsent.clone()
+    }
+
+    /// Remove the line returned by `next` after it was sent
+    fn sent(&mut self) {
+        self.unsent = None;
+    }
+}
 
-use super::{error::ClientError, formatter::MessageFormatter, ui::redisplay_prompt};
+/// State kept across reconnections
+pub struct SessionState {
+    /// Resume position, so that messages re-sent after a reconnect are not rendered twice
+    pub resume: Arc<Mutex<ResumeState>>,
+    /// Do-not-disturb state, so that mentions missed during quiet hours are summarized once
+    pub dnd: Arc<Mutex<DoNotDisturb>>,
+    /// Lines typed at the prompt, including those typed while disconnected
+    pub outbox: Outbox,
+    /// Records the frames exchanged with the server (disabled if `None`)
+    pub wire_log: Option<Arc<WireLog>>,
+    /// Round trip to the server, measured on `/ping` (and periodically if `show_latency`)
+    pub latency: Arc<Mutex<LatencyMeter>>,
+    /// Skew of the local clock, estimated from the server's clock in `room-connected` and
+    /// `heartbeat`
+    pub clock: Arc<Mutex<ClockSkew>>,
+    /// Where the events of the room are shown
+    pub screen: Screen,
+    /// Whether the latency is measured periodically to be shown in the prompt
+    pub show_latency: bool,
+    /// Name the client is shown as, asked for on each connection and updated by `/nick`
+    pub display_name: Arc<Mutex<Option<String>>>,
+    /// Local cache of the messages received in each room (disabled if `None`)
+    pub history_cache: Option<Arc<HistoryCache>>,
+    /// Room whose cached messages were shown, so that they are shown once
+    pub shown_cache: Arc<Mutex<Option<String>>>,
+}
+
+/// Record a frame exchanged with `peer` in the wire log, if enabled
+fn tap(wire_log: Option<&WireLog>, peer: &str, dir: WireDirection, frame: &Message) {
+    let Some(wire_log) = wire_log else { return };
+    let (kind, payload): (FrameKind, &[u8]) = match frame {
+        Message::Text(text) => (FrameKind::Text, text.as_bytes()),
+        Message::Binary(data) => (FrameKind::Binary, data),
+        Message::Ping(data) => (FrameKind::Ping, data),
+        Message::Pong(data) => (FrameKind::Pong, data),
+        Message::Close(frame) => (
+            FrameKind::Close,
+            frame
+                .as_ref()
+                .map_or(&[][..], |frame| frame.reason.as_bytes()),
+        ),
+        Message::Frame(frame) => (FrameKind::Binary, frame.payload()),
+    };
+    wire_log.record(dir, peer, kind, payload);
+}
 
 /// Run the WebSocket client session
+///
+/// The client joins `room` (the default room of the server for [`RoomTarget::Default`]).
+/// System notices are shown in `locale` if set, otherwise in the room's locale, on the screen
+/// of `state`. The access token of `server` is sent with the handshake if set.
+/// Lines queued in the outbox of `state` while disconnected are sent once connected.
 pub async fn run_client_session(
-    url: &str,
+    server: &Endpoint,
     client_id: &str,
+    room: &RoomTarget,
+    locale: Option<Locale>,
+    state: &mut SessionState,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Construct URL with client_id as query parameter
-    let url = format!("{}?client_id={}", url, client_id);
+    let screen = state.screen.clone();
+    let formatter = screen.formatter();
+    let resume = state.resume.clone();
+    let dnd = state.dnd.clone();
 
-    let (ws_stream, response) = match connect_async(&url).await {
-        Ok(result) => result,
-        Err(e) => {
-            // Check if it's an HTTP error response
-            let error_msg = e.to_string();
+    // Construct URL with client_id, the room, the display name (and the resume position when
+    // reconnecting) as query parameters
+    let url = format!(
+        "{}?client_id={}{}{}{}",
+        server.url,
+        client_id,
+        room.query(),
+        display_name_query(state.display_name.lock().unwrap().as_deref()),
+        resume.lock().unwrap().query()
+    );
+
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| ClientError::ConnectionError(e.to_string()))?;
+    if let Some(token) = &server.token {
+        let bearer = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|_| ClientError::AuthenticationFailed("invalid token".to_string()))?;
+        request.headers_mut().insert(AUTHORIZATION, bearer);
+    }
 
-            // Check for HTTP 409 Conflict
-            if error_msg.contains("409") || error_msg.contains("Conflict") {
-                return Err(Box::new(ClientError::DuplicateClientId(
-                    client_id.to_string(),
+    let connector = Some(server.tls.connector());
+    let (ws_stream, _response) =
+        match connect_async_tls_with_config(request, None, false, connector).await {
+            Ok(result) => result,
+            Err(tungstenite::Error::Http(response)) => {
+                // The server rejected the WebSocket handshake with an HTTP error status
+                return Err(Box::new(classify_handshake_status(
+                    response.status().as_u16(),
+                    client_id,
+                    room.slug(),
                 )));
             }
-
-            return Err(Box::new(ClientError::ConnectionError(error_msg)));
-        }
-    };
-
-    // Check HTTP status code from response
-    if response.status().as_u16() == 409 {
-        return Err(Box::new(ClientError::DuplicateClientId(
-            client_id.to_string(),
-        )));
-    }
+            Err(e) => {
+                return Err(Box::new(ClientError::ConnectionError(e.to_string())));
+            }
+        };
 
     tracing::info!("Connected to chat server!");
-    println!(
-        "\nYou are '{}'. Type messages and press Enter to send. Press Ctrl+C to exit.\n",
-        client_id
-    );
+    screen.status(ConnectionStatus::Connected);
+    screen.print(&format!(
+        "\nYou are '{}'. Type messages and press Enter to send. Type {} to list the rooms, {} <room ID> to switch rooms, {} to measure the latency, {} \"Question\" option1 option2 to post a poll. Press Ctrl+C to exit.\n\n",
+        client_id, LIST_ROOMS_COMMAND, JOIN_ROOM_COMMAND, PING_COMMAND, POLL_COMMAND
+    ));
 
-    let (mut write, mut read) = ws_stream.split();
+    let (mut write, read) = ws_stream.split();
+    // Announce the protocol before the server sends the room state
+    let hello = HelloMessage {
+        r#type: MessageType::Hello,
+        protocol_version: PROTOCOL_VERSION,
+        features: Feature::ALL
+            .iter()
+            .map(|feature| feature.as_str().to_string())
+            .collect(),
+    };
+    let frame = Message::Text(serde_json::to_string(&hello).unwrap().into());
+    tap(
+        state.wire_log.as_deref(),
+        &server.url,
+        WireDirection::Out,
+        &frame,
+    );
+    if let Err(e) = write.send(frame).await {
+        return Err(Box::new(ClientError::ConnectionError(e.to_string())));
+    }
+    // Frames batched by the server carry several messages; handle them one by one
+    let wire_log = state.wire_log.clone();
+    let peer = server.url.clone();
+    let mut read = read.flat_map(move |message| {
+        if let Ok(frame) = &message {
+            tap(wire_log.as_deref(), &peer, WireDirection::In, frame);
+        }
+        let messages = match message {
+            Ok(Message::Text(text)) => unbatch(&text)
+                .into_iter()
+                .map(|text| Ok(Message::Text(text.into())))
+                .collect(),
+            other => vec![other],
+        };
+        futures_util::stream::iter(messages)
+    });
 
     // Clone client_id for read task
     let client_id_for_read = client_id.to_string();
+    let screen_for_read = screen.clone();
+    let latency = state.latency.clone();
+    let clock = state.clock.clone();
+    let display_name = state.display_name.clone();
+
+    // ID of the room joined, given in `room-connected`, to search its messages
+    let room_id = Arc::new(Mutex::new(None::<String>));
+    let room_id_for_read = room_id.clone();
+
+    // Messages received are cached per room, if enabled
+    let history_cache = state.history_cache.clone();
+    let history_cache_for_search = state.history_cache.clone();
+    let shown_cache = state.shown_cache.clone();
+    let room_target = room.clone();
+
+    // Frames the read task asks the write task to send (e.g. backfill requests)
+    let (control_tx, mut control_rx) = mpsc::unbounded_channel::<String>();
+
+    // Pace of the lines sent, slowed down while the server asks
+    let throttle = Arc::new(Mutex::new(SendThrottle::default()));
+    let throttle_for_read = throttle.clone();
 
     // Spawn a task to handle incoming messages
     let mut read_task = tokio::spawn(async move {
-        let mut connection_error = false;
+        let screen = screen_for_read;
+        let mut connection_error = None;
+        let mut typing = TypingIndicators::default();
 
         while let Some(message) = read.next().await {
             match message {
                 Ok(Message::Text(text)) => {
-                    // Try to parse as RoomConnectedMessage first
-                    if let Ok(room_msg) = serde_json::from_str::<RoomConnectedMessage>(&text) {
-                        let formatted = MessageFormatter::format_room_connected(
-                            &room_msg.participants,
-                            &client_id_for_read,
+                    // Protocol the server agreed to, sent before the room state
+                    if let Ok(ack) = serde_json::from_str::<HelloAckMessage>(&text)
+                        && matches!(ack.r#type, MessageType::HelloAck)
+                    {
+                        tracing::info!(
+                            "Server speaks protocol version {} with {:?}",
+                            ack.protocol_version,
+                            ack.features
                         );
-                        print!("{}", formatted);
-                        redisplay_prompt(&client_id_for_read);
+                    }
+                    // Then the participants of the room
+                    else if let Ok(room_msg) = serde_json::from_str::<RoomConnectedMessage>(&text)
+                    {
+                        if let Some(server_time) = room_msg.server_time {
+                            observe_server_time(&clock, server_time, &screen);
+                        }
+                        if room_msg.room_id.is_some() {
+                            *room_id_for_read.lock().unwrap() = room_msg.room_id.clone();
+                        }
+                        let mut backfill = resume
+                            .lock()
+                            .unwrap()
+                            .on_room_connected(room_msg.resume_token.clone(), room_msg.last_seq);
+                        match (&room_target, room_msg.room_id.as_deref()) {
+                            (RoomTarget::Slug(slug), _) => screen.set_room(slug),
+                            (_, Some(joined)) => screen.set_room(joined),
+                            (_, None) => {}
+                        }
+                        screen.room_connected(&room_msg.participants, &client_id_for_read);
+                        // A fresh connection starts from the cached messages of the room
+                        if let (Some(cache), Some(joined)) =
+                            (&history_cache, room_msg.room_id.as_deref())
+                        {
+                            if let Err(e) = cache.remember_room(&room_target, joined) {
+                                tracing::warn!("Failed to update the history cache: {}", e);
+                            }
+                            let fresh = resume.lock().unwrap().last_seq() == 0;
+                            if fresh
+                                && let Some(cached_seq) = restore_cached_history(
+                                    cache,
+                                    joined,
+                                    &shown_cache,
+                                    &resume,
+                                    &screen,
+                                )
+                                && room_msg
+                                    .last_seq
+                                    .is_some_and(|last_seq| last_seq > cached_seq)
+                            {
+                                backfill.get_or_insert(cached_seq);
+                            }
+                        }
+                        // Fetch the messages sent while this client was disconnected
+                        if let Some(since_seq) = backfill {
+                            let request = BackfillRequestMessage {
+                                r#type: MessageType::BackfillRequest,
+                                since_seq,
+                            };
+                            let _ = control_tx.send(serde_json::to_string(&request).unwrap());
+                        }
+                    }
+                    // Server's clock sent with each keepalive Ping
+                    else if let Ok(heartbeat) = serde_json::from_str::<HeartbeatMessage>(&text)
+                        && matches!(heartbeat.r#type, MessageType::Heartbeat)
+                    {
+                        if observe_server_time(&clock, heartbeat.server_time, &screen) {
+                            screen.redisplay_prompt();
+                        }
+                    }
+                    // Latest messages of the room, sent once after room-connected
+                    else if let Ok(history) = serde_json::from_str::<RoomHistoryMessage>(&text) {
+                        // Skip messages already rendered before a reconnect
+                        let messages: Vec<_> = history
+                            .messages
+                            .into_iter()
+                            .filter(|message| resume.lock().unwrap().accept(message.seq))
+                            .collect();
+                        if !messages.is_empty() {
+                            cache_messages(&history_cache, &room_id_for_read, &messages);
+                            screen.history(&messages);
+                        }
                     }
                     // Try to parse as ParticipantJoinedMessage
                     else if let Ok(joined_msg) =
                         serde_json::from_str::<ParticipantJoinedMessage>(&text)
                     {
-                        let formatted = MessageFormatter::format_participant_joined(
+                        let notice = localized_notice(
+                            locale,
+                            SystemText::ParticipantJoined {
+                                client_id: &joined_msg.client_id,
+                            },
+                            joined_msg.notice.clone(),
+                        );
+                        let formatted = formatter.format_participant_joined(
                             &joined_msg.client_id,
                             joined_msg.connected_at,
+                            joined_msg.guest,
+                            notice.as_deref(),
+                        );
+                        screen.joined(
+                            &joined_msg.client_id,
+                            joined_msg.display_name.as_deref(),
+                            &formatted,
                         );
-                        print!("{}", formatted);
-                        redisplay_prompt(&client_id_for_read);
                     }
                     // Try to parse as ParticipantLeftMessage
                     else if let Ok(left_msg) =
                         serde_json::from_str::<ParticipantLeftMessage>(&text)
                     {
-                        let formatted = MessageFormatter::format_participant_left(
+                        let notice = localized_notice(
+                            locale,
+                            SystemText::ParticipantLeft {
+                                client_id: &left_msg.client_id,
+                            },
+                            left_msg.notice.clone(),
+                        );
+                        typing.stop(&left_msg.client_id);
+                        let formatted = formatter.format_participant_left(
                             &left_msg.client_id,
                             left_msg.disconnected_at,
+                            notice.as_deref(),
+                        );
+                        screen.left(&left_msg.client_id, &formatted);
+                        screen.typing(typing.participants(), false);
+                    }
+                    // Someone voted on a poll; show its new tally
+                    else if let Ok(updated) = serde_json::from_str::<PollUpdatedMessage>(&text)
+                        && matches!(updated.r#type, MessageType::PollUpdated)
+                    {
+                        screen.show(&formatter.format_poll_updated(updated.seq, &updated.options));
+                    }
+                    // The server confirmed a star added or removed with /star or /unstar
+                    else if let Ok(updated) = serde_json::from_str::<StarUpdatedMessage>(&text)
+                        && matches!(updated.r#type, MessageType::StarUpdated)
+                    {
+                        screen.show(&formatter.format_star_updated(updated.seq, updated.starred));
+                    }
+                    // A participant (possibly this client) set or cleared its activity
+                    else if let Ok(updated) =
+                        serde_json::from_str::<ActivityUpdatedMessage>(&text)
+                        && matches!(updated.r#type, MessageType::ActivityUpdated)
+                    {
+                        let activity = updated.activity.as_deref();
+                        screen.activity(
+                            &updated.client_id,
+                            activity,
+                            &formatter.format_activity_updated(&updated.client_id, activity),
+                        );
+                    }
+                    // A participant (possibly this client) set or cleared its display name
+                    else if let Ok(renamed) =
+                        serde_json::from_str::<ParticipantRenamedMessage>(&text)
+                        && matches!(renamed.r#type, MessageType::ParticipantRenamed)
+                    {
+                        // Reconnect under the name this client is shown as now
+                        if renamed.client_id == client_id_for_read {
+                            *display_name.lock().unwrap() = renamed.display_name.clone();
+                        }
+                        let name = renamed.display_name.as_deref();
+                        screen.renamed(
+                            &renamed.client_id,
+                            name,
+                            &formatter.format_participant_renamed(&renamed.client_id, name),
                         );
-                        print!("{}", formatted);
-                        redisplay_prompt(&client_id_for_read);
                     }
                     // Try to parse as ChatMessage
                     else if let Ok(chat_msg) = serde_json::from_str::<ChatMessage>(&text) {
-                        let formatted = MessageFormatter::format_chat_message(
-                            &chat_msg.client_id,
-                            &chat_msg.content,
-                            chat_msg.timestamp,
+                        // Skip messages already rendered before a reconnect
+                        if !resume.lock().unwrap().accept(chat_msg.seq) {
+                            continue;
+                        }
+                        typing.stop(&chat_msg.client_id);
+                        screen.typing(typing.participants(), false);
+                        cache_messages(
+                            &history_cache,
+                            &room_id_for_read,
+                            std::slice::from_ref(&chat_msg),
+                        );
+                        screen.chat(&chat_msg);
+                        // Ring the bell for mentions unless quiet hours mute it
+                        if chat_msg.client_id != client_id_for_read
+                            && mentions(&chat_msg.content, &client_id_for_read)
+                        {
+                            let mention = MissedMention {
+                                from: chat_msg.client_id.clone(),
+                                content: chat_msg.content.clone(),
+                                sent_at: chat_msg.timestamp,
+                            };
+                            let now = timestamp_to_jst_time(get_jst_timestamp());
+                            if dnd.lock().unwrap().on_mention(mention, now) {
+                                screen.bell();
+                            }
+                        }
+                    }
+                    // Another participant started or stopped typing
+                    else if let Ok(typing_msg) = serde_json::from_str::<TypingMessage>(&text)
+                        && matches!(
+                            typing_msg.r#type,
+                            MessageType::TypingStarted | MessageType::TypingStopped
+                        )
+                    {
+                        if matches!(typing_msg.r#type, MessageType::TypingStopped) {
+                            typing.stop(&typing_msg.client_id);
+                            screen.typing(typing.participants(), false);
+                        } else if typing_msg.client_id != client_id_for_read
+                            && typing.start(&typing_msg.client_id)
+                        {
+                            screen.typing(typing.participants(), true);
+                        }
+                    }
+                    // The server is restarting or shutting down; reconnect after the delay it asked for
+                    else if let Ok(shutdown_msg) =
+                        serde_json::from_str::<ServerShutdownMessage>(&text)
+                    {
+                        let system_text = match shutdown_msg.reason.as_str() {
+                            "shutdown" => SystemText::ServerShutdown,
+                            _ => SystemText::ServerRestart,
+                        };
+                        let notice =
+                            localized_notice(locale, system_text, shutdown_msg.notice.clone());
+                        let formatted = formatter.format_server_shutdown(
+                            shutdown_msg.reconnect_after_ms,
+                            notice.as_deref(),
                         );
-                        print!("{}", formatted);
-                        redisplay_prompt(&client_id_for_read);
+                        screen.print(&formatted);
+                        connection_error = Some(ClientError::ServerRestarting(
+                            Duration::from_millis(shutdown_msg.reconnect_after_ms),
+                        ));
+                        break;
+                    }
+                    // Rooms the client asked for with /rooms
+                    else if let Ok(room_list) = serde_json::from_str::<RoomListMessage>(&text) {
+                        screen.show(&formatter.format_room_list(&room_list.rooms));
+                    }
+                    // A message was deleted from the room history
+                    else if let Ok(deleted) = serde_json::from_str::<MessageDeletedMessage>(&text)
+                    {
+                        if let (Some(cache), Some(joined)) =
+                            (&history_cache, room_id_for_read.lock().unwrap().as_deref())
+                            && let Err(e) = cache.remove(joined, deleted.seq)
+                        {
+                            tracing::warn!("Failed to update the history cache: {}", e);
+                        }
+                        screen.show(&formatter.format_message_deleted(deleted.seq));
+                    }
+                    // An admin froze or unfroze the room
+                    else if let Ok(freeze) = serde_json::from_str::<RoomFreezeMessage>(&text)
+                        && matches!(
+                            freeze.r#type,
+                            MessageType::RoomFrozen | MessageType::RoomUnfrozen
+                        )
+                    {
+                        let frozen = matches!(freeze.r#type, MessageType::RoomFrozen);
+                        screen.show(&formatter.format_room_frozen(frozen));
+                    }
+                    // An admin is closing the connections of everyone in the room
+                    else if let Ok(disconnected) =
+                        serde_json::from_str::<DisconnectedMessage>(&text)
+                        && matches!(disconnected.r#type, MessageType::Disconnected)
+                    {
+                        screen.print(&formatter.format_disconnected(&disconnected.reason));
+                    }
+                    // The send queue of this client is backing up on the server (or has drained)
+                    else if let Ok(slow_down) = serde_json::from_str::<SlowDownMessage>(&text) {
+                        let interval = Duration::from_millis(slow_down.send_interval_ms);
+                        if throttle_for_read.lock().unwrap().advise(interval) {
+                            screen.show(&formatter.format_slow_down(slow_down.send_interval_ms));
+                        }
+                    }
+                    // Welcome message of the room, sent only to this client after joining
+                    else if let Ok(welcome) = serde_json::from_str::<WelcomeMessage>(&text)
+                        && matches!(welcome.r#type, MessageType::Welcome)
+                    {
+                        screen.show(&formatter.format_welcome(&welcome.content));
+                    }
+                    // The room requires approval; nothing arrives until a moderator decides
+                    else if let Ok(pending) = serde_json::from_str::<JoinPendingMessage>(&text)
+                        && matches!(pending.r#type, MessageType::JoinPending)
+                    {
+                        screen.show(&formatter.format_join_pending(&pending.message));
+                    }
+                    // Another client is waiting for approval to join this room
+                    else if let Ok(requested) =
+                        serde_json::from_str::<JoinRequestedMessage>(&text)
+                        && matches!(requested.r#type, MessageType::JoinRequested)
+                    {
+                        screen.show(&formatter.format_join_requested(&requested.client_id));
+                    }
+                    // The server rejected a message sent by this client
+                    else if let Ok(error_msg) = serde_json::from_str::<ErrorMessage>(&text) {
+                        let formatted = formatter.format_error(&error_msg.code, &error_msg.message);
+                        screen.show(&formatted);
                     }
                     // If parsing fails, display as raw text
                     else {
-                        let formatted = MessageFormatter::format_raw_message(&text);
-                        print!("{}", formatted);
-                        redisplay_prompt(&client_id_for_read);
+                        let formatted = formatter.format_raw_message(&text);
+                        screen.show(&formatted);
+                    }
+                }
+                // Answer to a Ping sent on /ping or to refresh the latency in the prompt
+                Ok(Message::Pong(data)) => {
+                    let measured = latency.lock().unwrap().on_pong(&data, Instant::now());
+                    if let Some((rtt, true)) = measured {
+                        screen.show(&formatter.format_latency(rtt));
                     }
                 }
                 Ok(Message::Binary(data)) => {
-                    let formatted = MessageFormatter::format_binary_message(data.len());
-                    print!("{}", formatted);
-                    redisplay_prompt(&client_id_for_read);
+                    let formatted = formatter.format_binary_message(data.len());
+                    screen.show(&formatted);
+                }
+                // Reconnecting would take the session back from the newer connection
+                Ok(Message::Close(Some(frame)))
+                    if u16::from(frame.code) == SESSION_REPLACED_CLOSE_CODE =>
+                {
+                    screen.print(&formatter.format_session_replaced());
+                    connection_error = Some(ClientError::SessionReplaced);
+                    break;
+                }
+                // Reconnecting after a kick would defeat it (and a ban rejects it)
+                Ok(Message::Close(Some(frame)))
+                    if matches!(u16::from(frame.code), KICKED_CLOSE_CODE | BANNED_CLOSE_CODE) =>
+                {
+                    let banned = u16::from(frame.code) == BANNED_CLOSE_CODE;
+                    screen.print(&formatter.format_removed(banned));
+                    connection_error = Some(ClientError::Removed { banned });
+                    break;
+                }
+                // The `disconnected` notice before the close already told the user why
+                Ok(Message::Close(Some(frame)))
+                    if u16::from(frame.code) == DISCONNECTED_CLOSE_CODE =>
+                {
+                    connection_error = Some(ClientError::Removed { banned: false });
+                    break;
+                }
+                // Reconnecting would only ask for approval again
+                Ok(Message::Close(Some(frame)))
+                    if u16::from(frame.code) == JOIN_REJECTED_CLOSE_CODE =>
+                {
+                    screen.print(&formatter.format_join_rejected());
+                    connection_error = Some(ClientError::JoinRejected);
+                    break;
                 }
                 Ok(Message::Close(_)) => {
                     tracing::info!("Server closed the connection");
-                    connection_error = true;
+                    connection_error = Some(ClientError::ConnectionLost);
                     break;
                 }
                 Err(e) => {
                     tracing::warn!("WebSocket read error: {}", e);
-                    connection_error = true;
+                    connection_error = Some(ClientError::ConnectionLost);
                     break;
                 }
                 _ => {}
@@ -134,113 +653,422 @@ pub async fn run_client_session(
         connection_error
     });
 
-    // Clone client_id for the input loop
-    let client_id = client_id.to_string();
-    let client_id_for_prompt = client_id.clone();
-
-    // Create channel for rustyline input
-    let (input_tx, mut input_rx) = mpsc::unbounded_channel::<String>();
-
-    // Spawn a blocking thread for rustyline (synchronous readline)
-    let _readline_handle = std::thread::spawn(move || {
-        let mut rl = match DefaultEditor::new() {
-            Ok(rl) => rl,
-            Err(e) => {
-                eprintln!("Failed to initialize readline: {}", e);
-                return;
-            }
-        };
-
-        let prompt = format!("{}> ", client_id_for_prompt);
+    // Lines typed while disconnected are sent first, in the order they were typed
+    let outbox = &mut state.outbox;
+    let queued = outbox.queued();
+    if queued > 0 {
+        tracing::info!("Sending {} line(s) typed while disconnected", queued);
+    }
 
+    // Send the lines typed at the prompt to the WebSocket
+    let client_id = client_id.to_string();
+    let wire_log = state.wire_log.as_deref();
+    let latency = state.latency.clone();
+    let clock = state.clock.clone();
+    let mut latency_probe = state
+        .show_latency
+        .then(|| tokio::time::interval(LATENCY_PROBE_INTERVAL));
+    let write_task = async move {
         loop {
-            match rl.readline(&prompt) {
-                Ok(line) => {
-                    let line = line.trim();
-                    if !line.is_empty() {
-                        rl.add_history_entry(line).ok();
-                        if input_tx.send(line.to_string()).is_err() {
-                            // Channel closed, exit thread
-                            break;
+            let line = tokio::select! {
+                line = outbox.next() => match line {
+                    Some(line) => line,
+                    None => return None,
+                },
+                Some(frame) = control_rx.recv() => {
+                    let frame = Message::Text(frame.into());
+                    tap(wire_log, &server.url, WireDirection::Out, &frame);
+                    if let Err(e) = write.send(frame).await {
+                        tracing::warn!("Failed to send message: {}", e);
+                        return Some(ClientError::ConnectionLost);
+                    }
+                    continue;
+                }
+                _ = async { latency_probe.as_mut().unwrap().tick().await }, if latency_probe.is_some() => {
+                    let payload = latency.lock().unwrap().probe(Instant::now(), false);
+                    let frame = Message::Ping(payload.into());
+                    tap(wire_log, &server.url, WireDirection::Out, &frame);
+                    if let Err(e) = write.send(frame).await {
+                        tracing::warn!("Failed to send ping: {}", e);
+                        return Some(ClientError::ConnectionLost);
+                    }
+                    continue;
+                }
+            };
+
+            let (frame, sent) = match Input::parse(&line) {
+                Input::Chat(content) => {
+                    // Create message with type "chat" and client_id
+                    let msg = ChatMessage {
+                        r#type: MessageType::Chat,
+                        client_id: client_id.clone(),
+                        content,
+                        // Stamped with the server's clock so that it reads like the others
+                        timestamp: clock.lock().unwrap().to_server_time(get_jst_timestamp()),
+                        seq: None,
+                        poll: None,
+                        forwarded_from: None,
+                    };
+                    match serde_json::to_string(&msg) {
+                        Ok(json) => (Message::Text(json.into()), Some(msg)),
+                        Err(e) => {
+                            tracing::error!("Failed to serialize message: {}", e);
+                            outbox.sent();
+                            continue;
                         }
                     }
                 }
-                Err(ReadlineError::Interrupted) => {
-                    // Ctrl+C
-                    tracing::info!("Interrupted");
-                    break;
+                Input::ListRooms => {
+                    let request = ListRoomsMessage {
+                        r#type: MessageType::ListRooms,
+                    };
+                    let json = serde_json::to_string(&request).unwrap();
+                    (Message::Text(json.into()), None)
                 }
-                Err(ReadlineError::Eof) => {
-                    // Ctrl+D
-                    tracing::info!("EOF");
-                    break;
+                // The server sends the poll back to this client too, with its sequence number
+                Input::Poll { question, options } => {
+                    let request = CreatePollMessage {
+                        r#type: MessageType::Poll,
+                        client_id: client_id.clone(),
+                        content: question,
+                        options,
+                        timestamp: clock.lock().unwrap().to_server_time(get_jst_timestamp()),
+                    };
+                    let json = serde_json::to_string(&request).unwrap();
+                    (Message::Text(json.into()), None)
                 }
-                Err(err) => {
-                    tracing::error!("Readline error: {}", err);
-                    break;
+                Input::Vote { seq, option } => {
+                    let request = VoteMessage {
+                        r#type: MessageType::Vote,
+                        seq,
+                        option,
+                    };
+                    let json = serde_json::to_string(&request).unwrap();
+                    (Message::Text(json.into()), None)
                 }
-            }
-        }
-    });
-
-    // Spawn a task to handle stdin input and send to WebSocket
-    let client_id_for_write = client_id.clone();
-    let mut write_task = tokio::spawn(async move {
-        let mut write_error = false;
-
-        while let Some(line) = input_rx.recv().await {
-            // Create message with type "chat" and client_id
-            let msg = ChatMessage {
-                r#type: MessageType::Chat,
-                client_id: client_id.clone(),
-                content: line,
-                timestamp: get_jst_timestamp(),
-            };
-
-            let json = match serde_json::to_string(&msg) {
-                Ok(json) => json,
-                Err(e) => {
-                    tracing::error!("Failed to serialize message: {}", e);
+                // The copy is broadcast in the target room, not in this one
+                Input::Forward { seq, room_id } => {
+                    let request = ForwardMessage {
+                        r#type: MessageType::Forward,
+                        seq,
+                        room_id,
+                    };
+                    let json = serde_json::to_string(&request).unwrap();
+                    (Message::Text(json.into()), None)
+                }
+                // Only this client is told, with star-updated
+                Input::Star { seq, starred } => {
+                    let request = StarMessage {
+                        r#type: if starred {
+                            MessageType::Star
+                        } else {
+                            MessageType::Unstar
+                        },
+                        seq,
+                    };
+                    let json = serde_json::to_string(&request).unwrap();
+                    (Message::Text(json.into()), None)
+                }
+                // The server broadcasts activity-updated to everyone, including this client
+                Input::SetActivity(activity) => {
+                    let request = SetActivityMessage {
+                        r#type: MessageType::SetActivity,
+                        activity,
+                    };
+                    let json = serde_json::to_string(&request).unwrap();
+                    (Message::Text(json.into()), None)
+                }
+                // The server broadcasts participant-renamed to everyone, including this client
+                Input::SetDisplayName(display_name) => {
+                    let request = SetDisplayNameMessage {
+                        r#type: MessageType::SetDisplayName,
+                        display_name,
+                    };
+                    let json = serde_json::to_string(&request).unwrap();
+                    (Message::Text(json.into()), None)
+                }
+                // The room is created over HTTP, then joined like with /join
+                Input::CreateRoom(name) => {
+                    let formatter = screen.formatter();
+                    outbox.sent();
+                    match create_room(server, &name).await {
+                        Ok(room) => {
+                            screen.show(&formatter.format_joining_room(&name, &room.id, true));
+                            return Some(ClientError::SwitchingRoom(room.id));
+                        }
+                        Err(e) => screen.show(&formatter.format_error("create", &e.to_string())),
+                    }
+                    continue;
+                }
+                // The session ends and the runner connects to the room
+                Input::JoinRoom(room_id) => {
+                    let formatter = screen.formatter();
+                    outbox.sent();
+                    match fetch_room(server, &room_id).await {
+                        Ok(room) => {
+                            let name = room.slug.as_deref().unwrap_or(&room.id);
+                            screen.show(&formatter.format_joining_room(name, &room.id, false));
+                            return Some(ClientError::SwitchingRoom(room.id));
+                        }
+                        Err(e) => screen.show(&formatter.format_error("join", &e.to_string())),
+                    }
                     continue;
                 }
+                // Stars are listed over HTTP, across all the rooms
+                Input::ListStarred => {
+                    let formatter = screen.formatter();
+                    match fetch_starred(server, &client_id).await {
+                        Ok(messages) => screen.show(&formatter.format_starred_messages(&messages)),
+                        Err(e) => screen.show(&formatter.format_error("starred", &e.to_string())),
+                    }
+                    outbox.sent();
+                    continue;
+                }
+                // Messages are searched over HTTP, in the room joined
+                Input::Search(query) => {
+                    let formatter = screen.formatter();
+                    let joined = room_id.lock().unwrap().clone();
+                    match joined {
+                        Some(room_id) => match fetch_search(server, &room_id, &query).await {
+                            Ok(messages) => {
+                                screen.show(&formatter.format_search_results(&query, &messages))
+                            }
+                            Err(e) => search_cached_history(
+                                history_cache_for_search.as_deref(),
+                                &room_id,
+                                &query,
+                                &e,
+                                &screen,
+                            ),
+                        },
+                        None => screen.show(
+                            &formatter
+                                .format_error("search", "the server did not give the room ID"),
+                        ),
+                    }
+                    outbox.sent();
+                    continue;
+                }
+                Input::Jump { seq } => {
+                    screen.jump(seq);
+                    outbox.sent();
+                    continue;
+                }
+                Input::Usage(usage) => {
+                    screen.show(&screen.formatter().format_usage(usage));
+                    outbox.sent();
+                    continue;
+                }
+                // The server's WebSocket layer answers with a Pong carrying the same payload
+                Input::Ping => {
+                    let payload = latency.lock().unwrap().probe(Instant::now(), true);
+                    (Message::Ping(payload.into()), None)
+                }
             };
 
-            if let Err(e) = write.send(Message::Text(json.into())).await {
+            // Space out the lines while the server asks to slow down (pings are not held back,
+            // as the wait would count in the latency)
+            let wait = throttle.lock().unwrap().wait(Instant::now());
+            if !wait.is_zero() && frame.is_text() {
+                tokio::time::sleep(wait).await;
+            }
+
+            // The line stays in the outbox to be sent again after reconnecting
+            tap(wire_log, &server.url, WireDirection::Out, &frame);
+            if let Err(e) = write.send(frame).await {
                 tracing::warn!("Failed to send message: {}", e);
-                write_error = true;
-                break;
+                return Some(ClientError::ConnectionLost);
             }
+            outbox.sent();
+            throttle.lock().unwrap().sent(Instant::now());
 
-            // Display sent timestamp and redisplay prompt
-            let formatted = MessageFormatter::format_sent_confirmation(msg.timestamp);
-            println!("{}", formatted);
-            redisplay_prompt(&client_id_for_write);
+            // Confirm the chat message sent (the server does not echo it back)
+            if let Some(message) = sent {
+                screen.sent(&message);
+            }
         }
-
-        write_error
-    });
+    };
+    tokio::pin!(write_task);
 
     // If any one of the tasks completes, abort the other
     tokio::select! {
         read_result = &mut read_task => {
-            write_task.abort();
-            let connection_error = read_result.unwrap_or(false);
-            if connection_error {
-                return Err(Box::new(ClientError::ConnectionError(
-                    "Connection lost".to_string(),
-                )));
+            if let Some(error) = read_result.unwrap_or(None) {
+                return Err(Box::new(error));
             }
         }
-        write_result = &mut write_task => {
+        write_error = &mut write_task => {
             read_task.abort();
-            let write_error = write_result.unwrap_or(false);
-            if write_error {
-                return Err(Box::new(ClientError::ConnectionError(
-                    "Connection lost".to_string(),
-                )));
+            if let Some(error) = write_error {
+                return Err(Box::new(error));
             }
         }
     }
 
     Ok(())
 }
+
+/// Append messages received in the room joined to the history cache, if enabled
+fn cache_messages(
+    cache: &Option<Arc<HistoryCache>>,
+    room_id: &Mutex<Option<String>>,
+    messages: &[ChatMessage],
+) {
+    if let (Some(cache), Some(room_id)) = (cache, room_id.lock().unwrap().as_deref())
+        && let Err(e) = cache.append(room_id, messages)
+    {
+        tracing::warn!("Failed to update the history cache: {}", e);
+    }
+}
+
+/// Show the cached messages of a room joined on a fresh connection (once per room), marking
+/// them as received so that the room history does not render them again
+///
+/// Returns the latest cached sequence number, to backfill the messages sent since.
+fn restore_cached_history(
+    cache: &HistoryCache,
+    room_id: &str,
+    shown: &Mutex<Option<String>>,
+    resume: &Mutex<ResumeState>,
+    screen: &Screen,
+) -> Option<u64> {
+    let cached = match cache.load(room_id) {
+        Ok(cached) => cached,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to read the history cache of room {}: {}",
+                room_id,
+                e
+            );
+            return None;
+        }
+    };
+    {
+        let mut resume = resume.lock().unwrap();
+        for message in &cached {
+            resume.accept(message.seq);
+        }
+    }
+    let mut shown = shown.lock().unwrap();
+    if shown.as_deref() != Some(room_id) && !cached.is_empty() {
+        screen.history(&cached);
+    }
+    *shown = Some(room_id.to_string());
+    cached.last().and_then(|message| message.seq)
+}
+
+/// Show the cached messages of the room matching a search the server could not answer
+fn search_cached_history(
+    cache: Option<&HistoryCache>,
+    room_id: &str,
+    query: &str,
+    error: &ClientError,
+    screen: &Screen,
+) {
+    let formatter = screen.formatter();
+    match cache.map(|cache| cache.search(room_id, query, CACHED_SEARCH_LIMIT)) {
+        Some(Ok(messages)) => {
+            screen.print(&formatter.format_cache_fallback(&error.to_string()));
+            screen.show(&formatter.format_search_results(query, &messages));
+        }
+        _ => screen.show(&formatter.format_error("search", &error.to_string())),
+    }
+}
+
+/// Update the skew of the local clock with the server's clock, telling the user once the
+/// clocks disagree
+///
+/// Returns whether a notice was printed.
+fn observe_server_time(clock: &Mutex<ClockSkew>, server_time: i64, screen: &Screen) -> bool {
+    let skewed = clock
+        .lock()
+        .unwrap()
+        .observe(server_time, get_jst_timestamp());
+    match skewed {
+        Some(offset_ms) => {
+            tracing::warn!("Local clock is off the server's by {}ms", offset_ms);
+            screen.print(&screen.formatter().format_clock_skew(offset_ms));
+            true
+        }
+        None => false,
+    }
+}
+
+/// Report quiet hours starting and ending, with the mentions missed during them
+///
+/// Runs for the whole client run so that the summary is printed even while reconnecting.
+pub async fn watch_quiet_hours(dnd: Arc<Mutex<DoNotDisturb>>, screen: Screen) {
+    let formatter = screen.formatter();
+    let mut interval = tokio::time::interval(QUIET_HOURS_CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        let now = timestamp_to_jst_time(get_jst_timestamp());
+        let event = dnd.lock().unwrap().tick(now);
+        let formatted = match event {
+            Some(QuietHoursEvent::Started { until }) => formatter.format_quiet_hours_started(until),
+            Some(QuietHoursEvent::Ended { missed }) => formatter.format_quiet_hours_ended(&missed),
+            None => continue,
+        };
+        screen.show(&formatted);
+    }
+}
+
+/// Read input lines with rustyline, showing the prompt (refreshed for each line)
+fn read_lines_with_editor(prompt: &Prompt, input_tx: mpsc::UnboundedSender<String>) {
+    let mut rl = match DefaultEditor::new() {
+        Ok(rl) => rl,
+        Err(e) => {
+            eprintln!("Failed to initialize readline: {}", e);
+            return;
+        }
+    };
+
+    loop {
+        match rl.readline(&prompt.text()) {
+            Ok(line) => {
+                let line = line.trim();
+                if !line.is_empty() {
+                    rl.add_history_entry(line).ok();
+                    prompt.line_sent();
+                    if input_tx.send(line.to_string()).is_err() {
+                        // Channel closed, exit thread
+                        break;
+                    }
+                }
+            }
+            Err(ReadlineError::Interrupted) => {
+                // Ctrl+C
+                tracing::info!("Interrupted");
+                break;
+            }
+            Err(ReadlineError::Eof) => {
+                // Ctrl+D
+                tracing::info!("EOF");
+                break;
+            }
+            Err(err) => {
+                tracing::error!("Readline error: {}", err);
+                break;
+            }
+        }
+    }
+}
+
+/// Read input lines from stdin without a prompt or line editing
+fn read_plain_lines(input_tx: mpsc::UnboundedSender<String>) {
+    for line in std::io::stdin().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                tracing::error!("Stdin read error: {}", err);
+                break;
+            }
+        };
+        let line = line.trim();
+        if !line.is_empty() && input_tx.send(line.to_string()).is_err() {
+            // Channel closed, exit thread
+            break;
+        }
+    }
+    tracing::info!("EOF");
+}
@@ -0,0 +1,53 @@
+//! Listing the messages the client starred.
+
+use std::time::Duration;
+
+use engawa_server::infrastructure::dto::http::{StarredMessageDto, StarredMessagesDto};
+
+use super::{
+    domain::{Endpoint, starred_url},
+    error::ClientError,
+};
+
+/// How long to wait for the list of starred messages
+const STARRED_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Get the messages the client starred, in the order they were starred
+///
+/// The endpoint is derived from the WebSocket URL of the server and sent the same access
+/// token as the connection.
+pub async fn fetch_starred(
+    server: &Endpoint,
+    client_id: &str,
+) -> Result<Vec<StarredMessageDto>, ClientError> {
+    let url = starred_url(&server.url, client_id).ok_or_else(|| {
+        ClientError::ConnectionError(format!("Cannot list stars on '{}'", server.url))
+    })?;
+    let mut request = server.tls.http_client().get(&url).timeout(STARRED_TIMEOUT);
+    if let Some(token) = &server.token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ClientError::ConnectionError(e.to_string()))?;
+    match response.status().as_u16() {
+        200 => {}
+        404 => {
+            return Err(ClientError::ConnectionError(
+                "the server does not keep starred messages".to_string(),
+            ));
+        }
+        status => {
+            return Err(ClientError::ConnectionError(format!(
+                "Listing stars failed with HTTP {}",
+                status
+            )));
+        }
+    }
+    let starred: StarredMessagesDto = response
+        .json()
+        .await
+        .map_err(|e| ClientError::ConnectionError(e.to_string()))?;
+    Ok(starred.messages)
+}
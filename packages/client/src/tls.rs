@@ -0,0 +1,227 @@
+//! Certificates trusted on `wss://` and `https://` connections.
+
+use std::{path::Path, sync::Arc};
+
+use rustls::{
+    ClientConfig, RootCertStore,
+    pki_types::{CertificateDer, pem::PemObject},
+};
+use tokio_tungstenite::Connector;
+
+use super::error::ConfigError;
+
+/// Certificate authorities the client trusts when connecting over TLS.
+///
+/// The public web PKI is always trusted; a custom CA (e.g. of a self-signed server
+/// certificate) can be added with `--ca-cert`.
+#[derive(Debug, Clone)]
+pub struct TlsTrust {
+    /// rustls configuration of WebSocket connections
+    config: Arc<ClientConfig>,
+    /// Custom CA certificates, also trusted by the login HTTP client
+    http_roots: Vec<reqwest::Certificate>,
+}
+
+impl TlsTrust {
+    /// Trust the public web PKI and, if given, the CA certificates in a PEM file.
+    ///
+    /// # Arguments
+    ///
+    /// * `ca_cert` - PEM file of additional CA certificates to trust
+    ///
+    /// # Returns
+    ///
+    /// The trust settings, or an error if the file cannot be read or holds no certificate
+    pub fn load(ca_cert: Option<&Path>) -> Result<Self, ConfigError> {
+        let mut roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        let mut http_roots = Vec::new();
+        if let Some(path) = ca_cert {
+            let invalid = |reason: String| ConfigError::CaCert {
+                path: path.display().to_string(),
+                reason,
+            };
+            let pem = std::fs::read(path).map_err(|e| invalid(e.to_string()))?;
+            let certs = CertificateDer::pem_slice_iter(&pem)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| invalid(e.to_string()))?;
+            if certs.is_empty() {
+                return Err(invalid("no certificate found".to_string()));
+            }
+            for cert in certs {
+                roots.add(cert).map_err(|e| invalid(e.to_string()))?;
+            }
+            http_roots =
+                reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| invalid(e.to_string()))?;
+        }
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let config = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .expect("ring should support the default protocol versions")
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        Ok(Self {
+            config: Arc::new(config),
+            http_roots,
+        })
+    }
+
+    /// Connector of WebSocket connections (plain for `ws://`, TLS for `wss://` URLs).
+    pub fn connector(&self) -> Connector {
+        Connector::Rustls(self.config.clone())
+    }
+
+    /// HTTP client of requests to the server, such as logging in.
+    pub fn http_client(&self) -> reqwest::Client {
+        self.http_roots
+            .iter()
+            .fold(reqwest::Client::builder(), |builder, certificate| {
+                builder.add_root_certificate(certificate.clone())
+            })
+            .build()
+            .expect("HTTP client should be built")
+    }
+}
+
+impl Default for TlsTrust {
+    /// Trust the public web PKI only.
+    fn default() -> Self {
+        Self::load(None).expect("the web PKI roots should be loaded")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    /// Self-signed certificate for `localhost` written to a temporary PEM file
+    struct SelfSigned {
+        cert: rcgen::Certificate,
+        key_pair: rcgen::KeyPair,
+        path: std::path::PathBuf,
+    }
+
+    impl SelfSigned {
+        fn generate(name: &str) -> Self {
+            let rcgen::CertifiedKey { cert, key_pair } =
+                rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+            let path = std::env::temp_dir().join(format!(
+                "engawa-client-ca-{}-{}.crt",
+                std::process::id(),
+                name
+            ));
+            std::fs::write(&path, cert.pem()).unwrap();
+            Self {
+                cert,
+                key_pair,
+                path,
+            }
+        }
+    }
+
+    impl Drop for SelfSigned {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    /// Accept one `wss://` connection with the certificate and echo a text message back
+    async fn serve_echo_once(certificate: &SelfSigned) -> std::net::SocketAddr {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let config = rustls::ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(
+                vec![certificate.cert.der().clone()],
+                rustls::pki_types::PrivateKeyDer::from_pem_slice(
+                    certificate.key_pair.serialize_pem().as_bytes(),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let Ok(tls) = acceptor.accept(tcp).await else {
+                return;
+            };
+            let mut ws = tokio_tungstenite::accept_async(tls).await.unwrap();
+            if let Some(Ok(message)) = ws.next().await {
+                ws.send(message).await.unwrap();
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_connect_wss_with_custom_ca() {
+        // テスト項目: --ca-cert で自己署名証明書を信頼すると wss:// に接続できる
+        // given (前提条件):
+        let certificate = SelfSigned::generate("trusted");
+        let addr = serve_echo_once(&certificate).await;
+        let trust = TlsTrust::load(Some(&certificate.path)).unwrap();
+        let url = format!("wss://localhost:{}/ws", addr.port());
+
+        // when (操作):
+        let (mut ws, _response) = tokio_tungstenite::connect_async_tls_with_config(
+            url,
+            None,
+            false,
+            Some(trust.connector()),
+        )
+        .await
+        .unwrap();
+        ws.send(Message::text("hello")).await.unwrap();
+        let echoed = ws.next().await.unwrap().unwrap();
+
+        // then (期待する結果):
+        assert_eq!(echoed, Message::text("hello"));
+    }
+
+    #[tokio::test]
+    async fn test_reject_self_signed_certificate_without_custom_ca() {
+        // テスト項目: 自己署名証明書は --ca-cert 無しでは信頼されず接続できない
+        // given (前提条件):
+        let certificate = SelfSigned::generate("untrusted");
+        let addr = serve_echo_once(&certificate).await;
+        let url = format!("wss://localhost:{}/ws", addr.port());
+
+        // when (操作):
+        let result = tokio_tungstenite::connect_async_tls_with_config(
+            url,
+            None,
+            false,
+            Some(TlsTrust::default().connector()),
+        )
+        .await;
+
+        // then (期待する結果):
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_load_rejects_file_without_certificate() {
+        // テスト項目: 証明書を含まないファイルや存在しないファイルはエラーになる
+        // given (前提条件):
+        let path = std::env::temp_dir().join(format!(
+            "engawa-client-ca-{}-invalid.pem",
+            std::process::id()
+        ));
+        std::fs::write(&path, "not a certificate").unwrap();
+
+        // when (操作):
+        let empty = TlsTrust::load(Some(&path));
+        let missing = TlsTrust::load(Some(&path.with_extension("missing")));
+        std::fs::remove_file(&path).unwrap();
+
+        // then (期待する結果):
+        assert!(matches!(empty, Err(ConfigError::CaCert { .. })));
+        assert!(matches!(missing, Err(ConfigError::CaCert { .. })));
+    }
+}
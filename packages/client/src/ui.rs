@@ -1,9 +1,1195 @@
-//! UI utilities for the client.
+//! Terminal user interface of the client.
+//!
+//! The chat runs in a full-screen terminal UI by default ([`UiMode::Tui`]): a scrollable
+//! message pane, a sidebar listing the participants, an input line and a status bar showing the
+//! connection state. Participants that set a display name are listed under it in the sidebar,
+//! and those that published an activity have it shown under their name. [`UiMode::Plain`] prints each event as it arrives and reads lines with a
+//! line editor instead; it is also used for `--accessible` output and when stdin or stdout is
+//! not a terminal.
 
-use std::io::Write;
+use std::{
+    collections::HashMap,
+    io::Write,
+    str::FromStr,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+        mpsc as std_mpsc,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use engawa_server::infrastructure::dto::websocket::{ChatMessage, ParticipantInfo};
+use engawa_shared::time::timestamp_to_jst_clock;
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, List, ListItem, Paragraph, Wrap},
+};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+
+use super::{
+    domain::{LatencyMeter, PromptTemplate, PromptValues},
+    error::ConfigError,
+    formatter::{MessageFormatter, OutputMode},
+};
+
+/// How long the terminal UI waits for a key before applying new events and redrawing
+const FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Lines scrolled by Page Up and Page Down
+const PAGE_SCROLL: usize = 10;
+
+/// Width of the participant sidebar
+const SIDEBAR_WIDTH: u16 = 24;
+
+/// How the chat uses the terminal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UiMode {
+    /// Full-screen terminal UI with a message pane, a participant sidebar and a status bar
+    #[default]
+    Tui,
+    /// Events printed line by line, with a line-editing prompt
+    Plain,
+}
+
+impl FromStr for UiMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tui" => Ok(Self::Tui),
+            "plain" => Ok(Self::Plain),
+            _ => Err(format!("unknown UI '{}' (expected: tui, plain)", s)),
+        }
+    }
+}
+
+/// Prompt shown before the typed line
+///
+/// `{client_id}> `, or `{client_id} [42ms]> ` with the latest round trip to the server when the
+/// latency indicator is enabled, unless a template is configured.
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    client_id: String,
+    latency: Option<Arc<Mutex<LatencyMeter>>>,
+    template: Option<PromptTemplate>,
+    /// Room shown by `{room}`
+    room: Arc<Mutex<String>>,
+    /// Messages received since the last line was sent, shown by `{unread}`
+    unread: Arc<AtomicUsize>,
+}
+
+impl Prompt {
+    /// Create the prompt of `client_id`, showing the latency measured by `latency` if given
+    pub fn new(client_id: &str, latency: Option<Arc<Mutex<LatencyMeter>>>) -> Self {
+        Self {
+            client_id: client_id.to_string(),
+            latency,
+            template: None,
+            room: Arc::new(Mutex::new(String::new())),
+            unread: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Render the prompt from a template instead (the default prompt if `None`)
+    pub fn with_template(mut self, template: Option<PromptTemplate>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Current text of the prompt
+    pub fn text(&self) -> String {
+        let latest = self
+            .latency
+            .as_ref()
+            .and_then(|latency| latency.lock().unwrap().latest());
+        if let Some(template) = &self.template {
+            return template.render(&PromptValues {
+                client_id: &self.client_id,
+                room: &self.room.lock().unwrap(),
+                unread: self.unread.load(Ordering::Relaxed),
+                latency: latest,
+            });
+        }
+        match latest {
+            Some(rtt) => format!("{} [{}ms]> ", self.client_id, rtt.as_millis()),
+            None => format!("{}> ", self.client_id),
+        }
+    }
+
+    /// Set the room shown by `{room}`
+    pub fn set_room(&self, room: &str) {
+        *self.room.lock().unwrap() = room.to_string();
+    }
+
+    /// Count a message received since the last line was sent
+    pub fn message_received(&self) {
+        self.unread.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Reset the unread count once the user sends a line
+    pub fn line_sent(&self) {
+        self.unread.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Colors of the terminal UI
+///
+/// Set with `"theme"` in the configuration file, either as the name of a built-in theme
+/// (`dark`, `light` or `mono`) or as an object overriding some colors of one:
+///
+/// ```json
+/// { "theme": { "base": "light", "sender": "#005f87", "notice": "magenta" } }
+/// ```
+///
+/// Colors are names (e.g. `green`, `darkgray`), `#rrggbb` or 256-color indexes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "ThemeConfig")]
+pub struct Theme {
+    /// This client's name, in its messages and in the sidebar
+    pub own_name: Color,
+    /// Names of the other participants in their messages
+    pub sender: Color,
+    /// Times of the messages and activities in the sidebar
+    pub muted: Color,
+    /// Notices in the message pane
+    pub notice: Color,
+    /// Background of the connection state in the status bar, by state
+    pub connected: Color,
+    pub connecting: Color,
+    pub reconnecting: Color,
+    /// Text of the connection state in the status bar
+    pub status_text: Color,
+}
+
+impl Theme {
+    /// For terminals with a dark background (the default)
+    pub const DARK: Self = Self {
+        own_name: Color::Green,
+        sender: Color::Cyan,
+        muted: Color::DarkGray,
+        notice: Color::Yellow,
+        connected: Color::Green,
+        connecting: Color::Yellow,
+        reconnecting: Color::Red,
+        status_text: Color::Black,
+    };
+
+    /// For terminals with a light background
+    pub const LIGHT: Self = Self {
+        own_name: Color::Green,
+        sender: Color::Blue,
+        muted: Color::DarkGray,
+        notice: Color::Magenta,
+        connected: Color::Green,
+        connecting: Color::Yellow,
+        reconnecting: Color::Red,
+        status_text: Color::Black,
+    };
+
+    /// Without colors, for terminals (or users) that do not tell them apart
+    pub const MONO: Self = Self {
+        own_name: Color::Reset,
+        sender: Color::Reset,
+        muted: Color::Reset,
+        notice: Color::Reset,
+        connected: Color::White,
+        connecting: Color::White,
+        reconnecting: Color::White,
+        status_text: Color::Black,
+    };
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+impl FromStr for Theme {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dark" => Ok(Self::DARK),
+            "light" => Ok(Self::LIGHT),
+            "mono" => Ok(Self::MONO),
+            _ => Err(ConfigError::InvalidTheme(format!(
+                "unknown theme '{}' (expected: dark, light, mono)",
+                s
+            ))),
+        }
+    }
+}
+
+/// `"theme"` in the configuration file: a built-in theme, or colors overriding one
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ThemeConfig {
+    Name(String),
+    Custom(CustomTheme),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CustomTheme {
+    base: Option<String>,
+    own_name: Option<String>,
+    sender: Option<String>,
+    muted: Option<String>,
+    notice: Option<String>,
+    connected: Option<String>,
+    connecting: Option<String>,
+    reconnecting: Option<String>,
+    status_text: Option<String>,
+}
+
+impl TryFrom<ThemeConfig> for Theme {
+    type Error = ConfigError;
+
+    fn try_from(config: ThemeConfig) -> Result<Self, Self::Error> {
+        let custom = match config {
+            ThemeConfig::Name(name) => return name.parse(),
+            ThemeConfig::Custom(custom) => custom,
+        };
+        let mut theme: Theme = custom.base.as_deref().unwrap_or("dark").parse()?;
+        for (slot, color) in [
+            (&mut theme.own_name, custom.own_name),
+            (&mut theme.sender, custom.sender),
+            (&mut theme.muted, custom.muted),
+            (&mut theme.notice, custom.notice),
+            (&mut theme.connected, custom.connected),
+            (&mut theme.connecting, custom.connecting),
+            (&mut theme.reconnecting, custom.reconnecting),
+            (&mut theme.status_text, custom.status_text),
+        ] {
+            if let Some(color) = color {
+                *slot = color
+                    .parse()
+                    .map_err(|_| ConfigError::InvalidTheme(format!("unknown color '{}'", color)))?;
+            }
+        }
+        Ok(theme)
+    }
+}
 
 /// Redisplay the prompt after receiving a message
-pub fn redisplay_prompt(client_id: &str) {
-    print!("{}> ", client_id);
+///
+/// Nothing is printed in accessible mode, where a redrawn prompt is only noise for screen readers.
+pub fn redisplay_prompt(prompt: &Prompt, mode: OutputMode) {
+    if mode == OutputMode::Accessible {
+        return;
+    }
+    print!("{}", prompt.text());
+    std::io::stdout().flush().ok();
+}
+
+/// Ring the terminal bell to alert the user of a mention
+pub fn ring_bell() {
+    print!("\x07");
     std::io::stdout().flush().ok();
 }
+
+/// Connection state shown in the status bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    /// Opening a connection to the server
+    Connecting,
+    /// Joined the room
+    Connected,
+    /// Waiting before the next connection attempt
+    Reconnecting {
+        delay: Duration,
+        retry: u32,
+        max_retries: u32,
+    },
+}
+
+/// What the terminal UI is told to show
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ScreenEvent {
+    /// Chat message of a participant (including this client's own)
+    Chat {
+        from: String,
+        content: String,
+        sent_at: i64,
+        /// Sequence number in the room, to jump back to the message
+        seq: Option<u64>,
+    },
+    /// Scroll the message pane back to the message with the sequence number
+    Jump(u64),
+    /// Any other event, as one labeled line
+    Notice(String),
+    /// Everyone in the room when joining it
+    Participants(Vec<String>),
+    Joined(String),
+    Left(String),
+    /// Activity a participant set (`None` when cleared)
+    Activity {
+        client_id: String,
+        activity: Option<String>,
+    },
+    /// Display name a participant set (`None` when cleared)
+    Renamed {
+        client_id: String,
+        display_name: Option<String>,
+    },
+    /// Participants currently typing
+    Typing(Vec<String>),
+    Status(ConnectionStatus),
+    Bell,
+    /// Restore the terminal, printing the reason the client stops if any
+    Close(Option<String>),
+}
+
+/// Where the session shows what happens in the room
+#[derive(Clone)]
+pub enum Screen {
+    /// Printed to stdout, followed by the prompt
+    Plain { prompt: Prompt, mode: OutputMode },
+    /// Sent to the terminal UI thread
+    Tui(TuiHandle),
+}
+
+/// Handle of the terminal UI thread
+#[derive(Clone)]
+pub struct TuiHandle {
+    events: std_mpsc::Sender<ScreenEvent>,
+    thread: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl Screen {
+    /// Print events to stdout in the given output mode, redisplaying `prompt` after each
+    pub fn plain(prompt: Prompt, mode: OutputMode) -> Self {
+        Self::Plain { prompt, mode }
+    }
+
+    /// Formatter of the notices (the terminal UI shows one labeled line per event)
+    pub fn formatter(&self) -> MessageFormatter {
+        match self {
+            Self::Plain { mode, .. } => MessageFormatter::new(*mode),
+            Self::Tui(_) => MessageFormatter::new(OutputMode::Accessible),
+        }
+    }
+
+    /// Show formatted text without redisplaying the prompt
+    pub fn print(&self, text: &str) {
+        match self {
+            Self::Plain { .. } => print!("{}", text),
+            Self::Tui(tui) => {
+                for line in text
+                    .lines()
+                    .map(str::trim_end)
+                    .filter(|line| !line.is_empty())
+                {
+                    tui.send(ScreenEvent::Notice(line.to_string()));
+                }
+            }
+        }
+    }
+
+    /// Redisplay the prompt after printing (the terminal UI always shows its input line)
+    pub fn redisplay_prompt(&self) {
+        if let Self::Plain { prompt, mode } = self {
+            redisplay_prompt(prompt, *mode);
+        }
+    }
+
+    /// Show formatted text and redisplay the prompt
+    pub fn show(&self, text: &str) {
+        self.print(text);
+        self.redisplay_prompt();
+    }
+
+    /// Show a chat message (or a poll) received from the room
+    pub fn chat(&self, message: &ChatMessage) {
+        if let Self::Plain { prompt, .. } = self {
+            prompt.message_received();
+        }
+        if let Some(poll) = &message.poll {
+            return self.show(&self.formatter().format_poll(message, poll));
+        }
+        if let Some(origin) = &message.forwarded_from {
+            match self {
+                Self::Plain { .. } => {
+                    return self.show(&self.formatter().format_forwarded_chat(message, origin));
+                }
+                Self::Tui(tui) => tui.send(ScreenEvent::Notice(
+                    self.formatter().format_forwarded_from(origin),
+                )),
+            }
+        }
+        match self {
+            Self::Plain { .. } => self.show(&self.formatter().format_chat_message(
+                &message.client_id,
+                &message.content,
+                message.timestamp,
+            )),
+            Self::Tui(tui) => tui.send(ScreenEvent::Chat {
+                from: message.client_id.clone(),
+                content: message.content.clone(),
+                sent_at: message.timestamp,
+                seq: message.seq,
+            }),
+        }
+    }
+
+    /// Scroll back to the message with the sequence number (only the terminal UI keeps a
+    /// scrollback to jump in)
+    pub fn jump(&self, seq: u64) {
+        match self {
+            Self::Plain { .. } => self.show(&self.formatter().format_jump_unavailable()),
+            Self::Tui(tui) => tui.send(ScreenEvent::Jump(seq)),
+        }
+    }
+
+    /// Set the room shown in the prompt
+    pub fn set_room(&self, room: &str) {
+        if let Self::Plain { prompt, .. } = self {
+            prompt.set_room(room);
+        }
+    }
+
+    /// Show the latest messages of the room sent when joining
+    pub fn history(&self, messages: &[ChatMessage]) {
+        match self {
+            Self::Plain { .. } => self.show(&self.formatter().format_room_history(messages)),
+            Self::Tui(_) => messages.iter().for_each(|message| self.chat(message)),
+        }
+    }
+
+    /// Show the participants of the room this client has just joined
+    pub fn room_connected(&self, participants: &[ParticipantInfo], client_id: &str) {
+        match self {
+            Self::Plain { .. } => self.show(
+                &self
+                    .formatter()
+                    .format_room_connected(participants, client_id),
+            ),
+            Self::Tui(tui) => {
+                tui.send(ScreenEvent::Participants(
+                    participants
+                        .iter()
+                        .map(|participant| participant.client_id.clone())
+                        .collect(),
+                ));
+                for participant in participants {
+                    if let Some(display_name) = &participant.display_name {
+                        tui.send(ScreenEvent::Renamed {
+                            client_id: participant.client_id.clone(),
+                            display_name: Some(display_name.clone()),
+                        });
+                    }
+                    if let Some(activity) = &participant.activity {
+                        tui.send(ScreenEvent::Activity {
+                            client_id: participant.client_id.clone(),
+                            activity: Some(activity.clone()),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Show the notice of a participant setting or clearing its activity and update the sidebar
+    pub fn activity(&self, client_id: &str, activity: Option<&str>, notice: &str) {
+        if let Self::Tui(tui) = self {
+            tui.send(ScreenEvent::Activity {
+                client_id: client_id.to_string(),
+                activity: activity.map(str::to_string),
+            });
+        }
+        self.show(notice);
+    }
+
+    /// Show the notice of a participant setting or clearing its display name and update the
+    /// sidebar
+    pub fn renamed(&self, client_id: &str, display_name: Option<&str>, notice: &str) {
+        if let Self::Tui(tui) = self {
+            tui.send(ScreenEvent::Renamed {
+                client_id: client_id.to_string(),
+                display_name: display_name.map(str::to_string),
+            });
+        }
+        self.show(notice);
+    }
+
+    /// Show the notice of a participant joining and add it to the participants
+    pub fn joined(&self, client_id: &str, display_name: Option<&str>, notice: &str) {
+        if let Self::Tui(tui) = self {
+            tui.send(ScreenEvent::Joined(client_id.to_string()));
+            if let Some(display_name) = display_name {
+                tui.send(ScreenEvent::Renamed {
+                    client_id: client_id.to_string(),
+                    display_name: Some(display_name.to_string()),
+                });
+            }
+        }
+        self.show(notice);
+    }
+
+    /// Show the notice of a participant leaving and remove it from the participants
+    pub fn left(&self, client_id: &str, notice: &str) {
+        if let Self::Tui(tui) = self {
+            tui.send(ScreenEvent::Left(client_id.to_string()));
+        }
+        self.show(notice);
+    }
+
+    /// Show who is typing (printed only when someone `started` in plain mode)
+    pub fn typing(&self, client_ids: &[String], started: bool) {
+        match self {
+            Self::Plain { .. } if started => self.show(&self.formatter().format_typing(client_ids)),
+            Self::Plain { .. } => {}
+            Self::Tui(tui) => tui.send(ScreenEvent::Typing(client_ids.to_vec())),
+        }
+    }
+
+    /// Confirm a chat message sent by this client (the server does not echo it back)
+    pub fn sent(&self, message: &ChatMessage) {
+        match self {
+            Self::Plain { .. } => {
+                self.show(&self.formatter().format_sent_confirmation(message.timestamp))
+            }
+            Self::Tui(_) => self.chat(message),
+        }
+    }
+
+    /// Show the state of the connection in the status bar
+    pub fn status(&self, status: ConnectionStatus) {
+        if let Self::Tui(tui) = self {
+            tui.send(ScreenEvent::Status(status));
+        }
+    }
+
+    /// Ring the terminal bell to alert the user of a mention
+    pub fn bell(&self) {
+        match self {
+            Self::Plain { .. } => ring_bell(),
+            Self::Tui(tui) => tui.send(ScreenEvent::Bell),
+        }
+    }
+
+    /// Restore the terminal before the client stops, printing `reason` below the shell prompt
+    ///
+    /// Does nothing in plain mode, where the reason has already been logged.
+    pub fn close(&self, reason: Option<&str>) {
+        let Self::Tui(tui) = self else { return };
+        tui.send(ScreenEvent::Close(reason.map(str::to_string)));
+        if let Some(thread) = tui.thread.lock().unwrap().take() {
+            thread.join().ok();
+        }
+    }
+}
+
+impl TuiHandle {
+    fn send(&self, event: ScreenEvent) {
+        // The UI thread is gone once the user quit; the session ends right after
+        let _ = self.events.send(event);
+    }
+}
+
+/// Start the terminal UI on its own thread
+///
+/// Returns the screen to show the session on and the lines typed in the input line. Ctrl+C or
+/// Ctrl+D restores the terminal and closes the lines, which ends the session like the end of
+/// stdin does in plain mode.
+///
+/// # Arguments
+///
+/// * `client_id` - ID of this client, marked in the participant sidebar
+/// * `latency` - Round trip to the server to show in the status bar, if enabled
+pub fn start_tui(
+    client_id: &str,
+    latency: Option<Arc<Mutex<LatencyMeter>>>,
+    theme: Theme,
+) -> std::io::Result<(Screen, mpsc::UnboundedReceiver<String>)> {
+    let terminal = ratatui::try_init()?;
+    let (events_tx, events) = std_mpsc::channel();
+    let (lines_tx, lines) = mpsc::unbounded_channel();
+    let state = TuiState::new(client_id, theme);
+    let thread = std::thread::spawn(move || run_tui(terminal, state, events, lines_tx, latency));
+    let screen = Screen::Tui(TuiHandle {
+        events: events_tx,
+        thread: Arc::new(Mutex::new(Some(thread))),
+    });
+    Ok((screen, lines))
+}
+
+/// Draw the terminal UI and handle keys until the user quits or the screen is closed
+fn run_tui(
+    mut terminal: DefaultTerminal,
+    mut state: TuiState,
+    events: std_mpsc::Receiver<ScreenEvent>,
+    lines: mpsc::UnboundedSender<String>,
+    latency: Option<Arc<Mutex<LatencyMeter>>>,
+) {
+    let reason = 'run: loop {
+        // Apply what happened in the session since the last frame
+        loop {
+            match events.try_recv() {
+                Ok(ScreenEvent::Close(reason)) => break 'run reason,
+                Ok(ScreenEvent::Bell) => ring_bell(),
+                Ok(event) => state.apply(event),
+                Err(std_mpsc::TryRecvError::Empty) => break,
+                Err(std_mpsc::TryRecvError::Disconnected) => break 'run None,
+            }
+        }
+
+        let rtt = latency
+            .as_ref()
+            .and_then(|latency| latency.lock().unwrap().latest());
+        if let Err(e) = terminal.draw(|frame| state.draw(frame, rtt)) {
+            break format!("Failed to draw the terminal: {}", e).into();
+        }
+
+        match event::poll(FRAME_INTERVAL).and_then(|ready| ready.then(event::read).transpose()) {
+            Ok(Some(Event::Key(key))) => match state.on_key(key) {
+                KeyAction::Send(line) => {
+                    if lines.send(line).is_err() {
+                        break None;
+                    }
+                }
+                KeyAction::Quit => break None,
+                KeyAction::None => {}
+            },
+            Ok(_) => {}
+            Err(e) => break format!("Failed to read the terminal: {}", e).into(),
+        }
+    };
+
+    ratatui::restore();
+    if let Some(reason) = reason {
+        eprintln!("{}", reason);
+    }
+    // Closing the lines ends the session
+    drop(lines);
+}
+
+/// What a key press asks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum KeyAction {
+    None,
+    /// Send the typed line
+    Send(String),
+    /// Quit the client
+    Quit,
+}
+
+/// State of the terminal UI
+struct TuiState {
+    client_id: String,
+    theme: Theme,
+    /// Lines of the message pane, oldest first
+    messages: Vec<Line<'static>>,
+    /// Index in `messages` of the line of each chat message, by sequence number
+    message_lines: HashMap<u64, usize>,
+    /// Line to scroll to on the next frame (wrapping depends on the width of the pane)
+    jump_to: Option<usize>,
+    /// Line of the message jumped to, highlighted until the next line is sent
+    highlighted: Option<usize>,
+    /// Participants in the room, sorted
+    participants: Vec<String>,
+    /// Display names of the participants that set one
+    display_names: HashMap<String, String>,
+    /// Activities of the participants that set one
+    activities: HashMap<String, String>,
+    /// Participants currently typing
+    typing: Vec<String>,
+    status: ConnectionStatus,
+    /// Line being typed and the byte offset of the cursor in it
+    input: String,
+    cursor: usize,
+    /// Lines the message pane is scrolled up from the latest message
+    scroll: usize,
+}
+
+impl TuiState {
+    fn new(client_id: &str, theme: Theme) -> Self {
+        Self {
+            client_id: client_id.to_string(),
+            theme,
+            messages: Vec::new(),
+            message_lines: HashMap::new(),
+            jump_to: None,
+            highlighted: None,
+            participants: Vec::new(),
+            display_names: HashMap::new(),
+            activities: HashMap::new(),
+            typing: Vec::new(),
+            status: ConnectionStatus::Connecting,
+            input: String::new(),
+            cursor: 0,
+            scroll: 0,
+        }
+    }
+
+    /// Update the state with an event of the session
+    fn apply(&mut self, event: ScreenEvent) {
+        match event {
+            ScreenEvent::Chat {
+                from,
+                content,
+                sent_at,
+                seq,
+            } => {
+                if let Some(seq) = seq {
+                    self.message_lines.insert(seq, self.messages.len());
+                }
+                let sender = if from == self.client_id {
+                    self.theme.own_name
+                } else {
+                    self.theme.sender
+                };
+                let sender = Style::new().fg(sender).add_modifier(Modifier::BOLD);
+                self.push(Line::from(vec![
+                    Span::styled(
+                        format!("{} ", timestamp_to_jst_clock(sent_at)),
+                        Style::new().fg(self.theme.muted),
+                    ),
+                    Span::styled(from, sender),
+                    Span::raw(": "),
+                    Span::raw(content),
+                ]));
+            }
+            ScreenEvent::Jump(seq) => match self.message_lines.get(&seq) {
+                Some(&index) => {
+                    self.jump_to = Some(index);
+                    self.highlighted = Some(index);
+                }
+                None => self.apply(ScreenEvent::Notice(format!(
+                    "Message {} is not in the scrollback",
+                    seq
+                ))),
+            },
+            ScreenEvent::Notice(text) => {
+                self.push(Line::styled(
+                    text,
+                    Style::new()
+                        .fg(self.theme.notice)
+                        .add_modifier(Modifier::ITALIC),
+                ));
+            }
+            ScreenEvent::Participants(mut participants) => {
+                participants.sort();
+                self.participants = participants;
+                self.display_names.clear();
+                self.activities.clear();
+            }
+            ScreenEvent::Joined(client_id) => {
+                if let Err(index) = self.participants.binary_search(&client_id) {
+                    self.participants.insert(index, client_id);
+                }
+            }
+            ScreenEvent::Left(client_id) => {
+                self.participants
+                    .retain(|participant| participant != &client_id);
+                self.typing.retain(|participant| participant != &client_id);
+                self.display_names.remove(&client_id);
+                self.activities.remove(&client_id);
+            }
+            ScreenEvent::Activity {
+                client_id,
+                activity,
+            } => match activity {
+                Some(activity) => {
+                    self.activities.insert(client_id, activity);
+                }
+                None => {
+                    self.activities.remove(&client_id);
+                }
+            },
+            ScreenEvent::Renamed {
+                client_id,
+                display_name,
+            } => match display_name {
+                Some(display_name) => {
+                    self.display_names.insert(client_id, display_name);
+                }
+                None => {
+                    self.display_names.remove(&client_id);
+                }
+            },
+            ScreenEvent::Typing(typing) => self.typing = typing,
+            ScreenEvent::Status(status) => {
+                // The participants of the room are sent again once reconnected
+                if status != ConnectionStatus::Connected {
+                    self.participants.clear();
+                    self.display_names.clear();
+                    self.activities.clear();
+                    self.typing.clear();
+                }
+                self.status = status;
+            }
+            ScreenEvent::Bell | ScreenEvent::Close(_) => {}
+        }
+    }
+
+    /// Add a line to the message pane, keeping the scrolled position if scrolled up
+    fn push(&mut self, line: Line<'static>) {
+        self.messages.push(line);
+        if self.scroll > 0 {
+            self.scroll += 1;
+        }
+    }
+
+    /// Edit the input line, scroll the message pane or quit
+    fn on_key(&mut self, key: KeyEvent) -> KeyAction {
+        if key.kind != KeyEventKind::Press {
+            return KeyAction::None;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c' | 'd') if ctrl => return KeyAction::Quit,
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.input.len(),
+            KeyCode::Char('u') if ctrl => {
+                self.input.drain(..self.cursor);
+                self.cursor = 0;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.input.insert(self.cursor, c);
+                self.cursor += c.len_utf8();
+            }
+            KeyCode::Enter => {
+                let line = self.input.trim().to_string();
+                self.input.clear();
+                self.cursor = 0;
+                if !line.is_empty() {
+                    self.scroll = 0;
+                    self.highlighted = None;
+                    return KeyAction::Send(line);
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(previous) = self.previous_boundary() {
+                    self.input.remove(previous);
+                    self.cursor = previous;
+                }
+            }
+            KeyCode::Delete if self.cursor < self.input.len() => {
+                self.input.remove(self.cursor);
+            }
+            KeyCode::Left => self.cursor = self.previous_boundary().unwrap_or(0),
+            KeyCode::Right => {
+                if let Some(c) = self.input[self.cursor..].chars().next() {
+                    self.cursor += c.len_utf8();
+                }
+            }
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.input.len(),
+            KeyCode::Up => self.scroll += 1,
+            KeyCode::Down => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageUp => self.scroll += PAGE_SCROLL,
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(PAGE_SCROLL),
+            _ => {}
+        }
+        KeyAction::None
+    }
+
+    /// Byte offset of the character before the cursor
+    fn previous_boundary(&self) -> Option<usize> {
+        self.input[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map(|(index, _)| index)
+    }
+
+    /// Draw the panes (`rtt` is the latest round trip to the server, if measured)
+    fn draw(&mut self, frame: &mut Frame, rtt: Option<Duration>) {
+        let [main, input, status] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [messages, sidebar] =
+            Layout::horizontal([Constraint::Min(20), Constraint::Length(SIDEBAR_WIDTH)])
+                .areas(main);
+
+        self.draw_messages(frame, messages);
+        self.draw_participants(frame, sidebar);
+        self.draw_input(frame, input);
+        frame.render_widget(self.status_line(rtt), status);
+    }
+
+    fn draw_messages(&mut self, frame: &mut Frame, area: Rect) {
+        let mut lines = self.messages.clone();
+        if let Some(line) = self.highlighted.and_then(|index| lines.get_mut(index)) {
+            line.style = line.style.add_modifier(Modifier::REVERSED);
+        }
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+        // Scrolling counts the lines after wrapping to the width of the pane
+        let width = area.width.saturating_sub(2);
+        let height = usize::from(area.height.saturating_sub(2));
+        let total = paragraph.line_count(width);
+        let bottom = total.saturating_sub(height);
+        // Show the message jumped to a third of the way down, below the lines before it
+        if let Some(index) = self.jump_to.take() {
+            let before = Paragraph::new(self.messages[..index].to_vec())
+                .wrap(Wrap { trim: false })
+                .line_count(width);
+            self.scroll = bottom.saturating_sub(before.saturating_sub(height / 3));
+        }
+        self.scroll = self.scroll.min(bottom);
+        let title = match self.scroll {
+            0 => " Messages ".to_string(),
+            scroll => format!(" Messages (scrolled up {} lines) ", scroll),
+        };
+        let offset = u16::try_from(bottom - self.scroll).unwrap_or(u16::MAX);
+        frame.render_widget(
+            paragraph
+                .block(Block::bordered().title(title))
+                .scroll((offset, 0)),
+            area,
+        );
+    }
+
+    fn draw_participants(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .participants
+            .iter()
+            .map(|participant| {
+                let name = match self.display_names.get(participant) {
+                    Some(display_name) => format!("{} ({})", display_name, participant),
+                    None => participant.clone(),
+                };
+                let (name, style) = if participant == &self.client_id {
+                    (
+                        format!("{} (you)", name),
+                        Style::new().fg(self.theme.own_name),
+                    )
+                } else {
+                    (name, Style::new())
+                };
+                let mut text = Text::from(Line::styled(name, style));
+                if let Some(activity) = self.activities.get(participant) {
+                    text.push_line(Line::styled(
+                        format!("  {}", activity),
+                        Style::new()
+                            .fg(self.theme.muted)
+                            .add_modifier(Modifier::ITALIC),
+                    ));
+                }
+                ListItem::new(text)
+            })
+            .collect();
+        let title = format!(" Participants ({}) ", self.participants.len());
+        frame.render_widget(List::new(items).block(Block::bordered().title(title)), area);
+    }
+
+    fn draw_input(&self, frame: &mut Frame, area: Rect) {
+        let width = usize::from(area.width.saturating_sub(2));
+        let before_cursor = Span::raw(&self.input[..self.cursor]).width();
+        // Scroll the line horizontally to keep the cursor in view
+        let offset = (before_cursor + 1).saturating_sub(width);
+        let offset = u16::try_from(offset).unwrap_or(u16::MAX);
+        frame.render_widget(
+            Paragraph::new(self.input.as_str())
+                .block(Block::bordered().title(" Message (Enter to send, Ctrl+C to quit) "))
+                .scroll((0, offset)),
+            area,
+        );
+        let x = u16::try_from(before_cursor).unwrap_or(u16::MAX) - offset;
+        frame.set_cursor_position(Position::new(area.x + 1 + x, area.y + 1));
+    }
+
+    /// Connection state, client ID, latency and who is typing
+    fn status_line(&self, rtt: Option<Duration>) -> Line<'static> {
+        let (state, color) = match self.status {
+            ConnectionStatus::Connecting => ("Connecting...".to_string(), self.theme.connecting),
+            ConnectionStatus::Connected => ("Connected".to_string(), self.theme.connected),
+            ConnectionStatus::Reconnecting {
+                delay,
+                retry,
+                max_retries,
+            } => (
+                format!(
+                    "Reconnecting in {:.1}s (retry {}/{})",
+                    delay.as_secs_f64(),
+                    retry,
+                    max_retries
+                ),
+                self.theme.reconnecting,
+            ),
+        };
+        let mut spans = vec![
+            Span::styled(
+                format!(" {} ", state),
+                Style::new().fg(self.theme.status_text).bg(color),
+            ),
+            Span::raw(format!(" {}", self.client_id)),
+        ];
+        if let Some(rtt) = rtt {
+            spans.push(Span::raw(format!(" | {}ms", rtt.as_millis())));
+        }
+        let typing = match self.typing.as_slice() {
+            [] => None,
+            [only] => Some(format!("{} is typing...", only)),
+            [rest @ .., last] => Some(format!("{} and {} are typing...", rest.join(", "), last)),
+        };
+        if let Some(typing) = typing {
+            spans.push(Span::styled(
+                format!(" | {}", typing),
+                Style::new().add_modifier(Modifier::ITALIC),
+            ));
+        }
+        Line::from(spans)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    /// Render the state on a terminal of the given size and return its lines
+    fn render(state: &mut TuiState, width: u16, height: u16) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|frame| state.draw(frame, Some(Duration::from_millis(42))))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_tui_shows_messages_participants_and_status() {
+        // テスト項目: メッセージ欄にチャットと通知が、サイドバーに参加者と表示名とアクティビティが、ステータスバーに接続状態が表示される
+        // given (前提条件):
+        let mut state = TuiState::new("alice", Theme::DARK);
+        for event in [
+            ScreenEvent::Status(ConnectionStatus::Connected),
+            ScreenEvent::Participants(vec!["bob".to_string(), "alice".to_string()]),
+            ScreenEvent::Joined("carol".to_string()),
+            ScreenEvent::Activity {
+                client_id: "carol".to_string(),
+                activity: Some("reviewing PR #42".to_string()),
+            },
+            ScreenEvent::Renamed {
+                client_id: "carol".to_string(),
+                display_name: Some("Carol".to_string()),
+            },
+            ScreenEvent::Chat {
+                from: "bob".to_string(),
+                content: "hello".to_string(),
+                sent_at: 1672498800000,
+                seq: Some(1),
+            },
+            ScreenEvent::Notice("Left: bob at 00:00".to_string()),
+            ScreenEvent::Left("bob".to_string()),
+            ScreenEvent::Typing(vec!["carol".to_string()]),
+        ] {
+            state.apply(event);
+        }
+
+        // when (操作):
+        let lines = render(&mut state, 60, 12);
+
+        // then (期待する結果):
+        assert_eq!(state.participants, vec!["alice", "carol"]);
+        assert!(lines[1].contains("00:00 bob: hello"), "{:#?}", lines);
+        assert!(lines[2].contains("Left: bob at 00:00"), "{:#?}", lines);
+        assert!(lines[0].contains("Participants (2)"), "{:#?}", lines);
+        assert!(lines[1].contains("alice (you)"), "{:#?}", lines);
+        assert!(lines[2].contains("Carol (carol)"), "{:#?}", lines);
+        assert!(lines[3].contains("  reviewing PR #42"), "{:#?}", lines);
+        assert!(
+            lines[11].starts_with(" Connected  alice | 42ms | carol is typing..."),
+            "{:#?}",
+            lines
+        );
+    }
+
+    #[test]
+    fn test_tui_input_line_editing_and_scrolling() {
+        // テスト項目: 入力行で文字の挿入・削除・カーソル移動ができ、Enter で送信、Ctrl+C で終了、PageUp で遡れる
+        // given (前提条件):
+        let mut state = TuiState::new("alice", Theme::DARK);
+        for seq in 0..30 {
+            state.apply(ScreenEvent::Notice(format!("notice {}", seq)));
+        }
+
+        // when (操作):
+        for code in [
+            KeyCode::Char('h'),
+            KeyCode::Char('é'),
+            KeyCode::Char('o'),
+            KeyCode::Left,
+            KeyCode::Backspace,
+            KeyCode::Char('i'),
+            KeyCode::End,
+            KeyCode::Char('!'),
+        ] {
+            assert_eq!(state.on_key(key(code)), KeyAction::None);
+        }
+        let sent = state.on_key(key(KeyCode::Enter));
+        let blank = state.on_key(key(KeyCode::Enter));
+        state.on_key(key(KeyCode::PageUp));
+        let scrolled = render(&mut state, 60, 12);
+        let quit = state.on_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+
+        // then (期待する結果):
+        assert_eq!(sent, KeyAction::Send("hio!".to_string()));
+        assert_eq!(blank, KeyAction::None);
+        assert_eq!(quit, KeyAction::Quit);
+        assert!(
+            scrolled[0].contains("scrolled up 10 lines"),
+            "{:#?}",
+            scrolled
+        );
+        assert!(scrolled[6].contains("notice 19"), "{:#?}", scrolled);
+        assert_eq!("tui".parse(), Ok(UiMode::Tui));
+        assert_eq!("plain".parse(), Ok(UiMode::Plain));
+        assert!("fancy".parse::<UiMode>().is_err());
+    }
+
+    #[test]
+    fn test_tui_jumps_to_message_in_scrollback() {
+        // テスト項目: メッセージ番号に移動するとメッセージ欄がそのメッセージまで遡り、スクロールバックに無い番号は通知される
+        // given (前提条件):
+        let mut state = TuiState::new("alice", Theme::DARK);
+        for seq in 1..=30 {
+            state.apply(ScreenEvent::Chat {
+                from: "bob".to_string(),
+                content: format!("message {}", seq),
+                sent_at: 1672498800000,
+                seq: Some(seq),
+            });
+        }
+
+        // when (操作):
+        state.apply(ScreenEvent::Jump(5));
+        let jumped = render(&mut state, 80, 12);
+        state.apply(ScreenEvent::Jump(99));
+        let missing = render(&mut state, 80, 12);
+
+        // then (期待する結果):
+        assert!(jumped[0].contains("scrolled up"), "{:#?}", jumped);
+        assert!(jumped[1].contains("bob: message 3"), "{:#?}", jumped);
+        assert!(jumped[3].contains("bob: message 5"), "{:#?}", jumped);
+        assert!(
+            state.messages[30]
+                .to_string()
+                .contains("Message 99 is not in the scrollback")
+        );
+        assert!(missing[3].contains("bob: message 5"), "{:#?}", missing);
+    }
+}
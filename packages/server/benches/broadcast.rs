@@ -0,0 +1,227 @@
+//! Benchmarks for pushing messages to many WebSocket connections.
+//!
+//! Run with:
+//! ```not_rust
+//! cargo bench -p engawa-server --bench broadcast
+//! ```
+//!
+//! Simulated clients are registered with the `WebSocketMessagePusher` like real connections,
+//! and a task per client drains its queue in place of the writer of the connection.
+//! `contended_push` sends from many tasks at once while clients connect and disconnect;
+//! with a single shard the client registry behaves like one mutex over the whole map. The
+//! difference only shows with several worker threads, i.e. on a machine with several cores.
+//!
+//! `broadcast_channel` compares delivering to every client through the pusher, once to clients
+//! still on their send queues (`pusher_queues`) and once to clients subscribed to the room feed
+//! (`pusher_feed`, how connections receive broadcasts; see
+//! `infrastructure/message_pusher/websocket.rs`), with a bare `tokio::sync::broadcast` channel.
+//! Each iteration waits until every receiver has seen the message, so they can be compared
+//! end to end; receivers that fall more than `CHANNEL_CAPACITY` behind skip the lost messages
+//! (`RecvError::Lagged`).
+
+use std::sync::Arc;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use engawa_server::{
+    domain::{ClientId, MessagePusher},
+    infrastructure::message_pusher::{
+        ClientChannels, DEFAULT_CLIENT_SHARDS, WebSocketMessagePusher,
+    },
+};
+use tokio::{
+    runtime::Runtime,
+    sync::{Barrier, broadcast},
+};
+
+/// Simulated clients connected to the room
+const CLIENTS: usize = 1000;
+
+/// Sizes of the broadcast messages in bytes
+const MESSAGE_SIZES: [usize; 2] = [64, 4096];
+
+/// Tasks sending at the same time in `contended_push`
+const SENDERS: usize = 8;
+
+/// Messages each task sends per iteration in `contended_push`
+const MESSAGES_PER_SENDER: usize = 200;
+
+/// Capacity of the room channel in `broadcast_channel`
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Register `CLIENTS` clients and return the pusher and their IDs
+fn connect_clients(
+    runtime: &Runtime,
+    shards: usize,
+) -> (Arc<WebSocketMessagePusher>, Vec<ClientId>) {
+    runtime.block_on(async {
+        let pusher = Arc::new(WebSocketMessagePusher::new(ClientChannels::with_shards(
+            shards,
+        )));
+        let mut targets = Vec::with_capacity(CLIENTS);
+        for i in 0..CLIENTS {
+            let client_id = ClientId::new(format!("client-{}", i)).unwrap();
+            let (sender, mut receiver) = WebSocketMessagePusher::channel();
+            pusher.register_client(client_id.clone(), sender).await;
+            tokio::spawn(async move { while receiver.recv().await.is_some() {} });
+            targets.push(client_id);
+        }
+        (pusher, targets)
+    })
+}
+
+/// Register `CLIENTS` pusher clients that report each message to `delivered`
+///
+/// With `subscribe`, the clients receive broadcasts from the room feed instead of their queues.
+fn connect_counted_clients(
+    runtime: &Runtime,
+    delivered: Arc<Barrier>,
+    subscribe: bool,
+) -> Arc<WebSocketMessagePusher> {
+    runtime.block_on(async {
+        let pusher = Arc::new(WebSocketMessagePusher::new(ClientChannels::default()));
+        for i in 0..CLIENTS {
+            let client_id = ClientId::new(format!("client-{}", i)).unwrap();
+            let (sender, mut receiver) = WebSocketMessagePusher::channel();
+            pusher
+                .register_client(client_id.clone(), sender.clone())
+                .await;
+            let delivered = delivered.clone();
+            let feed = match subscribe {
+                true => pusher.subscribe(&client_id, &sender).await,
+                false => None,
+            };
+            let Some(mut feed) = feed else {
+                tokio::spawn(async move {
+                    while receiver.recv().await.is_some() {
+                        delivered.wait().await;
+                    }
+                });
+                continue;
+            };
+            tokio::spawn(async move {
+                loop {
+                    match feed.receiver.recv().await {
+                        Ok(msg) if feed.skips(&msg) => {}
+                        Ok(_) => {
+                            delivered.wait().await;
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+        }
+        pusher
+    })
+}
+
+/// Subscribe `CLIENTS` receivers to a room channel that report each message to `delivered`
+fn subscribe_clients(runtime: &Runtime, delivered: Arc<Barrier>) -> broadcast::Sender<Arc<str>> {
+    runtime.block_on(async {
+        let (room, _) = broadcast::channel::<Arc<str>>(CHANNEL_CAPACITY);
+        for _ in 0..CLIENTS {
+            let mut receiver = room.subscribe();
+            let delivered = delivered.clone();
+            tokio::spawn(async move {
+                loop {
+                    match receiver.recv().await {
+                        Ok(_) => {
+                            delivered.wait().await;
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+        }
+        room
+    })
+}
+
+/// Push from `SENDERS` tasks at once while one client reconnects after every message
+async fn contended_push(pusher: Arc<WebSocketMessagePusher>, targets: Arc<Vec<ClientId>>) {
+    let churn = ClientId::new("reconnecting".to_string()).unwrap();
+    let mut tasks = Vec::with_capacity(SENDERS + 1);
+    for sender in 0..SENDERS {
+        let (pusher, targets) = (pusher.clone(), targets.clone());
+        tasks.push(tokio::spawn(async move {
+            for i in 0..MESSAGES_PER_SENDER {
+                let target = &targets[(sender * MESSAGES_PER_SENDER + i) % targets.len()];
+                pusher.push_to(target, r#"{"type":"chat"}"#).await.unwrap();
+            }
+        }));
+    }
+    tasks.push(tokio::spawn(async move {
+        for _ in 0..MESSAGES_PER_SENDER {
+            let (sender, _receiver) = WebSocketMessagePusher::channel();
+            pusher.register_client(churn.clone(), sender).await;
+            pusher.unregister_client(&churn).await;
+        }
+    }));
+    for task in tasks {
+        task.await.unwrap();
+    }
+}
+
+fn bench_broadcast(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    let (pusher, targets) = connect_clients(&runtime, DEFAULT_CLIENT_SHARDS);
+    let mut group = c.benchmark_group("broadcast");
+    group.throughput(Throughput::Elements(CLIENTS as u64));
+    for size in MESSAGE_SIZES {
+        let content = format!(r#"{{"type":"chat","content":"{}"}}"#, "x".repeat(size));
+        group.bench_with_input(
+            BenchmarkId::new(CLIENTS.to_string(), size),
+            &content,
+            |b, content| {
+                b.to_async(&runtime)
+                    .iter(|| pusher.broadcast(targets.clone(), content))
+            },
+        );
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("contended_push");
+    group.throughput(Throughput::Elements((SENDERS * MESSAGES_PER_SENDER) as u64));
+    for shards in [1, DEFAULT_CLIENT_SHARDS] {
+        let (pusher, targets) = connect_clients(&runtime, shards);
+        let targets = Arc::new(targets);
+        group.bench_with_input(BenchmarkId::new("shards", shards), &shards, |b, _| {
+            b.to_async(&runtime)
+                .iter(|| contended_push(pusher.clone(), targets.clone()))
+        });
+    }
+    group.finish();
+
+    // Both sides wait for every client to receive the message, plus the sending task
+    let mut group = c.benchmark_group("broadcast_channel");
+    group.throughput(Throughput::Elements(CLIENTS as u64));
+    let content = r#"{"type":"chat","content":"hello"}"#;
+    let targets: Vec<ClientId> = (0..CLIENTS)
+        .map(|i| ClientId::new(format!("client-{}", i)).unwrap())
+        .collect();
+    for (name, subscribe) in [("pusher_queues", false), ("pusher_feed", true)] {
+        let delivered = Arc::new(Barrier::new(CLIENTS + 1));
+        let pusher = connect_counted_clients(&runtime, delivered.clone(), subscribe);
+        group.bench_function(name, |b| {
+            b.to_async(&runtime).iter(|| async {
+                pusher.broadcast(targets.clone(), content).await.unwrap();
+                delivered.wait().await;
+            })
+        });
+    }
+    let delivered = Arc::new(Barrier::new(CLIENTS + 1));
+    let room = subscribe_clients(&runtime, delivered.clone());
+    let content: Arc<str> = Arc::from(content);
+    group.bench_function("tokio_broadcast", |b| {
+        b.to_async(&runtime).iter(|| async {
+            room.send(content.clone()).unwrap();
+            delivered.wait().await;
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_broadcast);
+criterion_main!(benches);
@@ -0,0 +1,142 @@
+//! Benchmarks comparing the `RoomRepository` backends.
+//!
+//! Run with:
+//! ```not_rust
+//! cargo bench -p engawa-server --bench repository
+//! ```
+//!
+//! Every backend runs the same operations so their costs can be compared directly; the
+//! conformance suite (`infrastructure::repository::conformance`) checks they behave the same.
+
+use std::{path::PathBuf, sync::Arc};
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use engawa_server::{
+    domain::{ClientId, MessageContent, Room, RoomIdFactory, RoomRepository, Timestamp},
+    infrastructure::repository::{InMemoryRoomRepository, WalRoomRepository, WriteAheadLog},
+};
+use tokio::runtime::Runtime;
+
+/// Messages already in the history when reading the room
+const HISTORY_SIZE: usize = 1000;
+
+/// Repository backends under benchmark
+#[derive(Debug, Clone, Copy)]
+enum Backend {
+    InMemory,
+    Wal,
+}
+
+impl Backend {
+    const ALL: [Backend; 2] = [Backend::InMemory, Backend::Wal];
+
+    fn name(self) -> &'static str {
+        match self {
+            Backend::InMemory => "inmemory",
+            Backend::Wal => "wal",
+        }
+    }
+
+    /// Create a repository holding an empty room with unbounded history
+    async fn new_repository(self) -> (Arc<dyn RoomRepository>, Option<PathBuf>) {
+        let room = Room::with_capacity(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(0),
+            usize::MAX,
+            usize::MAX,
+        );
+        match self {
+            Backend::InMemory => (Arc::new(InMemoryRoomRepository::new(room)), None),
+            Backend::Wal => {
+                let path = std::env::temp_dir()
+                    .join(format!("engawa-bench-{}.jsonl", uuid::Uuid::new_v4()));
+                let (wal, room, _) = WriteAheadLog::open(&path, move || room).await.unwrap();
+                let inner = Arc::new(InMemoryRoomRepository::new(room));
+                (
+                    Arc::new(WalRoomRepository::new(inner, Arc::new(wal))),
+                    Some(path),
+                )
+            }
+        }
+    }
+}
+
+fn client_id(name: &str) -> ClientId {
+    ClientId::new(name.to_string()).unwrap()
+}
+
+async fn add_message(repository: &Arc<dyn RoomRepository>) {
+    repository
+        .add_message(
+            client_id("alice"),
+            MessageContent::new("Hello, world!".to_string()).unwrap(),
+            Timestamp::new(1000),
+        )
+        .await
+        .unwrap();
+}
+
+fn bench_repository(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    let mut group = c.benchmark_group("add_message");
+    for backend in Backend::ALL {
+        let (repository, path) = runtime.block_on(backend.new_repository());
+        group.bench_with_input(
+            BenchmarkId::from_parameter(backend.name()),
+            &repository,
+            |b, repository| b.to_async(&runtime).iter(|| add_message(repository)),
+        );
+        if let Some(path) = path {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("get_room");
+    for backend in Backend::ALL {
+        let (repository, path) = runtime.block_on(async {
+            let (repository, path) = backend.new_repository().await;
+            for _ in 0..HISTORY_SIZE {
+                add_message(&repository).await;
+            }
+            (repository, path)
+        });
+        group.bench_with_input(
+            BenchmarkId::new(backend.name(), HISTORY_SIZE),
+            &repository,
+            |b, repository| b.to_async(&runtime).iter(|| repository.get_room()),
+        );
+        if let Some(path) = path {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("join_and_leave");
+    for backend in Backend::ALL {
+        let (repository, path) = runtime.block_on(backend.new_repository());
+        group.bench_with_input(
+            BenchmarkId::from_parameter(backend.name()),
+            &repository,
+            |b, repository| {
+                b.to_async(&runtime).iter(|| async {
+                    let bob = client_id("bob");
+                    repository
+                        .add_participant(bob.clone(), Timestamp::new(1000))
+                        .await
+                        .unwrap();
+                    repository.get_all_connected_client_ids().await;
+                    repository.remove_participant(&bob).await.unwrap();
+                })
+            },
+        );
+        if let Some(path) = path {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_repository);
+criterion_main!(benches);
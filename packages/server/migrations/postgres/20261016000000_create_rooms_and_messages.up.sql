@@ -0,0 +1,14 @@
+-- Rooms and their message history (participants are per-connection and not stored)
+CREATE TABLE rooms (
+    id TEXT PRIMARY KEY,
+    created_at BIGINT NOT NULL
+);
+
+CREATE TABLE messages (
+    room_id TEXT NOT NULL REFERENCES rooms (id) ON DELETE CASCADE,
+    seq BIGINT NOT NULL,
+    client_id TEXT NOT NULL,
+    content TEXT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    PRIMARY KEY (room_id, seq)
+);
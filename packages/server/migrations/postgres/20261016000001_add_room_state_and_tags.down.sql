@@ -0,0 +1,4 @@
+DROP TABLE message_tags;
+ALTER TABLE rooms DROP COLUMN last_seq;
+ALTER TABLE rooms DROP COLUMN class;
+ALTER TABLE rooms DROP COLUMN is_default;
@@ -0,0 +1,12 @@
+-- Room state needed to restore every room (the default room, created rooms and erased sequence numbers)
+ALTER TABLE rooms ADD COLUMN is_default BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE rooms ADD COLUMN class TEXT NOT NULL DEFAULT 'ephemeral';
+ALTER TABLE rooms ADD COLUMN last_seq BIGINT NOT NULL DEFAULT 0;
+
+CREATE TABLE message_tags (
+    room_id TEXT NOT NULL,
+    seq BIGINT NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (room_id, seq, tag),
+    FOREIGN KEY (room_id, seq) REFERENCES messages (room_id, seq) ON DELETE CASCADE
+);
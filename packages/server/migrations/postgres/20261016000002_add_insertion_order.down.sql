@@ -0,0 +1,2 @@
+ALTER TABLE message_tags DROP COLUMN position;
+ALTER TABLE rooms DROP COLUMN position;
@@ -0,0 +1,4 @@
+-- Insertion order of rooms and message tags, used to restore them in order
+-- (SQLite keeps it in the rowid)
+ALTER TABLE rooms ADD COLUMN position BIGSERIAL;
+ALTER TABLE message_tags ADD COLUMN position BIGSERIAL;
@@ -0,0 +1,2 @@
+ALTER TABLE rooms DROP COLUMN message_capacity;
+ALTER TABLE rooms DROP COLUMN participant_capacity;
@@ -0,0 +1,20 @@
+-- Polls attached to messages (the message content is the question) and the votes on them
+CREATE TABLE poll_options (
+    room_id TEXT NOT NULL,
+    seq BIGINT NOT NULL,
+    position INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    PRIMARY KEY (room_id, seq, position),
+    FOREIGN KEY (room_id, seq) REFERENCES messages (room_id, seq) ON DELETE CASCADE
+);
+
+-- Votes are restored in the order the clients first voted (SQLite keeps it in the rowid)
+CREATE TABLE poll_votes (
+    room_id TEXT NOT NULL,
+    seq BIGINT NOT NULL,
+    client_id TEXT NOT NULL,
+    option INTEGER NOT NULL,
+    position BIGSERIAL,
+    PRIMARY KEY (room_id, seq, client_id),
+    FOREIGN KEY (room_id, seq) REFERENCES messages (room_id, seq) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Capacities of created rooms (NULL follows the capacities the server is started with)
+ALTER TABLE rooms ADD COLUMN participant_capacity BIGINT;
+ALTER TABLE rooms ADD COLUMN message_capacity BIGINT;
@@ -0,0 +1 @@
+DROP TABLE forwarded_messages;
@@ -0,0 +1,10 @@
+-- Where messages forwarded from another room were first posted
+CREATE TABLE forwarded_messages (
+    room_id TEXT NOT NULL,
+    seq BIGINT NOT NULL,
+    origin_room_id TEXT NOT NULL,
+    origin_client_id TEXT NOT NULL,
+    origin_timestamp BIGINT NOT NULL,
+    PRIMARY KEY (room_id, seq),
+    FOREIGN KEY (room_id, seq) REFERENCES messages (room_id, seq) ON DELETE CASCADE
+);
@@ -6,42 +6,799 @@
 //! ```not_rust
 //! cargo run --bin server
 //! cargo run --bin server -- --host 0.0.0.0 --port 3000
+//! cargo run --bin server -- --config server.toml
+//! ENGAWA_PORT=3000 cargo run --bin server -- --config server.yaml
+//! cargo run --bin server --features sqlite -- migrate --database-url sqlite://engawa.db
+//! cargo run --bin server --features sqlite -- --storage sqlite --db-path engawa.db
+//! DATABASE_URL=postgres://localhost/engawa cargo run --bin server --features postgres -- --storage postgres
+//! echo 's3cret' | cargo run --bin server -- hash-password
+//! JWT_SECRET=... cargo run --bin server -- --auth-users users.txt
 //! ```
 
-use std::{collections::HashMap, sync::Arc};
+#[cfg(feature = "sqlite")]
+use std::path::Path;
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 
-use clap::Parser;
+use chrono::NaiveTime;
+use clap::{Parser, Subcommand};
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+use engawa_server::infrastructure::migration::{self, MigrationState, MigrationStatus};
+#[cfg(feature = "postgres")]
+use engawa_server::infrastructure::repository::{PostgresRoomRepository, PostgresStore};
+#[cfg(feature = "sqlite")]
+use engawa_server::infrastructure::repository::{SqliteRoomRepository, SqliteStore};
+#[cfg(feature = "tls")]
+use engawa_server::ui::{TlsConfig, TlsTermination};
+#[cfg(feature = "xmpp")]
+use engawa_server::ui::{XmppConfig, XmppGateway};
 use engawa_server::{
-    domain::{Room, RoomIdFactory, Timestamp},
-    infrastructure::{message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository},
-    ui::Server,
+    domain::{
+        BanList, ClientId, DEFAULT_MAX_MESSAGE_LENGTH, DEFAULT_MAX_ROOMS_PER_CLIENT, Locale,
+        MessageFilterChain, MessagePusher, PendingMessageStore, Room, RoomId, RoomIdFactory,
+        RoomRepository, RoomSlug, StarRepository, Timestamp,
+    },
+    infrastructure::{
+        analyzer::KeywordAnalyzer,
+        auth::{AccessTokens, Credentials, DEFAULT_TOKEN_TTL, hash_password},
+        backpressure::DEFAULT_SLOW_DOWN_THRESHOLD,
+        dedup::DEFAULT_DEDUP_WINDOW,
+        message_pusher::{ClientChannels, WebSocketMessagePusher},
+        proof_of_work::{DEFAULT_POW_DIFFICULTY, MAX_POW_DIFFICULTY},
+        repository::{
+            DEFAULT_DB_POOL_SIZE, DEFAULT_PENDING_MESSAGES, InMemoryBanList,
+            InMemoryIntegrationRepository, InMemoryPendingMessageStore, InMemoryRoomRepository,
+            InMemoryStarRepository, RoomRepositoryRouter, WalRoomRepository, WriteAheadLog,
+        },
+        sanitize::SanitizeProfile,
+        wire_log::{self, WireLog},
+    },
+    ui::{
+        Authentication, ClusterConfig, ClusterNode, ConfigFile, ConnectChallenge,
+        DEFAULT_IDLE_TIMEOUT, DEFAULT_PING_INTERVAL, DIGEST_SENDER, DuplicatePolicy, GuestMode,
+        GuestPolicy, Handover, IpNetwork, IpRules, Keepalive, MessageFilterSpec, RoomStorage,
+        SeedProfile, Server, ServerConfig, StorageBackend, TrustedProxies,
+    },
     usecase::{
-        ConnectParticipantUseCase, DisconnectParticipantUseCase, GetRoomDetailUseCase,
-        GetRoomStateUseCase, GetRoomsUseCase, SendMessageUseCase,
+        BroadcastTypingUseCase, CheckHealthUseCase, ComposeDailyDigestUseCase,
+        ConnectParticipantUseCase, CreateRoomUseCase, DEFAULT_HEALTH_CHECK_TIMEOUT,
+        DEFAULT_HISTORY_REPLAY, DEFAULT_MAX_ROOMS, DEFAULT_TYPING_DEBOUNCE,
+        DisconnectParticipantUseCase, EnforceMemoryLimitUseCase, EraseClientDataUseCase,
+        ExportMessagesUseCase, ForwardMessageUseCase, GetMessageHistoryUseCase,
+        GetRoomDetailUseCase, GetRoomMessagesUseCase, GetRoomStateUseCase, GetRoomStatsUseCase,
+        GetRoomsUseCase, JoinRoomUseCase, KickParticipantUseCase, ManageBreakoutsUseCase,
+        ManageIntegrationsUseCase, ModerateMessagesUseCase, RateLimiter, RenameParticipantUseCase,
+        RespondToIncidentUseCase, SearchMessagesUseCase, SeedDemoDataUseCase, SendMessageUseCase,
+        SetActivityUseCase, StarMessagesUseCase, VotePollUseCase,
+    },
+};
+#[cfg(feature = "mqtt")]
+use engawa_server::{
+    infrastructure::message_pusher::MqttMirrorPusher,
+    ui::{MqttBridge, MqttConfig},
+};
+#[cfg(feature = "webhooks")]
+use engawa_server::{
+    infrastructure::message_pusher::{OutgoingWebhookPusher, webhook},
+    ui::OutgoingWebhooks,
+};
+#[cfg(feature = "discord")]
+use engawa_server::{
+    infrastructure::{
+        discord::DiscordClient,
+        message_pusher::{DiscordRelayPusher, discord},
     },
+    ui::{DiscordConfig, DiscordRelay},
+};
+#[cfg(feature = "federation")]
+use engawa_server::{
+    infrastructure::{
+        federation::RemoteParticipants,
+        message_pusher::{FederationPusher, federation},
+    },
+    ui::{Federation, FederationConfig},
+};
+use engawa_shared::{
+    logger::{LogArgs, LogReloader, setup_logger},
+    time::get_jst_timestamp,
 };
-use engawa_shared::{logger::setup_logger, time::get_jst_timestamp};
-use tokio::sync::Mutex;
 
 #[derive(Parser, Debug)]
 #[command(name = "server")]
 #[command(about = "WebSocket chat server with broadcast support", long_about = None)]
 struct Args {
-    /// Host address to bind the server to
-    #[arg(short = 'H', long, default_value = "127.0.0.1")]
-    host: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// TOML or YAML file of settings (host, port, log_level, log_filter, room_capacity,
+    /// message_capacity, messages_per_second, message_burst, storage, db_path); ENGAWA_*
+    /// environment variables (e.g. ENGAWA_PORT) override the file, and options override both.
+    /// SIGHUP reads log_level and log_filter again
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Host address to bind the server to [default: 127.0.0.1]
+    #[arg(short = 'H', long)]
+    host: Option<String>,
+
+    /// Port number to bind the server to [default: 8080]
+    #[arg(short = 'p', long)]
+    port: Option<u16>,
+
+    #[command(flatten)]
+    log: LogArgs,
+
+    /// Proxies (CIDR, comma separated) whose X-Forwarded-For / Forwarded headers are trusted
+    #[arg(long, value_delimiter = ',')]
+    trusted_proxies: Vec<IpNetwork>,
+
+    /// Networks (CIDR, comma separated) allowed to connect; every other network gets 403
+    #[arg(long, value_delimiter = ',')]
+    ip_allow: Vec<IpNetwork>,
+
+    /// Networks (CIDR, comma separated) refused with 403, even if allowed
+    #[arg(long, value_delimiter = ',')]
+    ip_deny: Vec<IpNetwork>,
+
+    /// Secret token enabling the Slack-compatible incoming webhook at /api/v1/hooks/{token}
+    #[arg(long)]
+    incoming_webhook_token: Option<String>,
+
+    /// File of `<client_id>:<password hash>` lines (see `hash-password`); clients then log in
+    /// at /api/v1/auth/login and need the access token for /ws and /api/v1/rooms (JWT_SECRET
+    /// signs the tokens)
+    #[arg(long)]
+    auth_users: Option<PathBuf>,
+
+    /// Number of sequence numbers remembered per connection to skip duplicate deliveries
+    #[arg(long, default_value_t = DEFAULT_DEDUP_WINDOW)]
+    dedup_window: usize,
+
+    /// Milliseconds in which messages to a client are batched into one JSON array frame
+    /// (0 disables batching; clients must accept array frames when enabled)
+    #[arg(long, default_value = "0")]
+    batch_window_ms: u64,
+
+    /// Messages waiting in a client's send queue at which it is sent a `slow-down` advice
+    /// (0 disables the advice)
+    #[arg(long, default_value_t = DEFAULT_SLOW_DOWN_THRESHOLD)]
+    slow_down_threshold: usize,
+
+    /// Seconds between the WebSocket Pings sent to each connection (0 disables the keepalive)
+    #[arg(long, default_value_t = DEFAULT_PING_INTERVAL.as_secs())]
+    ping_interval_secs: u64,
+
+    /// Seconds without any frame from a client after which its connection is closed
+    #[arg(long, default_value_t = DEFAULT_IDLE_TIMEOUT.as_secs())]
+    idle_timeout_secs: u64,
+
+    /// What to do when a client connects with a client_id that is already connected
+    /// ("reject": 409 Conflict, "takeover": close the previous session, "multiplex": keep
+    /// every connection of the client)
+    #[arg(long, default_value = "reject")]
+    duplicate_policy: DuplicatePolicy,
+
+    /// How HTML in received messages is sanitized before storage and broadcast ("none",
+    /// "escape": escape special characters, "strip": remove tags and script/style contents)
+    #[arg(long, default_value = "none")]
+    sanitize_profile: SanitizeProfile,
+
+    /// Whether clients may connect without a client_id and get a guest ID such as guest-7f3a
+    /// ("disabled", "allowed", "read-only": guests cannot post)
+    #[arg(long, default_value = "disabled")]
+    guest_mode: GuestMode,
+
+    /// Cap on the messages a guest may post per minute (requires --guest-mode allowed)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    guest_messages_per_minute: Option<u32>,
+
+    /// Messages a client may send per second on average; sends beyond it get a
+    /// `rate_limited` error instead of being broadcast (unlimited if not given)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    messages_per_second: Option<u32>,
+
+    /// Messages a client may send in a row before --messages-per-second applies
+    /// [default: 10]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    message_burst: Option<u32>,
+
+    /// Require connecting clients to solve a proof-of-work (GET /api/v1/challenge) from
+    /// startup; admins toggle it at /api/v1/admin/challenge with ADMIN_TOKEN
+    #[arg(long)]
+    connect_challenge: bool,
+
+    /// Leading zero bits required of the connect challenge's solution hash
+    #[arg(
+        long,
+        default_value_t = DEFAULT_POW_DIFFICULTY,
+        value_parser = clap::value_parser!(u8).range(1..=i64::from(MAX_POW_DIFFICULTY)),
+    )]
+    connect_challenge_difficulty: u8,
+
+    /// Human-friendly name of the room (lowercase letters, digits and hyphens), usable instead
+    /// of its ID in /api/rooms/by-slug/{slug} and /ws?room_slug=...
+    #[arg(long)]
+    room_slug: Option<RoomSlug>,
+
+    /// Language of the system messages of the room such as join/leave notices ("en", "ja")
+    #[arg(long, default_value = "en")]
+    room_locale: Locale,
+
+    /// Maximum number of participants in the room [default: 10]
+    #[arg(long)]
+    room_capacity: Option<usize>,
+
+    /// Maximum number of messages kept in the room; sends beyond it are refused [default: 100]
+    #[arg(long)]
+    message_capacity: Option<usize>,
+
+    /// Maximum length of a message in bytes; created rooms may set a lower one
+    #[arg(long, default_value_t = DEFAULT_MAX_MESSAGE_LENGTH)]
+    max_message_length: usize,
+
+    /// Number of the latest messages sent to a newly connected client (0 to send none)
+    #[arg(long, default_value_t = DEFAULT_HISTORY_REPLAY)]
+    history_replay: usize,
+
+    /// Maximum number of rooms a client may be in at the same time (429 Too Many Requests
+    /// beyond it)
+    #[arg(long, default_value_t = DEFAULT_MAX_ROOMS_PER_CLIENT)]
+    max_rooms_per_client: usize,
+
+    /// Seconds a disconnected participant's missed messages are kept; reconnecting with the
+    /// same client ID within them delivers the messages [default: not kept]
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    durable_participants: Option<u64>,
+
+    /// Write-ahead log file; room messages are appended before acknowledging sends and
+    /// replayed on startup
+    #[arg(long)]
+    wal: Option<PathBuf>,
+
+    /// File (JSON Lines) recording every WebSocket frame sent and received, for protocol
+    /// debugging; print it with `wire-log dump`
+    #[arg(long)]
+    wire_log: Option<PathBuf>,
+
+    /// JSON fields (comma separated, e.g. content) whose values are redacted in --wire-log;
+    /// text payloads that are not JSON are redacted entirely
+    #[arg(long, value_delimiter = ',')]
+    wire_log_redact: Vec<String>,
+
+    /// Where rooms are stored ("memory": lost on restart, "sqlite": the database at --db-path,
+    /// "postgres": the database at --database-url; restored on startup and requiring the
+    /// feature of the same name) [default: memory]
+    #[arg(long)]
+    storage: Option<StorageBackend>,
+
+    /// SQLite database file of the sqlite storage (created and migrated on startup)
+    #[arg(long)]
+    db_path: Option<PathBuf>,
+
+    /// PostgreSQL database of the postgres storage (postgres://..., migrated on startup);
+    /// defaults to DATABASE_URL
+    #[arg(long)]
+    database_url: Option<String>,
+
+    /// Maximum number of pooled connections to the PostgreSQL database
+    #[arg(long, default_value_t = DEFAULT_DB_POOL_SIZE)]
+    db_pool_size: u32,
+
+    /// Cap (MiB) on the memory held by the room history and client send queues; the oldest
+    /// history is evicted when it is exceeded
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    memory_limit_mb: Option<u64>,
+
+    /// Seed the room at startup ("demo": bot participants and a short message history);
+    /// skipped if the room already has history
+    #[arg(long)]
+    seed: Option<SeedProfile>,
+
+    /// Time of day (JST, HH:MM) to post a digest of the last 24 hours (message count and most
+    /// active participants) to the room; disabled if omitted
+    #[arg(long)]
+    digest_at: Option<NaiveTime>,
+
+    /// Keywords to tag messages with for the room stats (comma separated, case-insensitive);
+    /// tag counts are served at /api/v1/rooms/{room_id}/stats
+    #[arg(long, value_delimiter = ',')]
+    analyze_keywords: Vec<String>,
+
+    /// Filter applied to the messages sent to the rooms, rejecting them with an error to the
+    /// sender: profanity:<words> (comma separated), max-links:<count> or regex:<pattern>;
+    /// repeat to chain filters, applied in the given order
+    #[arg(long = "message-filter")]
+    message_filters: Vec<MessageFilterSpec>,
+
+    /// Storage backend of created rooms per room class, as <class>=<backend> (comma
+    /// separated, e.g. persistent=memory); unlisted classes share the default room's storage
+    #[arg(long, value_delimiter = ',')]
+    room_storage: Vec<RoomStorage>,
+
+    /// Seconds a breakout room is kept without participants or messages before it is archived
+    /// (removed) and its parent room is told
+    #[arg(long, default_value = "900", value_parser = clap::value_parser!(u64).range(1..))]
+    breakout_idle_timeout: u64,
+
+    /// Seconds to wait for connections to close after handing the listener over (SIGUSR2)
+    #[arg(long, default_value = "30")]
+    drain_timeout: u64,
+
+    /// Milliseconds over which clients' reconnections are spread after a handover
+    #[arg(long, default_value = "5000")]
+    reconnect_stagger_ms: u64,
+
+    /// Seconds to wait for connections to receive the shutdown notice and close on shutdown
+    #[arg(long, default_value = "10")]
+    shutdown_timeout: u64,
+
+    /// UDP address to gossip with other cluster nodes on; enables clustering
+    #[arg(long)]
+    cluster_gossip_addr: Option<SocketAddr>,
+
+    /// Gossip address advertised to other nodes (defaults to --cluster-gossip-addr)
+    #[arg(long)]
+    cluster_advertise_addr: Option<SocketAddr>,
+
+    /// Gossip addresses of nodes to join the cluster through (comma separated)
+    #[arg(long, value_delimiter = ',')]
+    cluster_seeds: Vec<SocketAddr>,
+
+    /// ID of this node in the cluster (defaults to the advertised gossip address)
+    #[arg(long)]
+    cluster_node_id: Option<String>,
+
+    /// Client address (host:port) other nodes redirect this node's rooms to (defaults to --host:--port)
+    #[arg(long)]
+    cluster_http_addr: Option<String>,
+
+    /// PEM file of the TLS certificate chain; serves https:// and wss:// (requires --tls-key)
+    #[cfg(feature = "tls")]
+    #[arg(long)]
+    tls_cert: Option<PathBuf>,
+
+    /// PEM file of the private key of the TLS certificate (requires --tls-cert)
+    #[cfg(feature = "tls")]
+    #[arg(long)]
+    tls_key: Option<PathBuf>,
+
+    /// Port number of the gRPC health service (grpc.health.v1.Health); disabled if omitted
+    #[cfg(feature = "grpc")]
+    #[arg(long)]
+    grpc_health_port: Option<u16>,
+
+    /// Discord channel to relay messages with (bot token is read from DISCORD_BOT_TOKEN)
+    #[cfg(feature = "discord")]
+    #[arg(long)]
+    discord_channel_id: Option<String>,
+
+    /// Prefix of the client IDs of Discord users in the room
+    #[cfg(feature = "discord")]
+    #[arg(long, default_value = "discord:")]
+    discord_prefix: String,
+
+    /// Base URL of the Discord REST API
+    #[cfg(feature = "discord")]
+    #[arg(long, default_value = engawa_server::infrastructure::discord::DEFAULT_API_BASE)]
+    discord_api_base: String,
+
+    /// Host of the MQTT broker to bridge the room to; disabled if omitted
+    #[cfg(feature = "mqtt")]
+    #[arg(long)]
+    mqtt_host: Option<String>,
+
+    /// Port number of the MQTT broker
+    #[cfg(feature = "mqtt")]
+    #[arg(long, default_value = "1883")]
+    mqtt_port: u16,
+
+    /// ID of this server among federated servers; enables federation
+    /// (shared secret is read from FEDERATION_SECRET)
+    #[cfg(feature = "federation")]
+    #[arg(long)]
+    federation_id: Option<String>,
 
-    /// Port number to bind the server to
-    #[arg(short = 'p', long, default_value = "8080")]
-    port: u16,
+    /// Federation endpoints of peer servers to link with (comma separated,
+    /// e.g. ws://osaka.example.org:8080/federation)
+    #[cfg(feature = "federation")]
+    #[arg(long, value_delimiter = ',')]
+    federation_peers: Vec<String>,
+
+    /// Host of the XMPP server to connect to as a component; disabled if omitted
+    /// (shared secret is read from XMPP_COMPONENT_SECRET)
+    #[cfg(feature = "xmpp")]
+    #[arg(long)]
+    xmpp_component_host: Option<String>,
+
+    /// Component port of the XMPP server
+    #[cfg(feature = "xmpp")]
+    #[arg(long, default_value = "5347")]
+    xmpp_component_port: u16,
+
+    /// Component domain; the room is served as the MUC {room_id}@{domain}
+    #[cfg(feature = "xmpp")]
+    #[arg(long)]
+    xmpp_domain: Option<String>,
+
+    /// Prefix of the client IDs of XMPP users in the room
+    #[cfg(feature = "xmpp")]
+    #[arg(long, default_value = "xmpp:")]
+    xmpp_prefix: String,
+}
+
+/// Subcommands (the server runs when none is given)
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Apply the SQL schema migrations embedded in this binary
+    #[cfg(any(feature = "sqlite", feature = "postgres"))]
+    Migrate(MigrateArgs),
+    /// Read a password from stdin and print its hash for the --auth-users file
+    HashPassword,
+    /// Inspect a --wire-log file
+    WireLog {
+        #[command(subcommand)]
+        action: WireLogAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum WireLogAction {
+    /// Print the recorded frames, with JSON payloads pretty-printed
+    Dump {
+        /// Wire log file written by the server or the client
+        path: PathBuf,
+    },
+}
+
+/// Run `wire-log` and return the exit code
+fn run_wire_log(action: &WireLogAction) -> i32 {
+    let WireLogAction::Dump { path } = action;
+    match wire_log::read_records(path) {
+        Ok(records) => {
+            for record in &records {
+                println!("{}", wire_log::format_record(record));
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", path.display(), e);
+            1
+        }
+    }
+}
+
+/// Run `hash-password` and return the exit code
+fn run_hash_password() -> i32 {
+    let mut password = String::new();
+    if let Err(e) = std::io::stdin().read_line(&mut password) {
+        eprintln!("Failed to read the password: {}", e);
+        return 1;
+    }
+    let password = password.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        eprintln!("The password must not be empty");
+        return 2;
+    }
+    println!("{}", hash_password(password));
+    0
+}
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+#[derive(clap::Args, Debug)]
+struct MigrateArgs {
+    /// Database to migrate (sqlite://PATH or postgres://...); defaults to DATABASE_URL
+    #[arg(long, global = true)]
+    database_url: Option<String>,
+
+    #[command(subcommand)]
+    action: Option<MigrateAction>,
+}
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+#[derive(Subcommand, Debug, Clone, Copy)]
+enum MigrateAction {
+    /// Apply every pending migration (default)
+    Run,
+    /// List the migrations and whether they are applied
+    Status,
+    /// Revert the latest applied migration
+    Revert,
+}
+
+/// Run `migrate` and return the exit code
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+async fn run_migrate(args: &MigrateArgs) -> i32 {
+    let Some(database_url) = args
+        .database_url
+        .clone()
+        .or_else(|| std::env::var("DATABASE_URL").ok())
+    else {
+        eprintln!("--database-url or DATABASE_URL is required");
+        return 2;
+    };
+    let describe = |status: &MigrationStatus| format!("{} {}", status.version, status.description);
+
+    let result = match args.action.unwrap_or(MigrateAction::Run) {
+        MigrateAction::Run => migration::apply(&database_url).await.map(|applied| {
+            if applied.is_empty() {
+                println!("Database is up to date");
+            }
+            for status in &applied {
+                println!("Applied {}", describe(status));
+            }
+        }),
+        MigrateAction::Status => migration::status(&database_url).await.map(|statuses| {
+            for status in &statuses {
+                let state = match status.state {
+                    MigrationState::Applied => "applied",
+                    MigrationState::Pending => "pending",
+                    MigrationState::Modified => "modified",
+                    MigrationState::Missing => "missing",
+                };
+                println!("{:<8} {}", state, describe(status));
+            }
+        }),
+        MigrateAction::Revert => {
+            migration::revert(&database_url)
+                .await
+                .map(|reverted| match reverted {
+                    Some(status) => println!("Reverted {}", describe(&status)),
+                    None => println!("No migration to revert"),
+                })
+        }
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// Open the SQLite database and restore its default room, or exit
+#[cfg(feature = "sqlite")]
+async fn open_sqlite(path: &Path, new_room: impl FnOnce() -> Room) -> (Arc<SqliteStore>, Room) {
+    match SqliteStore::open(path, new_room).await {
+        Ok((store, room)) => {
+            tracing::info!(
+                "Restored room {} with {} messages from SQLite {}",
+                room.id,
+                room.messages.len(),
+                path.display()
+            );
+            (Arc::new(store), room)
+        }
+        Err(e) => {
+            tracing::error!("Failed to open SQLite {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Restore the created rooms from SQLite and persist changes to them, or exit
+#[cfg(feature = "sqlite")]
+async fn sqlite_repository(
+    inner: Arc<dyn RoomRepository>,
+    store: Arc<SqliteStore>,
+) -> Arc<dyn RoomRepository> {
+    match store.restore_rooms(inner.as_ref()).await {
+        Ok(count) => tracing::info!("Restored {} created rooms from SQLite", count),
+        Err(e) => {
+            tracing::error!("Failed to restore rooms from SQLite: {}", e);
+            std::process::exit(1);
+        }
+    }
+    Arc::new(SqliteRoomRepository::new(inner, store))
+}
+
+/// Connect to the PostgreSQL database and restore its default room, or exit
+#[cfg(feature = "postgres")]
+async fn connect_postgres(
+    database_url: &str,
+    pool_size: u32,
+    new_room: impl FnOnce() -> Room,
+) -> (Arc<PostgresStore>, Room) {
+    match PostgresStore::connect(database_url, pool_size, new_room).await {
+        Ok((store, room)) => {
+            tracing::info!(
+                "Restored room {} with {} messages from PostgreSQL",
+                room.id,
+                room.messages.len()
+            );
+            (Arc::new(store), room)
+        }
+        Err(e) => {
+            tracing::error!("Failed to connect to PostgreSQL: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Restore the created rooms from PostgreSQL and persist changes to them, or exit
+#[cfg(feature = "postgres")]
+async fn postgres_repository(
+    inner: Arc<dyn RoomRepository>,
+    store: Arc<PostgresStore>,
+) -> Arc<dyn RoomRepository> {
+    match store.restore_rooms(inner.as_ref()).await {
+        Ok(count) => tracing::info!("Restored {} created rooms from PostgreSQL", count),
+        Err(e) => {
+            tracing::error!("Failed to restore rooms from PostgreSQL: {}", e);
+            std::process::exit(1);
+        }
+    }
+    Arc::new(PostgresRoomRepository::new(inner, store))
+}
+
+impl Args {
+    /// Assemble the server configuration on top of the configuration file, reading secrets
+    /// from the environment
+    fn into_config(self, file: ConfigFile) -> ServerConfig {
+        let defaults = ServerConfig::default();
+        let storage = self.storage.or(file.storage).unwrap_or(defaults.storage);
+        let uses_database_url = std::iter::once(storage)
+            .chain(self.room_storage.iter().map(|storage| storage.backend))
+            .any(|backend| backend.database_option() == Some("--database-url"));
+        ServerConfig {
+            host: self.host.or(file.host).unwrap_or(defaults.host),
+            port: self.port.or(file.port).unwrap_or(defaults.port),
+            trusted_proxies: self.trusted_proxies,
+            ip_allow: self.ip_allow,
+            ip_deny: self.ip_deny,
+            incoming_webhook_token: self.incoming_webhook_token,
+            admin_token: std::env::var("ADMIN_TOKEN").ok(),
+            auth_users: self.auth_users,
+            jwt_secret: std::env::var("JWT_SECRET").ok(),
+            dedup_window: self.dedup_window,
+            batch_window: (self.batch_window_ms > 0)
+                .then(|| Duration::from_millis(self.batch_window_ms)),
+            slow_down_threshold: (self.slow_down_threshold > 0).then_some(self.slow_down_threshold),
+            keepalive: (self.ping_interval_secs > 0).then(|| Keepalive {
+                ping_interval: Duration::from_secs(self.ping_interval_secs),
+                idle_timeout: Duration::from_secs(self.idle_timeout_secs),
+            }),
+            duplicate_policy: self.duplicate_policy,
+            sanitize_profile: self.sanitize_profile,
+            guest_mode: self.guest_mode,
+            guest_messages_per_minute: self.guest_messages_per_minute,
+            messages_per_second: self.messages_per_second.or(file.messages_per_second),
+            message_burst: self
+                .message_burst
+                .or(file.message_burst)
+                .unwrap_or(defaults.message_burst),
+            connect_challenge: self.connect_challenge,
+            connect_challenge_difficulty: self.connect_challenge_difficulty,
+            room_slug: self.room_slug,
+            room_locale: self.room_locale,
+            room_capacity: self
+                .room_capacity
+                .or(file.room_capacity)
+                .unwrap_or(defaults.room_capacity),
+            message_capacity: self
+                .message_capacity
+                .or(file.message_capacity)
+                .unwrap_or(defaults.message_capacity),
+            max_message_length: self.max_message_length,
+            history_replay: self.history_replay,
+            max_rooms_per_client: self.max_rooms_per_client,
+            durable_participants: self.durable_participants.map(Duration::from_secs),
+            wal: self.wal,
+            wire_log: self.wire_log,
+            wire_log_redact: self.wire_log_redact,
+            storage,
+            db_path: self.db_path.or(file.db_path),
+            // Read from the environment unless given, as the URL may hold a password
+            database_url: self.database_url.or_else(|| {
+                uses_database_url
+                    .then(|| std::env::var("DATABASE_URL").ok())
+                    .flatten()
+            }),
+            db_pool_size: self.db_pool_size,
+            memory_limit_mb: self.memory_limit_mb,
+            seed: self.seed,
+            digest_at: self.digest_at,
+            analyze_keywords: self.analyze_keywords,
+            message_filters: if self.message_filters.is_empty() {
+                file.message_filters.unwrap_or_default()
+            } else {
+                self.message_filters
+            },
+            room_storage: self.room_storage,
+            breakout_idle_timeout: Duration::from_secs(self.breakout_idle_timeout),
+            drain_timeout: Duration::from_secs(self.drain_timeout),
+            reconnect_stagger: Duration::from_millis(self.reconnect_stagger_ms),
+            shutdown_timeout: Duration::from_secs(self.shutdown_timeout),
+            cluster: ClusterConfig {
+                gossip_addr: self.cluster_gossip_addr,
+                advertise_addr: self.cluster_advertise_addr,
+                seeds: self.cluster_seeds,
+                node_id: self.cluster_node_id,
+                http_addr: self.cluster_http_addr,
+            },
+            #[cfg(feature = "tls")]
+            tls: TlsConfig {
+                cert: self.tls_cert,
+                key: self.tls_key,
+            },
+            #[cfg(feature = "grpc")]
+            grpc_health_port: self.grpc_health_port,
+            #[cfg(feature = "discord")]
+            discord: DiscordConfig {
+                channel_id: self.discord_channel_id,
+                bot_token: std::env::var("DISCORD_BOT_TOKEN").ok(),
+                prefix: self.discord_prefix,
+                api_base: self.discord_api_base,
+            },
+            #[cfg(feature = "mqtt")]
+            mqtt: MqttConfig {
+                host: self.mqtt_host,
+                port: self.mqtt_port,
+            },
+            #[cfg(feature = "federation")]
+            federation: FederationConfig {
+                server_id: self.federation_id,
+                secret: std::env::var("FEDERATION_SECRET").ok(),
+                peers: self.federation_peers,
+            },
+            #[cfg(feature = "xmpp")]
+            xmpp: XmppConfig {
+                component_host: self.xmpp_component_host,
+                component_port: self.xmpp_component_port,
+                domain: self.xmpp_domain,
+                secret: std::env::var("XMPP_COMPONENT_SECRET").ok(),
+                prefix: self.xmpp_prefix,
+            },
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() {
+    let args = Args::parse();
+
+    // Read the configuration file, overridden by the environment
+    let file = match args.config.as_deref().map(ConfigFile::load).transpose() {
+        Ok(file) => file
+            .unwrap_or_default()
+            .with_env(|name| std::env::var(name).ok()),
+        Err(e) => Err(e),
+    };
+    let file = match file {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
     // Initialize tracing
-    setup_logger(env!("CARGO_BIN_NAME"), "debug");
+    let log_reloader =
+        match setup_logger(env!("CARGO_BIN_NAME"), "debug", &log_args(&args.log, &file)) {
+            Ok(log_reloader) => log_reloader,
+            Err(e) => {
+                eprintln!("invalid --log-filter: {}", e);
+                std::process::exit(2);
+            }
+        };
 
-    let args = Args::parse();
+    match &args.command {
+        #[cfg(any(feature = "sqlite", feature = "postgres"))]
+        Some(Command::Migrate(migrate)) => std::process::exit(run_migrate(migrate).await),
+        Some(Command::HashPassword) => std::process::exit(run_hash_password()),
+        Some(Command::WireLog { action }) => std::process::exit(run_wire_log(action)),
+        None => {}
+    }
+
+    // Validate the whole configuration before starting anything
+    let log_reload = args
+        .config
+        .clone()
+        .map(|path| (path, args.log.clone(), log_reloader));
+    let config = args.into_config(file);
+    if let Err(errors) = config.validate() {
+        eprintln!("{}", errors);
+        std::process::exit(2);
+    }
 
     // Initialize dependencies in order:
     // 1. Repository
@@ -50,34 +807,316 @@ async fn main() {
     // 4. AppState
     // 5. Server
 
-    // 1. Create Repository (in-memory database)
-    let room = Arc::new(Mutex::new(Room::new(
-        RoomIdFactory::generate().expect("Failed to generate RoomId"),
-        Timestamp::new(get_jst_timestamp()),
-    )));
-    tracing::info!("Room {} created!", room.lock().await.id.as_str());
-    let repository = Arc::new(InMemoryRoomRepository::new(room));
+    // 1. Create Repository (in-memory database, recovered from the WAL or SQLite if configured)
+    let new_room = || {
+        Room::with_capacity(
+            RoomIdFactory::generate().expect("Failed to generate RoomId"),
+            Timestamp::new(get_jst_timestamp()),
+            config.room_capacity,
+            config.message_capacity,
+        )
+    };
+    #[cfg(feature = "sqlite")]
+    let mut sqlite = None;
+    #[cfg(feature = "postgres")]
+    let mut postgres = None;
+    let (mut room, restored_rooms, wal) = match &config.wal {
+        Some(path) => match WriteAheadLog::open(path, new_room).await {
+            Ok((wal, room, rooms)) => {
+                tracing::info!(
+                    "Recovered {} messages and {} created rooms from WAL {}",
+                    room.messages.len(),
+                    rooms.len(),
+                    path.display()
+                );
+                (room, rooms, Some(Arc::new(wal)))
+            }
+            Err(e) => {
+                tracing::error!("Failed to open WAL {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        },
+        #[cfg(feature = "sqlite")]
+        None if config.storage == StorageBackend::Sqlite => {
+            let path = config.db_path.as_deref().expect("validated");
+            let (store, room) = open_sqlite(path, new_room).await;
+            sqlite = Some(store);
+            (room, Vec::new(), None)
+        }
+        #[cfg(feature = "postgres")]
+        None if config.storage == StorageBackend::Postgres => {
+            let database_url = config.database_url.as_deref().expect("validated");
+            let (store, room) = connect_postgres(database_url, config.db_pool_size, new_room).await;
+            postgres = Some(store);
+            (room, Vec::new(), None)
+        }
+        None => (new_room(), Vec::new(), None),
+    };
+    room.slug = config.room_slug.clone();
+    room.locale = config.room_locale;
+    room.message_policy.max_length = config.max_message_length;
+    let room_id = room.id.clone();
+    tracing::info!("Room {} created!", room_id.as_str());
+    let in_memory_repository = Arc::new(InMemoryRoomRepository::new(room));
+    for room in restored_rooms {
+        if let Err(e) = in_memory_repository.create_room(room).await {
+            tracing::error!("Failed to restore room from WAL: {}", e);
+            std::process::exit(1);
+        }
+    }
+    let repository: Arc<dyn RoomRepository> = match wal.clone() {
+        Some(wal) => Arc::new(WalRoomRepository::new(in_memory_repository, wal)),
+        None => in_memory_repository,
+    };
+    #[cfg(feature = "sqlite")]
+    let repository = match sqlite {
+        Some(store) => sqlite_repository(repository, store).await,
+        None => repository,
+    };
+    #[cfg(feature = "postgres")]
+    let repository = match postgres {
+        Some(store) => postgres_repository(repository, store).await,
+        None => repository,
+    };
+    // Route created rooms to the storage backend of their class
+    let repository: Arc<dyn RoomRepository> = if config.room_storage.is_empty() {
+        repository
+    } else {
+        let mut router = RoomRepositoryRouter::new(repository);
+        for storage in &config.room_storage {
+            let backend: Arc<dyn RoomRepository> = match storage.backend {
+                StorageBackend::Memory => Arc::new(InMemoryRoomRepository::new(new_room())),
+                #[cfg(feature = "sqlite")]
+                StorageBackend::Sqlite => {
+                    let path = config.db_path.as_deref().expect("validated");
+                    let (store, room) = open_sqlite(path, new_room).await;
+                    let inner = Arc::new(InMemoryRoomRepository::new(room));
+                    sqlite_repository(inner, store).await
+                }
+                #[cfg(feature = "postgres")]
+                StorageBackend::Postgres => {
+                    let database_url = config.database_url.as_deref().expect("validated");
+                    let (store, room) =
+                        connect_postgres(database_url, config.db_pool_size, new_room).await;
+                    let inner = Arc::new(InMemoryRoomRepository::new(room));
+                    postgres_repository(inner, store).await
+                }
+            };
+            tracing::info!("Storing {} rooms in {:?}", storage.class, storage.backend);
+            router = router.with_route(storage.class, backend);
+        }
+        Arc::new(router)
+    };
 
     // 2. Create MessagePusher (WebSocket implementation)
-    let message_pusher_clients = Arc::new(Mutex::new(HashMap::new()));
-    let message_pusher = Arc::new(WebSocketMessagePusher::new(message_pusher_clients.clone()));
+    let message_pusher: Arc<dyn MessagePusher> =
+        Arc::new(WebSocketMessagePusher::new(ClientChannels::default()));
+
+    // Mirror room messages to the MQTT broker if configured
+    #[cfg(feature = "mqtt")]
+    let (message_pusher, mqtt_bridge) = match &config.mqtt.host {
+        Some(mqtt_host) => {
+            let mut options = rumqttc::MqttOptions::new(
+                format!("engawa-server-{}", room_id.as_str()),
+                mqtt_host,
+                config.mqtt.port,
+            );
+            options.set_keep_alive(std::time::Duration::from_secs(30));
+            let (client, eventloop) = rumqttc::AsyncClient::new(options, 64);
+            let pusher: Arc<dyn MessagePusher> = Arc::new(MqttMirrorPusher::new(
+                message_pusher,
+                client.clone(),
+                &room_id,
+            ));
+            (
+                pusher,
+                Some(MqttBridge::new(client, eventloop, room_id.clone())),
+            )
+        }
+        None => (message_pusher, None),
+    };
+
+    // Relay chat messages to Discord if configured
+    #[cfg(feature = "discord")]
+    let (message_pusher, discord_relay) =
+        match (&config.discord.channel_id, &config.discord.bot_token) {
+            (Some(channel_id), Some(token)) => {
+                let (outbound_sender, outbound_receiver) =
+                    tokio::sync::mpsc::channel(discord::OUTBOUND_QUEUE_CAPACITY);
+                let pusher: Arc<dyn MessagePusher> = Arc::new(DiscordRelayPusher::new(
+                    message_pusher,
+                    outbound_sender,
+                    config.discord.prefix.clone(),
+                ));
+                let relay = DiscordRelay::new(
+                    DiscordClient::new(token.clone(), config.discord.api_base.clone()),
+                    channel_id.clone(),
+                    outbound_receiver,
+                    config.discord.prefix.clone(),
+                );
+                (pusher, Some(relay))
+            }
+            _ => (message_pusher, None),
+        };
+
+    // Send room events to federation peers if configured
+    #[cfg(feature = "federation")]
+    let (message_pusher, federation) =
+        match (&config.federation.server_id, &config.federation.secret) {
+            (Some(server_id), Some(secret)) => {
+                let remote = RemoteParticipants::new();
+                let (event_sender, event_receiver) =
+                    tokio::sync::mpsc::channel(federation::OUTBOUND_QUEUE_CAPACITY);
+                let pusher: Arc<dyn MessagePusher> = Arc::new(FederationPusher::new(
+                    message_pusher,
+                    event_sender,
+                    remote.clone(),
+                ));
+                let federation = Federation::new(
+                    server_id.clone(),
+                    secret.clone(),
+                    config.federation.peers.clone(),
+                    remote,
+                    event_receiver,
+                );
+                (pusher, Some(federation))
+            }
+            _ => (message_pusher, None),
+        };
+
+    // Post chat messages to the outgoing webhooks of the rooms if integrations are enabled
+    #[cfg(feature = "webhooks")]
+    let (webhook_sender, outgoing_webhooks) = match &config.admin_token {
+        Some(_) => {
+            let (sender, receiver) = tokio::sync::mpsc::channel(webhook::OUTBOUND_QUEUE_CAPACITY);
+            (Some(sender), Some(OutgoingWebhooks::new(receiver)))
+        }
+        None => (None, None),
+    };
+    #[cfg(feature = "webhooks")]
+    let message_pusher: Arc<dyn MessagePusher> = match &webhook_sender {
+        Some(sender) => Arc::new(OutgoingWebhookPusher::new(
+            message_pusher,
+            sender.clone(),
+            &room_id,
+        )),
+        None => message_pusher,
+    };
 
     // 3. Create UseCases
-    let connect_participant_usecase = Arc::new(ConnectParticipantUseCase::new(
-        repository.clone(),
-        message_pusher.clone(),
-    ));
-    let disconnect_participant_usecase = Arc::new(DisconnectParticipantUseCase::new(
-        repository.clone(),
-        message_pusher.clone(),
-    ));
-    let send_message_usecase = Arc::new(SendMessageUseCase::new(
+    // Messages missed by disconnected participants are kept until they reconnect
+    let pending_messages = config.durable_participants.map(|ttl| {
+        Arc::new(InMemoryPendingMessageStore::new(
+            ttl,
+            DEFAULT_PENDING_MESSAGES,
+        )) as Arc<dyn PendingMessageStore>
+    });
+    let mut connect_participant_usecase =
+        ConnectParticipantUseCase::new(repository.clone(), message_pusher.clone());
+    let mut disconnect_participant_usecase =
+        DisconnectParticipantUseCase::new(repository.clone(), message_pusher.clone());
+    if let Some(store) = &pending_messages {
+        connect_participant_usecase =
+            connect_participant_usecase.with_pending_messages(store.clone());
+        disconnect_participant_usecase =
+            disconnect_participant_usecase.with_pending_messages(store.clone());
+    }
+    let connect_participant_usecase = Arc::new(connect_participant_usecase);
+    let disconnect_participant_usecase = Arc::new(disconnect_participant_usecase);
+    let vote_poll_usecase = VotePollUseCase::new(repository.clone(), message_pusher.clone());
+    let set_activity_usecase = SetActivityUseCase::new(repository.clone(), message_pusher.clone());
+    let rename_participant_usecase =
+        RenameParticipantUseCase::new(repository.clone(), message_pusher.clone());
+    let respond_to_incident_usecase =
+        RespondToIncidentUseCase::new(repository.clone(), message_pusher.clone());
+    let broadcast_typing_usecase = BroadcastTypingUseCase::new(
         repository.clone(),
         message_pusher.clone(),
-    ));
+        DEFAULT_TYPING_DEBOUNCE,
+    );
+    let send_message_usecase = if config.analyze_keywords.is_empty() {
+        SendMessageUseCase::new(repository.clone(), message_pusher.clone())
+    } else {
+        let analyzer = KeywordAnalyzer::new(config.analyze_keywords.clone())
+            .expect("Keywords should be validated");
+        SendMessageUseCase::with_analyzer(
+            repository.clone(),
+            message_pusher.clone(),
+            Arc::new(analyzer),
+        )
+    };
+    // Clients share one send rate across the default room and the created rooms
+    let rate_limiter = config
+        .messages_per_second
+        .map(|per_second| Arc::new(RateLimiter::new(per_second, config.message_burst)));
+    // Messages from bridges and webhooks follow the default room's policy too
+    let send_message_usecase =
+        send_message_usecase.with_max_message_length(config.max_message_length);
+    let send_message_usecase = match &rate_limiter {
+        Some(rate_limiter) => send_message_usecase.with_rate_limiter(rate_limiter.clone()),
+        None => send_message_usecase,
+    };
+    // The default room and the created rooms share one filter chain
+    let message_filters = (!config.message_filters.is_empty()).then(|| {
+        Arc::new(MessageFilterChain::new(
+            config
+                .message_filters
+                .iter()
+                .map(MessageFilterSpec::build)
+                .collect(),
+        ))
+    });
+    let send_message_usecase = match &message_filters {
+        Some(filters) => send_message_usecase.with_filters(filters.clone()),
+        None => send_message_usecase,
+    };
+    let send_message_usecase = match &pending_messages {
+        Some(store) => send_message_usecase.with_pending_messages(store.clone()),
+        None => send_message_usecase,
+    };
+    let send_message_usecase = Arc::new(send_message_usecase);
     let get_room_state_usecase = Arc::new(GetRoomStateUseCase::new(repository.clone()));
     let get_rooms_usecase = Arc::new(GetRoomsUseCase::new(repository.clone()));
     let get_room_detail_usecase = Arc::new(GetRoomDetailUseCase::new(repository.clone()));
+    let get_room_messages_usecase = Arc::new(GetRoomMessagesUseCase::new(repository.clone()));
+    let check_health_usecase = CheckHealthUseCase::new(
+        repository.clone(),
+        message_pusher.clone(),
+        DEFAULT_HEALTH_CHECK_TIMEOUT,
+    );
+    let enforce_memory_limit_usecase = EnforceMemoryLimitUseCase::new(
+        repository.clone(),
+        config.memory_limit_mb.map(|mb| mb as usize * 1024 * 1024),
+    );
+    // Created rooms broadcast through their own pusher (bridges mirror the default room only)
+    let new_room_pusher = move |#[cfg_attr(not(feature = "webhooks"), allow(unused_variables))]
+                                room_id: &RoomId| {
+        let pusher: Arc<dyn MessagePusher> =
+            Arc::new(WebSocketMessagePusher::new(Default::default()));
+        #[cfg(feature = "webhooks")]
+        let pusher: Arc<dyn MessagePusher> = match &webhook_sender {
+            Some(sender) => Arc::new(OutgoingWebhookPusher::new(pusher, sender.clone(), room_id)),
+            None => pusher,
+        };
+        pusher
+    };
+    let join_room_usecase = JoinRoomUseCase::new(
+        repository.clone(),
+        config.max_rooms_per_client,
+        new_room_pusher,
+    );
+    let join_room_usecase = match rate_limiter {
+        Some(rate_limiter) => join_room_usecase.with_rate_limiter(rate_limiter),
+        None => join_room_usecase,
+    };
+    let join_room_usecase = Arc::new(match message_filters {
+        Some(filters) => join_room_usecase.with_filters(filters),
+        None => join_room_usecase,
+    });
+    let forward_message_usecase =
+        ForwardMessageUseCase::new(repository.clone(), join_room_usecase.clone());
+    // Stars are listed per client and removed along with the client's data
+    let stars: Arc<dyn StarRepository> = Arc::new(InMemoryStarRepository::new());
 
     // 4. Create and run the server
     let server = Server::new(
@@ -87,9 +1126,294 @@ async fn main() {
         get_room_state_usecase,
         get_rooms_usecase,
         get_room_detail_usecase,
-    );
-    if let Err(e) = server.run(args.host, args.port).await {
+        get_room_messages_usecase,
+    )
+    .with_trusted_proxies(TrustedProxies::new(config.trusted_proxies))
+    .with_ip_filter(IpRules {
+        allow: config.ip_allow,
+        deny: config.ip_deny,
+    })
+    .with_health_check(check_health_usecase)
+    .with_room_stats(GetRoomStatsUseCase::new(repository.clone()))
+    .with_message_search(SearchMessagesUseCase::new(repository.clone()))
+    .with_message_export(ExportMessagesUseCase::new(repository.clone()))
+    .with_rooms(
+        CreateRoomUseCase::new(repository.clone(), DEFAULT_MAX_ROOMS)
+            .with_capacity(config.room_capacity, config.message_capacity)
+            .with_max_message_length(config.max_message_length),
+        join_room_usecase.clone(),
+    )
+    .with_message_forwarding(forward_message_usecase)
+    .with_starred_messages(StarMessagesUseCase::new(repository.clone(), stars.clone()))
+    .with_breakouts(
+        ManageBreakoutsUseCase::new(
+            repository.clone(),
+            CreateRoomUseCase::new(repository.clone(), DEFAULT_MAX_ROOMS)
+                .with_capacity(config.room_capacity, config.message_capacity)
+                .with_max_message_length(config.max_message_length),
+        )
+        .with_idle_timeout(config.breakout_idle_timeout),
+    )
+    .with_dedup_window(config.dedup_window)
+    .with_duplicate_policy(config.duplicate_policy)
+    .with_sanitize_profile(config.sanitize_profile)
+    .with_locale(config.room_locale)
+    .with_guests(GuestPolicy::new(
+        config.guest_mode,
+        config.guest_messages_per_minute,
+    ))
+    .with_connect_challenge(ConnectChallenge::new(
+        config.connect_challenge,
+        config.connect_challenge_difficulty,
+    ))
+    .with_typing_indicators(broadcast_typing_usecase)
+    .with_poll_votes(vote_poll_usecase)
+    .with_activities(set_activity_usecase)
+    .with_display_names(rename_participant_usecase)
+    .with_incident_response(respond_to_incident_usecase)
+    .with_shutdown_timeout(config.shutdown_timeout)
+    .with_memory_guard(enforce_memory_limit_usecase);
+    let handover = Handover::new()
+        .with_drain_timeout(config.drain_timeout)
+        .with_reconnect_stagger(config.reconnect_stagger);
+    let server = match wal {
+        Some(wal) => server.with_handover(handover.with_wal(wal)),
+        None => server.with_handover(handover),
+    };
+    let server = match config.batch_window {
+        Some(window) => server.with_batch_window(window),
+        None => server,
+    };
+    let server = match config.slow_down_threshold {
+        Some(threshold) => server.with_slow_down_threshold(threshold),
+        None => server,
+    };
+    let server = match config.keepalive {
+        Some(keepalive) => server.with_keepalive(keepalive),
+        None => server,
+    };
+    let server = match &config.wire_log {
+        Some(path) => match WireLog::open(path, config.wire_log_redact.clone()) {
+            Ok(wire_log) => {
+                tracing::info!("Recording WebSocket frames to {}", path.display());
+                server.with_wire_log(Arc::new(wire_log))
+            }
+            Err(e) => {
+                tracing::error!("Failed to open wire log {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        },
+        None => server,
+    };
+    let server = match config.history_replay {
+        0 => server,
+        limit => {
+            server.with_message_history(GetMessageHistoryUseCase::new(repository.clone(), limit))
+        }
+    };
+    let server = match config.seed {
+        Some(SeedProfile::Demo) => server.with_demo_seed(SeedDemoDataUseCase::new(
+            repository.clone(),
+            message_pusher.clone(),
+        )),
+        None => server,
+    };
+    let server = match config.digest_at {
+        Some(at) => server.with_daily_digest(
+            at,
+            ComposeDailyDigestUseCase::new(
+                repository.clone(),
+                ClientId::new(DIGEST_SENDER.to_string()).expect("Invalid digest sender"),
+            ),
+        ),
+        None => server,
+    };
+    let server = match config.admin_token {
+        Some(token) => {
+            // Clients banned from the moderation queue and at /admin/ban share one list
+            let ban_list: Arc<dyn BanList> = Arc::new(InMemoryBanList::new());
+            server
+                .with_client_data_erasure(
+                    token.clone(),
+                    EraseClientDataUseCase::new(repository.clone(), message_pusher.clone())
+                        .with_stars(stars)
+                        .with_rooms(join_room_usecase.clone()),
+                )
+                .with_moderation(
+                    token.clone(),
+                    ModerateMessagesUseCase::new(
+                        repository.clone(),
+                        message_pusher.clone(),
+                        ban_list.clone(),
+                    )
+                    .with_rooms(join_room_usecase),
+                )
+                .with_participant_kick(
+                    token.clone(),
+                    KickParticipantUseCase::new(repository.clone(), ban_list),
+                )
+                .with_integrations(
+                    token,
+                    ManageIntegrationsUseCase::new(
+                        repository.clone(),
+                        Arc::new(InMemoryIntegrationRepository::new()),
+                    ),
+                )
+        }
+        None => server,
+    };
+    let server = match config.incoming_webhook_token {
+        Some(token) => server.with_incoming_webhook_token(token),
+        None => server,
+    };
+    let server = match (&config.auth_users, &config.jwt_secret) {
+        (Some(path), Some(secret)) => match Credentials::load(path) {
+            Ok(credentials) => {
+                tracing::info!(
+                    "Authentication enabled for {} clients from {}",
+                    credentials.len(),
+                    path.display()
+                );
+                server.with_auth(Authentication::new(
+                    credentials,
+                    AccessTokens::new(secret.as_bytes(), DEFAULT_TOKEN_TTL),
+                ))
+            }
+            Err(e) => {
+                tracing::error!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        _ => server,
+    };
+    let cluster = config.cluster;
+    let server = match cluster.gossip_addr {
+        Some(gossip_addr) => {
+            let advertise_addr = cluster.advertise_addr.unwrap_or(gossip_addr);
+            server.with_cluster(ClusterNode::new(
+                cluster
+                    .node_id
+                    .unwrap_or_else(|| advertise_addr.to_string()),
+                gossip_addr,
+                advertise_addr,
+                cluster
+                    .http_addr
+                    .unwrap_or_else(|| format!("{}:{}", config.host, config.port)),
+                cluster.seeds,
+            ))
+        }
+        None => server,
+    };
+    #[cfg(feature = "tls")]
+    let server = match (&config.tls.cert, &config.tls.key) {
+        (Some(cert), Some(key)) => match TlsTermination::load(cert, key) {
+            Ok(tls) => server.with_tls(tls),
+            Err(e) => {
+                tracing::error!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        _ => server,
+    };
+    #[cfg(feature = "grpc")]
+    let server = match config.grpc_health_port {
+        Some(port) => match format!("{}:{}", config.host, port).parse() {
+            Ok(addr) => server.with_grpc_health(addr),
+            Err(e) => {
+                tracing::error!("Invalid gRPC health address: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => server,
+    };
+    #[cfg(feature = "discord")]
+    let server = match discord_relay {
+        Some(relay) => server.with_discord_relay(relay),
+        None => server,
+    };
+    #[cfg(feature = "mqtt")]
+    let server = match mqtt_bridge {
+        Some(bridge) => server.with_mqtt_bridge(bridge),
+        None => server,
+    };
+    #[cfg(feature = "federation")]
+    let server = match federation {
+        Some(federation) => server.with_federation(federation),
+        None => server,
+    };
+    #[cfg(feature = "webhooks")]
+    let server = match outgoing_webhooks {
+        Some(webhooks) => server.with_outgoing_webhooks(webhooks),
+        None => server,
+    };
+    #[cfg(feature = "xmpp")]
+    let xmpp = config.xmpp;
+    #[cfg(feature = "xmpp")]
+    let server = match (xmpp.component_host, xmpp.domain, xmpp.secret) {
+        (Some(xmpp_host), Some(domain), Some(secret)) => {
+            server.with_xmpp_gateway(XmppGateway::new(
+                format!("{}:{}", xmpp_host, xmpp.component_port),
+                domain,
+                secret,
+                room_id,
+                xmpp.prefix,
+            ))
+        }
+        _ => server,
+    };
+    if let Some((path, cli, log_reloader)) = log_reload {
+        tokio::spawn(reload_logging(
+            server.reload_handle().subscribe(),
+            path,
+            cli,
+            log_reloader,
+        ));
+    }
+    if let Err(e) = server.run(config.host, config.port).await {
         tracing::error!("Server error: {}", e);
         std::process::exit(1);
     }
 }
+
+/// Logging options from the command line, falling back to the configuration file
+fn log_args(cli: &LogArgs, file: &ConfigFile) -> LogArgs {
+    LogArgs {
+        log_level: cli.log_level.clone().or(file.log_level.clone()),
+        log_filter: cli.log_filter.clone().or(file.log_filter.clone()),
+    }
+}
+
+/// Read the log settings of the configuration file again on every reload request (SIGHUP)
+///
+/// An unreadable file or an invalid filter is logged and the current logging is kept.
+async fn reload_logging(
+    mut reload: tokio::sync::watch::Receiver<u64>,
+    path: PathBuf,
+    cli: LogArgs,
+    log_reloader: LogReloader,
+) {
+    while reload.changed().await.is_ok() {
+        let file =
+            ConfigFile::load(&path).and_then(|file| file.with_env(|name| std::env::var(name).ok()));
+        let file = match file {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::error!("Failed to reload the configuration: {}", e);
+                continue;
+            }
+        };
+        let log = log_args(&cli, &file);
+        match log_reloader.reload(&log) {
+            Ok(()) => tracing::info!(
+                "Reloaded the log settings of {} (log_level: {}, log_filter: {})",
+                path.display(),
+                log.log_level.as_deref().unwrap_or("default"),
+                log.log_filter.as_deref().unwrap_or("none"),
+            ),
+            Err(e) => tracing::error!(
+                "Failed to reload the configuration: invalid log_filter: {}",
+                e
+            ),
+        }
+    }
+}
@@ -4,7 +4,11 @@ use serde::{Deserialize, Serialize};
 
 use super::{
     error::RoomError,
-    value_object::{ClientId, MessageContent, RoomId, Timestamp},
+    value_object::{
+        Activity, BridgeKind, ClientId, CronSchedule, DisplayName, Locale, MessageContent,
+        MessagePolicy, MessageTag, PollOption, RoomClass, RoomId, RoomSlug, SequenceNumber,
+        Timestamp,
+    },
 };
 
 /// Default maximum number of participants allowed in a room
@@ -28,6 +32,24 @@ pub struct Room {
     pub participant_capacity: usize,
     /// Maximum number of messages allowed (default: 100)
     pub message_capacity: usize,
+    /// Sequence number of the latest message (0 if no message has been sent)
+    #[serde(default)]
+    pub last_seq: SequenceNumber,
+    /// Human-friendly name usable instead of the ID (none by default)
+    #[serde(default)]
+    pub slug: Option<RoomSlug>,
+    /// Language of the system-generated texts of the room
+    #[serde(default)]
+    pub locale: Locale,
+    /// Storage requirements of the room (decides where it is stored)
+    #[serde(default)]
+    pub class: RoomClass,
+    /// Rules for the messages sent to the room
+    #[serde(default)]
+    pub message_policy: MessagePolicy,
+    /// Whether an admin froze the room (messages are rejected; the history stays readable)
+    #[serde(default)]
+    pub frozen: bool,
 }
 
 impl Room {
@@ -40,6 +62,12 @@ impl Room {
             created_at,
             participant_capacity: DEFAULT_PARTICIPANT_CAPACITY,
             message_capacity: DEFAULT_MESSAGE_CAPACITY,
+            last_seq: SequenceNumber::default(),
+            slug: None,
+            locale: Locale::default(),
+            class: RoomClass::default(),
+            message_policy: MessagePolicy::default(),
+            frozen: false,
         }
     }
 
@@ -57,6 +85,12 @@ impl Room {
             created_at,
             participant_capacity,
             message_capacity,
+            last_seq: SequenceNumber::default(),
+            slug: None,
+            locale: Locale::default(),
+            class: RoomClass::default(),
+            message_policy: MessagePolicy::default(),
+            frozen: false,
         }
     }
 
@@ -81,26 +115,328 @@ impl Room {
         self.participants.retain(|p| &p.id != participant_id);
     }
 
+    /// Set (or clear, with `None`) the display name of a participant
+    ///
+    /// Returns `false` if the participant is not in the room.
+    pub fn rename_participant(
+        &mut self,
+        participant_id: &ClientId,
+        display_name: Option<DisplayName>,
+    ) -> bool {
+        match self
+            .participants
+            .iter_mut()
+            .find(|p| &p.id == participant_id)
+        {
+            Some(participant) => {
+                participant.display_name = display_name;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set (or clear, with `None`) the activity of a participant
+    ///
+    /// Returns `false` if the participant is not in the room.
+    pub fn set_activity(&mut self, participant_id: &ClientId, activity: Option<Activity>) -> bool {
+        match self
+            .participants
+            .iter_mut()
+            .find(|p| &p.id == participant_id)
+        {
+            Some(participant) => {
+                participant.activity = activity;
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Add a message to the room history
     ///
+    /// The message is given the sequence number following the latest message.
+    ///
     /// # Errors
     ///
-    /// Returns `RoomError::MessageCapacityExceeded` if the room message history is at full capacity
-    pub fn add_message(&mut self, message: ChatMessage) -> Result<(), RoomError> {
+    /// Returns `RoomError::RoomFrozen` if the room is frozen, and
+    /// `RoomError::MessageCapacityExceeded` if the room message history is at full capacity
+    pub fn add_message(&mut self, mut message: ChatMessage) -> Result<SequenceNumber, RoomError> {
+        if self.frozen {
+            return Err(RoomError::RoomFrozen);
+        }
         if self.messages.len() >= self.message_capacity {
             return Err(RoomError::MessageCapacityExceeded {
                 capacity: self.message_capacity,
                 current: self.messages.len(),
             });
         }
+        self.last_seq = self.last_seq.next();
+        message.seq = self.last_seq;
         self.messages.push(message);
-        Ok(())
+        Ok(self.last_seq)
+    }
+
+    /// Get the messages with a sequence number greater than `seq`, oldest first
+    pub fn messages_since(&self, seq: SequenceNumber) -> &[ChatMessage] {
+        let start = self.messages.partition_point(|message| message.seq <= seq);
+        &self.messages[start..]
+    }
+
+    /// Attach tags to the message with the given sequence number
+    ///
+    /// Tags already on the message are kept; duplicates are not added twice.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the message is not in the history (never sent or evicted)
+    pub fn tag_message(&mut self, seq: SequenceNumber, tags: Vec<MessageTag>) -> bool {
+        let Ok(index) = self
+            .messages
+            .binary_search_by_key(&seq, |message| message.seq)
+        else {
+            return false;
+        };
+        let message = &mut self.messages[index];
+        for tag in tags {
+            if !message.tags.contains(&tag) {
+                message.tags.push(tag);
+            }
+        }
+        true
+    }
+
+    /// Turn the message with the given sequence number into a poll on `options`
+    ///
+    /// The message content is the question. A poll already on the message is replaced.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the message is not in the history (never sent or evicted)
+    pub fn attach_poll(&mut self, seq: SequenceNumber, options: Vec<PollOption>) -> bool {
+        let Ok(index) = self
+            .messages
+            .binary_search_by_key(&seq, |message| message.seq)
+        else {
+            return false;
+        };
+        self.messages[index].poll = Some(Poll::new(options));
+        true
+    }
+
+    /// Record where the message with the given sequence number was forwarded from
+    ///
+    /// # Returns
+    ///
+    /// `false` if the message is not in the history (never sent or evicted)
+    pub fn mark_forwarded(&mut self, seq: SequenceNumber, origin: ForwardedFrom) -> bool {
+        let Ok(index) = self
+            .messages
+            .binary_search_by_key(&seq, |message| message.seq)
+        else {
+            return false;
+        };
+        self.messages[index].forwarded_from = Some(origin);
+        true
+    }
+
+    /// Get the message with the given sequence number (`None` if not in the history)
+    pub fn message(&self, seq: SequenceNumber) -> Option<&ChatMessage> {
+        self.messages
+            .binary_search_by_key(&seq, |message| message.seq)
+            .ok()
+            .map(|index| &self.messages[index])
+    }
+
+    /// Record a vote on the poll of the message with the given sequence number
+    ///
+    /// # Returns
+    ///
+    /// The poll with the vote counted, or `None` if the message is not in the history
+    ///
+    /// # Errors
+    ///
+    /// Returns `RoomError::NotAPoll` if the message is not a poll, or
+    /// `RoomError::UnknownPollOption` if the poll has no such option
+    pub fn vote(
+        &mut self,
+        seq: SequenceNumber,
+        voter: ClientId,
+        option: usize,
+    ) -> Result<Option<Poll>, RoomError> {
+        let Ok(index) = self
+            .messages
+            .binary_search_by_key(&seq, |message| message.seq)
+        else {
+            return Ok(None);
+        };
+        let poll = self.messages[index]
+            .poll
+            .as_mut()
+            .ok_or(RoomError::NotAPoll { seq: seq.value() })?;
+        if option >= poll.options.len() {
+            return Err(RoomError::UnknownPollOption {
+                seq: seq.value(),
+                option,
+                options: poll.options.len(),
+            });
+        }
+        poll.vote(voter, option);
+        Ok(Some(poll.clone()))
+    }
+
+    /// Delete a single message (e.g. on moderation)
+    ///
+    /// The sequence number of the deleted message is not reused.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the message is not in the history
+    pub fn delete_message(&mut self, seq: SequenceNumber) -> bool {
+        let Ok(index) = self
+            .messages
+            .binary_search_by_key(&seq, |message| message.seq)
+        else {
+            return false;
+        };
+        self.messages.remove(index);
+        true
+    }
+
+    /// Erase every trace of a client: its participant record, all of its messages and its votes
+    ///
+    /// Sequence numbers of the erased messages are not reused.
+    ///
+    /// # Returns
+    ///
+    /// The sequence numbers of the erased messages, oldest first
+    pub fn erase_client(&mut self, client_id: &ClientId) -> Vec<SequenceNumber> {
+        self.remove_participant(client_id);
+        let erased = self
+            .messages
+            .iter()
+            .filter(|message| &message.from == client_id)
+            .map(|message| message.seq)
+            .collect();
+        self.messages.retain(|message| &message.from != client_id);
+        for poll in self
+            .messages
+            .iter_mut()
+            .filter_map(|message| message.poll.as_mut())
+        {
+            poll.retract(client_id);
+        }
+        erased
+    }
+
+    /// Get the latest `limit` messages, oldest first
+    pub fn recent_messages(&self, limit: usize) -> &[ChatMessage] {
+        &self.messages[self.messages.len().saturating_sub(limit)..]
+    }
+
+    /// Get the latest `limit` messages sent before `before` (all messages if `None`), oldest first
+    pub fn messages_before(&self, before: Option<Timestamp>, limit: usize) -> Vec<ChatMessage> {
+        let mut messages: Vec<ChatMessage> = self
+            .messages
+            .iter()
+            .rev()
+            .filter(|message| before.is_none_or(|before| message.timestamp < before))
+            .take(limit)
+            .cloned()
+            .collect();
+        messages.reverse();
+        messages
+    }
+
+    /// Get the room metadata without the participant list and message history
+    pub fn metadata(&self) -> RoomMetadata {
+        RoomMetadata {
+            id: self.id.clone(),
+            slug: self.slug.clone(),
+            locale: self.locale,
+            class: self.class,
+            created_at: self.created_at,
+            participant_count: self.participants.len(),
+            participant_capacity: self.participant_capacity,
+            max_message_length: self.message_policy.max_length,
+            frozen: self.frozen,
+            message_count: self.messages.len(),
+            last_seq: self.last_seq,
+            last_activity_at: self.last_activity_at(),
+        }
+    }
+
+    /// Approximate memory used by the message history, in bytes
+    pub fn history_bytes(&self) -> usize {
+        self.messages.iter().map(ChatMessage::approx_size).sum()
+    }
+
+    /// Evict the oldest messages until the history uses at most `max_bytes`
+    ///
+    /// Sequence numbers are not reused; evicted messages can no longer be backfilled.
+    ///
+    /// # Returns
+    ///
+    /// The number of evicted messages
+    pub fn evict_history(&mut self, max_bytes: usize) -> usize {
+        let mut bytes = self.history_bytes();
+        let evicted = self
+            .messages
+            .iter()
+            .take_while(|message| {
+                let over = bytes > max_bytes;
+                bytes -= message.approx_size();
+                over
+            })
+            .count();
+        self.messages.drain(..evicted);
+        evicted
     }
 
     /// Get a participant by ID
     pub fn get_participant(&self, participant_id: &ClientId) -> Option<&Participant> {
         self.participants.iter().find(|p| &p.id == participant_id)
     }
+
+    /// Get the timestamp of the latest activity in the room
+    ///
+    /// The latest of the room creation, participant connections, and messages.
+    pub fn last_activity_at(&self) -> Timestamp {
+        let last_joined = self.participants.iter().map(|p| p.connected_at);
+        let last_message = self.messages.iter().map(|m| m.timestamp);
+        last_joined
+            .chain(last_message)
+            .fold(self.created_at, Timestamp::max)
+    }
+}
+
+/// Room metadata, a projection of [`Room`] without the participant list and message history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomMetadata {
+    /// Room identifier
+    pub id: RoomId,
+    /// Human-friendly name usable instead of the ID
+    pub slug: Option<RoomSlug>,
+    /// Language of the system-generated texts of the room
+    pub locale: Locale,
+    /// Storage requirements of the room
+    pub class: RoomClass,
+    /// Timestamp when the room was created
+    pub created_at: Timestamp,
+    /// Number of participants currently in the room
+    pub participant_count: usize,
+    /// Maximum number of participants
+    pub participant_capacity: usize,
+    /// Maximum length of a message in bytes
+    pub max_message_length: usize,
+    /// Whether messages are rejected
+    pub frozen: bool,
+    /// Number of messages in the history
+    pub message_count: usize,
+    /// Sequence number of the latest message (0 if no message has been sent)
+    pub last_seq: SequenceNumber,
+    /// Timestamp of the latest activity in the room
+    pub last_activity_at: Timestamp,
 }
 
 /// Represents a participant in a chat room
@@ -110,35 +446,237 @@ pub struct Participant {
     pub id: ClientId,
     /// Timestamp when the participant connected
     pub connected_at: Timestamp,
+    /// Name the participant is shown as (the client ID if not set)
+    #[serde(default)]
+    pub display_name: Option<DisplayName>,
+    /// What the participant is doing, as published by the client (cleared on reconnect)
+    #[serde(default)]
+    pub activity: Option<Activity>,
 }
 
 impl Participant {
     /// Create a new participant
     pub fn new(id: ClientId, connected_at: Timestamp) -> Self {
-        Self { id, connected_at }
+        Self {
+            id,
+            connected_at,
+            display_name: None,
+            activity: None,
+        }
     }
 }
 
 /// Represents a chat message in the domain model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
+    /// Sequence number in the room (assigned when added to the room)
+    #[serde(default)]
+    pub seq: SequenceNumber,
     /// Sender's participant ID
     pub from: ClientId,
     /// Message content
     pub content: MessageContent,
     /// Timestamp when the message was sent
     pub timestamp: Timestamp,
+    /// Metadata attached by the message analyzer after the message was stored
+    #[serde(default)]
+    pub tags: Vec<MessageTag>,
+    /// Poll asking the content as its question (`None` for a plain message)
+    #[serde(default)]
+    pub poll: Option<Poll>,
+    /// Where the message was first posted if it was forwarded from another room
+    #[serde(default)]
+    pub forwarded_from: Option<ForwardedFrom>,
 }
 
 impl ChatMessage {
     /// Create a new chat message
     pub fn new(from: ClientId, content: MessageContent, timestamp: Timestamp) -> Self {
         Self {
+            seq: SequenceNumber::default(),
             from,
             content,
             timestamp,
+            tags: Vec::new(),
+            poll: None,
+            forwarded_from: None,
         }
     }
+
+    /// Approximate memory used by the message, in bytes
+    pub fn approx_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.from.as_str().len()
+            + self.content.as_str().len()
+            + self
+                .tags
+                .iter()
+                .map(|tag| std::mem::size_of::<MessageTag>() + tag.as_str().len())
+                .sum::<usize>()
+            + self.poll.as_ref().map_or(0, Poll::approx_size)
+            + self.forwarded_from.as_ref().map_or(0, |origin| {
+                origin.room_id.as_str().len() + origin.from.as_str().len()
+            })
+    }
+}
+
+/// Provenance of a message copied from another room
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForwardedFrom {
+    /// Room the message was first posted in
+    pub room_id: RoomId,
+    /// Author of the original message
+    pub from: ClientId,
+    /// Timestamp of the original message
+    pub timestamp: Timestamp,
+}
+
+/// Poll attached to a chat message, tallied by the server
+///
+/// Each client has at most one vote; voting again moves it to the new option.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Poll {
+    /// Options to vote for, in the order they are shown
+    pub options: Vec<PollOption>,
+    /// Votes cast, in the order the clients first voted
+    #[serde(default)]
+    pub votes: Vec<PollVote>,
+}
+
+impl Poll {
+    /// Minimum number of options of a poll
+    pub const MIN_OPTIONS: usize = 2;
+
+    /// Maximum number of options of a poll
+    pub const MAX_OPTIONS: usize = 10;
+
+    /// Create a poll on `options` without votes
+    pub fn new(options: Vec<PollOption>) -> Self {
+        Self {
+            options,
+            votes: Vec::new(),
+        }
+    }
+
+    /// Record the vote of a client, replacing its previous vote
+    ///
+    /// The option is not checked; see [`Room::vote`].
+    pub fn vote(&mut self, voter: ClientId, option: usize) {
+        match self.votes.iter_mut().find(|vote| vote.voter == voter) {
+            Some(vote) => vote.option = option,
+            None => self.votes.push(PollVote { voter, option }),
+        }
+    }
+
+    /// Remove the vote of a client, if any
+    pub fn retract(&mut self, voter: &ClientId) {
+        self.votes.retain(|vote| &vote.voter != voter);
+    }
+
+    /// Number of votes for each option, in the order of the options
+    pub fn tally(&self) -> Vec<usize> {
+        let mut tally = vec![0; self.options.len()];
+        for vote in &self.votes {
+            if let Some(count) = tally.get_mut(vote.option) {
+                *count += 1;
+            }
+        }
+        tally
+    }
+
+    /// Approximate memory used by the poll, in bytes
+    fn approx_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self
+                .options
+                .iter()
+                .map(|option| std::mem::size_of::<PollOption>() + option.as_str().len())
+                .sum::<usize>()
+            + self
+                .votes
+                .iter()
+                .map(|vote| std::mem::size_of::<PollVote>() + vote.voter.as_str().len())
+                .sum::<usize>()
+    }
+}
+
+/// Vote of a client on a poll
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PollVote {
+    /// Client that voted
+    pub voter: ClientId,
+    /// Index of the option voted for
+    pub option: usize,
+}
+
+/// Integration configured for a room (webhooks and bridge settings)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Integration {
+    /// Integration identifier, unique within the server
+    pub id: u64,
+    /// Room the integration belongs to
+    pub room_id: RoomId,
+    /// Kind-specific settings
+    pub kind: IntegrationKind,
+    /// Timestamp when the integration was created
+    pub created_at: Timestamp,
+}
+
+/// Settings of an integration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrationKind {
+    /// Chat messages of the room are posted to the URL
+    OutgoingWebhook { url: String },
+    /// Requests with the token post messages into the room
+    IncomingWebhook { token: String },
+    /// The room is bridged to the target of another chat service
+    Bridge { bridge: BridgeKind, target: String },
+    /// The text is sent to each participant joining the room, only to them
+    WelcomeMessage { text: MessageContent },
+    /// Clients joining the room wait until a moderator approves them
+    JoinApproval,
+    /// The text is posted to the room whenever the schedule (JST) fires
+    RecurringAnnouncement {
+        schedule: CronSchedule,
+        text: MessageContent,
+    },
+}
+
+/// Message a client starred to find it again later
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Star {
+    /// Client that starred the message
+    pub client_id: ClientId,
+    /// Room of the message
+    pub room_id: RoomId,
+    /// Sequence number of the message in the room
+    pub seq: SequenceNumber,
+    /// Timestamp when the message was starred
+    pub starred_at: Timestamp,
+}
+
+/// Short-lived room spawned from a parent room for some of its participants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakout {
+    /// The breakout room
+    pub room_id: RoomId,
+    /// Room the breakout was spawned from
+    pub parent_id: RoomId,
+    /// Name shown in the parent room
+    pub name: String,
+    /// Participants of the parent room who opted in (anyone may join if empty)
+    pub members: Vec<ClientId>,
+    /// Timestamp when the breakout was created
+    pub created_at: Timestamp,
+    /// Timestamp when the breakout was archived after inactivity (`None` while open)
+    pub archived_at: Option<Timestamp>,
+}
+
+impl Breakout {
+    /// Whether the client may join the breakout room
+    pub fn admits(&self, client_id: &ClientId) -> bool {
+        self.members.is_empty() || self.members.contains(client_id)
+    }
 }
 
 #[cfg(test)]
@@ -240,6 +778,212 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_room_add_message_assigns_sequence_numbers() {
+        // テスト項目: 追加したメッセージには 1 から連続するシーケンス番号が振られる
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let message = ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(3000),
+        );
+
+        // when (操作):
+        let first = room.add_message(message.clone()).unwrap();
+        let second = room.add_message(message).unwrap();
+
+        // then (期待する結果):
+        assert_eq!(first, SequenceNumber::new(1));
+        assert_eq!(second, SequenceNumber::new(2));
+        assert_eq!(room.last_seq, SequenceNumber::new(2));
+        assert_eq!(room.messages[1].seq, SequenceNumber::new(2));
+    }
+
+    #[test]
+    fn test_room_messages_since() {
+        // テスト項目: 指定したシーケンス番号より後のメッセージだけが古い順に返される
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        for content in ["first", "second", "third"] {
+            let message = ChatMessage::new(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new(content.to_string()).unwrap(),
+                Timestamp::new(3000),
+            );
+            room.add_message(message).unwrap();
+        }
+
+        // when (操作):
+        let missed = room.messages_since(SequenceNumber::new(1));
+
+        // then (期待する結果):
+        let contents: Vec<&str> = missed.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["second", "third"]);
+        assert!(room.messages_since(SequenceNumber::new(3)).is_empty());
+        assert_eq!(room.messages_since(SequenceNumber::default()).len(), 3);
+    }
+
+    #[test]
+    fn test_room_tag_message() {
+        // テスト項目: シーケンス番号で指定したメッセージにタグが重複なく付き、履歴に無い番号は失敗する
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        for content in ["first", "second"] {
+            let message = ChatMessage::new(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new(content.to_string()).unwrap(),
+                Timestamp::new(3000),
+            );
+            room.add_message(message).unwrap();
+        }
+        let tag = |tag: &str| MessageTag::new(tag.to_string()).unwrap();
+
+        // when (操作):
+        let tagged = room.tag_message(SequenceNumber::new(2), vec![tag("a"), tag("b")]);
+        let retagged = room.tag_message(SequenceNumber::new(2), vec![tag("b"), tag("c")]);
+        let missing = room.tag_message(SequenceNumber::new(3), vec![tag("a")]);
+
+        // then (期待する結果):
+        assert!(tagged && retagged);
+        assert!(!missing);
+        assert!(room.messages[0].tags.is_empty());
+        assert_eq!(room.messages[1].tags, vec![tag("a"), tag("b"), tag("c")]);
+    }
+
+    #[test]
+    fn test_room_vote_on_poll() {
+        // テスト項目: 投票はクライアントごとに 1 票で集計され、投票でないメッセージや無い選択肢には投票できない
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        for content in ["Lunch?", "plain"] {
+            let message = ChatMessage::new(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new(content.to_string()).unwrap(),
+                Timestamp::new(3000),
+            );
+            room.add_message(message).unwrap();
+        }
+        let options = ["Ramen", "Sushi"]
+            .map(|option| PollOption::new(option.to_string()).unwrap())
+            .to_vec();
+        let client = |id: &str| ClientId::new(id.to_string()).unwrap();
+        let poll = SequenceNumber::new(1);
+
+        // when (操作):
+        let attached = room.attach_poll(poll, options);
+        room.vote(poll, client("alice"), 0).unwrap();
+        room.vote(poll, client("bob"), 0).unwrap();
+        let changed = room.vote(poll, client("alice"), 1).unwrap().unwrap();
+        let unknown_option = room.vote(poll, client("bob"), 2);
+        let not_a_poll = room.vote(SequenceNumber::new(2), client("bob"), 0);
+        let missing = room.vote(SequenceNumber::new(3), client("bob"), 0);
+
+        // then (期待する結果):
+        assert!(attached);
+        assert_eq!(changed.tally(), vec![1, 1]);
+        assert_eq!(
+            unknown_option,
+            Err(RoomError::UnknownPollOption {
+                seq: 1,
+                option: 2,
+                options: 2
+            })
+        );
+        assert_eq!(not_a_poll, Err(RoomError::NotAPoll { seq: 2 }));
+        assert_eq!(missing, Ok(None));
+        room.erase_client(&client("bob"));
+        assert_eq!(room.messages[0].poll.as_ref().unwrap().tally(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_room_mark_forwarded() {
+        // テスト項目: 履歴にあるメッセージに転送元が記録され、履歴に無いメッセージには記録されない
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let message = ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Forwarded".to_string()).unwrap(),
+            Timestamp::new(3000),
+        );
+        let seq = room.add_message(message).unwrap();
+        let origin = ForwardedFrom {
+            room_id: RoomIdFactory::generate().unwrap(),
+            from: ClientId::new("bob".to_string()).unwrap(),
+            timestamp: Timestamp::new(2000),
+        };
+
+        // when (操作):
+        let marked = room.mark_forwarded(seq, origin.clone());
+        let missing = room.mark_forwarded(SequenceNumber::new(2), origin.clone());
+
+        // then (期待する結果):
+        assert!(marked);
+        assert!(!missing);
+        assert_eq!(room.message(seq).unwrap().forwarded_from, Some(origin));
+        assert!(room.message(SequenceNumber::new(2)).is_none());
+    }
+
+    #[test]
+    fn test_room_erase_client() {
+        // テスト項目: クライアントの参加者情報と全てのメッセージが削除され、番号は再利用されない
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        room.add_participant(Participant::new(alice.clone(), Timestamp::new(1000)))
+            .unwrap();
+        room.add_participant(Participant::new(bob.clone(), Timestamp::new(1000)))
+            .unwrap();
+        for from in [&alice, &bob, &alice] {
+            let message = ChatMessage::new(
+                from.clone(),
+                MessageContent::new("hi".to_string()).unwrap(),
+                Timestamp::new(3000),
+            );
+            room.add_message(message).unwrap();
+        }
+
+        // when (操作):
+        let erased = room.erase_client(&alice);
+
+        // then (期待する結果):
+        assert_eq!(erased, vec![SequenceNumber::new(1), SequenceNumber::new(3)]);
+        assert!(room.get_participant(&alice).is_none());
+        assert!(room.get_participant(&bob).is_some());
+        assert_eq!(room.messages.len(), 1);
+        assert_eq!(room.messages[0].from, bob);
+        assert_eq!(room.last_seq, SequenceNumber::new(3));
+    }
+
+    #[test]
+    fn test_room_evict_history() {
+        // テスト項目: 履歴のメモリ使用量が上限以下になるまで古いメッセージから削除される
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        for content in ["first", "second", "third"] {
+            let message = ChatMessage::new(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new(content.to_string()).unwrap(),
+                Timestamp::new(3000),
+            );
+            room.add_message(message).unwrap();
+        }
+        let newest = room.messages[2].approx_size();
+
+        // when (操作):
+        let unchanged = room.evict_history(room.history_bytes());
+        let evicted = room.evict_history(newest);
+
+        // then (期待する結果): シーケンス番号は振り直されない
+        assert_eq!(unchanged, 0);
+        assert_eq!(evicted, 2);
+        assert_eq!(room.messages.len(), 1);
+        assert_eq!(room.messages[0].seq, SequenceNumber::new(3));
+        assert_eq!(room.history_bytes(), newest);
+        assert_eq!(room.last_seq, SequenceNumber::new(3));
+    }
+
     #[test]
     fn test_room_get_participant() {
         // テスト項目: ID で参加者を取得できる
@@ -354,6 +1098,29 @@ mod tests {
         assert_eq!(room.messages.len(), 2);
     }
 
+    #[test]
+    fn test_room_frozen_rejects_messages() {
+        // テスト項目: 凍結したルームはメッセージを拒否し、凍結を解除すると再び受け付ける
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let message = ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(1000),
+        );
+
+        // when (操作):
+        room.frozen = true;
+        let frozen = room.add_message(message.clone());
+        room.frozen = false;
+        let thawed = room.add_message(message);
+
+        // then (期待する結果):
+        assert_eq!(frozen, Err(RoomError::RoomFrozen));
+        assert_eq!(thawed, Ok(SequenceNumber::new(1)));
+        assert_eq!(room.messages.len(), 1);
+    }
+
     #[test]
     fn test_room_default_capacities() {
         // テスト項目: デフォルトの上限値が正しく設定される
@@ -364,4 +1131,30 @@ mod tests {
         assert_eq!(room.participant_capacity, DEFAULT_PARTICIPANT_CAPACITY);
         assert_eq!(room.message_capacity, DEFAULT_MESSAGE_CAPACITY);
     }
+
+    #[test]
+    fn test_room_last_activity_at() {
+        // テスト項目: 最終アクティビティ時刻は作成・入室・メッセージのうち最新の時刻になる
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let created_only = room.last_activity_at();
+        room.add_participant(Participant::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            Timestamp::new(3000),
+        ))
+        .unwrap();
+        room.add_message(ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(2000),
+        ))
+        .unwrap();
+
+        // when (操作):
+        let last_activity = room.last_activity_at();
+
+        // then (期待する結果):
+        assert_eq!(created_only, Timestamp::new(1000));
+        assert_eq!(last_activity, Timestamp::new(3000));
+    }
 }
@@ -25,6 +25,30 @@ pub enum ValueObjectError {
     #[error("RoomId must be a valid UUID format (got: {0})")]
     RoomIdInvalidFormat(String),
 
+    /// RoomSlug invalid format error
+    #[error(
+        "RoomSlug must be 1-{max} lowercase letters, digits or hyphens, not starting or ending with a hyphen (got: {slug})"
+    )]
+    RoomSlugInvalidFormat { slug: String, max: usize },
+
+    /// Locale not supported by the server
+    #[error("Locale must be one of {supported} (got: {locale})")]
+    UnsupportedLocale { locale: String, supported: String },
+
+    /// Room class not supported by the server
+    #[error("Room class must be one of {supported} (got: {class})")]
+    UnsupportedRoomClass { class: String, supported: String },
+
+    /// Bridge kind not supported by the server
+    #[error("Bridge must be one of {supported} (got: {bridge})")]
+    UnsupportedBridge { bridge: String, supported: String },
+
+    /// CronSchedule invalid format error
+    #[error(
+        "Schedule must be a cron expression with five fields (minute hour day month weekday) (got: {schedule})"
+    )]
+    CronScheduleInvalidFormat { schedule: String },
+
     /// MessageContent validation error
     #[error("MessageContent cannot be empty")]
     MessageContentEmpty,
@@ -32,6 +56,22 @@ pub enum ValueObjectError {
     /// MessageContent too long error
     #[error("MessageContent cannot exceed {max} characters (got {actual})")]
     MessageContentTooLong { max: usize, actual: usize },
+
+    /// MessageTag invalid format error
+    #[error("MessageTag must be 1-{max} characters without whitespace (got: {tag})")]
+    MessageTagInvalidFormat { tag: String, max: usize },
+
+    /// PollOption invalid format error
+    #[error("PollOption must be 1-{max} characters (got: {option:?})")]
+    PollOptionInvalidFormat { option: String, max: usize },
+
+    /// DisplayName invalid format error
+    #[error("DisplayName must be 1-{max} characters without control characters (got: {name:?})")]
+    DisplayNameInvalidFormat { name: String, max: usize },
+
+    /// Activity invalid format error
+    #[error("Activity must be 1-{max} characters without line breaks (got: {activity:?})")]
+    ActivityInvalidFormat { activity: String, max: usize },
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -48,6 +88,30 @@ pub enum RoomError {
     /// Message capacity exceeded error
     #[error("Message capacity exceeded: maximum {capacity} messages allowed (current: {current})")]
     MessageCapacityExceeded { capacity: usize, current: usize },
+
+    /// Room frozen by an admin error
+    #[error("Room is frozen: messages are not accepted")]
+    RoomFrozen,
+
+    /// The message voted on is not a poll
+    #[error("Message {seq} is not a poll")]
+    NotAPoll { seq: u64 },
+
+    /// The option voted for is not one of the poll's
+    #[error("Poll {seq} has {options} options (got option {option})")]
+    UnknownPollOption {
+        seq: u64,
+        option: usize,
+        options: usize,
+    },
+}
+
+/// Errors related to the rooms a client is in
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum MembershipError {
+    /// Client is already in the maximum number of rooms
+    #[error("Too many rooms: a client may be in at most {limit} rooms at the same time")]
+    TooManyRooms { limit: usize },
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -68,6 +132,26 @@ pub enum RepositoryError {
     /// Room not found error
     #[error("Room not found")]
     RoomNotFound,
+
+    /// A room with the same ID already exists
+    #[error("Room already exists: {0}")]
+    RoomAlreadyExists(String),
+
+    /// The room cannot be deleted (the repository's own room)
+    #[error("Room cannot be deleted: {0}")]
+    RoomNotDeletable(String),
+
+    /// Message not found (never sent or evicted from the history)
+    #[error("Message not found: {0}")]
+    MessageNotFound(u64),
+
+    /// The change could not be persisted
+    #[error("Storage error: {0}")]
+    Storage(String),
+
+    /// The change breaks a rule of the room (e.g. its capacity)
+    #[error(transparent)]
+    Room(#[from] RoomError),
 }
 
 // ------------------------------------------------------------------------------------------------
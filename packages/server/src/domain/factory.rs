@@ -1,6 +1,6 @@
 //! Domain factories for creating domain entities and value objects.
 
-use super::{RoomId, error::ValueObjectError};
+use super::{ClientId, RoomId, error::ValueObjectError, value_object::GUEST_ID_PREFIX};
 
 /// Factory for generating RoomId instances.
 ///
@@ -25,6 +25,32 @@ impl RoomIdFactory {
     }
 }
 
+/// Factory for generating the ClientId of guests.
+///
+/// Guests connect without a client ID; the server gives them a short, readable one
+/// such as `guest-7f3a`.
+pub struct GuestIdFactory;
+
+impl GuestIdFactory {
+    /// Generate a guest ClientId with 4 random hexadecimal digits.
+    ///
+    /// IDs are short and may collide with a connected guest; callers retry on a duplicate.
+    pub fn generate() -> Result<ClientId, ValueObjectError> {
+        let uuid = uuid::Uuid::new_v4().simple().to_string();
+        ClientId::new(format!("{}{}", GUEST_ID_PREFIX, &uuid[..4]))
+    }
+}
+
+/// Factory for generating the tokens of incoming webhooks.
+pub struct WebhookTokenFactory;
+
+impl WebhookTokenFactory {
+    /// Generate a token of 32 random hexadecimal digits.
+    pub fn generate() -> String {
+        uuid::Uuid::new_v4().simple().to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,6 +70,19 @@ mod tests {
         assert_eq!(id_str.len(), 36); // UUID v4 の標準長（ハイフン含む）
     }
 
+    #[test]
+    fn test_guest_id_factory_generate() {
+        // テスト項目: GuestIdFactory::generate() で guest- に続く 4 桁の 16 進数の ID を生成できる
+        // when (操作):
+        let client_id = GuestIdFactory::generate().unwrap();
+
+        // then (期待する結果):
+        let suffix = client_id.as_str().strip_prefix("guest-").unwrap();
+        assert_eq!(suffix.len(), 4);
+        assert!(suffix.chars().all(|c| c.is_ascii_hexdigit()));
+        assert!(client_id.is_guest());
+    }
+
     #[test]
     fn test_room_id_factory_generate_uniqueness() {
         // テスト項目: RoomIdFactory::generate() は毎回異なる ID を生成する
@@ -0,0 +1,122 @@
+//! Policy bounding the number of rooms a client is in at the same time.
+
+use std::collections::HashMap;
+
+use super::{
+    error::MembershipError,
+    value_object::{ClientId, RoomId},
+};
+
+/// Default maximum number of rooms a client may be in at the same time
+pub const DEFAULT_MAX_ROOMS_PER_CLIENT: usize = 10;
+
+/// Rooms each client is currently in
+///
+/// A client may hold several connections to the same room (multiplexed connections or a
+/// takeover in progress); the room counts once and is left when its last connection leaves.
+#[derive(Debug)]
+pub struct RoomMemberships {
+    /// Maximum number of rooms per client
+    limit: usize,
+    /// Open connections of each client per room
+    rooms: HashMap<ClientId, HashMap<RoomId, usize>>,
+}
+
+impl RoomMemberships {
+    /// Create a policy allowing each client in at most `limit` rooms
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            rooms: HashMap::new(),
+        }
+    }
+
+    /// Maximum number of rooms per client
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Number of rooms the client is in
+    pub fn count(&self, client_id: &ClientId) -> usize {
+        self.rooms.get(client_id).map_or(0, HashMap::len)
+    }
+
+    /// Check whether the client has a connection to the room
+    pub fn contains(&self, client_id: &ClientId, room_id: &RoomId) -> bool {
+        self.rooms
+            .get(client_id)
+            .is_some_and(|rooms| rooms.contains_key(room_id))
+    }
+
+    /// Record a connection of the client to the room
+    ///
+    /// Another connection to a room the client is already in is always accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MembershipError::TooManyRooms` if the client is already in `limit` other rooms
+    pub fn join(&mut self, client_id: &ClientId, room_id: &RoomId) -> Result<(), MembershipError> {
+        let rooms = self.rooms.entry(client_id.clone()).or_default();
+        if !rooms.contains_key(room_id) && rooms.len() >= self.limit {
+            if rooms.is_empty() {
+                self.rooms.remove(client_id);
+            }
+            return Err(MembershipError::TooManyRooms { limit: self.limit });
+        }
+        *rooms.entry(room_id.clone()).or_default() += 1;
+        Ok(())
+    }
+
+    /// Record that a connection of the client to the room closed
+    pub fn leave(&mut self, client_id: &ClientId, room_id: &RoomId) {
+        let Some(rooms) = self.rooms.get_mut(client_id) else {
+            return;
+        };
+        if let Some(connections) = rooms.get_mut(room_id) {
+            *connections -= 1;
+            if *connections == 0 {
+                rooms.remove(room_id);
+            }
+        }
+        if rooms.is_empty() {
+            self.rooms.remove(client_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::RoomIdFactory;
+
+    #[test]
+    fn test_join_is_limited_per_client() {
+        // テスト項目: クライアントごとに上限までのルームに参加でき、退出すると別のルームに参加できる
+        // given (前提条件):
+        let mut memberships = RoomMemberships::new(2);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let rooms: Vec<RoomId> = (0..3).map(|_| RoomIdFactory::generate().unwrap()).collect();
+
+        // when (操作):
+        memberships.join(&alice, &rooms[0]).unwrap();
+        memberships.join(&alice, &rooms[1]).unwrap();
+        let again = memberships.join(&alice, &rooms[1]);
+        let third = memberships.join(&alice, &rooms[2]);
+        let other_client = memberships.join(&bob, &rooms[2]);
+
+        // then (期待する結果):
+        assert_eq!(again, Ok(()));
+        assert_eq!(third, Err(MembershipError::TooManyRooms { limit: 2 }));
+        assert_eq!(other_client, Ok(()));
+        assert_eq!(memberships.count(&alice), 2);
+        assert!(memberships.contains(&alice, &rooms[1]));
+        assert!(!memberships.contains(&alice, &rooms[2]));
+
+        // 同じルームへの接続が残っている間はルームに参加したまま
+        memberships.leave(&alice, &rooms[1]);
+        assert!(memberships.join(&alice, &rooms[2]).is_err());
+        memberships.leave(&alice, &rooms[1]);
+        assert_eq!(memberships.join(&alice, &rooms[2]), Ok(()));
+    }
+}
@@ -0,0 +1,35 @@
+//! メッセージ分析の抽象化
+//!
+//! ## 責務
+//!
+//! MessageAnalyzer は「保存されたメッセージを分析してタグを付ける」責務を持ちます。
+//! 分析の方法（キーワード集計、感情分析、外部の分析サービスなど）は問いません。
+//!
+//! ## 設計判断
+//!
+//! 分析はメッセージの永続化とブロードキャストが完了した後に別のタスクで行います。
+//! 分析が遅くても配信は遅れず、付いたタグは Repository を通じて履歴に保存され、
+//! ルームの統計（`GET /api/v1/rooms/{room_id}/stats`）から集計できます。
+
+use async_trait::async_trait;
+
+use super::{ChatMessage, MessageTag};
+
+/// メッセージ分析の抽象化
+///
+/// ## 実装
+///
+/// - `KeywordAnalyzer`: 設定したキーワードの出現を数えてタグを付ける実装（`infrastructure/analyzer.rs`）
+#[async_trait]
+pub trait MessageAnalyzer: Send + Sync {
+    /// メッセージを分析し、付けるタグを返す
+    ///
+    /// # 引数
+    ///
+    /// - `message`: シーケンス番号が振られた保存済みのメッセージ（Domain Model）
+    ///
+    /// # 戻り値
+    ///
+    /// メッセージに付けるタグ（付けるものが無い場合は空）
+    async fn analyze(&self, message: &ChatMessage) -> Vec<MessageTag>;
+}
@@ -0,0 +1,197 @@
+//! メッセージフィルターの抽象化
+//!
+//! ## 責務
+//!
+//! MessageFilter は「送信されたメッセージを配信してよいか判定する」責務を持ちます。
+//! 判定の方法（禁止語、リンクの数、正規表現、外部のモデレーションサービスなど）は問いません。
+//!
+//! ## 設計判断
+//!
+//! フィルターはメッセージの採番・永続化・ブロードキャストの前に、設定した順に適用します
+//! （[`MessageFilterChain`]）。最初に拒否したフィルターで判定を終え、拒否したメッセージは
+//! 履歴に残さず、拒否の理由を送信者にのみ返します。
+//!
+//! ルームはメッセージのポリシー（[`MessagePolicy`]）で、サーバーのフィルターのうち適用する
+//! ものを名前で選べます（[`MessageFilterChain::for_policy`]）。
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{ClientId, MessageContent, MessagePolicy};
+
+/// フィルターがメッセージを拒否した理由
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageRejection {
+    /// 拒否したフィルターの名前（例: `max-links`）
+    pub filter: &'static str,
+    /// 送信者に返す拒否の理由
+    pub reason: String,
+}
+
+/// メッセージフィルターの抽象化
+///
+/// ## 実装
+///
+/// - `ProfanityFilter`: 設定した禁止語を含むメッセージを拒否する実装（`infrastructure/filter.rs`）
+/// - `MaxLinksFilter`: リンクが多すぎるメッセージを拒否する実装（`infrastructure/filter.rs`）
+/// - `RegexBlocklistFilter`: 正規表現に一致するメッセージを拒否する実装（`infrastructure/filter.rs`）
+#[async_trait]
+pub trait MessageFilter: Send + Sync {
+    /// フィルターの名前（例: `max-links`、拒否した理由の `filter` と同じ）
+    fn name(&self) -> &'static str;
+
+    /// メッセージを判定する
+    ///
+    /// # 引数
+    ///
+    /// - `from`: メッセージの送信者（Domain Model）
+    /// - `content`: メッセージの内容（Domain Model）
+    ///
+    /// # 戻り値
+    ///
+    /// 配信してよい場合は `Ok(())`、拒否する場合は `Err(MessageRejection)`
+    async fn check(
+        &self,
+        from: &ClientId,
+        content: &MessageContent,
+    ) -> Result<(), MessageRejection>;
+}
+
+/// 設定した順に適用するフィルターの連なり
+#[derive(Clone, Default)]
+pub struct MessageFilterChain {
+    filters: Vec<Arc<dyn MessageFilter>>,
+}
+
+impl MessageFilterChain {
+    /// フィルターの連なりを作成（`filters` の順に適用する）
+    pub fn new(filters: Vec<Arc<dyn MessageFilter>>) -> Self {
+        Self { filters }
+    }
+
+    /// ルームのポリシーが適用するフィルターだけの連なりを作成
+    pub fn for_policy(&self, policy: &MessagePolicy) -> Self {
+        Self {
+            filters: self
+                .filters
+                .iter()
+                .filter(|filter| policy.applies_filter(filter.name()))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// フィルターが 1 つも無いかどうか
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    /// フィルターを順に適用し、最初に拒否したフィルターの理由を返す
+    pub async fn check(
+        &self,
+        from: &ClientId,
+        content: &MessageContent,
+    ) -> Result<(), MessageRejection> {
+        for filter in &self.filters {
+            filter.check(from, content).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 内容に `word` を含むメッセージを拒否するフィルター
+    struct Deny {
+        name: &'static str,
+        word: &'static str,
+    }
+
+    #[async_trait]
+    impl MessageFilter for Deny {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn check(
+            &self,
+            _from: &ClientId,
+            content: &MessageContent,
+        ) -> Result<(), MessageRejection> {
+            if content.as_str().contains(self.word) {
+                return Err(MessageRejection {
+                    filter: self.name,
+                    reason: format!("contains '{}'", self.word),
+                });
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chain_stops_at_first_rejection() {
+        // テスト項目: フィルターは設定した順に適用され、最初に拒否したフィルターの理由が返る
+        // given (前提条件):
+        let chain = MessageFilterChain::new(vec![
+            Arc::new(Deny {
+                name: "first",
+                word: "spam",
+            }),
+            Arc::new(Deny {
+                name: "second",
+                word: "s",
+            }),
+        ]);
+        let from = ClientId::new("alice".to_string()).unwrap();
+        let content = |text: &str| MessageContent::new(text.to_string()).unwrap();
+
+        // when (操作):
+        let both = chain.check(&from, &content("spam")).await;
+        let second = chain.check(&from, &content("sushi")).await;
+        let passed = chain.check(&from, &content("hello")).await;
+
+        // then (期待する結果):
+        assert_eq!(both.unwrap_err().filter, "first");
+        assert_eq!(second.unwrap_err().filter, "second");
+        assert!(passed.is_ok());
+        assert!(MessageFilterChain::default().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_chain_for_policy_keeps_selected_filters() {
+        // テスト項目: ルームのポリシーで選んだ名前のフィルターだけが適用される
+        // given (前提条件):
+        let chain = MessageFilterChain::new(vec![
+            Arc::new(Deny {
+                name: "first",
+                word: "spam",
+            }),
+            Arc::new(Deny {
+                name: "second",
+                word: "s",
+            }),
+        ]);
+        let policy = MessagePolicy {
+            filters: Some(vec!["second".to_string()]),
+            ..MessagePolicy::default()
+        };
+        let from = ClientId::new("alice".to_string()).unwrap();
+
+        // when (操作):
+        let selected = chain.for_policy(&policy);
+        let none = chain.for_policy(&MessagePolicy {
+            filters: Some(Vec::new()),
+            ..MessagePolicy::default()
+        });
+        let rejected = selected
+            .check(&from, &MessageContent::new("spam".to_string()).unwrap())
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(rejected.unwrap_err().filter, "second");
+        assert!(none.is_empty());
+    }
+}
@@ -14,6 +14,11 @@
 //! - ADR: `docs/adr/0001-message-pusher-abstraction-and-placement.md`
 //! - タスク: `docs/tasks/20251112-032514_introduce-message-pusher.md`
 
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
 use async_trait::async_trait;
 
 use super::{ClientId, MessagePushError};
@@ -22,7 +27,33 @@ use super::{ClientId, MessagePushError};
 ///
 /// WebSocket や他の通信プロトコルでメッセージを送信するための抽象化。
 /// 実装詳細（tokio の UnboundedSender）を隠蔽し、将来的な変更を容易にします。
-pub type PusherChannel = tokio::sync::mpsc::UnboundedSender<String>;
+/// メッセージは `Arc<str>` で共有し、ブロードキャストでも送信先の数だけ複製しません。
+/// 接続ごとのチャネルには個別の送信と、購読（[`RoomFeed`]）に切り替える前のメッセージが届きます。
+pub type PusherChannel = tokio::sync::mpsc::UnboundedSender<Arc<str>>;
+
+/// ルームの全員に配るメッセージの購読
+///
+/// ルームへのブロードキャストはルームごとの `tokio::sync::broadcast` のチャネルに 1 回だけ送られ、
+/// 購読している接続がそれぞれ受け取ります。送信先から除かれた接続（送信者を除くチャットなど）には、
+/// 送る前にそのメッセージが `skipped` に積まれるため、受け取っても書き込みません。
+pub struct RoomFeed {
+    /// ルームのブロードキャストの受信側
+    pub receiver: tokio::sync::broadcast::Receiver<Arc<str>>,
+    /// 受け取っても書き込まないメッセージ（受け取る順）
+    pub skipped: Arc<Mutex<VecDeque<Arc<str>>>>,
+}
+
+impl RoomFeed {
+    /// 受け取ったメッセージがこの接続の送信先から除かれているか（除かれていれば取り除く）
+    pub fn skips(&self, msg: &Arc<str>) -> bool {
+        let mut skipped = self.skipped.lock().unwrap();
+        if skipped.front().is_some_and(|front| Arc::ptr_eq(front, msg)) {
+            skipped.pop_front();
+            return true;
+        }
+        false
+    }
+}
 
 /// メッセージ送信（通知）の抽象化
 ///
@@ -37,6 +68,8 @@ pub type PusherChannel = tokio::sync::mpsc::UnboundedSender<String>;
 pub trait MessagePusher: Send + Sync {
     /// クライアントを登録
     ///
+    /// 同じクライアントを複数回登録すると、送信は全ての接続に行われます。
+    ///
     /// # 引数
     ///
     /// - `client_id`: クライアント ID（Domain Model）
@@ -59,6 +92,39 @@ pub trait MessagePusher: Send + Sync {
     /// 実装によっては、この操作は no-op（何もしない）になる場合があります。
     async fn unregister_client(&self, client_id: &ClientId);
 
+    /// クライアントの接続を 1 つだけ登録解除
+    ///
+    /// 同じクライアントが複数の接続（スマートフォンとノート PC など）を持つ場合に、
+    /// 閉じた接続の sender だけを取り除きます。
+    ///
+    /// # 引数
+    ///
+    /// - `client_id`: クライアント ID（Domain Model）
+    /// - `sender`: 閉じた接続の `register_client` で登録した sender
+    ///
+    /// # 戻り値
+    ///
+    /// クライアントに残っている接続の数
+    async fn unregister_connection(&self, client_id: &ClientId, sender: &PusherChannel) -> usize;
+
+    /// 登録した接続へのブロードキャストを、ルームの全員に配るメッセージの購読に切り替える
+    ///
+    /// 以降のブロードキャストは `sender` の代わりに返した購読に届きます（個別の送信は引き続き
+    /// `sender` に届きます）。接続への書き込みを始める時に呼びます。
+    ///
+    /// # 引数
+    ///
+    /// - `client_id`: クライアント ID（Domain Model）
+    /// - `sender`: `register_client` で登録した sender
+    ///
+    /// # 戻り値
+    ///
+    /// 購読。登録されていない接続の場合と、購読に対応しない実装では `None`
+    /// （全てのメッセージが `sender` に届く）
+    async fn subscribe(&self, _client_id: &ClientId, _sender: &PusherChannel) -> Option<RoomFeed> {
+        None
+    }
+
     /// 特定のクライアントにメッセージを送信
     ///
     /// # 引数
@@ -92,4 +158,11 @@ pub trait MessagePusher: Send + Sync {
         targets: Vec<ClientId>,
         content: &str,
     ) -> Result<(), MessagePushError>;
+
+    /// 送信の基盤が応答するかを確認（ヘルスチェック用）
+    ///
+    /// # エラー
+    ///
+    /// - `MessagePushError::PushFailed`: 送信の基盤が利用できない
+    async fn ping(&self) -> Result<(), MessagePushError>;
 }
@@ -6,13 +6,28 @@
 pub mod entity;
 pub mod error;
 pub mod factory;
+pub mod membership;
+pub mod message_analyzer;
+pub mod message_filter;
 pub mod message_pusher;
 pub mod repository;
 pub mod value_object;
 
-pub use entity::{ChatMessage, Participant, Room};
-pub use error::{MessagePushError, RepositoryError, RoomError, ValueObjectError};
-pub use factory::RoomIdFactory;
-pub use message_pusher::{MessagePusher, PusherChannel};
-pub use repository::RoomRepository;
-pub use value_object::{ClientId, MessageContent, RoomId, Timestamp};
+pub use entity::{
+    Breakout, ChatMessage, ForwardedFrom, Integration, IntegrationKind, Participant, Poll,
+    PollVote, Room, RoomMetadata, Star,
+};
+pub use error::{MembershipError, MessagePushError, RepositoryError, RoomError, ValueObjectError};
+pub use factory::{GuestIdFactory, RoomIdFactory, WebhookTokenFactory};
+pub use membership::{DEFAULT_MAX_ROOMS_PER_CLIENT, RoomMemberships};
+pub use message_analyzer::MessageAnalyzer;
+pub use message_filter::{MessageFilter, MessageFilterChain, MessageRejection};
+pub use message_pusher::{MessagePusher, PusherChannel, RoomFeed};
+pub use repository::{
+    BanList, IntegrationRepository, PendingMessageStore, RoomRepository, StarRepository,
+};
+pub use value_object::{
+    Activity, BridgeKind, ClientId, ClientIdentity, CronSchedule, DEFAULT_MAX_MESSAGE_LENGTH,
+    DisplayName, GUEST_ID_PREFIX, Locale, MessageContent, MessagePolicy, MessageTag, PollOption,
+    RoomClass, RoomId, RoomSlug, SequenceNumber, Timestamp,
+};
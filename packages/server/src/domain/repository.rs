@@ -3,9 +3,15 @@
 //! ドメイン層が必要とするデータアクセスのインターフェースを定義します。
 //! 具体的な実装は Infrastructure 層が提供します（依存性の逆転）。
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 
-use super::{ClientId, MessageContent, Participant, RepositoryError, Room, Timestamp};
+use super::{
+    Activity, ChatMessage, ClientId, DisplayName, ForwardedFrom, Integration, IntegrationKind,
+    MessageContent, MessageTag, Participant, Poll, PollOption, RepositoryError, Room, RoomId,
+    RoomMetadata, SequenceNumber, Star, Timestamp,
+};
 
 /// Room Repository trait
 ///
@@ -17,11 +23,30 @@ use super::{ClientId, MessageContent, Participant, RepositoryError, Room, Timest
 /// - ドメイン層が必要とするインターフェースをドメイン層自身が定義
 /// - Infrastructure 層がドメイン層のインターフェースに依存
 /// - ドメイン層は Infrastructure 層に依存しない
+///
+/// ## 複数のルーム
+///
+/// 1 つの Repository は 1 つのルーム（自身のルーム）を対象に操作します。
+/// 最初から Repository にあるルームを既定のルームと呼び、`create_room` で作成した他のルームは
+/// `for_room` で取得したそのルームの Repository で操作します。
 #[async_trait]
 pub trait RoomRepository: Send + Sync {
     /// Room エンティティを取得
     async fn get_room(&self) -> Result<Room, RepositoryError>;
 
+    /// 参加者リストとメッセージ履歴を除いた Room のメタデータを取得
+    async fn get_room_metadata(&self) -> Result<RoomMetadata, RepositoryError>;
+
+    /// 最新の `limit` 件のメッセージを古い順に取得
+    async fn get_recent_messages(&self, limit: usize) -> Result<Vec<ChatMessage>, RepositoryError>;
+
+    /// 送信日時が `before` より前（`None` の場合は全て）の最新の `limit` 件のメッセージを古い順に取得
+    async fn get_messages_before(
+        &self,
+        before: Option<Timestamp>,
+        limit: usize,
+    ) -> Result<Vec<ChatMessage>, RepositoryError>;
+
     /// 参加者を追加
     async fn add_participant(
         &self,
@@ -32,20 +57,238 @@ pub trait RoomRepository: Send + Sync {
     /// 参加者を削除
     async fn remove_participant(&self, client_id: &ClientId) -> Result<(), RepositoryError>;
 
+    /// 参加者の表示名を設定（`None` の場合は消去）
+    ///
+    /// 参加者がルームにいない場合は `RepositoryError::ParticipantNotFound`
+    async fn rename_participant(
+        &self,
+        client_id: &ClientId,
+        display_name: Option<DisplayName>,
+    ) -> Result<(), RepositoryError>;
+
+    /// 参加者のアクティビティを設定（`None` の場合は消去）
+    ///
+    /// 参加者がルームにいない場合は `RepositoryError::ParticipantNotFound`
+    async fn set_activity(
+        &self,
+        client_id: &ClientId,
+        activity: Option<Activity>,
+    ) -> Result<(), RepositoryError>;
+
     /// 接続中の全てのクライアント ID を取得
     async fn get_all_connected_client_ids(&self) -> Vec<ClientId>;
 
-    /// メッセージを Room に追加
+    /// メッセージを Room に追加し、振られたシーケンス番号を返す
     async fn add_message(
         &self,
         from_client_id: ClientId,
         content: MessageContent,
         timestamp: Timestamp,
+    ) -> Result<SequenceNumber, RepositoryError>;
+
+    /// クライアントの参加者情報・全てのメッセージ・投票の票を削除し、削除したメッセージのシーケンス番号を返す
+    ///
+    /// 永続化する実装は、保存済みのメッセージの内容も復元できないように消去する。
+    async fn erase_client(
+        &self,
+        client_id: &ClientId,
+    ) -> Result<Vec<SequenceNumber>, RepositoryError>;
+
+    /// 指定したシーケンス番号のメッセージを削除
+    ///
+    /// メッセージが履歴に無い場合は `RepositoryError::MessageNotFound`。
+    /// 永続化する実装は、保存済みのメッセージの内容も復元できないように消去する。
+    async fn delete_message(&self, seq: SequenceNumber) -> Result<(), RepositoryError>;
+
+    /// 指定したシーケンス番号のメッセージにタグ（分析結果のメタデータ）を追加
+    ///
+    /// メッセージが履歴に無い場合は `RepositoryError::MessageNotFound`
+    async fn tag_message(
+        &self,
+        seq: SequenceNumber,
+        tags: Vec<MessageTag>,
     ) -> Result<(), RepositoryError>;
 
+    /// 指定したシーケンス番号のメッセージを `options` の投票にする（メッセージの内容が質問になる）
+    ///
+    /// メッセージが履歴に無い場合は `RepositoryError::MessageNotFound`
+    async fn attach_poll(
+        &self,
+        seq: SequenceNumber,
+        options: Vec<PollOption>,
+    ) -> Result<(), RepositoryError>;
+
+    /// 指定したシーケンス番号のメッセージに転送元（元のルーム・送信者・送信日時）を記録する
+    ///
+    /// メッセージが履歴に無い場合は `RepositoryError::MessageNotFound`
+    async fn mark_forwarded(
+        &self,
+        seq: SequenceNumber,
+        origin: ForwardedFrom,
+    ) -> Result<(), RepositoryError>;
+
+    /// 指定したシーケンス番号の投票にクライアントの票を入れ、票を反映した投票を返す
+    ///
+    /// 同じクライアントが投票済みの場合は票を新しい選択肢に移す。
+    /// メッセージが履歴に無い場合は `RepositoryError::MessageNotFound`、投票でない場合と
+    /// 選択肢が無い場合は `RepositoryError::Room`
+    async fn vote(
+        &self,
+        seq: SequenceNumber,
+        voter: ClientId,
+        option: usize,
+    ) -> Result<Poll, RepositoryError>;
+
+    /// メッセージ履歴のおおよそのメモリ使用量（バイト）を取得
+    async fn history_bytes(&self) -> usize;
+
+    /// メッセージ履歴が `max_bytes` 以下になるまで古いメッセージから削除し、削除した件数を返す
+    async fn evict_history(&self, max_bytes: usize) -> usize;
+
+    /// ルームを凍結（`true`）または凍結を解除（`false`）
+    ///
+    /// 凍結したルームはメッセージの追加を拒否する（`RepositoryError::Room`）。履歴は読み取れる。
+    async fn set_frozen(&self, frozen: bool) -> Result<(), RepositoryError>;
+
     /// 接続中のクライアント数を取得
     async fn count_connected_clients(&self) -> usize;
 
     /// Room の参加者リストを取得
     async fn get_participants(&self) -> Vec<Participant>;
+
+    /// ルームを作成
+    ///
+    /// 同じ ID のルームが既にある場合は `RepositoryError::RoomAlreadyExists`
+    async fn create_room(&self, room: Room) -> Result<(), RepositoryError>;
+
+    /// ルームを削除
+    ///
+    /// ルームが無い場合は `RepositoryError::RoomNotFound`、既定のルームと
+    /// この Repository 自身のルームは削除できない（`RepositoryError::RoomNotDeletable`）
+    async fn delete_room(&self, room_id: &RoomId) -> Result<(), RepositoryError>;
+
+    /// ID を指定して Room エンティティを取得
+    async fn get_room_by_id(&self, room_id: &RoomId) -> Result<Room, RepositoryError>;
+
+    /// ID を指定して、読み取り用の Room の不変なスナップショットを取得
+    ///
+    /// 読み取りのエンドポイント向け。実装は書き込みのロックと競合せずに返すため、
+    /// 実行中の書き込みを反映していない場合がある。
+    /// ルームが無い場合は `RepositoryError::RoomNotFound`
+    async fn get_room_snapshot(&self, room_id: &RoomId) -> Result<Arc<Room>, RepositoryError>;
+
+    /// 全てのルームの ID を取得（既定のルームが先頭、以降は作成した順）
+    async fn get_room_ids(&self) -> Vec<RoomId>;
+
+    /// 指定したルームを対象に操作する Repository を取得
+    ///
+    /// ルームが無い場合は `RepositoryError::RoomNotFound`
+    async fn for_room(&self, room_id: &RoomId) -> Result<Arc<dyn RoomRepository>, RepositoryError>;
+
+    /// データストアが応答し、書き込める状態かを確認（ヘルスチェック用）
+    async fn ping(&self) -> Result<(), RepositoryError>;
+}
+
+/// BAN したクライアントの一覧の Repository trait
+///
+/// BAN したクライアント ID は全てのルームへの接続を拒否する対象になります。
+#[async_trait]
+pub trait BanList: Send + Sync {
+    /// クライアントを BAN する
+    ///
+    /// 既に BAN されていた場合は `false`
+    async fn ban(&self, client_id: ClientId) -> Result<bool, RepositoryError>;
+
+    /// クライアントが BAN されているかを確認
+    async fn is_banned(&self, client_id: &ClientId) -> Result<bool, RepositoryError>;
+}
+
+/// ルームの連携設定（Webhook とブリッジ）の Repository trait
+///
+/// 連携設定の ID はサーバー全体で一意に採番されます。
+#[async_trait]
+pub trait IntegrationRepository: Send + Sync {
+    /// ルームの連携設定を作成順に取得
+    async fn list(&self, room_id: &RoomId) -> Result<Vec<Integration>, RepositoryError>;
+
+    /// 連携設定を作成（ID を採番して返す）
+    async fn create(
+        &self,
+        room_id: RoomId,
+        kind: IntegrationKind,
+        created_at: Timestamp,
+    ) -> Result<Integration, RepositoryError>;
+
+    /// 連携設定の内容を置き換える
+    ///
+    /// ルームに該当する連携設定がない場合は `None`
+    async fn update(
+        &self,
+        room_id: &RoomId,
+        id: u64,
+        kind: IntegrationKind,
+    ) -> Result<Option<Integration>, RepositoryError>;
+
+    /// 連携設定を削除
+    ///
+    /// ルームに該当する連携設定がない場合は `false`
+    async fn delete(&self, room_id: &RoomId, id: u64) -> Result<bool, RepositoryError>;
+
+    /// 全てのルームの連携設定を作成順に取得
+    async fn list_all(&self) -> Result<Vec<Integration>, RepositoryError>;
+
+    /// トークンが一致する受信 Webhook の連携設定を全てのルームから探す
+    async fn find_incoming_webhook(
+        &self,
+        token: &str,
+    ) -> Result<Option<Integration>, RepositoryError>;
+}
+
+/// スター（クライアントが後で見返すために保存したメッセージ）の Repository trait
+///
+/// スターはクライアントとメッセージ（ルームとシーケンス番号）の組ごとに 1 つ保存されます。
+#[async_trait]
+pub trait StarRepository: Send + Sync {
+    /// メッセージにスターを付ける
+    ///
+    /// 既にスターが付いていた場合は `false`（スターを付けた日時は最初のまま）
+    async fn add(&self, star: Star) -> Result<bool, RepositoryError>;
+
+    /// メッセージのスターを外す
+    ///
+    /// スターが付いていなかった場合は `false`
+    async fn remove(
+        &self,
+        client_id: &ClientId,
+        room_id: &RoomId,
+        seq: SequenceNumber,
+    ) -> Result<bool, RepositoryError>;
+
+    /// クライアントのスターをスターを付けた順に取得
+    async fn list(&self, client_id: &ClientId) -> Result<Vec<Star>, RepositoryError>;
+
+    /// クライアントのスターを全て外す（外した数を返す）
+    async fn remove_all(&self, client_id: &ClientId) -> Result<usize, RepositoryError>;
+}
+
+/// 切断した参加者に再接続時に届けるメッセージ（メールボックス）の保存先 trait
+///
+/// メールボックスは参加者の切断時に開き、有効期限内に同じ `client_id` で再接続すると
+/// 取り出されます。期限を過ぎたメールボックスは破棄されます。
+#[async_trait]
+pub trait PendingMessageStore: Send + Sync {
+    /// 切断したクライアントのメールボックスを開く（既にあれば空にして開き直す）
+    async fn open(&self, client_id: ClientId, now: Timestamp) -> Result<(), RepositoryError>;
+
+    /// 期限内の全てのメールボックスにメッセージ（JSON）を追加
+    async fn push(&self, message: &str, now: Timestamp) -> Result<(), RepositoryError>;
+
+    /// クライアントのメールボックスを閉じ、溜まったメッセージを古い順に取り出す
+    ///
+    /// メールボックスが無いか期限を過ぎている場合は空
+    async fn take(
+        &self,
+        client_id: &ClientId,
+        now: Timestamp,
+    ) -> Result<Vec<String>, RepositoryError>;
 }
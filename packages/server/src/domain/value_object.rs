@@ -8,6 +8,12 @@ use std::fmt;
 
 use super::error::ValueObjectError;
 
+/// Prefix of the client IDs the server assigns to guests.
+///
+/// Client IDs with this prefix are reserved: only guests connecting without a client ID
+/// are given one.
+pub const GUEST_ID_PREFIX: &str = "guest-";
+
 /// Client identifier value object.
 ///
 /// Represents a unique identifier for a chat client.
@@ -47,6 +53,11 @@ impl ClientId {
     pub fn into_string(self) -> String {
         self.0
     }
+
+    /// Whether the ID was assigned to a guest by the server.
+    pub fn is_guest(&self) -> bool {
+        self.0.starts_with(GUEST_ID_PREFIX)
+    }
 }
 
 impl fmt::Display for ClientId {
@@ -63,11 +74,37 @@ impl TryFrom<String> for ClientId {
     }
 }
 
+/// Authenticated identity of a client.
+///
+/// Unlike a client ID a client merely claims when connecting, the client ID of an identity
+/// has been proven by a credential (e.g. a login token).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIdentity {
+    client_id: ClientId,
+}
+
+impl ClientIdentity {
+    /// Create the identity of a client whose credential has been verified.
+    pub fn new(client_id: ClientId) -> Self {
+        Self { client_id }
+    }
+
+    /// Get the authenticated client ID.
+    pub fn client_id(&self) -> &ClientId {
+        &self.client_id
+    }
+
+    /// Convert to the authenticated client ID.
+    pub fn into_client_id(self) -> ClientId {
+        self.client_id
+    }
+}
+
 /// Room identifier value object.
 ///
 /// Represents a unique identifier for a chat room.
 /// Room IDs must be valid UUID format strings.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct RoomId(String);
 
 impl RoomId {
@@ -128,6 +165,226 @@ impl fmt::Display for RoomId {
     }
 }
 
+/// Maximum length of a room slug.
+pub const MAX_ROOM_SLUG_LEN: usize = 64;
+
+/// Room slug value object.
+///
+/// A human-friendly, URL-safe name chosen for a room (e.g. `general`) that can be used
+/// instead of its UUID. Slugs are unique among rooms.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RoomSlug(String);
+
+impl RoomSlug {
+    /// Create a new RoomSlug.
+    ///
+    /// # Arguments
+    ///
+    /// * `slug` - The slug string
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the RoomSlug or an error if validation fails
+    ///
+    /// # Errors
+    ///
+    /// Returns an error unless the string is 1 to [`MAX_ROOM_SLUG_LEN`] lowercase ASCII
+    /// letters, digits or hyphens, and does not start or end with a hyphen
+    pub fn new(slug: String) -> Result<Self, ValueObjectError> {
+        let valid = !slug.is_empty()
+            && slug.len() <= MAX_ROOM_SLUG_LEN
+            && slug
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+            && !slug.starts_with('-')
+            && !slug.ends_with('-');
+        if !valid {
+            return Err(ValueObjectError::RoomSlugInvalidFormat {
+                slug,
+                max: MAX_ROOM_SLUG_LEN,
+            });
+        }
+        Ok(Self(slug))
+    }
+
+    /// Get the inner string value.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Convert to owned String.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for RoomSlug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for RoomSlug {
+    type Err = ValueObjectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s.to_string())
+    }
+}
+
+/// Locale value object.
+///
+/// Language of the system-generated texts (join/leave notices etc.) of a room.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// English
+    #[default]
+    En,
+    /// Japanese
+    Ja,
+}
+
+impl Locale {
+    /// Supported locales
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Ja];
+
+    /// Language tag of the locale (e.g. `ja`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ja => "ja",
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for Locale {
+    type Err = ValueObjectError;
+
+    /// Parse a language tag; region subtags are ignored (`ja-JP` is `ja`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s.split(['-', '_']).next().unwrap_or_default();
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.as_str().eq_ignore_ascii_case(language))
+            .ok_or_else(|| ValueObjectError::UnsupportedLocale {
+                locale: s.to_string(),
+                supported: Self::ALL.map(|locale| locale.as_str()).join(", "),
+            })
+    }
+}
+
+/// Room class value object.
+///
+/// Storage requirements of a room, used to choose the repository backend that stores it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoomClass {
+    /// Short-lived room; its data may be lost on restart
+    #[default]
+    Ephemeral,
+    /// Room whose data has to be kept (e.g. for compliance)
+    Persistent,
+}
+
+impl RoomClass {
+    /// Supported room classes
+    pub const ALL: [RoomClass; 2] = [RoomClass::Ephemeral, RoomClass::Persistent];
+
+    /// Name of the room class (e.g. `persistent`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoomClass::Ephemeral => "ephemeral",
+            RoomClass::Persistent => "persistent",
+        }
+    }
+}
+
+impl fmt::Display for RoomClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for RoomClass {
+    type Err = ValueObjectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|class| class.as_str() == s)
+            .ok_or_else(|| ValueObjectError::UnsupportedRoomClass {
+                class: s.to_string(),
+                supported: Self::ALL.map(|class| class.as_str()).join(", "),
+            })
+    }
+}
+
+/// Bridge kind value object.
+///
+/// Chat service a room can be bridged to by an integration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BridgeKind {
+    /// Discord channel (via its webhook URL)
+    Discord,
+    /// MQTT topic
+    Mqtt,
+    /// XMPP multi-user chat room
+    Xmpp,
+    /// Room of a peer server
+    Federation,
+}
+
+impl BridgeKind {
+    /// Supported bridge kinds
+    pub const ALL: [BridgeKind; 4] = [
+        BridgeKind::Discord,
+        BridgeKind::Mqtt,
+        BridgeKind::Xmpp,
+        BridgeKind::Federation,
+    ];
+
+    /// Name of the bridge kind (e.g. `discord`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BridgeKind::Discord => "discord",
+            BridgeKind::Mqtt => "mqtt",
+            BridgeKind::Xmpp => "xmpp",
+            BridgeKind::Federation => "federation",
+        }
+    }
+}
+
+impl fmt::Display for BridgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for BridgeKind {
+    type Err = ValueObjectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|bridge| bridge.as_str() == s)
+            .ok_or_else(|| ValueObjectError::UnsupportedBridge {
+                bridge: s.to_string(),
+                supported: Self::ALL.map(|bridge| bridge.as_str()).join(", "),
+            })
+    }
+}
+
+/// Maximum length of a message in bytes (rooms may lower it with their [`MessagePolicy`])
+pub const DEFAULT_MAX_MESSAGE_LENGTH: usize = 10000;
+
 /// Message content value object.
 ///
 /// Represents the content of a chat message with validation.
@@ -145,13 +402,18 @@ impl MessageContent {
     ///
     /// A Result containing the MessageContent or an error if validation fails
     pub fn new(content: String) -> Result<Self, ValueObjectError> {
+        Self::with_max_length(content, DEFAULT_MAX_MESSAGE_LENGTH)
+    }
+
+    /// Create a new MessageContent of at most `max_length` bytes.
+    pub fn with_max_length(content: String, max_length: usize) -> Result<Self, ValueObjectError> {
         if content.is_empty() {
             return Err(ValueObjectError::MessageContentEmpty);
         }
         let len = content.len();
-        if len > 10000 {
+        if len > max_length {
             return Err(ValueObjectError::MessageContentTooLong {
-                max: 10000,
+                max: max_length,
                 actual: len,
             });
         }
@@ -183,6 +445,40 @@ impl TryFrom<String> for MessageContent {
     }
 }
 
+/// Rules for the messages sent to a room.
+///
+/// Rooms follow the server's policy unless they were created with their own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessagePolicy {
+    /// Maximum length of a message in bytes
+    pub max_length: usize,
+    /// Names of the server's message filters applied to the room (all of them if `None`)
+    pub filters: Option<Vec<String>>,
+}
+
+impl Default for MessagePolicy {
+    fn default() -> Self {
+        Self {
+            max_length: DEFAULT_MAX_MESSAGE_LENGTH,
+            filters: None,
+        }
+    }
+}
+
+impl MessagePolicy {
+    /// Validate message content against the policy.
+    pub fn content(&self, content: String) -> Result<MessageContent, ValueObjectError> {
+        MessageContent::with_max_length(content, self.max_length)
+    }
+
+    /// Whether the server's message filter named `filter` applies to the room.
+    pub fn applies_filter(&self, filter: &str) -> bool {
+        self.filters
+            .as_ref()
+            .is_none_or(|filters| filters.iter().any(|name| name == filter))
+    }
+}
+
 /// Timestamp value object.
 ///
 /// Represents a Unix timestamp in milliseconds (JST).
@@ -221,6 +517,398 @@ impl From<i64> for Timestamp {
     }
 }
 
+/// Message tag value object.
+///
+/// Metadata attached to a stored chat message by a message analyzer (e.g. `keyword:deploy`).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct MessageTag(String);
+
+impl MessageTag {
+    /// Maximum length of a tag, in bytes
+    pub const MAX_LEN: usize = 100;
+
+    /// Create a new MessageTag.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tag is empty, longer than [`Self::MAX_LEN`] bytes, or contains
+    /// whitespace
+    pub fn new(tag: String) -> Result<Self, ValueObjectError> {
+        if tag.is_empty() || tag.len() > Self::MAX_LEN || tag.chars().any(char::is_whitespace) {
+            return Err(ValueObjectError::MessageTagInvalidFormat {
+                tag,
+                max: Self::MAX_LEN,
+            });
+        }
+        Ok(Self(tag))
+    }
+
+    /// Get the inner string value.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Convert to owned String.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for MessageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<String> for MessageTag {
+    type Error = ValueObjectError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+/// Poll option value object.
+///
+/// Label of one of the choices of a poll, shown to the participants voting on it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PollOption(String);
+
+impl PollOption {
+    /// Maximum length of an option, in characters
+    pub const MAX_CHARS: usize = 100;
+
+    /// Create a new PollOption.
+    ///
+    /// Surrounding whitespace is trimmed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the option is blank or longer than [`Self::MAX_CHARS`] characters
+    pub fn new(option: String) -> Result<Self, ValueObjectError> {
+        let trimmed = option.trim();
+        if trimmed.is_empty() || trimmed.chars().count() > Self::MAX_CHARS {
+            return Err(ValueObjectError::PollOptionInvalidFormat {
+                option,
+                max: Self::MAX_CHARS,
+            });
+        }
+        Ok(Self(trimmed.to_string()))
+    }
+
+    /// Get the inner string value.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Convert to owned String.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for PollOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<String> for PollOption {
+    type Error = ValueObjectError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+/// Display name value object.
+///
+/// Name a participant is shown as (e.g. `Alice Smith`), separate from its [`ClientId`]. Unlike
+/// the client ID it may contain spaces and change while connected.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DisplayName(String);
+
+impl DisplayName {
+    /// Maximum length of a display name, in characters
+    pub const MAX_CHARS: usize = 32;
+
+    /// Create a new DisplayName.
+    ///
+    /// Surrounding whitespace is trimmed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is blank, longer than [`Self::MAX_CHARS`] characters, or
+    /// contains control characters
+    pub fn new(name: String) -> Result<Self, ValueObjectError> {
+        let trimmed = name.trim();
+        if trimmed.is_empty()
+            || trimmed.chars().count() > Self::MAX_CHARS
+            || trimmed.chars().any(char::is_control)
+        {
+            return Err(ValueObjectError::DisplayNameInvalidFormat {
+                name,
+                max: Self::MAX_CHARS,
+            });
+        }
+        Ok(Self(trimmed.to_string()))
+    }
+
+    /// Get the inner string value.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Convert to owned String.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for DisplayName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<String> for DisplayName {
+    type Error = ValueObjectError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+/// Activity value object.
+///
+/// Short free-form description of what a participant is doing (e.g. `reviewing PR #42`),
+/// shown next to the participant in listings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Activity(String);
+
+impl Activity {
+    /// Maximum length of an activity, in characters
+    pub const MAX_CHARS: usize = 64;
+
+    /// Create a new Activity.
+    ///
+    /// Surrounding whitespace is trimmed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the activity is blank, longer than [`Self::MAX_CHARS`] characters,
+    /// or contains control characters (e.g. line breaks)
+    pub fn new(activity: String) -> Result<Self, ValueObjectError> {
+        let trimmed = activity.trim();
+        if trimmed.is_empty()
+            || trimmed.chars().count() > Self::MAX_CHARS
+            || trimmed.chars().any(char::is_control)
+        {
+            return Err(ValueObjectError::ActivityInvalidFormat {
+                activity,
+                max: Self::MAX_CHARS,
+            });
+        }
+        Ok(Self(trimmed.to_string()))
+    }
+
+    /// Get the inner string value.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Convert to owned String.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for Activity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl TryFrom<String> for Activity {
+    type Error = ValueObjectError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+/// Cron schedule value object.
+///
+/// Standard five-field cron expression (`minute hour day-of-month month day-of-week`), e.g.
+/// `30 9 * * 1-5` for 09:30 on weekdays. Each field is `*`, a number, a range (`1-5`), a step
+/// (`*/15`, `0-30/10`) or a comma-separated list of them; day-of-week is 0-7 with both 0 and 7
+/// meaning Sunday. As in cron, when both day fields are restricted a day matching either one
+/// matches. The schedule does not know the time zone; callers evaluate it in theirs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether the day-of-month field is `*`
+    any_day: bool,
+    /// Whether the day-of-week field is `*`
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    /// Create a new CronSchedule.
+    ///
+    /// Surrounding whitespace is trimmed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression does not have five fields or a field is malformed
+    /// or out of range
+    pub fn new(expression: String) -> Result<Self, ValueObjectError> {
+        let invalid = || ValueObjectError::CronScheduleInvalidFormat {
+            schedule: expression.clone(),
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(invalid());
+        };
+        let mut weekdays = Self::field(weekday, 0, 7).ok_or_else(invalid)?;
+        // Sunday may be written as 7
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+        Ok(Self {
+            minutes: Self::field(minute, 0, 59).ok_or_else(invalid)?,
+            hours: Self::field(hour, 0, 23).ok_or_else(invalid)?,
+            days: Self::field(day, 1, 31).ok_or_else(invalid)?,
+            months: Self::field(month, 1, 12).ok_or_else(invalid)?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+            expression: fields.join(" "),
+        })
+    }
+
+    /// Parse a field into a bit set of the values it matches
+    fn field(field: &str, min: u32, max: u32) -> Option<u64> {
+        let mut set = 0u64;
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (range, step.parse::<u32>().ok().filter(|&s| s > 0)?),
+                None => (part, 1),
+            };
+            let (start, end) = match range {
+                "*" => (min, max),
+                _ => match range.split_once('-') {
+                    Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+                    None => {
+                        let value = range.parse().ok()?;
+                        // `5/15` runs from 5 to the end of the range, as in cron
+                        (value, if part.contains('/') { max } else { value })
+                    }
+                },
+            };
+            if start < min || end > max || start > end {
+                return None;
+            }
+            for value in (start..=end).step_by(step as usize) {
+                set |= 1 << value;
+            }
+        }
+        Some(set)
+    }
+
+    /// Whether the schedule fires at the given minute.
+    ///
+    /// # Arguments
+    ///
+    /// * `minute` - Minute (0-59)
+    /// * `hour` - Hour (0-23)
+    /// * `day` - Day of the month (1-31)
+    /// * `month` - Month (1-12)
+    /// * `weekday` - Day of the week (0-6, Sunday is 0)
+    pub fn matches(&self, minute: u32, hour: u32, day: u32, month: u32, weekday: u32) -> bool {
+        let has = |set: u64, value: u32| value < 64 && set & (1 << value) != 0;
+        let day_matches = match (self.any_day, self.any_weekday) {
+            (false, false) => has(self.days, day) || has(self.weekdays, weekday),
+            _ => has(self.days, day) && has(self.weekdays, weekday),
+        };
+        has(self.minutes, minute) && has(self.hours, hour) && has(self.months, month) && day_matches
+    }
+
+    /// Get the expression.
+    pub fn as_str(&self) -> &str {
+        &self.expression
+    }
+
+    /// Convert to the owned expression.
+    pub fn into_string(self) -> String {
+        self.expression
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+impl TryFrom<String> for CronSchedule {
+    type Error = ValueObjectError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<CronSchedule> for String {
+    fn from(schedule: CronSchedule) -> Self {
+        schedule.expression
+    }
+}
+
+/// Sequence number value object.
+///
+/// Position of a chat message in its room, starting from 1. Messages are broadcast in
+/// sequence order, so every participant observes the same ordering.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct SequenceNumber(u64);
+
+impl SequenceNumber {
+    /// Create a new SequenceNumber.
+    pub fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Get the inner u64 value.
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// Get the sequence number following this one.
+    pub fn next(&self) -> Self {
+        Self(self.0 + 1)
+    }
+}
+
+impl fmt::Display for SequenceNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<u64> for SequenceNumber {
+    fn from(value: u64) -> Self {
+        Self::new(value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -346,6 +1034,67 @@ mod tests {
         assert_eq!(room_id.as_str(), uuid.to_string());
     }
 
+    #[test]
+    fn test_room_slug_new_success() {
+        // テスト項目: 英小文字・数字・ハイフンからなるスラッグを作成できる
+        // when (操作):
+        let result = RoomSlug::new("team-42".to_string());
+
+        // then (期待する結果):
+        assert_eq!(result.unwrap().as_str(), "team-42");
+    }
+
+    #[test]
+    fn test_room_slug_new_invalid_format_fails() {
+        // テスト項目: 空・大文字や記号を含む・ハイフンで始まる/終わる・長すぎるスラッグは作成できない
+        // given (前提条件):
+        let cases = [
+            String::new(),
+            "General".to_string(),
+            "my room".to_string(),
+            "a/b".to_string(),
+            "-general".to_string(),
+            "general-".to_string(),
+            "a".repeat(MAX_ROOM_SLUG_LEN + 1),
+        ];
+
+        for slug in cases {
+            // when (操作):
+            let result = RoomSlug::new(slug.clone());
+
+            // then (期待する結果):
+            assert_eq!(
+                result,
+                Err(ValueObjectError::RoomSlugInvalidFormat {
+                    slug,
+                    max: MAX_ROOM_SLUG_LEN,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_locale_from_str() {
+        // テスト項目: 言語タグからロケールを解析でき、地域サブタグは無視され、未対応の言語は拒否される
+        // when (操作):
+        let ja = "ja".parse::<Locale>();
+        let ja_jp = "ja-JP".parse::<Locale>();
+        let en_us = "EN_us".parse::<Locale>();
+        let fr = "fr".parse::<Locale>();
+
+        // then (期待する結果):
+        assert_eq!(ja, Ok(Locale::Ja));
+        assert_eq!(ja_jp, Ok(Locale::Ja));
+        assert_eq!(en_us, Ok(Locale::En));
+        assert_eq!(
+            fr,
+            Err(ValueObjectError::UnsupportedLocale {
+                locale: "fr".to_string(),
+                supported: "en, ja".to_string(),
+            })
+        );
+    }
+
     #[test]
     fn test_message_content_new_success() {
         // テスト項目: 有効なメッセージ内容を作成できる
@@ -394,6 +1143,139 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_message_policy_limits_length_and_filters() {
+        // テスト項目: ルームのポリシーの長さを超える内容は作成できず、指定したフィルターだけが適用される
+        // given (前提条件):
+        let policy = MessagePolicy {
+            max_length: 5,
+            filters: Some(vec!["max-links".to_string()]),
+        };
+
+        // when (操作):
+        let short = policy.content("hello".to_string());
+        let long = policy.content("hello!".to_string());
+
+        // then (期待する結果):
+        assert_eq!(short.unwrap().as_str(), "hello");
+        assert_eq!(
+            long,
+            Err(ValueObjectError::MessageContentTooLong { max: 5, actual: 6 })
+        );
+        assert!(policy.applies_filter("max-links"));
+        assert!(!policy.applies_filter("profanity"));
+        assert!(MessagePolicy::default().applies_filter("profanity"));
+    }
+
+    #[test]
+    fn test_message_tag_validation() {
+        // テスト項目: 空白を含まない 1〜100 バイトのタグのみ作成できる
+        // when (操作) / then (期待する結果):
+        assert_eq!(
+            MessageTag::new("keyword:deploy".to_string())
+                .unwrap()
+                .as_str(),
+            "keyword:deploy"
+        );
+        assert!(MessageTag::new(String::new()).is_err());
+        assert!(MessageTag::new("two words".to_string()).is_err());
+        assert!(MessageTag::new("a".repeat(101)).is_err());
+    }
+
+    #[test]
+    fn test_poll_option_validation() {
+        // テスト項目: 前後の空白を除いて 1〜100 文字の選択肢のみ作成できる
+        // when (操作) / then (期待する結果):
+        assert_eq!(
+            PollOption::new("  Ramen ".to_string()).unwrap().as_str(),
+            "Ramen"
+        );
+        assert!(PollOption::new("   ".to_string()).is_err());
+        assert!(PollOption::new("あ".repeat(100)).is_ok());
+        assert!(PollOption::new("a".repeat(101)).is_err());
+    }
+
+    #[test]
+    fn test_display_name_validation() {
+        // テスト項目: 前後の空白を除いて 1〜32 文字の、制御文字を含まない表示名のみ作成できる
+        // when (操作) / then (期待する結果):
+        assert_eq!(
+            DisplayName::new("  Alice Smith ".to_string())
+                .unwrap()
+                .as_str(),
+            "Alice Smith"
+        );
+        assert!(DisplayName::new(" ".to_string()).is_err());
+        assert!(DisplayName::new("あ".repeat(32)).is_ok());
+        assert!(DisplayName::new("a".repeat(33)).is_err());
+        assert!(DisplayName::new("Alice\tSmith".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_activity_validation() {
+        // テスト項目: 前後の空白を除いて 1〜64 文字の、制御文字を含まないアクティビティのみ作成できる
+        // when (操作) / then (期待する結果):
+        assert_eq!(
+            Activity::new(" reviewing PR #42 ".to_string())
+                .unwrap()
+                .as_str(),
+            "reviewing PR #42"
+        );
+        assert!(Activity::new("  ".to_string()).is_err());
+        assert!(Activity::new("あ".repeat(64)).is_ok());
+        assert!(Activity::new("a".repeat(65)).is_err());
+        assert!(Activity::new("line\nbreak".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_cron_schedule_matches() {
+        // テスト項目: cron 式の範囲・間隔・リストに一致する時刻で発火し、日付と曜日は両方指定するといずれかで一致する
+        // given (前提条件):
+        let weekdays = CronSchedule::new("30 9 * * 1-5".to_string()).unwrap();
+        let quarter = CronSchedule::new("*/15 * * * *".to_string()).unwrap();
+        let either_day = CronSchedule::new("0 0 1 * 0".to_string()).unwrap();
+
+        // when (操作):
+        // 2023-01-02 is a Monday and 2023-01-08 a Sunday
+        let monday = weekdays.matches(30, 9, 2, 1, 1);
+        let sunday = weekdays.matches(30, 9, 8, 1, 0);
+        let late = weekdays.matches(31, 9, 2, 1, 1);
+
+        // then (期待する結果):
+        assert!(monday);
+        assert!(!sunday);
+        assert!(!late);
+        assert!(quarter.matches(45, 13, 5, 6, 2));
+        assert!(!quarter.matches(50, 13, 5, 6, 2));
+        assert!(either_day.matches(0, 0, 1, 3, 3));
+        assert!(either_day.matches(0, 0, 8, 1, 0));
+        assert!(!either_day.matches(0, 0, 2, 1, 1));
+        assert!(
+            CronSchedule::new("0 8 * * 7".to_string())
+                .unwrap()
+                .matches(0, 8, 8, 1, 0)
+        );
+    }
+
+    #[test]
+    fn test_cron_schedule_validation() {
+        // テスト項目: 5 つのフィールドが範囲内の cron 式だけを受け付け、空白を正規化する
+        // then (期待する結果):
+        assert_eq!(
+            CronSchedule::new("  0  9 * * 1,3,5 ".to_string())
+                .unwrap()
+                .as_str(),
+            "0 9 * * 1,3,5"
+        );
+        assert!(CronSchedule::new("0 9 * *".to_string()).is_err());
+        assert!(CronSchedule::new("60 9 * * *".to_string()).is_err());
+        assert!(CronSchedule::new("0 9 0 * *".to_string()).is_err());
+        assert!(CronSchedule::new("0 9 * * 8".to_string()).is_err());
+        assert!(CronSchedule::new("*/0 9 * * *".to_string()).is_err());
+        assert!(CronSchedule::new("5-1 9 * * *".to_string()).is_err());
+        assert!(CronSchedule::new("@daily".to_string()).is_err());
+    }
+
     #[test]
     fn test_timestamp_new() {
         // テスト項目: タイムスタンプを作成できる
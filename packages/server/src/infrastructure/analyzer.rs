@@ -0,0 +1,112 @@
+//! キーワードによるメッセージ分析
+//!
+//! ## 責務
+//!
+//! - 設定したキーワードを含むメッセージに `keyword:<キーワード>` のタグを付ける
+//!
+//! ## 設計ノート
+//!
+//! `MessageAnalyzer` のサンプル実装です。大文字・小文字を区別せず、英数字以外の文字で
+//! 区切った単語単位で照合します（`deploy` は `Deploy!` に一致し、`deployment` には一致しない）。
+//! メッセージごとのタグは 1 つのキーワードにつき 1 つで、出現頻度はルームの統計で
+//! タグの付いたメッセージ数として集計します。
+
+use async_trait::async_trait;
+
+use crate::domain::{ChatMessage, MessageAnalyzer, MessageTag, ValueObjectError};
+
+/// キーワードのタグの接頭辞
+pub const KEYWORD_TAG_PREFIX: &str = "keyword:";
+
+/// 設定したキーワードの出現でタグを付ける MessageAnalyzer
+pub struct KeywordAnalyzer {
+    /// 小文字にしたキーワードと、それを含むメッセージに付けるタグ
+    keywords: Vec<(String, MessageTag)>,
+}
+
+impl KeywordAnalyzer {
+    /// 新しい KeywordAnalyzer を作成
+    ///
+    /// # 引数
+    ///
+    /// - `keywords`: 集計するキーワード（大文字・小文字は区別しない）
+    ///
+    /// # エラー
+    ///
+    /// キーワードがタグにできない（空、空白を含む、長すぎる）場合
+    pub fn new(keywords: impl IntoIterator<Item = String>) -> Result<Self, ValueObjectError> {
+        let mut analyzer = Self {
+            keywords: Vec::new(),
+        };
+        for keyword in keywords {
+            let keyword = keyword.to_lowercase();
+            let tag = MessageTag::new(format!("{}{}", KEYWORD_TAG_PREFIX, keyword))?;
+            if keyword.is_empty() {
+                return Err(ValueObjectError::MessageTagInvalidFormat {
+                    tag: tag.into_string(),
+                    max: MessageTag::MAX_LEN,
+                });
+            }
+            if !analyzer.keywords.iter().any(|(known, _)| known == &keyword) {
+                analyzer.keywords.push((keyword, tag));
+            }
+        }
+        Ok(analyzer)
+    }
+
+    /// メッセージの内容に含まれるキーワードのタグ（設定した順）
+    pub fn tags_for(&self, content: &str) -> Vec<MessageTag> {
+        let content = content.to_lowercase();
+        let words: Vec<&str> = content
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        self.keywords
+            .iter()
+            .filter(|(keyword, _)| words.contains(&keyword.as_str()))
+            .map(|(_, tag)| tag.clone())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl MessageAnalyzer for KeywordAnalyzer {
+    async fn analyze(&self, message: &ChatMessage) -> Vec<MessageTag> {
+        self.tags_for(message.content.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tags_for_matches_whole_words_ignoring_case() {
+        // テスト項目: キーワードが大文字・小文字を区別せず単語単位で照合され、1 つにつきタグが 1 つ付く
+        // given (前提条件):
+        let analyzer =
+            KeywordAnalyzer::new(["Deploy".to_string(), "incident".to_string()]).unwrap();
+
+        // when (操作):
+        let both = analyzer.tags_for("Deploy failed, deploy again? INCIDENT opened");
+        let partial = analyzer.tags_for("deployment is done");
+
+        // then (期待する結果):
+        assert_eq!(
+            both,
+            vec![
+                MessageTag::new("keyword:deploy".to_string()).unwrap(),
+                MessageTag::new("keyword:incident".to_string()).unwrap(),
+            ]
+        );
+        assert!(partial.is_empty());
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_keywords() {
+        // テスト項目: 空のキーワードや空白を含むキーワードは設定できない
+        // when (操作) / then (期待する結果):
+        assert!(KeywordAnalyzer::new([String::new()]).is_err());
+        assert!(KeywordAnalyzer::new(["two words".to_string()]).is_err());
+    }
+}
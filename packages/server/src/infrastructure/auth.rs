@@ -0,0 +1,257 @@
+//! ログインの資格情報の検証とアクセストークン（JWT）の発行・検証
+//!
+//! ## 責務
+//!
+//! - 資格情報ファイル（1 行に `<client_id>:<argon2 のパスワードハッシュ>`）の読み込みとパスワードの検証
+//! - ログインしたクライアントへのアクセストークンの発行（HS256 で署名した JWT）
+//! - アクセストークンの検証（署名・発行者・有効期限）と `ClientIdentity` への変換
+//!
+//! ## 設計ノート
+//!
+//! トークンの `sub` はクライアント ID で、認証したクライアントはそのクライアント ID でのみ
+//! 接続できます。トークンは状態を持たないため、失効させるには署名のシークレットを変更します
+//! （発行済みのすべてのトークンが無効になる）。
+//! パスワードハッシュは `engawa-server hash-password` で作成します。
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use argon2::{
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+    password_hash::{SaltString, rand_core::OsRng},
+};
+use hmac::{Hmac, Mac};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, errors::ErrorKind};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use super::error::AuthError;
+use crate::domain::{ClientId, ClientIdentity};
+
+/// アクセストークンの既定の有効期間
+pub const DEFAULT_TOKEN_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// 署名のシークレットの最小の長さ（バイト数）
+pub const MIN_SECRET_LEN: usize = 32;
+
+/// トークンの発行者（`iss`）
+const ISSUER: &str = "engawa";
+
+/// ログインできるクライアントとそのパスワードハッシュ
+#[derive(Debug, Default)]
+pub struct Credentials {
+    /// クライアント ID ごとの PHC 形式のパスワードハッシュ
+    users: HashMap<String, String>,
+}
+
+impl Credentials {
+    /// 資格情報ファイルを読み込む
+    pub fn load(path: &Path) -> Result<Self, AuthError> {
+        let text = std::fs::read_to_string(path).map_err(|e| AuthError::CredentialsFile {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+        Self::parse(&text)
+    }
+
+    /// 資格情報を解析する（空行と `#` で始まる行は無視する）
+    pub fn parse(text: &str) -> Result<Self, AuthError> {
+        let mut users = HashMap::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (client_id, hash) = line
+                .split_once(':')
+                .filter(|(client_id, hash)| {
+                    ClientId::new(client_id.to_string()).is_ok() && PasswordHash::new(hash).is_ok()
+                })
+                .ok_or(AuthError::InvalidCredentials(i + 1))?;
+            users.insert(client_id.to_string(), hash.to_string());
+        }
+        Ok(Self { users })
+    }
+
+    /// ログインできるクライアントの数
+    pub fn len(&self) -> usize {
+        self.users.len()
+    }
+
+    /// ログインできるクライアントがいないか
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+
+    /// クライアント ID とパスワードを検証する
+    pub fn verify(&self, client_id: &str, password: &str) -> Result<ClientId, AuthError> {
+        let hash = self.users.get(client_id).ok_or(AuthError::LoginFailed)?;
+        let hash = PasswordHash::new(hash).map_err(|_| AuthError::LoginFailed)?;
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .map_err(|_| AuthError::LoginFailed)?;
+        ClientId::new(client_id.to_string()).map_err(|_| AuthError::LoginFailed)
+    }
+}
+
+/// パスワードハッシュを作成する（資格情報ファイル向け、ランダムな salt を使う）
+pub fn hash_password(password: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .expect("argon2 hashes passwords of any length with default parameters")
+        .to_string()
+}
+
+/// 管理者トークンや Webhook のトークンなどの共有シークレットが一致するかを定数時間で判定する
+///
+/// 文字列の比較は最初に異なるバイトで打ち切られ、応答時間から一致する接頭辞の長さが推測できる
+/// ため、両方の HMAC-SHA256 を計算して固定長のタグを定数時間で比較します
+/// （`proof_of_work` の署名の検証と同じ方式）。
+pub fn secrets_match(expected: &str, given: &str) -> bool {
+    let tag = |secret: &str| {
+        let mut mac = Hmac::<Sha256>::new_from_slice(ISSUER.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(secret.as_bytes());
+        mac
+    };
+    tag(given)
+        .verify_slice(&tag(expected).finalize().into_bytes())
+        .is_ok()
+}
+
+/// JWT のクレーム
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    /// クライアント ID
+    sub: String,
+    /// 発行者
+    iss: String,
+    /// 発行日時（UNIX 時間の秒数）
+    iat: u64,
+    /// 有効期限（UNIX 時間の秒数）
+    exp: u64,
+}
+
+/// アクセストークンの発行と検証
+pub struct AccessTokens {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    validation: Validation,
+    ttl: Duration,
+}
+
+impl AccessTokens {
+    /// 署名のシークレットと有効期間を指定して作成する
+    pub fn new(secret: &[u8], ttl: Duration) -> Self {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_issuer(&[ISSUER]);
+        validation.set_required_spec_claims(&["exp", "iss", "sub"]);
+        validation.leeway = 0;
+        Self {
+            encoding: EncodingKey::from_secret(secret),
+            decoding: DecodingKey::from_secret(secret),
+            validation,
+            ttl,
+        }
+    }
+
+    /// トークンの有効期間
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// クライアントのトークンを発行する（`now` から有効期間の間有効）
+    pub fn issue(&self, client_id: &ClientId, now: SystemTime) -> String {
+        let iat = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let claims = Claims {
+            sub: client_id.as_str().to_string(),
+            iss: ISSUER.to_string(),
+            iat,
+            exp: iat + self.ttl.as_secs(),
+        };
+        jsonwebtoken::encode(&Header::new(Algorithm::HS256), &claims, &self.encoding)
+            .expect("HS256 signs any claims")
+    }
+
+    /// トークンを検証し、認証したクライアントを返す（有効期限は現在時刻で判定する）
+    pub fn verify(&self, token: &str) -> Result<ClientIdentity, AuthError> {
+        let claims = jsonwebtoken::decode::<Claims>(token, &self.decoding, &self.validation)
+            .map_err(|e| match e.kind() {
+                ErrorKind::ExpiredSignature => AuthError::ExpiredToken,
+                _ => AuthError::InvalidToken,
+            })?
+            .claims;
+        let client_id = ClientId::new(claims.sub).map_err(|_| AuthError::InvalidToken)?;
+        Ok(ClientIdentity::new(client_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn test_secrets_match() {
+        // テスト項目: 共有シークレットは完全に一致する場合だけ一致と判定される
+        // when (操作):
+        let same = secrets_match("admin-token", "admin-token");
+        let prefix = secrets_match("admin-token", "admin");
+        let different = secrets_match("admin-token", "admin-tokeN");
+        let empty = secrets_match("admin-token", "");
+
+        // then (期待する結果):
+        assert!(same);
+        assert!(!prefix);
+        assert!(!different);
+        assert!(!empty);
+    }
+
+    #[test]
+    fn test_credentials_verify_password() {
+        // テスト項目: 資格情報ファイルのパスワードハッシュでログインを検証し、不正な行を拒否する
+        // given (前提条件):
+        let text = format!("# users\nalice:{}\n\n", hash_password("s3cret"));
+        let credentials = Credentials::parse(&text).unwrap();
+
+        // when (操作):
+        let ok = credentials.verify("alice", "s3cret");
+        let wrong_password = credentials.verify("alice", "guess");
+        let unknown = credentials.verify("bob", "s3cret");
+        let malformed = Credentials::parse("alice:plaintext\n");
+
+        // then (期待する結果):
+        assert_eq!(ok.unwrap().as_str(), "alice");
+        assert_eq!(wrong_password, Err(AuthError::LoginFailed));
+        assert_eq!(unknown, Err(AuthError::LoginFailed));
+        assert!(matches!(malformed, Err(AuthError::InvalidCredentials(1))));
+    }
+
+    #[test]
+    fn test_access_tokens_round_trip() {
+        // テスト項目: 発行したトークンからクライアントを取得でき、期限切れ・別のシークレットのトークンは拒否される
+        // given (前提条件):
+        let tokens = AccessTokens::new(SECRET, DEFAULT_TOKEN_TTL);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let now = SystemTime::now();
+
+        // when (操作):
+        let valid = tokens.verify(&tokens.issue(&alice, now));
+        let expired = tokens.verify(&tokens.issue(&alice, now - DEFAULT_TOKEN_TTL * 2));
+        let other_secret =
+            AccessTokens::new(b"another secret of at least 32 bytes", DEFAULT_TOKEN_TTL)
+                .verify(&tokens.issue(&alice, now));
+        let garbage = tokens.verify("not-a-token");
+
+        // then (期待する結果):
+        assert_eq!(valid, Ok(ClientIdentity::new(alice)));
+        assert_eq!(expired, Err(AuthError::ExpiredToken));
+        assert_eq!(other_secret, Err(AuthError::InvalidToken));
+        assert_eq!(garbage, Err(AuthError::InvalidToken));
+    }
+}
@@ -0,0 +1,100 @@
+//! 送信キューの滞留に応じたクライアントへの減速の要請（slow-down）
+//!
+//! ## 責務
+//!
+//! - クライアントの送信キューに溜まっているメッセージ数の監視
+//! - しきい値を超えた時と解消した時に送る `slow-down` メッセージの作成
+//!
+//! ## 設計ノート
+//!
+//! 送信キューが溜まるのは、クライアントが受信を処理しきれていない時です（一度に大量に送信する
+//! bot など）。しきい値を超えると `send_interval_ms` の間隔を空けて送信するよう要請し、
+//! しきい値の 1/4 以下まで減ると `send_interval_ms: 0` で要請を解除します。
+//! 要請と解除の件数に差を設け、境界付近で通知が繰り返されないようにしています。
+//!
+//! 通知は送信キューを経由せず、滞留しているメッセージより先に接続へ書き込みます
+//! （[`WebSocketMessagePusher::pump`](super::message_pusher::WebSocketMessagePusher::pump)）。
+//! 要請に従うかはクライアント次第で、従わないクライアントを切断することはありません。
+
+use std::time::Duration;
+
+use super::dto::websocket::{MessageType, SlowDownMessage};
+
+/// 減速を要請する送信キューのメッセージ数の既定値
+pub const DEFAULT_SLOW_DOWN_THRESHOLD: usize = 256;
+
+/// 減速を要請する間、クライアントに求める送信の間隔
+pub const SLOW_DOWN_SEND_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 接続ごとの送信キューの滞留の状態
+#[derive(Debug, Clone)]
+pub struct Backpressure {
+    /// 減速を要請するメッセージ数
+    threshold: usize,
+    /// 減速を要請中か
+    slowed: bool,
+}
+
+impl Backpressure {
+    /// 減速を要請するメッセージ数（1 以上）を指定して作成
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            slowed: false,
+        }
+    }
+
+    /// 送信キューに残っているメッセージ数を記録し、要請・解除を知らせる場合はそのメッセージ（JSON）を返す
+    pub fn observe(&mut self, depth: usize) -> Option<String> {
+        let send_interval = if !self.slowed && depth >= self.threshold {
+            SLOW_DOWN_SEND_INTERVAL
+        } else if self.slowed && depth <= self.threshold / 4 {
+            Duration::ZERO
+        } else {
+            return None;
+        };
+        self.slowed = !send_interval.is_zero();
+        let message = SlowDownMessage {
+            r#type: MessageType::SlowDown,
+            queue_depth: depth,
+            send_interval_ms: send_interval.as_millis() as u64,
+        };
+        Some(serde_json::to_string(&message).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn send_interval_ms(advice: Option<String>) -> Option<u64> {
+        advice.map(|json| {
+            serde_json::from_str::<SlowDownMessage>(&json)
+                .unwrap()
+                .send_interval_ms
+        })
+    }
+
+    #[test]
+    fn test_observe_advises_once_until_the_queue_drains() {
+        // テスト項目: しきい値に達すると 1 回だけ減速を要請し、1/4 以下まで減ると 1 回だけ解除する
+        // given (前提条件):
+        let mut backpressure = Backpressure::new(8);
+
+        // when (操作):
+        let below = backpressure.observe(7);
+        let crossed = backpressure.observe(8);
+        let still_high = backpressure.observe(12);
+        let draining = backpressure.observe(3);
+        let drained = backpressure.observe(2);
+        let idle = backpressure.observe(0);
+
+        // then (期待する結果):
+        assert_eq!(send_interval_ms(below), None);
+        assert_eq!(send_interval_ms(crossed), Some(500));
+        assert_eq!(send_interval_ms(still_high), None);
+        assert_eq!(send_interval_ms(draining), None);
+        assert_eq!(send_interval_ms(drained), Some(0));
+        assert_eq!(send_interval_ms(idle), None);
+    }
+}
@@ -0,0 +1,430 @@
+//! クラスタのメンバーシップ管理（ゴシッププロトコル）
+//!
+//! ## 責務
+//!
+//! - 自ノードと既知のノードの状態（参加者数・ハートビート）の保持
+//! - 受信したゴシップのマージ（ハートビートが大きい方を採用）
+//! - ハートビートが進まないノードの `Suspect` / `Dead` 判定と削除
+//! - ゴシップの送信先の選択
+//! - ルームの担当ノードの決定（`Alive` のノードによるコンシステントハッシュ）
+//!
+//! UDP での送受信は UI 層のゴシップタスクが行います。時刻は引数で受け取るため、
+//! このモジュールは I/O を持ちません。
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use super::{
+    dto::cluster::{GossipMessage, GossipNode},
+    hash_ring::HashRing,
+};
+
+/// ハートビートが進まない場合に `Suspect` とみなすまでの時間
+pub const SUSPECT_AFTER: Duration = Duration::from_secs(5);
+
+/// ハートビートが進まない場合に `Dead` とみなすまでの時間
+pub const DEAD_AFTER: Duration = Duration::from_secs(15);
+
+/// `Dead` のノードをメンバーシップから削除するまでの時間
+pub const FORGET_AFTER: Duration = Duration::from_secs(60);
+
+/// ノードの状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeStatus {
+    Alive,
+    Suspect,
+    Dead,
+}
+
+impl NodeStatus {
+    /// 文字列表現（`alive` / `suspect` / `dead`）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeStatus::Alive => "alive",
+            NodeStatus::Suspect => "suspect",
+            NodeStatus::Dead => "dead",
+        }
+    }
+}
+
+/// メンバーシップから見たノード
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClusterNodeView {
+    pub node: GossipNode,
+    pub status: NodeStatus,
+    /// ハートビートが最後に進んでからの経過時間（自ノードは 0）
+    pub last_seen: Duration,
+    pub is_self: bool,
+}
+
+struct Peer {
+    node: GossipNode,
+    /// ハートビートが最後に進んだ時刻
+    updated_at: Instant,
+}
+
+/// クラスタのメンバーシップ
+pub struct ClusterMembership {
+    local: GossipNode,
+    peers: HashMap<String, Peer>,
+    /// 送信先をラウンドロビンで選ぶためのオフセット
+    round: usize,
+}
+
+impl ClusterMembership {
+    /// 新しいメンバーシップを作成
+    ///
+    /// # 引数
+    ///
+    /// - `node_id`: 自ノードの ID（クラスタ内で一意）
+    /// - `gossip_addr`: 他ノードから見たゴシップの受信アドレス
+    /// - `http_addr`: 他ノードから見たクライアント向けのアドレス
+    /// - `initial_heartbeat`: ハートビートの初期値（再起動後も増え続けるよう現在時刻を渡す）
+    pub fn new(
+        node_id: String,
+        gossip_addr: SocketAddr,
+        http_addr: String,
+        initial_heartbeat: u64,
+    ) -> Self {
+        Self {
+            local: GossipNode {
+                node_id,
+                gossip_addr: gossip_addr.to_string(),
+                http_addr,
+                participant_count: 0,
+                heartbeat: initial_heartbeat,
+            },
+            peers: HashMap::new(),
+            round: 0,
+        }
+    }
+
+    /// 自ノードの ID
+    pub fn node_id(&self) -> &str {
+        &self.local.node_id
+    }
+
+    /// ハートビートを進め、送信するゴシップを作成
+    ///
+    /// ゴシップには自ノードと `Alive` / `Suspect` のノードのみを含める（`Dead` のノードを
+    /// 他ノードで復活させないため）。
+    pub fn heartbeat(&mut self, participant_count: usize, now: Instant) -> GossipMessage {
+        self.local.heartbeat += 1;
+        self.local.participant_count = participant_count;
+
+        let mut nodes = vec![self.local.clone()];
+        nodes.extend(
+            self.peers
+                .values()
+                .filter(|peer| status_of(peer, now) != NodeStatus::Dead)
+                .map(|peer| peer.node.clone()),
+        );
+        GossipMessage {
+            sender: self.local.node_id.clone(),
+            nodes,
+            leaving: false,
+        }
+    }
+
+    /// 自ノードが離脱することを伝えるゴシップを作成
+    pub fn leave(&self) -> GossipMessage {
+        GossipMessage {
+            sender: self.local.node_id.clone(),
+            nodes: vec![self.local.clone()],
+            leaving: true,
+        }
+    }
+
+    /// 受信したゴシップをマージ
+    pub fn merge(&mut self, message: GossipMessage, now: Instant) {
+        if message.leaving {
+            self.peers.remove(&message.sender);
+            return;
+        }
+
+        for node in message.nodes {
+            if node.node_id == self.local.node_id {
+                continue;
+            }
+            match self.peers.get_mut(&node.node_id) {
+                Some(peer) if peer.node.heartbeat >= node.heartbeat => {}
+                Some(peer) => {
+                    peer.node = node;
+                    peer.updated_at = now;
+                }
+                None => {
+                    self.peers.insert(
+                        node.node_id.clone(),
+                        Peer {
+                            node,
+                            updated_at: now,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    /// `Dead` になってから十分に時間が経ったノードを削除
+    pub fn expire(&mut self, now: Instant) {
+        self.peers
+            .retain(|_, peer| now.duration_since(peer.updated_at) < FORGET_AFTER);
+    }
+
+    /// ゴシップの送信先を選択
+    ///
+    /// `Dead` でないノードからラウンドロビンで最大 `fanout` 件を選び、
+    /// `Alive` として知らないシードノードにも送る（起動直後や分断からの復帰）。
+    pub fn gossip_targets(
+        &mut self,
+        seeds: &[SocketAddr],
+        fanout: usize,
+        now: Instant,
+    ) -> Vec<SocketAddr> {
+        let mut candidates: Vec<&Peer> = self
+            .peers
+            .values()
+            .filter(|peer| status_of(peer, now) != NodeStatus::Dead)
+            .collect();
+        candidates.sort_by(|a, b| a.node.node_id.cmp(&b.node.node_id));
+
+        let mut targets: Vec<SocketAddr> = Vec::new();
+        if !candidates.is_empty() {
+            let count = fanout.min(candidates.len());
+            targets.extend(
+                (0..count)
+                    .map(|i| candidates[(self.round + i) % candidates.len()])
+                    .filter_map(|peer| peer.node.gossip_addr.parse::<SocketAddr>().ok()),
+            );
+            self.round = (self.round + count) % candidates.len();
+        }
+
+        // シードは既知のノードになるまで送り続ける（シード同士が別々の島にならないよう）
+        for seed in seeds {
+            let seed_addr = seed.to_string();
+            let known = seed_addr == self.local.gossip_addr
+                || self.peers.values().any(|peer| {
+                    peer.node.gossip_addr == seed_addr && status_of(peer, now) == NodeStatus::Alive
+                });
+            if !known && !targets.contains(seed) {
+                targets.push(*seed);
+            }
+        }
+        targets
+    }
+
+    /// ルームを担当するノード
+    ///
+    /// 自ノードと `Alive` のノードでハッシュリングを作るため、`Suspect` になったノードの
+    /// ルームは他のノードに移る。
+    pub fn room_owner(&self, room: &str, now: Instant) -> &GossipNode {
+        let alive: Vec<&GossipNode> = self
+            .peers
+            .values()
+            .filter(|peer| status_of(peer, now) == NodeStatus::Alive)
+            .map(|peer| &peer.node)
+            .collect();
+        let ring = HashRing::new(
+            alive
+                .iter()
+                .map(|node| node.node_id.as_str())
+                .chain([self.local.node_id.as_str()]),
+        );
+        match ring.owner(room) {
+            Some(owner) if owner != self.local.node_id => alive
+                .into_iter()
+                .find(|node| node.node_id == owner)
+                .unwrap_or(&self.local),
+            _ => &self.local,
+        }
+    }
+
+    /// 自ノードを先頭に、既知のノードを ID 順に返す
+    pub fn nodes(&self, now: Instant) -> Vec<ClusterNodeView> {
+        let mut peers: Vec<ClusterNodeView> = self
+            .peers
+            .values()
+            .map(|peer| ClusterNodeView {
+                node: peer.node.clone(),
+                status: status_of(peer, now),
+                last_seen: now.duration_since(peer.updated_at),
+                is_self: false,
+            })
+            .collect();
+        peers.sort_by(|a, b| a.node.node_id.cmp(&b.node.node_id));
+
+        let mut nodes = vec![ClusterNodeView {
+            node: self.local.clone(),
+            status: NodeStatus::Alive,
+            last_seen: Duration::ZERO,
+            is_self: true,
+        }];
+        nodes.extend(peers);
+        nodes
+    }
+}
+
+fn status_of(peer: &Peer, now: Instant) -> NodeStatus {
+    let elapsed = now.duration_since(peer.updated_at);
+    if elapsed >= DEAD_AFTER {
+        NodeStatus::Dead
+    } else if elapsed >= SUSPECT_AFTER {
+        NodeStatus::Suspect
+    } else {
+        NodeStatus::Alive
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn membership(node_id: &str, port: u16) -> ClusterMembership {
+        ClusterMembership::new(
+            node_id.to_string(),
+            SocketAddr::from(([127, 0, 0, 1], port)),
+            format!("127.0.0.1:{}", port + 1000),
+            0,
+        )
+    }
+
+    fn addr(port: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], port))
+    }
+
+    #[test]
+    fn test_merge_keeps_highest_heartbeat() {
+        // テスト項目: 受信したノードはハートビートが大きい場合のみ更新される
+        // given (前提条件):
+        let now = Instant::now();
+        let mut a = membership("a", 7001);
+        let mut b = membership("b", 7002);
+        let newer = b.heartbeat(3, now);
+        let older = GossipMessage {
+            nodes: vec![GossipNode {
+                participant_count: 99,
+                heartbeat: 0,
+                ..newer.nodes[0].clone()
+            }],
+            ..newer.clone()
+        };
+
+        // when (操作):
+        a.merge(newer, now);
+        a.merge(older, now);
+
+        // then (期待する結果): 自ノードの次に b が参加者数 3 で並ぶ
+        let nodes = a.nodes(now);
+        assert_eq!(nodes.len(), 2);
+        assert!(nodes[0].is_self);
+        assert_eq!(nodes[1].node.node_id, "b");
+        assert_eq!(nodes[1].node.participant_count, 3);
+    }
+
+    #[test]
+    fn test_status_transitions_and_expire() {
+        // テスト項目: ハートビートが進まないノードは Suspect → Dead となり、やがて削除される
+        // given (前提条件):
+        let now = Instant::now();
+        let mut a = membership("a", 7001);
+        let mut b = membership("b", 7002);
+        a.merge(b.heartbeat(0, now), now);
+
+        // then (期待する結果):
+        assert_eq!(a.nodes(now)[1].status, NodeStatus::Alive);
+        assert_eq!(a.nodes(now + SUSPECT_AFTER)[1].status, NodeStatus::Suspect);
+        assert_eq!(a.nodes(now + DEAD_AFTER)[1].status, NodeStatus::Dead);
+
+        // Dead のノードはゴシップに含めない
+        let gossip = a.heartbeat(0, now + DEAD_AFTER);
+        assert_eq!(gossip.nodes.len(), 1);
+
+        a.expire(now + FORGET_AFTER);
+        assert_eq!(a.nodes(now + FORGET_AFTER).len(), 1);
+    }
+
+    #[test]
+    fn test_gossip_propagates_transitively() {
+        // テスト項目: 間接的に知ったノードもメンバーシップに加わる
+        // given (前提条件):
+        let now = Instant::now();
+        let mut a = membership("a", 7001);
+        let mut b = membership("b", 7002);
+        let mut c = membership("c", 7003);
+        b.merge(c.heartbeat(1, now), now);
+
+        // when (操作):
+        a.merge(b.heartbeat(2, now), now);
+
+        // then (期待する結果):
+        let ids: Vec<String> = a.nodes(now).into_iter().map(|n| n.node.node_id).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_leave_removes_sender() {
+        // テスト項目: 離脱を伝えるゴシップを受けると送信元が即座に削除される
+        // given (前提条件):
+        let now = Instant::now();
+        let mut a = membership("a", 7001);
+        let mut b = membership("b", 7002);
+        a.merge(b.heartbeat(0, now), now);
+
+        // when (操作):
+        a.merge(b.leave(), now);
+
+        // then (期待する結果):
+        assert_eq!(a.nodes(now).len(), 1);
+    }
+
+    #[test]
+    fn test_gossip_targets() {
+        // テスト項目: 未知のシードには常に送り、既知のノードはラウンドロビンで選ぶ
+        // given (前提条件):
+        let now = Instant::now();
+        let mut a = membership("a", 7001);
+        let seeds = [addr(7001), addr(7002)];
+
+        // then (期待する結果): 自ノードのアドレスはシードから除かれる
+        assert_eq!(a.gossip_targets(&seeds, 2, now), vec![addr(7002)]);
+
+        for (id, port) in [("b", 7002), ("c", 7003), ("d", 7004)] {
+            a.merge(membership(id, port).heartbeat(0, now), now);
+        }
+        assert_eq!(
+            a.gossip_targets(&seeds, 2, now),
+            vec![addr(7002), addr(7003)]
+        );
+        assert_eq!(
+            a.gossip_targets(&seeds, 2, now),
+            vec![addr(7004), addr(7002)]
+        );
+    }
+
+    #[test]
+    fn test_room_owner_moves_with_membership() {
+        // テスト項目: ルームは Alive のノードに割り当てられ、ノードが Suspect になると他のノードに移る
+        // given (前提条件):
+        let now = Instant::now();
+        let mut a = membership("a", 7001);
+        let mut b = membership("b", 7002);
+        a.merge(b.heartbeat(0, now), now);
+        let rooms: Vec<String> = (0..100).map(|i| format!("room-{}", i)).collect();
+
+        // when (操作):
+        let owners: Vec<String> = rooms
+            .iter()
+            .map(|room| a.room_owner(room, now).node_id.clone())
+            .collect();
+
+        // then (期待する結果): 両方のノードが担当を持ち、b が Suspect になると全て a の担当になる
+        assert!(owners.iter().any(|owner| owner == "a"));
+        assert!(owners.iter().any(|owner| owner == "b"));
+        for room in &rooms {
+            assert_eq!(a.room_owner(room, now + SUSPECT_AFTER).node_id, "a");
+        }
+    }
+}
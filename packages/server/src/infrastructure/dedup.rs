@@ -0,0 +1,127 @@
+//! 配信済みメッセージの重複排除ウィンドウ
+//!
+//! ## 責務
+//!
+//! - 配信済みのシーケンス番号の記録（直近 `capacity` 件）
+//! - 再送・バックフィルで重なったメッセージの判定
+//!
+//! ## 設計ノート
+//!
+//! ウィンドウから押し出されたシーケンス番号以下は全て配信済みとみなします（`floor`）。
+//! サーバは接続ごとに、クライアントは再接続をまたいで同じウィンドウを使うため、
+//! 範囲が重なる再送があっても同じメッセージが 2 回表示されることはありません。
+
+use std::collections::BTreeSet;
+
+/// 重複排除ウィンドウの既定のサイズ
+pub const DEFAULT_DEDUP_WINDOW: usize = 1024;
+
+/// 重複排除ウィンドウ
+#[derive(Debug, Clone)]
+pub struct DedupWindow {
+    capacity: usize,
+    /// このシーケンス番号以下は全て配信済み
+    floor: u64,
+    /// `floor` より大きい配信済みのシーケンス番号
+    seen: BTreeSet<u64>,
+}
+
+impl DedupWindow {
+    /// 指定したサイズ（1 以上）のウィンドウを作成
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            floor: 0,
+            seen: BTreeSet::new(),
+        }
+    }
+
+    /// `last_seq` 以下を配信済みとする（再接続時の再開位置）
+    pub fn resume_from(&mut self, last_seq: u64) {
+        self.floor = self.floor.max(last_seq);
+        let floor = self.floor;
+        self.seen.retain(|seq| *seq > floor);
+    }
+
+    /// シーケンス番号を記録し、初めて配信するものであれば `true` を返す
+    pub fn insert(&mut self, seq: u64) -> bool {
+        if seq <= self.floor || !self.seen.insert(seq) {
+            return false;
+        }
+        while self.seen.len() > self.capacity {
+            if let Some(oldest) = self.seen.pop_first() {
+                self.floor = oldest;
+            }
+        }
+        true
+    }
+
+    /// 配信済みの最大のシーケンス番号（無い場合は 0）
+    pub fn last_seq(&self) -> u64 {
+        self.seen.last().copied().unwrap_or(self.floor)
+    }
+
+    /// 記録を全て消去（ルームが作り直された場合など）
+    pub fn reset(&mut self) {
+        self.floor = 0;
+        self.seen.clear();
+    }
+}
+
+impl Default for DedupWindow {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEDUP_WINDOW)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_rejects_duplicates() {
+        // テスト項目: 同じシーケンス番号は 2 回目以降は重複と判定される（順不同でも可）
+        // given (前提条件):
+        let mut window = DedupWindow::new(8);
+
+        // then (期待する結果):
+        assert!(window.insert(2));
+        assert!(window.insert(1));
+        assert!(!window.insert(2));
+        assert!(!window.insert(1));
+        assert_eq!(window.last_seq(), 2);
+    }
+
+    #[test]
+    fn test_overlapping_resend_after_resume() {
+        // テスト項目: 再開位置以下と、再開後に配信済みの範囲は重複と判定される
+        // given (前提条件):
+        let mut window = DedupWindow::new(8);
+        window.resume_from(10);
+        assert!(window.insert(11));
+
+        // when (操作): 9〜12 が再送される
+        let delivered: Vec<u64> = (9..=12).filter(|seq| window.insert(*seq)).collect();
+
+        // then (期待する結果):
+        assert_eq!(delivered, vec![12]);
+    }
+
+    #[test]
+    fn test_window_slides_when_full() {
+        // テスト項目: ウィンドウから押し出された番号以下は配信済みとみなされる
+        // given (前提条件):
+        let mut window = DedupWindow::new(3);
+        for seq in [1, 2, 4, 5] {
+            assert!(window.insert(seq));
+        }
+
+        // then (期待する結果): 1 が押し出され、1 以下は重複、3 はまだ配信できる
+        assert!(!window.insert(1));
+        assert!(window.insert(3));
+        assert_eq!(window.last_seq(), 5);
+
+        window.reset();
+        assert!(window.insert(1));
+    }
+}
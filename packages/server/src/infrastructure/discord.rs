@@ -0,0 +1,245 @@
+//! Discord REST API クライアント
+//!
+//! ## 責務
+//!
+//! - Discord Bot トークンを使ったチャンネルメッセージの取得・投稿
+//! - レートリミットの遵守（`429` の `retry_after` と `X-RateLimit-*` ヘッダー）
+//!
+//! Gateway（WebSocket）は使わず REST API のポーリングで受信するため、
+//! Bot には `MESSAGE_CONTENT` インテントと対象チャンネルの閲覧・投稿権限が必要です。
+
+use std::time::Duration;
+
+use reqwest::{RequestBuilder, Response, StatusCode, header::HeaderMap};
+
+use super::{
+    dto::discord::{
+        DiscordAllowedMentions, DiscordCreateMessage, DiscordMessage, DiscordRateLimit, DiscordUser,
+    },
+    error::DiscordError,
+};
+
+/// Discord REST API のベース URL
+pub const DEFAULT_API_BASE: &str = "https://discord.com/api/v10";
+
+/// Discord のメッセージ長の上限（文字数）
+pub const MAX_MESSAGE_LENGTH: usize = 2000;
+
+/// Discord エポック（2015-01-01T00:00:00Z、ミリ秒）
+const DISCORD_EPOCH_MILLIS: i64 = 1_420_070_400_000;
+
+/// `429` を受けた際の最大リトライ回数
+const MAX_RETRIES: usize = 3;
+
+/// Discord REST API クライアント
+pub struct DiscordClient {
+    http: reqwest::Client,
+    api_base: String,
+    token: String,
+}
+
+impl DiscordClient {
+    /// 新しい DiscordClient を作成
+    ///
+    /// # 引数
+    ///
+    /// - `token`: Bot トークン
+    /// - `api_base`: REST API のベース URL（通常は `DEFAULT_API_BASE`）
+    pub fn new(token: String, api_base: String) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            api_base: api_base.trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    /// Bot 自身のユーザー情報を取得
+    pub async fn current_user(&self) -> Result<DiscordUser, DiscordError> {
+        let request = self.http.get(format!("{}/users/@me", self.api_base));
+        json(self.send(request).await?).await
+    }
+
+    /// 指定したメッセージ ID より後のメッセージを古い順に取得（最大 100 件）
+    pub async fn messages_after(
+        &self,
+        channel_id: &str,
+        after: u64,
+    ) -> Result<Vec<DiscordMessage>, DiscordError> {
+        let request = self
+            .http
+            .get(format!(
+                "{}/channels/{}/messages",
+                self.api_base, channel_id
+            ))
+            .query(&[("after", after.to_string()), ("limit", "100".to_string())]);
+        let mut messages: Vec<DiscordMessage> = json(self.send(request).await?).await?;
+        // Discord は新しい順に返すため、ID（時刻順）で並べ替える
+        messages.sort_by_key(|message| message.id.parse::<u64>().unwrap_or(0));
+        Ok(messages)
+    }
+
+    /// チャンネルにメッセージを投稿（メンションは無効化）
+    pub async fn create_message(
+        &self,
+        channel_id: &str,
+        content: &str,
+    ) -> Result<(), DiscordError> {
+        let body = DiscordCreateMessage {
+            content: content.chars().take(MAX_MESSAGE_LENGTH).collect(),
+            allowed_mentions: DiscordAllowedMentions::default(),
+        };
+        let request = self
+            .http
+            .post(format!(
+                "{}/channels/{}/messages",
+                self.api_base, channel_id
+            ))
+            .json(&body);
+        self.send(request).await?;
+        Ok(())
+    }
+
+    /// リクエストを送信し、レートリミットに従って待機・リトライする
+    async fn send(&self, request: RequestBuilder) -> Result<Response, DiscordError> {
+        let request = request.header("Authorization", format!("Bot {}", self.token));
+
+        for _ in 0..=MAX_RETRIES {
+            let attempt = request
+                .try_clone()
+                .ok_or_else(|| DiscordError::Request("request is not cloneable".to_string()))?;
+            let response = attempt
+                .send()
+                .await
+                .map_err(|e| DiscordError::Request(e.to_string()))?;
+
+            if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                let headers = response.headers().clone();
+                let body = response.text().await.unwrap_or_default();
+                let delay = retry_after(&headers, &body);
+                tracing::warn!("Rate limited by Discord, retrying after {:?}", delay);
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+
+            if !response.status().is_success() {
+                let status = response.status().as_u16();
+                let body = response.text().await.unwrap_or_default();
+                return Err(DiscordError::Api { status, body });
+            }
+
+            // バケットを使い切った場合は次のリクエスト前にリセットを待つ
+            if let Some(delay) = bucket_exhausted_delay(response.headers()) {
+                tokio::time::sleep(delay).await;
+            }
+            return Ok(response);
+        }
+
+        Err(DiscordError::RateLimited)
+    }
+}
+
+async fn json<T: serde::de::DeserializeOwned>(response: Response) -> Result<T, DiscordError> {
+    response
+        .json()
+        .await
+        .map_err(|e| DiscordError::Request(e.to_string()))
+}
+
+/// `429` レスポンスから待機時間を求める（body の `retry_after` → `Retry-After` ヘッダー → 1 秒）
+pub fn retry_after(headers: &HeaderMap, body: &str) -> Duration {
+    serde_json::from_str::<DiscordRateLimit>(body)
+        .ok()
+        .map(|limit| limit.retry_after)
+        .or_else(|| header_seconds(headers, "retry-after"))
+        .map(Duration::from_secs_f64)
+        .unwrap_or(Duration::from_secs(1))
+}
+
+/// バケットの残りが 0 の場合、リセットまでの待機時間を返す
+pub fn bucket_exhausted_delay(headers: &HeaderMap) -> Option<Duration> {
+    let remaining = header_seconds(headers, "x-ratelimit-remaining")?;
+    if remaining > 0.0 {
+        return None;
+    }
+    header_seconds(headers, "x-ratelimit-reset-after").map(Duration::from_secs_f64)
+}
+
+fn header_seconds(headers: &HeaderMap, name: &str) -> Option<f64> {
+    headers
+        .get(name)?
+        .to_str()
+        .ok()?
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite() && *value >= 0.0)
+}
+
+/// Unix 時刻（ミリ秒）に対応する Snowflake を求める
+///
+/// `after` に指定すると、その時刻以降のメッセージのみを取得できる。
+pub fn snowflake_at(unix_millis: i64) -> u64 {
+    ((unix_millis - DISCORD_EPOCH_MILLIS).max(0) as u64) << 22
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_retry_after_from_body() {
+        // テスト項目: 429 の body の retry_after が優先して使われる
+        // given (前提条件):
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("5"));
+
+        // when (操作):
+        let delay = retry_after(
+            &headers,
+            r#"{"message": "You are being rate limited.", "retry_after": 0.25, "global": false}"#,
+        );
+
+        // then (期待する結果):
+        assert_eq!(delay, Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_retry_after_fallbacks() {
+        // テスト項目: body が読めない場合は Retry-After ヘッダー、それも無ければ 1 秒になる
+        // given (前提条件):
+        let mut headers = HeaderMap::new();
+        headers.insert("retry-after", HeaderValue::from_static("2"));
+
+        // then (期待する結果):
+        assert_eq!(retry_after(&headers, ""), Duration::from_secs(2));
+        assert_eq!(retry_after(&HeaderMap::new(), ""), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_bucket_exhausted_delay() {
+        // テスト項目: X-RateLimit-Remaining が 0 の場合のみリセットまで待機する
+        // given (前提条件):
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("x-ratelimit-reset-after", HeaderValue::from_static("1.5"));
+        let mut remaining = headers.clone();
+        remaining.insert("x-ratelimit-remaining", HeaderValue::from_static("3"));
+
+        // then (期待する結果):
+        assert_eq!(
+            bucket_exhausted_delay(&headers),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(bucket_exhausted_delay(&remaining), None);
+        assert_eq!(bucket_exhausted_delay(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_snowflake_at() {
+        // テスト項目: Unix 時刻から Snowflake が求められる
+        // then (期待する結果): Discord エポックは 0、1 ミリ秒ごとに 1 << 22 増える
+        assert_eq!(snowflake_at(DISCORD_EPOCH_MILLIS), 0);
+        assert_eq!(snowflake_at(DISCORD_EPOCH_MILLIS + 1), 1 << 22);
+        assert_eq!(snowflake_at(0), 0);
+    }
+}
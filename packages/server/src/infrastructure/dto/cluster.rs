@@ -0,0 +1,30 @@
+//! Cluster gossip DTOs.
+//!
+//! Gossip messages are sent as JSON in UDP datagrams between server nodes.
+
+use serde::{Deserialize, Serialize};
+
+/// Gossip message sent to a few peers every round
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GossipMessage {
+    /// Node ID of the sender
+    pub sender: String,
+    /// The sender's own state followed by the peers it considers alive
+    pub nodes: Vec<GossipNode>,
+    /// The sender is shutting down and should be removed right away
+    #[serde(default)]
+    pub leaving: bool,
+}
+
+/// State of a node as known by the sender
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GossipNode {
+    pub node_id: String,
+    /// Address receiving gossip (UDP)
+    pub gossip_addr: String,
+    /// Address serving clients (HTTP / WebSocket)
+    pub http_addr: String,
+    pub participant_count: usize,
+    /// Incremented by the node every round; the highest value wins when merging
+    pub heartbeat: u64,
+}
@@ -1,22 +1,57 @@
-//! Conversion logic between DTOs and domain entities.
+//! Conversion logic from received DTOs to domain entities.
+//!
+//! The conversions from domain entities to response DTOs are in `ui::presenter`.
 
 use crate::domain::{
     entity,
-    value_object::{ClientId, MessageContent, Timestamp},
+    value_object::{
+        Activity, ClientId, DisplayName, MessageContent, PollOption, RoomId, SequenceNumber,
+        Timestamp,
+    },
 };
 use crate::infrastructure::dto::websocket as dto;
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+use crate::{
+    domain::{ValueObjectError, value_object::MessageTag},
+    infrastructure::{
+        dto::database::{MessageData, RoomData},
+        error::DatabaseError,
+    },
+};
 
 // ========================================
 // DTO → Domain Entity
 // ========================================
 
+/// The votes of a poll are not carried in the DTO (only their counts), so the poll is
+/// restored without votes. An origin with an invalid room or client ID is dropped.
 impl From<dto::ChatMessage> for entity::ChatMessage {
     fn from(dto: dto::ChatMessage) -> Self {
         Self {
+            seq: SequenceNumber::new(dto.seq.unwrap_or_default()),
             from: ClientId::new(dto.client_id).expect("ClientId should be valid in DTO"),
             content: MessageContent::new(dto.content)
                 .expect("MessageContent should be valid in DTO"),
             timestamp: Timestamp::new(dto.timestamp),
+            tags: Vec::new(),
+            poll: dto.poll.map(|poll| {
+                entity::Poll::new(
+                    poll.options
+                        .into_iter()
+                        .map(|option| {
+                            PollOption::new(option.label)
+                                .expect("PollOption should be valid in DTO")
+                        })
+                        .collect(),
+                )
+            }),
+            forwarded_from: dto.forwarded_from.and_then(|origin| {
+                Some(entity::ForwardedFrom {
+                    room_id: RoomId::new(origin.room_id).ok()?,
+                    from: ClientId::new(origin.client_id).ok()?,
+                    timestamp: Timestamp::new(origin.timestamp),
+                })
+            }),
         }
     }
 }
@@ -26,31 +61,104 @@ impl From<dto::ParticipantInfo> for entity::Participant {
         Self {
             id: ClientId::new(dto.client_id).expect("ClientId should be valid in DTO"),
             connected_at: Timestamp::new(dto.connected_at),
+            display_name: dto
+                .display_name
+                .and_then(|name| DisplayName::new(name).ok()),
+            activity: dto
+                .activity
+                .and_then(|activity| Activity::new(activity).ok()),
         }
     }
 }
 
-// ========================================
-// Domain Entity → DTO
-// ========================================
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+impl RoomData {
+    /// Convert a stored room to the domain model
+    ///
+    /// The given capacities apply unless the room was stored with its own.
+    /// Fails with `DatabaseError::Corrupt` if a stored value is not valid in the domain model.
+    pub fn into_room(
+        self,
+        participant_capacity: usize,
+        message_capacity: usize,
+    ) -> Result<entity::Room, DatabaseError> {
+        let corrupt = |reason: String| DatabaseError::Corrupt {
+            room_id: self.id.clone(),
+            reason,
+        };
 
-impl From<entity::ChatMessage> for dto::ChatMessage {
-    fn from(model: entity::ChatMessage) -> Self {
-        Self {
-            r#type: dto::MessageType::Chat,
-            client_id: model.from.into_string(),
-            content: model.content.into_string(),
-            timestamp: model.timestamp.value(),
+        let mut room = entity::Room::with_capacity(
+            RoomId::new(self.id.clone()).map_err(|e| corrupt(e.to_string()))?,
+            Timestamp::new(self.created_at),
+            self.participant_capacity
+                .map_or(participant_capacity, |capacity| capacity as usize),
+            self.message_capacity
+                .map_or(message_capacity, |capacity| capacity as usize),
+        );
+        room.class = self
+            .class
+            .parse()
+            .map_err(|e: ValueObjectError| corrupt(e.to_string()))?;
+        for message in &self.messages {
+            room.messages.push(entity::ChatMessage {
+                seq: SequenceNumber::new(message.seq as u64),
+                from: ClientId::new(message.client_id.clone())
+                    .map_err(|e| corrupt(e.to_string()))?,
+                content: MessageContent::new(message.content.clone())
+                    .map_err(|e| corrupt(e.to_string()))?,
+                timestamp: Timestamp::new(message.timestamp),
+                tags: message
+                    .tags
+                    .iter()
+                    .map(|tag| MessageTag::new(tag.clone()))
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| corrupt(e.to_string()))?,
+                poll: message.poll().map_err(corrupt)?,
+                forwarded_from: message.forwarded_from().map_err(corrupt)?,
+            });
         }
+        room.last_seq = SequenceNumber::new(self.last_seq as u64);
+        Ok(room)
     }
 }
 
-impl From<entity::Participant> for dto::ParticipantInfo {
-    fn from(model: entity::Participant) -> Self {
-        Self {
-            client_id: model.id.into_string(),
-            connected_at: model.connected_at.value(),
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+impl MessageData {
+    /// Convert the stored poll of the message (`None` if the message is not a poll)
+    fn poll(&self) -> Result<Option<entity::Poll>, String> {
+        if self.poll_options.is_empty() {
+            return Ok(None);
         }
+        let options = self
+            .poll_options
+            .iter()
+            .map(|label| PollOption::new(label.clone()))
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?;
+        let mut poll = entity::Poll::new(options);
+        for (client_id, option) in &self.poll_votes {
+            let voter = ClientId::new(client_id.clone()).map_err(|e| e.to_string())?;
+            if *option < 0 || *option as usize >= poll.options.len() {
+                return Err(format!(
+                    "vote for unknown option {} of poll {}",
+                    option, self.seq
+                ));
+            }
+            poll.vote(voter, *option as usize);
+        }
+        Ok(Some(poll))
+    }
+
+    /// Convert the stored origin of the message (`None` if the message was not forwarded)
+    fn forwarded_from(&self) -> Result<Option<entity::ForwardedFrom>, String> {
+        let Some((room_id, client_id, timestamp)) = &self.forwarded_from else {
+            return Ok(None);
+        };
+        Ok(Some(entity::ForwardedFrom {
+            room_id: RoomId::new(room_id.clone()).map_err(|e| e.to_string())?,
+            from: ClientId::new(client_id.clone()).map_err(|e| e.to_string())?,
+            timestamp: Timestamp::new(*timestamp),
+        }))
     }
 }
 
@@ -67,6 +175,9 @@ mod tests {
             client_id: "alice".to_string(),
             content: "Hello!".to_string(),
             timestamp: 1000,
+            seq: Some(3),
+            poll: None,
+            forwarded_from: None,
         };
 
         // when (操作):
@@ -79,26 +190,7 @@ mod tests {
             MessageContent::new("Hello!".to_string()).unwrap()
         );
         assert_eq!(domain_msg.timestamp, Timestamp::new(1000));
-    }
-
-    #[test]
-    fn test_domain_chat_message_to_dto() {
-        // テスト項目: ドメインエンティティの ChatMessage が DTO に変換される
-        // given (前提条件):
-        let domain_msg = entity::ChatMessage {
-            from: ClientId::new("bob".to_string()).unwrap(),
-            content: MessageContent::new("Hi!".to_string()).unwrap(),
-            timestamp: Timestamp::new(2000),
-        };
-
-        // when (操作):
-        let dto_msg: dto::ChatMessage = domain_msg.into();
-
-        // then (期待する結果):
-        assert_eq!(dto_msg.client_id, "bob");
-        assert_eq!(dto_msg.content, "Hi!");
-        assert_eq!(dto_msg.timestamp, 2000);
-        assert!(matches!(dto_msg.r#type, dto::MessageType::Chat));
+        assert_eq!(domain_msg.seq, SequenceNumber::new(3));
     }
 
     #[test]
@@ -108,6 +200,9 @@ mod tests {
         let dto_participant = dto::ParticipantInfo {
             client_id: "alice".to_string(),
             connected_at: 1000,
+            guest: false,
+            display_name: Some("Alice".to_string()),
+            activity: Some("reviewing PR #42".to_string()),
         };
 
         // when (操作):
@@ -119,22 +214,10 @@ mod tests {
             ClientId::new("alice".to_string()).unwrap()
         );
         assert_eq!(domain_participant.connected_at, Timestamp::new(1000));
-    }
-
-    #[test]
-    fn test_domain_participant_to_dto() {
-        // テスト項目: ドメインエンティティの Participant が DTO に変換される
-        // given (前提条件):
-        let domain_participant = entity::Participant {
-            id: ClientId::new("bob".to_string()).unwrap(),
-            connected_at: Timestamp::new(2000),
-        };
-
-        // when (操作):
-        let dto_participant: dto::ParticipantInfo = domain_participant.into();
-
-        // then (期待する結果):
-        assert_eq!(dto_participant.client_id, "bob");
-        assert_eq!(dto_participant.connected_at, 2000);
+        assert_eq!(domain_participant.display_name.unwrap().as_str(), "Alice");
+        assert_eq!(
+            domain_participant.activity.unwrap().as_str(),
+            "reviewing PR #42"
+        );
     }
 }
@@ -0,0 +1,96 @@
+//! SQL database row DTOs.
+//!
+//! Rows of the `rooms`, `messages`, `message_tags`, `poll_options`, `poll_votes` and
+//! `forwarded_messages` tables are read into these DTOs and
+//! converted to the `Room` domain model in `conversion`, shared by the SQLite and PostgreSQL
+//! repositories.
+
+use sqlx::FromRow;
+
+/// Room stored in the database, with its message history
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct RoomData {
+    pub id: String,
+    /// Unix timestamp (milliseconds since epoch) in JST
+    pub created_at: i64,
+    /// Room class (`ephemeral` or `persistent`)
+    pub class: String,
+    /// Sequence number of the latest message, including deleted ones
+    pub last_seq: i64,
+    /// Participant capacity the room was created with (`None` follows the server's)
+    pub participant_capacity: Option<i64>,
+    /// Message capacity the room was created with (`None` follows the server's)
+    pub message_capacity: Option<i64>,
+    /// Stored messages, oldest first (loaded separately from the room row)
+    #[sqlx(skip)]
+    pub messages: Vec<MessageData>,
+}
+
+/// Chat message stored in the database
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct MessageData {
+    pub seq: i64,
+    pub client_id: String,
+    pub content: String,
+    /// Unix timestamp (milliseconds since epoch) in JST
+    pub timestamp: i64,
+    /// Tags added by the message analyzer (loaded separately from the message row)
+    #[sqlx(skip)]
+    pub tags: Vec<String>,
+    /// Options of the poll, in order (empty unless the message is a poll)
+    #[sqlx(skip)]
+    pub poll_options: Vec<String>,
+    /// Votes on the poll as client ID and option, in the order the clients first voted
+    #[sqlx(skip)]
+    pub poll_votes: Vec<(String, i64)>,
+    /// Room ID, client ID and timestamp of the original message if the message was forwarded
+    #[sqlx(skip)]
+    pub forwarded_from: Option<(String, String, i64)>,
+}
+
+impl RoomData {
+    /// Attach a stored tag to its message (ignored if the message was not loaded)
+    pub fn add_tag(&mut self, seq: i64, tag: String) {
+        if let Ok(index) = self
+            .messages
+            .binary_search_by_key(&seq, |message| message.seq)
+        {
+            self.messages[index].tags.push(tag);
+        }
+    }
+
+    /// Attach a stored poll option to its message (ignored if the message was not loaded)
+    pub fn add_poll_option(&mut self, seq: i64, label: String) {
+        if let Some(message) = self.message_mut(seq) {
+            message.poll_options.push(label);
+        }
+    }
+
+    /// Attach a stored vote to its poll (ignored if the message was not loaded)
+    pub fn add_poll_vote(&mut self, seq: i64, client_id: String, option: i64) {
+        if let Some(message) = self.message_mut(seq) {
+            message.poll_votes.push((client_id, option));
+        }
+    }
+
+    /// Attach the stored origin of a forwarded message (ignored if the message was not loaded)
+    pub fn set_forwarded_from(
+        &mut self,
+        seq: i64,
+        room_id: String,
+        client_id: String,
+        timestamp: i64,
+    ) {
+        if let Some(message) = self.message_mut(seq) {
+            message.forwarded_from = Some((room_id, client_id, timestamp));
+        }
+    }
+
+    fn message_mut(&mut self, seq: i64) -> Option<&mut MessageData> {
+        let index = self
+            .messages
+            .binary_search_by_key(&seq, |message| message.seq)
+            .ok()?;
+        Some(&mut self.messages[index])
+    }
+}
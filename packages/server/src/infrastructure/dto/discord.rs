@@ -0,0 +1,48 @@
+//! Discord REST API DTOs for the Discord relay.
+
+use serde::{Deserialize, Serialize};
+
+/// Discord user (message author)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscordUser {
+    pub id: String,
+    pub username: String,
+    /// Display name (falls back to `username` when unset)
+    #[serde(default)]
+    pub global_name: Option<String>,
+    #[serde(default)]
+    pub bot: bool,
+}
+
+/// Discord channel message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscordMessage {
+    /// Snowflake ID
+    pub id: String,
+    pub content: String,
+    pub author: DiscordUser,
+    /// Set when the message was posted by a webhook
+    #[serde(default)]
+    pub webhook_id: Option<String>,
+}
+
+/// Request body for creating a message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscordCreateMessage {
+    pub content: String,
+    pub allowed_mentions: DiscordAllowedMentions,
+}
+
+/// Mentions allowed in a created message
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiscordAllowedMentions {
+    /// Mention types to parse (empty: relayed text never pings anyone)
+    pub parse: Vec<String>,
+}
+
+/// Body of a `429 Too Many Requests` response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscordRateLimit {
+    /// Seconds to wait before retrying
+    pub retry_after: f64,
+}
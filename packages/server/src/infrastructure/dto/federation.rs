@@ -0,0 +1,52 @@
+//! Federation link DTOs.
+//!
+//! Every WebSocket text frame on a federation link is a [`FederationFrame`] whose `body` is a
+//! serialized [`FederationEnvelope`] signed with the shared secret.
+
+use serde::{Deserialize, Serialize};
+
+/// Signed frame exchanged between peer servers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FederationFrame {
+    /// Serialized `FederationEnvelope`
+    pub body: String,
+    /// Hex encoded HMAC-SHA256 of `body`
+    pub signature: String,
+}
+
+/// Event with its routing information
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FederationEnvelope {
+    /// Server where the event happened
+    pub origin: String,
+    /// Servers the event has passed through (including the origin), used for loop prevention
+    pub via: Vec<String>,
+    pub event: FederationEvent,
+}
+
+/// Event mirrored between peer servers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum FederationEvent {
+    /// First frame of a link, authenticating the sender
+    Hello {
+        /// Unix timestamp (milliseconds)
+        timestamp: i64,
+    },
+    /// Participants of the origin's room when the link is established
+    Snapshot {
+        participants: Vec<String>,
+    },
+    ParticipantJoined {
+        client_id: String,
+    },
+    ParticipantLeft {
+        client_id: String,
+    },
+    Chat {
+        client_id: String,
+        content: String,
+        /// Unix timestamp (milliseconds)
+        timestamp: i64,
+    },
+}
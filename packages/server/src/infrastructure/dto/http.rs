@@ -1,26 +1,485 @@
-//! HTTP API response DTOs for the chat application.
+//! HTTP API request and response DTOs for the chat application.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Room summary for list endpoint
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RoomSummaryDto {
     pub id: String,
+    /// Human-friendly name usable instead of the ID (omitted if not set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    /// Room class selecting its storage backend (`ephemeral` or `persistent`)
+    pub class: String,
     pub participants: Vec<String>,
     pub created_at: String, // ISO 8601
+    pub participant_count: usize,
+    /// Maximum number of participants; joining a full room is rejected with 503
+    #[serde(default)]
+    pub capacity: usize,
+    /// Maximum length of a message in bytes; longer messages are rejected with
+    /// `invalid_message`
+    #[serde(default)]
+    pub max_message_length: usize,
+    /// Whether an admin froze the room; messages are rejected with `room_frozen`
+    #[serde(default)]
+    pub frozen: bool,
+    pub message_count: usize,
+    /// Latest of the room creation, participant connections, and messages
+    pub last_activity_at: String, // ISO 8601
+}
+
+/// Page of the room list
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RoomsPageDto {
+    pub rooms: Vec<RoomSummaryDto>,
+    /// Number of rooms matching the filter, across all pages
+    pub total: usize,
+    pub limit: usize,
+    pub offset: usize,
 }
 
 /// Room detail for detail endpoint
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RoomDetailDto {
     pub id: String,
-    pub participants: Vec<ParticipantDetailDto>,
+    /// Language of the system messages of the room
+    pub locale: String,
+    /// Human-friendly name usable instead of the ID (omitted if not set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
     pub created_at: String, // ISO 8601
+    pub participant_count: usize,
+    /// Sequence number of the latest message in the room
+    pub last_seq: u64,
+    /// Present when `participants` is included (the default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub participants: Option<Vec<ParticipantDetailDto>>,
+    /// Latest messages, oldest first; present when `messages` is included
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<MessageDto>>,
+}
+
+/// Full room state for the debug endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RoomStateDto {
+    pub id: String,
+    pub created_at: String, // ISO 8601
+    pub participant_capacity: usize,
+    pub message_capacity: usize,
+    /// Sequence number of the latest message in the room
+    pub last_seq: u64,
+    pub participants: Vec<ParticipantDetailDto>,
+    pub messages: Vec<MessageDto>,
 }
 
 /// Participant detail for room detail endpoint
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ParticipantDetailDto {
     pub client_id: String,
     pub connected_at: String, // ISO 8601
+    /// Whether the participant connected as a guest
+    pub guest: bool,
+    /// Name the participant is shown as (omitted if not set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// What the participant is doing (omitted if not set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activity: Option<String>,
+}
+
+/// Messages of a room after a sequence number (backfill)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RoomMessagesDto {
+    pub room_id: String,
+    /// Sequence number of the latest message in the room
+    pub last_seq: u64,
+    pub messages: Vec<MessageDto>,
+}
+
+/// Messages of a room matching a search, newest first
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MessageSearchDto {
+    pub room_id: String,
+    pub query: String,
+    pub messages: Vec<MessageDto>,
+}
+
+/// Messages a client starred, in the order they were starred
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StarredMessagesDto {
+    pub client_id: String,
+    pub messages: Vec<StarredMessageDto>,
+}
+
+/// Message starred by a client
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StarredMessageDto {
+    pub room_id: String,
+    pub message: MessageDto,
+    /// When the message was starred (RFC 3339 in JST)
+    pub starred_at: String,
+}
+
+/// Chat message in the room history
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MessageDto {
+    pub seq: u64,
+    pub client_id: String,
+    pub content: String,
+    pub timestamp: String, // ISO 8601
+    /// Tags added by the message analyzer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Message analytics of a room
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RoomStatsDto {
+    pub room_id: String,
+    /// Number of messages in the room history
+    pub message_count: usize,
+    /// Number of messages with at least one tag
+    pub tagged_message_count: usize,
+    /// Message counts per tag, most frequent first
+    pub tags: Vec<TagCountDto>,
+}
+
+/// Number of messages with a tag
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TagCountDto {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// Result of erasing the data of a client
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ErasedClientDataDto {
+    pub client_id: String,
+    /// Rooms messages were deleted from, the default room first
+    pub rooms: Vec<ErasedRoomMessagesDto>,
+}
+
+/// Messages of a client deleted from one room
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ErasedRoomMessagesDto {
+    pub room_id: String,
+    /// Sequence numbers of the deleted messages
+    pub deleted_messages: Vec<u64>,
+}
+
+/// Result of kicking or banning a client
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct KickedClientDto {
+    pub client_id: String,
+    /// Whether the client's future connections are rejected
+    pub banned: bool,
+    /// Number of the client's connections that were closed
+    pub closed_connections: usize,
+}
+
+/// Freeze state of a room after an admin froze or unfroze it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RoomFreezeDto {
+    pub room_id: String,
+    /// Whether messages are rejected
+    pub frozen: bool,
+}
+
+/// Body of a request disconnecting everyone in a room
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DisconnectRoomRequestDto {
+    /// Why the participants are disconnected, shown to them before their connection closes
+    pub reason: String,
+}
+
+/// Result of disconnecting everyone in a room
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DisconnectedRoomDto {
+    pub room_id: String,
+    pub reason: String,
+    /// Number of connections that were closed
+    pub closed_connections: usize,
+}
+
+/// Settings of a room integration, tagged by `type`
+///
+/// ```txt
+/// {"type": "outgoing-webhook", "url": "https://example.com/hook"}
+/// {"type": "incoming-webhook", "token": "0123456789abcdef"}
+/// {"type": "bridge", "bridge": "mqtt", "target": "engawa/lobby"}
+/// {"type": "welcome-message", "text": "Welcome! Please read the pinned rules."}
+/// {"type": "join-approval"}
+/// {"type": "recurring-announcement", "schedule": "0 10 * * 1-5", "text": "Standup time!"}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum IntegrationSettingsDto {
+    /// Chat messages of the room are posted to `url` as Slack-compatible payloads
+    OutgoingWebhook { url: String },
+    /// `POST /api/v1/hooks/{token}` posts into the room
+    IncomingWebhook {
+        /// Generated by the server if omitted
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+    },
+    /// The room is bridged to `target` (a topic, channel or room of the bridged service)
+    Bridge {
+        /// `discord`, `mqtt`, `xmpp` or `federation`
+        bridge: String,
+        target: String,
+    },
+    /// `text` is sent as a `welcome` message to each participant joining the room
+    WelcomeMessage { text: String },
+    /// Clients joining the room are held as pending until approved through
+    /// `POST /api/v1/admin/rooms/{room_id}/pending/{client_id}/resolve`
+    JoinApproval,
+    /// `text` is posted to the room as a chat message from `announcement` whenever the cron
+    /// expression `schedule` (minute hour day month weekday, JST) fires
+    RecurringAnnouncement { schedule: String, text: String },
+}
+
+/// Integration configured for a room
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IntegrationDto {
+    pub id: u64,
+    pub room_id: String,
+    #[serde(flatten)]
+    pub settings: IntegrationSettingsDto,
+    pub created_at: String, // ISO 8601
+}
+
+/// Integrations configured for a room, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IntegrationListDto {
+    pub integrations: Vec<IntegrationDto>,
+}
+
+/// Clients waiting for approval to join a room, oldest request first
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PendingJoinsDto {
+    pub room_id: String,
+    pub pending: Vec<PendingJoinDto>,
+}
+
+/// Client waiting for approval to join a room
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PendingJoinDto {
+    pub client_id: String,
+    pub requested_at: String, // ISO 8601
+}
+
+/// Body of a decision on a pending client
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ResolveJoinRequestDto {
+    pub action: JoinDecisionDto,
+}
+
+/// Decision on a pending client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JoinDecisionDto {
+    /// Let the client join the room
+    Approve,
+    /// Close the client's connection
+    Reject,
+}
+
+/// Body of a breakout creation
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateBreakoutRequestDto {
+    /// Name shown in the parent room (1 to 64 characters)
+    pub name: String,
+    /// Participants of the parent room who opted in; anyone may join if empty
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+/// Breakout room spawned from a parent room
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BreakoutDto {
+    pub room_id: String,
+    pub parent_id: String,
+    pub name: String,
+    pub members: Vec<String>,
+    pub created_at: String, // ISO 8601
+    /// When the breakout was archived after inactivity (its room is removed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<String>, // ISO 8601
+}
+
+/// Breakouts of a room, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BreakoutListDto {
+    pub breakouts: Vec<BreakoutDto>,
+}
+
+/// Body of a message report
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReportMessageRequestDto {
+    /// Client ID of the reporting participant
+    pub reporter: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Accepted message report
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReportAcceptedDto {
+    /// ID of the report (the first report's ID if the reporter already reported the message)
+    pub report_id: u64,
+}
+
+/// Reported messages awaiting moderation, oldest message first
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModerationQueueDto {
+    pub items: Vec<ModerationItemDto>,
+}
+
+/// Reported message with its surrounding messages
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModerationItemDto {
+    pub room_id: String,
+    pub message: MessageDto,
+    /// Messages just before and after the reported one, oldest first
+    pub context: Vec<MessageDto>,
+    pub reports: Vec<MessageReportDto>,
+}
+
+/// Report of a message
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MessageReportDto {
+    pub id: u64,
+    pub reporter: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub reported_at: String, // ISO 8601
+}
+
+/// Body of a report resolution
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ResolveReportRequestDto {
+    pub action: ModerationActionDto,
+}
+
+/// How a report is resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationActionDto {
+    /// Keep the message
+    Dismiss,
+    /// Delete the message
+    Delete,
+    /// Delete the message and ban its sender
+    Ban,
+}
+
+/// Result of resolving the reports of a message
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReportResolutionDto {
+    pub action: ModerationActionDto,
+    /// Room of the reported message
+    pub room_id: String,
+    /// Sequence number of the reported message
+    pub seq: u64,
+    /// Sender of the reported message
+    pub client_id: String,
+    /// IDs of the resolved reports
+    pub resolved_reports: Vec<u64>,
+}
+
+/// Credentials sent to log in
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LoginRequestDto {
+    pub client_id: String,
+    pub password: String,
+}
+
+/// Access token issued on login
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AccessTokenDto {
+    pub access_token: String,
+    /// Always `Bearer`
+    pub token_type: String,
+    /// Seconds until the token expires
+    pub expires_in_secs: u64,
+}
+
+/// Proof-of-work challenge to solve before connecting
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConnectChallengeDto {
+    pub challenge: String,
+    /// Leading zero bits required of `SHA-256("<challenge>:<nonce>")`
+    pub difficulty: u8,
+    /// Seconds until the challenge expires
+    pub expires_in_secs: u64,
+}
+
+/// Whether connecting clients must solve a proof-of-work
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChallengeModeDto {
+    pub enabled: bool,
+    pub difficulty: u8,
+}
+
+/// Body of a connect challenge toggle
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChallengeModeRequestDto {
+    pub enabled: bool,
+}
+
+/// Networks allowed and denied by the server (CIDR notation)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IpRulesDto {
+    /// Networks allowed to send requests (any network if empty)
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Networks refused even if allowed
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+/// Cluster topology for the admin endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClusterDto {
+    /// ID of the node serving the request
+    pub node_id: String,
+    /// Sum of the participant counts of the alive nodes
+    pub total_participants: usize,
+    pub nodes: Vec<ClusterNodeDto>,
+}
+
+/// Node of the cluster
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClusterNodeDto {
+    pub node_id: String,
+    pub gossip_addr: String,
+    pub http_addr: String,
+    pub participant_count: usize,
+    /// `alive`, `suspect` or `dead`
+    pub status: String,
+    /// Milliseconds since the node's heartbeat last advanced (0 for the serving node)
+    pub last_seen_ms_ago: u64,
+}
+
+/// Health of the server and its dependencies
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HealthDto {
+    /// `ok` if every dependency is up, `unavailable` otherwise
+    pub status: String,
+    pub dependencies: Vec<DependencyHealthDto>,
+}
+
+/// Health of a dependency (repository, message pusher)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DependencyHealthDto {
+    pub name: String,
+    /// `up` or `down`
+    pub status: String,
+    /// Time until the dependency answered (or the check timed out)
+    pub latency_ms: f64,
+    /// Why the dependency is down
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
@@ -1,9 +1,31 @@
 //! Data Transfer Objects (DTOs) for the chat application.
 //!
 //! DTOs are organized by protocol:
+//! - `cluster`: Cluster gossip DTOs
+//! - `database`: SQL database row DTOs (`sqlite` / `postgres` feature)
 //! - `websocket`: WebSocket message DTOs
 //! - `http`: HTTP API response DTOs
+//! - `discord`: Discord REST API DTOs (`discord` feature)
+//! - `federation`: Federation link DTOs (`federation` feature)
+//! - `mqtt`: MQTT bridge command DTOs (`mqtt` feature)
+//! - `schema`: JSON Schemas of the WebSocket and HTTP DTOs
+//! - `wal`: Write-ahead log record DTOs
+//! - `webhook`: Incoming webhook payload DTOs
+//! - `wire_log`: Wire log record DTOs
 
+pub mod cluster;
 pub mod conversion;
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub mod database;
+#[cfg(feature = "discord")]
+pub mod discord;
+#[cfg(feature = "federation")]
+pub mod federation;
 pub mod http;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod schema;
+pub mod wal;
+pub mod webhook;
 pub mod websocket;
+pub mod wire_log;
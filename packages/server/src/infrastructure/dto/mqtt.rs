@@ -0,0 +1,10 @@
+//! MQTT message DTOs for the chat application.
+
+use serde::{Deserialize, Serialize};
+
+/// Command published by a device to inject a chat message into a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttCommandMessage {
+    pub client_id: String,
+    pub content: String,
+}
@@ -0,0 +1,132 @@
+//! JSON Schemas of the WebSocket protocol and REST API messages.
+//!
+//! Generated from the DTOs with `schemars`, so the published contract always matches what
+//! the server sends and accepts.
+
+use schemars::{JsonSchema, Schema, schema_for};
+use serde_json::{Map, Value};
+
+use super::{http, webhook, websocket};
+
+/// Schema of one message type, keyed by the DTO name
+fn entry<T: JsonSchema>() -> (String, Value) {
+    let schema: Schema = schema_for!(T);
+    (T::schema_name().into_owned(), schema.to_value())
+}
+
+fn collect(entries: impl IntoIterator<Item = (String, Value)>) -> Value {
+    Value::Object(entries.into_iter().collect::<Map<_, _>>())
+}
+
+/// Build the schema document served at `/api/schema`
+///
+/// ```txt
+/// {
+///   "websocket": {
+///     "client": {"ClientMessage": {...}},
+///     "server": {"RoomConnectedMessage": {...}, "ChatMessage": {...}, ...}
+///   },
+///   "http": {
+///     "requests": {"SlackWebhookPayload": {...}},
+///     "responses": {"RoomsPageDto": {...}, ...}
+///   }
+/// }
+/// ```
+pub fn protocol_schemas() -> Value {
+    let websocket_client = collect([entry::<websocket::ClientMessage>()]);
+    let websocket_server = collect([
+        entry::<websocket::HelloAckMessage>(),
+        entry::<websocket::RoomConnectedMessage>(),
+        entry::<websocket::RoomHistoryMessage>(),
+        entry::<websocket::ParticipantJoinedMessage>(),
+        entry::<websocket::ParticipantLeftMessage>(),
+        entry::<websocket::ChatMessage>(),
+        entry::<websocket::ServerShutdownMessage>(),
+        entry::<websocket::RoomListMessage>(),
+        entry::<websocket::MessageDeletedMessage>(),
+        entry::<websocket::TypingMessage>(),
+        entry::<websocket::SlowDownMessage>(),
+        entry::<websocket::HeartbeatMessage>(),
+        entry::<websocket::WelcomeMessage>(),
+        entry::<websocket::JoinPendingMessage>(),
+        entry::<websocket::JoinRequestedMessage>(),
+        entry::<websocket::PollUpdatedMessage>(),
+        entry::<websocket::StarUpdatedMessage>(),
+        entry::<websocket::ActivityUpdatedMessage>(),
+        entry::<websocket::ParticipantRenamedMessage>(),
+        entry::<websocket::RoomFreezeMessage>(),
+        entry::<websocket::DisconnectedMessage>(),
+        entry::<websocket::ErrorMessage>(),
+    ]);
+    let http_requests = collect([
+        entry::<webhook::SlackWebhookPayload>(),
+        entry::<webhook::SlackWebhookForm>(),
+        entry::<http::ReportMessageRequestDto>(),
+        entry::<http::ResolveReportRequestDto>(),
+        entry::<http::ChallengeModeRequestDto>(),
+        entry::<http::LoginRequestDto>(),
+        entry::<http::IpRulesDto>(),
+        entry::<http::IntegrationSettingsDto>(),
+        entry::<http::ResolveJoinRequestDto>(),
+        entry::<http::CreateBreakoutRequestDto>(),
+        entry::<http::DisconnectRoomRequestDto>(),
+    ]);
+    let http_responses = collect([
+        entry::<http::HealthDto>(),
+        entry::<http::RoomsPageDto>(),
+        entry::<http::RoomDetailDto>(),
+        entry::<http::RoomMessagesDto>(),
+        entry::<http::MessageSearchDto>(),
+        entry::<http::RoomStatsDto>(),
+        entry::<http::StarredMessagesDto>(),
+        entry::<http::RoomStateDto>(),
+        entry::<http::ClusterDto>(),
+        entry::<http::ErasedClientDataDto>(),
+        entry::<http::KickedClientDto>(),
+        entry::<http::RoomFreezeDto>(),
+        entry::<http::DisconnectedRoomDto>(),
+        entry::<http::IntegrationDto>(),
+        entry::<http::IntegrationListDto>(),
+        entry::<http::PendingJoinsDto>(),
+        entry::<http::BreakoutDto>(),
+        entry::<http::BreakoutListDto>(),
+        entry::<http::ReportAcceptedDto>(),
+        entry::<http::ModerationQueueDto>(),
+        entry::<http::ReportResolutionDto>(),
+        entry::<http::ConnectChallengeDto>(),
+        entry::<http::AccessTokenDto>(),
+        entry::<http::ChallengeModeDto>(),
+        entry::<http::IpRulesDto>(),
+    ]);
+
+    serde_json::json!({
+        "websocket": {
+            "client": websocket_client,
+            "server": websocket_server,
+        },
+        "http": {
+            "requests": http_requests,
+            "responses": http_responses,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_schemas_describe_client_message_types() {
+        // テスト項目: クライアントが送信できる全ての type がスキーマに含まれる
+        // when (操作):
+        let schemas = protocol_schemas();
+
+        // then (期待する結果):
+        let client =
+            serde_json::to_string(&schemas["websocket"]["client"]["ClientMessage"]).unwrap();
+        for r#type in websocket::ClientMessage::TYPES {
+            assert!(client.contains(&format!("\"{}\"", r#type)), "{}", r#type);
+        }
+        assert!(schemas["http"]["responses"]["RoomsPageDto"].is_object());
+    }
+}
@@ -0,0 +1,100 @@
+//! Write-ahead log record DTOs.
+//!
+//! Each record is a domain event serialized as one line of JSON.
+//! Records of a room created after the first one carry its ID in `room`.
+
+use serde::{Deserialize, Serialize};
+
+/// Record appended to the write-ahead log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum WalRecord {
+    /// The room was created (always the first record)
+    RoomCreated {
+        room_id: String,
+        /// Unix timestamp (milliseconds since epoch) in JST
+        created_at: i64,
+    },
+    /// Another room was created through the API
+    RoomAdded {
+        room_id: String,
+        /// Unix timestamp (milliseconds since epoch) in JST
+        created_at: i64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        slug: Option<String>,
+        locale: String,
+        class: String,
+        participant_capacity: usize,
+        message_capacity: usize,
+        /// Maximum length of a message in bytes
+        max_length: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filters: Option<Vec<String>>,
+    },
+    /// A chat message was added to the room
+    MessageAdded {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>,
+        seq: u64,
+        client_id: String,
+        content: String,
+        /// Unix timestamp (milliseconds since epoch) in JST
+        timestamp: i64,
+    },
+    /// The message analyzer tagged a chat message
+    MessageTagged {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>,
+        seq: u64,
+        tags: Vec<String>,
+    },
+    /// A chat message was turned into a poll on the options
+    PollAttached {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>,
+        seq: u64,
+        options: Vec<String>,
+    },
+    /// A chat message was copied from another room (`room_id`, `client_id` and `timestamp`
+    /// are the original message's)
+    MessageForwarded {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>,
+        seq: u64,
+        room_id: String,
+        client_id: String,
+        /// Unix timestamp (milliseconds since epoch) in JST
+        timestamp: i64,
+    },
+    /// A client voted on a poll (replacing its previous vote)
+    PollVoted {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>,
+        seq: u64,
+        client_id: String,
+        option: usize,
+    },
+    /// A chat message that was deleted or whose sender's data was erased
+    /// (replaces its `message-added` record)
+    MessageErased {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room: Option<String>,
+        seq: u64,
+    },
+}
+
+impl WalRecord {
+    /// ID of the room the record belongs to (`None` for the first room)
+    pub fn room(&self) -> Option<&str> {
+        match self {
+            WalRecord::RoomCreated { .. } => None,
+            WalRecord::RoomAdded { room_id, .. } => Some(room_id),
+            WalRecord::MessageAdded { room, .. }
+            | WalRecord::MessageTagged { room, .. }
+            | WalRecord::PollAttached { room, .. }
+            | WalRecord::MessageForwarded { room, .. }
+            | WalRecord::PollVoted { room, .. }
+            | WalRecord::MessageErased { room, .. } => room.as_deref(),
+        }
+    }
+}
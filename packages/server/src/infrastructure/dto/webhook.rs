@@ -0,0 +1,267 @@
+//! Webhook payload DTOs for the chat application.
+//!
+//! Payloads follow Slack's incoming webhook format so that tooling that posts to Slack
+//! (CI notifications, monitoring alerts, ...) can be pointed at this server unchanged, and
+//! outgoing webhooks can be pointed at Slack-compatible endpoints.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Slack-compatible incoming webhook payload
+///
+/// ```txt
+/// {"text": "Build failed", "blocks": [{"type": "section", "text": {"type": "mrkdwn", "text": "*Build* failed"}}]}
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SlackWebhookPayload {
+    /// Plain text (used as a fallback when `blocks` is present, as in Slack)
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Block Kit blocks
+    #[serde(default)]
+    pub blocks: Option<Vec<Value>>,
+    /// Sender name override
+    #[serde(default)]
+    pub username: Option<String>,
+}
+
+/// Form-encoded variant (`payload=<json>`) posted by legacy Slack tooling
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SlackWebhookForm {
+    pub payload: String,
+}
+
+/// Payload posted to the outgoing webhooks of a room for each chat message
+///
+/// `text` and `username` are understood by Slack-compatible endpoints; the other fields are
+/// ignored by them.
+///
+/// ```txt
+/// {"text": "hello", "username": "alice", "room_id": "...", "seq": 42}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutgoingWebhookPayload {
+    pub text: String,
+    /// Client ID of the sender
+    pub username: String,
+    pub room_id: String,
+    /// Sequence number of the message in the room
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+}
+
+impl SlackWebhookPayload {
+    /// Get the message text, flattening blocks to text when present
+    ///
+    /// Returns `None` if the payload contains no text.
+    pub fn message_text(&self) -> Option<String> {
+        let from_blocks = self
+            .blocks
+            .as_deref()
+            .map(flatten_blocks)
+            .filter(|text| !text.trim().is_empty());
+        from_blocks.or_else(|| self.text.clone().filter(|text| !text.trim().is_empty()))
+    }
+}
+
+/// Flatten Block Kit blocks to text, one line per block
+pub fn flatten_blocks(blocks: &[Value]) -> String {
+    blocks
+        .iter()
+        .filter_map(flatten_block)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn flatten_block(block: &Value) -> Option<String> {
+    match block.get("type")?.as_str()? {
+        "section" => {
+            let mut lines: Vec<String> = block
+                .get("text")
+                .and_then(text_object)
+                .into_iter()
+                .collect();
+            if let Some(fields) = block.get("fields").and_then(Value::as_array) {
+                lines.extend(fields.iter().filter_map(text_object));
+            }
+            Some(lines.join("\n"))
+        }
+        "header" => block.get("text").and_then(text_object),
+        "context" => {
+            let elements = block.get("elements")?.as_array()?;
+            Some(
+                elements
+                    .iter()
+                    .filter_map(text_object)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+        }
+        "divider" => Some("---".to_string()),
+        "image" => block
+            .get("title")
+            .and_then(text_object)
+            .or_else(|| block.get("alt_text")?.as_str().map(str::to_string)),
+        "rich_text" => Some(rich_text(block.get("elements")?)),
+        // Interactive blocks (actions, input, ...) have no meaningful text
+        _ => None,
+    }
+}
+
+/// Text of a composition text object (`{"type": "mrkdwn", "text": "..."}`)
+fn text_object(value: &Value) -> Option<String> {
+    value.get("text")?.as_str().map(str::to_string)
+}
+
+/// Flatten the elements of a rich text block
+fn rich_text(elements: &Value) -> String {
+    let Some(elements) = elements.as_array() else {
+        return String::new();
+    };
+    let mut sections = Vec::new();
+    for element in elements {
+        match element.get("type").and_then(Value::as_str) {
+            Some("rich_text_list") => {
+                let items = element
+                    .get("elements")
+                    .and_then(Value::as_array)
+                    .map(|items| {
+                        items
+                            .iter()
+                            .map(|item| format!("- {}", rich_text_inline(item)))
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                sections.extend(items);
+            }
+            Some("rich_text_quote") => sections.push(format!("> {}", rich_text_inline(element))),
+            _ => sections.push(rich_text_inline(element)),
+        }
+    }
+    sections.join("\n")
+}
+
+/// Flatten the inline elements of a rich text section
+fn rich_text_inline(section: &Value) -> String {
+    let Some(elements) = section.get("elements").and_then(Value::as_array) else {
+        return String::new();
+    };
+    elements
+        .iter()
+        .map(
+            |element| match element.get("type").and_then(Value::as_str) {
+                Some("link") => element
+                    .get("text")
+                    .or_else(|| element.get("url"))
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                Some("emoji") => format!(
+                    ":{}:",
+                    element
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                ),
+                Some("user") => format!(
+                    "<@{}>",
+                    element
+                        .get("user_id")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                ),
+                _ => element
+                    .get("text")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_message_text_prefers_blocks() {
+        // テスト項目: blocks がある場合は blocks を平文化したものが使われ、text はフォールバックになる
+        // given (前提条件):
+        let payload: SlackWebhookPayload = serde_json::from_value(json!({
+            "text": "fallback",
+            "blocks": [
+                {"type": "header", "text": {"type": "plain_text", "text": "Deploy"}},
+                {"type": "section", "text": {"type": "mrkdwn", "text": "*prod* succeeded"},
+                 "fields": [{"type": "mrkdwn", "text": "v1.2.3"}]},
+                {"type": "divider"},
+                {"type": "context", "elements": [{"type": "mrkdwn", "text": "by"}, {"type": "plain_text", "text": "ci"}]},
+                {"type": "actions", "elements": []}
+            ]
+        }))
+        .unwrap();
+
+        // when (操作):
+        let text = payload.message_text();
+
+        // then (期待する結果):
+        assert_eq!(
+            text.as_deref(),
+            Some("Deploy\n*prod* succeeded\nv1.2.3\n---\nby ci")
+        );
+    }
+
+    #[test]
+    fn test_message_text_falls_back_to_text() {
+        // テスト項目: blocks が無い、または平文化できない場合は text が使われる
+        // given (前提条件):
+        let payload: SlackWebhookPayload = serde_json::from_value(json!({
+            "text": "Build failed",
+            "blocks": [{"type": "actions", "elements": []}]
+        }))
+        .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(payload.message_text().as_deref(), Some("Build failed"));
+    }
+
+    #[test]
+    fn test_message_text_empty_payload() {
+        // テスト項目: テキストを含まない payload は None になる
+        // then (期待する結果):
+        assert_eq!(SlackWebhookPayload::default().message_text(), None);
+    }
+
+    #[test]
+    fn test_flatten_rich_text() {
+        // テスト項目: rich_text ブロックのリスト・引用・リンク・絵文字が平文化される
+        // given (前提条件):
+        let blocks = vec![json!({
+            "type": "rich_text",
+            "elements": [
+                {"type": "rich_text_section", "elements": [
+                    {"type": "text", "text": "See "},
+                    {"type": "link", "url": "https://example.com"},
+                    {"type": "emoji", "name": "tada"}
+                ]},
+                {"type": "rich_text_list", "elements": [
+                    {"type": "rich_text_section", "elements": [{"type": "text", "text": "one"}]},
+                    {"type": "rich_text_section", "elements": [{"type": "text", "text": "two"}]}
+                ]},
+                {"type": "rich_text_quote", "elements": [{"type": "text", "text": "quoted"}]}
+            ]
+        })];
+
+        // when (操作):
+        let text = flatten_blocks(&blocks);
+
+        // then (期待する結果):
+        assert_eq!(
+            text,
+            "See https://example.com:tada:\n- one\n- two\n> quoted"
+        );
+    }
+}
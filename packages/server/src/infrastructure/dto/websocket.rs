@@ -1,53 +1,677 @@
 //! WebSocket message DTOs for the chat application.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::infrastructure::error::InboundMessageError;
 
 /// Message type enum
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum MessageType {
     RoomConnected,
+    RoomHistory,
     ParticipantJoined,
     ParticipantLeft,
     Chat,
+    ServerShutdown,
+    BackfillRequest,
+    ListRooms,
+    RoomList,
+    MessageDeleted,
+    TypingStarted,
+    TypingStopped,
+    SlowDown,
+    Heartbeat,
+    Welcome,
+    JoinPending,
+    JoinRequested,
+    Poll,
+    Vote,
+    PollUpdated,
+    Forward,
+    Star,
+    Unstar,
+    StarUpdated,
+    Hello,
+    HelloAck,
+    SetActivity,
+    ActivityUpdated,
+    SetDisplayName,
+    ParticipantRenamed,
+    RoomFrozen,
+    RoomUnfrozen,
+    Disconnected,
+    Error,
 }
 
 /// Participant information including client_id and connection timestamp
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ParticipantInfo {
     pub client_id: String,
     /// Unix timestamp (milliseconds since epoch) in JST
     pub connected_at: i64,
+    /// Whether the participant connected as a guest (with a server-assigned ID)
+    #[serde(default)]
+    pub guest: bool,
+    /// Name the participant is shown as (omitted if not set; show `client_id` instead)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// What the participant is doing (omitted if not set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activity: Option<String>,
 }
 
 /// Room connected participants message sent when a client connects (initial)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RoomConnectedMessage {
     pub r#type: MessageType,
+    /// ID of the connecting client (assigned by the server when connecting as a guest)
+    #[serde(default)]
+    pub client_id: String,
+    /// ID of the room the client joined, for the REST API of the room (e.g. its search)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_id: Option<String>,
     pub participants: Vec<ParticipantInfo>,
+    /// Language of the system messages of the room (e.g. `ja`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Opaque token to send back with `last_seq` when reconnecting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resume_token: Option<String>,
+    /// Sequence number of the latest message in the room
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seq: Option<u64>,
+    /// Server's clock (Unix timestamp in milliseconds) when the message was sent, to estimate
+    /// the skew of the client's clock
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_time: Option<i64>,
+}
+
+/// Latest messages of the room sent to a newly connected client after `room-connected`
+///
+/// Not sent to a client resuming with `resume_token` and `last_seq` (it backfills instead).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RoomHistoryMessage {
+    pub r#type: MessageType,
+    /// Messages oldest first
+    pub messages: Vec<ChatMessage>,
 }
 
 /// Participant joined notification
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ParticipantJoinedMessage {
     pub r#type: MessageType,
     pub client_id: String,
     pub connected_at: i64,
+    /// Whether the participant connected as a guest
+    #[serde(default)]
+    pub guest: bool,
+    /// Name the participant is shown as (omitted if not set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// Localized text of the notice in the room's locale
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notice: Option<String>,
 }
 
 /// Participant left notification
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ParticipantLeftMessage {
     pub r#type: MessageType,
     pub client_id: String,
     pub disconnected_at: i64,
+    /// Localized text of the notice in the room's locale
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notice: Option<String>,
+}
+
+/// Notice sent before the server closes the connection for a restart
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ServerShutdownMessage {
+    pub r#type: MessageType,
+    /// Why the server is shutting down (e.g. `restart`)
+    pub reason: String,
+    /// Milliseconds to wait before reconnecting (spread across clients)
+    pub reconnect_after_ms: u64,
+    /// Localized text of the notice in the room's locale
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notice: Option<String>,
+}
+
+/// Request from a client for the messages after `since_seq`
+///
+/// The server replies with the missed `chat` messages; ones already delivered on the
+/// connection are skipped.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BackfillRequestMessage {
+    pub r#type: MessageType,
+    pub since_seq: u64,
+}
+
+/// Request from a client for the rooms it can join
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListRoomsMessage {
+    pub r#type: MessageType,
+}
+
+/// Room listed in a `room-list` message
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RoomInfo {
+    pub room_id: String,
+    /// Display name of the room (its slug if set, otherwise the room ID)
+    pub name: String,
+    /// Topic of the room, if one is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    pub participant_count: usize,
+}
+
+/// Rooms available to join, sent in reply to `list-rooms`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RoomListMessage {
+    pub r#type: MessageType,
+    pub rooms: Vec<RoomInfo>,
+}
+
+/// Notice that a message was deleted from the room history (e.g. on a data erasure request)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MessageDeletedMessage {
+    pub r#type: MessageType,
+    /// Sequence number of the deleted message
+    pub seq: u64,
+}
+
+/// Notice that an admin froze the room (`room-frozen`) or unfroze it (`room-unfrozen`)
+///
+/// While the room is frozen, messages sent to it are rejected with `room_frozen`; the history
+/// can still be read.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RoomFreezeMessage {
+    pub r#type: MessageType,
+    pub room_id: String,
+}
+
+/// Notice sent to every participant of a room before an admin closes their connections
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DisconnectedMessage {
+    pub r#type: MessageType,
+    /// Why the admin disconnected the participants
+    pub reason: String,
+}
+
+/// Notice that a participant started or stopped typing (`typing-started` / `typing-stopped`)
+///
+/// Clients send the type alone; the server adds `client_id` and relays it to the other
+/// participants. Repeated `typing-started` from the same client are relayed at most once every
+/// few seconds, and a participant's indicator ends when it sends a chat message or leaves.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TypingMessage {
+    pub r#type: MessageType,
+    pub client_id: String,
+}
+
+/// Advice to a client whose send queue on the server is backing up
+///
+/// Sent ahead of the queued messages when the queue crosses the server's threshold, and again
+/// with `send_interval_ms: 0` once it has drained. Clients should space out the messages they
+/// send by `send_interval_ms` until then.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SlowDownMessage {
+    pub r#type: MessageType,
+    /// Messages waiting in the client's send queue
+    pub queue_depth: usize,
+    /// Minimum milliseconds between messages sent by the client (`0` lifts the advice)
+    pub send_interval_ms: u64,
+}
+
+/// Welcome message of the room, sent only to a participant that has just joined
+///
+/// Sent after `room-connected` and the room history, once for each welcome message configured
+/// for the room. Additional connections of a participant already in the room do not get it.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WelcomeMessage {
+    pub r#type: MessageType,
+    pub room_id: String,
+    pub content: String,
+}
+
+/// Sent instead of `room-connected` to a client joining a room that requires approval
+///
+/// The client waits on the open connection without receiving the room's messages:
+/// `room-connected` follows once a moderator approves it, and the connection is closed with
+/// code 4014 (`join-rejected`) if a moderator rejects it.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JoinPendingMessage {
+    pub r#type: MessageType,
+    pub room_id: String,
+    /// Notice to show while waiting
+    pub message: String,
+}
+
+/// Sent to the participants of a room requiring approval when a client asks to join it
+///
+/// Moderators approve or reject the client with
+/// `POST /api/v1/admin/rooms/{room_id}/pending/{client_id}/resolve`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JoinRequestedMessage {
+    pub r#type: MessageType,
+    pub room_id: String,
+    pub client_id: String,
+    /// Unix timestamp (milliseconds since epoch) in JST when the client asked to join
+    pub requested_at: i64,
+}
+
+/// Request from a client to post a poll to the room
+///
+/// The server broadcasts it to every participant, the sender included, as a `poll` message:
+/// a [`ChatMessage`] whose `content` is the question and whose `poll` lists the options.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreatePollMessage {
+    pub r#type: MessageType,
+    pub client_id: String,
+    /// Question of the poll
+    pub content: String,
+    /// Options to vote for (2 to 10)
+    pub options: Vec<String>,
+    pub timestamp: i64,
+}
+
+/// Vote of a client on a poll
+///
+/// Voting again moves the client's vote to the new option. The server broadcasts the new
+/// tally to every participant as [`PollUpdatedMessage`].
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VoteMessage {
+    pub r#type: MessageType,
+    /// Sequence number of the poll
+    pub seq: u64,
+    /// Index of the option voted for, starting from 0
+    pub option: usize,
+}
+
+/// Request from a client to copy a message of its room into another room it is in
+///
+/// The server posts the copy to the other room as a [`ChatMessage`] from the client, with
+/// the original room, author and timestamp in `forwarded_from`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ForwardMessage {
+    pub r#type: MessageType,
+    /// Sequence number of the message in the client's room
+    pub seq: u64,
+    /// ID of the room to post the copy to
+    pub room_id: String,
+}
+
+/// Request from a client to star (`star`) or unstar (`unstar`) a message of its room
+///
+/// The server answers the client only with a [`StarUpdatedMessage`]. Starred messages are
+/// listed at `GET /api/v1/users/{client_id}/starred`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StarMessage {
+    pub r#type: MessageType,
+    /// Sequence number of the message in the client's room
+    pub seq: u64,
+}
+
+/// Star of a message added or removed, sent only to the client that asked for it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StarUpdatedMessage {
+    pub r#type: MessageType,
+    pub room_id: String,
+    /// Sequence number of the message
+    pub seq: u64,
+    /// Whether the message is starred now
+    pub starred: bool,
+}
+
+/// Request from a client to change the name it is shown as
+///
+/// A missing, `null` or blank `display_name` clears it. The server broadcasts a
+/// [`ParticipantRenamedMessage`] to every participant of the room, including the client.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetDisplayNameMessage {
+    pub r#type: MessageType,
+    /// At most 32 characters, without control characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+}
+
+/// Display name of a participant changed, sent to every participant of the room
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ParticipantRenamedMessage {
+    pub r#type: MessageType,
+    pub client_id: String,
+    /// New display name (omitted when cleared)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+}
+
+/// Request from a client to publish what it is doing (e.g. `reviewing PR #42`)
+///
+/// A missing, `null` or blank `activity` clears it. The server broadcasts an
+/// [`ActivityUpdatedMessage`] to every participant of the room, including the client.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetActivityMessage {
+    pub r#type: MessageType,
+    /// At most 64 characters, without line breaks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activity: Option<String>,
+}
+
+/// Activity of a participant set or cleared, sent to every participant of the room
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ActivityUpdatedMessage {
+    pub r#type: MessageType,
+    pub client_id: String,
+    /// New activity (omitted when cleared)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activity: Option<String>,
+}
+
+/// First message of a client after connecting, announcing what it understands
+///
+/// The server waits briefly for it before sending `room-connected` and answers with a
+/// [`HelloAckMessage`]. Clients that do not send it are served protocol version 1 with every
+/// feature.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HelloMessage {
+    pub r#type: MessageType,
+    /// Latest protocol version the client speaks
+    pub protocol_version: u32,
+    /// Optional features the client understands (e.g. `history`, `typing`, `polls`)
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+/// Protocol version and features used on the connection, sent in reply to `hello`
+///
+/// The server does not send the frames of features left out (polls are sent as plain `chat`
+/// messages instead).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HelloAckMessage {
+    pub r#type: MessageType,
+    /// Protocol version used on the connection (the lower of the client's and the server's)
+    pub protocol_version: u32,
+    /// Features both the client and the server support
+    pub features: Vec<String>,
+}
+
+/// Where a forwarded message was first posted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ForwardedFromInfo {
+    pub room_id: String,
+    /// Author of the original message
+    pub client_id: String,
+    /// Unix timestamp (milliseconds since epoch) in JST of the original message
+    pub timestamp: i64,
+}
+
+/// Option of a poll with the number of votes for it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PollOptionInfo {
+    pub label: String,
+    pub votes: usize,
+}
+
+/// Options of the poll carried by a `poll` message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PollInfo {
+    /// Options in the order they are shown (indexes of `vote`)
+    pub options: Vec<PollOptionInfo>,
+}
+
+/// New tally of a poll, sent to every participant after a vote
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PollUpdatedMessage {
+    pub r#type: MessageType,
+    /// Sequence number of the poll
+    pub seq: u64,
+    pub options: Vec<PollOptionInfo>,
+}
+
+/// Server's clock sent along with each keepalive Ping
+///
+/// Lets clients keep their estimate of the skew between their clock and the server's up to
+/// date (see `server_time` of `room-connected`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HeartbeatMessage {
+    pub r#type: MessageType,
+    /// Unix timestamp (milliseconds since epoch) of the server when the heartbeat was sent
+    pub server_time: i64,
+}
+
+/// Error sent to a client whose message was rejected
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ErrorMessage {
+    pub r#type: MessageType,
+    /// `invalid_json`, `missing_type`, `unknown_message_type`, `invalid_message`, `read_only`,
+    /// `rate_limited`, `room_history_full`, `room_frozen`, `invalid_vote`, `invalid_forward`,
+    /// `message_rejected`, `invalid_star`, `invalid_activity`, `invalid_display_name`,
+    /// `too_many_rooms` (body of a `429` connection rejection) or `room_full` (body of a `503`
+    /// connection rejection)
+    pub code: String,
+    /// Human-readable description of the problem
+    pub message: String,
+}
+
+/// Message a client can send to the server
+///
+/// Inbound messages are validated strictly: unknown types and unknown fields are rejected
+/// with an [`ErrorMessage`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum ClientMessage {
+    /// Chat message to broadcast to the room
+    ///
+    /// `client_id` is kept for compatibility; the server attributes the message to the client
+    /// of the connection.
+    Chat {
+        client_id: String,
+        content: String,
+        timestamp: i64,
+    },
+    /// Request for the messages after `since_seq` (see [`BackfillRequestMessage`])
+    BackfillRequest { since_seq: u64 },
+    /// Request for the rooms the client can join (see [`ListRoomsMessage`])
+    ListRooms,
+    /// The client started typing (see [`TypingMessage`])
+    TypingStarted,
+    /// The client stopped typing without sending (see [`TypingMessage`])
+    TypingStopped,
+    /// Poll to post to the room (see [`CreatePollMessage`]); attributed like `Chat`
+    Poll {
+        client_id: String,
+        content: String,
+        options: Vec<String>,
+        timestamp: i64,
+    },
+    /// Vote on a poll (see [`VoteMessage`])
+    Vote { seq: u64, option: usize },
+    /// Copy of a message to post to another room (see [`ForwardMessage`])
+    Forward { seq: u64, room_id: String },
+    /// Star of a message of the room (see [`StarMessage`])
+    Star { seq: u64 },
+    /// Removal of the star of a message of the room (see [`StarMessage`])
+    Unstar { seq: u64 },
+    /// Protocol version and features of the client (see [`HelloMessage`]); only valid as the
+    /// first message of the connection
+    Hello {
+        protocol_version: u32,
+        #[serde(default)]
+        features: Vec<String>,
+    },
+    /// Activity to show next to the client in participant listings (see
+    /// [`SetActivityMessage`])
+    SetActivity {
+        #[serde(default)]
+        activity: Option<String>,
+    },
+    /// Name to show the client as instead of its client ID (see [`SetDisplayNameMessage`])
+    SetDisplayName {
+        #[serde(default)]
+        display_name: Option<String>,
+    },
+}
+
+impl ClientMessage {
+    /// Message types a client can send
+    pub const TYPES: [&str; 13] = [
+        "chat",
+        "backfill-request",
+        "list-rooms",
+        "typing-started",
+        "typing-stopped",
+        "poll",
+        "vote",
+        "forward",
+        "star",
+        "unstar",
+        "hello",
+        "set-activity",
+        "set-display-name",
+    ];
+
+    /// Parse a text frame received from a client
+    ///
+    /// # Errors
+    ///
+    /// Returns an `InboundMessageError` describing why the frame was rejected
+    pub fn parse(text: &str) -> Result<Self, InboundMessageError> {
+        let value: Value = serde_json::from_str(text)
+            .map_err(|e| InboundMessageError::InvalidJson(e.to_string()))?;
+        match value.get("type").and_then(Value::as_str) {
+            Some(r#type) if Self::TYPES.contains(&r#type) => {}
+            Some(r#type) => return Err(InboundMessageError::UnknownType(r#type.to_string())),
+            None => return Err(InboundMessageError::MissingType),
+        }
+        serde_json::from_value(value)
+            .map_err(|e| InboundMessageError::InvalidMessage(e.to_string()))
+    }
 }
 
 /// Chat message sent and received between clients
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Polls are sent with the type `poll`, the question as `content` and the options in `poll`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ChatMessage {
     pub r#type: MessageType,
     pub client_id: String,
     pub content: String,
     pub timestamp: i64,
+    /// Sequence number in the room; set by the server when broadcasting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+    /// Options of the poll and their votes (only on `poll` messages)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll: Option<PollInfo>,
+    /// Where the message was first posted (only on messages forwarded from another room)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forwarded_from: Option<ForwardedFromInfo>,
+}
+
+impl ChatMessage {
+    /// Serialize the message with the sequence number assigned by the room
+    pub fn to_json_with_seq(&self, seq: u64) -> String {
+        serde_json::to_string(&ChatMessage {
+            seq: Some(seq),
+            ..self.clone()
+        })
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_client_messages() {
+        // テスト項目: クライアントが送信できるメッセージが解析される
+        // when (操作):
+        let chat = ClientMessage::parse(
+            r#"{"type":"chat","client_id":"alice","content":"Hi!","timestamp":1000}"#,
+        );
+        let backfill = ClientMessage::parse(r#"{"type":"backfill-request","since_seq":3}"#);
+        let list_rooms = ClientMessage::parse(r#"{"type":"list-rooms"}"#);
+        let typing = ClientMessage::parse(r#"{"type":"typing-started"}"#);
+        let vote = ClientMessage::parse(r#"{"type":"vote","seq":4,"option":1}"#);
+        let forward = ClientMessage::parse(r#"{"type":"forward","seq":4,"room_id":"room-1"}"#);
+        let star = ClientMessage::parse(r#"{"type":"star","seq":4}"#);
+        let unstar = ClientMessage::parse(r#"{"type":"unstar","seq":4}"#);
+        let hello = ClientMessage::parse(r#"{"type":"hello","protocol_version":2}"#);
+        let activity = ClientMessage::parse(r#"{"type":"set-activity","activity":"reviewing"}"#);
+        let rename = ClientMessage::parse(r#"{"type":"set-display-name","display_name":"Alice"}"#);
+
+        // then (期待する結果):
+        assert_eq!(
+            chat,
+            Ok(ClientMessage::Chat {
+                client_id: "alice".to_string(),
+                content: "Hi!".to_string(),
+                timestamp: 1000,
+            })
+        );
+        assert_eq!(
+            backfill,
+            Ok(ClientMessage::BackfillRequest { since_seq: 3 })
+        );
+        assert_eq!(list_rooms, Ok(ClientMessage::ListRooms));
+        assert_eq!(typing, Ok(ClientMessage::TypingStarted));
+        assert_eq!(vote, Ok(ClientMessage::Vote { seq: 4, option: 1 }));
+        assert_eq!(
+            forward,
+            Ok(ClientMessage::Forward {
+                seq: 4,
+                room_id: "room-1".to_string()
+            })
+        );
+        assert_eq!(star, Ok(ClientMessage::Star { seq: 4 }));
+        assert_eq!(unstar, Ok(ClientMessage::Unstar { seq: 4 }));
+        assert_eq!(
+            hello,
+            Ok(ClientMessage::Hello {
+                protocol_version: 2,
+                features: Vec::new()
+            })
+        );
+        assert_eq!(
+            activity,
+            Ok(ClientMessage::SetActivity {
+                activity: Some("reviewing".to_string())
+            })
+        );
+        assert_eq!(
+            rename,
+            Ok(ClientMessage::SetDisplayName {
+                display_name: Some("Alice".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_messages() {
+        // テスト項目: JSON でない・type が無い・未知の type・スキーマ不一致のメッセージは拒否される
+        // when (操作):
+        let cases = [
+            ("hello", "invalid_json"),
+            (r#"{"content":"Hi!"}"#, "missing_type"),
+            (
+                r#"{"type":"room-connected","participants":[]}"#,
+                "unknown_message_type",
+            ),
+            (r#"{"type":"chat","content":"Hi!"}"#, "invalid_message"),
+            (
+                r#"{"type":"backfill-request","since_seq":3,"extra":true}"#,
+                "invalid_message",
+            ),
+        ];
+
+        // then (期待する結果):
+        for (text, code) in cases {
+            let error = ClientMessage::parse(text).unwrap_err();
+            assert_eq!(error.code(), code, "{}", text);
+        }
+    }
 }
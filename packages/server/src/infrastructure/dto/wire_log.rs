@@ -0,0 +1,42 @@
+//! Wire log record DTOs.
+//!
+//! Each record is one WebSocket frame serialized as one line of JSON.
+
+use serde::{Deserialize, Serialize};
+
+/// Which way a frame went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WireDirection {
+    /// Received from the peer
+    In,
+    /// Sent to the peer
+    Out,
+}
+
+/// Kind of WebSocket frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FrameKind {
+    Text,
+    Binary,
+    Ping,
+    Pong,
+    Close,
+}
+
+/// Frame recorded in the wire log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WireRecord {
+    /// Unix timestamp (milliseconds since epoch) in JST
+    pub ts: i64,
+    pub dir: WireDirection,
+    /// Client ID on the server, server URL on the client
+    pub peer: String,
+    pub kind: FrameKind,
+    /// Size of the payload in bytes (before redaction)
+    pub len: usize,
+    /// Text payload after redaction (not recorded for binary and control frames)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+}
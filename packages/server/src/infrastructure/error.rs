@@ -0,0 +1,264 @@
+//! Infrastructure layer error definitions.
+
+use thiserror::Error;
+
+/// Errors related to the Discord REST API
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum DiscordError {
+    /// The request could not be sent or the response could not be read
+    #[error("Discord request failed: {0}")]
+    Request(String),
+
+    /// Discord responded with an error status
+    #[error("Discord API error (status {status}): {body}")]
+    Api { status: u16, body: String },
+
+    /// Still rate limited after retrying
+    #[error("Discord rate limit exceeded")]
+    RateLimited,
+}
+
+/// Errors related to the XMPP component connection
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum XmppError {
+    /// Network error
+    #[error("XMPP connection error: {0}")]
+    Io(String),
+
+    /// Malformed XML received from the server
+    #[error("Invalid XML from XMPP server: {0}")]
+    Xml(String),
+
+    /// The server rejected the component handshake (wrong domain or secret)
+    #[error("XMPP component handshake failed: {0}")]
+    HandshakeFailed(String),
+
+    /// The server closed the stream
+    #[error("XMPP stream closed")]
+    StreamClosed,
+}
+
+/// Errors related to federation links
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum FederationError {
+    /// The frame is not a valid federation frame
+    #[error("Invalid federation frame: {0}")]
+    InvalidFrame(String),
+
+    /// The frame signature does not match (wrong shared secret or tampered frame)
+    #[error("Invalid federation frame signature")]
+    InvalidSignature,
+
+    /// The first frame of a link was not a hello
+    #[error("Federation link must start with a hello")]
+    HelloExpected,
+
+    /// The hello timestamp is too far from the local clock (possible replay)
+    #[error("Federation hello timestamp is out of range")]
+    ClockSkew,
+
+    /// The peer uses the same server ID as this server
+    #[error("Federation peer uses our own server ID '{0}'")]
+    SelfLink(String),
+}
+
+/// Errors related to the write-ahead log
+#[derive(Debug, Error)]
+pub enum WalError {
+    /// The log file could not be read or written
+    #[error("WAL I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A record other than the last one is malformed or out of order
+    #[error("WAL is corrupt at line {line}: {reason}")]
+    Corrupt { line: usize, reason: String },
+
+    /// The log has been handed over to a new process and no longer accepts records
+    #[error("WAL is sealed for handover")]
+    Sealed,
+}
+
+/// Errors related to the wire log
+#[derive(Debug, Error)]
+pub enum WireLogError {
+    /// The log file could not be read or written
+    #[error("Wire log I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A record is malformed
+    #[error("Wire log is corrupt at line {line}: {reason}")]
+    Corrupt { line: usize, reason: String },
+}
+
+/// Errors related to the SQL database room storage (SQLite / PostgreSQL)
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+#[derive(Debug, Error)]
+pub enum DatabaseError {
+    /// The database could not be reached or a statement failed
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    /// The schema could not be migrated to the version this binary expects
+    #[error(transparent)]
+    Migration(#[from] MigrationError),
+
+    /// A stored row does not convert to the domain model
+    #[error("Stored room {room_id} is corrupt: {reason}")]
+    Corrupt { room_id: String, reason: String },
+}
+
+/// Errors related to SQL schema migrations
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    /// The database URL is neither `sqlite:` nor `postgres://`
+    #[error("Unsupported database URL '{0}' (expected sqlite:... or postgres://...)")]
+    UnsupportedUrl(String),
+
+    /// The backend of the database URL was not compiled in
+    #[error("This binary was built without the '{0}' feature")]
+    BackendDisabled(&'static str),
+
+    /// The latest applied migration has no down migration
+    #[error("Migration {0} cannot be reverted")]
+    Irreversible(i64),
+
+    /// The database could not be reached or a statement failed
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    /// The migration history is inconsistent or a migration failed
+    #[error("Migration error: {0}")]
+    Migrate(#[from] sqlx::migrate::MigrateError),
+}
+
+/// Errors related to connect-time proof-of-work solutions
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ProofOfWorkError {
+    /// The solution is not `<challenge>:<nonce>`
+    #[error("Proof-of-work solution is malformed")]
+    Malformed,
+
+    /// The challenge was not issued by this server
+    #[error("Proof-of-work challenge was not issued by this server")]
+    UnknownChallenge,
+
+    /// The challenge has expired
+    #[error("Proof-of-work challenge has expired")]
+    Expired,
+
+    /// The hash does not have enough leading zero bits
+    #[error("Proof-of-work solution does not meet difficulty {0}")]
+    InsufficientWork(u8),
+
+    /// The challenge was already used by another connection
+    #[error("Proof-of-work challenge was already used")]
+    Replayed,
+}
+
+/// Errors related to login credentials and access tokens
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum AuthError {
+    /// The credentials file could not be read
+    #[error("Failed to read credentials file '{path}': {reason}")]
+    CredentialsFile { path: String, reason: String },
+
+    /// A line of the credentials file is not `<client_id>:<password hash>`
+    #[error("Invalid credentials on line {0}: expected <client_id>:<argon2 password hash>")]
+    InvalidCredentials(usize),
+
+    /// The username or the password is wrong
+    #[error("Invalid username or password")]
+    LoginFailed,
+
+    /// The access token is malformed or not signed by this server
+    #[error("Access token is invalid")]
+    InvalidToken,
+
+    /// The access token has expired
+    #[error("Access token has expired")]
+    ExpiredToken,
+}
+
+/// Errors related to messages received from WebSocket clients
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum InboundMessageError {
+    /// The frame is not valid JSON
+    #[error("Message is not valid JSON: {0}")]
+    InvalidJson(String),
+
+    /// The message has no `type` field
+    #[error("Message has no type")]
+    MissingType,
+
+    /// The message type is not one a client can send
+    #[error("Unknown message type '{0}'")]
+    UnknownType(String),
+
+    /// The message does not match the schema of its type, or a field value is invalid
+    #[error("Invalid message: {0}")]
+    InvalidMessage(String),
+
+    /// The client may not post messages (e.g. a guest in read-only guest mode)
+    #[error("Posting is not allowed for this client")]
+    ReadOnly,
+
+    /// The client sent too many messages
+    #[error("Too many messages; retry in {retry_after_ms} ms")]
+    RateLimited { retry_after_ms: u64 },
+
+    /// The room history holds as many messages as the room allows
+    #[error("The room history is full ({capacity} messages at most)")]
+    RoomHistoryFull { capacity: usize },
+
+    /// An admin froze the room; the history stays readable
+    #[error("The room is frozen; messages are not accepted")]
+    RoomFrozen,
+
+    /// The vote is not on a poll in the room history, or for an option the poll does not have
+    #[error("Invalid vote: {0}")]
+    InvalidVote(String),
+
+    /// The message to forward is not in the room history, or the client is not in the room to
+    /// forward it to
+    #[error("Invalid forward: {0}")]
+    InvalidForward(String),
+
+    /// A message filter of the server rejected the message
+    #[error("Message rejected by the {filter} filter: {reason}")]
+    MessageRejected { filter: String, reason: String },
+
+    /// The message to star is not in the room history, or the client has too many stars
+    #[error("Invalid star: {0}")]
+    InvalidStar(String),
+
+    /// The activity is too long or contains line breaks, or activities are disabled
+    #[error("Invalid activity: {0}")]
+    InvalidActivity(String),
+
+    /// The display name is too long or contains control characters, or renaming is disabled
+    #[error("Invalid display name: {0}")]
+    InvalidDisplayName(String),
+}
+
+impl InboundMessageError {
+    /// Stable error code sent to the client
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidJson(_) => "invalid_json",
+            Self::MissingType => "missing_type",
+            Self::UnknownType(_) => "unknown_message_type",
+            Self::InvalidMessage(_) => "invalid_message",
+            Self::ReadOnly => "read_only",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::RoomHistoryFull { .. } => "room_history_full",
+            Self::RoomFrozen => "room_frozen",
+            Self::InvalidVote(_) => "invalid_vote",
+            Self::InvalidForward(_) => "invalid_forward",
+            Self::MessageRejected { .. } => "message_rejected",
+            Self::InvalidStar(_) => "invalid_star",
+            Self::InvalidActivity(_) => "invalid_activity",
+            Self::InvalidDisplayName(_) => "invalid_display_name",
+        }
+    }
+}
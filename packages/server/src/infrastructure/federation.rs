@@ -0,0 +1,219 @@
+//! サーバ間フェデレーションのリンク署名とリモート参加者の管理
+//!
+//! ## 責務
+//!
+//! - フレームの署名と検証（共有シークレットによる HMAC-SHA256）
+//! - リンク開始時の hello の検証（時刻のずれによるリプレイ対策）
+//! - ピアから同期されたリモート参加者の ID の管理（ループ防止に使用）
+//!
+//! リンクの確立やイベントの適用・転送は UI 層のフェデレーションタスクが扱います。
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use super::{
+    dto::federation::{FederationEnvelope, FederationEvent, FederationFrame},
+    error::FederationError,
+};
+
+/// hello の時刻として許容するずれ（ミリ秒）
+pub const MAX_CLOCK_SKEW_MILLIS: i64 = 5 * 60 * 1000;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// リモート参加者のクライアント ID（`{client_id}@{origin}`）
+pub fn remote_client_id(client_id: &str, origin: &str) -> String {
+    format!("{}@{}", client_id, origin)
+}
+
+fn mac(secret: &str, body: &str) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    mac
+}
+
+/// エンベロープを署名付きフレーム（JSON）にする
+pub fn seal(secret: &str, envelope: &FederationEnvelope) -> String {
+    let body = serde_json::to_string(envelope).unwrap();
+    let signature = mac(secret, &body)
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    serde_json::to_string(&FederationFrame { body, signature }).unwrap()
+}
+
+/// 署名付きフレームを検証してエンベロープを取り出す
+pub fn open(secret: &str, frame: &str) -> Result<FederationEnvelope, FederationError> {
+    let frame: FederationFrame =
+        serde_json::from_str(frame).map_err(|e| FederationError::InvalidFrame(e.to_string()))?;
+    let signature = decode_hex(&frame.signature).ok_or(FederationError::InvalidSignature)?;
+    // 定数時間で比較する
+    mac(secret, &frame.body)
+        .verify_slice(&signature)
+        .map_err(|_| FederationError::InvalidSignature)?;
+    serde_json::from_str(&frame.body).map_err(|e| FederationError::InvalidFrame(e.to_string()))
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// リンク最初のエンベロープ（hello）を検証し、ピアのサーバ ID を返す
+///
+/// # 引数
+///
+/// - `envelope`: 署名検証済みのエンベロープ
+/// - `server_id`: 自サーバの ID
+/// - `now`: 現在時刻（Unix 時刻、ミリ秒）
+pub fn verify_hello(
+    envelope: &FederationEnvelope,
+    server_id: &str,
+    now: i64,
+) -> Result<String, FederationError> {
+    let FederationEvent::Hello { timestamp } = envelope.event else {
+        return Err(FederationError::HelloExpected);
+    };
+    if (now - timestamp).abs() > MAX_CLOCK_SKEW_MILLIS {
+        return Err(FederationError::ClockSkew);
+    }
+    if envelope.origin == server_id {
+        return Err(FederationError::SelfLink(envelope.origin.clone()));
+    }
+    Ok(envelope.origin.clone())
+}
+
+/// ピアから同期されたリモート参加者のクライアント ID の集合
+///
+/// フェデレーションタスクが追加・削除し、`FederationPusher` はここに含まれる参加者の
+/// イベントをピアに送り返さない。
+#[derive(Debug, Clone, Default)]
+pub struct RemoteParticipants {
+    ids: Arc<Mutex<HashSet<String>>>,
+}
+
+impl RemoteParticipants {
+    /// 空の集合を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// リモート参加者を追加（既に含まれていた場合は `false`）
+    pub fn insert(&self, client_id: &str) -> bool {
+        self.ids.lock().unwrap().insert(client_id.to_string())
+    }
+
+    /// リモート参加者を削除
+    pub fn remove(&self, client_id: &str) {
+        self.ids.lock().unwrap().remove(client_id);
+    }
+
+    /// リモート参加者かどうか
+    pub fn contains(&self, client_id: &str) -> bool {
+        self.ids.lock().unwrap().contains(client_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hello(origin: &str, timestamp: i64) -> FederationEnvelope {
+        FederationEnvelope {
+            origin: origin.to_string(),
+            via: vec![origin.to_string()],
+            event: FederationEvent::Hello { timestamp },
+        }
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        // テスト項目: 同じシークレットで署名したフレームは検証でき、元のエンベロープが得られる
+        // given (前提条件):
+        let envelope = FederationEnvelope {
+            origin: "tokyo".to_string(),
+            via: vec!["tokyo".to_string()],
+            event: FederationEvent::Chat {
+                client_id: "alice".to_string(),
+                content: "hello".to_string(),
+                timestamp: 1,
+            },
+        };
+
+        // when (操作):
+        let frame = seal("secret", &envelope);
+
+        // then (期待する結果):
+        assert_eq!(open("secret", &frame), Ok(envelope));
+    }
+
+    #[test]
+    fn test_open_rejects_wrong_secret_and_tampering() {
+        // テスト項目: シークレットが異なる場合や本文が改ざんされた場合は署名エラーになる
+        // given (前提条件):
+        let frame = seal("secret", &hello("tokyo", 0));
+        let tampered = frame.replace("tokyo", "osaka");
+
+        // then (期待する結果):
+        assert_eq!(
+            open("other", &frame),
+            Err(FederationError::InvalidSignature)
+        );
+        assert_eq!(
+            open("secret", &tampered),
+            Err(FederationError::InvalidSignature)
+        );
+        assert!(matches!(
+            open("secret", "not json"),
+            Err(FederationError::InvalidFrame(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_hello() {
+        // テスト項目: hello は時刻のずれと自サーバ ID を検証する
+        // given (前提条件):
+        let now = 1_000_000_000;
+
+        // then (期待する結果):
+        assert_eq!(
+            verify_hello(&hello("tokyo", now - 1000), "osaka", now),
+            Ok("tokyo".to_string())
+        );
+        assert_eq!(
+            verify_hello(
+                &hello("tokyo", now - MAX_CLOCK_SKEW_MILLIS - 1),
+                "osaka",
+                now
+            ),
+            Err(FederationError::ClockSkew)
+        );
+        assert_eq!(
+            verify_hello(&hello("osaka", now), "osaka", now),
+            Err(FederationError::SelfLink("osaka".to_string()))
+        );
+        let snapshot = FederationEnvelope {
+            event: FederationEvent::Snapshot {
+                participants: vec![],
+            },
+            ..hello("tokyo", now)
+        };
+        assert_eq!(
+            verify_hello(&snapshot, "osaka", now),
+            Err(FederationError::HelloExpected)
+        );
+    }
+}
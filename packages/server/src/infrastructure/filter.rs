@@ -0,0 +1,228 @@
+//! 組み込みのメッセージフィルター
+//!
+//! ## 責務
+//!
+//! - `ProfanityFilter`: 設定した禁止語を含むメッセージを拒否する
+//! - `MaxLinksFilter`: 設定した数より多くのリンクを含むメッセージを拒否する
+//! - `RegexBlocklistFilter`: 設定した正規表現のいずれかに一致するメッセージを拒否する
+//!
+//! ## 設計ノート
+//!
+//! 禁止語は `KeywordAnalyzer` と同じく、大文字・小文字を区別せず英数字以外の文字で区切った
+//! 単語単位で照合します（`heck` は `Heck!` に一致し、`checkout` には一致しない）。
+//! リンクは `http://`・`https://`・`www.` で始まる空白区切りの語を数えます。
+//! 拒否の理由には一致した語やパターンを含めません（送信者に禁止語の一覧を知らせない）。
+
+use async_trait::async_trait;
+use regex::Regex;
+
+use crate::domain::{ClientId, MessageContent, MessageFilter, MessageRejection};
+
+/// リンクとして数える語の接頭辞
+const LINK_PREFIXES: [&str; 3] = ["http://", "https://", "www."];
+
+/// 設定した禁止語を含むメッセージを拒否する MessageFilter
+pub struct ProfanityFilter {
+    /// 小文字にした禁止語
+    words: Vec<String>,
+}
+
+impl ProfanityFilter {
+    /// フィルターの名前
+    pub const NAME: &'static str = "profanity";
+
+    /// 新しい ProfanityFilter を作成
+    ///
+    /// # 引数
+    ///
+    /// - `words`: 禁止語（大文字・小文字は区別しない、空の語は無視する）
+    pub fn new(words: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            words: words
+                .into_iter()
+                .map(|word| word.trim().to_lowercase())
+                .filter(|word| !word.is_empty())
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl MessageFilter for ProfanityFilter {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn check(
+        &self,
+        _from: &ClientId,
+        content: &MessageContent,
+    ) -> Result<(), MessageRejection> {
+        let content = content.as_str().to_lowercase();
+        let blocked = content
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|word| self.words.iter().any(|blocked| blocked == word));
+        if blocked {
+            return Err(MessageRejection {
+                filter: Self::NAME,
+                reason: "the message contains a blocked word".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// 設定した数より多くのリンクを含むメッセージを拒否する MessageFilter
+pub struct MaxLinksFilter {
+    /// 1 つのメッセージに含められるリンクの数
+    max: usize,
+}
+
+impl MaxLinksFilter {
+    /// フィルターの名前
+    pub const NAME: &'static str = "max-links";
+
+    /// 新しい MaxLinksFilter を作成（`max` は 1 つのメッセージに含められるリンクの数）
+    pub fn new(max: usize) -> Self {
+        Self { max }
+    }
+}
+
+#[async_trait]
+impl MessageFilter for MaxLinksFilter {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn check(
+        &self,
+        _from: &ClientId,
+        content: &MessageContent,
+    ) -> Result<(), MessageRejection> {
+        let links = content
+            .as_str()
+            .split_whitespace()
+            .filter(|word| {
+                let word = word.to_lowercase();
+                LINK_PREFIXES.iter().any(|prefix| word.starts_with(prefix))
+            })
+            .count();
+        if links > self.max {
+            return Err(MessageRejection {
+                filter: Self::NAME,
+                reason: format!("the message has {} links ({} at most)", links, self.max),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// 設定した正規表現のいずれかに一致するメッセージを拒否する MessageFilter
+pub struct RegexBlocklistFilter {
+    patterns: Vec<Regex>,
+}
+
+impl RegexBlocklistFilter {
+    /// フィルターの名前
+    pub const NAME: &'static str = "regex";
+
+    /// 新しい RegexBlocklistFilter を作成
+    ///
+    /// # 引数
+    ///
+    /// - `patterns`: 拒否するメッセージの正規表現（`regex` クレートの構文、大文字・小文字を
+    ///   区別しない場合は `(?i)` を付ける）
+    ///
+    /// # エラー
+    ///
+    /// 正規表現として解釈できないパターンがある場合
+    pub fn new<S: AsRef<str>>(patterns: impl IntoIterator<Item = S>) -> Result<Self, regex::Error> {
+        Ok(Self {
+            patterns: patterns
+                .into_iter()
+                .map(|pattern| Regex::new(pattern.as_ref()))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[async_trait]
+impl MessageFilter for RegexBlocklistFilter {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn check(
+        &self,
+        _from: &ClientId,
+        content: &MessageContent,
+    ) -> Result<(), MessageRejection> {
+        if self
+            .patterns
+            .iter()
+            .any(|pattern| pattern.is_match(content.as_str()))
+        {
+            return Err(MessageRejection {
+                filter: Self::NAME,
+                reason: "the message matches a blocked pattern".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn check(filter: &dyn MessageFilter, text: &str) -> Result<(), MessageRejection> {
+        let from = ClientId::new("alice".to_string()).unwrap();
+        filter
+            .check(&from, &MessageContent::new(text.to_string()).unwrap())
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_profanity_filter_matches_whole_words_ignoring_case() {
+        // テスト項目: 禁止語は大文字・小文字を区別せず単語単位で照合される
+        // given (前提条件):
+        let filter = ProfanityFilter::new(["Heck".to_string()]);
+
+        // when (操作) / then (期待する結果):
+        let rejected = check(&filter, "What the HECK!").await.unwrap_err();
+        assert_eq!(rejected.filter, "profanity");
+        assert!(!rejected.reason.contains("heck"));
+        assert!(check(&filter, "checkout is done").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_max_links_filter_counts_links() {
+        // テスト項目: http(s):// と www. で始まる語をリンクとして数え、上限を超えると拒否される
+        // given (前提条件):
+        let filter = MaxLinksFilter::new(1);
+
+        // when (操作):
+        let one = check(&filter, "see https://example.com").await;
+        let two = check(&filter, "HTTP://a.example and www.b.example").await;
+
+        // then (期待する結果):
+        assert!(one.is_ok());
+        assert_eq!(
+            two.unwrap_err().reason,
+            "the message has 2 links (1 at most)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_regex_blocklist_filter() {
+        // テスト項目: いずれかの正規表現に一致するメッセージが拒否され、不正な正規表現は設定できない
+        // given (前提条件):
+        let filter = RegexBlocklistFilter::new(["(?i)buy now", r"\d{4}-\d{4}-\d{4}"]).unwrap();
+
+        // when (操作) / then (期待する結果):
+        assert!(check(&filter, "BUY NOW!!").await.is_err());
+        assert!(check(&filter, "card 1234-5678-9012").await.is_err());
+        assert!(check(&filter, "buy later").await.is_ok());
+        assert!(RegexBlocklistFilter::new(["(unclosed"]).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! ルームをノードに割り当てるコンシステントハッシュリング
+//!
+//! ## 責務
+//!
+//! - ノード ID から仮想ノードを作り、リング上に配置
+//! - ルームのキーから担当ノードを決定
+//!
+//! ## 設計ノート
+//!
+//! ノードの追加・削除で担当が変わるのは、そのノードが担当する（担当することになる）ルームのみです。
+//! 全ノードが同じ割り当てを計算できるよう、ハッシュにはビルドに依存しない FNV-1a を使います。
+
+/// 1 ノードあたりの仮想ノード数
+pub const VIRTUAL_NODES: usize = 64;
+
+/// コンシステントハッシュリング
+#[derive(Debug, Clone, Default)]
+pub struct HashRing {
+    /// (ハッシュ値, ノード ID) をハッシュ値の昇順に並べたもの
+    points: Vec<(u64, String)>,
+}
+
+impl HashRing {
+    /// ノード ID の一覧からリングを作成
+    pub fn new<'a>(node_ids: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut points: Vec<(u64, String)> = node_ids
+            .into_iter()
+            .flat_map(|node_id| {
+                (0..VIRTUAL_NODES)
+                    .map(move |i| (hash(&format!("{}#{}", node_id, i)), node_id.to_string()))
+            })
+            .collect();
+        points.sort();
+        points.dedup();
+        Self { points }
+    }
+
+    /// キーを担当するノード ID（ノードが無い場合は `None`）
+    pub fn owner(&self, key: &str) -> Option<&str> {
+        if self.points.is_empty() {
+            return None;
+        }
+        let hash = hash(key);
+        let index = self.points.partition_point(|(point, _)| *point < hash);
+        let (_, node_id) = &self.points[index % self.points.len()];
+        Some(node_id)
+    }
+}
+
+/// FNV-1a（64 ビット）に splitmix64 の最終処理を加えたハッシュ
+fn hash(key: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in key.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    // 末尾だけが異なるキー（仮想ノード名など）をリング上に散らばらせる
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> Vec<String> {
+        (0..1000).map(|i| format!("room-{}", i)).collect()
+    }
+
+    #[test]
+    fn test_owner_is_deterministic() {
+        // テスト項目: 同じノード構成であれば、ノードの並び順によらず同じ担当ノードになる
+        // given (前提条件):
+        let ring = HashRing::new(["a", "b", "c"]);
+        let reversed = HashRing::new(["c", "b", "a"]);
+
+        // then (期待する結果):
+        for key in keys() {
+            assert_eq!(ring.owner(&key), reversed.owner(&key));
+        }
+        assert_eq!(HashRing::new([]).owner("room-0"), None);
+    }
+
+    #[test]
+    fn test_keys_are_balanced() {
+        // テスト項目: キーが各ノードにおおよそ均等に割り当てられる
+        // given (前提条件):
+        let ring = HashRing::new(["a", "b", "c"]);
+
+        // when (操作):
+        let owners: Vec<&str> = keys().iter().filter_map(|key| ring.owner(key)).collect();
+
+        // then (期待する結果): 各ノードが 1000 件中 200 件以上を担当する
+        for node_id in ["a", "b", "c"] {
+            let count = owners.iter().filter(|owner| **owner == node_id).count();
+            assert!(count >= 200, "{} owns only {} keys", node_id, count);
+        }
+    }
+
+    #[test]
+    fn test_removing_node_only_moves_its_keys() {
+        // テスト項目: ノードを削除しても、そのノード以外が担当するキーは移動しない
+        // given (前提条件):
+        let before = HashRing::new(["a", "b", "c"]);
+
+        // when (操作):
+        let after = HashRing::new(["a", "b"]);
+
+        // then (期待する結果):
+        for key in keys() {
+            let owner = before.owner(&key).unwrap();
+            if owner != "c" {
+                assert_eq!(after.owner(&key), Some(owner));
+            }
+        }
+    }
+}
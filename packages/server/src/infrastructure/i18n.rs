@@ -0,0 +1,208 @@
+//! システムメッセージの多言語化
+//!
+//! ## 責務
+//!
+//! - サーバが生成するテキスト（入室・退室の通知など）のロケールごとの翻訳表
+//!
+//! ## 設計ノート
+//!
+//! サーバはルームのロケールで通知文（`notice`）を付けて配信します。
+//! クライアントは同じ表を使って自分のロケールで表示し直せるため、
+//! ルームのロケールと異なる言語で表示したい場合も通知文を解析する必要はありません。
+
+use crate::domain::Locale;
+
+/// サーバが生成するテキスト
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemText<'a> {
+    /// 参加者の入室
+    ParticipantJoined { client_id: &'a str },
+    /// 参加者の退室
+    ParticipantLeft { client_id: &'a str },
+    /// サーバの再起動
+    ServerRestart,
+    /// サーバの停止
+    ServerShutdown,
+    /// 承認が必要なルームへの参加の承認待ち
+    JoinPending,
+    /// ブレイクアウトの作成（親のルームへの通知）
+    BreakoutOpened { name: &'a str, room_id: &'a str },
+    /// ブレイクアウトのアーカイブ（親のルームへの通知）
+    BreakoutArchived { name: &'a str },
+    /// デイリーダイジェスト（`most_active` はクライアント ID と発言数、発言数の多い順）
+    DailyDigest {
+        message_count: usize,
+        most_active: &'a [(&'a str, usize)],
+    },
+}
+
+impl SystemText<'_> {
+    /// 指定したロケールのテキストを作成
+    pub fn localize(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (SystemText::ParticipantJoined { client_id }, Locale::En) => {
+                format!("{} joined the room", client_id)
+            }
+            (SystemText::ParticipantJoined { client_id }, Locale::Ja) => {
+                format!("{} さんが入室しました", client_id)
+            }
+            (SystemText::ParticipantLeft { client_id }, Locale::En) => {
+                format!("{} left the room", client_id)
+            }
+            (SystemText::ParticipantLeft { client_id }, Locale::Ja) => {
+                format!("{} さんが退室しました", client_id)
+            }
+            (SystemText::ServerRestart, Locale::En) => {
+                "The server is restarting; you will be reconnected shortly".to_string()
+            }
+            (SystemText::ServerRestart, Locale::Ja) => {
+                "サーバを再起動しています。まもなく再接続します".to_string()
+            }
+            (SystemText::ServerShutdown, Locale::En) => {
+                "The server is shutting down; you will be reconnected once it is back".to_string()
+            }
+            (SystemText::ServerShutdown, Locale::Ja) => {
+                "サーバを停止しています。再開後に再接続します".to_string()
+            }
+            (SystemText::JoinPending, Locale::En) => {
+                "This room requires approval; waiting for a moderator to let you in".to_string()
+            }
+            (SystemText::JoinPending, Locale::Ja) => {
+                "このルームへの参加には承認が必要です。モデレーターの承認を待っています".to_string()
+            }
+            (SystemText::BreakoutOpened { name, room_id }, Locale::En) => {
+                format!(
+                    "Breakout room \"{}\" opened; join it with room_id {}",
+                    name, room_id
+                )
+            }
+            (SystemText::BreakoutOpened { name, room_id }, Locale::Ja) => {
+                format!(
+                    "ブレイクアウトルーム「{}」を作成しました。room_id {} で参加できます",
+                    name, room_id
+                )
+            }
+            (SystemText::BreakoutArchived { name }, Locale::En) => {
+                format!("Breakout room \"{}\" was archived after inactivity", name)
+            }
+            (SystemText::BreakoutArchived { name }, Locale::Ja) => {
+                format!(
+                    "ブレイクアウトルーム「{}」は使われていないためアーカイブしました",
+                    name
+                )
+            }
+            (
+                SystemText::DailyDigest {
+                    message_count,
+                    most_active,
+                },
+                Locale::En,
+            ) => {
+                let senders = most_active
+                    .iter()
+                    .map(|(client_id, messages)| format!("{} ({})", client_id, messages))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "Daily digest: {} messages in the last 24 hours. Most active: {}",
+                    message_count, senders
+                )
+            }
+            (
+                SystemText::DailyDigest {
+                    message_count,
+                    most_active,
+                },
+                Locale::Ja,
+            ) => {
+                let senders = most_active
+                    .iter()
+                    .map(|(client_id, messages)| format!("{} さん（{} 件）", client_id, messages))
+                    .collect::<Vec<_>>()
+                    .join("、");
+                format!(
+                    "デイリーダイジェスト: 過去 24 時間のメッセージは {} 件でした。よく発言した人: {}",
+                    message_count, senders
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localize_participant_joined() {
+        // テスト項目: 入室の通知がロケールごとに翻訳される
+        // given (前提条件):
+        let text = SystemText::ParticipantJoined { client_id: "alice" };
+
+        // when (操作):
+        let en = text.localize(Locale::En);
+        let ja = text.localize(Locale::Ja);
+
+        // then (期待する結果):
+        assert_eq!(en, "alice joined the room");
+        assert_eq!(ja, "alice さんが入室しました");
+    }
+
+    #[test]
+    fn test_localize_daily_digest() {
+        // テスト項目: デイリーダイジェストにメッセージ数と発言数の多い参加者が載る
+        // given (前提条件):
+        let text = SystemText::DailyDigest {
+            message_count: 3,
+            most_active: &[("alice", 2), ("bob", 1)],
+        };
+
+        // when (操作):
+        let en = text.localize(Locale::En);
+        let ja = text.localize(Locale::Ja);
+
+        // then (期待する結果):
+        assert_eq!(
+            en,
+            "Daily digest: 3 messages in the last 24 hours. Most active: alice (2), bob (1)"
+        );
+        assert_eq!(
+            ja,
+            "デイリーダイジェスト: 過去 24 時間のメッセージは 3 件でした。よく発言した人: alice さん（2 件）、bob さん（1 件）"
+        );
+    }
+
+    #[test]
+    fn test_every_text_is_translated() {
+        // テスト項目: 全てのテキストが全てのロケールで空でない
+        // given (前提条件):
+        let texts = [
+            SystemText::ParticipantJoined { client_id: "alice" },
+            SystemText::ParticipantLeft { client_id: "alice" },
+            SystemText::ServerRestart,
+            SystemText::ServerShutdown,
+            SystemText::JoinPending,
+            SystemText::BreakoutOpened {
+                name: "Design review",
+                room_id: "room-1",
+            },
+            SystemText::BreakoutArchived {
+                name: "Design review",
+            },
+            SystemText::DailyDigest {
+                message_count: 3,
+                most_active: &[("alice", 2), ("bob", 1)],
+            },
+        ];
+
+        for text in texts {
+            for locale in Locale::ALL {
+                // when (操作):
+                let localized = text.localize(locale);
+
+                // then (期待する結果):
+                assert!(!localized.is_empty(), "{:?} in {}", text, locale);
+            }
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Discord にチャットメッセージを中継する MessagePusher 実装
+//!
+//! ## 責務
+//!
+//! - 内側の MessagePusher（WebSocket など）への送信をそのまま委譲
+//! - ルームへのチャットメッセージを `**{client_id}**: {content}` 形式で送信キューに積む
+//!
+//! ## 設計ノート
+//!
+//! Discord への投稿はレートリミットで待たされることがあるため、ここでは有界キューに積むだけにし、
+//! 実際の投稿は UI 層のリレータスクが行います。キューが溢れた場合は古い順に処理しきれないため
+//! 新しいメッセージを破棄してログに残します。
+//!
+//! Discord から中継されたメッセージ（送信者 ID がリレー用の接頭辞で始まるもの）は
+//! Discord に送り返さないことでループを防ぎます。参加・退出通知は中継しません。
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use crate::{
+    domain::{ClientId, MessagePushError, MessagePusher, PusherChannel, RoomFeed},
+    infrastructure::dto::websocket::{ChatMessage, MessageType},
+};
+
+/// Discord への送信キューの容量
+pub const OUTBOUND_QUEUE_CAPACITY: usize = 256;
+
+/// Discord にチャットメッセージを中継する MessagePusher 実装
+pub struct DiscordRelayPusher {
+    /// 委譲先の MessagePusher
+    inner: Arc<dyn MessagePusher>,
+    /// Discord への送信キュー
+    outbound: mpsc::Sender<String>,
+    /// Discord から中継された送信者に付く接頭辞（ループ防止に使用）
+    relay_prefix: String,
+}
+
+impl DiscordRelayPusher {
+    /// 新しい DiscordRelayPusher を作成
+    ///
+    /// # 引数
+    ///
+    /// - `inner`: 委譲先の MessagePusher
+    /// - `outbound`: Discord への送信キュー（受信側は UI 層のリレータスク）
+    /// - `relay_prefix`: Discord から中継された送信者 ID の接頭辞（例: `discord:`）
+    pub fn new(
+        inner: Arc<dyn MessagePusher>,
+        outbound: mpsc::Sender<String>,
+        relay_prefix: String,
+    ) -> Self {
+        Self {
+            inner,
+            outbound,
+            relay_prefix,
+        }
+    }
+
+    /// ブロードキャスト内容から Discord に投稿する行を作成（中継対象外なら `None`）
+    pub fn outbound_line(&self, content: &str) -> Option<String> {
+        let message: ChatMessage = serde_json::from_str(content).ok()?;
+        if !matches!(message.r#type, MessageType::Chat)
+            || message.client_id.starts_with(&self.relay_prefix)
+        {
+            return None;
+        }
+        Some(format!(
+            "**{}**: {}",
+            escape_markdown(&message.client_id),
+            message.content
+        ))
+    }
+}
+
+/// Discord の Markdown で意味を持つ文字をエスケープ
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '*' | '_' | '~' | '`' | '|' | '\\' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[async_trait]
+impl MessagePusher for DiscordRelayPusher {
+    async fn register_client(&self, client_id: ClientId, sender: PusherChannel) {
+        self.inner.register_client(client_id, sender).await;
+    }
+
+    async fn unregister_client(&self, client_id: &ClientId) {
+        self.inner.unregister_client(client_id).await;
+    }
+
+    async fn unregister_connection(&self, client_id: &ClientId, sender: &PusherChannel) -> usize {
+        self.inner.unregister_connection(client_id, sender).await
+    }
+
+    async fn subscribe(&self, client_id: &ClientId, sender: &PusherChannel) -> Option<RoomFeed> {
+        self.inner.subscribe(client_id, sender).await
+    }
+
+    async fn push_to(&self, client_id: &ClientId, content: &str) -> Result<(), MessagePushError> {
+        self.inner.push_to(client_id, content).await
+    }
+
+    async fn broadcast(
+        &self,
+        targets: Vec<ClientId>,
+        content: &str,
+    ) -> Result<(), MessagePushError> {
+        let result = self.inner.broadcast(targets, content).await;
+
+        if let Some(line) = self.outbound_line(content)
+            && let Err(e) = self.outbound.try_send(line)
+        {
+            tracing::warn!("Dropping message relayed to Discord: {}", e);
+        }
+
+        result
+    }
+
+    async fn ping(&self) -> Result<(), MessagePushError> {
+        self.inner.ping().await?;
+        if self.outbound.is_closed() {
+            return Err(MessagePushError::PushFailed(
+                "Discord relay has stopped".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NoopPusher;
+
+    #[async_trait]
+    impl MessagePusher for NoopPusher {
+        async fn register_client(&self, _client_id: ClientId, _sender: PusherChannel) {}
+
+        async fn unregister_client(&self, _client_id: &ClientId) {}
+        async fn unregister_connection(
+            &self,
+            _client_id: &ClientId,
+            _sender: &PusherChannel,
+        ) -> usize {
+            0
+        }
+
+        async fn push_to(
+            &self,
+            _client_id: &ClientId,
+            _content: &str,
+        ) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+
+        async fn broadcast(
+            &self,
+            _targets: Vec<ClientId>,
+            _content: &str,
+        ) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+
+        async fn ping(&self) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+    }
+
+    fn pusher() -> (DiscordRelayPusher, mpsc::Receiver<String>) {
+        let (sender, receiver) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
+        (
+            DiscordRelayPusher::new(Arc::new(NoopPusher), sender, "discord:".to_string()),
+            receiver,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_relays_chat_message() {
+        // テスト項目: チャットメッセージは送信者名付きで Discord の送信キューに積まれる
+        // given (前提条件):
+        let (pusher, mut receiver) = pusher();
+        let content = r#"{"type":"chat","client_id":"alice_1","content":"hello","timestamp":0}"#;
+
+        // when (操作):
+        pusher.broadcast(vec![], content).await.unwrap();
+
+        // then (期待する結果): 送信者名の Markdown 記号はエスケープされる
+        assert_eq!(receiver.try_recv().unwrap(), "**alice\\_1**: hello");
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_skips_relayed_and_non_chat_messages() {
+        // テスト項目: Discord から中継されたメッセージと参加通知は中継されない（ループ防止）
+        // given (前提条件):
+        let (pusher, mut receiver) = pusher();
+        let relayed = r#"{"type":"chat","client_id":"discord:bob","content":"hi","timestamp":0}"#;
+        let joined = r#"{"type":"participant-joined","client_id":"carol","connected_at":0}"#;
+
+        // when (操作):
+        pusher.broadcast(vec![], relayed).await.unwrap();
+        pusher.broadcast(vec![], joined).await.unwrap();
+
+        // then (期待する結果):
+        assert!(receiver.try_recv().is_err());
+    }
+}
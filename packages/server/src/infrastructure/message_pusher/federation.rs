@@ -0,0 +1,283 @@
+//! ルームのイベントをフェデレーションのピアに送る MessagePusher 実装
+//!
+//! ## 責務
+//!
+//! - 内側の MessagePusher（WebSocket など）への送信をそのまま委譲
+//! - ローカル参加者のチャットメッセージ・参加・退出通知を `FederationEvent` にして送信キューに積む
+//!
+//! ## 設計ノート
+//!
+//! ピアから同期されたリモート参加者（`RemoteParticipants` に含まれる参加者）のイベントは
+//! 送り返さないことでループを防ぎます。ピア間の転送は UI 層のフェデレーションタスクが
+//! `via` を見て行います。
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+
+use crate::{
+    domain::{ClientId, MessagePushError, MessagePusher, PusherChannel, RoomFeed},
+    infrastructure::{
+        dto::{
+            federation::FederationEvent,
+            websocket::{
+                ChatMessage, MessageType, ParticipantJoinedMessage, ParticipantLeftMessage,
+            },
+        },
+        federation::RemoteParticipants,
+    },
+};
+
+/// ピアへの送信キューの容量
+pub const OUTBOUND_QUEUE_CAPACITY: usize = 1024;
+
+#[derive(Deserialize)]
+struct Envelope {
+    r#type: MessageType,
+}
+
+/// ルームのイベントをフェデレーションのピアに送る MessagePusher 実装
+pub struct FederationPusher {
+    /// 委譲先の MessagePusher
+    inner: Arc<dyn MessagePusher>,
+    /// ピアへの送信キュー
+    outbound: mpsc::Sender<FederationEvent>,
+    /// ピアから同期されたリモート参加者（ループ防止に使用）
+    remote: RemoteParticipants,
+}
+
+impl FederationPusher {
+    /// 新しい FederationPusher を作成
+    ///
+    /// # 引数
+    ///
+    /// - `inner`: 委譲先の MessagePusher
+    /// - `outbound`: ピアへの送信キュー（受信側は UI 層のフェデレーションタスク）
+    /// - `remote`: フェデレーションタスクと共有するリモート参加者の集合
+    pub fn new(
+        inner: Arc<dyn MessagePusher>,
+        outbound: mpsc::Sender<FederationEvent>,
+        remote: RemoteParticipants,
+    ) -> Self {
+        Self {
+            inner,
+            outbound,
+            remote,
+        }
+    }
+
+    /// ブロードキャスト内容からピアに送るイベントを作成（送信対象外なら `None`）
+    pub fn local_event(&self, content: &str) -> Option<FederationEvent> {
+        let event = match serde_json::from_str::<Envelope>(content).ok()?.r#type {
+            MessageType::Chat => {
+                let message: ChatMessage = serde_json::from_str(content).ok()?;
+                FederationEvent::Chat {
+                    client_id: message.client_id,
+                    content: message.content,
+                    timestamp: message.timestamp,
+                }
+            }
+            MessageType::ParticipantJoined => {
+                let message: ParticipantJoinedMessage = serde_json::from_str(content).ok()?;
+                FederationEvent::ParticipantJoined {
+                    client_id: message.client_id,
+                }
+            }
+            MessageType::ParticipantLeft => {
+                let message: ParticipantLeftMessage = serde_json::from_str(content).ok()?;
+                FederationEvent::ParticipantLeft {
+                    client_id: message.client_id,
+                }
+            }
+            MessageType::RoomConnected
+            | MessageType::RoomHistory
+            | MessageType::ServerShutdown
+            | MessageType::BackfillRequest
+            | MessageType::ListRooms
+            | MessageType::Forward
+            | MessageType::Star
+            | MessageType::Unstar
+            | MessageType::StarUpdated
+            | MessageType::Hello
+            | MessageType::HelloAck
+            | MessageType::SetActivity
+            | MessageType::ActivityUpdated
+            | MessageType::SetDisplayName
+            | MessageType::ParticipantRenamed
+            | MessageType::RoomFrozen
+            | MessageType::RoomUnfrozen
+            | MessageType::Disconnected
+            | MessageType::RoomList
+            | MessageType::MessageDeleted
+            | MessageType::TypingStarted
+            | MessageType::TypingStopped
+            | MessageType::SlowDown
+            | MessageType::Heartbeat
+            | MessageType::Welcome
+            | MessageType::JoinPending
+            | MessageType::JoinRequested
+            | MessageType::Poll
+            | MessageType::Vote
+            | MessageType::PollUpdated
+            | MessageType::Error => return None,
+        };
+
+        let client_id = match &event {
+            FederationEvent::Chat { client_id, .. }
+            | FederationEvent::ParticipantJoined { client_id }
+            | FederationEvent::ParticipantLeft { client_id } => client_id,
+            _ => return None,
+        };
+        if self.remote.contains(client_id) {
+            return None;
+        }
+        Some(event)
+    }
+}
+
+#[async_trait]
+impl MessagePusher for FederationPusher {
+    async fn register_client(&self, client_id: ClientId, sender: PusherChannel) {
+        self.inner.register_client(client_id, sender).await;
+    }
+
+    async fn unregister_client(&self, client_id: &ClientId) {
+        self.inner.unregister_client(client_id).await;
+    }
+
+    async fn unregister_connection(&self, client_id: &ClientId, sender: &PusherChannel) -> usize {
+        self.inner.unregister_connection(client_id, sender).await
+    }
+
+    async fn subscribe(&self, client_id: &ClientId, sender: &PusherChannel) -> Option<RoomFeed> {
+        self.inner.subscribe(client_id, sender).await
+    }
+
+    async fn push_to(&self, client_id: &ClientId, content: &str) -> Result<(), MessagePushError> {
+        self.inner.push_to(client_id, content).await
+    }
+
+    async fn broadcast(
+        &self,
+        targets: Vec<ClientId>,
+        content: &str,
+    ) -> Result<(), MessagePushError> {
+        let result = self.inner.broadcast(targets, content).await;
+
+        if let Some(event) = self.local_event(content)
+            && let Err(e) = self.outbound.try_send(event)
+        {
+            tracing::warn!("Dropping event sent to federation peers: {}", e);
+        }
+
+        result
+    }
+
+    async fn ping(&self) -> Result<(), MessagePushError> {
+        self.inner.ping().await?;
+        if self.outbound.is_closed() {
+            return Err(MessagePushError::PushFailed(
+                "federation link has stopped".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NoopPusher;
+
+    #[async_trait]
+    impl MessagePusher for NoopPusher {
+        async fn register_client(&self, _client_id: ClientId, _sender: PusherChannel) {}
+
+        async fn unregister_client(&self, _client_id: &ClientId) {}
+        async fn unregister_connection(
+            &self,
+            _client_id: &ClientId,
+            _sender: &PusherChannel,
+        ) -> usize {
+            0
+        }
+
+        async fn push_to(
+            &self,
+            _client_id: &ClientId,
+            _content: &str,
+        ) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+
+        async fn broadcast(
+            &self,
+            _targets: Vec<ClientId>,
+            _content: &str,
+        ) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+
+        async fn ping(&self) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+    }
+
+    fn pusher(remote: RemoteParticipants) -> (FederationPusher, mpsc::Receiver<FederationEvent>) {
+        let (sender, receiver) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
+        (
+            FederationPusher::new(Arc::new(NoopPusher), sender, remote),
+            receiver,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_sends_local_events() {
+        // テスト項目: ローカル参加者のチャットメッセージと参加通知がピアへの送信キューに積まれる
+        // given (前提条件):
+        let (pusher, mut receiver) = pusher(RemoteParticipants::new());
+        let chat = r#"{"type":"chat","client_id":"alice","content":"hello","timestamp":5}"#;
+        let joined = r#"{"type":"participant-joined","client_id":"bob","connected_at":0}"#;
+
+        // when (操作):
+        pusher.broadcast(vec![], chat).await.unwrap();
+        pusher.broadcast(vec![], joined).await.unwrap();
+
+        // then (期待する結果):
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            FederationEvent::Chat {
+                client_id: "alice".to_string(),
+                content: "hello".to_string(),
+                timestamp: 5,
+            }
+        );
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            FederationEvent::ParticipantJoined {
+                client_id: "bob".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_skips_remote_participants() {
+        // テスト項目: リモート参加者のイベントはピアに送り返されない（ループ防止）
+        // given (前提条件):
+        let remote = RemoteParticipants::new();
+        remote.insert("carol@osaka");
+        let (pusher, mut receiver) = pusher(remote);
+        let chat = r#"{"type":"chat","client_id":"carol@osaka","content":"hi","timestamp":0}"#;
+        let left = r#"{"type":"participant-left","client_id":"carol@osaka","disconnected_at":0}"#;
+
+        // when (操作):
+        pusher.broadcast(vec![], chat).await.unwrap();
+        pusher.broadcast(vec![], left).await.unwrap();
+
+        // then (期待する結果):
+        assert!(receiver.try_recv().is_err());
+    }
+}
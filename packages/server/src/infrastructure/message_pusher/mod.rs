@@ -7,8 +7,28 @@
 //! ## 実装
 //!
 //! - `websocket`: WebSocket を使った実装
+//! - `discord`: チャットメッセージを Discord に中継するデコレーター（`discord` feature）
+//! - `federation`: ルームのイベントをピアサーバに送るデコレーター（`federation` feature）
+//! - `mqtt`: ブロードキャストを MQTT にミラーするデコレーター（`mqtt` feature）
+//! - `webhook`: チャットメッセージをルームの送信 Webhook に送るデコレーター（`webhooks` feature）
 //! - 将来的に: `redis`, `kafka` など
 
+#[cfg(feature = "discord")]
+pub mod discord;
+#[cfg(feature = "federation")]
+pub mod federation;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+#[cfg(feature = "webhooks")]
+pub mod webhook;
 pub mod websocket;
 
-pub use websocket::WebSocketMessagePusher;
+#[cfg(feature = "discord")]
+pub use discord::DiscordRelayPusher;
+#[cfg(feature = "federation")]
+pub use federation::FederationPusher;
+#[cfg(feature = "mqtt")]
+pub use mqtt::MqttMirrorPusher;
+#[cfg(feature = "webhooks")]
+pub use webhook::OutgoingWebhookPusher;
+pub use websocket::{ClientChannels, DEFAULT_CLIENT_SHARDS, PumpOptions, WebSocketMessagePusher};
@@ -0,0 +1,141 @@
+//! MQTT にルームのメッセージをミラーする MessagePusher 実装
+//!
+//! ## 責務
+//!
+//! - 内側の MessagePusher（WebSocket など）への送信をそのまま委譲
+//! - ルーム全体へのブロードキャストを MQTT ブローカーの `chat/rooms/{room_id}` トピックにも publish
+//!
+//! ## 設計ノート
+//!
+//! デコレーターとして実装しているため、UseCase 層は MQTT の存在を意識しません。
+//! 特定クライアント宛ての `push_to`（接続時の参加者一覧など）はルームのメッセージではないため
+//! ミラーしません。
+//!
+//! MQTT への publish 失敗はログに残すのみで、WebSocket クライアントへの配信は妨げません。
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rumqttc::{AsyncClient, QoS};
+
+use crate::domain::{ClientId, MessagePushError, MessagePusher, PusherChannel, RoomFeed, RoomId};
+
+/// ルームのメッセージを publish するトピック
+pub fn room_topic(room_id: &RoomId) -> String {
+    format!("chat/rooms/{}", room_id.as_str())
+}
+
+/// ルームにメッセージを投入するためのコマンドトピック
+pub fn command_topic(room_id: &RoomId) -> String {
+    format!("chat/rooms/{}/commands", room_id.as_str())
+}
+
+/// ブロードキャストを MQTT にミラーする MessagePusher 実装
+///
+/// ## 使用例
+///
+/// ```ignore
+/// let (client, eventloop) = AsyncClient::new(options, 64);
+/// let pusher = MqttMirrorPusher::new(websocket_pusher, client, &room_id);
+/// ```
+pub struct MqttMirrorPusher {
+    /// 委譲先の MessagePusher
+    inner: Arc<dyn MessagePusher>,
+    /// MQTT クライアント（EventLoop は UI 層のブリッジが駆動する）
+    client: AsyncClient,
+    /// ミラー先のトピック
+    topic: String,
+}
+
+impl MqttMirrorPusher {
+    /// 新しい MqttMirrorPusher を作成
+    ///
+    /// # 引数
+    ///
+    /// - `inner`: 委譲先の MessagePusher
+    /// - `client`: MQTT クライアント
+    /// - `room_id`: ミラー対象のルーム ID
+    pub fn new(inner: Arc<dyn MessagePusher>, client: AsyncClient, room_id: &RoomId) -> Self {
+        Self {
+            inner,
+            client,
+            topic: room_topic(room_id),
+        }
+    }
+}
+
+#[async_trait]
+impl MessagePusher for MqttMirrorPusher {
+    async fn register_client(&self, client_id: ClientId, sender: PusherChannel) {
+        self.inner.register_client(client_id, sender).await;
+    }
+
+    async fn unregister_client(&self, client_id: &ClientId) {
+        self.inner.unregister_client(client_id).await;
+    }
+
+    async fn unregister_connection(&self, client_id: &ClientId, sender: &PusherChannel) -> usize {
+        self.inner.unregister_connection(client_id, sender).await
+    }
+
+    async fn subscribe(&self, client_id: &ClientId, sender: &PusherChannel) -> Option<RoomFeed> {
+        self.inner.subscribe(client_id, sender).await
+    }
+
+    async fn push_to(&self, client_id: &ClientId, content: &str) -> Result<(), MessagePushError> {
+        self.inner.push_to(client_id, content).await
+    }
+
+    async fn broadcast(
+        &self,
+        targets: Vec<ClientId>,
+        content: &str,
+    ) -> Result<(), MessagePushError> {
+        let result = self.inner.broadcast(targets, content).await;
+
+        if let Err(e) = self
+            .client
+            .publish(
+                &self.topic,
+                QoS::AtLeastOnce,
+                false,
+                content.as_bytes().to_vec(),
+            )
+            .await
+        {
+            tracing::warn!(
+                "Failed to mirror message to MQTT topic '{}': {}",
+                self.topic,
+                e
+            );
+        }
+
+        result
+    }
+
+    async fn ping(&self) -> Result<(), MessagePushError> {
+        self.inner.ping().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topics() {
+        // テスト項目: ルーム ID からミラー用トピックとコマンドトピックが生成される
+        // given (前提条件):
+        let room_id = RoomId::new("550e8400-e29b-41d4-a716-446655440000".to_string()).unwrap();
+
+        // then (期待する結果):
+        assert_eq!(
+            room_topic(&room_id),
+            "chat/rooms/550e8400-e29b-41d4-a716-446655440000"
+        );
+        assert_eq!(
+            command_topic(&room_id),
+            "chat/rooms/550e8400-e29b-41d4-a716-446655440000/commands"
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! ルームの送信 Webhook にチャットメッセージを送る MessagePusher 実装
+//!
+//! ## 責務
+//!
+//! - 内側の MessagePusher（WebSocket など）への送信をそのまま委譲
+//! - ルームへのチャットメッセージを Slack 互換のペイロードにして送信キューに積む
+//!
+//! ## 設計ノート
+//!
+//! 送信先の URL は API から変更されるため、ここでは URL を知らずにルームの ID と共にキューに積むだけにし、
+//! 実際の送信は UI 層の配送タスクがその時点のルームの送信 Webhook に対して行います。
+//! キューが溢れた場合は新しいメッセージを破棄してログに残します。参加・退出通知は送りません。
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use crate::{
+    domain::{ClientId, MessagePushError, MessagePusher, PusherChannel, RoomFeed, RoomId},
+    infrastructure::dto::{
+        webhook::OutgoingWebhookPayload,
+        websocket::{ChatMessage, MessageType},
+    },
+};
+
+/// 送信 Webhook への送信キューの容量
+pub const OUTBOUND_QUEUE_CAPACITY: usize = 256;
+
+/// 送信 Webhook に送るチャットメッセージ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutgoingWebhookPost {
+    /// メッセージが送られたルーム（このルームの送信 Webhook に送る）
+    pub room_id: RoomId,
+    pub payload: OutgoingWebhookPayload,
+}
+
+/// ルームの送信 Webhook にチャットメッセージを送る MessagePusher 実装
+pub struct OutgoingWebhookPusher {
+    /// 委譲先の MessagePusher
+    inner: Arc<dyn MessagePusher>,
+    /// 送信 Webhook への送信キュー
+    outbound: mpsc::Sender<OutgoingWebhookPost>,
+    /// 対象のルームの ID
+    room_id: RoomId,
+}
+
+impl OutgoingWebhookPusher {
+    /// 新しい OutgoingWebhookPusher を作成
+    ///
+    /// # 引数
+    ///
+    /// - `inner`: 委譲先の MessagePusher
+    /// - `outbound`: 送信 Webhook への送信キュー（受信側は UI 層の配送タスク、全てのルームで共有）
+    /// - `room_id`: 対象のルームの ID
+    pub fn new(
+        inner: Arc<dyn MessagePusher>,
+        outbound: mpsc::Sender<OutgoingWebhookPost>,
+        room_id: &RoomId,
+    ) -> Self {
+        Self {
+            inner,
+            outbound,
+            room_id: room_id.clone(),
+        }
+    }
+
+    /// ブロードキャスト内容から送信 Webhook に送るメッセージを作成（チャット以外は `None`）
+    pub fn outbound_post(&self, content: &str) -> Option<OutgoingWebhookPost> {
+        let message: ChatMessage = serde_json::from_str(content).ok()?;
+        if !matches!(message.r#type, MessageType::Chat) {
+            return None;
+        }
+        Some(OutgoingWebhookPost {
+            room_id: self.room_id.clone(),
+            payload: OutgoingWebhookPayload {
+                text: message.content,
+                username: message.client_id,
+                room_id: self.room_id.as_str().to_string(),
+                seq: message.seq,
+            },
+        })
+    }
+}
+
+#[async_trait]
+impl MessagePusher for OutgoingWebhookPusher {
+    async fn register_client(&self, client_id: ClientId, sender: PusherChannel) {
+        self.inner.register_client(client_id, sender).await;
+    }
+
+    async fn unregister_client(&self, client_id: &ClientId) {
+        self.inner.unregister_client(client_id).await;
+    }
+
+    async fn unregister_connection(&self, client_id: &ClientId, sender: &PusherChannel) -> usize {
+        self.inner.unregister_connection(client_id, sender).await
+    }
+
+    async fn subscribe(&self, client_id: &ClientId, sender: &PusherChannel) -> Option<RoomFeed> {
+        self.inner.subscribe(client_id, sender).await
+    }
+
+    async fn push_to(&self, client_id: &ClientId, content: &str) -> Result<(), MessagePushError> {
+        self.inner.push_to(client_id, content).await
+    }
+
+    async fn broadcast(
+        &self,
+        targets: Vec<ClientId>,
+        content: &str,
+    ) -> Result<(), MessagePushError> {
+        let result = self.inner.broadcast(targets, content).await;
+
+        if let Some(post) = self.outbound_post(content)
+            && let Err(e) = self.outbound.try_send(post)
+        {
+            tracing::warn!("Dropping message for outgoing webhooks: {}", e);
+        }
+
+        result
+    }
+
+    async fn ping(&self) -> Result<(), MessagePushError> {
+        self.inner.ping().await?;
+        if self.outbound.is_closed() {
+            return Err(MessagePushError::PushFailed(
+                "Outgoing webhook delivery has stopped".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{domain::RoomIdFactory, infrastructure::message_pusher::WebSocketMessagePusher};
+
+    #[tokio::test]
+    async fn test_broadcast_queues_chat_messages_only() {
+        // テスト項目: チャットメッセージだけがルームの ID 付きで送信キューに積まれる
+        // given (前提条件):
+        let room_id = RoomIdFactory::generate().unwrap();
+        let (outbound, mut queued) = mpsc::channel(OUTBOUND_QUEUE_CAPACITY);
+        let inner = Arc::new(WebSocketMessagePusher::new(Default::default()));
+        let pusher = OutgoingWebhookPusher::new(inner, outbound, &room_id);
+        let chat = ChatMessage {
+            r#type: MessageType::Chat,
+            client_id: "alice".to_string(),
+            content: "hello".to_string(),
+            timestamp: 1000,
+            seq: None,
+            poll: None,
+            forwarded_from: None,
+        };
+
+        // when (操作):
+        pusher
+            .broadcast(Vec::new(), &chat.to_json_with_seq(7))
+            .await
+            .unwrap();
+        pusher
+            .broadcast(
+                Vec::new(),
+                r#"{"type":"participant-joined","client_id":"bob","connected_at":1000}"#,
+            )
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(
+            queued.try_recv().unwrap(),
+            OutgoingWebhookPost {
+                room_id: room_id.clone(),
+                payload: OutgoingWebhookPayload {
+                    text: "hello".to_string(),
+                    username: "alice".to_string(),
+                    room_id: room_id.as_str().to_string(),
+                    seq: Some(7),
+                },
+            }
+        );
+        assert!(queued.try_recv().is_err());
+    }
+}
@@ -2,36 +2,185 @@
 //!
 //! ## 責務
 //!
-//! - WebSocket の `UnboundedSender` を管理
+//! - クライアントごとの送信キュー（`UnboundedSender`）の作成と管理
 //! - クライアントへのメッセージ送信（push_to, broadcast）
+//! - 送信キューから WebSocket 接続への書き込み（[`WebSocketMessagePusher::pump`]）
 //!
 //! ## 設計ノート
 //!
-//! WebSocket の生成は UI 層（`src/ui/handler/websocket.rs`）で行われます。
-//! この実装は生成された `UnboundedSender` を受け取り、メッセージ送信に使用します。
+//! WebSocket 接続の受付とプロトコルの解釈は UI 層（`src/ui/handler/websocket.rs`）で行われます。
+//! 送信キューの作成・登録・接続への書き込みはこの実装が担当し、UI 層は
+//! 接続を閉じる条件（ルームの移動、再起動など）と、そのときに送るフレームだけを指定します。
 //!
-//! これにより、「WebSocket の生成」と「メッセージの送信」が分離されます：
-//! - UI 層: WebSocket 接続の受付、sender の生成
-//! - Infrastructure 層: sender の管理、メッセージ送信
+//! - UI 層: WebSocket 接続の受付、受信メッセージの解釈、UseCase の呼び出し
+//! - Infrastructure 層: 送信キューの管理、メッセージ送信、重複排除
+//!
+//! バッチ送信（マイクロバッチ）を有効にすると、最初のメッセージから一定時間内に送信キューに
+//! 入ったメッセージを 1 つの JSON 配列のフレーム（`[{...}, {...}]`）にまとめて送ります。
+//! 発言の多いルームでフレームとシステムコールの数を減らす代わりに、配信が最大でその時間だけ
+//! 遅れます。まとめるメッセージが 1 件だけのときは通常どおり 1 つのオブジェクトとして送ります。
+//!
+//! 送信キューの滞留を監視する場合（[`Backpressure`]）、減速の要請と解除（`slow-down`）は
+//! 送信キューを経由せず、滞留しているメッセージより先に接続へ書き込みます。
+//! 接続の死活監視を行う場合は、一定の間隔で Ping フレームも書き込みます。Ping の直前には
+//! サーバーの現在時刻を `heartbeat` として送り、クライアントが時計のずれを見積もれるようにします。
+//! ワイヤーログを記録する場合は、接続に書き込む全てのフレームを記録します。
+//! クライアントが使わない機能のメッセージは、送信キューから取り出す時に変換するか捨てます
+//! （[`Capabilities::downgrade`]）。
+//!
+//! ブロードキャストはルームの全員に同じ `tokio::sync::broadcast` のチャネル（ルームの購読、
+//! [`RoomFeed`]）で 1 回だけ送ります（MessagePusher はルームごとに作成されます）。
+//! 接続は書き込みを始める時に購読に切り替え（[`MessagePusher::subscribe`]）、それ以降の
+//! ブロードキャストをチャネルから受け取ります。送信先から除かれた接続（送信者を除くチャットなど）には
+//! 送る前にメッセージを積んでおき、受け取っても書き込みません。メッセージは `Arc<str>` として共有し、
+//! 接続に書き込む時に初めてフレームにコピーします。送信先の振り分けの間だけロックを取り、
+//! チャネルと送信キューへの送信はロックを解放してから行います。
+//!
+//! 個別の送信（`push_to`）と、購読に切り替える前の接続へのブロードキャストは送信キューに届きます。
+//! 両方に溜まっている場合は送信キューを先に書き込むため、接続時に送るメッセージ（切断中に届いた
+//! メッセージなど）は購読のメッセージより先に届きます。
+//! チャネルの容量（[`ROOM_FEED_CAPACITY`]）を超えて遅れた接続は取りこぼしたメッセージを書き込めないため、
+//! Close コード [`LAGGED_CLOSE_CODE`] で閉じます。クライアントは再接続して `last_seq` から
+//! 取りこぼしを埋め直します。
+//!
+//! 接続中のクライアントの送信キューは client_id のハッシュで複数のシャードに分け、シャードごとに
+//! ロックします（[`ClientChannels`]）。メッセージの送信や接続の登録・解除は対象のクライアントの
+//! シャードだけをロックするため、別のクライアントへの操作と互いに待ちません。
+//! ブロードキャストは送信先をシャードごとにまとめ、各シャードを 1 回ずつロックします。
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::{BuildHasher, RandomState},
+    sync::Arc,
+    time::Duration,
+};
 
 use async_trait::async_trait;
-use tokio::sync::Mutex;
+use axum::extract::ws::{CloseFrame, Message};
+use futures_util::{Sink, SinkExt};
+use serde::Deserialize;
+use tokio::{
+    sync::{
+        Mutex,
+        broadcast::{self, error::RecvError, error::TryRecvError},
+        mpsc,
+    },
+    task::JoinHandle,
+};
+
+use crate::{
+    domain::{ClientId, MessagePushError, MessagePusher, PusherChannel, RoomFeed},
+    infrastructure::{
+        backpressure::Backpressure,
+        dedup::DedupWindow,
+        dto::{
+            websocket::{HeartbeatMessage, MessageType},
+            wire_log::WireDirection,
+        },
+        protocol::{Capabilities, Feature},
+        wire_log::WireTap,
+    },
+};
+use engawa_shared::time::get_jst_timestamp;
+
+/// バッチ送信で 1 つのフレームにまとめるメッセージの最大数
+pub const MAX_BATCH_MESSAGES: usize = 64;
+
+/// ルームの購読のチャネルに溜められるメッセージの数（超えて遅れた接続は閉じる）
+pub const ROOM_FEED_CAPACITY: usize = 1024;
+
+/// ルームの購読に追いつけなかった接続を閉じる Close コード
+pub const LAGGED_CLOSE_CODE: u16 = 4016;
+
+/// ルームの購読に追いつけなかった接続を閉じる理由
+pub const LAGGED_REASON: &str = "lagged";
+
+/// 送信キューから WebSocket 接続への書き込みの設定
+#[derive(Default)]
+pub struct PumpOptions {
+    /// バッチ送信でメッセージをまとめる時間（`None` の場合は 1 件ずつ送る）
+    pub batch_window: Option<Duration>,
+    /// 送信キューの滞留に応じて減速を要請する場合はその状態
+    pub backpressure: Option<Backpressure>,
+    /// Ping フレームと `heartbeat` を送る間隔（送らない場合は `None`）
+    pub ping_interval: Option<Duration>,
+    /// 書き込んだフレームを記録するワイヤーログ（記録しない場合は `None`）
+    pub wire_tap: Option<WireTap>,
+    /// 接続で使うプロトコルの機能（使わない機能のメッセージは変換するか送らない）
+    pub capabilities: Capabilities,
+    /// ルームの購読（購読に切り替えていない場合は `None`）
+    pub feed: Option<RoomFeed>,
+}
+
+/// [`ClientChannels`] の既定のシャードの数
+pub const DEFAULT_CLIENT_SHARDS: usize = 16;
 
-use crate::domain::{ClientId, MessagePushError, MessagePusher, PusherChannel};
+/// 送信先から除いたメッセージを積む先（[`RoomFeed::skipped`] と共有する）
+type SkipList = Arc<std::sync::Mutex<VecDeque<Arc<str>>>>;
+
+/// クライアントの接続
+#[derive(Clone)]
+struct Connection {
+    /// 接続の送信キュー
+    channel: PusherChannel,
+    /// ルームの購読に切り替えた場合、送信先から除いたメッセージを積む先
+    skipped: Option<SkipList>,
+}
+
+/// 1 つのシャードの sender のマップ（Key: client_id、Value: 接続ごとの送信キュー）
+type ClientShard = Mutex<HashMap<String, Vec<Connection>>>;
+
+/// 接続中のクライアントの sender のマップ
+///
+/// client_id のハッシュでシャードに分け、シャードごとにロックします。
+/// 複製したものは同じマップを共有します。
+#[derive(Clone)]
+pub struct ClientChannels {
+    shards: Arc<[ClientShard]>,
+    hasher: RandomState,
+}
+
+impl ClientChannels {
+    /// 指定した数のシャードに分けた空のマップを作成（0 の場合は 1）
+    pub fn with_shards(shards: usize) -> Self {
+        Self {
+            shards: (0..shards.max(1)).map(|_| ClientShard::default()).collect(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// シャードの数
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// クライアントのシャードの番号
+    fn shard_index(&self, client_id: &str) -> usize {
+        (self.hasher.hash_one(client_id) % self.shards.len() as u64) as usize
+    }
+
+    /// クライアントのシャード
+    fn shard(&self, client_id: &str) -> &ClientShard {
+        &self.shards[self.shard_index(client_id)]
+    }
+}
+
+impl Default for ClientChannels {
+    fn default() -> Self {
+        Self::with_shards(DEFAULT_CLIENT_SHARDS)
+    }
+}
 
 /// WebSocket を使った MessagePusher 実装
 ///
 /// ## フィールド
 ///
-/// - `clients`: 接続中のクライアントと対応する WebSocket sender のマップ
+/// - `clients`: 接続中のクライアントと対応する WebSocket sender のマップ（クライアントごとに接続の数だけ）
 ///
 /// ## 使用例
 ///
 /// ```ignore
-/// let clients = Arc::new(Mutex::new(HashMap::new()));
-/// let pusher = WebSocketMessagePusher::new(clients.clone());
+/// let pusher = WebSocketMessagePusher::new(ClientChannels::default());
 ///
 /// // クライアントに送信
 /// pusher.push_to(&client_id, "{\"type\":\"chat\",\"content\":\"Hello\"}").await?;
@@ -40,8 +189,13 @@ pub struct WebSocketMessagePusher {
     /// 接続中のクライアントの WebSocket sender
     ///
     /// Key: client_id (String)
-    /// Value: PusherChannel
-    clients: Arc<Mutex<HashMap<String, PusherChannel>>>,
+    /// Value: クライアントの接続ごとの PusherChannel
+    clients: ClientChannels,
+    /// ルームの全員に配るメッセージのチャネル（購読に切り替えた接続が受け取る）
+    feed: broadcast::Sender<Arc<str>>,
+    /// 購読への切り替えと、ブロードキャストの送信先の振り分け・チャネルへの送信を排他する
+    /// （振り分けた後に切り替えた接続に、チャネルと送信キューの両方から届かないようにする）
+    feed_lock: Mutex<()>,
 }
 
 impl WebSocketMessagePusher {
@@ -55,24 +209,280 @@ impl WebSocketMessagePusher {
     ///
     /// `clients` は Repository と共有される可能性があります。
     /// これは一時的な設計であり、将来的には MessagePusher が独立して管理します。
-    pub fn new(clients: Arc<Mutex<HashMap<String, PusherChannel>>>) -> Self {
-        Self { clients }
+    pub fn new(clients: ClientChannels) -> Self {
+        let (feed, _) = broadcast::channel(ROOM_FEED_CAPACITY);
+        Self {
+            clients,
+            feed,
+            feed_lock: Mutex::new(()),
+        }
+    }
+
+    /// クライアントの送信キューを作成
+    ///
+    /// sender は `register_client` で登録し、receiver は [`Self::pump`] に渡します。
+    pub fn channel() -> (PusherChannel, mpsc::UnboundedReceiver<Arc<str>>) {
+        mpsc::unbounded_channel()
+    }
+
+    /// 送信キューとルームの購読のメッセージを WebSocket 接続に書き込むタスクを起動
+    ///
+    /// 配信済みのシーケンス番号のメッセージ（再接続時のバックフィルとの重複）は送信しません。
+    /// 送信キューが閉じる、接続への書き込みに失敗する、または `stop` が完了すると終了し、
+    /// `stop` が完了した場合はキューに残っているメッセージと `stop` が返したフレーム
+    /// （終了の通知や Close フレーム）を送信してから終了します。
+    /// ルームの購読が遅れてメッセージを取りこぼした場合は、Close フレーム
+    /// （[`LAGGED_CLOSE_CODE`]）を送信して終了します。
+    ///
+    /// # 引数
+    ///
+    /// - `rx`: クライアントの送信キュー
+    /// - `sink`: WebSocket 接続の送信側
+    /// - `dedup`: 配信済みのシーケンス番号
+    /// - `stop`: 接続を閉じる条件。完了時に最後に送るフレームを返す
+    /// - `queue_depth`: メッセージを取り出すたびに、残りの件数とおおよそのバイト数で呼ばれる
+    /// - `options`: バッチ送信・減速の要請・Ping・ワイヤーログ・プロトコルの機能・ルームの購読の設定
+    pub fn pump<S>(
+        mut rx: mpsc::UnboundedReceiver<Arc<str>>,
+        sink: S,
+        mut dedup: DedupWindow,
+        stop: impl Future<Output = Vec<Message>> + Send + 'static,
+        queue_depth: impl Fn(usize, usize) + Send + 'static,
+        options: PumpOptions,
+    ) -> JoinHandle<()>
+    where
+        S: Sink<Message> + Unpin + Send + 'static,
+        S::Error: Send,
+    {
+        engawa_shared::task::spawn("ws-pusher", async move {
+            // メッセージサイズの移動平均（キューに残っているバイト数の見積もりに使う）
+            let mut average_len = 0;
+            let PumpOptions {
+                batch_window,
+                mut backpressure,
+                ping_interval,
+                wire_tap,
+                capabilities,
+                mut feed,
+            } = options;
+            // 取り出したメッセージのうち配信済みでないもの
+            let mut take = move |batch: &mut Vec<Arc<str>>, msg: Arc<str>, remaining: usize| {
+                average_len = (average_len * 7 + msg.len()) / 8;
+                queue_depth(remaining, remaining * average_len);
+                let Some(msg) = capabilities.downgrade(msg) else {
+                    return;
+                };
+                match sequence_number(&msg) {
+                    Some(seq) if !dedup.insert(seq) => {
+                        tracing::debug!("Skipping message {} already delivered", seq);
+                    }
+                    _ => batch.push(msg),
+                }
+            };
+            // 書き込むフレームはワイヤーログに記録してから接続に渡す
+            let mut sink = sink.with(move |frame: Message| {
+                if let Some(tap) = &wire_tap {
+                    tap.record(WireDirection::Out, &frame);
+                }
+                std::future::ready(Ok::<_, S::Error>(frame))
+            });
+            let mut ping = ping_interval.map(|period| {
+                let mut ping =
+                    tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+                ping.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                ping
+            });
+            tokio::pin!(stop);
+            loop {
+                tokio::select! {
+                    next = next_message(&mut rx, &mut feed) => {
+                        let mut batch = Vec::new();
+                        let mut lagged = None;
+                        match next {
+                            Next::Message(msg) => take(&mut batch, msg, queued(&rx, &feed)),
+                            Next::Lagged(missed) => lagged = Some(missed),
+                            Next::Closed => break,
+                        }
+                        // 一定時間内に届いたメッセージをまとめる
+                        if let Some(window) = batch_window
+                            && lagged.is_none()
+                        {
+                            let deadline = tokio::time::Instant::now() + window;
+                            while batch.len() < MAX_BATCH_MESSAGES {
+                                let next = next_message(&mut rx, &mut feed);
+                                match tokio::time::timeout_at(deadline, next).await {
+                                    Ok(Next::Message(msg)) => {
+                                        take(&mut batch, msg, queued(&rx, &feed))
+                                    }
+                                    Ok(Next::Lagged(missed)) => {
+                                        lagged = Some(missed);
+                                        break;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                        }
+                        // 減速の要請・解除は滞留しているメッセージより先に送る
+                        if let Some(advice) =
+                            backpressure.as_mut().and_then(|b| b.observe(queued(&rx, &feed)))
+                            && sink.send(Message::Text(advice.into())).await.is_err()
+                        {
+                            break;
+                        }
+                        let frame = match batch.len() {
+                            0 => None,
+                            1 => Some(batch.swap_remove(0).as_ref().into()),
+                            _ => Some(format!("[{}]", batch.join(",")).into()),
+                        };
+                        if let Some(frame) = frame
+                            && sink.send(Message::Text(frame)).await.is_err()
+                        {
+                            break;
+                        }
+                        // 取りこぼしたメッセージは書き込めないため、再接続して埋め直してもらう
+                        if let Some(missed) = lagged {
+                            tracing::warn!(
+                                "Closing a connection that missed {} messages of its room",
+                                missed
+                            );
+                            let _ = sink.send(lagged_frame()).await;
+                            break;
+                        }
+                    }
+                    _ = async { ping.as_mut().unwrap().tick().await }, if ping.is_some() => {
+                        if capabilities.supports(Feature::Heartbeat)
+                            && sink.send(heartbeat_frame(get_jst_timestamp())).await.is_err()
+                        {
+                            break;
+                        }
+                        if sink.send(Message::Ping(Default::default())).await.is_err() {
+                            break;
+                        }
+                    }
+                    frames = &mut stop => {
+                        // キューと購読に残っているブロードキャストを送ってから最後のフレームを送る
+                        let mut batch = Vec::new();
+                        while let Ok(msg) = rx.try_recv() {
+                            take(&mut batch, msg, queued(&rx, &feed));
+                        }
+                        if let Some(room) = &mut feed {
+                            loop {
+                                match room.receiver.try_recv() {
+                                    Ok(msg) if room.skips(&msg) => {}
+                                    Ok(msg) => take(&mut batch, msg, room.receiver.len()),
+                                    Err(TryRecvError::Lagged(_)) => {}
+                                    Err(_) => break,
+                                }
+                            }
+                        }
+                        let queued = batch.into_iter().map(|msg| Message::Text(msg.as_ref().into()));
+                        for frame in queued.chain(frames) {
+                            if sink.send(frame).await.is_err() {
+                                break;
+                            }
+                        }
+                        break;
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// 送信キューとルームの購読から次に受け取ったもの
+enum Next {
+    /// 書き込むメッセージ
+    Message(Arc<str>),
+    /// ルームの購読が取りこぼした（受け取る前に上書きされた）メッセージの数
+    Lagged(u64),
+    /// 送信キューが閉じた
+    Closed,
+}
+
+/// 送信キューとルームの購読から次に書き込むメッセージを受け取る
+///
+/// 両方に溜まっている場合は送信キューを先にする。送信先から除かれた購読のメッセージは読み捨てる。
+async fn next_message(
+    rx: &mut mpsc::UnboundedReceiver<Arc<str>>,
+    feed: &mut Option<RoomFeed>,
+) -> Next {
+    loop {
+        let Some(room) = feed.as_mut() else {
+            return rx.recv().await.map_or(Next::Closed, Next::Message);
+        };
+        let received = tokio::select! {
+            biased;
+            msg = rx.recv() => return msg.map_or(Next::Closed, Next::Message),
+            received = room.receiver.recv() => received,
+        };
+        match received {
+            Ok(msg) if room.skips(&msg) => {}
+            Ok(msg) => return Next::Message(msg),
+            Err(RecvError::Lagged(missed)) => return Next::Lagged(missed),
+            // ルームの MessagePusher が破棄された（以降は送信キューのみ）
+            Err(RecvError::Closed) => *feed = None,
+        }
+    }
+}
+
+/// 送信キューとルームの購読に残っているメッセージの数
+fn queued(rx: &mpsc::UnboundedReceiver<Arc<str>>, feed: &Option<RoomFeed>) -> usize {
+    rx.len() + feed.as_ref().map_or(0, |room| room.receiver.len())
+}
+
+/// ルームの購読に追いつけなかった接続に送る Close フレーム
+fn lagged_frame() -> Message {
+    Message::Close(Some(CloseFrame {
+        code: LAGGED_CLOSE_CODE,
+        reason: LAGGED_REASON.into(),
+    }))
+}
+
+/// サーバーの時刻 `server_time` を伝える `heartbeat` のフレーム
+fn heartbeat_frame(server_time: i64) -> Message {
+    let heartbeat = HeartbeatMessage {
+        r#type: MessageType::Heartbeat,
+        server_time,
+    };
+    Message::Text(serde_json::to_string(&heartbeat).unwrap().into())
+}
+
+/// 送信するメッセージのシーケンス番号（チャットメッセージと投票のみ）
+///
+/// 他のメッセージのシーケンス番号を持つ通知（投票の集計・メッセージの削除）は重複配信として
+/// 扱わない。
+fn sequence_number(msg: &str) -> Option<u64> {
+    #[derive(Deserialize)]
+    struct Sequenced {
+        #[serde(default)]
+        r#type: Option<MessageType>,
+        seq: Option<u64>,
+    }
+    let sequenced = serde_json::from_str::<Sequenced>(msg).ok()?;
+    match sequenced.r#type {
+        Some(MessageType::PollUpdated | MessageType::MessageDeleted) => None,
+        _ => sequenced.seq,
     }
 }
 
 #[async_trait]
 impl MessagePusher for WebSocketMessagePusher {
     async fn register_client(&self, client_id: ClientId, sender: PusherChannel) {
-        let mut clients = self.clients.lock().await;
-        clients.insert(client_id.as_str().to_string(), sender);
+        let mut clients = self.clients.shard(client_id.as_str()).lock().await;
+        let connections = clients.entry(client_id.as_str().to_string()).or_default();
+        connections.push(Connection {
+            channel: sender,
+            skipped: None,
+        });
         tracing::debug!(
-            "Client '{}' registered to MessagePusher",
-            client_id.as_str()
+            "Client '{}' registered to MessagePusher ({} connections)",
+            client_id.as_str(),
+            connections.len()
         );
     }
 
     async fn unregister_client(&self, client_id: &ClientId) {
-        let mut clients = self.clients.lock().await;
+        let mut clients = self.clients.shard(client_id.as_str()).lock().await;
         clients.remove(client_id.as_str());
         tracing::debug!(
             "Client '{}' unregistered from MessagePusher",
@@ -80,20 +490,50 @@ impl MessagePusher for WebSocketMessagePusher {
         );
     }
 
+    async fn unregister_connection(&self, client_id: &ClientId, sender: &PusherChannel) -> usize {
+        let mut clients = self.clients.shard(client_id.as_str()).lock().await;
+        let Some(connections) = clients.get_mut(client_id.as_str()) else {
+            return 0;
+        };
+        connections.retain(|connection| !connection.channel.same_channel(sender));
+        let remaining = connections.len();
+        if remaining == 0 {
+            clients.remove(client_id.as_str());
+        }
+        tracing::debug!(
+            "Connection of client '{}' unregistered from MessagePusher ({} remaining)",
+            client_id.as_str(),
+            remaining
+        );
+        remaining
+    }
+
     async fn push_to(&self, client_id: &ClientId, content: &str) -> Result<(), MessagePushError> {
-        let clients = self.clients.lock().await;
+        let shard = self.clients.shard(client_id.as_str());
+        let connections = match shard.lock().await.get(client_id.as_str()) {
+            Some(connections) => connections.clone(),
+            None => {
+                return Err(MessagePushError::ClientNotFound(
+                    client_id.as_str().to_string(),
+                ));
+            }
+        };
 
-        if let Some(sender) = clients.get(client_id.as_str()) {
-            sender
-                .send(content.to_string())
-                .map_err(|e| MessagePushError::PushFailed(e.to_string()))?;
+        // 送信できた接続が 1 つでもあれば成功とする
+        let content: Arc<str> = Arc::from(content);
+        let mut result = Ok(());
+        let mut delivered = false;
+        for connection in connections {
+            match connection.channel.send(content.clone()) {
+                Ok(()) => delivered = true,
+                Err(e) => result = Err(MessagePushError::PushFailed(e.to_string())),
+            }
+        }
+        if delivered {
             tracing::debug!("Pushed message to client '{}'", client_id.as_str());
-            Ok(())
-        } else {
-            Err(MessagePushError::ClientNotFound(
-                client_id.as_str().to_string(),
-            ))
+            return Ok(());
         }
+        result
     }
 
     async fn broadcast(
@@ -101,30 +541,115 @@ impl MessagePusher for WebSocketMessagePusher {
         targets: Vec<ClientId>,
         content: &str,
     ) -> Result<(), MessagePushError> {
-        let clients = self.clients.lock().await;
-
+        // 送信先をシャードごとにまとめ、ロックの中では送信先を振り分けるだけにする
+        let mut by_shard: Vec<Vec<ClientId>> = vec![Vec::new(); self.clients.shard_count()];
         for target in targets {
-            if let Some(sender) = clients.get(target.as_str()) {
-                // ブロードキャストでは一部の送信失敗を許容
-                if let Err(e) = sender.send(content.to_string()) {
+            by_shard[self.clients.shard_index(target.as_str())].push(target);
+        }
+        let _feed = self.feed_lock.lock().await;
+        // 購読に切り替えていない接続には送信キューで送る
+        let mut queued: Vec<(ClientId, PusherChannel)> = Vec::new();
+        let mut subscribed: Vec<(ClientId, PusherChannel)> = Vec::new();
+        for (shard, targets) in self.clients.shards.iter().zip(by_shard) {
+            if targets.is_empty() {
+                continue;
+            }
+            let clients = shard.lock().await;
+            for target in targets {
+                let Some(connections) = clients.get(target.as_str()) else {
                     tracing::warn!(
-                        "Failed to push message to client '{}': {}",
-                        target.as_str(),
-                        e
+                        "Client '{}' not found during broadcast, skipping",
+                        target.as_str()
                     );
-                } else {
-                    tracing::debug!("Broadcasted message to client '{}'", target.as_str());
+                    continue;
+                };
+                for connection in connections {
+                    let entry = (target.clone(), connection.channel.clone());
+                    match connection.skipped {
+                        Some(_) => subscribed.push(entry),
+                        None => queued.push(entry),
+                    }
                 }
-            } else {
+            }
+        }
+
+        // 全ての送信先で同じメッセージを共有する
+        let content: Arc<str> = Arc::from(content);
+        // 購読している接続の大半に送る場合はチャネルで 1 回だけ送り、送信先から除く接続には
+        // メッセージを積んでおく（少数に送る場合は除く接続の方が多いため送信キューで送る）
+        let excluded = self.feed.receiver_count().saturating_sub(subscribed.len());
+        if !subscribed.is_empty() && excluded <= subscribed.len() {
+            let wanted: HashSet<&str> = queued
+                .iter()
+                .chain(&subscribed)
+                .map(|(target, _)| target.as_str())
+                .collect();
+            for shard in self.clients.shards.iter() {
+                let clients = shard.lock().await;
+                let skipped = clients
+                    .iter()
+                    .filter(|(client_id, _)| !wanted.contains(client_id.as_str()))
+                    .flat_map(|(_, connections)| connections)
+                    .filter_map(|connection| connection.skipped.as_ref());
+                for skipped in skipped {
+                    skipped.lock().unwrap().push_back(content.clone());
+                }
+            }
+            // 受け取る接続が全て切断済みの場合のエラーは無視する
+            let _ = self.feed.send(content.clone());
+            tracing::debug!(
+                "Broadcasted message to {} subscribed connections",
+                subscribed.len()
+            );
+        } else {
+            queued.append(&mut subscribed);
+        }
+        drop(_feed);
+
+        for (target, sender) in queued {
+            // ブロードキャストでは一部の送信失敗を許容
+            if let Err(e) = sender.send(content.clone()) {
                 tracing::warn!(
-                    "Client '{}' not found during broadcast, skipping",
-                    target.as_str()
+                    "Failed to push message to client '{}': {}",
+                    target.as_str(),
+                    e
                 );
+            } else {
+                tracing::debug!("Broadcasted message to client '{}'", target.as_str());
             }
         }
 
         Ok(())
     }
+
+    async fn subscribe(&self, client_id: &ClientId, sender: &PusherChannel) -> Option<RoomFeed> {
+        let _feed = self.feed_lock.lock().await;
+        let mut clients = self.clients.shard(client_id.as_str()).lock().await;
+        let connection = clients
+            .get_mut(client_id.as_str())?
+            .iter_mut()
+            .find(|connection| connection.channel.same_channel(sender))?;
+        let skipped = connection
+            .skipped
+            .get_or_insert_with(Default::default)
+            .clone();
+        tracing::debug!(
+            "Connection of client '{}' subscribed to its room",
+            client_id.as_str()
+        );
+        Some(RoomFeed {
+            receiver: self.feed.subscribe(),
+            skipped,
+        })
+    }
+
+    // 送信先のマップの全てのシャードをロックできれば応答可能
+    async fn ping(&self) -> Result<(), MessagePushError> {
+        for shard in self.clients.shards.iter() {
+            let _clients = shard.lock().await;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -151,29 +676,26 @@ mod tests {
     // 2. push_to の失敗ケース（クライアントが存在しない）
     // 3. broadcast の成功ケース（複数クライアント）
     // 4. broadcast の部分失敗ケース（一部のクライアントが存在しない）
+    // 5. 同じクライアントの複数の接続への送信と、接続ごとの登録解除
+    // 5'. シャードの数に関わらない配信
+    // 6. pump による接続への書き込み（重複排除、終了時のフレーム）
+    // 7. Ping の直前に送るサーバーの時刻（heartbeat）
+    // 8. ルームの購読によるブロードキャストと、追いつけなかった接続の切断
     // ========================================
 
-    fn create_test_pusher() -> (
-        WebSocketMessagePusher,
-        Arc<Mutex<HashMap<String, PusherChannel>>>,
-    ) {
-        let clients = Arc::new(Mutex::new(HashMap::new()));
-        let pusher = WebSocketMessagePusher::new(clients.clone());
-        (pusher, clients)
+    fn create_test_pusher() -> WebSocketMessagePusher {
+        WebSocketMessagePusher::new(ClientChannels::default())
     }
 
     #[tokio::test]
     async fn test_push_to_success() {
         // テスト項目: 特定のクライアントにメッセージを送信できる
         // given (前提条件):
-        let (pusher, clients) = create_test_pusher();
+        let pusher = create_test_pusher();
         let (tx, mut rx) = mpsc::unbounded_channel();
         let client_id = ClientId::new("alice".to_string()).unwrap();
 
-        {
-            let mut clients_lock = clients.lock().await;
-            clients_lock.insert(client_id.as_str().to_string(), tx);
-        }
+        pusher.register_client(client_id.clone(), tx).await;
 
         // when (操作):
         let result = pusher.push_to(&client_id, "Hello").await;
@@ -181,14 +703,14 @@ mod tests {
         // then (期待する結果):
         assert!(result.is_ok());
         let received = rx.recv().await;
-        assert_eq!(received, Some("Hello".to_string()));
+        assert_eq!(received.as_deref(), Some("Hello"));
     }
 
     #[tokio::test]
     async fn test_push_to_client_not_found() {
         // テスト項目: 存在しないクライアントへの送信はエラーを返す
         // given (前提条件):
-        let (pusher, _clients) = create_test_pusher();
+        let pusher = create_test_pusher();
         let client_id = ClientId::new("nonexistent".to_string()).unwrap();
 
         // when (操作):
@@ -206,17 +728,14 @@ mod tests {
     async fn test_broadcast_success() {
         // テスト項目: 複数のクライアントにメッセージをブロードキャストできる
         // given (前提条件):
-        let (pusher, clients) = create_test_pusher();
+        let pusher = create_test_pusher();
         let (tx1, mut rx1) = mpsc::unbounded_channel();
         let (tx2, mut rx2) = mpsc::unbounded_channel();
         let alice = ClientId::new("alice".to_string()).unwrap();
         let bob = ClientId::new("bob".to_string()).unwrap();
 
-        {
-            let mut clients_lock = clients.lock().await;
-            clients_lock.insert(alice.as_str().to_string(), tx1);
-            clients_lock.insert(bob.as_str().to_string(), tx2);
-        }
+        pusher.register_client(alice.clone(), tx1).await;
+        pusher.register_client(bob.clone(), tx2).await;
 
         // when (操作):
         let targets = vec![alice, bob];
@@ -224,23 +743,20 @@ mod tests {
 
         // then (期待する結果):
         assert!(result.is_ok());
-        assert_eq!(rx1.recv().await, Some("Broadcast message".to_string()));
-        assert_eq!(rx2.recv().await, Some("Broadcast message".to_string()));
+        assert_eq!(rx1.recv().await.as_deref(), Some("Broadcast message"));
+        assert_eq!(rx2.recv().await.as_deref(), Some("Broadcast message"));
     }
 
     #[tokio::test]
     async fn test_broadcast_partial_failure() {
         // テスト項目: ブロードキャスト時、一部のクライアントが存在しなくても成功する
         // given (前提条件):
-        let (pusher, clients) = create_test_pusher();
+        let pusher = create_test_pusher();
         let (tx1, mut rx1) = mpsc::unbounded_channel();
         let alice = ClientId::new("alice".to_string()).unwrap();
         let nonexistent = ClientId::new("nonexistent".to_string()).unwrap();
 
-        {
-            let mut clients_lock = clients.lock().await;
-            clients_lock.insert(alice.as_str().to_string(), tx1);
-        }
+        pusher.register_client(alice.clone(), tx1).await;
 
         // when (操作):
         let targets = vec![alice.clone(), nonexistent];
@@ -248,14 +764,14 @@ mod tests {
 
         // then (期待する結果):
         assert!(result.is_ok()); // ブロードキャストは部分失敗を許容
-        assert_eq!(rx1.recv().await, Some("Broadcast message".to_string()));
+        assert_eq!(rx1.recv().await.as_deref(), Some("Broadcast message"));
     }
 
     #[tokio::test]
     async fn test_broadcast_empty_targets() {
         // テスト項目: 空のターゲットリストでもエラーにならない
         // given (前提条件):
-        let (pusher, _clients) = create_test_pusher();
+        let pusher = create_test_pusher();
 
         // when (操作):
         let result = pusher.broadcast(vec![], "Message").await;
@@ -263,4 +779,396 @@ mod tests {
         // then (期待する結果):
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_broadcast_across_shards() {
+        // テスト項目: シャードの数（0 は 1 として扱う）に関わらず、全ての送信先に 1 回ずつ配信される
+        for shards in [0, 1, 4, DEFAULT_CLIENT_SHARDS] {
+            // given (前提条件):
+            let pusher = WebSocketMessagePusher::new(ClientChannels::with_shards(shards));
+            let mut targets = Vec::new();
+            let mut receivers = Vec::new();
+            for i in 0..20 {
+                let client_id = ClientId::new(format!("client-{}", i)).unwrap();
+                let (tx, rx) = mpsc::unbounded_channel();
+                pusher.register_client(client_id.clone(), tx).await;
+                targets.push(client_id);
+                receivers.push(rx);
+            }
+
+            // when (操作):
+            pusher.broadcast(targets, "Hello").await.unwrap();
+
+            // then (期待する結果):
+            for rx in &mut receivers {
+                assert_eq!(rx.try_recv().unwrap().as_ref(), "Hello");
+                assert!(rx.try_recv().is_err());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multiple_connections_of_client() {
+        // テスト項目: 同じクライアントの全ての接続に送信され、閉じた接続だけを登録解除できる
+        // given (前提条件):
+        let pusher = create_test_pusher();
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let (phone, mut phone_rx) = mpsc::unbounded_channel();
+        let (laptop, mut laptop_rx) = mpsc::unbounded_channel();
+        pusher.register_client(alice.clone(), phone.clone()).await;
+        pusher.register_client(alice.clone(), laptop.clone()).await;
+
+        // when (操作):
+        pusher
+            .broadcast(vec![alice.clone()], "Hello")
+            .await
+            .unwrap();
+        let remaining = pusher.unregister_connection(&alice, &phone).await;
+        pusher.push_to(&alice, "Still here").await.unwrap();
+        let last = pusher.unregister_connection(&alice, &laptop).await;
+
+        // then (期待する結果):
+        assert_eq!(phone_rx.recv().await.unwrap().as_ref(), "Hello");
+        assert_eq!(laptop_rx.recv().await.unwrap().as_ref(), "Hello");
+        assert_eq!(remaining, 1);
+        assert_eq!(laptop_rx.recv().await.unwrap().as_ref(), "Still here");
+        assert!(phone_rx.try_recv().is_err());
+        assert_eq!(last, 0);
+        assert!(matches!(
+            pusher.push_to(&alice, "Gone").await,
+            Err(MessagePushError::ClientNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_pump_skips_delivered_messages_and_sends_stop_frames() {
+        // テスト項目: 配信済みのシーケンス番号は送信せず、stop の完了時にそのフレームを送って終了する
+        // given (前提条件):
+        let (tx, rx) = WebSocketMessagePusher::channel();
+        let (sink_tx, mut sink_rx) = mpsc::unbounded_channel::<Message>();
+        let sink = Box::pin(futures_util::sink::unfold(
+            sink_tx,
+            |sink_tx, message: Message| async move {
+                sink_tx.send(message).map_err(|_| ())?;
+                Ok::<_, ()>(sink_tx)
+            },
+        ));
+        let mut dedup = DedupWindow::new(16);
+        dedup.resume_from(1);
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let stop = async move {
+            let _ = stop_rx.await;
+            vec![Message::Text("bye".into())]
+        };
+
+        // when (操作):
+        let pump =
+            WebSocketMessagePusher::pump(rx, sink, dedup, stop, |_, _| {}, PumpOptions::default());
+        tx.send(r#"{"seq":1}"#.into()).unwrap();
+        tx.send(r#"{"seq":2}"#.into()).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        stop_tx.send(()).unwrap();
+        pump.await.unwrap();
+
+        // then (期待する結果):
+        let mut sent = Vec::new();
+        while let Ok(Message::Text(text)) = sink_rx.try_recv() {
+            sent.push(text.to_string());
+        }
+        assert_eq!(sent, vec![r#"{"seq":2}"#.to_string(), "bye".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_pump_flushes_queued_messages_before_stop_frames() {
+        // テスト項目: stop の完了時にキューに残っているメッセージを送ってから stop のフレームを送る
+        // given (前提条件):
+        let (tx, rx) = WebSocketMessagePusher::channel();
+        let (sink_tx, mut sink_rx) = mpsc::unbounded_channel::<Message>();
+        let sink = Box::pin(futures_util::sink::unfold(
+            sink_tx,
+            |sink_tx, message: Message| async move {
+                sink_tx.send(message).map_err(|_| ())?;
+                Ok::<_, ()>(sink_tx)
+            },
+        ));
+        for seq in 1..=3 {
+            tx.send(format!(r#"{{"seq":{}}}"#, seq).into()).unwrap();
+        }
+        let stop = async { vec![Message::Text("bye".into())] };
+
+        // when (操作):
+        let pump = WebSocketMessagePusher::pump(
+            rx,
+            sink,
+            DedupWindow::new(16),
+            stop,
+            |_, _| {},
+            PumpOptions::default(),
+        );
+        pump.await.unwrap();
+
+        // then (期待する結果):
+        let mut sent = Vec::new();
+        while let Ok(Message::Text(text)) = sink_rx.try_recv() {
+            sent.push(text.to_string());
+        }
+        assert_eq!(
+            sent,
+            vec![
+                r#"{"seq":1}"#.to_string(),
+                r#"{"seq":2}"#.to_string(),
+                r#"{"seq":3}"#.to_string(),
+                "bye".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sequence_number_skips_notices_about_other_messages() {
+        // テスト項目: チャットメッセージのシーケンス番号は取得され、投票の集計と削除の通知のシーケンス番号は取得されない
+        // when (操作) / then (期待する結果):
+        assert_eq!(sequence_number(r#"{"type":"chat","seq":3}"#), Some(3));
+        assert_eq!(sequence_number(r#"{"type":"poll","seq":3}"#), Some(3));
+        assert_eq!(
+            sequence_number(r#"{"type":"poll-updated","seq":3,"options":[]}"#),
+            None
+        );
+        assert_eq!(
+            sequence_number(r#"{"type":"message-deleted","seq":3}"#),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pump_batches_messages_within_window() {
+        // テスト項目: バッチ送信が有効な場合、時間内に届いたメッセージを 1 つの JSON 配列のフレームにまとめる
+        // given (前提条件):
+        let (tx, rx) = WebSocketMessagePusher::channel();
+        let (sink_tx, mut sink_rx) = mpsc::unbounded_channel::<Message>();
+        let sink = Box::pin(futures_util::sink::unfold(
+            sink_tx,
+            |sink_tx, message: Message| async move {
+                sink_tx.send(message).map_err(|_| ())?;
+                Ok::<_, ()>(sink_tx)
+            },
+        ));
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let stop = async move {
+            let _ = stop_rx.await;
+            Vec::new()
+        };
+
+        // when (操作):
+        let options = PumpOptions {
+            batch_window: Some(std::time::Duration::from_millis(20)),
+            ..Default::default()
+        };
+        let pump =
+            WebSocketMessagePusher::pump(rx, sink, DedupWindow::new(16), stop, |_, _| {}, options);
+        tx.send(r#"{"seq":1}"#.into()).unwrap();
+        tx.send(r#"{"seq":2}"#.into()).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        tx.send(r#"{"seq":3}"#.into()).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        stop_tx.send(()).unwrap();
+        pump.await.unwrap();
+
+        // then (期待する結果):
+        let mut sent = Vec::new();
+        while let Ok(Message::Text(text)) = sink_rx.try_recv() {
+            sent.push(text.to_string());
+        }
+        assert_eq!(
+            sent,
+            vec![
+                r#"[{"seq":1},{"seq":2}]"#.to_string(),
+                r#"{"seq":3}"#.to_string()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pump_advises_slow_down_ahead_of_the_backlog() {
+        // テスト項目: 送信キューがしきい値に達すると滞留しているメッセージより先に slow-down を送り、空になると解除を送る
+        // given (前提条件):
+        let (tx, rx) = WebSocketMessagePusher::channel();
+        let (sink_tx, mut sink_rx) = mpsc::unbounded_channel::<Message>();
+        let sink = Box::pin(futures_util::sink::unfold(
+            sink_tx,
+            |sink_tx, message: Message| async move {
+                sink_tx.send(message).map_err(|_| ())?;
+                Ok::<_, ()>(sink_tx)
+            },
+        ));
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let stop = async move {
+            let _ = stop_rx.await;
+            Vec::new()
+        };
+        for seq in 1..=3 {
+            tx.send(format!(r#"{{"seq":{}}}"#, seq).into()).unwrap();
+        }
+
+        // when (操作):
+        let pump = WebSocketMessagePusher::pump(
+            rx,
+            sink,
+            DedupWindow::new(16),
+            stop,
+            |_, _| {},
+            PumpOptions {
+                backpressure: Some(Backpressure::new(2)),
+                ..Default::default()
+            },
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        stop_tx.send(()).unwrap();
+        pump.await.unwrap();
+
+        // then (期待する結果):
+        let mut sent = Vec::new();
+        while let Ok(Message::Text(text)) = sink_rx.try_recv() {
+            sent.push(text.to_string());
+        }
+        assert_eq!(
+            sent,
+            vec![
+                r#"{"type":"slow-down","queue_depth":2,"send_interval_ms":500}"#.to_string(),
+                r#"{"seq":1}"#.to_string(),
+                r#"{"seq":2}"#.to_string(),
+                r#"{"type":"slow-down","queue_depth":0,"send_interval_ms":0}"#.to_string(),
+                r#"{"seq":3}"#.to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pump_sends_heartbeat_before_each_ping() {
+        // テスト項目: Ping の間隔ごとにサーバーの時刻を含む heartbeat を Ping の直前に送る
+        // given (前提条件):
+        let (_tx, rx) = WebSocketMessagePusher::channel();
+        let (sink_tx, mut sink_rx) = mpsc::unbounded_channel::<Message>();
+        let sink = Box::pin(futures_util::sink::unfold(
+            sink_tx,
+            |sink_tx, message: Message| async move {
+                sink_tx.send(message).map_err(|_| ())?;
+                Ok::<_, ()>(sink_tx)
+            },
+        ));
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let stop = async move {
+            let _ = stop_rx.await;
+            Vec::new()
+        };
+        let before = get_jst_timestamp();
+
+        // when (操作):
+        let pump = WebSocketMessagePusher::pump(
+            rx,
+            sink,
+            DedupWindow::new(16),
+            stop,
+            |_, _| {},
+            PumpOptions {
+                ping_interval: Some(std::time::Duration::from_millis(30)),
+                ..Default::default()
+            },
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(45)).await;
+        stop_tx.send(()).unwrap();
+        pump.await.unwrap();
+
+        // then (期待する結果):
+        let Ok(Message::Text(heartbeat)) = sink_rx.try_recv() else {
+            panic!("expected a heartbeat first");
+        };
+        let heartbeat: HeartbeatMessage = serde_json::from_str(&heartbeat).unwrap();
+        assert!(matches!(heartbeat.r#type, MessageType::Heartbeat));
+        assert!(heartbeat.server_time >= before);
+        assert!(matches!(sink_rx.try_recv(), Ok(Message::Ping(_))));
+        assert!(sink_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_through_room_feed_skips_excluded_connections() {
+        // テスト項目: 購読に切り替えた接続にはチャネルで 1 回だけ送り、送信先から除いた接続は読み捨てる
+        // given (前提条件):
+        let pusher = create_test_pusher();
+        let mut feeds = Vec::new();
+        let mut queues = Vec::new();
+        for name in ["alice", "bob", "carol"] {
+            let client_id = ClientId::new(name.to_string()).unwrap();
+            let (tx, rx) = WebSocketMessagePusher::channel();
+            pusher.register_client(client_id.clone(), tx.clone()).await;
+            feeds.push(pusher.subscribe(&client_id, &tx).await.unwrap());
+            queues.push(rx);
+        }
+        let targets = vec![
+            ClientId::new("bob".to_string()).unwrap(),
+            ClientId::new("carol".to_string()).unwrap(),
+        ];
+
+        // when (操作):
+        pusher.broadcast(targets, "Hello").await.unwrap();
+
+        // then (期待する結果):
+        for rx in &mut queues {
+            assert!(rx.try_recv().is_err());
+        }
+        let alice = &mut feeds[0];
+        let msg = alice.receiver.try_recv().unwrap();
+        assert!(alice.skips(&msg));
+        for feed in &mut feeds[1..] {
+            let msg = feed.receiver.try_recv().unwrap();
+            assert!(!feed.skips(&msg));
+            assert_eq!(msg.as_ref(), "Hello");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pump_closes_connection_lagging_behind_room_feed() {
+        // テスト項目: ルームの購読が容量を超えて遅れた接続は LAGGED_CLOSE_CODE で閉じる
+        // given (前提条件):
+        let pusher = create_test_pusher();
+        let client_id = ClientId::new("alice".to_string()).unwrap();
+        let (tx, rx) = WebSocketMessagePusher::channel();
+        pusher.register_client(client_id.clone(), tx.clone()).await;
+        let feed = pusher.subscribe(&client_id, &tx).await;
+        for seq in 0..=ROOM_FEED_CAPACITY {
+            let content = format!(r#"{{"seq":{}}}"#, seq + 1);
+            pusher
+                .broadcast(vec![client_id.clone()], &content)
+                .await
+                .unwrap();
+        }
+        let (sink_tx, mut sink_rx) = mpsc::unbounded_channel::<Message>();
+        let sink = Box::pin(futures_util::sink::unfold(
+            sink_tx,
+            |sink_tx, message: Message| async move {
+                sink_tx.send(message).map_err(|_| ())?;
+                Ok::<_, ()>(sink_tx)
+            },
+        ));
+
+        // when (操作):
+        let pump = WebSocketMessagePusher::pump(
+            rx,
+            sink,
+            DedupWindow::new(16),
+            std::future::pending(),
+            |_, _| {},
+            PumpOptions {
+                feed,
+                ..Default::default()
+            },
+        );
+        pump.await.unwrap();
+
+        // then (期待する結果):
+        let Ok(Message::Close(Some(frame))) = sink_rx.try_recv() else {
+            panic!("expected a close frame");
+        };
+        assert_eq!(frame.code, LAGGED_CLOSE_CODE);
+        assert!(sink_rx.try_recv().is_err());
+    }
 }
@@ -0,0 +1,315 @@
+//! Prometheus 形式のメトリクス
+//!
+//! ## 責務
+//!
+//! - ブロードキャストのレイテンシ（メッセージ受信から最後のクライアントへの送信まで）のヒストグラム
+//! - クライアントごとの送信チャネルに溜まっているメッセージ数とおおよそのバイト数のゲージ
+//! - メッセージ履歴と送信キューのおおよそのメモリ使用量、上限、削除した履歴の数
+//! - テキスト形式（`text/plain; version=0.0.4`）への出力
+//!
+//! ## 設計ノート
+//!
+//! 送信チャネル（`UnboundedSender`）は長さを取得できないため、キューの深さは各接続の
+//! 送信ループがメッセージを取り出すたびに受信側（`UnboundedReceiver::len`）から記録します。
+//! 遅いクライアントほど送信待ちが長く、取り出した時点で大きな値が記録されます。
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+/// ブロードキャストのレイテンシのバケット（秒）
+pub const BROADCAST_LATENCY_BUCKETS: [f64; 12] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5,
+];
+
+/// Content-Type of the text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// 累積ヒストグラム（秒）
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: &'static [f64],
+    /// バケットごとの観測数（累積ではない）。最後の要素は `+Inf`
+    counts: Vec<AtomicU64>,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    /// 上限値の昇順のバケットでヒストグラムを作成
+    pub fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    /// 観測値を記録
+    pub fn observe(&self, value: Duration) {
+        let seconds = value.as_secs_f64();
+        let bucket = self
+            .bounds
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(self.bounds.len());
+        self.counts[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(value.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn render(&self, name: &str, help: &str, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        let mut cumulative = 0;
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            cumulative += count.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+        }
+        cumulative += self.counts[self.bounds.len()].load(Ordering::Relaxed);
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, cumulative);
+        let sum = self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let _ = writeln!(out, "{}_sum {}", name, sum);
+        let _ = writeln!(out, "{}_count {}", name, cumulative);
+    }
+}
+
+/// 送信チャネルに溜まっているメッセージ
+#[derive(Debug, Clone, Copy, Default)]
+struct QueueGauge {
+    depth: usize,
+    bytes: usize,
+}
+
+/// サーバのメトリクス
+#[derive(Debug)]
+pub struct Metrics {
+    broadcast_latency: Histogram,
+    /// クライアント ID ごとの送信チャネルの深さ
+    queue_depths: Mutex<BTreeMap<String, QueueGauge>>,
+    /// メッセージ履歴のおおよそのメモリ使用量
+    history_bytes: AtomicUsize,
+    /// メモリ使用量の上限（0 の場合は上限なし）
+    memory_limit_bytes: AtomicUsize,
+    /// メモリ使用量の上限を超えたために削除したメッセージ数
+    evicted_messages: AtomicU64,
+}
+
+impl Metrics {
+    /// 空のメトリクスを作成
+    pub fn new() -> Self {
+        Self {
+            broadcast_latency: Histogram::new(&BROADCAST_LATENCY_BUCKETS),
+            queue_depths: Mutex::new(BTreeMap::new()),
+            history_bytes: AtomicUsize::new(0),
+            memory_limit_bytes: AtomicUsize::new(0),
+            evicted_messages: AtomicU64::new(0),
+        }
+    }
+
+    /// メッセージの受信から最後のクライアントへの送信までの時間を記録
+    pub fn observe_broadcast(&self, latency: Duration) {
+        self.broadcast_latency.observe(latency);
+    }
+
+    /// クライアントの送信チャネルに溜まっているメッセージ数とおおよそのバイト数を記録
+    pub fn set_queue_depth(&self, client_id: &str, depth: usize, bytes: usize) {
+        let gauge = QueueGauge { depth, bytes };
+        let mut depths = self.queue_depths.lock().unwrap();
+        match depths.get_mut(client_id) {
+            Some(current) => *current = gauge,
+            None => {
+                depths.insert(client_id.to_string(), gauge);
+            }
+        }
+    }
+
+    /// 全クライアントの送信チャネルのおおよそのバイト数
+    pub fn queue_bytes(&self) -> usize {
+        let depths = self.queue_depths.lock().unwrap();
+        depths.values().map(|gauge| gauge.bytes).sum()
+    }
+
+    /// メモリ使用量の上限を記録
+    pub fn set_memory_limit(&self, limit_bytes: Option<usize>) {
+        self.memory_limit_bytes
+            .store(limit_bytes.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// メッセージ履歴のメモリ使用量と、上限を超えたために削除したメッセージ数を記録
+    pub fn record_history(&self, history_bytes: usize, evicted_messages: usize) {
+        self.history_bytes.store(history_bytes, Ordering::Relaxed);
+        self.evicted_messages
+            .fetch_add(evicted_messages as u64, Ordering::Relaxed);
+    }
+
+    /// 切断したクライアントのゲージを削除
+    pub fn remove_queue(&self, client_id: &str) {
+        self.queue_depths.lock().unwrap().remove(client_id);
+    }
+
+    /// テキスト形式で出力
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.broadcast_latency.render(
+            "engawa_broadcast_latency_seconds",
+            "Time from receiving a chat message to pushing it to the last recipient",
+            &mut out,
+        );
+
+        let _ = writeln!(
+            out,
+            "# HELP engawa_client_send_queue_depth Messages waiting in a client's send channel"
+        );
+        let _ = writeln!(out, "# TYPE engawa_client_send_queue_depth gauge");
+        let depths = self.queue_depths.lock().unwrap().clone();
+        for (client_id, gauge) in &depths {
+            let _ = writeln!(
+                out,
+                "engawa_client_send_queue_depth{{client_id=\"{}\"}} {}",
+                escape_label(client_id),
+                gauge.depth
+            );
+        }
+        let _ = writeln!(
+            out,
+            "# HELP engawa_client_send_queue_bytes Approximate bytes waiting in a client's send channel"
+        );
+        let _ = writeln!(out, "# TYPE engawa_client_send_queue_bytes gauge");
+        for (client_id, gauge) in &depths {
+            let _ = writeln!(
+                out,
+                "engawa_client_send_queue_bytes{{client_id=\"{}\"}} {}",
+                escape_label(client_id),
+                gauge.bytes
+            );
+        }
+
+        let queue_bytes: usize = depths.values().map(|gauge| gauge.bytes).sum();
+        let gauges = [
+            (
+                "engawa_memory_history_bytes",
+                "Approximate bytes used by room message histories",
+                self.history_bytes.load(Ordering::Relaxed),
+            ),
+            (
+                "engawa_memory_queue_bytes",
+                "Approximate bytes waiting in all client send channels",
+                queue_bytes,
+            ),
+        ];
+        for (name, help, value) in gauges {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} gauge", name);
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+        let limit = self.memory_limit_bytes.load(Ordering::Relaxed);
+        if limit > 0 {
+            let _ = writeln!(
+                out,
+                "# HELP engawa_memory_limit_bytes Cap on history and queue memory before history is evicted"
+            );
+            let _ = writeln!(out, "# TYPE engawa_memory_limit_bytes gauge");
+            let _ = writeln!(out, "engawa_memory_limit_bytes {}", limit);
+        }
+        let _ = writeln!(
+            out,
+            "# HELP engawa_history_evicted_messages_total Messages evicted from history to stay under the memory limit"
+        );
+        let _ = writeln!(out, "# TYPE engawa_history_evicted_messages_total counter");
+        let _ = writeln!(
+            out,
+            "engawa_history_evicted_messages_total {}",
+            self.evicted_messages.load(Ordering::Relaxed)
+        );
+        out
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ラベル値のエスケープ（`\`、`"`、改行）
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        // テスト項目: バケットは上限以下の観測数の累積で出力され、上限を超えた値は +Inf に入る
+        // given (前提条件):
+        let metrics = Metrics::new();
+
+        // when (操作):
+        metrics.observe_broadcast(Duration::from_micros(300));
+        metrics.observe_broadcast(Duration::from_millis(3));
+        metrics.observe_broadcast(Duration::from_secs(10));
+        let text = metrics.render();
+
+        // then (期待する結果):
+        assert!(text.contains("engawa_broadcast_latency_seconds_bucket{le=\"0.0005\"} 1\n"));
+        assert!(text.contains("engawa_broadcast_latency_seconds_bucket{le=\"0.005\"} 2\n"));
+        assert!(text.contains("engawa_broadcast_latency_seconds_bucket{le=\"2.5\"} 2\n"));
+        assert!(text.contains("engawa_broadcast_latency_seconds_bucket{le=\"+Inf\"} 3\n"));
+        assert!(text.contains("engawa_broadcast_latency_seconds_count 3\n"));
+        assert!(text.contains("engawa_broadcast_latency_seconds_sum 10.0033\n"));
+    }
+
+    #[test]
+    fn test_queue_depth_gauge_per_client() {
+        // テスト項目: クライアントごとのキューの深さが出力され、切断したクライアントは消える
+        // given (前提条件):
+        let metrics = Metrics::new();
+        metrics.set_queue_depth("alice", 3, 300);
+        metrics.set_queue_depth("bob\"", 1, 40);
+        metrics.set_queue_depth("alice", 5, 500);
+
+        // when (操作):
+        let before = metrics.render();
+        metrics.remove_queue("alice");
+        let after = metrics.render();
+
+        // then (期待する結果):
+        assert!(before.contains("engawa_client_send_queue_depth{client_id=\"alice\"} 5\n"));
+        assert!(before.contains("engawa_client_send_queue_depth{client_id=\"bob\\\"\"} 1\n"));
+        assert!(before.contains("engawa_client_send_queue_bytes{client_id=\"alice\"} 500\n"));
+        assert!(before.contains("engawa_memory_queue_bytes 540\n"));
+        assert!(!after.contains("client_id=\"alice\""));
+        assert_eq!(metrics.queue_bytes(), 40);
+    }
+
+    #[test]
+    fn test_memory_gauges() {
+        // テスト項目: 履歴のメモリ使用量、上限、削除したメッセージ数の累計が出力される
+        // given (前提条件):
+        let metrics = Metrics::new();
+        let unlimited = metrics.render();
+
+        // when (操作):
+        metrics.set_memory_limit(Some(1024));
+        metrics.record_history(2048, 3);
+        metrics.record_history(512, 2);
+        let text = metrics.render();
+
+        // then (期待する結果): 上限は設定されている場合のみ出力する
+        assert!(!unlimited.contains("engawa_memory_limit_bytes"));
+        assert!(text.contains("engawa_memory_history_bytes 512\n"));
+        assert!(text.contains("engawa_memory_limit_bytes 1024\n"));
+        assert!(text.contains("engawa_history_evicted_messages_total 5\n"));
+    }
+}
@@ -0,0 +1,329 @@
+//! SQL データベースのスキーマのマイグレーション
+//!
+//! ## 責務
+//!
+//! - バイナリに埋め込んだマイグレーション（`migrations/sqlite`, `migrations/postgres`）の適用
+//! - 適用状況の一覧と、最後に適用したマイグレーションの取り消し
+//!
+//! ## 設計ノート
+//!
+//! 接続先はデータベース URL のスキームで選びます（`sqlite:` / `postgres://`）。
+//! 適用済みのマイグレーションの記録は sqlx と同じ `_sqlx_migrations` テーブルを使うため、
+//! `sqlx migrate` CLI で管理していたデータベースにもそのまま使えます。
+//! 適用と取り消しの間はデータベースをロックし、複数のプロセスが同時に実行しても
+//! 同じマイグレーションを二重に適用しません。
+
+use std::collections::HashMap;
+
+use sqlx::migrate::{Migrate, MigrateError, Migrator};
+#[cfg(feature = "sqlite")]
+use sqlx::{ConnectOptions as _, SqliteConnection, sqlite::SqliteConnectOptions};
+#[cfg(feature = "postgres")]
+use sqlx::{Connection as _, PgConnection};
+
+use crate::infrastructure::error::MigrationError;
+
+/// SQLite のマイグレーション
+#[cfg(feature = "sqlite")]
+static SQLITE_MIGRATOR: Migrator = sqlx::migrate!("migrations/sqlite");
+
+/// PostgreSQL のマイグレーション
+#[cfg(feature = "postgres")]
+static POSTGRES_MIGRATOR: Migrator = sqlx::migrate!("migrations/postgres");
+
+/// マイグレーションの適用状況
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationState {
+    /// 適用済み
+    Applied,
+    /// 未適用
+    Pending,
+    /// 適用後に内容が変更された（チェックサムが一致しない）
+    Modified,
+    /// 適用済みだが、このバイナリに含まれていない
+    Missing,
+}
+
+/// マイグレーションごとの適用状況
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// バージョン（ファイル名の先頭の数字）
+    pub version: i64,
+    /// 説明（ファイル名のバージョン以降）
+    pub description: String,
+    /// 適用状況
+    pub state: MigrationState,
+}
+
+/// データベースへの接続
+enum Connection {
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqliteConnection),
+    #[cfg(feature = "postgres")]
+    Postgres(PgConnection),
+}
+
+impl Connection {
+    /// データベース URL のスキームに応じて接続する（SQLite のファイルが無ければ作成する）
+    async fn open(database_url: &str) -> Result<Self, MigrationError> {
+        if database_url.starts_with("sqlite:") {
+            #[cfg(feature = "sqlite")]
+            {
+                let options = database_url
+                    .parse::<SqliteConnectOptions>()?
+                    .create_if_missing(true);
+                return Ok(Self::Sqlite(options.connect().await?));
+            }
+            #[cfg(not(feature = "sqlite"))]
+            return Err(MigrationError::BackendDisabled("sqlite"));
+        }
+        if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+            #[cfg(feature = "postgres")]
+            return Ok(Self::Postgres(PgConnection::connect(database_url).await?));
+            #[cfg(not(feature = "postgres"))]
+            return Err(MigrationError::BackendDisabled("postgres"));
+        }
+        Err(MigrationError::UnsupportedUrl(database_url.to_string()))
+    }
+}
+
+/// 未適用のマイグレーションを全て適用し、適用したマイグレーションを返す
+///
+/// # Errors
+///
+/// 接続に失敗した場合、適用済みのマイグレーションが変更・削除されている場合、
+/// または途中で失敗したマイグレーションが残っている場合
+pub async fn apply(database_url: &str) -> Result<Vec<MigrationStatus>, MigrationError> {
+    match Connection::open(database_url).await? {
+        #[cfg(feature = "sqlite")]
+        Connection::Sqlite(mut conn) => locked(&mut conn, &SQLITE_MIGRATOR, apply_pending).await,
+        #[cfg(feature = "postgres")]
+        Connection::Postgres(mut conn) => {
+            locked(&mut conn, &POSTGRES_MIGRATOR, apply_pending).await
+        }
+    }
+}
+
+/// 全てのマイグレーションの適用状況をバージョン順に返す
+///
+/// # Errors
+///
+/// 接続に失敗した場合
+pub async fn status(database_url: &str) -> Result<Vec<MigrationStatus>, MigrationError> {
+    match Connection::open(database_url).await? {
+        #[cfg(feature = "sqlite")]
+        Connection::Sqlite(mut conn) => status_of(&mut conn, &SQLITE_MIGRATOR).await,
+        #[cfg(feature = "postgres")]
+        Connection::Postgres(mut conn) => status_of(&mut conn, &POSTGRES_MIGRATOR).await,
+    }
+}
+
+/// 最後に適用したマイグレーションを取り消し、取り消したマイグレーションを返す
+///
+/// 適用済みのマイグレーションが無い場合は `None` を返す。
+///
+/// # Errors
+///
+/// 接続に失敗した場合、または最後に適用したマイグレーションを取り消せない場合
+pub async fn revert(database_url: &str) -> Result<Option<MigrationStatus>, MigrationError> {
+    match Connection::open(database_url).await? {
+        #[cfg(feature = "sqlite")]
+        Connection::Sqlite(mut conn) => locked(&mut conn, &SQLITE_MIGRATOR, revert_latest).await,
+        #[cfg(feature = "postgres")]
+        Connection::Postgres(mut conn) => {
+            locked(&mut conn, &POSTGRES_MIGRATOR, revert_latest).await
+        }
+    }
+}
+
+/// データベースをロックして `operation` を実行する
+async fn locked<C, T>(
+    conn: &mut C,
+    migrator: &Migrator,
+    operation: impl AsyncFnOnce(&mut C, &Migrator) -> Result<T, MigrationError>,
+) -> Result<T, MigrationError>
+where
+    C: Migrate,
+{
+    conn.lock().await?;
+    let result = async {
+        conn.ensure_migrations_table().await?;
+        if let Some(version) = conn.dirty_version().await? {
+            return Err(MigrateError::Dirty(version).into());
+        }
+        operation(conn, migrator).await
+    }
+    .await;
+    conn.unlock().await?;
+    result
+}
+
+async fn status_of<C: Migrate>(
+    conn: &mut C,
+    migrator: &Migrator,
+) -> Result<Vec<MigrationStatus>, MigrationError> {
+    conn.ensure_migrations_table().await?;
+    let mut applied: HashMap<i64, Vec<u8>> = conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| (migration.version, migration.checksum.into_owned()))
+        .collect();
+
+    let mut statuses: Vec<MigrationStatus> = migrator
+        .iter()
+        .filter(|migration| migration.migration_type.is_up_migration())
+        .map(|migration| {
+            let state = match applied.remove(&migration.version) {
+                None => MigrationState::Pending,
+                Some(checksum) if checksum == *migration.checksum => MigrationState::Applied,
+                Some(_) => MigrationState::Modified,
+            };
+            MigrationStatus {
+                version: migration.version,
+                description: migration.description.to_string(),
+                state,
+            }
+        })
+        .collect();
+    statuses.extend(applied.into_keys().map(|version| MigrationStatus {
+        version,
+        description: String::new(),
+        state: MigrationState::Missing,
+    }));
+    statuses.sort_by_key(|status| status.version);
+    Ok(statuses)
+}
+
+async fn apply_pending<C: Migrate>(
+    conn: &mut C,
+    migrator: &Migrator,
+) -> Result<Vec<MigrationStatus>, MigrationError> {
+    let statuses = status_of(conn, migrator).await?;
+    // 適用済みの履歴が食い違っている場合は何も適用しない
+    for status in &statuses {
+        match status.state {
+            MigrationState::Modified => {
+                return Err(MigrateError::VersionMismatch(status.version).into());
+            }
+            MigrationState::Missing => {
+                return Err(MigrateError::VersionMissing(status.version).into());
+            }
+            MigrationState::Applied | MigrationState::Pending => {}
+        }
+    }
+
+    let mut applied = Vec::new();
+    for mut status in statuses
+        .into_iter()
+        .filter(|status| status.state == MigrationState::Pending)
+    {
+        let migration = migrator
+            .iter()
+            .find(|m| m.version == status.version && m.migration_type.is_up_migration())
+            .expect("pending migrations come from the migrator");
+        conn.apply(migration).await?;
+        status.state = MigrationState::Applied;
+        applied.push(status);
+    }
+    Ok(applied)
+}
+
+async fn revert_latest<C: Migrate>(
+    conn: &mut C,
+    migrator: &Migrator,
+) -> Result<Option<MigrationStatus>, MigrationError> {
+    let statuses = status_of(conn, migrator).await?;
+    let Some(mut latest) = statuses
+        .into_iter()
+        .rfind(|status| status.state != MigrationState::Pending)
+    else {
+        return Ok(None);
+    };
+    match latest.state {
+        MigrationState::Modified => {
+            return Err(MigrateError::VersionMismatch(latest.version).into());
+        }
+        MigrationState::Missing => {
+            return Err(MigrateError::VersionMissing(latest.version).into());
+        }
+        MigrationState::Applied | MigrationState::Pending => {}
+    }
+
+    let down = migrator
+        .iter()
+        .find(|m| m.version == latest.version && m.migration_type.is_down_migration())
+        .ok_or(MigrationError::Irreversible(latest.version))?;
+    conn.revert(down).await?;
+    latest.state = MigrationState::Pending;
+    Ok(Some(latest))
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    fn temp_database_url() -> (String, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("engawa-migrate-{}.db", uuid::Uuid::new_v4()));
+        (format!("sqlite://{}", path.display()), path)
+    }
+
+    #[tokio::test]
+    async fn test_apply_and_revert() {
+        // テスト項目: 未適用のマイグレーションを適用し、最後のものから取り消せる
+        // given (前提条件): 空のデータベース
+        let (url, path) = temp_database_url();
+        let pending = status(&url).await.unwrap();
+        assert!(!pending.is_empty());
+        assert!(pending.iter().all(|s| s.state == MigrationState::Pending));
+
+        // when (操作): 適用する
+        let applied = apply(&url).await.unwrap();
+
+        // then (期待する結果): 全て適用され、再度の適用では何もしない
+        assert_eq!(applied.len(), pending.len());
+        assert!(
+            status(&url)
+                .await
+                .unwrap()
+                .iter()
+                .all(|s| s.state == MigrationState::Applied)
+        );
+        assert!(apply(&url).await.unwrap().is_empty());
+
+        // when (操作): 取り消す
+        let reverted = revert(&url).await.unwrap().unwrap();
+
+        // then (期待する結果): 最後のマイグレーションが未適用に戻る
+        assert_eq!(reverted.version, applied.last().unwrap().version);
+        let after = status(&url).await.unwrap();
+        assert_eq!(after.last().unwrap().state, MigrationState::Pending);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_revert_without_applied_migrations() {
+        // テスト項目: 適用済みのマイグレーションが無い場合は何も取り消さない
+        // given (前提条件):
+        let (url, path) = temp_database_url();
+
+        // when (操作):
+        let reverted = revert(&url).await.unwrap();
+
+        // then (期待する結果):
+        assert_eq!(reverted, None);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_url() {
+        // テスト項目: SQLite / PostgreSQL 以外の URL はエラーになる
+        // when (操作):
+        let result = status("mysql://localhost/engawa").await;
+
+        // then (期待する結果):
+        assert!(matches!(result, Err(MigrationError::UnsupportedUrl(_))));
+    }
+}
@@ -1,3 +1,25 @@
+pub mod analyzer;
+pub mod auth;
+pub mod backpressure;
+pub mod cluster;
+pub mod dedup;
+#[cfg(feature = "discord")]
+pub mod discord;
 pub mod dto;
+pub mod error;
+#[cfg(feature = "federation")]
+pub mod federation;
+pub mod filter;
+pub mod hash_ring;
+pub mod i18n;
 pub mod message_pusher;
+pub mod metrics;
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub mod migration;
+pub mod proof_of_work;
+pub mod protocol;
 pub mod repository;
+pub mod sanitize;
+pub mod wire_log;
+#[cfg(feature = "xmpp")]
+pub mod xmpp;
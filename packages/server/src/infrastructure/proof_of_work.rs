@@ -0,0 +1,225 @@
+//! 接続時の proof-of-work（PoW）チャレンジの発行と検証
+//!
+//! ## 責務
+//!
+//! - チャレンジの発行（有効期限とランダムな salt をサーバのシークレットで署名する）
+//! - 解（`<challenge>:<nonce>`）の検証（署名・有効期限・難易度・再利用）
+//!
+//! ## 設計ノート
+//!
+//! チャレンジは署名付きで状態を持たないため、発行するだけではメモリを消費しません。
+//! 再利用を防ぐために覚えておくのは検証に成功したチャレンジのみで（有効期限まで）、
+//! 覚える数は攻撃者が解ける速さに比例します。
+//! 解は `SHA-256("<challenge>:<nonce>")` の先頭のゼロのビット数が難易度以上であるものです。
+//! シークレットはプロセスごとに生成するため、再起動すると未使用のチャレンジは無効になります。
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use super::error::ProofOfWorkError;
+
+/// 既定の難易度（先頭のゼロのビット数、平均 2^18 回のハッシュ計算）
+pub const DEFAULT_POW_DIFFICULTY: u8 = 18;
+
+/// 難易度の上限
+pub const MAX_POW_DIFFICULTY: u8 = 32;
+
+/// チャレンジの有効期限
+pub const CHALLENGE_TTL: Duration = Duration::from_secs(120);
+
+/// nonce の最大長
+const MAX_NONCE_LEN: usize = 64;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// チャレンジの発行と解の検証
+pub struct ProofOfWork {
+    /// チャレンジの署名に使うシークレット
+    secret: [u8; 32],
+    /// チャレンジの有効期限の基準時刻
+    epoch: Instant,
+    difficulty: u8,
+    /// 検証に成功したチャレンジとその有効期限（基準時刻からの秒数）
+    solved: Mutex<HashMap<String, u64>>,
+}
+
+impl ProofOfWork {
+    /// 難易度を指定して作成する（シークレットはランダムに生成する）
+    pub fn new(difficulty: u8) -> Self {
+        let mut secret = [0u8; 32];
+        secret[..16].copy_from_slice(uuid::Uuid::new_v4().as_bytes());
+        secret[16..].copy_from_slice(uuid::Uuid::new_v4().as_bytes());
+        Self {
+            secret,
+            epoch: Instant::now(),
+            difficulty: difficulty.min(MAX_POW_DIFFICULTY),
+            solved: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 難易度（解のハッシュの先頭のゼロのビット数）
+    pub fn difficulty(&self) -> u8 {
+        self.difficulty
+    }
+
+    /// チャレンジを発行する（`now` から `CHALLENGE_TTL` の間有効）
+    pub fn issue(&self, now: Instant) -> String {
+        let expires = self.seconds(now) + CHALLENGE_TTL.as_secs();
+        let body = format!("{}.{}", expires, uuid::Uuid::new_v4().simple());
+        format!(
+            "{}.{}",
+            body,
+            encode_hex(&self.mac(&body).finalize().into_bytes())
+        )
+    }
+
+    /// 解（`<challenge>:<nonce>`）を検証する
+    ///
+    /// 検証に成功したチャレンジは有効期限まで覚えておき、再利用を拒否します。
+    pub fn verify(&self, solution: &str, now: Instant) -> Result<(), ProofOfWorkError> {
+        let (challenge, nonce) = solution
+            .split_once(':')
+            .filter(|(_, nonce)| !nonce.is_empty() && nonce.len() <= MAX_NONCE_LEN)
+            .ok_or(ProofOfWorkError::Malformed)?;
+        let (body, signature) = challenge
+            .rsplit_once('.')
+            .ok_or(ProofOfWorkError::Malformed)?;
+        let signature = decode_hex(signature).ok_or(ProofOfWorkError::Malformed)?;
+        // 定数時間で比較する
+        self.mac(body)
+            .verify_slice(&signature)
+            .map_err(|_| ProofOfWorkError::UnknownChallenge)?;
+        let expires: u64 = body
+            .split_once('.')
+            .and_then(|(expires, _)| expires.parse().ok())
+            .ok_or(ProofOfWorkError::Malformed)?;
+        let now = self.seconds(now);
+        if now >= expires {
+            return Err(ProofOfWorkError::Expired);
+        }
+        if leading_zero_bits(&hash(challenge, nonce)) < u32::from(self.difficulty) {
+            return Err(ProofOfWorkError::InsufficientWork(self.difficulty));
+        }
+
+        let mut solved = self.solved.lock().unwrap();
+        solved.retain(|_, solved_expires| *solved_expires > now);
+        if solved.insert(challenge.to_string(), expires).is_some() {
+            return Err(ProofOfWorkError::Replayed);
+        }
+        Ok(())
+    }
+
+    fn seconds(&self, now: Instant) -> u64 {
+        now.saturating_duration_since(self.epoch).as_secs()
+    }
+
+    fn mac(&self, body: &str) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(body.as_bytes());
+        mac
+    }
+}
+
+/// チャレンジを解いて nonce を返す（クライアントと同じ手順、テストやツール向け）
+pub fn solve(challenge: &str, difficulty: u8) -> String {
+    (0u64..)
+        .map(|nonce| nonce.to_string())
+        .find(|nonce| leading_zero_bits(&hash(challenge, nonce)) >= u32::from(difficulty))
+        .expect("a nonce exists for any difficulty up to 64 bits")
+}
+
+fn hash(challenge: &str, nonce: &str) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(challenge.as_bytes())
+        .chain_update(b":")
+        .chain_update(nonce.as_bytes())
+        .finalize()
+        .into()
+}
+
+fn leading_zero_bits(hash: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in hash {
+        bits += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solved_challenge_is_accepted_once() {
+        // テスト項目: 解いたチャレンジは 1 回だけ受け付けられ、再利用は拒否される
+        // given (前提条件):
+        let pow = ProofOfWork::new(8);
+        let now = Instant::now();
+        let challenge = pow.issue(now);
+        let solution = format!("{}:{}", challenge, solve(&challenge, 8));
+
+        // when (操作):
+        let first = pow.verify(&solution, now);
+        let replayed = pow.verify(&solution, now);
+
+        // then (期待する結果):
+        assert_eq!(first, Ok(()));
+        assert_eq!(replayed, Err(ProofOfWorkError::Replayed));
+    }
+
+    #[test]
+    fn test_invalid_solutions_are_rejected() {
+        // テスト項目: 改ざん・期限切れ・難易度不足・形式不正の解が拒否される
+        // given (前提条件):
+        let pow = ProofOfWork::new(16);
+        let now = Instant::now();
+        let challenge = pow.issue(now);
+        let nonce = solve(&challenge, 16);
+        let tampered = challenge.replacen('.', "1.", 1);
+        let weak_nonce = (0u64..)
+            .map(|nonce| nonce.to_string())
+            .find(|nonce| leading_zero_bits(&hash(&challenge, nonce)) < 16)
+            .unwrap();
+
+        // when (操作):
+        let other_server = ProofOfWork::new(16).verify(&format!("{}:{}", challenge, nonce), now);
+        let tampered = pow.verify(&format!("{}:{}", tampered, nonce), now);
+        let expired = pow.verify(
+            &format!("{}:{}", challenge, nonce),
+            now + CHALLENGE_TTL + Duration::from_secs(1),
+        );
+        let weak = pow.verify(&format!("{}:{}", challenge, weak_nonce), now);
+        let malformed = pow.verify(&challenge, now);
+
+        // then (期待する結果):
+        assert_eq!(other_server, Err(ProofOfWorkError::UnknownChallenge));
+        assert_eq!(tampered, Err(ProofOfWorkError::UnknownChallenge));
+        assert_eq!(expired, Err(ProofOfWorkError::Expired));
+        assert_eq!(weak, Err(ProofOfWorkError::InsufficientWork(16)));
+        assert_eq!(malformed, Err(ProofOfWorkError::Malformed));
+    }
+}
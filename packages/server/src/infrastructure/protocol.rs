@@ -0,0 +1,232 @@
+//! WebSocket プロトコルのバージョンと機能のネゴシエーション
+//!
+//! ## 責務
+//!
+//! - クライアントの `hello` から、接続で使うプロトコルのバージョンと機能（[`Capabilities`]）を決める
+//! - 接続で使わない機能のメッセージを、クライアントが解釈できる形に変換する（または送らない）
+//!
+//! ## 設計ノート
+//!
+//! クライアントは接続直後の最初のフレームとして `hello` を送り、理解できるプロトコルの
+//! バージョンと任意の機能を伝えます。サーバーは双方が対応するバージョン（小さい方）と、
+//! 双方が対応する機能（共通部分）を `hello-ack` で返します。サーバーが対応していない機能
+//! （例: `reactions`）は返しません。
+//!
+//! `hello` を送らないクライアントは、ハンドシェイクが導入される前のクライアント
+//! （バージョン 1）として扱い、その時点の全ての機能を使います。
+//!
+//! 使わない機能のメッセージは送信キューから接続に書き込む時に変換します。
+//!
+//! - `typing`: `typing-started`・`typing-stopped` を送らない
+//! - `polls`: 投票を通常のチャットメッセージ（`chat`）として送り、`poll-updated` を送らない
+//! - `history`・`heartbeat`・`slow-down`・`batching`: それぞれ `room-history`・`heartbeat`・
+//!   `slow-down`・複数のメッセージをまとめたフレームを送らない
+
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::infrastructure::dto::websocket::{ChatMessage, HelloMessage, MessageType};
+
+/// サーバーのプロトコルのバージョン
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// `hello` を送らないクライアントのプロトコルのバージョン
+pub const LEGACY_PROTOCOL_VERSION: u32 = 1;
+
+/// ネゴシエーションで有効・無効を決める任意の機能
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// 接続直後の `room-history`
+    History,
+    /// `typing-started`・`typing-stopped`
+    Typing,
+    /// `poll` と `poll-updated`
+    Polls,
+    /// Ping と一緒に送る `heartbeat`
+    Heartbeat,
+    /// 送信キューの滞留に応じた `slow-down`
+    SlowDown,
+    /// 複数のメッセージを 1 つの JSON 配列にまとめたフレーム
+    Batching,
+}
+
+impl Feature {
+    /// サーバーが対応する全ての機能
+    pub const ALL: [Feature; 6] = [
+        Feature::History,
+        Feature::Typing,
+        Feature::Polls,
+        Feature::Heartbeat,
+        Feature::SlowDown,
+        Feature::Batching,
+    ];
+
+    /// `hello`・`hello-ack` での機能の名前
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Feature::History => "history",
+            Feature::Typing => "typing",
+            Feature::Polls => "polls",
+            Feature::Heartbeat => "heartbeat",
+            Feature::SlowDown => "slow-down",
+            Feature::Batching => "batching",
+        }
+    }
+
+    /// 名前から機能を取得（サーバーが対応していない機能は `None`）
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|feature| feature.as_str() == name)
+    }
+
+    /// ビットマスクでの位置
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// 接続で使うプロトコルのバージョンと機能
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// 接続で使うプロトコルのバージョン
+    pub protocol_version: u32,
+    /// 有効な機能のビットマスク
+    features: u8,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::legacy()
+    }
+}
+
+impl Capabilities {
+    /// `hello` を送らないクライアントの Capabilities（全ての機能を使う）
+    pub fn legacy() -> Self {
+        Self {
+            protocol_version: LEGACY_PROTOCOL_VERSION,
+            features: Feature::ALL.iter().fold(0, |bits, f| bits | f.bit()),
+        }
+    }
+
+    /// クライアントが伝えたバージョンと機能から Capabilities を決める
+    ///
+    /// # 引数
+    ///
+    /// - `protocol_version`: クライアントのプロトコルのバージョン
+    /// - `features`: クライアントが対応する機能の名前（サーバーが知らない名前は無視する）
+    pub fn negotiate<S: AsRef<str>>(protocol_version: u32, features: &[S]) -> Self {
+        Self {
+            protocol_version: protocol_version.clamp(LEGACY_PROTOCOL_VERSION, PROTOCOL_VERSION),
+            features: features
+                .iter()
+                .filter_map(|name| Feature::parse(name.as_ref()))
+                .fold(0, |bits, f| bits | f.bit()),
+        }
+    }
+
+    /// クライアントのテキストフレームが `hello` であれば、その Capabilities を返す
+    pub fn from_hello(text: &str) -> Option<Self> {
+        let hello = serde_json::from_str::<HelloMessage>(text).ok()?;
+        matches!(hello.r#type, MessageType::Hello)
+            .then(|| Self::negotiate(hello.protocol_version, &hello.features))
+    }
+
+    /// 機能が有効かどうか
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.features & feature.bit() != 0
+    }
+
+    /// 有効な機能（`hello-ack` で返す名前）
+    pub fn feature_names(&self) -> Vec<String> {
+        Feature::ALL
+            .into_iter()
+            .filter(|feature| self.supports(*feature))
+            .map(|feature| feature.as_str().to_string())
+            .collect()
+    }
+
+    /// 投票を使わないクライアントのために、投票を通常のチャットメッセージにする
+    pub fn downgrade_chat(&self, message: &mut ChatMessage) {
+        if !self.supports(Feature::Polls) && message.poll.is_some() {
+            message.r#type = MessageType::Chat;
+            message.poll = None;
+        }
+    }
+
+    /// 送信キューのメッセージを接続で使う機能に合わせて変換する
+    ///
+    /// 送らないメッセージの場合は `None` を返す。JSON として解釈できないメッセージはそのまま返す。
+    pub fn downgrade(&self, msg: Arc<str>) -> Option<Arc<str>> {
+        if self.supports(Feature::Typing) && self.supports(Feature::Polls) {
+            return Some(msg);
+        }
+        let Ok(mut value) = serde_json::from_str::<Value>(&msg) else {
+            return Some(msg);
+        };
+        match value.get("type").and_then(Value::as_str) {
+            Some("typing-started" | "typing-stopped") if !self.supports(Feature::Typing) => None,
+            Some("poll-updated") if !self.supports(Feature::Polls) => None,
+            Some("poll") if !self.supports(Feature::Polls) => {
+                let object = value.as_object_mut()?;
+                object.insert("type".to_string(), Value::from("chat"));
+                object.remove("poll");
+                Some(value.to_string().into())
+            }
+            _ => Some(msg),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_keeps_common_version_and_features() {
+        // テスト項目: バージョンは双方が対応するものに、機能はサーバーが対応するものだけになる
+        // when (操作):
+        let newer = Capabilities::negotiate(99, &["typing", "reactions", "history"]);
+        let older = Capabilities::negotiate(0, &[] as &[&str]);
+
+        // then (期待する結果):
+        assert_eq!(newer.protocol_version, PROTOCOL_VERSION);
+        assert_eq!(newer.feature_names(), vec!["history", "typing"]);
+        assert_eq!(older.protocol_version, LEGACY_PROTOCOL_VERSION);
+        assert!(older.feature_names().is_empty());
+        assert_eq!(
+            Capabilities::legacy().feature_names().len(),
+            Feature::ALL.len()
+        );
+    }
+
+    #[test]
+    fn test_downgrade_messages_of_unsupported_features() {
+        // テスト項目: 使わない機能のメッセージは送らないか、解釈できる形に変換される
+        // given (前提条件):
+        let capabilities = Capabilities::negotiate(PROTOCOL_VERSION, &["history"]);
+        let poll = r#"{"type":"poll","client_id":"alice","content":"Lunch?","timestamp":1000,"seq":3,"poll":{"options":[{"label":"Yes","votes":0}]}}"#;
+
+        // when (操作):
+        let typing =
+            capabilities.downgrade(r#"{"type":"typing-started","client_id":"alice"}"#.into());
+        let updated =
+            capabilities.downgrade(r#"{"type":"poll-updated","seq":3,"options":[]}"#.into());
+        let chat = capabilities.downgrade(poll.into()).unwrap();
+
+        // then (期待する結果):
+        assert_eq!(typing, None);
+        assert_eq!(updated, None);
+        let chat: ChatMessage = serde_json::from_str(&chat).unwrap();
+        assert!(matches!(chat.r#type, MessageType::Chat));
+        assert_eq!(chat.content, "Lunch?");
+        assert_eq!(chat.seq, Some(3));
+        assert!(chat.poll.is_none());
+        assert_eq!(
+            Capabilities::legacy().downgrade(poll.into()).as_deref(),
+            Some(poll)
+        );
+    }
+}
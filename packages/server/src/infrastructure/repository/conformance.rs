@@ -0,0 +1,700 @@
+//! Room Repository の共通の適合テスト
+//!
+//! 全ての `RoomRepository` の実装が同じ振る舞いをすることを確認するテストの集まりです。
+//! 実装ごとのテストから、初期状態のルームを受け取って Repository を作る関数を渡して
+//! [`run`] を呼び出します。
+//!
+//! ```ignore
+//! #[tokio::test]
+//! async fn test_conformance() {
+//!     conformance::run(|room| async move {
+//!         Arc::new(InMemoryRoomRepository::new(room))
+//!             as Arc<dyn RoomRepository>
+//!     })
+//!     .await;
+//! }
+//! ```
+//!
+//! ## 設計ノート
+//!
+//! 各テストは新しい Repository で実行し、失敗した場合はテスト名を含むメッセージで
+//! どの振る舞いが食い違ったかを示します。
+
+use std::sync::Arc;
+
+use crate::domain::{
+    ChatMessage, ClientId, ForwardedFrom, MessageContent, MessageTag, PollOption, RepositoryError,
+    Room, RoomError, RoomIdFactory, RoomRepository, SequenceNumber, Timestamp,
+};
+
+/// 全ての適合テストを実行する
+///
+/// # Arguments
+///
+/// * `new_repository` - 初期状態のルームを保持する Repository を作る関数
+pub async fn run<F, Fut>(new_repository: F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    get_room_returns_initial_room(&new_repository).await;
+    participants_are_added_and_removed(&new_repository).await;
+    removing_unknown_participant_succeeds(&new_repository).await;
+    participant_capacity_is_enforced(&new_repository).await;
+    messages_are_numbered_consecutively(&new_repository).await;
+    message_capacity_is_enforced(&new_repository).await;
+    history_is_evicted_oldest_first(&new_repository).await;
+    messages_are_tagged(&new_repository).await;
+    polls_are_voted_on(&new_repository).await;
+    forwarded_messages_keep_their_origin(&new_repository).await;
+    client_data_is_erased(&new_repository).await;
+    messages_are_deleted(&new_repository).await;
+    projections_match_room(&new_repository).await;
+    messages_are_paged_by_timestamp(&new_repository).await;
+    snapshots_are_immutable(&new_repository).await;
+    rooms_are_created_and_scoped(&new_repository).await;
+    rooms_are_deleted(&new_repository).await;
+    ping_succeeds(&new_repository).await;
+}
+
+fn room(participant_capacity: usize, message_capacity: usize) -> Room {
+    Room::with_capacity(
+        RoomIdFactory::generate().unwrap(),
+        Timestamp::new(1000),
+        participant_capacity,
+        message_capacity,
+    )
+}
+
+fn client_id(name: &str) -> ClientId {
+    ClientId::new(name.to_string()).unwrap()
+}
+
+async fn add_message(repository: &Arc<dyn RoomRepository>, content: &str) -> SequenceNumber {
+    repository
+        .add_message(
+            client_id("alice"),
+            MessageContent::new(content.to_string()).unwrap(),
+            Timestamp::new(2000),
+        )
+        .await
+        .unwrap()
+}
+
+async fn get_room_returns_initial_room<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: 渡したルームの ID と作成日時がそのまま取得できる
+    // given (前提条件):
+    let initial = room(10, 100);
+    let repository = new_repository(initial.clone()).await;
+
+    // when (操作):
+    let room = repository.get_room().await.unwrap();
+
+    // then (期待する結果):
+    assert_eq!(room.id, initial.id, "get_room_returns_initial_room");
+    assert_eq!(room.created_at, initial.created_at);
+    assert!(room.participants.is_empty());
+    assert!(room.messages.is_empty());
+}
+
+async fn participants_are_added_and_removed<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: 追加した参加者が全ての取得方法に反映され、削除すると消える
+    // given (前提条件):
+    let repository = new_repository(room(10, 100)).await;
+
+    // when (操作):
+    for name in ["alice", "bob"] {
+        repository
+            .add_participant(client_id(name), Timestamp::new(3000))
+            .await
+            .unwrap();
+    }
+    repository
+        .remove_participant(&client_id("alice"))
+        .await
+        .unwrap();
+
+    // then (期待する結果):
+    let name = "participants_are_added_and_removed";
+    assert_eq!(repository.count_connected_clients().await, 1, "{}", name);
+    assert_eq!(
+        repository.get_all_connected_client_ids().await,
+        vec![client_id("bob")],
+        "{}",
+        name
+    );
+    let participants = repository.get_participants().await;
+    assert_eq!(participants.len(), 1, "{}", name);
+    assert_eq!(participants[0].id, client_id("bob"), "{}", name);
+    assert_eq!(
+        participants[0].connected_at,
+        Timestamp::new(3000),
+        "{}",
+        name
+    );
+    assert_eq!(
+        repository.get_room().await.unwrap().participants.len(),
+        1,
+        "{}",
+        name
+    );
+}
+
+async fn removing_unknown_participant_succeeds<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: 参加していないクライアントの削除は成功し、何も変わらない
+    // given (前提条件):
+    let repository = new_repository(room(10, 100)).await;
+
+    // when (操作):
+    let result = repository.remove_participant(&client_id("nobody")).await;
+
+    // then (期待する結果):
+    assert!(result.is_ok(), "removing_unknown_participant_succeeds");
+    assert_eq!(repository.count_connected_clients().await, 0);
+}
+
+async fn participant_capacity_is_enforced<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: 定員を超える参加者は追加できない
+    // given (前提条件):
+    let repository = new_repository(room(2, 100)).await;
+    for name in ["alice", "bob"] {
+        repository
+            .add_participant(client_id(name), Timestamp::new(3000))
+            .await
+            .unwrap();
+    }
+
+    // when (操作):
+    let result = repository
+        .add_participant(client_id("carol"), Timestamp::new(3000))
+        .await;
+
+    // then (期待する結果):
+    assert!(result.is_err(), "participant_capacity_is_enforced");
+    assert_eq!(repository.count_connected_clients().await, 2);
+}
+
+async fn messages_are_numbered_consecutively<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: メッセージには 1 から連番が振られ、追加した順に取得できる
+    // given (前提条件):
+    let repository = new_repository(room(10, 100)).await;
+
+    // when (操作):
+    let mut seqs = Vec::new();
+    for content in ["one", "two", "three"] {
+        seqs.push(add_message(&repository, content).await.value());
+    }
+
+    // then (期待する結果):
+    let name = "messages_are_numbered_consecutively";
+    assert_eq!(seqs, vec![1, 2, 3], "{}", name);
+    let room = repository.get_room().await.unwrap();
+    let contents: Vec<_> = room
+        .messages
+        .iter()
+        .map(|m| (m.seq.value(), m.content.as_str()))
+        .collect();
+    assert_eq!(
+        contents,
+        vec![(1, "one"), (2, "two"), (3, "three")],
+        "{}",
+        name
+    );
+    assert_eq!(room.last_seq.value(), 3, "{}", name);
+}
+
+async fn message_capacity_is_enforced<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: 履歴の上限を超えるメッセージは追加できず、番号も消費しない
+    // given (前提条件):
+    let repository = new_repository(room(10, 2)).await;
+    add_message(&repository, "one").await;
+    add_message(&repository, "two").await;
+
+    // when (操作):
+    let result = repository
+        .add_message(
+            client_id("alice"),
+            MessageContent::new("three".to_string()).unwrap(),
+            Timestamp::new(2000),
+        )
+        .await;
+
+    // then (期待する結果):
+    assert!(result.is_err(), "message_capacity_is_enforced");
+    assert_eq!(repository.get_room().await.unwrap().last_seq.value(), 2);
+}
+
+async fn history_is_evicted_oldest_first<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: 履歴は古いものから上限以下になるまで削除され、番号は再利用されない
+    // given (前提条件):
+    let repository = new_repository(room(10, 100)).await;
+    for i in 0..10 {
+        add_message(&repository, &format!("message {}", i)).await;
+    }
+    let history_bytes = repository.history_bytes().await;
+
+    // when (操作):
+    let evicted = repository.evict_history(history_bytes / 2).await;
+
+    // then (期待する結果):
+    let name = "history_is_evicted_oldest_first";
+    assert!(evicted > 0, "{}", name);
+    assert!(
+        repository.history_bytes().await <= history_bytes / 2,
+        "{}",
+        name
+    );
+    let room = repository.get_room().await.unwrap();
+    assert_eq!(room.messages.len(), 10 - evicted, "{}", name);
+    assert_eq!(room.messages.last().unwrap().seq.value(), 10, "{}", name);
+    assert_eq!(
+        add_message(&repository, "next").await.value(),
+        11,
+        "{}",
+        name
+    );
+}
+
+async fn messages_are_tagged<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: メッセージに付けたタグが重複なく保存され、存在しないメッセージはエラーになる
+    // given (前提条件):
+    let repository = new_repository(room(10, 100)).await;
+    let seq = add_message(&repository, "deploy").await;
+    let tag = MessageTag::new("keyword:deploy".to_string()).unwrap();
+
+    // when (操作):
+    let first = repository.tag_message(seq, vec![tag.clone()]).await;
+    let again = repository.tag_message(seq, vec![tag.clone()]).await;
+    let missing = repository
+        .tag_message(SequenceNumber::new(99), vec![tag.clone()])
+        .await;
+
+    // then (期待する結果):
+    let name = "messages_are_tagged";
+    assert!(first.is_ok(), "{}", name);
+    assert!(again.is_ok(), "{}", name);
+    assert!(
+        matches!(missing, Err(RepositoryError::MessageNotFound(99))),
+        "{}",
+        name
+    );
+    let room = repository.get_room().await.unwrap();
+    assert_eq!(room.messages[0].tags, vec![tag], "{}", name);
+}
+
+async fn polls_are_voted_on<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: 投票にしたメッセージの票がクライアントごとに集計され、消去したクライアントの票は消える
+    // given (前提条件):
+    let repository = new_repository(room(10, 100)).await;
+    let seq = add_message(&repository, "Lunch?").await;
+    let plain = add_message(&repository, "plain").await;
+    let options = ["Ramen", "Sushi"]
+        .map(|option| PollOption::new(option.to_string()).unwrap())
+        .to_vec();
+
+    // when (操作):
+    let attached = repository.attach_poll(seq, options.clone()).await;
+    repository.vote(seq, client_id("bob"), 0).await.unwrap();
+    repository.vote(seq, client_id("carol"), 0).await.unwrap();
+    let moved = repository.vote(seq, client_id("bob"), 1).await;
+    let not_a_poll = repository.vote(plain, client_id("bob"), 0).await;
+    let missing = repository
+        .attach_poll(SequenceNumber::new(99), options.clone())
+        .await;
+    let erased = repository.erase_client(&client_id("carol")).await;
+
+    // then (期待する結果):
+    let name = "polls_are_voted_on";
+    assert!(attached.is_ok(), "{}", name);
+    assert_eq!(moved.unwrap().tally(), vec![1, 1], "{}", name);
+    assert!(
+        matches!(
+            not_a_poll,
+            Err(RepositoryError::Room(RoomError::NotAPoll { .. }))
+        ),
+        "{}",
+        name
+    );
+    assert!(
+        matches!(missing, Err(RepositoryError::MessageNotFound(99))),
+        "{}",
+        name
+    );
+    assert!(erased.is_ok(), "{}", name);
+    let room = repository.get_room().await.unwrap();
+    let poll = room.messages[0].poll.as_ref().unwrap();
+    assert_eq!(poll.options, options, "{}", name);
+    assert_eq!(poll.tally(), vec![0, 1], "{}", name);
+}
+
+async fn forwarded_messages_keep_their_origin<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: 転送したメッセージに転送元が記録され、履歴に無いメッセージには記録できない
+    // given (前提条件):
+    let repository = new_repository(room(10, 100)).await;
+    let seq = add_message(&repository, "Forwarded").await;
+    let origin = ForwardedFrom {
+        room_id: RoomIdFactory::generate().unwrap(),
+        from: client_id("bob"),
+        timestamp: Timestamp::new(500),
+    };
+
+    // when (操作):
+    let marked = repository.mark_forwarded(seq, origin.clone()).await;
+    let missing = repository
+        .mark_forwarded(SequenceNumber::new(99), origin.clone())
+        .await;
+
+    // then (期待する結果):
+    let name = "forwarded_messages_keep_their_origin";
+    assert!(marked.is_ok(), "{}", name);
+    assert!(
+        matches!(missing, Err(RepositoryError::MessageNotFound(99))),
+        "{}",
+        name
+    );
+    let room = repository.get_room().await.unwrap();
+    assert_eq!(room.messages[0].forwarded_from, Some(origin), "{}", name);
+}
+
+async fn client_data_is_erased<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: クライアントの参加者情報とメッセージが削除され、シーケンス番号は再利用されない
+    // given (前提条件):
+    let repository = new_repository(room(10, 100)).await;
+    repository
+        .add_participant(client_id("alice"), Timestamp::new(1500))
+        .await
+        .unwrap();
+    add_message(&repository, "one").await;
+    repository
+        .add_message(
+            client_id("bob"),
+            MessageContent::new("two".to_string()).unwrap(),
+            Timestamp::new(2000),
+        )
+        .await
+        .unwrap();
+
+    // when (操作):
+    let erased = repository.erase_client(&client_id("alice")).await;
+    let next = add_message(&repository, "three").await;
+
+    // then (期待する結果):
+    let name = "client_data_is_erased";
+    assert_eq!(erased.unwrap(), vec![SequenceNumber::new(1)], "{}", name);
+    assert_eq!(next.value(), 3, "{}", name);
+    let room = repository.get_room().await.unwrap();
+    assert!(
+        room.get_participant(&client_id("alice")).is_none(),
+        "{}",
+        name
+    );
+    let seqs: Vec<_> = room.messages.iter().map(|m| m.seq.value()).collect();
+    assert_eq!(seqs, vec![2, 3], "{}", name);
+}
+
+async fn messages_are_deleted<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: 指定したメッセージだけが削除され、シーケンス番号は再利用されない
+    // given (前提条件):
+    let repository = new_repository(room(10, 100)).await;
+    for content in ["one", "two", "three"] {
+        add_message(&repository, content).await;
+    }
+    let tag = MessageTag::new("keyword:two".to_string()).unwrap();
+    repository
+        .tag_message(SequenceNumber::new(2), vec![tag])
+        .await
+        .unwrap();
+
+    // when (操作):
+    let deleted = repository.delete_message(SequenceNumber::new(2)).await;
+    let again = repository.delete_message(SequenceNumber::new(2)).await;
+    let next = add_message(&repository, "four").await;
+
+    // then (期待する結果):
+    let name = "messages_are_deleted";
+    assert!(deleted.is_ok(), "{}", name);
+    assert!(
+        matches!(again, Err(RepositoryError::MessageNotFound(2))),
+        "{}",
+        name
+    );
+    assert_eq!(next.value(), 4, "{}", name);
+    let room = repository.get_room().await.unwrap();
+    let seqs: Vec<_> = room.messages.iter().map(|m| m.seq.value()).collect();
+    assert_eq!(seqs, vec![1, 3, 4], "{}", name);
+}
+
+async fn projections_match_room<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: メタデータと最新のメッセージの射影が Room 全体の取得結果と一致する
+    // given (前提条件):
+    let repository = new_repository(room(10, 100)).await;
+    repository
+        .add_participant(client_id("bob"), Timestamp::new(3000))
+        .await
+        .unwrap();
+    for content in ["one", "two", "three"] {
+        add_message(&repository, content).await;
+    }
+
+    // when (操作):
+    let metadata = repository.get_room_metadata().await.unwrap();
+    let recent = repository.get_recent_messages(2).await.unwrap();
+    let all = repository.get_recent_messages(10).await.unwrap();
+
+    // then (期待する結果):
+    let name = "projections_match_room";
+    let room = repository.get_room().await.unwrap();
+    assert_eq!(metadata, room.metadata(), "{}", name);
+    assert_eq!(metadata.participant_count, 1, "{}", name);
+    assert_eq!(metadata.message_count, 3, "{}", name);
+    let seqs: Vec<_> = recent.iter().map(|m| m.seq.value()).collect();
+    assert_eq!(seqs, vec![2, 3], "{}", name);
+    assert_eq!(all.len(), 3, "{}", name);
+}
+
+async fn messages_are_paged_by_timestamp<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: 指定した日時より前の最新のメッセージが古い順に件数まで取得できる
+    // given (前提条件):
+    let repository = new_repository(room(10, 100)).await;
+    for timestamp in [2000, 3000, 4000, 5000] {
+        repository
+            .add_message(
+                client_id("alice"),
+                MessageContent::new(format!("at {}", timestamp)).unwrap(),
+                Timestamp::new(timestamp),
+            )
+            .await
+            .unwrap();
+    }
+
+    // when (操作):
+    let latest = repository.get_messages_before(None, 2).await.unwrap();
+    let before = repository
+        .get_messages_before(Some(Timestamp::new(4000)), 10)
+        .await
+        .unwrap();
+    let none = repository
+        .get_messages_before(Some(Timestamp::new(2000)), 10)
+        .await
+        .unwrap();
+
+    // then (期待する結果):
+    let name = "messages_are_paged_by_timestamp";
+    let seqs =
+        |messages: &[ChatMessage]| messages.iter().map(|m| m.seq.value()).collect::<Vec<_>>();
+    assert_eq!(seqs(&latest), vec![3, 4], "{}", name);
+    assert_eq!(seqs(&before), vec![1, 2], "{}", name);
+    assert!(none.is_empty(), "{}", name);
+}
+
+async fn snapshots_are_immutable<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: スナップショットは取得した時点の状態のままで、変更後に取得すると変更が反映される
+    // given (前提条件):
+    let repository = new_repository(room(10, 100)).await;
+    let room_id = repository.get_room().await.unwrap().id;
+    add_message(&repository, "one").await;
+
+    // when (操作):
+    let before = repository.get_room_snapshot(&room_id).await.unwrap();
+    let again = repository.get_room_snapshot(&room_id).await.unwrap();
+    add_message(&repository, "two").await;
+    let after = repository.get_room_snapshot(&room_id).await.unwrap();
+    let unknown = repository
+        .get_room_snapshot(&RoomIdFactory::generate().unwrap())
+        .await;
+
+    // then (期待する結果):
+    let name = "snapshots_are_immutable";
+    assert_eq!(before.messages.len(), 1, "{}", name);
+    assert_eq!(again.messages.len(), 1, "{}", name);
+    assert_eq!(after.messages.len(), 2, "{}", name);
+    assert_eq!(after.last_seq.value(), 2, "{}", name);
+    assert!(
+        matches!(unknown, Err(RepositoryError::RoomNotFound)),
+        "{}",
+        name
+    );
+}
+
+async fn rooms_are_created_and_scoped<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: 作成したルームは ID で取得でき、そのルームの Repository の変更は他のルームに影響しない
+    // given (前提条件):
+    let initial = room(10, 100);
+    let repository = new_repository(initial.clone()).await;
+    let other = room(10, 100);
+
+    // when (操作):
+    let created = repository.create_room(other.clone()).await;
+    let duplicate = repository.create_room(other.clone()).await;
+    let duplicate_default = repository.create_room(initial.clone()).await;
+    let scoped = repository.for_room(&other.id).await.unwrap();
+    add_message(&scoped, "hello").await;
+    scoped
+        .add_participant(client_id("bob"), Timestamp::new(3000))
+        .await
+        .unwrap();
+    let unknown = repository
+        .for_room(&RoomIdFactory::generate().unwrap())
+        .await;
+
+    // then (期待する結果):
+    let name = "rooms_are_created_and_scoped";
+    assert!(created.is_ok(), "{}", name);
+    assert!(
+        matches!(duplicate, Err(RepositoryError::RoomAlreadyExists(_))),
+        "{}",
+        name
+    );
+    assert!(
+        matches!(
+            duplicate_default,
+            Err(RepositoryError::RoomAlreadyExists(_))
+        ),
+        "{}",
+        name
+    );
+    assert!(
+        matches!(unknown, Err(RepositoryError::RoomNotFound)),
+        "{}",
+        name
+    );
+    assert_eq!(
+        repository.get_room_ids().await,
+        vec![initial.id.clone(), other.id.clone()],
+        "{}",
+        name
+    );
+    let default_room = repository.get_room().await.unwrap();
+    assert!(default_room.messages.is_empty(), "{}", name);
+    assert_eq!(repository.count_connected_clients().await, 0, "{}", name);
+    let scoped_room = repository.get_room_by_id(&other.id).await.unwrap();
+    assert_eq!(scoped_room.messages.len(), 1, "{}", name);
+    assert_eq!(scoped_room.participants.len(), 1, "{}", name);
+    assert_eq!(scoped.get_room().await.unwrap().id, other.id, "{}", name);
+}
+
+async fn rooms_are_deleted<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: 作成したルームは削除でき、既定のルームは削除できない
+    // given (前提条件):
+    let initial = room(10, 100);
+    let repository = new_repository(initial.clone()).await;
+    let other = room(10, 100);
+    repository.create_room(other.clone()).await.unwrap();
+
+    // when (操作):
+    let default_deleted = repository.delete_room(&initial.id).await;
+    let deleted = repository.delete_room(&other.id).await;
+    let again = repository.delete_room(&other.id).await;
+
+    // then (期待する結果):
+    let name = "rooms_are_deleted";
+    assert!(
+        matches!(default_deleted, Err(RepositoryError::RoomNotDeletable(_))),
+        "{}",
+        name
+    );
+    assert!(deleted.is_ok(), "{}", name);
+    assert!(
+        matches!(again, Err(RepositoryError::RoomNotFound)),
+        "{}",
+        name
+    );
+    assert!(
+        matches!(
+            repository.get_room_by_id(&other.id).await,
+            Err(RepositoryError::RoomNotFound)
+        ),
+        "{}",
+        name
+    );
+    assert_eq!(
+        repository.get_room_ids().await,
+        vec![initial.id],
+        "{}",
+        name
+    );
+}
+
+async fn ping_succeeds<F, Fut>(new_repository: &F)
+where
+    F: Fn(Room) -> Fut,
+    Fut: Future<Output = Arc<dyn RoomRepository>>,
+{
+    // テスト項目: 利用可能な Repository は ping に応答する
+    // given (前提条件):
+    let repository = new_repository(room(10, 100)).await;
+
+    // when (操作):
+    let result = repository.ping().await;
+
+    // then (期待する結果):
+    assert!(result.is_ok(), "ping_succeeds");
+}
@@ -0,0 +1,200 @@
+//! ルームを所有するタスク（アクター）
+//!
+//! ## 責務
+//!
+//! - 1 つのルームの状態（`Room`）を専用のタスクで所有し、読み書きを 1 件ずつ順に処理する
+//! - 読み取り用のスナップショット（`Arc<Room>`）をコピーオンライトで提供する
+//!
+//! ## 設計ノート
+//!
+//! ルームの状態は共有のロック（`Mutex<Room>`）ではなく、メールボックス（mpsc）で受け取った
+//! 操作としてアクターのタスクだけが触れます。操作はメールボックスに届いた順に 1 件ずつ適用
+//! されるため、同じルームへの変更の順序はロックの取得順に左右されず、読み取りが書き込みの
+//! ロックを待つこともありません。
+//!
+//! アクターは操作を適用するたびに、ルームの `Arc<Room>` を `watch` チャネルで公開します。
+//! スナップショットは公開された `Arc<Room>` をメールボックスを通さずに返すため、書き込みが
+//! メールボックスに溜まっていても待ちません（返るのは直前に適用した操作までの状態）。
+//! 公開したルームを変更する時は `Arc::make_mut` でルームを複製します（コピーオンライト）。
+//! メールボックスに溜まった操作はまとめて適用してから 1 回だけ公開するため、書き込みが
+//! 続く間の複製はまとめた操作ごとに 1 回です。操作の結果は公開した後に返すので、
+//! 書き込みの完了後に取得したスナップショットにはその書き込みが含まれます。
+//!
+//! アクターは全てのハンドル（[`RoomActor`]）が破棄されると終了します。
+//!
+//! ## 適用範囲
+//!
+//! このアクターはインメモリ Repository のストレージであり、ルームの状態を直列化するだけです。
+//! メッセージの採番・永続化・ブロードキャストの順序は、どのバックエンドでもルームごとの
+//! シーケンサー（`SendMessageUseCase`）が Repository をポートとして保証します。
+//! SQLite / PostgreSQL のルームはこのアクターを使わず、状態の直列化はデータベースに任せます。
+//! 入退室・名前の変更・投票などメッセージの送信以外の変更はシーケンサーを通らず、それぞれの UseCase から
+//! Repository を直接呼び出します（ブロードキャストとの順序は保証しません）。
+
+use std::sync::Arc;
+
+use tokio::sync::{mpsc, oneshot, watch};
+
+use crate::domain::{RepositoryError, Room, RoomId};
+
+/// ルームのメールボックスの容量（満杯の場合、送信側は空くまで待つ）
+pub const ROOM_MAILBOX_CAPACITY: usize = 1024;
+
+/// スナップショットを公開するまでにまとめて適用する操作の最大数
+const MAX_BATCH_OPERATIONS: usize = 64;
+
+/// 操作の結果を呼び出し元に返す関数（スナップショットを公開した後に呼ぶ）
+type Reply = Box<dyn FnOnce() + Send>;
+
+/// アクターのタスクで適用する操作
+type Operation = Box<dyn FnOnce(&mut Arc<Room>) -> Reply + Send>;
+
+/// ルームを所有するアクターへのハンドル
+#[derive(Debug)]
+pub struct RoomActor {
+    /// ルームの ID（変わらないため、アクターに問い合わせずに参照する）
+    id: RoomId,
+    /// アクターのメールボックス
+    mailbox: mpsc::Sender<Operation>,
+    /// アクターが公開する最新のルーム
+    snapshot: watch::Receiver<Arc<Room>>,
+}
+
+impl RoomActor {
+    /// ルームを所有するアクターを起動する
+    ///
+    /// Tokio ランタイム上で呼び出す必要がある。
+    pub fn spawn(room: Room) -> Self {
+        let id = room.id.clone();
+        let room = Arc::new(room);
+        let (mailbox, operations) = mpsc::channel(ROOM_MAILBOX_CAPACITY);
+        let (published, snapshot) = watch::channel(room.clone());
+        engawa_shared::task::spawn("room", run(room, operations, published));
+        Self {
+            id,
+            mailbox,
+            snapshot,
+        }
+    }
+
+    /// ルームの ID
+    pub fn id(&self) -> &RoomId {
+        &self.id
+    }
+
+    /// ルームを読み取る
+    pub async fn read<T>(
+        &self,
+        f: impl FnOnce(&Room) -> T + Send + 'static,
+    ) -> Result<T, RepositoryError>
+    where
+        T: Send + 'static,
+    {
+        self.call(move |room| f(room)).await
+    }
+
+    /// ルームを変更する（スナップショットが参照されている場合はルームを複製してから変更する）
+    pub async fn update<T>(
+        &self,
+        f: impl FnOnce(&mut Room) -> T + Send + 'static,
+    ) -> Result<T, RepositoryError>
+    where
+        T: Send + 'static,
+    {
+        self.call(move |room| f(Arc::make_mut(room))).await
+    }
+
+    /// 読み取り用の不変なスナップショットを取得する（メールボックスの操作を待たない）
+    pub fn snapshot(&self) -> Arc<Room> {
+        self.snapshot.borrow().clone()
+    }
+
+    /// 操作をメールボックスに送り、アクターが適用した結果を待つ
+    async fn call<T>(
+        &self,
+        f: impl FnOnce(&mut Arc<Room>) -> T + Send + 'static,
+    ) -> Result<T, RepositoryError>
+    where
+        T: Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        let operation: Operation = Box::new(move |room| {
+            let result = f(room);
+            Box::new(move || {
+                let _ = reply.send(result);
+            })
+        });
+        self.mailbox
+            .send(operation)
+            .await
+            .map_err(|_| stopped(&self.id))?;
+        result.await.map_err(|_| stopped(&self.id))
+    }
+}
+
+/// メールボックスに届いた操作を順に適用する（全てのハンドルが破棄されると終了する）
+///
+/// 溜まっている操作をまとめて適用し、ルームが変わっていれば公開してから結果を返す。
+async fn run(
+    mut room: Arc<Room>,
+    mut operations: mpsc::Receiver<Operation>,
+    published: watch::Sender<Arc<Room>>,
+) {
+    let mut replies = Vec::with_capacity(MAX_BATCH_OPERATIONS);
+    while let Some(operation) = operations.recv().await {
+        replies.push(operation(&mut room));
+        while replies.len() < MAX_BATCH_OPERATIONS
+            && let Ok(operation) = operations.try_recv()
+        {
+            replies.push(operation(&mut room));
+        }
+        // 変更した操作は `Arc::make_mut` で公開中のルームとは別の `Arc` にしている
+        if !Arc::ptr_eq(&room, &published.borrow()) {
+            published.send_replace(room.clone());
+        }
+        for reply in replies.drain(..) {
+            reply();
+        }
+    }
+    tracing::debug!("Room actor '{}' stopped", room.id);
+}
+
+fn stopped(id: &RoomId) -> RepositoryError {
+    RepositoryError::Storage(format!("the task of room '{}' has stopped", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ChatMessage, ClientId, MessageContent, RoomIdFactory, Timestamp};
+
+    #[tokio::test]
+    async fn test_snapshot_is_copied_on_write() {
+        // テスト項目: スナップショットは変更が無い間はルームを共有し、変更後も取得した時点の状態のまま
+        // given (前提条件):
+        let actor = RoomActor::spawn(Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(1000),
+        ));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+
+        // when (操作):
+        let before = actor.snapshot();
+        let shared = actor.snapshot();
+        actor
+            .update(move |room| {
+                let content = MessageContent::new("Hello".to_string()).unwrap();
+                room.add_message(ChatMessage::new(alice, content, Timestamp::new(2000)))
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        let after = actor.snapshot();
+
+        // then (期待する結果):
+        assert!(Arc::ptr_eq(&before, &shared));
+        assert!(before.messages.is_empty());
+        assert_eq!(after.messages.len(), 1);
+        assert_eq!(actor.read(|room| room.messages.len()).await.unwrap(), 1);
+    }
+}
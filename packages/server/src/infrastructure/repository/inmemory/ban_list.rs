@@ -0,0 +1,59 @@
+//! InMemory BanList 実装
+//!
+//! ドメイン層が定義する BanList trait の具体的な実装。
+//! BAN したクライアント ID はメモリ上に保持し、サーバーを再起動すると失われます。
+
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use crate::domain::{BanList, ClientId, RepositoryError};
+
+/// BAN したクライアントをメモリ上に保持する BanList
+#[derive(Default)]
+pub struct InMemoryBanList {
+    banned: RwLock<HashSet<ClientId>>,
+}
+
+impl InMemoryBanList {
+    /// BAN したクライアントが居ない BanList を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl BanList for InMemoryBanList {
+    async fn ban(&self, client_id: ClientId) -> Result<bool, RepositoryError> {
+        Ok(self.banned.write().await.insert(client_id))
+    }
+
+    async fn is_banned(&self, client_id: &ClientId) -> Result<bool, RepositoryError> {
+        Ok(self.banned.read().await.contains(client_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ban_is_recorded_once() {
+        // テスト項目: BAN したクライアントだけが BAN 済みになり、2 回目の BAN は false を返す
+        // given (前提条件):
+        let ban_list = InMemoryBanList::new();
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+
+        // when (操作):
+        let first = ban_list.ban(alice.clone()).await.unwrap();
+        let second = ban_list.ban(alice.clone()).await.unwrap();
+
+        // then (期待する結果):
+        assert!(first);
+        assert!(!second);
+        assert!(ban_list.is_banned(&alice).await.unwrap());
+        assert!(!ban_list.is_banned(&bob).await.unwrap());
+    }
+}
@@ -0,0 +1,175 @@
+//! InMemory IntegrationRepository 実装
+//!
+//! ドメイン層が定義する IntegrationRepository trait の具体的な実装。
+//! 連携設定はメモリ上に保持し、サーバーを再起動すると失われます。
+
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use crate::domain::{
+    Integration, IntegrationKind, IntegrationRepository, RepositoryError, RoomId, Timestamp,
+};
+
+/// 連携設定をメモリ上に保持する IntegrationRepository
+#[derive(Default)]
+pub struct InMemoryIntegrationRepository {
+    state: RwLock<State>,
+}
+
+#[derive(Default)]
+struct State {
+    /// 作成順の連携設定
+    integrations: Vec<Integration>,
+    /// 最後に採番した ID
+    last_id: u64,
+}
+
+impl InMemoryIntegrationRepository {
+    /// 連携設定が無い IntegrationRepository を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl IntegrationRepository for InMemoryIntegrationRepository {
+    async fn list(&self, room_id: &RoomId) -> Result<Vec<Integration>, RepositoryError> {
+        Ok(self
+            .state
+            .read()
+            .await
+            .integrations
+            .iter()
+            .filter(|integration| &integration.room_id == room_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_all(&self) -> Result<Vec<Integration>, RepositoryError> {
+        Ok(self.state.read().await.integrations.clone())
+    }
+
+    async fn create(
+        &self,
+        room_id: RoomId,
+        kind: IntegrationKind,
+        created_at: Timestamp,
+    ) -> Result<Integration, RepositoryError> {
+        let mut state = self.state.write().await;
+        state.last_id += 1;
+        let integration = Integration {
+            id: state.last_id,
+            room_id,
+            kind,
+            created_at,
+        };
+        state.integrations.push(integration.clone());
+        Ok(integration)
+    }
+
+    async fn update(
+        &self,
+        room_id: &RoomId,
+        id: u64,
+        kind: IntegrationKind,
+    ) -> Result<Option<Integration>, RepositoryError> {
+        let mut state = self.state.write().await;
+        Ok(state
+            .integrations
+            .iter_mut()
+            .find(|integration| integration.id == id && &integration.room_id == room_id)
+            .map(|integration| {
+                integration.kind = kind;
+                integration.clone()
+            }))
+    }
+
+    async fn delete(&self, room_id: &RoomId, id: u64) -> Result<bool, RepositoryError> {
+        let mut state = self.state.write().await;
+        let before = state.integrations.len();
+        state
+            .integrations
+            .retain(|integration| !(integration.id == id && &integration.room_id == room_id));
+        Ok(state.integrations.len() != before)
+    }
+
+    async fn find_incoming_webhook(
+        &self,
+        token: &str,
+    ) -> Result<Option<Integration>, RepositoryError> {
+        Ok(self
+            .state
+            .read()
+            .await
+            .integrations
+            .iter()
+            .find(|integration| match &integration.kind {
+                IntegrationKind::IncomingWebhook { token: existing } => existing == token,
+                _ => false,
+            })
+            .cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::RoomIdFactory;
+
+    #[tokio::test]
+    async fn test_integrations_are_scoped_to_their_room() {
+        // テスト項目: 連携設定はルームごとに取得・更新・削除され、他のルームの ID では操作できない
+        // given (前提条件):
+        let repository = InMemoryIntegrationRepository::new();
+        let room = RoomIdFactory::generate().unwrap();
+        let other = RoomIdFactory::generate().unwrap();
+        let webhook = repository
+            .create(
+                room.clone(),
+                IntegrationKind::OutgoingWebhook {
+                    url: "https://example.com/hook".to_string(),
+                },
+                Timestamp::new(1000),
+            )
+            .await
+            .unwrap();
+        let incoming = repository
+            .create(
+                other.clone(),
+                IntegrationKind::IncomingWebhook {
+                    token: "secret".to_string(),
+                },
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap();
+
+        // when (操作):
+        let updated_elsewhere = repository
+            .update(
+                &other,
+                webhook.id,
+                IntegrationKind::OutgoingWebhook {
+                    url: "https://example.com/other".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        let deleted_elsewhere = repository.delete(&room, incoming.id).await.unwrap();
+        let found = repository.find_incoming_webhook("secret").await.unwrap();
+        let deleted = repository.delete(&other, incoming.id).await.unwrap();
+
+        // then (期待する結果):
+        assert_ne!(webhook.id, incoming.id);
+        assert_eq!(updated_elsewhere, None);
+        assert!(!deleted_elsewhere);
+        assert_eq!(found, Some(incoming));
+        assert!(deleted);
+        assert_eq!(repository.list(&room).await.unwrap(), vec![webhook]);
+        assert!(repository.list(&other).await.unwrap().is_empty());
+        assert_eq!(
+            repository.find_incoming_webhook("secret").await.unwrap(),
+            None
+        );
+    }
+}
@@ -1,7 +1,16 @@
 //! InMemory Repository 実装
 //!
-//! HashMap をインメモリ DB として使用する Repository 実装。
+//! ルームごとのアクター（タスク）をインメモリ DB として使用する Repository 実装。
 
+mod actor;
+mod ban_list;
+mod integration;
+mod pending_message;
 mod room;
+mod star;
 
+pub use ban_list::InMemoryBanList;
+pub use integration::InMemoryIntegrationRepository;
+pub use pending_message::{DEFAULT_PENDING_MESSAGES, InMemoryPendingMessageStore};
 pub use room::InMemoryRoomRepository;
+pub use star::InMemoryStarRepository;
@@ -0,0 +1,128 @@
+//! InMemory PendingMessageStore 実装
+//!
+//! ドメイン層が定義する PendingMessageStore trait の具体的な実装。
+//! メールボックスはメモリ上に保持し、サーバーを再起動すると失われます。
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use crate::domain::{ClientId, PendingMessageStore, RepositoryError, Timestamp};
+
+/// メールボックスに溜めるメッセージ数の既定の上限
+pub const DEFAULT_PENDING_MESSAGES: usize = 100;
+
+/// 切断したクライアントのメールボックス
+struct Mailbox {
+    /// 有効期限（Unix タイムスタンプ、ミリ秒）
+    expires_at: i64,
+    /// 溜まったメッセージ（古い順）
+    messages: VecDeque<String>,
+}
+
+/// メールボックスをメモリ上に保持する PendingMessageStore
+pub struct InMemoryPendingMessageStore {
+    /// 切断からメールボックスを破棄するまでの時間
+    ttl: Duration,
+    /// メールボックスに溜めるメッセージ数の上限（超えた場合は古いものから捨てる）
+    capacity: usize,
+    /// クライアントごとのメールボックス
+    mailboxes: RwLock<HashMap<ClientId, Mailbox>>,
+}
+
+impl InMemoryPendingMessageStore {
+    /// メールボックスが無い PendingMessageStore を作成
+    ///
+    /// # 引数
+    ///
+    /// - `ttl`: 切断からメールボックスを破棄するまでの時間
+    /// - `capacity`: メールボックスに溜めるメッセージ数の上限
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            mailboxes: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl PendingMessageStore for InMemoryPendingMessageStore {
+    async fn open(&self, client_id: ClientId, now: Timestamp) -> Result<(), RepositoryError> {
+        let ttl = i64::try_from(self.ttl.as_millis()).unwrap_or(i64::MAX);
+        let mailbox = Mailbox {
+            expires_at: now.value().saturating_add(ttl),
+            messages: VecDeque::new(),
+        };
+        self.mailboxes.write().await.insert(client_id, mailbox);
+        Ok(())
+    }
+
+    async fn push(&self, message: &str, now: Timestamp) -> Result<(), RepositoryError> {
+        let mut mailboxes = self.mailboxes.write().await;
+        // 期限を過ぎたメールボックスはここで破棄する
+        mailboxes.retain(|_, mailbox| mailbox.expires_at > now.value());
+        for mailbox in mailboxes.values_mut() {
+            if mailbox.messages.len() >= self.capacity {
+                mailbox.messages.pop_front();
+            }
+            mailbox.messages.push_back(message.to_string());
+        }
+        Ok(())
+    }
+
+    async fn take(
+        &self,
+        client_id: &ClientId,
+        now: Timestamp,
+    ) -> Result<Vec<String>, RepositoryError> {
+        Ok(self
+            .mailboxes
+            .write()
+            .await
+            .remove(client_id)
+            .filter(|mailbox| mailbox.expires_at > now.value())
+            .map(|mailbox| mailbox.messages.into())
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mailbox_keeps_messages_until_ttl() {
+        // テスト項目: 期限内に取り出すと切断後のメッセージが上限まで古い順に返り、期限を過ぎると破棄される
+        // given (前提条件):
+        let store = InMemoryPendingMessageStore::new(Duration::from_secs(60), 2);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        store.push("before", Timestamp::new(0)).await.unwrap();
+        store
+            .open(alice.clone(), Timestamp::new(1_000))
+            .await
+            .unwrap();
+        store
+            .open(bob.clone(), Timestamp::new(1_000))
+            .await
+            .unwrap();
+
+        // when (操作):
+        for message in ["first", "second", "third"] {
+            store.push(message, Timestamp::new(2_000)).await.unwrap();
+        }
+        let delivered = store.take(&alice, Timestamp::new(3_000)).await.unwrap();
+        let taken_twice = store.take(&alice, Timestamp::new(3_000)).await.unwrap();
+        let expired = store.take(&bob, Timestamp::new(61_000)).await.unwrap();
+
+        // then (期待する結果):
+        assert_eq!(delivered, vec!["second", "third"]);
+        assert!(taken_twice.is_empty());
+        assert!(expired.is_empty());
+    }
+}
@@ -1,50 +1,106 @@
 //! InMemory Room Repository 実装
 //!
 //! ドメイン層が定義する RoomRepository trait の具体的な実装。
-//! HashMap をインメモリ DB として使用します。
+//! ルームごとのアクター（[`RoomActor`]）をインメモリ DB として使用します。
 //!
 //! ## 技術的負債
 //!
 //! 現在、ドメインモデル（`Room`）を直接ストレージとして使用しています。
-//! これは InMemory 実装では許容される妥協ですが、将来 PostgreSQL などの
-//! DBMS を実装する際は、以下の変換層が必要になります：
+//! これは InMemory 実装では許容される妥協です。SQLite / PostgreSQL の実装は
+//! `dto::database` の変換層を通してルームを復元します：
 //!
 //! ```text
-//! DB Row/JSON → RoomData (DTO) → Room (ドメインモデル)
+//! DB Row → RoomData (DTO) → Room (ドメインモデル)
 //! ```
 //!
-//! PostgreSQL 実装時に対応予定。
+//! ## 複数のルーム
+//!
+//! `new` で渡したルームを既定のルームとし、`create_room` で作成したルームと合わせた
+//! ルームの一覧を `for_room` で作った Repository 同士で共有します。
+//! ルームはそれぞれのアクターが所有し、Repository の操作はメッセージとしてアクターに送ります。
+//! アクターはストレージとしてルームの状態を直列化するだけで、採番とブロードキャストの順序は
+//! `SendMessageUseCase` のシーケンサーが担当します。
+//!
+//! ## 読み取り用のスナップショット
+//!
+//! `get_room_snapshot` はアクターが公開しているルームの不変なスナップショット（`Arc<Room>`）を
+//! メールボックスを通さずに返すため、溜まっている書き込みを待ちません。
 
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use tokio::sync::Mutex;
+use tokio::sync::RwLock;
 
+use super::actor::RoomActor;
 use crate::domain::{
-    ChatMessage, ClientId, MessageContent, Participant, RepositoryError, Room, RoomRepository,
-    Timestamp,
+    Activity, ChatMessage, ClientId, DisplayName, ForwardedFrom, MessageContent, MessageTag,
+    Participant, Poll, PollOption, RepositoryError, Room, RoomId, RoomMetadata, RoomRepository,
+    SequenceNumber, Timestamp,
 };
 
+/// 複数の Repository で共有するルーム
+type SharedRoom = Arc<RoomActor>;
+
 /// インメモリ Room Repository 実装
 ///
-/// Room ドメインモデルを保持し、ドメイン層の RoomRepository trait を実装します（依存性の逆転）。
+/// Room ドメインモデルを所有するアクターを操作し、ドメイン層の RoomRepository trait を
+/// 実装します（依存性の逆転）。
 pub struct InMemoryRoomRepository {
-    /// Room ドメインモデル
-    room: Arc<Mutex<Room>>,
+    /// この Repository が操作するルーム
+    room: SharedRoom,
+    /// 既定のルーム（削除できない）
+    default_room: SharedRoom,
+    /// `create_room` で作成したルーム（作成した順）
+    rooms: Arc<RwLock<Vec<SharedRoom>>>,
 }
 
 impl InMemoryRoomRepository {
     /// 新しい InMemoryRoomRepository を作成
-    pub fn new(room: Arc<Mutex<Room>>) -> Self {
-        Self { room }
+    ///
+    /// ルームを所有するアクターを起動するため、Tokio ランタイム上で呼び出す必要がある。
+    pub fn new(room: Room) -> Self {
+        let room = Arc::new(RoomActor::spawn(room));
+        Self {
+            room: room.clone(),
+            default_room: room,
+            rooms: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+
+    /// ID が一致するルームを探す
+    async fn find_room(&self, room_id: &RoomId) -> Option<SharedRoom> {
+        if self.default_room.id() == room_id {
+            return Some(self.default_room.clone());
+        }
+        let rooms = self.rooms.read().await;
+        rooms.iter().find(|room| room.id() == room_id).cloned()
     }
 }
 
 #[async_trait]
 impl RoomRepository for InMemoryRoomRepository {
     async fn get_room(&self) -> Result<Room, RepositoryError> {
-        let room = self.room.lock().await;
-        Ok(room.clone())
+        self.room.read(Room::clone).await
+    }
+
+    async fn get_room_metadata(&self) -> Result<RoomMetadata, RepositoryError> {
+        self.room.read(Room::metadata).await
+    }
+
+    async fn get_recent_messages(&self, limit: usize) -> Result<Vec<ChatMessage>, RepositoryError> {
+        self.room
+            .read(move |room| room.recent_messages(limit).to_vec())
+            .await
+    }
+
+    async fn get_messages_before(
+        &self,
+        before: Option<Timestamp>,
+        limit: usize,
+    ) -> Result<Vec<ChatMessage>, RepositoryError> {
+        self.room
+            .read(move |room| room.messages_before(before, limit))
+            .await
     }
 
     async fn add_participant(
@@ -52,24 +108,66 @@ impl RoomRepository for InMemoryRoomRepository {
         client_id: ClientId,
         timestamp: Timestamp,
     ) -> Result<(), RepositoryError> {
-        let participant = Participant::new(client_id.clone(), timestamp);
+        let participant = Participant::new(client_id, timestamp);
 
-        let mut room = self.room.lock().await;
-        room.add_participant(participant)
-            .map_err(|_| RepositoryError::ParticipantNotFound(client_id.as_str().to_string()))?;
+        self.room
+            .update(move |room| room.add_participant(participant))
+            .await??;
 
         Ok(())
     }
 
     async fn remove_participant(&self, client_id: &ClientId) -> Result<(), RepositoryError> {
-        let mut room = self.room.lock().await;
-        room.remove_participant(client_id);
+        let client_id = client_id.clone();
+        self.room
+            .update(move |room| room.remove_participant(&client_id))
+            .await?;
         Ok(())
     }
 
+    async fn rename_participant(
+        &self,
+        client_id: &ClientId,
+        display_name: Option<DisplayName>,
+    ) -> Result<(), RepositoryError> {
+        let id = client_id.clone();
+        if self
+            .room
+            .update(move |room| room.rename_participant(&id, display_name))
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(RepositoryError::ParticipantNotFound(
+                client_id.as_str().to_string(),
+            ))
+        }
+    }
+
+    async fn set_activity(
+        &self,
+        client_id: &ClientId,
+        activity: Option<Activity>,
+    ) -> Result<(), RepositoryError> {
+        let id = client_id.clone();
+        if self
+            .room
+            .update(move |room| room.set_activity(&id, activity))
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(RepositoryError::ParticipantNotFound(
+                client_id.as_str().to_string(),
+            ))
+        }
+    }
+
     async fn get_all_connected_client_ids(&self) -> Vec<ClientId> {
-        let room = self.room.lock().await;
-        room.participants.iter().map(|p| p.id.clone()).collect()
+        self.room
+            .read(|room| room.participants.iter().map(|p| p.id.clone()).collect())
+            .await
+            .unwrap_or_default()
     }
 
     async fn add_message(
@@ -77,22 +175,194 @@ impl RoomRepository for InMemoryRoomRepository {
         from_client_id: ClientId,
         content: MessageContent,
         timestamp: Timestamp,
-    ) -> Result<(), RepositoryError> {
-        let mut room = self.room.lock().await;
+    ) -> Result<SequenceNumber, RepositoryError> {
         let message = ChatMessage::new(from_client_id, content, timestamp);
-        room.add_message(message)
-            .map_err(|_| RepositoryError::RoomNotFound)?;
-        Ok(())
+        self.room
+            .update(move |room| room.add_message(message))
+            .await?
+            .map_err(RepositoryError::from)
+    }
+
+    async fn erase_client(
+        &self,
+        client_id: &ClientId,
+    ) -> Result<Vec<SequenceNumber>, RepositoryError> {
+        let client_id = client_id.clone();
+        self.room
+            .update(move |room| room.erase_client(&client_id))
+            .await
+    }
+
+    async fn delete_message(&self, seq: SequenceNumber) -> Result<(), RepositoryError> {
+        if self
+            .room
+            .update(move |room| room.delete_message(seq))
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(RepositoryError::MessageNotFound(seq.value()))
+        }
+    }
+
+    async fn tag_message(
+        &self,
+        seq: SequenceNumber,
+        tags: Vec<MessageTag>,
+    ) -> Result<(), RepositoryError> {
+        if self
+            .room
+            .update(move |room| room.tag_message(seq, tags))
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(RepositoryError::MessageNotFound(seq.value()))
+        }
+    }
+
+    async fn attach_poll(
+        &self,
+        seq: SequenceNumber,
+        options: Vec<PollOption>,
+    ) -> Result<(), RepositoryError> {
+        if self
+            .room
+            .update(move |room| room.attach_poll(seq, options))
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(RepositoryError::MessageNotFound(seq.value()))
+        }
+    }
+
+    async fn mark_forwarded(
+        &self,
+        seq: SequenceNumber,
+        origin: ForwardedFrom,
+    ) -> Result<(), RepositoryError> {
+        if self
+            .room
+            .update(move |room| room.mark_forwarded(seq, origin))
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(RepositoryError::MessageNotFound(seq.value()))
+        }
+    }
+
+    async fn vote(
+        &self,
+        seq: SequenceNumber,
+        voter: ClientId,
+        option: usize,
+    ) -> Result<Poll, RepositoryError> {
+        self.room
+            .update(move |room| room.vote(seq, voter, option))
+            .await??
+            .ok_or(RepositoryError::MessageNotFound(seq.value()))
+    }
+
+    async fn history_bytes(&self) -> usize {
+        self.room
+            .read(Room::history_bytes)
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn evict_history(&self, max_bytes: usize) -> usize {
+        self.room
+            .update(move |room| room.evict_history(max_bytes))
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn set_frozen(&self, frozen: bool) -> Result<(), RepositoryError> {
+        self.room.update(move |room| room.frozen = frozen).await
     }
 
     async fn count_connected_clients(&self) -> usize {
-        let room = self.room.lock().await;
-        room.participants.len()
+        self.room
+            .read(|room| room.participants.len())
+            .await
+            .unwrap_or_default()
     }
 
     async fn get_participants(&self) -> Vec<Participant> {
-        let room = self.room.lock().await;
-        room.participants.clone()
+        self.room
+            .read(|room| room.participants.clone())
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn create_room(&self, room: Room) -> Result<(), RepositoryError> {
+        // 確認と追加の間に同じ ID のルームが作成されないよう、書き込みのロックを取ってから確認する
+        let mut rooms = self.rooms.write().await;
+        if self.default_room.id() == &room.id || rooms.iter().any(|r| r.id() == &room.id) {
+            return Err(RepositoryError::RoomAlreadyExists(
+                room.id.as_str().to_string(),
+            ));
+        }
+        rooms.push(Arc::new(RoomActor::spawn(room)));
+        Ok(())
+    }
+
+    async fn delete_room(&self, room_id: &RoomId) -> Result<(), RepositoryError> {
+        if self.room.id() == room_id || self.default_room.id() == room_id {
+            return Err(RepositoryError::RoomNotDeletable(
+                room_id.as_str().to_string(),
+            ));
+        }
+        let mut rooms = self.rooms.write().await;
+        let before = rooms.len();
+        // 最後のハンドルが破棄されるとルームのアクターも終了する
+        rooms.retain(|room| room.id() != room_id);
+        if rooms.len() == before {
+            return Err(RepositoryError::RoomNotFound);
+        }
+        Ok(())
+    }
+
+    async fn get_room_by_id(&self, room_id: &RoomId) -> Result<Room, RepositoryError> {
+        let room = self
+            .find_room(room_id)
+            .await
+            .ok_or(RepositoryError::RoomNotFound)?;
+        room.read(Room::clone).await
+    }
+
+    async fn get_room_snapshot(&self, room_id: &RoomId) -> Result<Arc<Room>, RepositoryError> {
+        let room = self
+            .find_room(room_id)
+            .await
+            .ok_or(RepositoryError::RoomNotFound)?;
+        Ok(room.snapshot())
+    }
+
+    async fn get_room_ids(&self) -> Vec<RoomId> {
+        let mut ids = vec![self.default_room.id().clone()];
+        let rooms = self.rooms.read().await;
+        ids.extend(rooms.iter().map(|room| room.id().clone()));
+        ids
+    }
+
+    async fn for_room(&self, room_id: &RoomId) -> Result<Arc<dyn RoomRepository>, RepositoryError> {
+        let room = self
+            .find_room(room_id)
+            .await
+            .ok_or(RepositoryError::RoomNotFound)?;
+        Ok(Arc::new(Self {
+            room,
+            default_room: self.default_room.clone(),
+            rooms: self.rooms.clone(),
+        }))
+    }
+
+    // アクターが操作を処理すれば応答可能（滞留や停止は呼び出し側のタイムアウトで検出）
+    async fn ping(&self) -> Result<(), RepositoryError> {
+        self.room.read(|_| ()).await
     }
 }
 
@@ -124,11 +394,10 @@ mod tests {
     // ========================================
 
     fn create_test_repository() -> InMemoryRoomRepository {
-        let room = Arc::new(Mutex::new(Room::new(
+        InMemoryRoomRepository::new(Room::new(
             RoomIdFactory::generate().expect("Failed to generate RoomId"),
             Timestamp::new(get_jst_timestamp()),
-        )));
-        InMemoryRoomRepository::new(room)
+        ))
     }
 
     #[tokio::test]
@@ -261,4 +530,97 @@ mod tests {
         assert_eq!(room.messages.len(), 1);
         assert_eq!(room.messages[0].from, client_id);
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_snapshot_does_not_wait_for_writes() {
+        // テスト項目: スナップショットは変更が無い間は共有され、書き込みの処理中は待たずに直前のものが返される
+        // given (前提条件):
+        let repo = Arc::new(create_test_repository());
+        let room_id = repo.default_room.id().clone();
+        let first = repo.get_room_snapshot(&room_id).await.unwrap();
+        // アクターが処理中の操作を止めておく
+        let (started, stalling) = tokio::sync::oneshot::channel();
+        let (release, released) = std::sync::mpsc::channel::<()>();
+        let stalled = {
+            let repo = repo.clone();
+            tokio::spawn(async move {
+                repo.default_room
+                    .update(move |_| {
+                        started.send(()).unwrap();
+                        released.recv().unwrap()
+                    })
+                    .await
+                    .unwrap()
+            })
+        };
+        stalling.await.unwrap();
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let content = MessageContent::new("Hello".to_string()).unwrap();
+        let write = {
+            let repo = repo.clone();
+            tokio::spawn(async move {
+                repo.add_message(alice, content, Timestamp::new(get_jst_timestamp()))
+                    .await
+                    .unwrap()
+            })
+        };
+
+        // when (操作):
+        let shared = repo.get_room_snapshot(&room_id).await.unwrap();
+        let while_writing = tokio::time::timeout(
+            std::time::Duration::from_millis(100),
+            repo.get_room_snapshot(&room_id),
+        )
+        .await;
+        release.send(()).unwrap();
+        stalled.await.unwrap();
+        write.await.unwrap();
+        let after = repo.get_room_snapshot(&room_id).await.unwrap();
+
+        // then (期待する結果):
+        assert!(Arc::ptr_eq(&first, &shared));
+        assert!(Arc::ptr_eq(&first, &while_writing.unwrap().unwrap()));
+        assert_eq!(after.messages.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_writes_are_serialized() {
+        // テスト項目: 同時に送ったメッセージの追加はアクターが 1 件ずつ処理し、連番が重複しない
+        // given (前提条件):
+        let repo = Arc::new(create_test_repository());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+
+        // when (操作):
+        let writers: Vec<_> = (0..50)
+            .map(|i| {
+                let repo = repo.clone();
+                let alice = alice.clone();
+                tokio::spawn(async move {
+                    let content = MessageContent::new(format!("message {}", i)).unwrap();
+                    repo.add_message(alice, content, Timestamp::new(get_jst_timestamp()))
+                        .await
+                        .unwrap()
+                })
+            })
+            .collect();
+        let mut seqs = Vec::new();
+        for writer in writers {
+            seqs.push(writer.await.unwrap().value());
+        }
+        seqs.sort_unstable();
+        seqs.dedup();
+
+        // then (期待する結果):
+        assert_eq!(seqs.len(), 50);
+        assert_eq!(repo.get_room().await.unwrap().messages.len(), 50);
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        // テスト項目: 全ての Repository に共通の振る舞いを満たす
+        crate::infrastructure::repository::conformance::run(|room| async move {
+            Arc::new(InMemoryRoomRepository::new(room)) as Arc<dyn RoomRepository>
+        })
+        .await;
+    }
 }
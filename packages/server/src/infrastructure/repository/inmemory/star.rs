@@ -0,0 +1,127 @@
+//! InMemory StarRepository 実装
+//!
+//! ドメイン層が定義する StarRepository trait の具体的な実装。
+//! スターはメモリ上に保持し、サーバーを再起動すると失われます。
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use crate::domain::{ClientId, RepositoryError, RoomId, SequenceNumber, Star, StarRepository};
+
+/// スターをメモリ上に保持する StarRepository
+#[derive(Default)]
+pub struct InMemoryStarRepository {
+    /// クライアントごとのスター（スターを付けた順）
+    stars: RwLock<HashMap<ClientId, Vec<Star>>>,
+}
+
+impl InMemoryStarRepository {
+    /// スターが無い StarRepository を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl StarRepository for InMemoryStarRepository {
+    async fn add(&self, star: Star) -> Result<bool, RepositoryError> {
+        let mut stars = self.stars.write().await;
+        let starred = stars.entry(star.client_id.clone()).or_default();
+        if starred
+            .iter()
+            .any(|existing| existing.room_id == star.room_id && existing.seq == star.seq)
+        {
+            return Ok(false);
+        }
+        starred.push(star);
+        Ok(true)
+    }
+
+    async fn remove(
+        &self,
+        client_id: &ClientId,
+        room_id: &RoomId,
+        seq: SequenceNumber,
+    ) -> Result<bool, RepositoryError> {
+        let mut stars = self.stars.write().await;
+        let Some(starred) = stars.get_mut(client_id) else {
+            return Ok(false);
+        };
+        let before = starred.len();
+        starred.retain(|star| !(&star.room_id == room_id && star.seq == seq));
+        let removed = starred.len() != before;
+        if starred.is_empty() {
+            stars.remove(client_id);
+        }
+        Ok(removed)
+    }
+
+    async fn list(&self, client_id: &ClientId) -> Result<Vec<Star>, RepositoryError> {
+        Ok(self
+            .stars
+            .read()
+            .await
+            .get(client_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn remove_all(&self, client_id: &ClientId) -> Result<usize, RepositoryError> {
+        Ok(self
+            .stars
+            .write()
+            .await
+            .remove(client_id)
+            .map_or(0, |stars| stars.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{RoomIdFactory, Timestamp};
+
+    #[tokio::test]
+    async fn test_stars_are_kept_per_client_and_message() {
+        // テスト項目: スターはクライアントとメッセージの組ごとに 1 つ保存され、付けた順に取得・外せる
+        // given (前提条件):
+        let repository = InMemoryStarRepository::new();
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let room = RoomIdFactory::generate().unwrap();
+        let star = |client_id: &ClientId, seq: u64, starred_at: i64| Star {
+            client_id: client_id.clone(),
+            room_id: room.clone(),
+            seq: SequenceNumber::new(seq),
+            starred_at: Timestamp::new(starred_at),
+        };
+
+        // when (操作):
+        let first = repository.add(star(&alice, 2, 1000)).await.unwrap();
+        let again = repository.add(star(&alice, 2, 3000)).await.unwrap();
+        repository.add(star(&alice, 1, 2000)).await.unwrap();
+        repository.add(star(&bob, 2, 2000)).await.unwrap();
+        let removed = repository
+            .remove(&alice, &room, SequenceNumber::new(1))
+            .await
+            .unwrap();
+        let not_starred = repository
+            .remove(&alice, &room, SequenceNumber::new(1))
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert!(first);
+        assert!(!again);
+        assert!(removed);
+        assert!(!not_starred);
+        assert_eq!(
+            repository.list(&alice).await.unwrap(),
+            vec![star(&alice, 2, 1000)]
+        );
+        assert_eq!(repository.remove_all(&bob).await.unwrap(), 1);
+        assert!(repository.list(&bob).await.unwrap().is_empty());
+    }
+}
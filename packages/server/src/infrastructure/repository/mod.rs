@@ -3,6 +3,26 @@
 //! ドメイン層が定義する Repository trait の具体的な実装を提供します。
 //! UseCase 層は trait（ドメイン層）に依存し、この実装に直接依存しません（依存性の逆転）。
 
+#[cfg(test)]
+pub mod conformance;
 pub mod inmemory;
+#[cfg(feature = "postgres")]
+pub mod postgresql;
+pub mod router;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod wal;
 
-pub use inmemory::InMemoryRoomRepository;
+pub use inmemory::{
+    DEFAULT_PENDING_MESSAGES, InMemoryBanList, InMemoryIntegrationRepository,
+    InMemoryPendingMessageStore, InMemoryRoomRepository, InMemoryStarRepository,
+};
+#[cfg(feature = "postgres")]
+pub use postgresql::{PostgresRoomRepository, PostgresStore};
+pub use router::RoomRepositoryRouter;
+#[cfg(feature = "sqlite")]
+pub use sqlite::{SqliteRoomRepository, SqliteStore};
+pub use wal::{WalRoomRepository, WalWriter, WriteAheadLog};
+
+/// PostgreSQL の接続プールの既定の最大接続数
+pub const DEFAULT_DB_POOL_SIZE: u32 = 10;
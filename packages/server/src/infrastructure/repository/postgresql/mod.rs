@@ -1 +1,8 @@
-// TODO
+//! PostgreSQL Repository 実装
+//!
+//! ルームとメッセージ履歴を PostgreSQL に保存し、再起動後も復元できる Repository 実装。
+//! スキーマは `migrations/postgres` のマイグレーションで管理します（起動時に未適用のものを適用する）。
+
+mod room;
+
+pub use room::{PostgresRoomRepository, PostgresStore};
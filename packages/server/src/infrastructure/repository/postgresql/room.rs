@@ -0,0 +1,882 @@
+//! PostgreSQL に書き込んでから結果を返す Room Repository 実装
+//!
+//! ## 責務
+//!
+//! - ルームの作成・削除、メッセージの追加・タグ付け・削除、投票と票、転送元を PostgreSQL に書き込む
+//! - 起動時に PostgreSQL からルーム（既定のルーム・作成したルーム・メッセージ履歴・シーケンス番号）を復元
+//!
+//! ## 設計ノート
+//!
+//! `SqliteRoomRepository` と同じく、読み取りと参加者の管理は内側の Repository（インメモリ）が行い、
+//! 変更は内側の Repository に適用した後、PostgreSQL に書き込むまで呼び出し元に返りません。
+//! 読み込んだ行は `RoomData`（DTO）に集めてから `Room`（ドメインモデル）に変換します。
+//!
+//! 接続はプールで管理し、同時に書き込む操作の数はプールの大きさまでです。
+//! 1 つのデータベースを使うサーバーは 1 つだけの前提です（既定のルームを 1 つだけ保存する）。
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sqlx::{PgPool, postgres::PgPoolOptions};
+
+use crate::{
+    domain::{
+        Activity, ChatMessage, ClientId, DisplayName, ForwardedFrom, MessageContent, MessageTag,
+        Participant, Poll, PollOption, RepositoryError, Room, RoomId, RoomMetadata, RoomRepository,
+        SequenceNumber, Timestamp,
+    },
+    infrastructure::{
+        dto::database::{MessageData, RoomData},
+        error::DatabaseError,
+        migration,
+    },
+};
+
+/// ルームとメッセージ履歴を保存する PostgreSQL データベース
+pub struct PostgresStore {
+    pool: PgPool,
+    /// 復元したルームの容量（参加者数・メッセージ数）
+    capacity: (usize, usize),
+}
+
+impl PostgresStore {
+    /// データベースに接続し、既定のルームを復元する
+    ///
+    /// 未適用のマイグレーションを適用してから、最大 `pool_size` 本の接続プールを作る。
+    /// 既定のルームが無い場合は `new_room` で作成したルームを保存する。復元したルームの容量は
+    /// `new_room` のルームに合わせる。
+    ///
+    /// # Errors
+    ///
+    /// データベースに接続できない場合、マイグレーションに失敗した場合、
+    /// または保存されたルームを Domain Model に変換できない場合
+    pub async fn connect(
+        database_url: &str,
+        pool_size: u32,
+        new_room: impl FnOnce() -> Room,
+    ) -> Result<(Self, Room), DatabaseError> {
+        migration::apply(database_url).await?;
+        let pool = PgPoolOptions::new()
+            .max_connections(pool_size)
+            .connect(database_url)
+            .await?;
+        let template = new_room();
+        let store = Self {
+            pool,
+            capacity: (template.participant_capacity, template.message_capacity),
+        };
+
+        let row: Option<RoomData> = sqlx::query_as(
+            "SELECT id, created_at, class, last_seq, participant_capacity, message_capacity FROM rooms WHERE is_default = TRUE",
+        )
+        .fetch_optional(&store.pool)
+        .await?;
+        let room = match row {
+            Some(row) => store.load_room(row).await?,
+            None => {
+                store.insert_room(&template, true).await?;
+                template
+            }
+        };
+        Ok((store, room))
+    }
+
+    /// 作成したルーム（既定のルーム以外）を作成順に復元し、`repository` に追加する
+    ///
+    /// 復元したルームの容量は既定のルームに合わせる。
+    ///
+    /// # Returns
+    ///
+    /// 復元したルームの数
+    pub async fn restore_rooms(
+        &self,
+        repository: &dyn RoomRepository,
+    ) -> Result<usize, DatabaseError> {
+        let rows: Vec<RoomData> = sqlx::query_as(
+            "SELECT id, created_at, class, last_seq, participant_capacity, message_capacity FROM rooms WHERE is_default = FALSE \
+             ORDER BY created_at, position",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let count = rows.len();
+        for row in rows {
+            let room = self.load_room(row).await?;
+            let room_id = room.id.as_str().to_string();
+            repository
+                .create_room(room)
+                .await
+                .map_err(|e| DatabaseError::Corrupt {
+                    room_id,
+                    reason: e.to_string(),
+                })?;
+        }
+        Ok(count)
+    }
+
+    /// ルームの行にメッセージ履歴・タグ・投票を読み込み、Domain Model に変換する
+    async fn load_room(&self, mut data: RoomData) -> Result<Room, DatabaseError> {
+        let mut messages: Vec<MessageData> = sqlx::query_as(
+            "SELECT seq, client_id, content, timestamp FROM messages WHERE room_id = $1 \
+             ORDER BY seq DESC LIMIT $2",
+        )
+        .bind(&data.id)
+        .bind(data.message_capacity.unwrap_or(self.capacity.1 as i64))
+        .fetch_all(&self.pool)
+        .await?;
+        messages.reverse();
+        data.messages = messages;
+
+        let tags: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT seq, tag FROM message_tags WHERE room_id = $1 ORDER BY seq, position",
+        )
+        .bind(&data.id)
+        .fetch_all(&self.pool)
+        .await?;
+        for (seq, tag) in tags {
+            // 容量を超えて復元しなかったメッセージのタグは無視する
+            data.add_tag(seq, tag);
+        }
+        let options: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT seq, label FROM poll_options WHERE room_id = $1 ORDER BY seq, position",
+        )
+        .bind(&data.id)
+        .fetch_all(&self.pool)
+        .await?;
+        for (seq, label) in options {
+            data.add_poll_option(seq, label);
+        }
+        let votes: Vec<(i64, String, i32)> = sqlx::query_as(
+            "SELECT seq, client_id, option FROM poll_votes WHERE room_id = $1 \
+             ORDER BY seq, position",
+        )
+        .bind(&data.id)
+        .fetch_all(&self.pool)
+        .await?;
+        for (seq, client_id, option) in votes {
+            data.add_poll_vote(seq, client_id, option.into());
+        }
+        let forwarded: Vec<(i64, String, String, i64)> = sqlx::query_as(
+            "SELECT seq, origin_room_id, origin_client_id, origin_timestamp \
+             FROM forwarded_messages WHERE room_id = $1",
+        )
+        .bind(&data.id)
+        .fetch_all(&self.pool)
+        .await?;
+        for (seq, room_id, client_id, timestamp) in forwarded {
+            data.set_forwarded_from(seq, room_id, client_id, timestamp);
+        }
+
+        data.into_room(self.capacity.0, self.capacity.1)
+    }
+
+    /// ルームをメッセージ履歴ごと保存する
+    async fn insert_room(&self, room: &Room, is_default: bool) -> Result<(), DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO rooms \
+             (id, created_at, is_default, class, last_seq, participant_capacity, message_capacity) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(room.id.as_str())
+        .bind(room.created_at.value())
+        .bind(is_default)
+        .bind(room.class.as_str())
+        .bind(room.last_seq.value() as i64)
+        // The default room follows the capacities the server is started with
+        .bind((!is_default).then_some(room.participant_capacity as i64))
+        .bind((!is_default).then_some(room.message_capacity as i64))
+        .execute(&mut *tx)
+        .await?;
+        for message in &room.messages {
+            sqlx::query(
+                "INSERT INTO messages (room_id, seq, client_id, content, timestamp) \
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(room.id.as_str())
+            .bind(message.seq.value() as i64)
+            .bind(message.from.as_str())
+            .bind(message.content.as_str())
+            .bind(message.timestamp.value())
+            .execute(&mut *tx)
+            .await?;
+            for tag in &message.tags {
+                insert_tag(&mut tx, &room.id, message.seq, tag).await?;
+            }
+            if let Some(poll) = &message.poll {
+                insert_poll(&mut tx, &room.id, message.seq, &poll.options).await?;
+                for vote in &poll.votes {
+                    upsert_vote(&mut tx, &room.id, message.seq, &vote.voter, vote.option).await?;
+                }
+            }
+            if let Some(origin) = &message.forwarded_from {
+                upsert_forwarded(&mut tx, &room.id, message.seq, origin).await?;
+            }
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// ルームをメッセージ履歴ごと削除する
+    async fn delete_room(&self, room_id: &RoomId) -> Result<(), DatabaseError> {
+        sqlx::query("DELETE FROM rooms WHERE id = $1")
+            .bind(room_id.as_str())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// メッセージを保存し、ルームの最後のシーケンス番号を更新する
+    async fn insert_message(
+        &self,
+        room_id: &RoomId,
+        seq: SequenceNumber,
+        from_client_id: &ClientId,
+        content: &MessageContent,
+        timestamp: Timestamp,
+    ) -> Result<(), DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO messages (room_id, seq, client_id, content, timestamp) \
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(room_id.as_str())
+        .bind(seq.value() as i64)
+        .bind(from_client_id.as_str())
+        .bind(content.as_str())
+        .bind(timestamp.value())
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE rooms SET last_seq = $1 WHERE id = $2")
+            .bind(seq.value() as i64)
+            .bind(room_id.as_str())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// メッセージのタグを保存する（保存済みのタグは無視する）
+    async fn insert_tags(
+        &self,
+        room_id: &RoomId,
+        seq: SequenceNumber,
+        tags: &[MessageTag],
+    ) -> Result<(), DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        for tag in tags {
+            insert_tag(&mut tx, room_id, seq, tag).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// メッセージを投票にする（保存済みの投票は置き換える）
+    async fn insert_poll(
+        &self,
+        room_id: &RoomId,
+        seq: SequenceNumber,
+        options: &[PollOption],
+    ) -> Result<(), DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        insert_poll(&mut tx, room_id, seq, options).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// メッセージの転送元を保存する（保存済みの転送元は置き換える）
+    async fn insert_forwarded(
+        &self,
+        room_id: &RoomId,
+        seq: SequenceNumber,
+        origin: &ForwardedFrom,
+    ) -> Result<(), DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        upsert_forwarded(&mut tx, room_id, seq, origin).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 投票の票を保存する（クライアントの票は置き換える）
+    async fn insert_vote(
+        &self,
+        room_id: &RoomId,
+        seq: SequenceNumber,
+        voter: &ClientId,
+        option: usize,
+    ) -> Result<(), DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        upsert_vote(&mut tx, room_id, seq, voter, option).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// クライアントが送信したメッセージと票を削除する（メッセージのタグと投票も削除される）
+    async fn erase_client(
+        &self,
+        room_id: &RoomId,
+        client_id: &ClientId,
+    ) -> Result<(), DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM messages WHERE room_id = $1 AND client_id = $2")
+            .bind(room_id.as_str())
+            .bind(client_id.as_str())
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM poll_votes WHERE room_id = $1 AND client_id = $2")
+            .bind(room_id.as_str())
+            .bind(client_id.as_str())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// メッセージを削除する（タグと投票も削除される）
+    async fn delete_message(
+        &self,
+        room_id: &RoomId,
+        seq: SequenceNumber,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("DELETE FROM messages WHERE room_id = $1 AND seq = $2")
+            .bind(room_id.as_str())
+            .bind(seq.value() as i64)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// データベースに問い合わせできるかを確認する
+    async fn check(&self) -> Result<(), DatabaseError> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+}
+
+/// メッセージのタグを 1 つ保存する（保存済みのタグは無視する）
+async fn insert_tag(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    room_id: &RoomId,
+    seq: SequenceNumber,
+    tag: &MessageTag,
+) -> Result<(), DatabaseError> {
+    sqlx::query(
+        "INSERT INTO message_tags (room_id, seq, tag) VALUES ($1, $2, $3) \
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(room_id.as_str())
+    .bind(seq.value() as i64)
+    .bind(tag.as_str())
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// メッセージの投票の選択肢を保存する（保存済みの投票は票ごと置き換える）
+async fn insert_poll(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    room_id: &RoomId,
+    seq: SequenceNumber,
+    options: &[PollOption],
+) -> Result<(), DatabaseError> {
+    for table in ["poll_votes", "poll_options"] {
+        sqlx::query(&format!(
+            "DELETE FROM {} WHERE room_id = $1 AND seq = $2",
+            table
+        ))
+        .bind(room_id.as_str())
+        .bind(seq.value() as i64)
+        .execute(&mut **tx)
+        .await?;
+    }
+    for (position, option) in options.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO poll_options (room_id, seq, position, label) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(room_id.as_str())
+        .bind(seq.value() as i64)
+        .bind(position as i32)
+        .bind(option.as_str())
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+/// メッセージの転送元を保存する（保存済みの転送元は置き換える）
+async fn upsert_forwarded(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    room_id: &RoomId,
+    seq: SequenceNumber,
+    origin: &ForwardedFrom,
+) -> Result<(), DatabaseError> {
+    sqlx::query(
+        "INSERT INTO forwarded_messages \
+         (room_id, seq, origin_room_id, origin_client_id, origin_timestamp) \
+         VALUES ($1, $2, $3, $4, $5) \
+         ON CONFLICT (room_id, seq) DO UPDATE SET origin_room_id = excluded.origin_room_id, \
+         origin_client_id = excluded.origin_client_id, \
+         origin_timestamp = excluded.origin_timestamp",
+    )
+    .bind(room_id.as_str())
+    .bind(seq.value() as i64)
+    .bind(origin.room_id.as_str())
+    .bind(origin.from.as_str())
+    .bind(origin.timestamp.value())
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// 投票の票を 1 つ保存する（クライアントの票は置き換える）
+async fn upsert_vote(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    room_id: &RoomId,
+    seq: SequenceNumber,
+    voter: &ClientId,
+    option: usize,
+) -> Result<(), DatabaseError> {
+    sqlx::query(
+        "INSERT INTO poll_votes (room_id, seq, client_id, option) VALUES ($1, $2, $3, $4) \
+         ON CONFLICT (room_id, seq, client_id) DO UPDATE SET option = excluded.option",
+    )
+    .bind(room_id.as_str())
+    .bind(seq.value() as i64)
+    .bind(voter.as_str())
+    .bind(option as i32)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// PostgreSQL に書き込んでから結果を返す Room Repository
+#[derive(Clone)]
+pub struct PostgresRoomRepository {
+    /// 委譲先の Repository
+    inner: Arc<dyn RoomRepository>,
+    /// 書き込み先のデータベース
+    store: Arc<PostgresStore>,
+}
+
+impl PostgresRoomRepository {
+    /// 新しい PostgresRoomRepository を作成
+    ///
+    /// # 引数
+    ///
+    /// - `inner`: PostgreSQL から復元したルームを保持する Repository
+    /// - `store`: 書き込み先のデータベース
+    pub fn new(inner: Arc<dyn RoomRepository>, store: Arc<PostgresStore>) -> Self {
+        Self { inner, store }
+    }
+
+    /// 書き込みに失敗した場合のエラー
+    fn storage_error(&self, e: DatabaseError) -> RepositoryError {
+        tracing::error!("Failed to write to PostgreSQL: {}", e);
+        RepositoryError::Storage(e.to_string())
+    }
+
+    /// 操作対象のルームの ID
+    async fn room_id(&self) -> Result<RoomId, RepositoryError> {
+        Ok(self.inner.get_room_metadata().await?.id)
+    }
+}
+
+#[async_trait]
+impl RoomRepository for PostgresRoomRepository {
+    async fn get_room(&self) -> Result<Room, RepositoryError> {
+        self.inner.get_room().await
+    }
+
+    async fn get_room_metadata(&self) -> Result<RoomMetadata, RepositoryError> {
+        self.inner.get_room_metadata().await
+    }
+
+    async fn get_recent_messages(&self, limit: usize) -> Result<Vec<ChatMessage>, RepositoryError> {
+        self.inner.get_recent_messages(limit).await
+    }
+
+    async fn get_messages_before(
+        &self,
+        before: Option<Timestamp>,
+        limit: usize,
+    ) -> Result<Vec<ChatMessage>, RepositoryError> {
+        self.inner.get_messages_before(before, limit).await
+    }
+
+    async fn add_participant(
+        &self,
+        client_id: ClientId,
+        timestamp: Timestamp,
+    ) -> Result<(), RepositoryError> {
+        self.inner.add_participant(client_id, timestamp).await
+    }
+
+    async fn remove_participant(&self, client_id: &ClientId) -> Result<(), RepositoryError> {
+        self.inner.remove_participant(client_id).await
+    }
+
+    async fn rename_participant(
+        &self,
+        client_id: &ClientId,
+        display_name: Option<DisplayName>,
+    ) -> Result<(), RepositoryError> {
+        self.inner.rename_participant(client_id, display_name).await
+    }
+
+    async fn set_activity(
+        &self,
+        client_id: &ClientId,
+        activity: Option<Activity>,
+    ) -> Result<(), RepositoryError> {
+        self.inner.set_activity(client_id, activity).await
+    }
+
+    async fn get_all_connected_client_ids(&self) -> Vec<ClientId> {
+        self.inner.get_all_connected_client_ids().await
+    }
+
+    async fn add_message(
+        &self,
+        from_client_id: ClientId,
+        content: MessageContent,
+        timestamp: Timestamp,
+    ) -> Result<SequenceNumber, RepositoryError> {
+        let room_id = self.room_id().await?;
+        let seq = self
+            .inner
+            .add_message(from_client_id.clone(), content.clone(), timestamp)
+            .await?;
+        self.store
+            .insert_message(&room_id, seq, &from_client_id, &content, timestamp)
+            .await
+            .map_err(|e| self.storage_error(e))?;
+        Ok(seq)
+    }
+
+    async fn erase_client(
+        &self,
+        client_id: &ClientId,
+    ) -> Result<Vec<SequenceNumber>, RepositoryError> {
+        let room_id = self.room_id().await?;
+        let erased = self.inner.erase_client(client_id).await?;
+        self.store
+            .erase_client(&room_id, client_id)
+            .await
+            .map_err(|e| self.storage_error(e))?;
+        Ok(erased)
+    }
+
+    async fn delete_message(&self, seq: SequenceNumber) -> Result<(), RepositoryError> {
+        let room_id = self.room_id().await?;
+        self.inner.delete_message(seq).await?;
+        self.store
+            .delete_message(&room_id, seq)
+            .await
+            .map_err(|e| self.storage_error(e))
+    }
+
+    async fn tag_message(
+        &self,
+        seq: SequenceNumber,
+        tags: Vec<MessageTag>,
+    ) -> Result<(), RepositoryError> {
+        let room_id = self.room_id().await?;
+        self.inner.tag_message(seq, tags.clone()).await?;
+        self.store
+            .insert_tags(&room_id, seq, &tags)
+            .await
+            .map_err(|e| self.storage_error(e))
+    }
+
+    async fn attach_poll(
+        &self,
+        seq: SequenceNumber,
+        options: Vec<PollOption>,
+    ) -> Result<(), RepositoryError> {
+        let room_id = self.room_id().await?;
+        self.inner.attach_poll(seq, options.clone()).await?;
+        self.store
+            .insert_poll(&room_id, seq, &options)
+            .await
+            .map_err(|e| self.storage_error(e))
+    }
+
+    async fn mark_forwarded(
+        &self,
+        seq: SequenceNumber,
+        origin: ForwardedFrom,
+    ) -> Result<(), RepositoryError> {
+        let room_id = self.room_id().await?;
+        self.inner.mark_forwarded(seq, origin.clone()).await?;
+        self.store
+            .insert_forwarded(&room_id, seq, &origin)
+            .await
+            .map_err(|e| self.storage_error(e))
+    }
+
+    async fn vote(
+        &self,
+        seq: SequenceNumber,
+        voter: ClientId,
+        option: usize,
+    ) -> Result<Poll, RepositoryError> {
+        let room_id = self.room_id().await?;
+        let poll = self.inner.vote(seq, voter.clone(), option).await?;
+        self.store
+            .insert_vote(&room_id, seq, &voter, option)
+            .await
+            .map_err(|e| self.storage_error(e))?;
+        Ok(poll)
+    }
+
+    async fn history_bytes(&self) -> usize {
+        self.inner.history_bytes().await
+    }
+
+    // 削除はメモリ上の履歴のみ（PostgreSQL には残る）
+    async fn evict_history(&self, max_bytes: usize) -> usize {
+        self.inner.evict_history(max_bytes).await
+    }
+
+    async fn set_frozen(&self, frozen: bool) -> Result<(), RepositoryError> {
+        self.inner.set_frozen(frozen).await
+    }
+
+    async fn count_connected_clients(&self) -> usize {
+        self.inner.count_connected_clients().await
+    }
+
+    async fn get_participants(&self) -> Vec<Participant> {
+        self.inner.get_participants().await
+    }
+
+    async fn create_room(&self, room: Room) -> Result<(), RepositoryError> {
+        self.inner.create_room(room.clone()).await?;
+        self.store
+            .insert_room(&room, false)
+            .await
+            .map_err(|e| self.storage_error(e))
+    }
+
+    async fn delete_room(&self, room_id: &RoomId) -> Result<(), RepositoryError> {
+        self.inner.delete_room(room_id).await?;
+        self.store
+            .delete_room(room_id)
+            .await
+            .map_err(|e| self.storage_error(e))
+    }
+
+    async fn get_room_by_id(&self, room_id: &RoomId) -> Result<Room, RepositoryError> {
+        self.inner.get_room_by_id(room_id).await
+    }
+
+    async fn get_room_snapshot(&self, room_id: &RoomId) -> Result<Arc<Room>, RepositoryError> {
+        self.inner.get_room_snapshot(room_id).await
+    }
+
+    async fn get_room_ids(&self) -> Vec<RoomId> {
+        self.inner.get_room_ids().await
+    }
+
+    // 作成したルームも PostgreSQL に書き込む Repository として返す
+    async fn for_room(&self, room_id: &RoomId) -> Result<Arc<dyn RoomRepository>, RepositoryError> {
+        let inner = self.inner.for_room(room_id).await?;
+        Ok(Arc::new(Self::new(inner, self.store.clone())))
+    }
+
+    async fn ping(&self) -> Result<(), RepositoryError> {
+        self.inner.ping().await?;
+        self.store
+            .check()
+            .await
+            .map_err(|e| RepositoryError::Storage(e.to_string()))
+    }
+}
+
+// PostgreSQL が必要なため既定では実行しない。テストごとにデータベースを作成して削除する:
+// ENGAWA_TEST_POSTGRES_URL=postgres://postgres@localhost:5432 \
+//     cargo test -p engawa-server --features postgres -- --ignored
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{domain::RoomIdFactory, infrastructure::repository::InMemoryRoomRepository};
+    use sqlx::{Connection, PgConnection};
+    use tokio::sync::Mutex;
+
+    /// テストごとに作成するデータベース
+    struct TestDatabase {
+        /// データベースを作成するサーバーの URL（データベース名なし）
+        server_url: String,
+        name: String,
+    }
+
+    impl TestDatabase {
+        async fn create() -> Self {
+            let server_url = std::env::var("ENGAWA_TEST_POSTGRES_URL")
+                .expect("ENGAWA_TEST_POSTGRES_URL must be set to run the PostgreSQL tests");
+            let server_url = server_url.trim_end_matches('/').to_string();
+            let name = format!("engawa_test_{}", uuid::Uuid::new_v4().simple());
+            let mut conn = PgConnection::connect(&format!("{}/postgres", server_url))
+                .await
+                .unwrap();
+            sqlx::query(&format!("CREATE DATABASE {}", name))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            Self { server_url, name }
+        }
+
+        fn url(&self) -> String {
+            format!("{}/{}", self.server_url, self.name)
+        }
+
+        async fn drop(self) {
+            let mut conn = PgConnection::connect(&format!("{}/postgres", self.server_url))
+                .await
+                .unwrap();
+            sqlx::query(&format!("DROP DATABASE {} WITH (FORCE)", self.name))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+    }
+
+    fn new_room() -> Room {
+        Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000))
+    }
+
+    async fn open_repository(url: &str) -> (PostgresRoomRepository, Room) {
+        let (store, room) = PostgresStore::connect(url, 2, new_room).await.unwrap();
+        let inner = Arc::new(InMemoryRoomRepository::new(room.clone()));
+        store.restore_rooms(inner.as_ref()).await.unwrap();
+        (PostgresRoomRepository::new(inner, Arc::new(store)), room)
+    }
+
+    async fn send(repository: &dyn RoomRepository, from: &str, content: &str) -> SequenceNumber {
+        repository
+            .add_message(
+                ClientId::new(from.to_string()).unwrap(),
+                MessageContent::new(content.to_string()).unwrap(),
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL at ENGAWA_TEST_POSTGRES_URL"]
+    async fn test_reconnect_restores_room_messages_and_tags() {
+        // テスト項目: 再起動後にルーム ID・メッセージ・タグ・シーケンス番号が復元される
+        // given (前提条件):
+        let database = TestDatabase::create().await;
+        let (repository, room) = open_repository(&database.url()).await;
+        send(&repository, "alice", "hello").await;
+        let seq = send(&repository, "bob", "deploy").await;
+        let tags = vec![
+            MessageTag::new("keyword:deploy".to_string()).unwrap(),
+            MessageTag::new("keyword:bob".to_string()).unwrap(),
+        ];
+        repository.tag_message(seq, tags.clone()).await.unwrap();
+        drop(repository);
+
+        // when (操作):
+        let (repository, recovered) = open_repository(&database.url()).await;
+
+        // then (期待する結果):
+        assert_eq!(recovered.id, room.id);
+        assert_eq!(recovered.messages.len(), 2);
+        assert_eq!(recovered.messages[0].content.as_str(), "hello");
+        assert_eq!(recovered.messages[1].tags, tags);
+        assert_eq!(recovered.last_seq, SequenceNumber::new(2));
+        assert_eq!(
+            send(&repository, "alice", "again").await,
+            SequenceNumber::new(3)
+        );
+        drop(repository);
+        database.drop().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL at ENGAWA_TEST_POSTGRES_URL"]
+    async fn test_reconnect_restores_created_rooms() {
+        // テスト項目: 作成したルームとそのメッセージが作成順に復元され、削除したルームは復元されない
+        // given (前提条件):
+        let database = TestDatabase::create().await;
+        let (repository, _) = open_repository(&database.url()).await;
+        let rooms = [new_room(), new_room(), new_room()];
+        for room in &rooms {
+            repository.create_room(room.clone()).await.unwrap();
+        }
+        let scoped = repository.for_room(&rooms[0].id).await.unwrap();
+        send(scoped.as_ref(), "alice", "in another room").await;
+        repository.delete_room(&rooms[1].id).await.unwrap();
+        drop((repository, scoped));
+
+        // when (操作):
+        let (repository, recovered) = open_repository(&database.url()).await;
+
+        // then (期待する結果):
+        assert_eq!(
+            repository.get_room_ids().await,
+            vec![recovered.id, rooms[0].id.clone(), rooms[2].id.clone()]
+        );
+        let room = repository.get_room_by_id(&rooms[0].id).await.unwrap();
+        assert_eq!(room.messages.len(), 1);
+        drop(repository);
+        database.drop().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL at ENGAWA_TEST_POSTGRES_URL"]
+    async fn test_erased_client_is_removed_from_database() {
+        // テスト項目: クライアントのデータを削除するとデータベースから消え、番号は再利用されない
+        // given (前提条件):
+        let database = TestDatabase::create().await;
+        let (repository, _) = open_repository(&database.url()).await;
+        let seq = send(&repository, "alice", "secret").await;
+        let tag = MessageTag::new("keyword:secret".to_string()).unwrap();
+        repository.tag_message(seq, vec![tag]).await.unwrap();
+        send(&repository, "bob", "hello").await;
+
+        // when (操作):
+        repository
+            .erase_client(&ClientId::new("alice".to_string()).unwrap())
+            .await
+            .unwrap();
+        drop(repository);
+        let (repository, recovered) = open_repository(&database.url()).await;
+
+        // then (期待する結果):
+        assert_eq!(recovered.messages.len(), 1);
+        assert_eq!(recovered.messages[0].content.as_str(), "hello");
+        assert_eq!(
+            send(&repository, "bob", "again").await,
+            SequenceNumber::new(3)
+        );
+        drop(repository);
+        database.drop().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires PostgreSQL at ENGAWA_TEST_POSTGRES_URL"]
+    async fn test_conformance() {
+        // テスト項目: 全ての Repository に共通の振る舞いを満たす
+        let databases = Arc::new(Mutex::new(Vec::new()));
+        crate::infrastructure::repository::conformance::run(|room| {
+            let databases = databases.clone();
+            async move {
+                let database = TestDatabase::create().await;
+                let (store, room) = PostgresStore::connect(&database.url(), 2, move || room)
+                    .await
+                    .unwrap();
+                databases.lock().await.push(database);
+                let inner = Arc::new(InMemoryRoomRepository::new(room));
+                Arc::new(PostgresRoomRepository::new(inner, Arc::new(store)))
+                    as Arc<dyn RoomRepository>
+            }
+        })
+        .await;
+        for database in databases.lock().await.drain(..) {
+            database.drop().await;
+        }
+    }
+}
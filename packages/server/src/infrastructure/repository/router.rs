@@ -0,0 +1,359 @@
+//! ルームのクラスで保存先を振り分ける Room Repository 実装
+//!
+//! ## 責務
+//!
+//! - 作成したルームを、ルームのクラス（`RoomClass`）に対応する Repository（バックエンド）に保存する
+//! - ID を指定した操作を、そのルームを保存しているバックエンドに委譲する
+//!
+//! ## 設計ノート
+//!
+//! 既定のルームの操作と、振り分け先を設定していないクラスのルームは既定の Repository が扱います。
+//! バックエンドは `create_room` と `for_room` でルームを管理する Repository で、バックエンド自身の
+//! ルーム（既定のルーム）はルーム一覧にも ID を指定した操作にも現れません。
+//!
+//! ルームの ID はバックエンドをまたいで一意である前提です（ID は UUID で採番する）。
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::domain::{
+    Activity, ChatMessage, ClientId, DisplayName, ForwardedFrom, MessageContent, MessageTag,
+    Participant, Poll, PollOption, RepositoryError, Room, RoomClass, RoomId, RoomMetadata,
+    RoomRepository, SequenceNumber, Timestamp,
+};
+
+/// ルームのクラスで保存先を振り分ける Room Repository
+pub struct RoomRepositoryRouter {
+    /// 既定のルームと、振り分け先を設定していないクラスのルームを保存する Repository
+    default: Arc<dyn RoomRepository>,
+    /// ルームのクラスごとの保存先
+    routes: Vec<(RoomClass, Arc<dyn RoomRepository>)>,
+}
+
+impl RoomRepositoryRouter {
+    /// 新しい RoomRepositoryRouter を作成
+    ///
+    /// # 引数
+    ///
+    /// - `default`: 既定のルームを保持する Repository
+    pub fn new(default: Arc<dyn RoomRepository>) -> Self {
+        Self {
+            default,
+            routes: Vec::new(),
+        }
+    }
+
+    /// `class` のルームを `backend` に保存する（同じクラスを再び指定した場合は置き換える）
+    pub fn with_route(mut self, class: RoomClass, backend: Arc<dyn RoomRepository>) -> Self {
+        self.routes.retain(|(routed, _)| *routed != class);
+        self.routes.push((class, backend));
+        self
+    }
+
+    /// `class` のルームを保存する Repository
+    fn backend_for(&self, class: RoomClass) -> &Arc<dyn RoomRepository> {
+        self.routes
+            .iter()
+            .find(|(routed, _)| *routed == class)
+            .map_or(&self.default, |(_, backend)| backend)
+    }
+
+    /// ルームを保存している Repository を探す（見つからない場合は `RoomNotFound`）
+    async fn backend_of(
+        &self,
+        room_id: &RoomId,
+    ) -> Result<&Arc<dyn RoomRepository>, RepositoryError> {
+        match self.default.for_room(room_id).await {
+            Ok(_) => return Ok(&self.default),
+            Err(RepositoryError::RoomNotFound) => {}
+            Err(e) => return Err(e),
+        }
+        for (_, backend) in &self.routes {
+            // バックエンド自身のルームは公開しない
+            if backend.get_room_metadata().await?.id == *room_id {
+                continue;
+            }
+            match backend.for_room(room_id).await {
+                Ok(_) => return Ok(backend),
+                Err(RepositoryError::RoomNotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Err(RepositoryError::RoomNotFound)
+    }
+}
+
+#[async_trait]
+impl RoomRepository for RoomRepositoryRouter {
+    async fn get_room(&self) -> Result<Room, RepositoryError> {
+        self.default.get_room().await
+    }
+
+    async fn get_room_metadata(&self) -> Result<RoomMetadata, RepositoryError> {
+        self.default.get_room_metadata().await
+    }
+
+    async fn get_recent_messages(&self, limit: usize) -> Result<Vec<ChatMessage>, RepositoryError> {
+        self.default.get_recent_messages(limit).await
+    }
+
+    async fn get_messages_before(
+        &self,
+        before: Option<Timestamp>,
+        limit: usize,
+    ) -> Result<Vec<ChatMessage>, RepositoryError> {
+        self.default.get_messages_before(before, limit).await
+    }
+
+    async fn add_participant(
+        &self,
+        client_id: ClientId,
+        timestamp: Timestamp,
+    ) -> Result<(), RepositoryError> {
+        self.default.add_participant(client_id, timestamp).await
+    }
+
+    async fn remove_participant(&self, client_id: &ClientId) -> Result<(), RepositoryError> {
+        self.default.remove_participant(client_id).await
+    }
+
+    async fn rename_participant(
+        &self,
+        client_id: &ClientId,
+        display_name: Option<DisplayName>,
+    ) -> Result<(), RepositoryError> {
+        self.default
+            .rename_participant(client_id, display_name)
+            .await
+    }
+
+    async fn set_activity(
+        &self,
+        client_id: &ClientId,
+        activity: Option<Activity>,
+    ) -> Result<(), RepositoryError> {
+        self.default.set_activity(client_id, activity).await
+    }
+
+    async fn get_all_connected_client_ids(&self) -> Vec<ClientId> {
+        self.default.get_all_connected_client_ids().await
+    }
+
+    async fn add_message(
+        &self,
+        from_client_id: ClientId,
+        content: MessageContent,
+        timestamp: Timestamp,
+    ) -> Result<SequenceNumber, RepositoryError> {
+        self.default
+            .add_message(from_client_id, content, timestamp)
+            .await
+    }
+
+    async fn erase_client(
+        &self,
+        client_id: &ClientId,
+    ) -> Result<Vec<SequenceNumber>, RepositoryError> {
+        self.default.erase_client(client_id).await
+    }
+
+    async fn delete_message(&self, seq: SequenceNumber) -> Result<(), RepositoryError> {
+        self.default.delete_message(seq).await
+    }
+
+    async fn tag_message(
+        &self,
+        seq: SequenceNumber,
+        tags: Vec<MessageTag>,
+    ) -> Result<(), RepositoryError> {
+        self.default.tag_message(seq, tags).await
+    }
+
+    async fn attach_poll(
+        &self,
+        seq: SequenceNumber,
+        options: Vec<PollOption>,
+    ) -> Result<(), RepositoryError> {
+        self.default.attach_poll(seq, options).await
+    }
+
+    async fn mark_forwarded(
+        &self,
+        seq: SequenceNumber,
+        origin: ForwardedFrom,
+    ) -> Result<(), RepositoryError> {
+        self.default.mark_forwarded(seq, origin).await
+    }
+
+    async fn vote(
+        &self,
+        seq: SequenceNumber,
+        voter: ClientId,
+        option: usize,
+    ) -> Result<Poll, RepositoryError> {
+        self.default.vote(seq, voter, option).await
+    }
+
+    async fn history_bytes(&self) -> usize {
+        self.default.history_bytes().await
+    }
+
+    async fn evict_history(&self, max_bytes: usize) -> usize {
+        self.default.evict_history(max_bytes).await
+    }
+
+    async fn set_frozen(&self, frozen: bool) -> Result<(), RepositoryError> {
+        self.default.set_frozen(frozen).await
+    }
+
+    async fn count_connected_clients(&self) -> usize {
+        self.default.count_connected_clients().await
+    }
+
+    async fn get_participants(&self) -> Vec<Participant> {
+        self.default.get_participants().await
+    }
+
+    async fn create_room(&self, room: Room) -> Result<(), RepositoryError> {
+        // 他のバックエンドに同じ ID のルームがあれば作成しない
+        match self.backend_of(&room.id).await {
+            Ok(_) => Err(RepositoryError::RoomAlreadyExists(
+                room.id.as_str().to_string(),
+            )),
+            Err(RepositoryError::RoomNotFound) => {
+                self.backend_for(room.class).create_room(room).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn delete_room(&self, room_id: &RoomId) -> Result<(), RepositoryError> {
+        self.backend_of(room_id).await?.delete_room(room_id).await
+    }
+
+    async fn get_room_by_id(&self, room_id: &RoomId) -> Result<Room, RepositoryError> {
+        self.backend_of(room_id)
+            .await?
+            .get_room_by_id(room_id)
+            .await
+    }
+
+    async fn get_room_snapshot(&self, room_id: &RoomId) -> Result<Arc<Room>, RepositoryError> {
+        self.backend_of(room_id)
+            .await?
+            .get_room_snapshot(room_id)
+            .await
+    }
+
+    async fn get_room_ids(&self) -> Vec<RoomId> {
+        let mut ids = self.default.get_room_ids().await;
+        for (_, backend) in &self.routes {
+            // 先頭はバックエンド自身のルーム
+            ids.extend(backend.get_room_ids().await.into_iter().skip(1));
+        }
+        ids
+    }
+
+    async fn for_room(&self, room_id: &RoomId) -> Result<Arc<dyn RoomRepository>, RepositoryError> {
+        self.backend_of(room_id).await?.for_room(room_id).await
+    }
+
+    // 全ての保存先が応答する必要がある
+    async fn ping(&self) -> Result<(), RepositoryError> {
+        self.default.ping().await?;
+        for (_, backend) in &self.routes {
+            backend.ping().await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::RoomIdFactory,
+        infrastructure::repository::{InMemoryRoomRepository, conformance},
+    };
+
+    fn in_memory(room: Room) -> Arc<dyn RoomRepository> {
+        Arc::new(InMemoryRoomRepository::new(room))
+    }
+
+    fn new_room(class: RoomClass) -> Room {
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        room.class = class;
+        room
+    }
+
+    #[tokio::test]
+    async fn test_rooms_are_stored_in_the_backend_of_their_class() {
+        // テスト項目: 作成したルームはクラスに対応するバックエンドに保存され、ID で操作できる
+        // given (前提条件):
+        let default_room = new_room(RoomClass::Ephemeral);
+        let default = in_memory(default_room.clone());
+        let persistent = in_memory(new_room(RoomClass::Ephemeral));
+        let router = RoomRepositoryRouter::new(default.clone())
+            .with_route(RoomClass::Persistent, persistent.clone());
+        let ephemeral_room = new_room(RoomClass::Ephemeral);
+        let persistent_room = new_room(RoomClass::Persistent);
+
+        // when (操作):
+        router.create_room(ephemeral_room.clone()).await.unwrap();
+        router.create_room(persistent_room.clone()).await.unwrap();
+        let scoped = router.for_room(&persistent_room.id).await.unwrap();
+        scoped
+            .add_message(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new("hello".to_string()).unwrap(),
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap();
+        let duplicate = router.create_room(persistent_room.clone()).await;
+
+        // then (期待する結果):
+        assert!(default.get_room_by_id(&ephemeral_room.id).await.is_ok());
+        assert!(default.get_room_by_id(&persistent_room.id).await.is_err());
+        let stored = persistent
+            .get_room_by_id(&persistent_room.id)
+            .await
+            .unwrap();
+        assert_eq!(stored.messages.len(), 1);
+        assert_eq!(
+            router.get_room_ids().await,
+            vec![default_room.id, ephemeral_room.id, persistent_room.id],
+        );
+        assert!(matches!(
+            duplicate,
+            Err(RepositoryError::RoomAlreadyExists(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_backend_room_is_not_exposed() {
+        // テスト項目: バックエンド自身のルームはルーターから参照できない
+        // given (前提条件):
+        let backend_room = new_room(RoomClass::Ephemeral);
+        let router = RoomRepositoryRouter::new(in_memory(new_room(RoomClass::Ephemeral)))
+            .with_route(RoomClass::Persistent, in_memory(backend_room.clone()));
+
+        // when (操作):
+        let result = router.get_room_by_id(&backend_room.id).await;
+
+        // then (期待する結果):
+        assert!(matches!(result, Err(RepositoryError::RoomNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        conformance::run(|room| async move {
+            Arc::new(RoomRepositoryRouter::new(in_memory(room)).with_route(
+                RoomClass::Persistent,
+                in_memory(new_room(RoomClass::Ephemeral)),
+            )) as Arc<dyn RoomRepository>
+        })
+        .await;
+    }
+}
@@ -0,0 +1,8 @@
+//! SQLite Repository 実装
+//!
+//! ルームとメッセージ履歴を SQLite に保存し、再起動後も復元できる Repository 実装。
+//! スキーマは `migrations/sqlite` のマイグレーションで管理します（起動時に未適用のものを適用する）。
+
+mod room;
+
+pub use room::{SqliteRoomRepository, SqliteStore};
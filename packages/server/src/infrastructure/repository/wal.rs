@@ -0,0 +1,1175 @@
+//! Write-ahead log（WAL）付き Room Repository 実装
+//!
+//! ## 責務
+//!
+//! - ルームの作成・メッセージの追加・メッセージへのタグ付け・投票・転送元をドメインイベントとして WAL（JSON Lines）に追記
+//! - 起動時に WAL を再生してルーム（ID・メッセージ履歴・シーケンス番号・投票の票・転送元）を復元
+//!
+//! ## 設計ノート
+//!
+//! `WalRoomRepository` は WAL に追記して `fsync` してから内側の Repository（インメモリ）に
+//! 変更を適用します。適用に失敗した場合（凍結中・容量超過など）は追記したレコードを切り捨てるため、
+//! メモリ上の状態は常に WAL に記録された内容と一致します。送信はシーケンサーが `add_message` の
+//! 完了を待ってからブロードキャストするため、参加者に届いたメッセージは必ず WAL に残っています。
+//!
+//! 参加者は接続に紐づく状態のため記録しません（再起動後はクライアントが再接続します）。
+//! 書き込み途中でクラッシュした場合に備え、最終行が壊れている場合のみ切り捨てて復旧します。
+//!
+//! クライアントのデータを削除する場合は、そのクライアントの `message-added` レコードを
+//! `message-erased`（シーケンス番号のみ）に置き換え、票（`poll-voted`）を取り除いて WAL を
+//! 書き直し、内容をディスクに残しません。
+//!
+//! リスナーのハンドオーバー中は WAL を封印（`seal`）し、新しいプロセスが再生した後に
+//! 古いプロセスが追記しないようにします。封印中の送信は永続化に失敗し、配信されません。
+//!
+//! `create_room` で作成したルームは `room-added` レコードとして同じ WAL に記録し、そのルームの
+//! レコードにはルーム ID（`room`）を付けます。再起動時はルームごと復元します。ルームを削除すると
+//! そのルームのレコードを取り除いて WAL を書き直すため、再生時に存在しないルームのメッセージは
+//! 残りません。
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use async_trait::async_trait;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::{AsyncSeekExt, AsyncWriteExt},
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::{
+    domain::{
+        Activity, ChatMessage, ClientId, DisplayName, ForwardedFrom, MessageContent, MessagePolicy,
+        MessageTag, Participant, Poll, PollOption, RepositoryError, Room, RoomId, RoomMetadata,
+        RoomRepository, RoomSlug, SequenceNumber, Timestamp, ValueObjectError,
+    },
+    infrastructure::{dto::wal::WalRecord, error::WalError},
+};
+
+/// Write-ahead log ファイル
+pub struct WriteAheadLog {
+    path: PathBuf,
+    file: Mutex<File>,
+    /// 封印中は追記しない
+    sealed: AtomicBool,
+    /// 最初に記録したルーム（レコードにルーム ID を付けない）の ID
+    room_id: RoomId,
+}
+
+impl WriteAheadLog {
+    /// WAL を開き、記録されたルームを復元する
+    ///
+    /// 最初に記録したルームと、`create_room` で作成したルームを返す。
+    /// ファイルが無い（空の）場合は `new_room` で作成したルームを記録する。
+    /// 最初のルームの容量は `new_room` のルームに合わせる。
+    ///
+    /// # Errors
+    ///
+    /// ファイルの読み書きに失敗した場合、または最終行以外のレコードが壊れている場合
+    pub async fn open(
+        path: impl AsRef<Path>,
+        new_room: impl FnOnce() -> Room,
+    ) -> Result<(Self, Room, Vec<Room>), WalError> {
+        let path = path.as_ref().to_path_buf();
+        let contents = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let (records, valid_len) = parse_records(&contents)?;
+        if valid_len < contents.len() {
+            tracing::warn!(
+                "Discarding incomplete last record of WAL {}",
+                path.display()
+            );
+        }
+
+        let template = new_room();
+        let (room, rooms) = if records.is_empty() {
+            (template, Vec::new())
+        } else {
+            replay(&records, &template)?
+        };
+
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(&path)
+            .await?;
+        file.set_len(valid_len as u64).await?;
+        let mut file = file;
+        file.seek(std::io::SeekFrom::End(0)).await?;
+        let wal = Self {
+            path,
+            file: Mutex::new(file),
+            sealed: AtomicBool::new(false),
+            room_id: room.id.clone(),
+        };
+
+        if records.is_empty() {
+            wal.append(&WalRecord::RoomCreated {
+                room_id: room.id.as_str().to_string(),
+                created_at: room.created_at.value(),
+            })
+            .await?;
+        }
+        Ok((wal, room, rooms))
+    }
+
+    /// WAL のパス
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 以降の追記を拒否する（別のプロセスに WAL を引き継ぐ前に呼ぶ）
+    ///
+    /// 書き込み中のレコードがあれば、その完了を待ってから返る。
+    pub async fn seal(&self) {
+        let _file = self.file.lock().await;
+        self.sealed.store(true, Ordering::SeqCst);
+    }
+
+    /// 封印を解除する（引き継ぎに失敗した場合）
+    pub fn unseal(&self) {
+        self.sealed.store(false, Ordering::SeqCst);
+    }
+
+    /// レコードを追記し、ディスクに書き込まれるまで待つ
+    pub async fn append(&self, record: &WalRecord) -> Result<(), WalError> {
+        self.writer().await?.append(record).await
+    }
+
+    /// 追記の権利を取得する
+    ///
+    /// 取得している間は他の追記と封印が待たされるため、取得後に記録した変更は
+    /// 必ず同じ順序で適用できる。
+    pub async fn writer(&self) -> Result<WalWriter<'_>, WalError> {
+        let file = self.file.lock().await;
+        if self.sealed.load(Ordering::SeqCst) {
+            return Err(WalError::Sealed);
+        }
+        Ok(WalWriter {
+            path: &self.path,
+            file,
+            appended_from: None,
+        })
+    }
+
+    /// 追記できる状態か（封印されておらず、ファイルにアクセスできるか）を確認する
+    pub async fn check(&self) -> Result<(), WalError> {
+        let writer = self.writer().await?;
+        writer.file.metadata().await?;
+        Ok(())
+    }
+}
+
+/// WAL への追記の権利
+pub struct WalWriter<'a> {
+    path: &'a Path,
+    file: MutexGuard<'a, File>,
+    /// 直前の追記の前のファイルの長さ
+    appended_from: Option<u64>,
+}
+
+impl WalWriter<'_> {
+    /// レコードを追記し、ディスクに書き込まれるまで待つ
+    pub async fn append(&mut self, record: &WalRecord) -> Result<(), WalError> {
+        let mut line = serde_json::to_string(record).unwrap();
+        line.push('\n');
+        self.appended_from = Some(self.file.metadata().await?.len());
+        self.file.write_all(line.as_bytes()).await?;
+        self.file.flush().await?;
+        self.file.sync_data().await?;
+        Ok(())
+    }
+
+    /// 直前の追記（書きかけの場合も含む）を取り消す
+    pub async fn revert(&mut self) -> Result<(), WalError> {
+        if let Some(len) = self.appended_from.take() {
+            self.file.set_len(len).await?;
+            self.file.seek(std::io::SeekFrom::End(0)).await?;
+            self.file.sync_data().await?;
+        }
+        Ok(())
+    }
+
+    /// 記録済みのレコードを書き換え、WAL を置き換える
+    ///
+    /// 書き換えたレコードを一時ファイルに書き込んでから置き換えるため、途中で失敗しても
+    /// 元の WAL は壊れない。
+    pub async fn rewrite(
+        mut self,
+        rewrite: impl FnOnce(Vec<WalRecord>) -> Vec<WalRecord>,
+    ) -> Result<(), WalError> {
+        let contents = tokio::fs::read_to_string(self.path).await?;
+        let (records, _) = parse_records(&contents)?;
+        let mut rewritten = String::new();
+        for record in rewrite(records) {
+            rewritten.push_str(&serde_json::to_string(&record).unwrap());
+            rewritten.push('\n');
+        }
+
+        let mut temp_name = self.path.as_os_str().to_owned();
+        temp_name.push(".tmp");
+        let temp_path = PathBuf::from(temp_name);
+        let mut temp = File::create(&temp_path).await?;
+        temp.write_all(rewritten.as_bytes()).await?;
+        temp.sync_all().await?;
+        drop(temp);
+        tokio::fs::rename(&temp_path, self.path).await?;
+
+        *self.file = OpenOptions::new().append(true).open(self.path).await?;
+        Ok(())
+    }
+}
+
+/// ルーム（`None` は最初のルーム）のクライアントの `message-added` レコードを
+/// `message-erased` に置き換え、そのタグ付けとクライアントの票を取り除く
+fn erase_client_records(
+    records: Vec<WalRecord>,
+    room: Option<&str>,
+    client_id: &str,
+) -> Vec<WalRecord> {
+    erase_records(records, room, |_, from| from == client_id)
+        .into_iter()
+        .filter(|record| {
+            record.room() != room
+                || !matches!(record, WalRecord::PollVoted { client_id: voter, .. } if voter == client_id)
+        })
+        .collect()
+}
+
+/// ルーム（`None` は最初のルーム）の `erase` に一致する `message-added` レコードを
+/// `message-erased` に置き換え、そのタグ付け・投票・転送元を取り除く
+fn erase_records(
+    records: Vec<WalRecord>,
+    room: Option<&str>,
+    erase: impl Fn(u64, &str) -> bool,
+) -> Vec<WalRecord> {
+    let mut erased = Vec::new();
+    records
+        .into_iter()
+        .filter_map(|record| {
+            if record.room() != room {
+                return Some(record);
+            }
+            match record {
+                WalRecord::MessageAdded {
+                    room,
+                    seq,
+                    client_id: ref from,
+                    ..
+                } if erase(seq, from) => {
+                    erased.push(seq);
+                    Some(WalRecord::MessageErased { room, seq })
+                }
+                WalRecord::MessageTagged { seq, .. }
+                | WalRecord::PollAttached { seq, .. }
+                | WalRecord::PollVoted { seq, .. }
+                | WalRecord::MessageForwarded { seq, .. }
+                    if erased.contains(&seq) =>
+                {
+                    None
+                }
+                record => Some(record),
+            }
+        })
+        .collect()
+}
+
+/// 行ごとにレコードを読み、読み取れたレコードと有効な部分の長さを返す
+fn parse_records(contents: &str) -> Result<(Vec<WalRecord>, usize), WalError> {
+    let mut records = Vec::new();
+    let mut offset = 0;
+    let lines: Vec<&str> = contents.split_inclusive('\n').collect();
+    for (index, line) in lines.iter().enumerate() {
+        let is_last = index + 1 == lines.len();
+        match serde_json::from_str::<WalRecord>(line.trim_end()) {
+            // 改行で終わっていない最終行は書き込み途中とみなす
+            Ok(record) if line.ends_with('\n') => records.push(record),
+            _ if is_last => break,
+            Ok(_) => unreachable!("only the last line can lack a newline"),
+            Err(e) => {
+                return Err(WalError::Corrupt {
+                    line: index + 1,
+                    reason: e.to_string(),
+                });
+            }
+        }
+        offset += line.len();
+    }
+    Ok((records, offset))
+}
+
+/// レコードを再生して最初のルームと作成されたルームを復元する
+fn replay(records: &[WalRecord], template: &Room) -> Result<(Room, Vec<Room>), WalError> {
+    let corrupt = |line: usize, reason: String| WalError::Corrupt { line, reason };
+
+    let mut room = match &records[0] {
+        WalRecord::RoomCreated {
+            room_id,
+            created_at,
+        } => Room::with_capacity(
+            RoomId::new(room_id.clone()).map_err(|e| corrupt(1, e.to_string()))?,
+            Timestamp::new(*created_at),
+            template.participant_capacity,
+            template.message_capacity,
+        ),
+        _ => return Err(corrupt(1, "the first record must be room-created".into())),
+    };
+    let mut rooms: Vec<Room> = Vec::new();
+
+    for (index, record) in records.iter().enumerate().skip(1) {
+        let line = index + 1;
+        if let WalRecord::RoomAdded { room_id, .. } = record {
+            if *room_id == room.id.as_str() || rooms.iter().any(|r| r.id.as_str() == room_id) {
+                return Err(corrupt(line, format!("room {} added twice", room_id)));
+            }
+            rooms.push(restore_room(record).map_err(|reason| corrupt(line, reason))?);
+            continue;
+        }
+        let target = match record.room() {
+            None => &mut room,
+            Some(room_id) => rooms
+                .iter_mut()
+                .find(|r| r.id.as_str() == room_id)
+                .ok_or_else(|| corrupt(line, format!("record of unknown room {}", room_id)))?,
+        };
+        apply_record(target, record).map_err(|reason| corrupt(line, reason))?;
+    }
+    Ok((room, rooms))
+}
+
+/// `room-added` レコードからルームを作成する
+fn restore_room(record: &WalRecord) -> Result<Room, String> {
+    let WalRecord::RoomAdded {
+        room_id,
+        created_at,
+        slug,
+        locale,
+        class,
+        participant_capacity,
+        message_capacity,
+        max_length,
+        filters,
+    } = record
+    else {
+        unreachable!("only room-added records create rooms");
+    };
+    let mut room = Room::with_capacity(
+        RoomId::new(room_id.clone()).map_err(|e| e.to_string())?,
+        Timestamp::new(*created_at),
+        *participant_capacity,
+        *message_capacity,
+    );
+    room.slug = slug
+        .clone()
+        .map(RoomSlug::new)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    room.locale = locale
+        .parse()
+        .map_err(|e: ValueObjectError| e.to_string())?;
+    room.class = class.parse().map_err(|e: ValueObjectError| e.to_string())?;
+    room.message_policy = MessagePolicy {
+        max_length: *max_length,
+        filters: filters.clone(),
+    };
+    Ok(room)
+}
+
+/// ルームのレコードを適用する
+fn apply_record(room: &mut Room, record: &WalRecord) -> Result<(), String> {
+    match record {
+        WalRecord::MessageAdded {
+            seq,
+            client_id,
+            content,
+            timestamp,
+            ..
+        } => {
+            let message = ChatMessage::new(
+                ClientId::new(client_id.clone()).map_err(|e| e.to_string())?,
+                MessageContent::new(content.clone()).map_err(|e| e.to_string())?,
+                Timestamp::new(*timestamp),
+            );
+            let added = room.add_message(message).map_err(|e| e.to_string())?;
+            if added.value() != *seq {
+                return Err(format!("expected message {} but found {}", added, seq));
+            }
+        }
+        WalRecord::MessageTagged { seq, tags, .. } => {
+            let tags = tags
+                .iter()
+                .map(|tag| MessageTag::new(tag.clone()))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?;
+            if !room.tag_message(SequenceNumber::new(*seq), tags) {
+                return Err(format!("tagged unknown message {}", seq));
+            }
+        }
+        WalRecord::PollAttached { seq, options, .. } => {
+            let options = options
+                .iter()
+                .map(|option| PollOption::new(option.clone()))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?;
+            if !room.attach_poll(SequenceNumber::new(*seq), options) {
+                return Err(format!("poll on unknown message {}", seq));
+            }
+        }
+        WalRecord::MessageForwarded {
+            seq,
+            room_id,
+            client_id,
+            timestamp,
+            ..
+        } => {
+            let origin = ForwardedFrom {
+                room_id: RoomId::new(room_id.clone()).map_err(|e| e.to_string())?,
+                from: ClientId::new(client_id.clone()).map_err(|e| e.to_string())?,
+                timestamp: Timestamp::new(*timestamp),
+            };
+            if !room.mark_forwarded(SequenceNumber::new(*seq), origin) {
+                return Err(format!("forwarded unknown message {}", seq));
+            }
+        }
+        WalRecord::PollVoted {
+            seq,
+            client_id,
+            option,
+            ..
+        } => {
+            let voter = ClientId::new(client_id.clone()).map_err(|e| e.to_string())?;
+            match room.vote(SequenceNumber::new(*seq), voter, *option) {
+                Ok(Some(_)) => {}
+                Ok(None) => return Err(format!("vote on unknown message {}", seq)),
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+        WalRecord::MessageErased { seq, .. } => {
+            // 削除されたメッセージの番号は再利用しない
+            let expected = room.last_seq.next();
+            if expected.value() != *seq {
+                return Err(format!("expected message {} but found {}", expected, seq));
+            }
+            room.last_seq = expected;
+        }
+        WalRecord::RoomCreated { .. } => return Err("unexpected room-created".into()),
+        WalRecord::RoomAdded { .. } => unreachable!("room-added records are replayed separately"),
+    }
+    Ok(())
+}
+
+/// WAL に追記してから変更を適用する Room Repository
+#[derive(Clone)]
+pub struct WalRoomRepository {
+    /// 委譲先の Repository
+    inner: Arc<dyn RoomRepository>,
+    /// 追記先の WAL
+    wal: Arc<WriteAheadLog>,
+    /// 操作するルームの ID
+    room_id: RoomId,
+}
+
+impl WalRoomRepository {
+    /// 新しい WalRoomRepository を作成
+    ///
+    /// # 引数
+    ///
+    /// - `inner`: WAL から復元したルームを保持する Repository
+    /// - `wal`: 追記先の WAL
+    pub fn new(inner: Arc<dyn RoomRepository>, wal: Arc<WriteAheadLog>) -> Self {
+        let room_id = wal.room_id.clone();
+        Self {
+            inner,
+            wal,
+            room_id,
+        }
+    }
+
+    /// レコードに付けるルーム ID（最初のルームの場合は付けない）
+    fn record_room(&self) -> Option<String> {
+        (self.room_id != self.wal.room_id).then(|| self.room_id.as_str().to_string())
+    }
+
+    fn storage_error(&self, e: WalError) -> RepositoryError {
+        tracing::error!("Failed to write WAL {}: {}", self.wal.path().display(), e);
+        RepositoryError::Storage(e.to_string())
+    }
+
+    async fn writer(&self) -> Result<WalWriter<'_>, RepositoryError> {
+        self.wal.writer().await.map_err(|e| self.storage_error(e))
+    }
+
+    /// レコードを追記してディスクに書き込んでから、`apply` で内側の Repository に適用する
+    ///
+    /// 追記または適用に失敗した場合は追記を取り消す。
+    async fn write_ahead<T>(
+        &self,
+        mut writer: WalWriter<'_>,
+        record: &WalRecord,
+        apply: impl Future<Output = Result<T, RepositoryError>>,
+    ) -> Result<T, RepositoryError> {
+        let result = match writer.append(record).await {
+            Ok(()) => apply.await,
+            Err(e) => Err(self.storage_error(e)),
+        };
+        if result.is_err() {
+            writer.revert().await.map_err(|e| self.storage_error(e))?;
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl RoomRepository for WalRoomRepository {
+    async fn get_room(&self) -> Result<Room, RepositoryError> {
+        self.inner.get_room().await
+    }
+
+    async fn get_room_metadata(&self) -> Result<RoomMetadata, RepositoryError> {
+        self.inner.get_room_metadata().await
+    }
+
+    async fn get_recent_messages(&self, limit: usize) -> Result<Vec<ChatMessage>, RepositoryError> {
+        self.inner.get_recent_messages(limit).await
+    }
+
+    async fn get_messages_before(
+        &self,
+        before: Option<Timestamp>,
+        limit: usize,
+    ) -> Result<Vec<ChatMessage>, RepositoryError> {
+        self.inner.get_messages_before(before, limit).await
+    }
+
+    async fn add_participant(
+        &self,
+        client_id: ClientId,
+        timestamp: Timestamp,
+    ) -> Result<(), RepositoryError> {
+        self.inner.add_participant(client_id, timestamp).await
+    }
+
+    async fn remove_participant(&self, client_id: &ClientId) -> Result<(), RepositoryError> {
+        self.inner.remove_participant(client_id).await
+    }
+
+    async fn rename_participant(
+        &self,
+        client_id: &ClientId,
+        display_name: Option<DisplayName>,
+    ) -> Result<(), RepositoryError> {
+        self.inner.rename_participant(client_id, display_name).await
+    }
+
+    async fn set_activity(
+        &self,
+        client_id: &ClientId,
+        activity: Option<Activity>,
+    ) -> Result<(), RepositoryError> {
+        self.inner.set_activity(client_id, activity).await
+    }
+
+    async fn get_all_connected_client_ids(&self) -> Vec<ClientId> {
+        self.inner.get_all_connected_client_ids().await
+    }
+
+    async fn add_message(
+        &self,
+        from_client_id: ClientId,
+        content: MessageContent,
+        timestamp: Timestamp,
+    ) -> Result<SequenceNumber, RepositoryError> {
+        // 封印中は記録する前に拒否する（採番済みの番号を欠番にしない）
+        let writer = self.writer().await?;
+
+        // ルームを変更する操作は全て追記の権利を取得するため、取得している間は次の番号が変わらない
+        let seq = self
+            .inner
+            .get_room_snapshot(&self.room_id)
+            .await?
+            .last_seq
+            .next();
+        let record = WalRecord::MessageAdded {
+            room: self.record_room(),
+            seq: seq.value(),
+            client_id: from_client_id.as_str().to_string(),
+            content: content.as_str().to_string(),
+            timestamp: timestamp.value(),
+        };
+        let added = self
+            .write_ahead(
+                writer,
+                &record,
+                self.inner.add_message(from_client_id, content, timestamp),
+            )
+            .await?;
+        debug_assert_eq!(added, seq);
+        Ok(added)
+    }
+
+    async fn erase_client(
+        &self,
+        client_id: &ClientId,
+    ) -> Result<Vec<SequenceNumber>, RepositoryError> {
+        let writer = self.writer().await?;
+
+        let erased = self.inner.erase_client(client_id).await?;
+        let room = self.record_room();
+        writer
+            .rewrite(|records| erase_client_records(records, room.as_deref(), client_id.as_str()))
+            .await
+            .map_err(|e| self.storage_error(e))?;
+        Ok(erased)
+    }
+
+    async fn delete_message(&self, seq: SequenceNumber) -> Result<(), RepositoryError> {
+        let writer = self.writer().await?;
+
+        self.inner.delete_message(seq).await?;
+        let room = self.record_room();
+        writer
+            .rewrite(|records| {
+                erase_records(records, room.as_deref(), |erased, _| erased == seq.value())
+            })
+            .await
+            .map_err(|e| self.storage_error(e))
+    }
+
+    async fn tag_message(
+        &self,
+        seq: SequenceNumber,
+        tags: Vec<MessageTag>,
+    ) -> Result<(), RepositoryError> {
+        let writer = self.writer().await?;
+
+        let record = WalRecord::MessageTagged {
+            room: self.record_room(),
+            seq: seq.value(),
+            tags: tags.iter().map(|tag| tag.as_str().to_string()).collect(),
+        };
+        self.write_ahead(writer, &record, self.inner.tag_message(seq, tags))
+            .await
+    }
+
+    async fn attach_poll(
+        &self,
+        seq: SequenceNumber,
+        options: Vec<PollOption>,
+    ) -> Result<(), RepositoryError> {
+        let writer = self.writer().await?;
+
+        let record = WalRecord::PollAttached {
+            room: self.record_room(),
+            seq: seq.value(),
+            options: options
+                .iter()
+                .map(|option| option.as_str().to_string())
+                .collect(),
+        };
+        self.write_ahead(writer, &record, self.inner.attach_poll(seq, options))
+            .await
+    }
+
+    async fn mark_forwarded(
+        &self,
+        seq: SequenceNumber,
+        origin: ForwardedFrom,
+    ) -> Result<(), RepositoryError> {
+        let writer = self.writer().await?;
+
+        let record = WalRecord::MessageForwarded {
+            room: self.record_room(),
+            seq: seq.value(),
+            room_id: origin.room_id.as_str().to_string(),
+            client_id: origin.from.as_str().to_string(),
+            timestamp: origin.timestamp.value(),
+        };
+        self.write_ahead(writer, &record, self.inner.mark_forwarded(seq, origin))
+            .await
+    }
+
+    async fn vote(
+        &self,
+        seq: SequenceNumber,
+        voter: ClientId,
+        option: usize,
+    ) -> Result<Poll, RepositoryError> {
+        let writer = self.writer().await?;
+
+        let record = WalRecord::PollVoted {
+            room: self.record_room(),
+            seq: seq.value(),
+            client_id: voter.as_str().to_string(),
+            option,
+        };
+        self.write_ahead(writer, &record, self.inner.vote(seq, voter, option))
+            .await
+    }
+
+    async fn history_bytes(&self) -> usize {
+        self.inner.history_bytes().await
+    }
+
+    // 削除はメモリ上の履歴のみ（WAL には残り、再起動時に復元される）
+    async fn evict_history(&self, max_bytes: usize) -> usize {
+        self.inner.evict_history(max_bytes).await
+    }
+
+    async fn set_frozen(&self, frozen: bool) -> Result<(), RepositoryError> {
+        self.inner.set_frozen(frozen).await
+    }
+
+    async fn count_connected_clients(&self) -> usize {
+        self.inner.count_connected_clients().await
+    }
+
+    async fn get_participants(&self) -> Vec<Participant> {
+        self.inner.get_participants().await
+    }
+
+    async fn create_room(&self, room: Room) -> Result<(), RepositoryError> {
+        let writer = self.writer().await?;
+
+        let record = WalRecord::RoomAdded {
+            room_id: room.id.as_str().to_string(),
+            created_at: room.created_at.value(),
+            slug: room.slug.as_ref().map(|slug| slug.as_str().to_string()),
+            locale: room.locale.as_str().to_string(),
+            class: room.class.as_str().to_string(),
+            participant_capacity: room.participant_capacity,
+            message_capacity: room.message_capacity,
+            max_length: room.message_policy.max_length,
+            filters: room.message_policy.filters.clone(),
+        };
+        self.write_ahead(writer, &record, self.inner.create_room(room))
+            .await
+    }
+
+    async fn delete_room(&self, room_id: &RoomId) -> Result<(), RepositoryError> {
+        let writer = self.writer().await?;
+
+        self.inner.delete_room(room_id).await?;
+        // 削除したルームの内容をディスクに残さない
+        writer
+            .rewrite(|records| {
+                records
+                    .into_iter()
+                    .filter(|record| record.room() != Some(room_id.as_str()))
+                    .collect()
+            })
+            .await
+            .map_err(|e| self.storage_error(e))
+    }
+
+    async fn get_room_by_id(&self, room_id: &RoomId) -> Result<Room, RepositoryError> {
+        self.inner.get_room_by_id(room_id).await
+    }
+
+    async fn get_room_snapshot(&self, room_id: &RoomId) -> Result<Arc<Room>, RepositoryError> {
+        self.inner.get_room_snapshot(room_id).await
+    }
+
+    async fn get_room_ids(&self) -> Vec<RoomId> {
+        self.inner.get_room_ids().await
+    }
+
+    // どのルームも WAL に追記し続ける Repository のまま返す
+    async fn for_room(&self, room_id: &RoomId) -> Result<Arc<dyn RoomRepository>, RepositoryError> {
+        Ok(Arc::new(Self {
+            inner: self.inner.for_room(room_id).await?,
+            wal: self.wal.clone(),
+            room_id: room_id.clone(),
+        }))
+    }
+
+    async fn ping(&self) -> Result<(), RepositoryError> {
+        self.inner.ping().await?;
+        self.wal
+            .check()
+            .await
+            .map_err(|e| RepositoryError::Storage(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{domain::RoomIdFactory, infrastructure::repository::InMemoryRoomRepository};
+
+    fn temp_wal_path() -> PathBuf {
+        std::env::temp_dir().join(format!("engawa-wal-{}.jsonl", uuid::Uuid::new_v4()))
+    }
+
+    fn new_room() -> Room {
+        Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000))
+    }
+
+    async fn open_repository(path: &Path) -> (WalRoomRepository, Room) {
+        let (wal, room, rooms) = WriteAheadLog::open(path, new_room).await.unwrap();
+        let inner = Arc::new(InMemoryRoomRepository::new(room.clone()));
+        for room in rooms {
+            inner.create_room(room).await.unwrap();
+        }
+        (WalRoomRepository::new(inner, Arc::new(wal)), room)
+    }
+
+    #[tokio::test]
+    async fn test_replay_restores_room_and_messages() {
+        // テスト項目: 再起動後に WAL を再生すると、ルーム ID・メッセージ・シーケンス番号が復元される
+        // given (前提条件):
+        let path = temp_wal_path();
+        let (repository, room) = open_repository(&path).await;
+        for content in ["hello", "world"] {
+            repository
+                .add_message(
+                    ClientId::new("alice".to_string()).unwrap(),
+                    MessageContent::new(content.to_string()).unwrap(),
+                    Timestamp::new(2000),
+                )
+                .await
+                .unwrap();
+        }
+        drop(repository);
+
+        // when (操作):
+        let (repository, recovered) = open_repository(&path).await;
+
+        // then (期待する結果):
+        assert_eq!(recovered.id, room.id);
+        assert_eq!(recovered.messages.len(), 2);
+        assert_eq!(recovered.messages[1].content.as_str(), "world");
+        assert_eq!(recovered.last_seq, SequenceNumber::new(2));
+
+        // 復元後のメッセージは続きの番号になる
+        let seq = repository
+            .add_message(
+                ClientId::new("bob".to_string()).unwrap(),
+                MessageContent::new("again".to_string()).unwrap(),
+                Timestamp::new(3000),
+            )
+            .await
+            .unwrap();
+        assert_eq!(seq, SequenceNumber::new(3));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_restores_message_tags() {
+        // テスト項目: 再起動後に WAL を再生すると、メッセージに付けたタグと転送元が復元される
+        // given (前提条件):
+        let path = temp_wal_path();
+        let (repository, _) = open_repository(&path).await;
+        let seq = repository
+            .add_message(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new("deploy".to_string()).unwrap(),
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap();
+        let tag = MessageTag::new("keyword:deploy".to_string()).unwrap();
+        repository
+            .tag_message(seq, vec![tag.clone()])
+            .await
+            .unwrap();
+        let origin = ForwardedFrom {
+            room_id: RoomIdFactory::generate().unwrap(),
+            from: ClientId::new("bob".to_string()).unwrap(),
+            timestamp: Timestamp::new(1500),
+        };
+        repository
+            .mark_forwarded(seq, origin.clone())
+            .await
+            .unwrap();
+        drop(repository);
+
+        // when (操作):
+        let (_, recovered) = open_repository(&path).await;
+
+        // then (期待する結果):
+        assert_eq!(recovered.messages[0].tags, vec![tag]);
+        assert_eq!(recovered.messages[0].forwarded_from, Some(origin));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_restores_polls_without_erased_votes() {
+        // テスト項目: 再起動後に WAL を再生すると投票と票が復元され、データを削除したクライアントの票は残らない
+        // given (前提条件):
+        let path = temp_wal_path();
+        let (repository, _) = open_repository(&path).await;
+        let seq = repository
+            .add_message(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new("Lunch?".to_string()).unwrap(),
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap();
+        let options = vec![
+            PollOption::new("Ramen".to_string()).unwrap(),
+            PollOption::new("Sushi".to_string()).unwrap(),
+        ];
+        repository.attach_poll(seq, options.clone()).await.unwrap();
+        for (voter, option) in [("bob", 0), ("carol", 1), ("bob", 1)] {
+            let voter = ClientId::new(voter.to_string()).unwrap();
+            repository.vote(seq, voter, option).await.unwrap();
+        }
+        let carol = ClientId::new("carol".to_string()).unwrap();
+        repository.erase_client(&carol).await.unwrap();
+        drop(repository);
+
+        // when (操作):
+        let (_, recovered) = open_repository(&path).await;
+
+        // then (期待する結果):
+        let poll = recovered.messages[0].poll.as_ref().unwrap();
+        assert_eq!(poll.options, options);
+        assert_eq!(poll.tally(), vec![0, 1]);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains("carol"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_erased_client_is_removed_from_wal() {
+        // テスト項目: クライアントのデータを削除すると WAL から内容が消え、再生後も番号は続きになる
+        // given (前提条件):
+        let path = temp_wal_path();
+        let (repository, _) = open_repository(&path).await;
+        for (from, content) in [("alice", "secret"), ("bob", "hello")] {
+            let seq = repository
+                .add_message(
+                    ClientId::new(from.to_string()).unwrap(),
+                    MessageContent::new(content.to_string()).unwrap(),
+                    Timestamp::new(2000),
+                )
+                .await
+                .unwrap();
+            let tag = MessageTag::new(format!("keyword:{}", content)).unwrap();
+            repository.tag_message(seq, vec![tag]).await.unwrap();
+        }
+
+        // when (操作):
+        let erased = repository
+            .erase_client(&ClientId::new("alice".to_string()).unwrap())
+            .await
+            .unwrap();
+        drop(repository);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let (repository, recovered) = open_repository(&path).await;
+
+        // then (期待する結果):
+        assert_eq!(erased, vec![SequenceNumber::new(1)]);
+        assert!(!contents.contains("alice") && !contents.contains("secret"));
+        assert_eq!(recovered.messages.len(), 1);
+        assert_eq!(recovered.messages[0].content.as_str(), "hello");
+        assert_eq!(recovered.messages[0].tags.len(), 1);
+        assert_eq!(recovered.last_seq, SequenceNumber::new(2));
+        let seq = repository
+            .add_message(
+                ClientId::new("bob".to_string()).unwrap(),
+                MessageContent::new("again".to_string()).unwrap(),
+                Timestamp::new(3000),
+            )
+            .await
+            .unwrap();
+        assert_eq!(seq, SequenceNumber::new(3));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_incomplete_last_record_is_discarded() {
+        // テスト項目: 書き込み途中の最終行は切り捨てられ、以降の追記は正しく読める
+        // given (前提条件):
+        let path = temp_wal_path();
+        let (repository, _) = open_repository(&path).await;
+        drop(repository);
+        let mut contents = std::fs::read_to_string(&path).unwrap();
+        contents.push_str(r#"{"type":"message-added","seq":1,"client_"#);
+        std::fs::write(&path, contents).unwrap();
+
+        // when (操作):
+        let (repository, recovered) = open_repository(&path).await;
+        repository
+            .add_message(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new("hello".to_string()).unwrap(),
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap();
+        drop(repository);
+
+        // then (期待する結果):
+        assert_eq!(recovered.messages.len(), 0);
+        let (_, recovered) = open_repository(&path).await;
+        assert_eq!(recovered.messages.len(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_record_is_rejected() {
+        // テスト項目: 最終行以外のレコードが壊れている場合はエラーになる
+        // given (前提条件):
+        let path = temp_wal_path();
+        let (repository, _) = open_repository(&path).await;
+        drop(repository);
+        let mut contents = std::fs::read_to_string(&path).unwrap();
+        contents.push_str("not json\n");
+        contents.push_str(
+            r#"{"type":"message-added","seq":1,"client_id":"a","content":"b","timestamp":0}"#,
+        );
+        contents.push('\n');
+        std::fs::write(&path, contents).unwrap();
+
+        // when (操作):
+        let result = WriteAheadLog::open(&path, new_room).await;
+
+        // then (期待する結果):
+        assert!(matches!(result, Err(WalError::Corrupt { line: 2, .. })));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rejected_message_is_not_recorded() {
+        // テスト項目: ルームに追加できなかったメッセージは WAL に残らず、番号も欠番にならない
+        // given (前提条件):
+        let path = temp_wal_path();
+        let (repository, _) = open_repository(&path).await;
+        let send = |content: &str| {
+            repository.add_message(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new(content.to_string()).unwrap(),
+                Timestamp::new(2000),
+            )
+        };
+        let before = std::fs::read_to_string(&path).unwrap();
+
+        // when (操作):
+        repository.set_frozen(true).await.unwrap();
+        let frozen = send("rejected").await;
+        let after = std::fs::read_to_string(&path).unwrap();
+        repository.set_frozen(false).await.unwrap();
+        let seq = send("accepted").await.unwrap();
+        drop(repository);
+
+        // then (期待する結果):
+        assert!(matches!(
+            frozen,
+            Err(RepositoryError::Room(crate::domain::RoomError::RoomFrozen))
+        ));
+        assert_eq!(after, before);
+        assert_eq!(seq, SequenceNumber::new(1));
+        let (_, recovered) = open_repository(&path).await;
+        assert_eq!(recovered.messages.len(), 1);
+        assert_eq!(recovered.messages[0].content.as_str(), "accepted");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_restores_created_rooms() {
+        // テスト項目: 再起動後に WAL を再生すると作成したルームとそのメッセージが復元され、削除したルームは残らない
+        // given (前提条件):
+        let path = temp_wal_path();
+        let (repository, room) = open_repository(&path).await;
+        let mut kept = new_room();
+        kept.slug = Some(RoomSlug::new("kept".to_string()).unwrap());
+        let deleted = new_room();
+        for created in [&kept, &deleted] {
+            repository.create_room(created.clone()).await.unwrap();
+            repository
+                .for_room(&created.id)
+                .await
+                .unwrap()
+                .add_message(
+                    ClientId::new("alice".to_string()).unwrap(),
+                    MessageContent::new(format!("in {}", created.id.as_str())).unwrap(),
+                    Timestamp::new(2000),
+                )
+                .await
+                .unwrap();
+        }
+        repository.delete_room(&deleted.id).await.unwrap();
+        drop(repository);
+
+        // when (操作):
+        let (repository, recovered) = open_repository(&path).await;
+
+        // then (期待する結果):
+        assert_eq!(recovered.id, room.id);
+        assert!(recovered.messages.is_empty());
+        let restored = repository.get_room_by_id(&kept.id).await.unwrap();
+        assert_eq!(restored.slug, kept.slug);
+        assert_eq!(restored.messages.len(), 1);
+        assert_eq!(restored.last_seq, SequenceNumber::new(1));
+        assert!(matches!(
+            repository.get_room_by_id(&deleted.id).await,
+            Err(RepositoryError::RoomNotFound)
+        ));
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains(deleted.id.as_str()));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sealed_wal_rejects_messages() {
+        // テスト項目: 封印中の WAL には追記されず、封印を解除すると再び追記できる
+        // given (前提条件):
+        let path = temp_wal_path();
+        let (wal, room, _) = WriteAheadLog::open(&path, new_room).await.unwrap();
+        let wal = Arc::new(wal);
+        let inner = Arc::new(InMemoryRoomRepository::new(room));
+        let repository = WalRoomRepository::new(inner, wal.clone());
+        let send = |content: &str| {
+            repository.add_message(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new(content.to_string()).unwrap(),
+                Timestamp::new(2000),
+            )
+        };
+
+        // when (操作):
+        wal.seal().await;
+        let sealed = send("lost").await;
+        wal.unseal();
+        let unsealed = send("kept").await;
+
+        // then (期待する結果):
+        assert!(matches!(sealed, Err(RepositoryError::Storage(_))));
+        assert!(unsealed.is_ok());
+        drop(repository);
+        let (_, recovered, _) = WriteAheadLog::open(&path, new_room).await.unwrap();
+        assert_eq!(recovered.messages.len(), 1);
+        assert_eq!(recovered.messages[0].content.as_str(), "kept");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        // テスト項目: 全ての Repository に共通の振る舞いを満たす
+        let paths = std::sync::Mutex::new(Vec::new());
+        crate::infrastructure::repository::conformance::run(|room| {
+            let path = temp_wal_path();
+            paths.lock().unwrap().push(path.clone());
+            async move {
+                let (wal, room, _) = WriteAheadLog::open(&path, move || room).await.unwrap();
+                let inner = Arc::new(InMemoryRoomRepository::new(room));
+                Arc::new(WalRoomRepository::new(inner, Arc::new(wal))) as Arc<dyn RoomRepository>
+            }
+        })
+        .await;
+        for path in paths.into_inner().unwrap() {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! メッセージ内容の HTML サニタイズ
+//!
+//! ## 責務
+//!
+//! - 受信したメッセージ内容の HTML をプロファイルに従ってエスケープ・除去する
+//!
+//! ## 設計ノート
+//!
+//! サニタイズはメッセージを Domain Model に変換する前（保存・ブロードキャストの前）に行うため、
+//! 履歴・バックフィル・ブリッジへの中継も全てサニタイズ後の内容になります。
+//! `strip` はタグとコメントを取り除き、`<script>` / `<style>` は中身ごと取り除きます。
+//! タグの始まりにならない `<`（`a < b` など）はそのまま残します。
+
+use std::{borrow::Cow, str::FromStr};
+
+/// 中身ごと取り除く要素
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// メッセージ内容のサニタイズのプロファイル
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SanitizeProfile {
+    /// 受信した内容をそのまま保存する
+    #[default]
+    None,
+    /// HTML の特殊文字をエスケープする（`<b>` は `&lt;b&gt;` として表示される）
+    Escape,
+    /// タグ・コメントと `<script>` / `<style>` の中身を取り除く
+    Strip,
+}
+
+impl FromStr for SanitizeProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "escape" => Ok(Self::Escape),
+            "strip" => Ok(Self::Strip),
+            _ => Err(format!(
+                "unknown sanitize profile '{}' (expected: none, escape, strip)",
+                s
+            )),
+        }
+    }
+}
+
+impl SanitizeProfile {
+    /// メッセージ内容をサニタイズする（`strip` で全て取り除かれた場合は空文字列）
+    pub fn apply<'a>(&self, content: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::None => Cow::Borrowed(content),
+            Self::Escape => escape_html(content),
+            Self::Strip => strip_html(content),
+        }
+    }
+}
+
+/// HTML の特殊文字をエスケープ
+fn escape_html(content: &str) -> Cow<'_, str> {
+    if !content.contains(['&', '<', '>', '"', '\'']) {
+        return Cow::Borrowed(content);
+    }
+    let mut escaped = String::with_capacity(content.len());
+    for c in content.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// タグ・コメントと `<script>` / `<style>` の中身を取り除く
+fn strip_html(content: &str) -> Cow<'_, str> {
+    if !content.contains('<') {
+        return Cow::Borrowed(content);
+    }
+    let mut stripped = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find('<') {
+        stripped.push_str(&rest[..start]);
+        let tag = &rest[start..];
+        if let Some(comment) = tag.strip_prefix("<!--") {
+            // 閉じていないコメントは末尾まで取り除く
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let name: String = tag[1..]
+            .trim_start_matches('/')
+            .chars()
+            .take_while(char::is_ascii_alphanumeric)
+            .collect();
+        let starts_tag = tag[1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '/' || c == '!');
+        if !starts_tag {
+            stripped.push('<');
+            rest = &tag[1..];
+            continue;
+        }
+        // 閉じていないタグは末尾まで取り除く
+        let Some(end) = tag.find('>') else {
+            rest = "";
+            break;
+        };
+        rest = &tag[end + 1..];
+        let name = name.to_ascii_lowercase();
+        if !tag[1..].starts_with('/') && RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+            let closing = format!("</{}", name);
+            rest = rest
+                .to_ascii_lowercase()
+                .find(&closing)
+                .and_then(|close| rest[close..].find('>').map(|end| &rest[close + end + 1..]))
+                .unwrap_or("");
+        }
+    }
+    stripped.push_str(rest);
+    Cow::Owned(stripped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_profile() {
+        // テスト項目: HTML の特殊文字がエスケープされ、特殊文字が無ければそのまま返る
+        // when (操作):
+        let escaped = SanitizeProfile::Escape.apply("<b>Tom & \"Jerry\"</b>");
+        let plain = SanitizeProfile::Escape.apply("hello");
+
+        // then (期待する結果):
+        assert_eq!(escaped, "&lt;b&gt;Tom &amp; &quot;Jerry&quot;&lt;/b&gt;");
+        assert!(matches!(plain, Cow::Borrowed("hello")));
+    }
+
+    #[test]
+    fn test_strip_profile() {
+        // テスト項目: タグ・コメント・スクリプトの中身が取り除かれ、タグでない `<` は残る
+        // when (操作):
+        let cases = [
+            ("<b>bold</b> text", "bold text"),
+            ("hi<script>alert('x')</script>!", "hi!"),
+            ("a<SCRIPT src=x>evil()</Script>b", "ab"),
+            ("<!-- note -->visible", "visible"),
+            ("1 < 2 and 3 <= 4", "1 < 2 and 3 <= 4"),
+            ("<img src=x onerror=alert(1)>", ""),
+            ("text <unclosed", "text "),
+        ];
+
+        // then (期待する結果):
+        for (input, expected) in cases {
+            assert_eq!(SanitizeProfile::Strip.apply(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_none_profile_keeps_content() {
+        // テスト項目: `none` では内容がそのまま返る
+        // when (操作):
+        let content = SanitizeProfile::None.apply("<b>bold</b>");
+
+        // then (期待する結果):
+        assert_eq!(content, "<b>bold</b>");
+    }
+}
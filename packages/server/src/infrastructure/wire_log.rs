@@ -0,0 +1,250 @@
+//! プロトコルのデバッグ用のワイヤーログ（WebSocket フレームの記録）
+//!
+//! ## 責務
+//!
+//! - 送受信した WebSocket フレームを時刻・方向・相手と共に JSON Lines で追記
+//! - ペイロードのうち指定したフィールドの伏せ字（redaction）
+//! - 記録したフレームの読み込みと、人が読める形への整形（`wire-log dump`）
+//!
+//! ## 設計ノート
+//!
+//! サーバ（`--wire-log`）とクライアント（`--wire-log`）で同じ形式を使います。
+//! サーバは接続ごとに受信したフレームと送信キューから書き込んだフレームを、クライアントは
+//! サーバとの間のフレームを記録します。バッチ送信のフレームは分割せずに 1 件として記録します。
+//!
+//! ペイロードが JSON の場合、伏せ字にするフィールド（例: `content`）の値を入れ子も含めて
+//! `"[redacted]"` に置き換えます。伏せ字にするフィールドが指定されていて JSON でない
+//! ペイロードは、内容を判断できないためペイロード全体を伏せ字にします。
+//! バイナリと制御フレームはペイロードを記録せず、長さのみ記録します。
+//!
+//! 記録はデバッグ用のため、1 フレームごとに同期的に追記します（記録に失敗してもフレームの
+//! 送受信は続けます）。
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{LineWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use axum::extract::ws::Message;
+
+use engawa_shared::time::{get_jst_timestamp, timestamp_to_jst_rfc3339};
+use serde_json::Value;
+
+use super::{
+    dto::wire_log::{FrameKind, WireDirection, WireRecord},
+    error::WireLogError,
+};
+
+/// 伏せ字にした値
+pub const REDACTED: &str = "[redacted]";
+
+/// ワイヤーログのファイル
+#[derive(Debug)]
+pub struct WireLog {
+    file: Mutex<LineWriter<File>>,
+    /// 伏せ字にする JSON のフィールド名
+    redact: Vec<String>,
+}
+
+impl WireLog {
+    /// ワイヤーログを追記モードで開く（無い場合は作成する）
+    ///
+    /// # Errors
+    ///
+    /// ファイルを開けない場合
+    pub fn open(path: impl AsRef<Path>, redact: Vec<String>) -> Result<Self, WireLogError> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(LineWriter::new(file)),
+            redact,
+        })
+    }
+
+    /// フレームを記録する
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - 受信か送信か
+    /// * `peer` - 相手（サーバではクライアント ID、クライアントではサーバの URL）
+    /// * `kind` - フレームの種類
+    /// * `payload` - フレームのペイロード（テキストフレームの場合のみ記録する）
+    pub fn record(&self, dir: WireDirection, peer: &str, kind: FrameKind, payload: &[u8]) {
+        let text = match kind {
+            FrameKind::Text => Some(self.redacted(&String::from_utf8_lossy(payload))),
+            _ => None,
+        };
+        let record = WireRecord {
+            ts: get_jst_timestamp(),
+            dir,
+            peer: peer.to_string(),
+            kind,
+            len: payload.len(),
+            payload: text,
+        };
+        let mut line = serde_json::to_string(&record).unwrap();
+        line.push('\n');
+        if let Err(e) = self.file.lock().unwrap().write_all(line.as_bytes()) {
+            tracing::warn!("Failed to write the wire log: {}", e);
+        }
+    }
+
+    /// ペイロードの伏せ字にするフィールドを置き換える
+    fn redacted(&self, payload: &str) -> String {
+        if self.redact.is_empty() {
+            return payload.to_string();
+        }
+        match serde_json::from_str::<Value>(payload) {
+            Ok(mut value) => {
+                redact_fields(&mut value, &self.redact);
+                value.to_string()
+            }
+            Err(_) => REDACTED.to_string(),
+        }
+    }
+}
+
+/// 1 つの接続のフレームを記録するためのワイヤーログと相手
+#[derive(Debug, Clone)]
+pub struct WireTap {
+    log: Arc<WireLog>,
+    peer: String,
+}
+
+impl WireTap {
+    /// `peer` との間のフレームを `log` に記録する
+    pub fn new(log: Arc<WireLog>, peer: impl Into<String>) -> Self {
+        Self {
+            log,
+            peer: peer.into(),
+        }
+    }
+
+    /// axum の WebSocket フレームを記録する
+    pub fn record(&self, dir: WireDirection, frame: &Message) {
+        let (kind, payload): (FrameKind, &[u8]) = match frame {
+            Message::Text(text) => (FrameKind::Text, text.as_bytes()),
+            Message::Binary(data) => (FrameKind::Binary, data),
+            Message::Ping(data) => (FrameKind::Ping, data),
+            Message::Pong(data) => (FrameKind::Pong, data),
+            Message::Close(frame) => (
+                FrameKind::Close,
+                frame
+                    .as_ref()
+                    .map_or(&[][..], |frame| frame.reason.as_bytes()),
+            ),
+        };
+        self.log.record(dir, &self.peer, kind, payload);
+    }
+}
+
+/// JSON の値のうち `fields` に含まれるフィールドの値を入れ子も含めて伏せ字にする
+fn redact_fields(value: &mut Value, fields: &[String]) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                if fields.iter().any(|field| field == key) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_fields(value, fields);
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                redact_fields(value, fields);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// ワイヤーログを読み込む
+///
+/// # Errors
+///
+/// ファイルを読めない場合、またはレコードが壊れている場合
+pub fn read_records(path: impl AsRef<Path>) -> Result<Vec<WireRecord>, WireLogError> {
+    let contents = std::fs::read_to_string(path)?;
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| WireLogError::Corrupt {
+                line: i + 1,
+                reason: e.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// レコードを人が読める形に整形する
+///
+/// 1 行目に時刻・方向・相手・種類・長さを、JSON のペイロードは続けてインデントして表示する。
+pub fn format_record(record: &WireRecord) -> String {
+    let arrow = match record.dir {
+        WireDirection::In => "<-",
+        WireDirection::Out => "->",
+    };
+    let kind = serde_json::to_value(record.kind).unwrap();
+    let mut formatted = format!(
+        "{} {} {} {} ({} bytes)",
+        timestamp_to_jst_rfc3339(record.ts),
+        arrow,
+        record.peer,
+        kind.as_str().unwrap_or_default(),
+        record.len
+    );
+    if let Some(payload) = &record.payload {
+        let pretty = serde_json::from_str::<Value>(payload)
+            .map(|value| serde_json::to_string_pretty(&value).unwrap())
+            .unwrap_or_else(|_| payload.clone());
+        for line in pretty.lines() {
+            formatted.push_str("\n    ");
+            formatted.push_str(line);
+        }
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_redacts_fields_and_reads_back() {
+        // テスト項目: 記録したフレームが読み込め、指定したフィールドは入れ子も含めて伏せ字になる
+        // given (前提条件):
+        let path =
+            std::env::temp_dir().join(format!("engawa-wire-log-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let log = WireLog::open(&path, vec!["content".to_string()]).unwrap();
+
+        // when (操作):
+        log.record(
+            WireDirection::In,
+            "alice",
+            FrameKind::Text,
+            br#"{"type":"chat","content":"secret","nested":[{"content":"x"}]}"#,
+        );
+        log.record(WireDirection::Out, "alice", FrameKind::Text, b"not json");
+        log.record(WireDirection::Out, "alice", FrameKind::Ping, b"");
+        let records = read_records(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // then (期待する結果):
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].dir, WireDirection::In);
+        assert_eq!(records[0].len, 61);
+        assert_eq!(
+            records[0].payload.as_deref(),
+            Some(r#"{"content":"[redacted]","nested":[{"content":"[redacted]"}],"type":"chat"}"#)
+        );
+        assert_eq!(records[1].payload.as_deref(), Some(REDACTED));
+        assert_eq!(records[2].kind, FrameKind::Ping);
+        assert_eq!(records[2].payload, None);
+        assert!(format_record(&records[2]).ends_with("-> alice ping (0 bytes)"));
+    }
+}
@@ -0,0 +1,256 @@
+//! XMPP コンポーネント接続（XEP-0114: Jabber Component Protocol）
+//!
+//! ## 責務
+//!
+//! - XMPP サーバのコンポーネントポートへの接続とハンドシェイク（SHA-1 ダイジェスト）
+//! - ストリームからのスタンザ（`<message/>` / `<presence/>` / `<iq/>`）の読み出し
+//! - スタンザの書き込み
+//!
+//! スタンザの意味（MUC としての振る舞い）は UI 層のゲートウェイが扱います。
+
+use quick_xml::{Reader, events::Event};
+use sha1::{Digest, Sha1};
+use tokio::{
+    io::{AsyncWriteExt, BufReader},
+    net::{
+        TcpStream,
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+    },
+};
+
+use super::error::XmppError;
+
+/// XML 要素（スタンザ）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Element {
+    /// 要素名（プレフィックス付き、例: `message`, `stream:error`）
+    pub name: String,
+    /// 属性（名前, 値）
+    pub attrs: Vec<(String, String)>,
+    /// 子要素
+    pub children: Vec<Element>,
+    /// テキスト内容（アンエスケープ済み）
+    pub text: String,
+}
+
+impl Element {
+    /// 属性値を取得
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// 名前が一致する最初の子要素を取得
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+}
+
+/// XML の特殊文字をエスケープ
+pub fn escape(text: &str) -> String {
+    quick_xml::escape::escape(text).into_owned()
+}
+
+/// コンポーネントハンドシェイクのダイジェスト（`hex(SHA1(stream_id + secret))`）
+pub fn handshake_digest(stream_id: &str, secret: &str) -> String {
+    let digest = Sha1::digest(format!("{}{}", stream_id, secret).as_bytes());
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// コンポーネントとして XMPP サーバに接続し、ハンドシェイクを行う
+///
+/// # 引数
+///
+/// - `server_addr`: XMPP サーバのコンポーネントポート（例: `localhost:5347`）
+/// - `domain`: コンポーネントのドメイン（例: `chat.example.org`）
+/// - `secret`: XMPP サーバに設定した共有シークレット
+pub async fn connect(
+    server_addr: &str,
+    domain: &str,
+    secret: &str,
+) -> Result<(StanzaReader, StanzaWriter), XmppError> {
+    let stream = TcpStream::connect(server_addr)
+        .await
+        .map_err(|e| XmppError::Io(e.to_string()))?;
+    let (read_half, write_half) = stream.into_split();
+    let mut reader = StanzaReader::new(read_half);
+    let mut writer = StanzaWriter { inner: write_half };
+
+    writer
+        .send(&format!(
+            "<stream:stream xmlns='jabber:component:accept' \
+             xmlns:stream='http://etherx.jabber.org/streams' to='{}'>",
+            escape(domain)
+        ))
+        .await?;
+    let stream_id = reader.read_stream_header().await?;
+
+    writer
+        .send(&format!(
+            "<handshake>{}</handshake>",
+            handshake_digest(&stream_id, secret)
+        ))
+        .await?;
+    let response = reader.read_stanza().await?;
+    if response.name != "handshake" {
+        let condition = response
+            .children
+            .first()
+            .map(|child| child.name.clone())
+            .unwrap_or(response.name);
+        return Err(XmppError::HandshakeFailed(condition));
+    }
+
+    Ok((reader, writer))
+}
+
+/// ストリームからスタンザを読み出す
+pub struct StanzaReader {
+    reader: Reader<BufReader<OwnedReadHalf>>,
+    buf: Vec<u8>,
+}
+
+impl StanzaReader {
+    fn new(read_half: OwnedReadHalf) -> Self {
+        Self {
+            reader: Reader::from_reader(BufReader::new(read_half)),
+            buf: Vec::new(),
+        }
+    }
+
+    /// サーバのストリームヘッダーを読み、ストリーム ID を返す
+    async fn read_stream_header(&mut self) -> Result<String, XmppError> {
+        loop {
+            self.buf.clear();
+            match self.next_event().await? {
+                Event::Start(start) if start.name().as_ref() == b"stream:stream" => {
+                    let header = element_from(&start)?;
+                    return header
+                        .attr("id")
+                        .map(str::to_string)
+                        .ok_or_else(|| XmppError::Xml("stream header without id".to_string()));
+                }
+                Event::Eof => return Err(XmppError::StreamClosed),
+                _ => {}
+            }
+        }
+    }
+
+    /// 次のトップレベルのスタンザを読み出す
+    ///
+    /// サーバがストリームを閉じた場合は `XmppError::StreamClosed` を返す。
+    /// このメソッドはキャンセル安全ではないため、専用のタスクから呼び出すこと。
+    pub async fn read_stanza(&mut self) -> Result<Element, XmppError> {
+        let mut stack: Vec<Element> = Vec::new();
+        loop {
+            self.buf.clear();
+            let completed = match self.next_event().await? {
+                Event::Start(start) => {
+                    stack.push(element_from(&start)?);
+                    None
+                }
+                Event::Empty(empty) => Some(element_from(&empty)?),
+                Event::Text(text) => {
+                    if let Some(current) = stack.last_mut() {
+                        let text = text.unescape().map_err(|e| XmppError::Xml(e.to_string()))?;
+                        current.text.push_str(&text);
+                    }
+                    None
+                }
+                Event::CData(cdata) => {
+                    if let Some(current) = stack.last_mut() {
+                        current
+                            .text
+                            .push_str(&String::from_utf8_lossy(&cdata.into_inner()));
+                    }
+                    None
+                }
+                // </stream:stream> がトップレベルで来た場合はストリーム終了
+                Event::End(_) if stack.is_empty() => return Err(XmppError::StreamClosed),
+                Event::End(_) => stack.pop(),
+                Event::Eof => return Err(XmppError::StreamClosed),
+                _ => None,
+            };
+
+            if let Some(element) = completed {
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+        }
+    }
+
+    async fn next_event(&mut self) -> Result<Event<'_>, XmppError> {
+        self.reader
+            .read_event_into_async(&mut self.buf)
+            .await
+            .map_err(|e| XmppError::Xml(e.to_string()))
+    }
+}
+
+fn element_from(start: &quick_xml::events::BytesStart<'_>) -> Result<Element, XmppError> {
+    let mut element = Element {
+        name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+        ..Default::default()
+    };
+    for attr in start.attributes() {
+        let attr = attr.map_err(|e| XmppError::Xml(e.to_string()))?;
+        let value = attr
+            .unescape_value()
+            .map_err(|e| XmppError::Xml(e.to_string()))?;
+        element.attrs.push((
+            String::from_utf8_lossy(attr.key.as_ref()).into_owned(),
+            value.into_owned(),
+        ));
+    }
+    Ok(element)
+}
+
+/// ストリームにスタンザを書き込む
+pub struct StanzaWriter {
+    inner: OwnedWriteHalf,
+}
+
+impl StanzaWriter {
+    /// シリアライズ済みのスタンザを送信
+    pub async fn send(&mut self, xml: &str) -> Result<(), XmppError> {
+        self.inner
+            .write_all(xml.as_bytes())
+            .await
+            .map_err(|e| XmppError::Io(e.to_string()))
+    }
+
+    /// ストリームを閉じる
+    pub async fn close(&mut self) {
+        let _ = self.send("</stream:stream>").await;
+        let _ = self.inner.shutdown().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handshake_digest() {
+        // テスト項目: ストリーム ID と共有シークレットから XEP-0114 のダイジェストが求められる
+        // when (操作):
+        let digest = handshake_digest("3BF96D32", "secret");
+
+        // then (期待する結果): SHA-1("3BF96D32secret") の 16 進表記
+        assert_eq!(digest, "b09ea9b3b7f586be8a08d0a3dd7466f110aeb136");
+    }
+
+    #[test]
+    fn test_escape() {
+        // テスト項目: XML の特殊文字がエスケープされる
+        // then (期待する結果):
+        assert_eq!(
+            escape("<b>\"Tom\" & 'Jerry'</b>"),
+            "&lt;b&gt;&quot;Tom&quot; &amp; &apos;Jerry&apos;&lt;/b&gt;"
+        );
+    }
+}
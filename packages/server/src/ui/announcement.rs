@@ -0,0 +1,148 @@
+//! Recurring announcements of the rooms (`recurring-announcement` integrations).
+//!
+//! A background task wakes up at the start of every minute, looks up the announcements whose
+//! schedule (a cron expression evaluated in JST) fires at that minute and posts each of them
+//! to its room as a chat message from [`ANNOUNCEMENT_SENDER`]. Minutes missed while the server
+//! was stopped or suspended are not caught up.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::{Datelike, FixedOffset, TimeZone, Timelike};
+use engawa_shared::time::get_jst_timestamp;
+
+use crate::{
+    domain::{ClientId, CronSchedule},
+    infrastructure::dto::websocket::{ChatMessage, MessageType},
+    usecase::{ManageIntegrationsUseCase, RecurringAnnouncement, SendMessageError},
+};
+
+use super::{signal::ShutdownToken, state::AppState};
+
+/// Client ID the announcements are posted as
+pub const ANNOUNCEMENT_SENDER: &str = "announcement";
+
+/// Length of a minute in milliseconds
+const MINUTE_MILLIS: i64 = 60 * 1000;
+
+/// Whether the schedule fires at the minute of `at` (Unix milliseconds) in JST
+pub fn is_due(schedule: &CronSchedule, at: i64) -> bool {
+    let jst = FixedOffset::east_opt(9 * 3600).unwrap(); // JST is UTC+9
+    let at = jst.timestamp_millis_opt(at).single().unwrap_or_default();
+    schedule.matches(
+        at.minute(),
+        at.hour(),
+        at.day(),
+        at.month(),
+        at.weekday().num_days_from_sunday(),
+    )
+}
+
+/// Post the announcements due every minute until the server shuts down
+pub async fn run_recurring_announcements(
+    usecase: Arc<ManageIntegrationsUseCase>,
+    state: Arc<AppState>,
+    shutdown: ShutdownToken,
+) {
+    let sender =
+        ClientId::new(ANNOUNCEMENT_SENDER.to_string()).expect("Invalid announcement sender");
+    loop {
+        // Evaluate the schedules at the start of the next minute rather than at the time the
+        // task wakes up, so that an early or late wake-up does not skip or repeat a minute
+        let now = get_jst_timestamp();
+        let minute = (now.div_euclid(MINUTE_MILLIS) + 1) * MINUTE_MILLIS;
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis((minute - now) as u64)) => {}
+            _ = shutdown.cancelled() => return,
+        }
+
+        let announcements = match usecase.recurring_announcements().await {
+            Ok(announcements) => announcements,
+            Err(e) => {
+                tracing::warn!("Failed to look up the recurring announcements: {}", e);
+                continue;
+            }
+        };
+        for announcement in announcements {
+            if is_due(&announcement.schedule, minute) {
+                post(&state, &sender, announcement, minute).await;
+            }
+        }
+    }
+}
+
+/// Post an announcement to its room
+async fn post(
+    state: &AppState,
+    sender: &ClientId,
+    announcement: RecurringAnnouncement,
+    timestamp: i64,
+) {
+    let RecurringAnnouncement { room_id, text, .. } = announcement;
+    let room = match state.join_room(Some(room_id.as_str())).await {
+        Ok(room) => room,
+        Err(e) => {
+            tracing::warn!(
+                "Room '{}' of the recurring announcement is unavailable: {:?}",
+                room_id,
+                e
+            );
+            return;
+        }
+    };
+    let message = ChatMessage {
+        r#type: MessageType::Chat,
+        client_id: ANNOUNCEMENT_SENDER.to_string(),
+        content: text.as_str().to_string(),
+        timestamp,
+        seq: None,
+        poll: None,
+        forwarded_from: None,
+    };
+    tracing::info!(
+        "Posting the recurring announcement to room '{}': {}",
+        room_id,
+        message.content
+    );
+    match room
+        .send_message
+        .execute(sender.clone(), text, move |seq| {
+            message.to_json_with_seq(seq.value())
+        })
+        .await
+    {
+        Ok(_) => {}
+        Err(SendMessageError::RoomFrozen) => {
+            tracing::info!(
+                "Room '{}' is frozen; skipping the recurring announcement",
+                room_id
+            );
+        }
+        Err(e) => tracing::warn!("Failed to post the recurring announcement: {:?}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_due_in_jst() {
+        // テスト項目: スケジュールは JST の時刻と曜日で評価され、分の途中の時刻もその分として扱われる
+        // given (前提条件):
+        let schedule = CronSchedule::new("0 9 * * 1".to_string()).unwrap();
+        // 2023-01-02 (Monday) 09:00:00 JST
+        let monday = 1672498800000 + (24 + 9) * 3600 * 1000;
+
+        // when (操作):
+        let on_time = is_due(&schedule, monday);
+        let within_minute = is_due(&schedule, monday + 59 * 1000);
+        let next_minute = is_due(&schedule, monday + MINUTE_MILLIS);
+        let next_day = is_due(&schedule, monday + 24 * 3600 * 1000);
+
+        // then (期待する結果):
+        assert!(on_time);
+        assert!(within_minute);
+        assert!(!next_minute);
+        assert!(!next_day);
+    }
+}
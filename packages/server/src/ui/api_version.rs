@@ -0,0 +1,148 @@
+//! REST API versioning.
+//!
+//! ## Policy
+//!
+//! - Every endpoint is served under a versioned prefix (`/api/v1/...`). Breaking DTO changes
+//!   ship as a new prefix (`/api/v2/...`) while older versions keep working.
+//! - The unversioned paths (`/api/...`) are compatibility aliases of v1. Their responses carry
+//!   `Deprecation: true` and a `Link: <...>; rel="successor-version"` header.
+//! - Every response carries the `API-Version` header of the version that served it.
+//! - Clients may send `Accept-Version: <n>`; requesting a version the path cannot serve
+//!   is answered with `406 Not Acceptable` and the list of supported versions.
+
+use axum::{
+    Json,
+    extract::{OriginalUri, Request},
+    http::{HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Current (latest) REST API version
+pub const CURRENT_API_VERSION: u32 = 1;
+
+/// REST API versions served by this server
+pub const SUPPORTED_API_VERSIONS: &[u32] = &[1];
+
+/// Request header used by clients to ask for a specific version
+const ACCEPT_VERSION: &str = "accept-version";
+
+/// Response header reporting the version that served the request
+const API_VERSION: &str = "api-version";
+
+/// Negotiate the API version for a request.
+///
+/// # Arguments
+///
+/// * `accept_version` - Value of the `Accept-Version` request header, if any
+/// * `served_version` - Version served by the requested path
+///
+/// # Returns
+///
+/// `true` if the path can serve the requested version
+pub fn is_acceptable(accept_version: Option<&str>, served_version: u32) -> bool {
+    match accept_version {
+        None => true,
+        Some(requested) => requested
+            .trim()
+            .trim_start_matches(['v', 'V'])
+            .parse::<u32>()
+            .is_ok_and(|requested| requested == served_version),
+    }
+}
+
+/// Get the versioned successor of an unversioned API path (e.g. `/api/rooms` → `/api/v1/rooms`)
+pub fn successor_path(path: &str) -> String {
+    match path.strip_prefix("/api") {
+        Some(rest) => format!("/api/v{}{}", CURRENT_API_VERSION, rest),
+        None => path.to_string(),
+    }
+}
+
+/// Middleware for `/api/v1` routes
+pub async fn v1(request: Request, next: Next) -> Response {
+    serve_version(request, next, 1, false).await
+}
+
+/// Middleware for the unversioned compatibility routes (aliases of the current version)
+pub async fn deprecated_alias(request: Request, next: Next) -> Response {
+    serve_version(request, next, CURRENT_API_VERSION, true).await
+}
+
+async fn serve_version(request: Request, next: Next, version: u32, deprecated: bool) -> Response {
+    let accept_version = request
+        .headers()
+        .get(ACCEPT_VERSION)
+        .and_then(|value| value.to_str().ok());
+    if !is_acceptable(accept_version, version) {
+        return (
+            StatusCode::NOT_ACCEPTABLE,
+            Json(serde_json::json!({
+                "error": "unsupported_api_version",
+                "supported_versions": SUPPORTED_API_VERSIONS,
+            })),
+        )
+            .into_response();
+    }
+
+    // Nested routers strip the prefix from the request URI, so use the original one
+    let successor = deprecated.then(|| {
+        let path = match request.extensions().get::<OriginalUri>() {
+            Some(OriginalUri(uri)) => uri.path(),
+            None => request.uri().path(),
+        };
+        successor_path(path)
+    });
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(API_VERSION, HeaderValue::from(version));
+    if let Some(successor) = successor {
+        headers.insert("deprecation", HeaderValue::from_static("true"));
+        if let Ok(link) =
+            HeaderValue::from_str(&format!("<{}>; rel=\"successor-version\"", successor))
+        {
+            headers.insert("link", link);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_acceptable_without_header() {
+        // テスト項目: Accept-Version が無い場合はどのバージョンでも受け入れられる
+        // when (操作):
+        let result = is_acceptable(None, 1);
+
+        // then (期待する結果):
+        assert!(result);
+    }
+
+    #[test]
+    fn test_is_acceptable_with_matching_version() {
+        // テスト項目: 要求バージョンが一致する場合は受け入れられる（"v" 接頭辞も許容）
+        // then (期待する結果):
+        assert!(is_acceptable(Some("1"), 1));
+        assert!(is_acceptable(Some("v1"), 1));
+    }
+
+    #[test]
+    fn test_is_acceptable_with_unsupported_version() {
+        // テスト項目: 提供できないバージョンや不正な値は受け入れられない
+        // then (期待する結果):
+        assert!(!is_acceptable(Some("2"), 1));
+        assert!(!is_acceptable(Some("latest"), 1));
+    }
+
+    #[test]
+    fn test_successor_path() {
+        // テスト項目: 非バージョンパスから後継のバージョン付きパスが求められる
+        // then (期待する結果):
+        assert_eq!(successor_path("/api/rooms"), "/api/v1/rooms");
+        assert_eq!(successor_path("/api/rooms/abc"), "/api/v1/rooms/abc");
+        assert_eq!(successor_path("/other"), "/other");
+    }
+}
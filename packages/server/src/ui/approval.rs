@@ -0,0 +1,366 @@
+//! Join approval for protected rooms.
+//!
+//! A room with a `join-approval` integration is protected: a client connecting to it is held
+//! in a pending state instead of joining. The pending client receives `join-pending` and
+//! nothing else from the room (no participants, history or messages), and the participants of
+//! the room receive `join-requested`. Moderators list the pending clients with
+//! `GET /api/v1/admin/rooms/{room_id}/pending` and approve or reject them with
+//! `POST /api/v1/admin/rooms/{room_id}/pending/{client_id}/resolve`.
+//!
+//! An approved client joins the room on the same connection, as if it had just connected. A
+//! rejected client's connection is closed with [`JOIN_REJECTED_CLOSE_CODE`].
+
+use std::{collections::HashMap, net::IpAddr, sync::Arc, sync::Mutex, time::Duration};
+
+use axum::extract::ws::{CloseFrame, Message, WebSocket};
+use tokio::sync::oneshot;
+
+use engawa_shared::time::get_jst_timestamp;
+
+use super::{
+    connection::{Connection, restart_frames, shutdown_frames},
+    handler::{ConnectQuery, JoinedClient, connect, connect_error_message},
+    handover::reconnect_delay,
+    presenter::websocket::{join_pending, join_requested},
+    state::AppState,
+};
+use crate::{
+    domain::{ClientId, Locale, RoomId, Timestamp},
+    infrastructure::{
+        dto::websocket::{ErrorMessage, MessageType},
+        protocol::Capabilities,
+    },
+    usecase::RoomUseCases,
+};
+
+/// Close code sent to a pending client a moderator rejected
+pub const JOIN_REJECTED_CLOSE_CODE: u16 = 4014;
+
+/// Close reason sent to a pending client a moderator rejected
+pub const JOIN_REJECTED_REASON: &str = "join-rejected";
+
+/// Moderator's decision on a pending client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinDecision {
+    /// Let the client join the room
+    Approve,
+    /// Close the client's connection
+    Reject,
+}
+
+struct PendingJoin {
+    requested_at: Timestamp,
+    /// Tells the waiting connection what the moderator decided
+    decide: oneshot::Sender<JoinDecision>,
+}
+
+/// Clients waiting for approval, by room and client ID
+#[derive(Default)]
+pub struct PendingJoins {
+    pending: Mutex<HashMap<(RoomId, ClientId), PendingJoin>>,
+}
+
+impl PendingJoins {
+    /// Create a registry with no pending clients
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hold a client asking to join a protected room
+    ///
+    /// Returns a receiver completing with the moderator's decision, or `None` if the client
+    /// is already waiting to join the room on another connection.
+    pub fn register(
+        &self,
+        room_id: &RoomId,
+        client_id: &ClientId,
+        requested_at: Timestamp,
+    ) -> Option<oneshot::Receiver<JoinDecision>> {
+        let mut pending = self.pending.lock().unwrap();
+        let key = (room_id.clone(), client_id.clone());
+        if pending.contains_key(&key) {
+            return None;
+        }
+        let (decide, decided) = oneshot::channel();
+        pending.insert(
+            key,
+            PendingJoin {
+                requested_at,
+                decide,
+            },
+        );
+        Some(decided)
+    }
+
+    /// Forget a client that stopped waiting (decided or disconnected)
+    pub fn unregister(&self, room_id: &RoomId, client_id: &ClientId) {
+        self.pending
+            .lock()
+            .unwrap()
+            .remove(&(room_id.clone(), client_id.clone()));
+    }
+
+    /// Clients waiting to join the room and when they asked, oldest first
+    pub fn list(&self, room_id: &RoomId) -> Vec<(ClientId, Timestamp)> {
+        let mut pending: Vec<_> = self
+            .pending
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((room, _), _)| room == room_id)
+            .map(|((_, client_id), join)| (client_id.clone(), join.requested_at))
+            .collect();
+        pending.sort_by(|(a, a_at), (b, b_at)| {
+            a_at.cmp(b_at).then_with(|| a.as_str().cmp(b.as_str()))
+        });
+        pending
+    }
+
+    /// Tell a pending client the moderator's decision
+    ///
+    /// Returns `false` if the client is not waiting to join the room.
+    pub fn decide(&self, room_id: &RoomId, client_id: &ClientId, decision: JoinDecision) -> bool {
+        let join = self
+            .pending
+            .lock()
+            .unwrap()
+            .remove(&(room_id.clone(), client_id.clone()));
+        // The connection may be closing already
+        join.is_some_and(|join| join.decide.send(decision).is_ok())
+    }
+}
+
+/// Hold the connection of a client asking to join a protected room until a moderator decides
+///
+/// The client joins the room on this connection once approved. Its connection is closed if it
+/// is rejected, and it is no longer counted in the room if it does not join.
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `room` - Protected room the client asked to join
+/// * `client_id` - Client of the connection
+/// * `socket` - Upgraded WebSocket connection
+/// * `query` - Query parameters of the connection request
+/// * `client_ip` - Address of the client (for logging)
+pub(crate) async fn wait_for_approval(
+    state: Arc<AppState>,
+    room: Arc<RoomUseCases>,
+    client_id: ClientId,
+    mut socket: WebSocket,
+    query: ConnectQuery,
+    client_ip: IpAddr,
+) {
+    let requested_at = Timestamp::new(get_jst_timestamp());
+    let Some(decided) = state
+        .pending_joins
+        .register(&room.room_id, &client_id, requested_at)
+    else {
+        tracing::warn!(
+            "Client '{}' is already waiting to join room {}; rejecting the connection from {}",
+            client_id,
+            room.room_id,
+            client_ip
+        );
+        let error = ErrorMessage {
+            r#type: MessageType::Error,
+            code: "join_pending".to_string(),
+            message: "Another connection of this client is waiting for approval".to_string(),
+        };
+        close_with_error(&mut socket, &error).await;
+        state.leave_room(&client_id, &room).await;
+        return;
+    };
+    tracing::info!(
+        "Client '{}' from {} is waiting for approval to join room {}",
+        client_id,
+        client_ip,
+        room.room_id
+    );
+
+    // Protocol negotiated by a `hello` sent while waiting
+    let mut hello = None;
+    let decision = {
+        // Keep the connection counted while waiting so that a draining server waits for it
+        let _connection = state.connections.track();
+        let pending = serde_json::to_string(&join_pending(&room.room_id, state.locale)).unwrap();
+        let requested = join_requested(&room.room_id, &client_id, requested_at);
+        if let Err(e) = room
+            .connect_participant
+            .broadcast_to_participants(&serde_json::to_string(&requested).unwrap())
+            .await
+        {
+            tracing::warn!("Failed to broadcast join-requested: {}", e);
+        }
+
+        if socket.send(Message::Text(pending.into())).await.is_err() {
+            None
+        } else {
+            // Frames sent before closing if the server stops while the client waits
+            let mut closing: Option<fn(Duration, Locale) -> Vec<Message>> = None;
+            let decision = tokio::select! {
+                decision = decided => decision.ok(),
+                _ = client_closed(&mut socket, &mut hello) => None,
+                _ = state.draining.cancelled() => {
+                    closing = Some(restart_frames);
+                    None
+                }
+                _ = state.stopping.cancelled() => {
+                    // A handover triggers `draining` before stopping this process
+                    closing = Some(if state.draining.is_triggered() {
+                        restart_frames
+                    } else {
+                        shutdown_frames
+                    });
+                    None
+                }
+            };
+            if let Some(frames) = closing {
+                // Ask the client to reconnect (and wait again) once the server is back
+                let reconnect_after = reconnect_delay(client_id.as_str(), state.reconnect_stagger);
+                for frame in frames(reconnect_after, state.locale) {
+                    let _ = socket.send(frame).await;
+                }
+            }
+            decision
+        }
+    };
+    state.pending_joins.unregister(&room.room_id, &client_id);
+
+    match decision {
+        Some(JoinDecision::Approve) => {
+            tracing::info!(
+                "Client '{}' was approved to join room {}",
+                client_id,
+                room.room_id
+            );
+            match connect(&state, &room, Some(client_id.clone()), client_ip).await {
+                Ok(JoinedClient {
+                    client_id,
+                    connected,
+                    outbox,
+                    rx,
+                }) => {
+                    Connection::new(state, room, client_id, connected.joined)
+                        .with_hello(hello)
+                        .run(socket, outbox, rx, query, connected.connected_at)
+                        .await;
+                }
+                Err(e) => {
+                    tracing::warn!("Approved client '{}' could not join: {:?}", client_id, e);
+                    close_with_error(&mut socket, &connect_error_message(&e)).await;
+                }
+            }
+        }
+        Some(JoinDecision::Reject) => {
+            tracing::info!(
+                "Client '{}' was rejected from room {}",
+                client_id,
+                room.room_id
+            );
+            let frame = CloseFrame {
+                code: JOIN_REJECTED_CLOSE_CODE,
+                reason: JOIN_REJECTED_REASON.into(),
+            };
+            let _ = socket.send(Message::Close(Some(frame))).await;
+            state.leave_room(&client_id, &room).await;
+        }
+        None => {
+            tracing::info!(
+                "Client '{}' stopped waiting to join room {}",
+                client_id,
+                room.room_id
+            );
+            state.leave_room(&client_id, &room).await;
+        }
+    }
+}
+
+/// Wait until the client closes the connection
+///
+/// Frames sent while pending are ignored, except `hello`: the protocol it negotiates is kept
+/// in `hello` for the connection once approved.
+async fn client_closed(socket: &mut WebSocket, hello: &mut Option<Capabilities>) {
+    while let Some(Ok(frame)) = socket.recv().await {
+        match frame {
+            Message::Close(_) => return,
+            Message::Text(text) => {
+                if let Some(capabilities) = Capabilities::from_hello(&text) {
+                    *hello = Some(capabilities);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Send an `error` message and close the connection
+async fn close_with_error(socket: &mut WebSocket, error: &ErrorMessage) {
+    let error = serde_json::to_string(error).unwrap();
+    let _ = socket.send(Message::Text(error.into())).await;
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::RoomIdFactory;
+
+    fn client_id(name: &str) -> ClientId {
+        ClientId::new(name.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_pending_clients_listed_per_room_oldest_first() {
+        // テスト項目: 承認待ちのクライアントはルームごとに古い順で一覧でき、同じルームに二重には登録できない
+        // given (前提条件):
+        let registry = PendingJoins::new();
+        let room = RoomIdFactory::generate().unwrap();
+        let other = RoomIdFactory::generate().unwrap();
+        let _bob = registry.register(&room, &client_id("bob"), Timestamp::new(2000));
+        let _alice = registry.register(&room, &client_id("alice"), Timestamp::new(1000));
+        let _carol = registry.register(&other, &client_id("carol"), Timestamp::new(1500));
+
+        // when (操作):
+        let pending = registry.list(&room);
+        let again = registry.register(&room, &client_id("bob"), Timestamp::new(3000));
+        registry.unregister(&room, &client_id("alice"));
+
+        // then (期待する結果):
+        assert_eq!(
+            pending,
+            vec![
+                (client_id("alice"), Timestamp::new(1000)),
+                (client_id("bob"), Timestamp::new(2000)),
+            ]
+        );
+        assert!(again.is_none());
+        assert_eq!(
+            registry.list(&room),
+            vec![(client_id("bob"), Timestamp::new(2000))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decide_notifies_pending_client_once() {
+        // テスト項目: 決定は承認待ちの接続に 1 度だけ通知され、承認待ちでないクライアントには通知できない
+        // given (前提条件):
+        let registry = PendingJoins::new();
+        let room = RoomIdFactory::generate().unwrap();
+        let decided = registry
+            .register(&room, &client_id("alice"), Timestamp::new(1000))
+            .unwrap();
+
+        // when (操作):
+        let approved = registry.decide(&room, &client_id("alice"), JoinDecision::Approve);
+        let again = registry.decide(&room, &client_id("alice"), JoinDecision::Reject);
+        let unknown = registry.decide(&room, &client_id("bob"), JoinDecision::Approve);
+
+        // then (期待する結果):
+        assert!(approved);
+        assert_eq!(decided.await, Ok(JoinDecision::Approve));
+        assert!(!again);
+        assert!(!unknown);
+        assert!(registry.list(&room).is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+//! Token-based authentication of WebSocket connections and room endpoints.
+//!
+//! Clients log in at `POST /api/v1/auth/login` with their client ID and password and receive
+//! a JWT access token. While authentication is enabled, `/ws` and `/api/v1/rooms/...` require
+//! the token as `Authorization: Bearer <token>` (or `?access_token=<token>`, since browsers
+//! cannot set headers on WebSocket requests); the authenticated client is passed to the
+//! handlers as a `ClientIdentity` request extension.
+
+use std::{sync::Arc, time::SystemTime};
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderValue, StatusCode, header::AUTHORIZATION, header::WWW_AUTHENTICATE},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use super::state::AppState;
+use crate::{
+    domain::ClientIdentity,
+    infrastructure::{
+        auth::{AccessTokens, Credentials},
+        error::AuthError,
+    },
+};
+
+/// Query parameter carrying the access token (for WebSocket clients that cannot set headers)
+pub const ACCESS_TOKEN_QUERY: &str = "access_token";
+
+/// Clients allowed to log in and the access tokens issued to them
+pub struct Authentication {
+    credentials: Credentials,
+    tokens: AccessTokens,
+}
+
+impl Authentication {
+    /// Enable authentication with the clients allowed to log in
+    pub fn new(credentials: Credentials, tokens: AccessTokens) -> Self {
+        Self {
+            credentials,
+            tokens,
+        }
+    }
+
+    /// Check the password of the client and issue an access token
+    pub fn login(&self, client_id: &str, password: &str) -> Result<String, AuthError> {
+        let client_id = self.credentials.verify(client_id, password)?;
+        Ok(self.tokens.issue(&client_id, SystemTime::now()))
+    }
+
+    /// Lifetime of the issued access tokens in seconds
+    pub fn token_ttl_secs(&self) -> u64 {
+        self.tokens.ttl().as_secs()
+    }
+
+    /// Check an access token and get the client it was issued to
+    pub fn verify(&self, token: &str) -> Result<ClientIdentity, AuthError> {
+        self.tokens.verify(token)
+    }
+}
+
+/// Middleware requiring a valid access token while authentication is enabled
+///
+/// Responds with `401 Unauthorized` (and `WWW-Authenticate: Bearer`) if the token is missing,
+/// invalid or expired.
+pub async fn require_token(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(auth) = &state.auth else {
+        return next.run(request).await;
+    };
+    let identity = match bearer_token(&request).map(|token| auth.verify(token)) {
+        Some(Ok(identity)) => identity,
+        Some(Err(e)) => {
+            tracing::debug!("Refusing {}: {}", request.uri().path(), e);
+            return unauthorized(&e.to_string());
+        }
+        None => return unauthorized("access token required"),
+    };
+    request.extensions_mut().insert(identity);
+    next.run(request).await
+}
+
+/// Get the access token from the `Authorization` header or the query
+fn bearer_token(request: &Request) -> Option<&str> {
+    let header = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    header.or_else(|| {
+        request.uri().query()?.split('&').find_map(|pair| {
+            pair.strip_prefix(ACCESS_TOKEN_QUERY)?
+                .strip_prefix('=')
+                .filter(|token| !token.is_empty())
+        })
+    })
+}
+
+fn unauthorized(reason: &str) -> Response {
+    let challenge = format!(
+        "Bearer error=\"invalid_token\", error_description=\"{}\"",
+        reason
+    );
+    let challenge = HeaderValue::from_str(&challenge).unwrap_or(HeaderValue::from_static("Bearer"));
+    (StatusCode::UNAUTHORIZED, [(WWW_AUTHENTICATE, challenge)]).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    #[test]
+    fn test_bearer_token_from_header_or_query() {
+        // テスト項目: アクセストークンを Authorization ヘッダーまたはクエリから取得する（ヘッダーを優先）
+        // given (前提条件):
+        let header = Request::builder()
+            .uri("/ws?access_token=from-query")
+            .header(AUTHORIZATION, "Bearer from-header")
+            .body(Body::empty())
+            .unwrap();
+        let query = Request::builder()
+            .uri("/ws?client_id=alice&access_token=from-query")
+            .body(Body::empty())
+            .unwrap();
+        let missing = Request::builder()
+            .uri("/ws?client_id=alice&access_token=")
+            .body(Body::empty())
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(bearer_token(&header), Some("from-header"));
+        assert_eq!(bearer_token(&query), Some("from-query"));
+        assert_eq!(bearer_token(&missing), None);
+    }
+}
@@ -0,0 +1,94 @@
+//! Breakout rooms spawned from a parent room.
+//!
+//! Creating a breakout at `POST /api/v1/rooms/{room_id}/breakouts` posts a chat message from
+//! [`BREAKOUT_SENDER`] to the parent room with the breakout's room ID, in the server's locale.
+//! A background task archives breakouts left unused for the idle timeout and tells the parent
+//! room about it the same way.
+
+use std::{sync::Arc, time::Duration};
+
+use engawa_shared::time::get_jst_timestamp;
+
+use crate::{
+    domain::{Breakout, ClientId, MessageContent, Timestamp},
+    infrastructure::{
+        dto::websocket::{ChatMessage, MessageType},
+        i18n::SystemText,
+    },
+    usecase::ManageBreakoutsUseCase,
+};
+
+use super::{signal::ShutdownToken, state::AppState};
+
+/// Client ID the breakout notices are posted as
+pub const BREAKOUT_SENDER: &str = "breakout";
+
+/// Longest wait between two checks for unused breakouts
+const ARCHIVE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Post a notice about a breakout to its parent room as a chat message
+pub async fn announce(state: &AppState, breakout: &Breakout, text: SystemText<'_>) {
+    let text = text.localize(state.locale);
+    let parent = match state.join_room(Some(breakout.parent_id.as_str())).await {
+        Ok(parent) => parent,
+        Err(e) => {
+            tracing::warn!(
+                "Parent room {} of breakout {} is gone: {:?}",
+                breakout.parent_id,
+                breakout.room_id,
+                e
+            );
+            return;
+        }
+    };
+    let Ok(content) = MessageContent::new(text.clone()) else {
+        tracing::warn!("Breakout notice is not a valid message: {}", text);
+        return;
+    };
+    let message = ChatMessage {
+        r#type: MessageType::Chat,
+        client_id: BREAKOUT_SENDER.to_string(),
+        content: text,
+        timestamp: get_jst_timestamp(),
+        seq: None,
+        poll: None,
+        forwarded_from: None,
+    };
+    let sender = ClientId::new(BREAKOUT_SENDER.to_string()).expect("Invalid breakout sender");
+    if let Err(e) = parent
+        .send_message
+        .execute(sender, content, move |seq| {
+            message.to_json_with_seq(seq.value())
+        })
+        .await
+    {
+        tracing::warn!(
+            "Failed to post the breakout notice to room {}: {:?}",
+            breakout.parent_id,
+            e
+        );
+    }
+}
+
+/// Archive unused breakouts and tell their parent rooms until the server shuts down
+pub async fn run_breakout_archiver(
+    usecase: Arc<ManageBreakoutsUseCase>,
+    state: Arc<AppState>,
+    shutdown: ShutdownToken,
+) {
+    let interval = usecase.idle_timeout().min(ARCHIVE_CHECK_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = shutdown.cancelled() => return,
+        }
+
+        let now = Timestamp::new(get_jst_timestamp());
+        for breakout in usecase.archive_idle(now).await {
+            let text = SystemText::BreakoutArchived {
+                name: &breakout.name,
+            };
+            announce(&state, &breakout, text).await;
+        }
+    }
+}
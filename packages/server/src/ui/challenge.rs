@@ -0,0 +1,71 @@
+//! Connect challenge: a proof-of-work required before the WebSocket upgrade.
+//!
+//! While enabled (at startup with `--connect-challenge`, or at runtime by an admin), clients
+//! fetch a challenge from `GET /api/v1/challenge`, find a nonce whose hash meets the difficulty,
+//! and connect with `/ws?pow=<challenge>:<nonce>` (or the `X-Engawa-Pow` header). Each
+//! challenge admits a single connection, which makes join floods expensive.
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Instant,
+};
+
+use crate::infrastructure::{
+    error::ProofOfWorkError,
+    proof_of_work::{DEFAULT_POW_DIFFICULTY, ProofOfWork},
+};
+
+/// Header carrying the solution for clients that can set headers on the upgrade request
+pub const POW_HEADER: &str = "x-engawa-pow";
+
+/// Whether connecting clients must solve a proof-of-work, and its challenges
+pub struct ConnectChallenge {
+    enabled: AtomicBool,
+    pow: ProofOfWork,
+}
+
+impl Default for ConnectChallenge {
+    fn default() -> Self {
+        Self::new(false, DEFAULT_POW_DIFFICULTY)
+    }
+}
+
+impl ConnectChallenge {
+    /// Create a connect challenge
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether connections require a solution from startup
+    /// * `difficulty` - Leading zero bits required of the solution's hash
+    pub fn new(enabled: bool, difficulty: u8) -> Self {
+        Self {
+            enabled: AtomicBool::new(enabled),
+            pow: ProofOfWork::new(difficulty),
+        }
+    }
+
+    /// Whether connections currently require a solution
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Require (or stop requiring) a solution from new connections
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Leading zero bits required of the solution's hash
+    pub fn difficulty(&self) -> u8 {
+        self.pow.difficulty()
+    }
+
+    /// Issue a challenge (`None` while the challenge is disabled)
+    pub fn issue(&self, now: Instant) -> Option<String> {
+        self.is_enabled().then(|| self.pow.issue(now))
+    }
+
+    /// Verify the solution of a connecting client (each challenge is accepted once)
+    pub fn verify(&self, solution: &str, now: Instant) -> Result<(), ProofOfWorkError> {
+        self.pow.verify(solution, now)
+    }
+}
@@ -0,0 +1,327 @@
+//! Client IP resolution behind reverse proxies.
+//!
+//! When the server runs behind nginx or a cloud load balancer, the TCP peer address is the
+//! proxy's address. The real client address is taken from `Forwarded` (RFC 7239) or
+//! `X-Forwarded-For` headers, but only when the peer is a configured trusted proxy;
+//! otherwise the headers could be spoofed by the client itself.
+
+use std::{
+    fmt,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::Arc,
+};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{HeaderMap, request::Parts},
+};
+
+use super::{error::IpNetworkError, state::AppState};
+
+/// IP network in CIDR notation (e.g. `10.0.0.0/8`, `::1/128`).
+///
+/// A bare address (e.g. `127.0.0.1`) is treated as a single-host network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    address: IpAddr,
+    prefix: u8,
+}
+
+impl IpNetwork {
+    /// Check whether the network contains the given address
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.address, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            // IPv4-mapped IPv6 peers (e.g. on dual-stack listeners)
+            (IpAddr::V4(_), IpAddr::V6(ip)) => ip
+                .to_ipv4_mapped()
+                .is_some_and(|ip| self.contains(IpAddr::V4(ip))),
+            (IpAddr::V6(_), IpAddr::V4(_)) => false,
+        }
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = IpNetworkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address_str, prefix_str) = match s.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (s, None),
+        };
+        let address: IpAddr = address_str
+            .trim()
+            .parse()
+            .map_err(|_| IpNetworkError::InvalidAddress(address_str.to_string()))?;
+        let max = if address.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix_str {
+            Some(prefix) => prefix
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max)
+                .ok_or_else(|| IpNetworkError::InvalidPrefix {
+                    prefix: prefix.to_string(),
+                    max,
+                })?,
+            None => max,
+        };
+        Ok(Self { address, prefix })
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix)
+    }
+}
+
+/// Set of proxies whose forwarding headers are trusted
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    networks: Vec<IpNetwork>,
+}
+
+impl TrustedProxies {
+    /// Create a new set of trusted proxies
+    pub fn new(networks: Vec<IpNetwork>) -> Self {
+        Self { networks }
+    }
+
+    /// Check whether the address belongs to a trusted proxy
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.networks.iter().any(|network| network.contains(ip))
+    }
+
+    /// Resolve the real client IP address.
+    ///
+    /// Forwarding headers are only honored when `peer` is a trusted proxy. The forwarding
+    /// chain is walked from the nearest hop outwards and the first untrusted address is
+    /// returned, so clients cannot spoof their address by prepending entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `peer` - Address of the TCP peer
+    /// * `headers` - Request headers
+    pub fn resolve(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        if !self.is_trusted(peer) {
+            return peer;
+        }
+
+        let chain = forwarded_chain(headers);
+        let mut resolved = peer;
+        for hop in chain.iter().rev() {
+            match hop {
+                Some(ip) => {
+                    resolved = *ip;
+                    if !self.is_trusted(*ip) {
+                        break;
+                    }
+                }
+                // Obfuscated or malformed entry: nothing beyond it can be trusted
+                None => break,
+            }
+        }
+        resolved
+    }
+}
+
+/// Extract the forwarding chain (client first) from `Forwarded` or `X-Forwarded-For`.
+///
+/// `Forwarded` takes precedence when present. Entries that are not IP addresses
+/// (e.g. `for=unknown`) are returned as `None`.
+fn forwarded_chain(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    let forwarded: Vec<&str> = headers
+        .get_all("forwarded")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded
+            .iter()
+            .flat_map(|value| value.split(','))
+            .filter_map(|element| {
+                element.split(';').find_map(|pair| {
+                    let (key, value) = pair.split_once('=')?;
+                    key.trim()
+                        .eq_ignore_ascii_case("for")
+                        .then(|| parse_node(value))
+                })
+            })
+            .collect();
+    }
+
+    headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(parse_node)
+        .collect()
+}
+
+/// Parse a node identifier such as `192.0.2.1`, `192.0.2.1:8080`, `"[2001:db8::1]:4711"`.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(ip);
+    }
+    if let Some(rest) = node.strip_prefix('[') {
+        return rest.split(']').next()?.parse().ok();
+    }
+    node.parse::<SocketAddr>().ok().map(|addr| addr.ip())
+}
+
+/// Extractor for the real client IP address, honoring trusted proxies.
+///
+/// Requires the router to be served with `into_make_service_with_connect_info::<SocketAddr>()`.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub IpAddr);
+
+impl FromRequestParts<Arc<AppState>> for ClientIp {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+            .unwrap_or(IpAddr::from([0, 0, 0, 0]));
+        Ok(Self(state.trusted_proxies.resolve(peer, &parts.headers)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn proxies(networks: &[&str]) -> TrustedProxies {
+        TrustedProxies::new(networks.iter().map(|n| n.parse().unwrap()).collect())
+    }
+
+    #[test]
+    fn test_ip_network_parse_and_contains() {
+        // テスト項目: CIDR 表記をパースし、範囲内のアドレスを判定できる
+        // given (前提条件):
+        let network: IpNetwork = "10.0.0.0/8".parse().unwrap();
+
+        // when (操作):
+        let inside = network.contains(ip("10.1.2.3"));
+        let outside = network.contains(ip("11.0.0.1"));
+
+        // then (期待する結果):
+        assert!(inside);
+        assert!(!outside);
+    }
+
+    #[test]
+    fn test_ip_network_bare_address_is_single_host() {
+        // テスト項目: プレフィックスなしのアドレスは単一ホストとして扱われる
+        // given (前提条件):
+        let network: IpNetwork = "::1".parse().unwrap();
+
+        // then (期待する結果):
+        assert_eq!(network.to_string(), "::1/128");
+        assert!(network.contains(ip("::1")));
+        assert!(!network.contains(ip("::2")));
+    }
+
+    #[test]
+    fn test_ip_network_parse_errors() {
+        // テスト項目: 不正な CIDR 表記はエラーになる
+        // when (操作):
+        let invalid_address = "not-an-ip/8".parse::<IpNetwork>();
+        let invalid_prefix = "10.0.0.0/33".parse::<IpNetwork>();
+
+        // then (期待する結果):
+        assert!(matches!(
+            invalid_address,
+            Err(IpNetworkError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            invalid_prefix,
+            Err(IpNetworkError::InvalidPrefix { max: 32, .. })
+        ));
+    }
+
+    #[test]
+    fn test_resolve_ignores_headers_from_untrusted_peer() {
+        // テスト項目: 信頼されていない peer からの転送ヘッダーは無視される（なりすまし防止）
+        // given (前提条件):
+        let trusted = proxies(&["10.0.0.0/8"]);
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("1.2.3.4"));
+
+        // when (操作):
+        let resolved = trusted.resolve(ip("203.0.113.9"), &headers);
+
+        // then (期待する結果):
+        assert_eq!(resolved, ip("203.0.113.9"));
+    }
+
+    #[test]
+    fn test_resolve_x_forwarded_for_skips_trusted_hops() {
+        // テスト項目: X-Forwarded-For の右端から信頼済みプロキシを飛ばして実 IP を特定する
+        // given (前提条件):
+        let trusted = proxies(&["10.0.0.0/8"]);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("6.6.6.6, 198.51.100.7, 10.0.0.2"),
+        );
+
+        // when (操作):
+        let resolved = trusted.resolve(ip("10.0.0.1"), &headers);
+
+        // then (期待する結果): 先頭の偽装値ではなく最初の非信頼アドレスが選ばれる
+        assert_eq!(resolved, ip("198.51.100.7"));
+    }
+
+    #[test]
+    fn test_resolve_forwarded_header() {
+        // テスト項目: Forwarded ヘッダー（RFC 7239）から実 IP を特定する
+        // given (前提条件):
+        let trusted = proxies(&["127.0.0.1"]);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "forwarded",
+            HeaderValue::from_static("for=\"[2001:db8:cafe::17]:4711\";proto=https"),
+        );
+        headers.insert("x-forwarded-for", HeaderValue::from_static("9.9.9.9"));
+
+        // when (操作):
+        let resolved = trusted.resolve(ip("127.0.0.1"), &headers);
+
+        // then (期待する結果): Forwarded が優先される
+        assert_eq!(resolved, ip("2001:db8:cafe::17"));
+    }
+
+    #[test]
+    fn test_resolve_without_headers_returns_peer() {
+        // テスト項目: 転送ヘッダーが無い場合は peer のアドレスが返される
+        // given (前提条件):
+        let trusted = proxies(&["127.0.0.1"]);
+
+        // when (操作):
+        let resolved = trusted.resolve(ip("127.0.0.1"), &HeaderMap::new());
+
+        // then (期待する結果):
+        assert_eq!(resolved, ip("127.0.0.1"));
+    }
+}
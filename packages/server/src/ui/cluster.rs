@@ -0,0 +1,237 @@
+//! Cluster membership gossip.
+//!
+//! Every [`GOSSIP_INTERVAL`], each node advances its heartbeat and sends its membership (its
+//! own participant count and the peers it considers alive) as a UDP datagram to a few peers
+//! and to any seed node it has not heard from yet. Received gossip is merged into the
+//! [`ClusterMembership`] exposed by `/api/v1/admin/cluster`.
+//!
+//! Rooms are sharded across the alive nodes by consistent hashing ([`RoomShards`]). A client
+//! that connects with `/ws?room=...` to a node not owning the room is redirected to the owner,
+//! and connections whose room moves to another node after a membership change are closed with
+//! [`ROOM_MOVED_CLOSE_CODE`] so that the client reconnects to the new owner. Until a node hosts
+//! several rooms, all room keys owned by a node share its room.
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    net::UdpSocket,
+    sync::{Mutex, oneshot},
+};
+
+use crate::infrastructure::{
+    cluster::ClusterMembership,
+    dto::cluster::{GossipMessage, GossipNode},
+};
+use engawa_shared::time::get_jst_timestamp;
+
+use super::{signal::ShutdownToken, state::AppState};
+
+/// Interval between gossip rounds
+pub const GOSSIP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of peers gossiped to every round
+const FANOUT: usize = 3;
+
+/// Maximum size of a gossip datagram
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// WebSocket close code sent to clients whose room moved to another node
+///
+/// The close reason is the client address of the new owner.
+pub const ROOM_MOVED_CLOSE_CODE: u16 = 4010;
+
+/// Node of the cluster
+pub struct ClusterNode {
+    bind_addr: SocketAddr,
+    seeds: Vec<SocketAddr>,
+    membership: Arc<Mutex<ClusterMembership>>,
+    shards: Arc<RoomShards>,
+}
+
+impl ClusterNode {
+    /// Create a new cluster node
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - ID of this node, unique in the cluster
+    /// * `bind_addr` - Address to receive gossip on (UDP)
+    /// * `advertise_addr` - Gossip address other nodes should use (usually `bind_addr`)
+    /// * `http_addr` - Client address advertised to other nodes
+    /// * `seeds` - Gossip addresses of nodes to join the cluster through
+    pub fn new(
+        node_id: String,
+        bind_addr: SocketAddr,
+        advertise_addr: SocketAddr,
+        http_addr: String,
+        seeds: Vec<SocketAddr>,
+    ) -> Self {
+        // Heartbeats start from the current time so that a restarted node is not ignored
+        let membership = ClusterMembership::new(
+            node_id,
+            advertise_addr,
+            http_addr,
+            get_jst_timestamp().max(0) as u64,
+        );
+        let membership = Arc::new(Mutex::new(membership));
+        Self {
+            bind_addr,
+            seeds,
+            shards: Arc::new(RoomShards::new(membership.clone())),
+            membership,
+        }
+    }
+
+    /// Membership shared with the admin endpoint
+    pub fn membership(&self) -> Arc<Mutex<ClusterMembership>> {
+        self.membership.clone()
+    }
+
+    /// Room sharding shared with the WebSocket handler
+    pub fn room_shards(&self) -> Arc<RoomShards> {
+        self.shards.clone()
+    }
+}
+
+/// Connection sharded by room
+struct ShardedConnection {
+    room: String,
+    /// Receives the client address of the new owner when the room moves
+    moved: oneshot::Sender<String>,
+}
+
+/// Assignment of rooms to cluster nodes and the local connections of each room
+pub struct RoomShards {
+    membership: Arc<Mutex<ClusterMembership>>,
+    /// Local connections keyed by client ID
+    connections: std::sync::Mutex<HashMap<String, ShardedConnection>>,
+}
+
+impl RoomShards {
+    fn new(membership: Arc<Mutex<ClusterMembership>>) -> Self {
+        Self {
+            membership,
+            connections: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Node owning the room, or `None` if this node owns it
+    pub async fn remote_owner(&self, room: &str) -> Option<GossipNode> {
+        let membership = self.membership.lock().await;
+        let owner = membership.room_owner(room, Instant::now());
+        (owner.node_id != membership.node_id()).then(|| owner.clone())
+    }
+
+    /// Track a local connection to the room
+    ///
+    /// The returned receiver gets the client address of the new owner if the room moves.
+    pub fn track(&self, client_id: &str, room: String) -> oneshot::Receiver<String> {
+        let (moved, receiver) = oneshot::channel();
+        self.connections
+            .lock()
+            .unwrap()
+            .insert(client_id.to_string(), ShardedConnection { room, moved });
+        receiver
+    }
+
+    /// Stop tracking a local connection
+    pub fn untrack(&self, client_id: &str) {
+        self.connections.lock().unwrap().remove(client_id);
+    }
+
+    /// Notify the connections whose room is now owned by another node
+    async fn rebalance(&self) {
+        let membership = self.membership.lock().await;
+        let now = Instant::now();
+        let mut connections = self.connections.lock().unwrap();
+        let moved: Vec<String> = connections
+            .iter()
+            .filter(|(_, connection)| {
+                membership.room_owner(&connection.room, now).node_id != membership.node_id()
+            })
+            .map(|(client_id, _)| client_id.clone())
+            .collect();
+        for client_id in moved {
+            let Some(connection) = connections.remove(&client_id) else {
+                continue;
+            };
+            let owner = membership.room_owner(&connection.room, now);
+            tracing::info!(
+                "Room '{}' moved to node '{}'; closing connection of '{}'",
+                connection.room,
+                owner.node_id,
+                client_id
+            );
+            let _ = connection.moved.send(owner.http_addr.clone());
+        }
+    }
+}
+
+/// Run the gossip loop until shutdown is requested
+pub async fn run_gossip(node: ClusterNode, state: Arc<AppState>, shutdown: ShutdownToken) {
+    let socket = match UdpSocket::bind(node.bind_addr).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::error!("Failed to bind gossip socket {}: {}", node.bind_addr, e);
+            return;
+        }
+    };
+    tracing::info!(
+        "Cluster node '{}' gossiping on {}",
+        node.membership.lock().await.node_id(),
+        node.bind_addr
+    );
+
+    let mut interval = tokio::time::interval(GOSSIP_INTERVAL);
+    let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let participant_count = state
+                    .connect_participant_usecase
+                    .build_participant_list()
+                    .await
+                    .len();
+                let now = Instant::now();
+                let (message, targets) = {
+                    let mut membership = node.membership.lock().await;
+                    membership.expire(now);
+                    let message = membership.heartbeat(participant_count, now);
+                    (message, membership.gossip_targets(&node.seeds, FANOUT, now))
+                };
+                send(&socket, &message, &targets).await;
+                node.shards.rebalance().await;
+            }
+            received = socket.recv_from(&mut buf) => match received {
+                Ok((len, from)) => match serde_json::from_slice::<GossipMessage>(&buf[..len]) {
+                    Ok(message) => node.membership.lock().await.merge(message, Instant::now()),
+                    Err(e) => tracing::debug!("Ignoring invalid gossip from {}: {}", from, e),
+                },
+                Err(e) => tracing::debug!("Failed to receive gossip: {}", e),
+            },
+            _ = shutdown.cancelled() => break,
+        }
+    }
+
+    // Let the peers remove this node right away instead of waiting for it to time out
+    let (message, targets) = {
+        let mut membership = node.membership.lock().await;
+        let targets = membership.gossip_targets(&[], usize::MAX, Instant::now());
+        (membership.leave(), targets)
+    };
+    send(&socket, &message, &targets).await;
+    tracing::info!("Cluster gossip stopped");
+}
+
+async fn send(socket: &UdpSocket, message: &GossipMessage, targets: &[SocketAddr]) {
+    let payload = serde_json::to_vec(message).unwrap();
+    for target in targets {
+        if let Err(e) = socket.send_to(&payload, target).await {
+            tracing::debug!("Failed to send gossip to {}: {}", target, e);
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! Server configuration file.
+//!
+//! The basic settings can be kept in a TOML or YAML file given with `--config` (the format is
+//! chosen by the extension) instead of being repeated on the command line:
+//!
+//! ```toml
+//! host = "0.0.0.0"
+//! port = 3000
+//! log_level = "info"
+//! log_filter = "engawa_server::ui::handler=debug"
+//! room_capacity = 50
+//! message_capacity = 1000
+//! messages_per_second = 5
+//! message_burst = 10
+//! storage = "sqlite"
+//! db_path = "engawa.db"
+//! message_filters = ["max-links:2", "regex:(?i)buy now"]
+//! ```
+//!
+//! Each setting is overridden by the environment variable of the same name in upper case with
+//! the `ENGAWA_` prefix (e.g. `ENGAWA_PORT`), and command-line options override both.
+//!
+//! `log_level` and `log_filter` are read again on SIGHUP, so the logging of a running server can
+//! be changed by editing the file; the other settings take effect on restart.
+
+use std::{fmt::Display, path::Path, path::PathBuf, str::FromStr};
+
+use engawa_shared::logger::LOG_LEVELS;
+use serde::{Deserialize, Deserializer};
+
+use super::{
+    config::{MessageFilterSpec, StorageBackend},
+    error::ConfigFileError,
+};
+
+/// Settings read from the configuration file (`None` if not set)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Host address to bind the server to (`--host`)
+    pub host: Option<String>,
+    /// Port number to bind the server to (`--port`)
+    pub port: Option<u16>,
+    /// Log level of the server's crates (`--log-level`)
+    pub log_level: Option<String>,
+    /// Additional log filter directives in EnvFilter syntax (`--log-filter`)
+    pub log_filter: Option<String>,
+    /// Maximum number of participants in the room (`--room-capacity`)
+    pub room_capacity: Option<usize>,
+    /// Maximum number of messages kept in the room (`--message-capacity`)
+    pub message_capacity: Option<usize>,
+    /// Messages a client may send per second on average (`--messages-per-second`)
+    pub messages_per_second: Option<u32>,
+    /// Messages a client may send in a row (`--message-burst`)
+    pub message_burst: Option<u32>,
+    /// Repository backend of the room (`--storage`)
+    #[serde(deserialize_with = "from_str")]
+    pub storage: Option<StorageBackend>,
+    /// SQLite database file of the sqlite storage (`--db-path`)
+    pub db_path: Option<PathBuf>,
+    /// Filters applied in order to the messages sent to the rooms (`--message-filter`)
+    #[serde(deserialize_with = "from_str_list")]
+    pub message_filters: Option<Vec<MessageFilterSpec>>,
+}
+
+impl ConfigFile {
+    /// Load the settings from a `.toml`, `.yaml` or `.yml` file
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigFileError` if the file cannot be read, has another extension, or is not
+    /// a valid configuration
+    pub fn load(path: &Path) -> Result<Self, ConfigFileError> {
+        let display = path.display().to_string();
+        let contents = std::fs::read_to_string(path).map_err(|source| ConfigFileError::Read {
+            path: display.clone(),
+            source,
+        })?;
+        let invalid = |reason: String| ConfigFileError::Parse {
+            path: display.clone(),
+            reason,
+        };
+        let file: Self = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => toml::from_str(&contents).map_err(|e| invalid(e.to_string()))?,
+            Some("yaml" | "yml") => {
+                serde_yaml::from_str(&contents).map_err(|e| invalid(e.to_string()))?
+            }
+            _ => return Err(ConfigFileError::UnsupportedFormat { path: display }),
+        };
+        if let Some(level) = &file.log_level {
+            parse_log_level(level).map_err(invalid)?;
+        }
+        Ok(file)
+    }
+
+    /// Override the settings with the `ENGAWA_*` environment variables looked up by `var`
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigFileError::InvalidEnv` if a variable has an invalid value
+    pub fn with_env(
+        mut self,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigFileError> {
+        override_with(&var, "ENGAWA_HOST", &mut self.host, parse)?;
+        override_with(&var, "ENGAWA_PORT", &mut self.port, parse)?;
+        override_with(
+            &var,
+            "ENGAWA_LOG_LEVEL",
+            &mut self.log_level,
+            parse_log_level,
+        )?;
+        override_with(&var, "ENGAWA_LOG_FILTER", &mut self.log_filter, parse)?;
+        override_with(&var, "ENGAWA_ROOM_CAPACITY", &mut self.room_capacity, parse)?;
+        override_with(
+            &var,
+            "ENGAWA_MESSAGE_CAPACITY",
+            &mut self.message_capacity,
+            parse,
+        )?;
+        override_with(
+            &var,
+            "ENGAWA_MESSAGES_PER_SECOND",
+            &mut self.messages_per_second,
+            parse,
+        )?;
+        override_with(&var, "ENGAWA_MESSAGE_BURST", &mut self.message_burst, parse)?;
+        override_with(&var, "ENGAWA_STORAGE", &mut self.storage, parse)?;
+        override_with(&var, "ENGAWA_DB_PATH", &mut self.db_path, parse)?;
+        Ok(self)
+    }
+}
+
+/// Replace `setting` with the value of the environment variable `name` if it is set
+fn override_with<T>(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &'static str,
+    setting: &mut Option<T>,
+    parse: fn(&str) -> Result<T, String>,
+) -> Result<(), ConfigFileError> {
+    if let Some(value) = var(name) {
+        let parsed = parse(&value).map_err(|reason| ConfigFileError::InvalidEnv {
+            var: name,
+            value,
+            reason,
+        })?;
+        *setting = Some(parsed);
+    }
+    Ok(())
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<T, String>
+where
+    T::Err: Display,
+{
+    value.parse().map_err(|e: T::Err| e.to_string())
+}
+
+fn parse_log_level(value: &str) -> Result<String, String> {
+    if LOG_LEVELS.contains(&value) {
+        Ok(value.to_string())
+    } else {
+        Err(format!(
+            "unknown log level '{}' (expected: {})",
+            value,
+            LOG_LEVELS.join(", ")
+        ))
+    }
+}
+
+/// Deserialize a setting written as a string in the format of its command-line option
+fn from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// Deserialize a list of settings written as strings in the format of their command-line option
+fn from_str_list<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    Option::<Vec<String>>::deserialize(deserializer)?
+        .map(|values| {
+            values
+                .iter()
+                .map(|value| value.parse().map_err(serde::de::Error::custom))
+                .collect()
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn write_file(name: &str, contents: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!(
+            "engawa-server-config-{}-{}",
+            std::process::id(),
+            name
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn test_load_toml_and_yaml() {
+        // テスト項目: TOML と YAML の設定ファイルが同じ設定として読み込まれる
+        // given (前提条件):
+        let (toml_dir, toml_path) = write_file(
+            "server.toml",
+            "host = \"0.0.0.0\"\nport = 3000\nroom_capacity = 50\nstorage = \"memory\"\n\
+             message_filters = [\"max-links:2\"]\n",
+        );
+        let (yaml_dir, yaml_path) = write_file(
+            "server.yaml",
+            "host: 0.0.0.0\nport: 3000\nroom_capacity: 50\nstorage: memory\n\
+             message_filters: [\"max-links:2\"]\n",
+        );
+
+        // when (操作):
+        let toml = ConfigFile::load(&toml_path).unwrap();
+        let yaml = ConfigFile::load(&yaml_path).unwrap();
+        std::fs::remove_dir_all(toml_dir).unwrap();
+        std::fs::remove_dir_all(yaml_dir).unwrap();
+
+        // then (期待する結果):
+        assert_eq!(toml.host.as_deref(), Some("0.0.0.0"));
+        assert_eq!(toml.port, Some(3000));
+        assert_eq!(toml.room_capacity, Some(50));
+        assert_eq!(toml.storage, Some(StorageBackend::Memory));
+        assert_eq!(
+            toml.message_filters,
+            Some(vec![MessageFilterSpec::MaxLinks(2)])
+        );
+        assert_eq!(toml.log_level, None);
+        assert_eq!(toml, yaml);
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_files() {
+        // テスト項目: 未知の設定・不正なログレベル・対応していない拡張子はエラーになる
+        // given (前提条件):
+        let (unknown_dir, unknown) = write_file("unknown.toml", "prot = 3000\n");
+        let (level_dir, level) = write_file("level.yml", "log_level: verbose\n");
+        let (json_dir, json) = write_file("server.json", "{}");
+
+        // when (操作):
+        let unknown_error = ConfigFile::load(&unknown).unwrap_err();
+        let level_error = ConfigFile::load(&level).unwrap_err();
+        let json_error = ConfigFile::load(&json).unwrap_err();
+        for dir in [unknown_dir, level_dir, json_dir] {
+            std::fs::remove_dir_all(dir).unwrap();
+        }
+
+        // then (期待する結果):
+        assert!(matches!(unknown_error, ConfigFileError::Parse { .. }));
+        assert!(unknown_error.to_string().contains("prot"));
+        assert!(
+            level_error
+                .to_string()
+                .contains("unknown log level 'verbose'")
+        );
+        assert!(matches!(
+            json_error,
+            ConfigFileError::UnsupportedFormat { .. }
+        ));
+    }
+
+    #[test]
+    fn test_env_overrides_file() {
+        // テスト項目: ENGAWA_* 環境変数がファイルの設定を上書きし、不正な値はエラーになる
+        // given (前提条件):
+        let file = ConfigFile {
+            host: Some("0.0.0.0".to_string()),
+            port: Some(3000),
+            ..ConfigFile::default()
+        };
+        let env = HashMap::from([
+            ("ENGAWA_PORT", "4000"),
+            ("ENGAWA_LOG_LEVEL", "warn"),
+            ("ENGAWA_LOG_FILTER", "tower_http=debug"),
+        ]);
+        let invalid = HashMap::from([("ENGAWA_ROOM_CAPACITY", "many")]);
+
+        // when (操作):
+        let overridden = file
+            .clone()
+            .with_env(|name| env.get(name).map(|value| value.to_string()))
+            .unwrap();
+        let error = file
+            .with_env(|name| invalid.get(name).map(|value| value.to_string()))
+            .unwrap_err();
+
+        // then (期待する結果):
+        assert_eq!(overridden.host.as_deref(), Some("0.0.0.0"));
+        assert_eq!(overridden.port, Some(4000));
+        assert_eq!(overridden.log_level.as_deref(), Some("warn"));
+        assert_eq!(overridden.log_filter.as_deref(), Some("tower_http=debug"));
+        assert!(matches!(
+            error,
+            ConfigFileError::InvalidEnv {
+                var: "ENGAWA_ROOM_CAPACITY",
+                ..
+            }
+        ));
+    }
+}
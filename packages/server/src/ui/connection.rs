@@ -0,0 +1,987 @@
+//! Lifecycle of a WebSocket connection.
+//!
+//! Every connection is driven through explicit states:
+//!
+//! ```txt
+//! Connecting ──joined──▶ Joined ──drain──▶ Draining
+//!     │                    │                  │
+//!     └──────closed────────┴──────closed──────┴──▶ Closed
+//! ```
+//!
+//! - `Connecting`: the client is registered with the message pusher, but has not received the
+//!   room state yet; the protocol is negotiated if the client starts with `hello`
+//! - `Joined`: the send pump is running and frames from the client are handled
+//! - `Draining`: the server is closing the connection (the room moved, the process restarts or
+//!   shuts down, or a newer connection of the client took over); no more frames are read and the
+//!   messages still queued are flushed before the final frames
+//! - `Closed`: the participant is removed from the room
+//!
+//! [`Connection`] performs the side effects of each transition: joining answers the client's
+//! `hello` with `hello-ack` (see [`crate::infrastructure::protocol`]), sends `room-connected`,
+//! applies the resume point used for backfill, announces the participant, sends the room's
+//! welcome messages and starts the send pump; closing unregisters the client and announces its departure. A joined connection that
+//! stays silent for longer than the idle timeout is closed (see [`super::heartbeat`]).
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::extract::ws::{CloseFrame, Message, WebSocket};
+use futures_util::{
+    sink::SinkExt,
+    stream::{self, SplitSink, SplitStream, StreamExt},
+};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::{
+    domain::{ClientId, DisplayName, Locale, Participant, Timestamp},
+    infrastructure::{
+        backpressure::Backpressure,
+        dedup::DedupWindow,
+        dto::{
+            websocket::{
+                HelloAckMessage, MessageType, RoomConnectedMessage, RoomHistoryMessage,
+                ServerShutdownMessage,
+            },
+            wire_log::WireDirection,
+        },
+        i18n::SystemText,
+        message_pusher::{PumpOptions, WebSocketMessagePusher},
+        protocol::{Capabilities, Feature},
+        wire_log::WireTap,
+    },
+    ui::{
+        cluster::ROOM_MOVED_CLOSE_CODE,
+        error::ConnectionStateError,
+        handler::{ConnectQuery, on_text_frame},
+        handover::{SERVICE_RESTART_CLOSE_CODE, reconnect_delay},
+        presenter::websocket::{participant_joined, participant_left, welcome},
+        session::{SessionEnd, SessionId},
+        signal::{SERVER_SHUTDOWN_CLOSE_CODE, ShutdownToken},
+        state::AppState,
+    },
+    usecase::RoomUseCases,
+};
+use engawa_shared::time::get_jst_timestamp;
+
+/// How long to wait for the `hello` of a client before serving it as a legacy client
+pub const HELLO_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Why the server is closing a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrainReason {
+    /// The room moved to another node
+    RoomMoved,
+    /// The listener is handed over to a new process
+    Restart,
+    /// The server is shutting down
+    Shutdown,
+    /// A newer connection of the same client replaced this one
+    SessionReplaced,
+    /// An admin kicked the client
+    Kicked,
+    /// The client was banned
+    Banned,
+    /// An admin disconnected everyone in the room
+    Disconnected,
+}
+
+/// Why a connection was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseReason {
+    /// The client sent a Close frame
+    ClientClosed,
+    /// The connection was lost or failed to read
+    ConnectionLost,
+    /// Writing to the connection failed
+    SendFailed,
+    /// The server finished draining the connection
+    Drained(DrainReason),
+    /// No frame was received from the client for longer than the idle timeout
+    IdleTimeout,
+}
+
+/// State of a WebSocket connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connecting,
+    Joined {
+        /// When the client joined the room
+        since: Instant,
+        /// When the last frame was received from the client
+        last_seen: Instant,
+    },
+    Draining(DrainReason),
+    Closed(CloseReason),
+}
+
+/// Something that happened on a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// The room state was sent to the client
+    Joined,
+    /// A frame was received from the client
+    FrameReceived,
+    /// The server started closing the connection
+    Drain(DrainReason),
+    /// The connection was closed
+    Closed(CloseReason),
+}
+
+impl ConnectionEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Joined => "joined",
+            Self::FrameReceived => "frame-received",
+            Self::Drain(_) => "drain",
+            Self::Closed(_) => "closed",
+        }
+    }
+}
+
+impl ConnectionState {
+    /// Name of the state (for logging)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Connecting => "connecting",
+            Self::Joined { .. } => "joined",
+            Self::Draining(_) => "draining",
+            Self::Closed(_) => "closed",
+        }
+    }
+
+    /// State after `event` occurs at `now`
+    ///
+    /// A closed connection accepts no event, and frames are only handled while joined.
+    pub fn next(self, event: ConnectionEvent, now: Instant) -> Result<Self, ConnectionStateError> {
+        match (self, event) {
+            (Self::Connecting, ConnectionEvent::Joined) => Ok(Self::Joined {
+                since: now,
+                last_seen: now,
+            }),
+            (Self::Joined { since, .. }, ConnectionEvent::FrameReceived) => Ok(Self::Joined {
+                since,
+                last_seen: now,
+            }),
+            (Self::Joined { .. }, ConnectionEvent::Drain(reason)) => Ok(Self::Draining(reason)),
+            (Self::Closed(_), _) => Err(ConnectionStateError::InvalidTransition {
+                state: self.name(),
+                event: event.name(),
+            }),
+            (_, ConnectionEvent::Closed(reason)) => Ok(Self::Closed(reason)),
+            _ => Err(ConnectionStateError::InvalidTransition {
+                state: self.name(),
+                event: event.name(),
+            }),
+        }
+    }
+}
+
+/// A WebSocket connection of one client
+pub struct Connection {
+    state: Arc<AppState>,
+    /// Room the client joined
+    room: Arc<RoomUseCases>,
+    client_id: ClientId,
+    /// Whether the participant joined the room with this connection (not another connection
+    /// of the same client)
+    joined_room: bool,
+    lifecycle: ConnectionState,
+    /// Records the frames of the connection (disabled if `None`)
+    wire_tap: Option<WireTap>,
+    /// Protocol negotiated before the connection was created (e.g. while waiting for approval)
+    hello: Option<Capabilities>,
+    /// Session of the connection once it has joined (other devices of the client have their own)
+    session: Option<SessionId>,
+}
+
+impl Connection {
+    /// Create a connection for a client already registered with the message pusher
+    ///
+    /// `joined_room` is `false` for an additional connection of a participant already in the
+    /// room; its arrival is not announced to the other participants.
+    pub fn new(
+        state: Arc<AppState>,
+        room: Arc<RoomUseCases>,
+        client_id: ClientId,
+        joined_room: bool,
+    ) -> Self {
+        let wire_tap = state
+            .wire_log
+            .clone()
+            .map(|log| WireTap::new(log, client_id.as_str()));
+        Self {
+            state,
+            room,
+            client_id,
+            joined_room,
+            lifecycle: ConnectionState::Connecting,
+            wire_tap,
+            hello: None,
+            session: None,
+        }
+    }
+
+    /// Use the protocol the client already negotiated instead of waiting for its `hello`
+    pub fn with_hello(mut self, hello: Option<Capabilities>) -> Self {
+        self.hello = hello;
+        self
+    }
+
+    /// Record a frame in the wire log, if enabled
+    fn tap(&self, dir: WireDirection, frame: &Message) {
+        if let Some(tap) = &self.wire_tap {
+            tap.record(dir, frame);
+        }
+    }
+
+    /// Apply an event to the lifecycle
+    fn transition(&mut self, event: ConnectionEvent) {
+        match self.lifecycle.next(event, Instant::now()) {
+            Ok(next) => {
+                if next.name() != self.lifecycle.name() {
+                    tracing::debug!(
+                        "Connection '{}': {} -> {}",
+                        self.client_id.as_str(),
+                        self.lifecycle.name(),
+                        next.name()
+                    );
+                }
+                self.lifecycle = next;
+            }
+            Err(e) => tracing::warn!("Connection '{}': {}", self.client_id.as_str(), e),
+        }
+    }
+
+    /// Drive the connection until it is closed
+    ///
+    /// # Arguments
+    ///
+    /// * `socket` - Upgraded WebSocket connection
+    /// * `outbox` - Channel to the client (for backfilled messages and errors)
+    /// * `rx` - Send queue registered with the message pusher
+    /// * `query` - Query parameters of the connection request
+    /// * `connected_at` - When the participant was added to the room
+    pub async fn run(
+        mut self,
+        socket: WebSocket,
+        outbox: mpsc::UnboundedSender<Arc<str>>,
+        rx: mpsc::UnboundedReceiver<Arc<str>>,
+        query: ConnectQuery,
+        connected_at: Timestamp,
+    ) {
+        let state = self.state.clone();
+        let room = self.room.clone();
+        let client_id_str = self.client_id.as_str().to_string();
+        let session_key = state.session_key(&room, &client_id_str);
+        // Keep the connection counted until it is closed so that a draining server waits for it
+        let _connection = state.connections.track();
+        let (mut sender, mut receiver) = socket.split();
+
+        // Agree on the protocol before sending the room state
+        let (hello, first_frame) = match self.hello {
+            Some(hello) => (Some(hello), None),
+            None => self.receive_hello(&mut receiver).await,
+        };
+        if let Some(hello) = hello
+            && !self.send_hello_ack(&mut sender, hello).await
+        {
+            self.transition(ConnectionEvent::Closed(CloseReason::SendFailed));
+            self.close(&outbox).await;
+            return;
+        }
+        let capabilities = hello.unwrap_or_default();
+        // A first frame other than `hello` is handled once joined
+        let mut receiver = stream::iter(first_frame).chain(receiver);
+
+        // Connecting -> Joined
+        let Some((room_id, dedup)) = self
+            .join(&mut sender, &query, connected_at, capabilities)
+            .await
+        else {
+            self.transition(ConnectionEvent::Closed(CloseReason::SendFailed));
+            self.close(&outbox).await;
+            return;
+        };
+        self.transition(ConnectionEvent::Joined);
+
+        // Track the connection so that it is closed if its room moves to another node
+        let moved = match (&state.room_shards, query.room) {
+            (Some(shards), Some(room)) => Some(shards.track(&client_id_str, room)),
+            _ => None,
+        };
+        // Dropped once the connection has left the room, letting a takeover proceed
+        let (session, ended, _session) = state.sessions.register(&session_key);
+        self.session = Some(session);
+        let closing = closing_frames(
+            moved,
+            ended,
+            state.draining.clone(),
+            state.stopping.clone(),
+            reconnect_delay(&client_id_str, state.reconnect_stagger),
+            state.locale,
+        );
+        tokio::pin!(closing);
+
+        // Closed by the heartbeat reaper once the client stays silent for too long
+        let heartbeat = state
+            .keepalive
+            .map(|_| state.heartbeats.register(&client_id_str, Instant::now()));
+        let (heartbeat_id, reaped) = heartbeat.unzip();
+        let reaped = async move {
+            if let Some(reaped) = reaped
+                && reaped.await.is_ok()
+            {
+                return;
+            }
+            std::future::pending::<()>().await
+        };
+        tokio::pin!(reaped);
+
+        // Room broadcasts now come from the room feed; earlier ones are still in the send queue
+        let feed = room
+            .connect_participant
+            .subscribe(&self.client_id, &outbox)
+            .await;
+
+        // Messages from other clients are written by the pump; dropping `stop_tx` stops it
+        let (stop_tx, stop_rx) = oneshot::channel::<Vec<Message>>();
+        let mut stop_tx = Some(stop_tx);
+        let mut pump = WebSocketMessagePusher::pump(
+            rx,
+            sender,
+            dedup,
+            async move { stop_rx.await.unwrap_or_default() },
+            {
+                let metrics = state.metrics.clone();
+                let client_id = client_id_str.clone();
+                move |depth, bytes| metrics.set_queue_depth(&client_id, depth, bytes)
+            },
+            PumpOptions {
+                batch_window: state
+                    .batch_window
+                    .filter(|_| capabilities.supports(Feature::Batching)),
+                backpressure: state
+                    .slow_down_threshold
+                    .filter(|_| capabilities.supports(Feature::SlowDown))
+                    .map(Backpressure::new),
+                ping_interval: state.keepalive.map(|keepalive| keepalive.ping_interval),
+                wire_tap: self.wire_tap.clone(),
+                capabilities,
+                feed,
+            },
+        );
+
+        while let ConnectionState::Joined { .. } = self.lifecycle {
+            tokio::select! {
+                frame = receiver.next() => {
+                    if let Some(Ok(received)) = &frame {
+                        self.tap(WireDirection::In, received);
+                        if let Some(id) = heartbeat_id {
+                            state.heartbeats.touch(id, Instant::now());
+                        }
+                    }
+                    match frame {
+                        Some(Ok(Message::Text(text))) => {
+                            self.transition(ConnectionEvent::FrameReceived);
+                            tracing::info!("Received text: {}", text);
+                            on_text_frame(
+                                &state,
+                                &room,
+                                &self.client_id,
+                                room_id.as_deref(),
+                                &text,
+                                &outbox,
+                            )
+                            .await;
+                        }
+                        Some(Ok(Message::Close(_))) => {
+                            tracing::info!("Client '{}' requested close", client_id_str);
+                            self.transition(ConnectionEvent::Closed(CloseReason::ClientClosed));
+                        }
+                        // Pongs answer the pings of the send pump
+                        Some(Ok(_)) => self.transition(ConnectionEvent::FrameReceived),
+                        Some(Err(e)) => {
+                            tracing::error!("WebSocket error: {}", e);
+                            self.transition(ConnectionEvent::Closed(CloseReason::ConnectionLost));
+                        }
+                        None => self.transition(ConnectionEvent::Closed(CloseReason::ConnectionLost)),
+                    }
+                }
+                _ = &mut reaped => {
+                    tracing::info!("Client '{}' timed out", client_id_str);
+                    self.transition(ConnectionEvent::Closed(CloseReason::IdleTimeout));
+                }
+                _ = &mut pump => {
+                    self.transition(ConnectionEvent::Closed(CloseReason::SendFailed));
+                }
+                (reason, frames) = &mut closing => {
+                    self.transition(ConnectionEvent::Drain(reason));
+                    if let Some(stop_tx) = stop_tx.take() {
+                        let _ = stop_tx.send(frames);
+                    }
+                }
+            }
+        }
+
+        // Draining -> Closed: wait for the final frames to be flushed
+        if let ConnectionState::Draining(reason) = self.lifecycle {
+            let _ = (&mut pump).await;
+            self.transition(ConnectionEvent::Closed(CloseReason::Drained(reason)));
+        }
+        pump.abort();
+        if let Some(id) = heartbeat_id {
+            state.heartbeats.unregister(id);
+        }
+        self.close(&outbox).await;
+    }
+
+    /// Wait for the `hello` of the client
+    ///
+    /// Returns the protocol it negotiated, or `None` for a legacy client that sent nothing
+    /// within [`HELLO_TIMEOUT`] or started with another frame, returned to be handled once
+    /// joined.
+    async fn receive_hello(
+        &self,
+        receiver: &mut SplitStream<WebSocket>,
+    ) -> (Option<Capabilities>, Option<Result<Message, axum::Error>>) {
+        match tokio::time::timeout(HELLO_TIMEOUT, receiver.next()).await {
+            Ok(Some(Ok(frame))) => {
+                let hello = match &frame {
+                    Message::Text(text) => Capabilities::from_hello(text),
+                    _ => None,
+                };
+                match hello {
+                    Some(hello) => {
+                        self.tap(WireDirection::In, &frame);
+                        (Some(hello), None)
+                    }
+                    None => (None, Some(Ok(frame))),
+                }
+            }
+            Ok(Some(Err(e))) => (None, Some(Err(e))),
+            // Closed connections are noticed by the read loop
+            Ok(None) | Err(_) => (None, None),
+        }
+    }
+
+    /// Tell the client the protocol version and features used on the connection
+    ///
+    /// Returns `false` if the connection failed.
+    async fn send_hello_ack(
+        &self,
+        sender: &mut SplitSink<WebSocket, Message>,
+        capabilities: Capabilities,
+    ) -> bool {
+        let ack = HelloAckMessage {
+            r#type: MessageType::HelloAck,
+            protocol_version: capabilities.protocol_version,
+            features: capabilities.feature_names(),
+        };
+        tracing::info!(
+            "Client '{}' speaks protocol version {} with {:?}",
+            self.client_id,
+            ack.protocol_version,
+            ack.features
+        );
+        let frame = Message::Text(serde_json::to_string(&ack).unwrap().into());
+        self.tap(WireDirection::Out, &frame);
+        if let Err(e) = sender.send(frame).await {
+            tracing::error!("Failed to send hello-ack to '{}': {}", self.client_id, e);
+            return false;
+        }
+        true
+    }
+
+    /// Send the room state to the client and announce the participant
+    ///
+    /// Returns the room of the connection and the messages already delivered to the client,
+    /// or `None` if the connection failed.
+    async fn join(
+        &self,
+        sender: &mut SplitSink<WebSocket, Message>,
+        query: &ConnectQuery,
+        connected_at: Timestamp,
+        capabilities: Capabilities,
+    ) -> Option<(Option<String>, DedupWindow)> {
+        let state = &self.state;
+        let client_id_str = self.client_id.as_str();
+
+        // The room ID is the resume token: sequence numbers are only meaningful within the same room
+        let (resume_token, last_seq) = match self.room.get_room_state.execute().await {
+            Ok(room) => (
+                Some(room.id.as_str().to_string()),
+                Some(room.last_seq.value()),
+            ),
+            Err(_) => (None, None),
+        };
+        let room_id = resume_token.clone();
+        let mut dedup = DedupWindow::new(state.dedup_window);
+        let mut resumed = false;
+        if let (Some(token), Some(seq)) = (&query.resume_token, query.last_seq)
+            && resume_token.as_ref() == Some(token)
+        {
+            tracing::info!("Client '{}' resumes after message {}", client_id_str, seq);
+            dedup.resume_from(seq);
+            resumed = true;
+        }
+
+        // Record the display name asked for on connect, so the participant list includes it
+        let mut display_name = None;
+        if let (Some(usecase), Some(name), true) = (
+            &self.room.rename_participant,
+            &query.display_name,
+            self.joined_room,
+        ) && let Ok(name) = DisplayName::new(name.clone())
+        {
+            match usecase.record(&self.client_id, name.clone()).await {
+                Ok(()) => display_name = Some(name),
+                Err(e) => tracing::warn!(
+                    "Failed to set the display name of '{}': {:?}",
+                    client_id_str,
+                    e
+                ),
+            }
+        }
+
+        // Send current room participants to the newly connected client
+        {
+            // Use ConnectParticipantUseCase to build participant list
+            let participants = self.room.connect_participant.build_participant_list().await;
+
+            // Domain Model から DTO への変換
+            let room_msg = RoomConnectedMessage {
+                r#type: MessageType::RoomConnected,
+                client_id: self.client_id.as_str().to_string(),
+                room_id: Some(self.room.room_id.as_str().to_string()),
+                participants: participants.into_iter().map(Into::into).collect(),
+                resume_token,
+                last_seq,
+                locale: Some(state.locale.to_string()),
+                server_time: Some(get_jst_timestamp()),
+            };
+
+            let room_json = serde_json::to_string(&room_msg).unwrap();
+            let frame = Message::Text(room_json.into());
+            self.tap(WireDirection::Out, &frame);
+            if let Err(e) = sender.send(frame).await {
+                tracing::error!(
+                    "Failed to send room connected to '{}': {}",
+                    client_id_str,
+                    e
+                );
+                return None;
+            }
+            tracing::info!("Sent room connected list to '{}'", client_id_str);
+        }
+
+        // Send the latest messages to a new client (a resumed client backfills what it missed)
+        if let (Some(usecase), Some(room_id), false, true) = (
+            &state.get_message_history_usecase,
+            &room_id,
+            resumed,
+            capabilities.supports(Feature::History),
+        ) {
+            match usecase.execute(room_id).await {
+                Ok(messages) if !messages.is_empty() => {
+                    // Live messages already in the send queue are skipped by the pump
+                    for message in &messages {
+                        dedup.insert(message.seq.value());
+                    }
+                    let history = RoomHistoryMessage {
+                        r#type: MessageType::RoomHistory,
+                        messages: messages
+                            .into_iter()
+                            .map(|message| {
+                                let mut message = message.into();
+                                capabilities.downgrade_chat(&mut message);
+                                message
+                            })
+                            .collect(),
+                    };
+                    let history_json = serde_json::to_string(&history).unwrap();
+                    let frame = Message::Text(history_json.into());
+                    self.tap(WireDirection::Out, &frame);
+                    if let Err(e) = sender.send(frame).await {
+                        tracing::error!(
+                            "Failed to send room history to '{}': {}",
+                            client_id_str,
+                            e
+                        );
+                        return None;
+                    }
+                    tracing::info!(
+                        "Sent {} history messages to '{}'",
+                        history.messages.len(),
+                        client_id_str
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to get the room history: {:?}", e),
+            }
+        }
+
+        // Broadcast participant-joined to all other clients
+        if self.joined_room {
+            let participant = Participant {
+                display_name,
+                ..Participant::new(self.client_id.clone(), connected_at)
+            };
+            let joined_msg = participant_joined(participant, state.locale);
+
+            let joined_json = serde_json::to_string(&joined_msg).unwrap();
+            if let Err(e) = self
+                .room
+                .connect_participant
+                .broadcast_participant_joined(&self.client_id, &joined_json)
+                .await
+            {
+                tracing::warn!("Failed to broadcast participant-joined: {}", e);
+            } else {
+                tracing::info!("Broadcasted participant-joined for '{}'", client_id_str);
+            }
+            self.send_welcome().await;
+        }
+
+        Some((room_id, dedup))
+    }
+
+    /// Send the welcome messages of the room to the participant that has just joined
+    ///
+    /// They are queued like any other message, so the send pump delivers them after the room
+    /// state.
+    async fn send_welcome(&self) {
+        let Some(usecase) = &self.state.manage_integrations_usecase else {
+            return;
+        };
+        let texts = match usecase.welcome_messages(&self.room.room_id).await {
+            Ok(texts) => texts,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to get the welcome messages of room '{}': {}",
+                    self.room.room_id,
+                    e
+                );
+                return;
+            }
+        };
+        for text in texts {
+            let message = welcome(&self.room.room_id, text);
+            let json = serde_json::to_string(&message).unwrap();
+            if let Err(e) = self
+                .room
+                .connect_participant
+                .send_to_participant(&self.client_id, &json)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to send the welcome message to '{}': {}",
+                    self.client_id,
+                    e
+                );
+            }
+        }
+    }
+
+    /// Remove the participant from the room and announce its departure
+    ///
+    /// The participant stays in the room while it has other connections.
+    async fn close(&self, outbox: &mpsc::UnboundedSender<Arc<str>>) {
+        let state = &self.state;
+        let client_id_str = self.client_id.as_str();
+        tracing::info!(
+            "Connection '{}' closed ({:?})",
+            client_id_str,
+            self.lifecycle
+        );
+
+        if let Some(shards) = &state.room_shards {
+            shards.untrack(client_id_str);
+        }
+        if let Some(session) = self.session {
+            state
+                .sessions
+                .unregister(&state.session_key(&self.room, client_id_str), session);
+        }
+        state.guests.forget(client_id_str);
+        self.room.send_message.forget(&self.client_id);
+        state.metrics.remove_queue(client_id_str);
+        state.leave_room(&self.client_id, &self.room).await;
+
+        // Use DisconnectParticipantUseCase to handle disconnection
+        match self
+            .room
+            .disconnect_participant
+            .execute_connection(self.client_id.clone(), outbox)
+            .await
+        {
+            Ok(None) => {
+                tracing::info!(
+                    "Client '{}' is still connected from another connection",
+                    client_id_str
+                );
+            }
+            Ok(Some(notify_targets)) => {
+                tracing::info!(
+                    "Client '{}' disconnected and removed from registry",
+                    client_id_str
+                );
+                // Participants clear the indicator when they see the client leave
+                if let Some(typing) = &self.room.broadcast_typing {
+                    typing.clear(&self.client_id);
+                }
+
+                // Broadcast participant-left to all remaining clients
+                let left_msg = participant_left(
+                    &self.client_id,
+                    Timestamp::new(get_jst_timestamp()),
+                    state.locale,
+                );
+
+                let left_json = serde_json::to_string(&left_msg).unwrap();
+                if let Err(e) = self
+                    .room
+                    .disconnect_participant
+                    .broadcast_participant_left(notify_targets, &left_json)
+                    .await
+                {
+                    tracing::warn!("Failed to broadcast participant-left: {}", e);
+                } else {
+                    tracing::info!("Broadcasted participant-left for '{}'", client_id_str);
+                }
+            }
+            Err(_) => {
+                tracing::warn!("Failed to disconnect participant '{}'", client_id_str);
+            }
+        }
+    }
+}
+
+/// Wait until the server has to close the connection and return the frames to send before closing
+///
+/// # Arguments
+///
+/// * `moved` - Receives the new owner's address if the room moves to another node
+/// * `ended` - Completes when the session is replaced by a newer connection or ended by an admin
+/// * `draining` - Triggered when the listener is handed over to a new process
+/// * `stopping` - Triggered when the server shuts down
+/// * `reconnect_after` - Delay the client is asked to wait before reconnecting when draining
+///   or stopping
+/// * `locale` - Language of the restart and shutdown notices
+async fn closing_frames(
+    moved: Option<oneshot::Receiver<String>>,
+    ended: oneshot::Receiver<SessionEnd>,
+    draining: ShutdownToken,
+    stopping: ShutdownToken,
+    reconnect_after: Duration,
+    locale: Locale,
+) -> (DrainReason, Vec<Message>) {
+    tokio::select! {
+        owner = room_moved(moved) => {
+            // Tell the client where to reconnect
+            let frame = CloseFrame {
+                code: ROOM_MOVED_CLOSE_CODE,
+                reason: owner.into(),
+            };
+            (DrainReason::RoomMoved, vec![Message::Close(Some(frame))])
+        }
+        // Unregistering the session drops the sender without replacing it
+        Ok(end) = ended => {
+            let (code, reason) = end.close_frame();
+            let frame = CloseFrame {
+                code,
+                reason: reason.into(),
+            };
+            let drain = match end {
+                SessionEnd::Replaced => DrainReason::SessionReplaced,
+                SessionEnd::Kicked => DrainReason::Kicked,
+                SessionEnd::Banned => DrainReason::Banned,
+                SessionEnd::Disconnected => DrainReason::Disconnected,
+            };
+            (drain, vec![Message::Close(Some(frame))])
+        }
+        _ = draining.cancelled() => {
+            // Ask the client to reconnect to the process that took over the listener
+            (DrainReason::Restart, restart_frames(reconnect_after, locale))
+        }
+        _ = stopping.cancelled() => {
+            // A handover triggers `draining` before stopping this process
+            if draining.is_triggered() {
+                (DrainReason::Restart, restart_frames(reconnect_after, locale))
+            } else {
+                (DrainReason::Shutdown, shutdown_frames(reconnect_after, locale))
+            }
+        }
+    }
+}
+
+/// Frames asking the client to reconnect to the process that took over the listener
+pub(crate) fn restart_frames(reconnect_after: Duration, locale: Locale) -> Vec<Message> {
+    server_shutdown_frames(
+        "restart",
+        SERVICE_RESTART_CLOSE_CODE,
+        reconnect_after,
+        SystemText::ServerRestart.localize(locale),
+    )
+}
+
+/// Frames telling the client that the server is shutting down
+pub(crate) fn shutdown_frames(reconnect_after: Duration, locale: Locale) -> Vec<Message> {
+    server_shutdown_frames(
+        "shutdown",
+        SERVER_SHUTDOWN_CLOSE_CODE,
+        reconnect_after,
+        SystemText::ServerShutdown.localize(locale),
+    )
+}
+
+/// A `server-shutdown` notice followed by a Close frame with `code`
+fn server_shutdown_frames(
+    reason: &str,
+    code: u16,
+    reconnect_after: Duration,
+    notice: String,
+) -> Vec<Message> {
+    let notice = ServerShutdownMessage {
+        r#type: MessageType::ServerShutdown,
+        reason: reason.to_string(),
+        reconnect_after_ms: reconnect_after.as_millis() as u64,
+        notice: Some(notice),
+    };
+    let frame = CloseFrame {
+        code,
+        reason: reason.into(),
+    };
+    vec![
+        Message::Text(serde_json::to_string(&notice).unwrap().into()),
+        Message::Close(Some(frame)),
+    ]
+}
+
+/// Wait until the room moves to another node and return the new owner's address
+async fn room_moved(moved: Option<oneshot::Receiver<String>>) -> String {
+    if let Some(moved) = moved
+        && let Ok(owner) = moved.await
+    {
+        return owner;
+    }
+    std::future::pending().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_lifecycle_transitions() {
+        // テスト項目: Connecting → Joined → Draining → Closed の順に遷移し、受信時刻が更新される
+        // given (前提条件):
+        let start = Instant::now();
+        let later = start + Duration::from_secs(5);
+
+        // when (操作):
+        let joined = ConnectionState::Connecting
+            .next(ConnectionEvent::Joined, start)
+            .unwrap();
+        let seen = joined.next(ConnectionEvent::FrameReceived, later).unwrap();
+        let draining = seen
+            .next(ConnectionEvent::Drain(DrainReason::Restart), later)
+            .unwrap();
+        let closed = draining
+            .next(
+                ConnectionEvent::Closed(CloseReason::Drained(DrainReason::Restart)),
+                later,
+            )
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(
+            seen,
+            ConnectionState::Joined {
+                since: start,
+                last_seen: later
+            }
+        );
+        assert_eq!(draining, ConnectionState::Draining(DrainReason::Restart));
+        assert_eq!(
+            closed,
+            ConnectionState::Closed(CloseReason::Drained(DrainReason::Restart))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_closing_frames_on_shutdown() {
+        // テスト項目: サーバーの停止時は停止の通知とクローズコード 1001 を返し、引き継ぎによる停止時は再起動の通知を返す
+        // given (前提条件):
+        let closing = |draining: ShutdownToken, stopping: ShutdownToken| {
+            let (_ended_tx, ended) = oneshot::channel();
+            async move {
+                closing_frames(
+                    None,
+                    ended,
+                    draining,
+                    stopping,
+                    Duration::from_millis(1500),
+                    Locale::En,
+                )
+                .await
+            }
+        };
+        let stopping = ShutdownToken::new();
+        let handed_over = ShutdownToken::new();
+        let draining = ShutdownToken::new();
+
+        // when (操作):
+        stopping.trigger();
+        let (shutdown, frames) = closing(ShutdownToken::new(), stopping.clone()).await;
+        draining.trigger();
+        handed_over.trigger();
+        let (restart, _) = closing(draining, handed_over).await;
+
+        // then (期待する結果):
+        assert_eq!(shutdown, DrainReason::Shutdown);
+        assert_eq!(restart, DrainReason::Restart);
+        let Message::Text(notice) = &frames[0] else {
+            panic!("expected a server-shutdown notice");
+        };
+        let notice: ServerShutdownMessage = serde_json::from_str(notice).unwrap();
+        assert_eq!(notice.reason, "shutdown");
+        assert_eq!(notice.reconnect_after_ms, 1500);
+        assert!(matches!(
+            &frames[1],
+            Message::Close(Some(frame)) if frame.code == SERVER_SHUTDOWN_CLOSE_CODE
+        ));
+    }
+
+    #[test]
+    fn test_connection_rejects_invalid_transitions() {
+        // テスト項目: 参加前のフレーム受信・退避中の再参加・切断後のイベントは拒否される
+        // given (前提条件):
+        let now = Instant::now();
+        let closed = ConnectionState::Closed(CloseReason::ClientClosed);
+
+        // when (操作):
+        let frame_before_join =
+            ConnectionState::Connecting.next(ConnectionEvent::FrameReceived, now);
+        let rejoin =
+            ConnectionState::Draining(DrainReason::RoomMoved).next(ConnectionEvent::Joined, now);
+        let close_twice = closed.next(ConnectionEvent::Closed(CloseReason::SendFailed), now);
+
+        // then (期待する結果):
+        assert_eq!(
+            frame_before_join,
+            Err(ConnectionStateError::InvalidTransition {
+                state: "connecting",
+                event: "frame-received"
+            })
+        );
+        assert!(rejoin.is_err());
+        assert!(close_twice.is_err());
+        // 切断はどの状態からでも可能
+        assert!(matches!(
+            ConnectionState::Connecting.next(ConnectionEvent::Closed(CloseReason::SendFailed), now),
+            Ok(ConnectionState::Closed(CloseReason::SendFailed))
+        ));
+    }
+}
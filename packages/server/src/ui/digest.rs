@@ -0,0 +1,146 @@
+//! Daily digest of the room (`--digest-at`).
+//!
+//! A background task wakes up every day at the configured time (JST), composes a digest of
+//! the last 24 hours (message count and most active participants) and posts it to the room
+//! as a chat message from [`DIGEST_SENDER`], in the room's locale. Nothing is posted for a
+//! day without messages.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::{FixedOffset, NaiveTime, TimeZone};
+use engawa_shared::time::get_jst_timestamp;
+
+use crate::{
+    domain::{Locale, MessageContent, Timestamp},
+    infrastructure::{
+        dto::websocket::{ChatMessage, MessageType},
+        i18n::SystemText,
+    },
+    usecase::{ComposeDailyDigestUseCase, DailyDigest},
+};
+
+use super::{signal::ShutdownToken, state::AppState};
+
+/// Client ID the digest is posted as
+pub const DIGEST_SENDER: &str = "digest";
+
+/// Period covered by a digest
+const DIGEST_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Time to wait from `now` (Unix milliseconds) until the next `at` in JST
+///
+/// A digest due exactly now is scheduled for the next day, so that a digest that has just
+/// been posted is not posted again.
+pub fn until_next(at: NaiveTime, now: i64) -> Duration {
+    let jst = FixedOffset::east_opt(9 * 3600).unwrap(); // JST is UTC+9
+    let now = jst.timestamp_millis_opt(now).single().unwrap_or_default();
+    let today = now.date_naive().and_time(at);
+    let next = if today > now.naive_local() {
+        today
+    } else {
+        today + chrono::Duration::days(1)
+    };
+    (next - now.naive_local()).to_std().unwrap_or_default()
+}
+
+/// Render the digest as the text posted to the room
+pub fn digest_text(digest: &DailyDigest, locale: Locale) -> String {
+    let most_active: Vec<(&str, usize)> = digest
+        .most_active
+        .iter()
+        .map(|activity| (activity.client_id.as_str(), activity.messages))
+        .collect();
+    SystemText::DailyDigest {
+        message_count: digest.message_count,
+        most_active: &most_active,
+    }
+    .localize(locale)
+}
+
+/// Post the daily digest every day at `at` (JST) until the server shuts down
+pub async fn run_daily_digest(
+    at: NaiveTime,
+    usecase: Arc<ComposeDailyDigestUseCase>,
+    state: Arc<AppState>,
+    shutdown: ShutdownToken,
+) {
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(until_next(at, get_jst_timestamp())) => {}
+            _ = shutdown.cancelled() => return,
+        }
+
+        let until = get_jst_timestamp();
+        let since = until - DIGEST_PERIOD.as_millis() as i64;
+        let digest = match usecase
+            .execute(Timestamp::new(since), Timestamp::new(until))
+            .await
+        {
+            Ok(digest) => digest,
+            Err(e) => {
+                tracing::warn!("Failed to compose the daily digest: {:?}", e);
+                continue;
+            }
+        };
+        if digest.message_count == 0 {
+            tracing::info!("No messages in the last 24 hours; skipping the daily digest");
+            continue;
+        }
+
+        let text = digest_text(&digest, state.locale);
+        let Ok(content) = MessageContent::new(text.clone()) else {
+            tracing::warn!("Daily digest is not a valid message: {}", text);
+            continue;
+        };
+        let message = ChatMessage {
+            r#type: MessageType::Chat,
+            client_id: DIGEST_SENDER.to_string(),
+            content: text,
+            timestamp: until,
+            seq: None,
+            poll: None,
+            forwarded_from: None,
+        };
+        tracing::info!(
+            "Posting the daily digest of room {} ({} messages)",
+            digest.room_id.as_str(),
+            digest.message_count
+        );
+        if let Err(e) = state
+            .send_message_usecase
+            .execute(usecase.sender().clone(), content, move |seq| {
+                message.to_json_with_seq(seq.value())
+            })
+            .await
+        {
+            tracing::warn!("Failed to post the daily digest: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_until_next_same_day_and_next_day() {
+        // テスト項目: 次の指定時刻（JST）までの待ち時間が、当日と翌日の場合で正しく計算される
+        // given (前提条件):
+        // 2023-01-01 08:30:00 JST
+        let now = 1672498800000 + (8 * 3600 + 30 * 60) * 1000;
+
+        // when (操作):
+        let later_today = until_next(time(9, 0), now);
+        let tomorrow = until_next(time(8, 0), now);
+        let exactly_now = until_next(time(8, 30), now);
+
+        // then (期待する結果):
+        assert_eq!(later_today, Duration::from_secs(30 * 60));
+        assert_eq!(tomorrow, Duration::from_secs(23 * 3600 + 30 * 60));
+        assert_eq!(exactly_now, Duration::from_secs(24 * 3600));
+    }
+}
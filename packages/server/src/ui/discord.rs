@@ -0,0 +1,254 @@
+//! Discord relay bot.
+//!
+//! Relays messages bidirectionally between a Discord channel and the room:
+//!
+//! - Room → Discord: chat messages queued by [`DiscordRelayPusher`] are posted as
+//!   `**{client_id}**: {content}`, honoring Discord's rate limits.
+//! - Discord → room: new channel messages are polled and injected through `SendMessageUseCase`
+//!   as `{prefix}{display name}` (e.g. `discord:alice`), so they are recognizable in the room
+//!   and never relayed back.
+//!
+//! Messages posted by the bot itself, other bots and webhooks are ignored.
+//!
+//! [`DiscordRelayPusher`]: crate::infrastructure::message_pusher::DiscordRelayPusher
+
+use std::{sync::Arc, time::Duration};
+
+use tokio::sync::mpsc;
+
+use crate::{
+    domain::{ClientId, MessageContent},
+    infrastructure::{
+        discord::{DiscordClient, snowflake_at},
+        dto::{
+            discord::{DiscordMessage, DiscordUser},
+            websocket::{ChatMessage, MessageType},
+        },
+    },
+};
+use engawa_shared::time::get_jst_timestamp;
+
+use super::{signal::ShutdownToken, state::AppState};
+
+/// Interval between polls of the Discord channel
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Connection between a Discord channel and the room
+pub struct DiscordRelay {
+    client: Arc<DiscordClient>,
+    channel_id: String,
+    outbound: mpsc::Receiver<String>,
+    relay_prefix: String,
+}
+
+impl DiscordRelay {
+    /// Create a new relay
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Discord REST API client
+    /// * `channel_id` - Discord channel to relay
+    /// * `outbound` - Queue filled by `DiscordRelayPusher`
+    /// * `relay_prefix` - Prefix of the client IDs of Discord users in the room
+    pub fn new(
+        client: DiscordClient,
+        channel_id: String,
+        outbound: mpsc::Receiver<String>,
+        relay_prefix: String,
+    ) -> Self {
+        Self {
+            client: Arc::new(client),
+            channel_id,
+            outbound,
+            relay_prefix,
+        }
+    }
+}
+
+/// Client ID of a Discord user in the room (`{prefix}{display name}`, whitespace replaced by `_`)
+pub fn inbound_client_id(prefix: &str, author: &DiscordUser) -> String {
+    let name = author
+        .global_name
+        .as_deref()
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or(&author.username);
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect();
+    format!("{}{}", prefix, name)
+}
+
+/// Check whether a Discord message should be relayed to the room
+pub fn should_relay(message: &DiscordMessage, bot_user_id: Option<&str>) -> bool {
+    !message.author.bot
+        && message.webhook_id.is_none()
+        && Some(message.author.id.as_str()) != bot_user_id
+        && !message.content.trim().is_empty()
+}
+
+/// Run the relay until shutdown is requested
+pub async fn run_relay(relay: DiscordRelay, state: Arc<AppState>, shutdown: ShutdownToken) {
+    let DiscordRelay {
+        client,
+        channel_id,
+        mut outbound,
+        relay_prefix,
+    } = relay;
+
+    let bot_user_id = match client.current_user().await {
+        Ok(user) => {
+            tracing::info!(
+                "Relaying Discord channel {} as '{}'",
+                channel_id,
+                user.username
+            );
+            Some(user.id)
+        }
+        Err(e) => {
+            tracing::warn!("Failed to get Discord bot user: {}", e);
+            None
+        }
+    };
+
+    // Room → Discord
+    let outbound_client = client.clone();
+    let outbound_channel_id = channel_id.clone();
+    let outbound_task = engawa_shared::task::spawn("discord-outbound", async move {
+        while let Some(line) = outbound.recv().await {
+            if let Err(e) = outbound_client
+                .create_message(&outbound_channel_id, &line)
+                .await
+            {
+                tracing::warn!("Failed to relay message to Discord: {}", e);
+            }
+        }
+    });
+
+    // Discord → room (only messages posted after startup)
+    let mut after = snowflake_at(get_jst_timestamp());
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {},
+            _ = shutdown.cancelled() => break,
+        }
+
+        let messages = match client.messages_after(&channel_id, after).await {
+            Ok(messages) => messages,
+            Err(e) => {
+                tracing::warn!("Failed to poll Discord channel: {}", e);
+                continue;
+            }
+        };
+        for message in messages {
+            if let Ok(id) = message.id.parse::<u64>() {
+                after = after.max(id);
+            }
+            if should_relay(&message, bot_user_id.as_deref()) {
+                inject_message(&state, &relay_prefix, message).await;
+            }
+        }
+    }
+
+    outbound_task.abort();
+    tracing::info!("Discord relay stopped");
+}
+
+/// Inject a Discord message into the room as a chat message
+async fn inject_message(state: &AppState, relay_prefix: &str, message: DiscordMessage) {
+    let sender = inbound_client_id(relay_prefix, &message.author);
+    let text = state.sanitize_profile.apply(&message.content).into_owned();
+    let (Ok(client_id), Ok(content)) = (
+        ClientId::try_from(sender.clone()),
+        MessageContent::try_from(text.clone()),
+    ) else {
+        tracing::warn!("Ignoring invalid Discord message from '{}'", sender);
+        return;
+    };
+
+    let chat_message = ChatMessage {
+        r#type: MessageType::Chat,
+        client_id: sender,
+        content: text,
+        timestamp: get_jst_timestamp(),
+        seq: None,
+        poll: None,
+        forwarded_from: None,
+    };
+    tracing::info!(
+        "Relaying message from Discord user '{}': {}",
+        chat_message.client_id,
+        chat_message.content
+    );
+
+    if let Err(e) = state
+        .send_message_usecase
+        .execute(client_id, content, move |seq| {
+            chat_message.to_json_with_seq(seq.value())
+        })
+        .await
+    {
+        tracing::warn!("Failed to relay Discord message: {:?}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(id: &str, username: &str, global_name: Option<&str>, bot: bool) -> DiscordUser {
+        DiscordUser {
+            id: id.to_string(),
+            username: username.to_string(),
+            global_name: global_name.map(str::to_string),
+            bot,
+        }
+    }
+
+    fn message(author: DiscordUser, content: &str) -> DiscordMessage {
+        DiscordMessage {
+            id: "1".to_string(),
+            content: content.to_string(),
+            author,
+            webhook_id: None,
+        }
+    }
+
+    #[test]
+    fn test_inbound_client_id() {
+        // テスト項目: 表示名（無ければユーザー名）に接頭辞を付け、空白を _ に置き換える
+        // then (期待する結果):
+        assert_eq!(
+            inbound_client_id(
+                "discord:",
+                &user("1", "alice", Some("Alice Liddell"), false)
+            ),
+            "discord:Alice_Liddell"
+        );
+        assert_eq!(
+            inbound_client_id("discord:", &user("1", "alice", None, false)),
+            "discord:alice"
+        );
+    }
+
+    #[test]
+    fn test_should_relay() {
+        // テスト項目: Bot 自身・他の Bot・Webhook・空のメッセージは中継しない
+        // given (前提条件):
+        let human = message(user("10", "alice", None, false), "hello");
+        let own = message(user("99", "relay", None, true), "**bob**: hi");
+        let other_bot = message(user("20", "ci", None, true), "build ok");
+        let mut webhook = message(user("30", "hook", None, false), "alert");
+        webhook.webhook_id = Some("30".to_string());
+        let empty = message(user("10", "alice", None, false), " ");
+
+        // then (期待する結果):
+        assert!(should_relay(&human, Some("99")));
+        assert!(!should_relay(&own, Some("99")));
+        assert!(!should_relay(&other_bot, Some("99")));
+        assert!(!should_relay(&webhook, Some("99")));
+        assert!(!should_relay(&empty, Some("99")));
+    }
+}
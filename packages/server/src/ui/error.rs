@@ -0,0 +1,141 @@
+//! UI layer error definitions.
+
+use thiserror::Error;
+
+/// Errors related to parsing IP networks (CIDR notation)
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum IpNetworkError {
+    /// Address part is not a valid IP address
+    #[error("Invalid IP address: {0}")]
+    InvalidAddress(String),
+
+    /// Prefix length is not a number or is out of range for the address family
+    #[error("Invalid prefix length '{prefix}' (max {max})")]
+    InvalidPrefix { prefix: String, max: u8 },
+}
+
+/// Errors related to the lifecycle of a WebSocket connection
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ConnectionStateError {
+    /// Event cannot happen in the current state
+    #[error("cannot handle '{event}' while {state}")]
+    InvalidTransition {
+        state: &'static str,
+        event: &'static str,
+    },
+}
+
+/// Errors related to commands received over the MQTT bridge
+#[cfg(feature = "mqtt")]
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum MqttCommandError {
+    /// Payload is not a valid command JSON
+    #[error("Invalid command payload: {0}")]
+    InvalidPayload(String),
+
+    /// client_id or content failed validation
+    #[error("Invalid command: {0}")]
+    InvalidValue(#[from] crate::domain::ValueObjectError),
+}
+
+/// A problem found while validating the server configuration
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Option value is malformed or out of range
+    #[error("{option} {value:?} is invalid: {reason}")]
+    InvalidValue {
+        option: &'static str,
+        value: String,
+        reason: String,
+    },
+
+    /// Two options use the same port
+    #[error("{option} {port} is already used by {other}; choose a different port")]
+    PortConflict {
+        option: &'static str,
+        other: &'static str,
+        port: u16,
+    },
+
+    /// Option is only meaningful together with another option
+    #[error("{option} has no effect without {requires}; set {requires} or remove {option}")]
+    MissingDependency {
+        option: &'static str,
+        requires: &'static str,
+    },
+
+    /// Option is required by another option
+    #[error("{option} must be set when {required_by} is given")]
+    MissingOption {
+        option: &'static str,
+        required_by: &'static str,
+    },
+
+    /// Secret is read from an environment variable that is not set
+    #[error("environment variable {env} must be set when {required_by} is given")]
+    MissingSecret {
+        env: &'static str,
+        required_by: &'static str,
+    },
+
+    /// Path cannot be used
+    #[error("{option} {path}: {reason}")]
+    InvalidPath {
+        option: &'static str,
+        path: String,
+        reason: String,
+    },
+}
+
+/// Every problem found while validating the server configuration
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("invalid server configuration:{}", .0.iter().map(|e| format!("\n  - {}", e)).collect::<String>())]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+/// Errors related to loading the server configuration file
+#[derive(Debug, Error)]
+pub enum ConfigFileError {
+    /// The file could not be read
+    #[error("Failed to read config file '{path}': {source}")]
+    Read {
+        path: String,
+        source: std::io::Error,
+    },
+
+    /// The file extension is neither TOML nor YAML
+    #[error("Unsupported config file '{path}': expected a .toml, .yaml or .yml file")]
+    UnsupportedFormat { path: String },
+
+    /// The file is not a valid configuration
+    #[error("Invalid config file '{path}': {reason}")]
+    Parse { path: String, reason: String },
+
+    /// An environment variable overriding a setting has an invalid value
+    #[error("Invalid environment variable {var}={value:?}: {reason}")]
+    InvalidEnv {
+        var: &'static str,
+        value: String,
+        reason: String,
+    },
+}
+
+/// Errors related to loading the TLS certificate and private key
+#[cfg(feature = "tls")]
+#[derive(Debug, Error)]
+pub enum TlsError {
+    /// A PEM file could not be read or parsed
+    #[error("Failed to read {what} '{path}': {reason}")]
+    Pem {
+        what: &'static str,
+        path: String,
+        reason: String,
+    },
+
+    /// The certificate file holds no certificate
+    #[error("No certificate found in '{path}'")]
+    NoCertificate { path: String },
+
+    /// The certificate and key were rejected by rustls (e.g. the key does not match)
+    #[error("Invalid TLS configuration: {0}")]
+    Config(#[from] rustls::Error),
+}
@@ -0,0 +1,505 @@
+//! Server-to-server federation.
+//!
+//! Peer servers are linked over WebSocket (`/federation`) and mirror their rooms so that a
+//! chat can span several sites without a shared database:
+//!
+//! - Every frame is signed with the shared secret (HMAC-SHA256). A link starts with a signed
+//!   hello from both sides and is closed on any invalid frame.
+//! - After the hello, each side sends a snapshot of its local participants, then streams chat
+//!   messages and participant joins/leaves collected by [`FederationPusher`].
+//! - Participants of a peer appear in the local room as `{client_id}@{origin}` and are removed
+//!   when the link that introduced them goes down.
+//! - Events are forwarded to the other links, recording the servers they passed through in
+//!   `via`; an event is never applied or sent to a server already in `via`, and events of
+//!   remote participants are never sent back by the pusher, which prevents loops.
+//!
+//! Each server has a single room, so a link mirrors the rooms of the two servers. Only one
+//! side of a pair needs to dial the other.
+//!
+//! [`FederationPusher`]: crate::infrastructure::message_pusher::FederationPusher
+
+use std::{collections::HashMap, pin::pin, sync::Arc, time::Duration};
+
+use axum::{
+    Router,
+    extract::{
+        State,
+        ws::{Message, WebSocketUpgrade},
+    },
+    response::IntoResponse,
+    routing::get,
+};
+use futures_util::{Sink, SinkExt, Stream, StreamExt, future::ready};
+use tokio::{
+    sync::{Mutex, mpsc},
+    task::JoinHandle,
+};
+use tokio_tungstenite::tungstenite;
+
+use crate::{
+    domain::{ClientId, MessageContent, Participant, Timestamp},
+    infrastructure::{
+        dto::{
+            federation::{FederationEnvelope, FederationEvent},
+            websocket::{ChatMessage, MessageType},
+        },
+        error::FederationError,
+        federation::{RemoteParticipants, open, remote_client_id, seal, verify_hello},
+    },
+};
+use engawa_shared::time::get_jst_timestamp;
+
+use super::{
+    presenter::websocket::{participant_joined, participant_left},
+    signal::ShutdownToken,
+    state::AppState,
+};
+
+/// Path of the federation endpoint
+pub const FEDERATION_PATH: &str = "/federation";
+
+/// Delay before redialing a peer
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Time allowed for the peer's hello
+const HELLO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Federation settings
+pub struct Federation {
+    server_id: String,
+    secret: String,
+    peers: Vec<String>,
+    remote: RemoteParticipants,
+    local_events: mpsc::Receiver<FederationEvent>,
+}
+
+impl Federation {
+    /// Create a new federation
+    ///
+    /// # Arguments
+    ///
+    /// * `server_id` - ID of this server, unique among the federated servers
+    /// * `secret` - Secret shared by the federated servers
+    /// * `peers` - Federation endpoints to dial (e.g. `ws://osaka.example.org:8080/federation`)
+    /// * `remote` - Remote participants shared with `FederationPusher`
+    /// * `local_events` - Queue filled by `FederationPusher`
+    pub fn new(
+        server_id: String,
+        secret: String,
+        peers: Vec<String>,
+        remote: RemoteParticipants,
+        local_events: mpsc::Receiver<FederationEvent>,
+    ) -> Self {
+        Self {
+            server_id,
+            secret,
+            peers,
+            remote,
+            local_events,
+        }
+    }
+}
+
+/// Participant of a peer server in the local room
+struct RemoteParticipant {
+    /// Peer whose link introduced the participant
+    link: String,
+    /// Task discarding the room broadcasts pushed to the participant
+    drain: JoinHandle<()>,
+}
+
+/// Links and remote participants shared by the federation tasks
+struct Hub {
+    server_id: String,
+    secret: String,
+    state: Arc<AppState>,
+    remote: RemoteParticipants,
+    shutdown: ShutdownToken,
+    /// Outgoing frames of each established link, keyed by peer server ID
+    links: Mutex<HashMap<String, mpsc::UnboundedSender<String>>>,
+    /// Remote participants keyed by client ID
+    participants: Mutex<HashMap<String, RemoteParticipant>>,
+}
+
+/// Start dialing the peers and forwarding local events
+///
+/// Returns the router serving the federation endpoint for inbound links.
+pub fn start(federation: Federation, state: Arc<AppState>, shutdown: ShutdownToken) -> Router {
+    let hub = Arc::new(Hub {
+        server_id: federation.server_id,
+        secret: federation.secret,
+        state,
+        remote: federation.remote,
+        shutdown,
+        links: Mutex::new(HashMap::new()),
+        participants: Mutex::new(HashMap::new()),
+    });
+    tracing::info!("Federation enabled as '{}'", hub.server_id);
+
+    engawa_shared::task::spawn(
+        "federation-forward",
+        forward_local_events(hub.clone(), federation.local_events),
+    );
+    for peer in federation.peers {
+        engawa_shared::task::spawn("federation-dial", dial(hub.clone(), peer));
+    }
+
+    Router::new()
+        .route(FEDERATION_PATH, get(accept))
+        .with_state(hub)
+}
+
+/// Accept an inbound link
+async fn accept(ws: WebSocketUpgrade, State(hub): State<Arc<Hub>>) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move {
+        let (sink, stream) = socket.split();
+        let incoming = stream
+            .take_while(|message| ready(message.is_ok()))
+            .filter_map(|message| {
+                ready(match message {
+                    Ok(Message::Text(text)) => Some(text.to_string()),
+                    _ => None,
+                })
+            });
+        let outgoing =
+            sink.with(|frame: String| ready(Ok::<_, axum::Error>(Message::Text(frame.into()))));
+        hub.run_link(incoming, outgoing).await;
+    })
+}
+
+/// Keep a link with a peer, redialing when it goes down
+async fn dial(hub: Arc<Hub>, url: String) {
+    while !hub.shutdown.is_triggered() {
+        let connection = tokio::select! {
+            connection = tokio_tungstenite::connect_async(url.as_str()) => connection,
+            _ = hub.shutdown.cancelled() => break,
+        };
+        match connection {
+            Ok((socket, _)) => {
+                let (sink, stream) = socket.split();
+                let incoming = stream
+                    .take_while(|message| ready(message.is_ok()))
+                    .filter_map(|message| {
+                        ready(match message {
+                            Ok(tungstenite::Message::Text(text)) => Some(text.to_string()),
+                            _ => None,
+                        })
+                    });
+                let outgoing = sink.with(|frame: String| {
+                    ready(Ok::<_, tungstenite::Error>(tungstenite::Message::Text(
+                        frame.into(),
+                    )))
+                });
+                hub.clone().run_link(incoming, outgoing).await;
+            }
+            Err(e) => tracing::warn!("Failed to connect to federation peer {}: {}", url, e),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(RECONNECT_DELAY) => {},
+            _ = hub.shutdown.cancelled() => break,
+        }
+    }
+}
+
+/// Send the events of local participants to every peer
+async fn forward_local_events(hub: Arc<Hub>, mut local_events: mpsc::Receiver<FederationEvent>) {
+    while let Some(event) = local_events.recv().await {
+        let frame = seal(&hub.secret, &hub.envelope(event));
+        for link in hub.links.lock().await.values() {
+            let _ = link.send(frame.clone());
+        }
+    }
+}
+
+impl Hub {
+    fn envelope(&self, event: FederationEvent) -> FederationEnvelope {
+        FederationEnvelope {
+            origin: self.server_id.clone(),
+            via: vec![self.server_id.clone()],
+            event,
+        }
+    }
+
+    /// Run a link until it goes down or shutdown is requested
+    async fn run_link<St, Si>(self: Arc<Self>, incoming: St, outgoing: Si)
+    where
+        St: Stream<Item = String>,
+        Si: Sink<String>,
+    {
+        let mut incoming = pin!(incoming);
+        let mut outgoing = pin!(outgoing);
+
+        let hello = self.envelope(FederationEvent::Hello {
+            timestamp: get_jst_timestamp(),
+        });
+        if outgoing.send(seal(&self.secret, &hello)).await.is_err() {
+            return;
+        }
+        let peer = match tokio::time::timeout(HELLO_TIMEOUT, incoming.next()).await {
+            Ok(Some(frame)) => match open(&self.secret, &frame)
+                .and_then(|hello| verify_hello(&hello, &self.server_id, get_jst_timestamp()))
+            {
+                Ok(peer) => peer,
+                Err(e) => {
+                    tracing::warn!("Rejected federation link: {}", e);
+                    return;
+                }
+            },
+            _ => {
+                tracing::warn!("Federation peer did not send a hello");
+                return;
+            }
+        };
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        {
+            let mut links = self.links.lock().await;
+            if links.contains_key(&peer) {
+                tracing::warn!("Already linked with federation peer '{}'", peer);
+                return;
+            }
+            links.insert(peer.clone(), sender.clone());
+        }
+        tracing::info!("Federation link with '{}' established", peer);
+
+        let participants = self.local_participants().await;
+        let _ = sender.send(seal(
+            &self.secret,
+            &self.envelope(FederationEvent::Snapshot { participants }),
+        ));
+        drop(sender);
+
+        loop {
+            tokio::select! {
+                frame = receiver.recv() => match frame {
+                    Some(frame) => {
+                        if outgoing.send(frame).await.is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                },
+                frame = incoming.next() => match frame {
+                    Some(frame) => {
+                        if let Err(e) = self.handle_frame(&peer, &frame).await {
+                            tracing::warn!("Closing federation link with '{}': {}", peer, e);
+                            break;
+                        }
+                    }
+                    None => break,
+                },
+                _ = self.shutdown.cancelled() => break,
+            }
+        }
+
+        self.links.lock().await.remove(&peer);
+        self.remove_link_participants(&peer).await;
+        let _ = outgoing.close().await;
+        tracing::info!("Federation link with '{}' closed", peer);
+    }
+
+    /// Client IDs of the participants connected to this server
+    async fn local_participants(&self) -> Vec<String> {
+        self.state
+            .connect_participant_usecase
+            .build_participant_list()
+            .await
+            .into_iter()
+            .map(|participant| participant.id.as_str().to_string())
+            .filter(|client_id| !self.remote.contains(client_id))
+            .collect()
+    }
+
+    /// Apply a frame received from a peer and forward it to the other peers
+    async fn handle_frame(&self, peer: &str, frame: &str) -> Result<(), FederationError> {
+        let mut envelope = open(&self.secret, frame)?;
+        if envelope.via.contains(&self.server_id) {
+            return Ok(());
+        }
+
+        let origin = envelope.origin.as_str();
+        match &envelope.event {
+            FederationEvent::Hello { .. } => return Ok(()),
+            FederationEvent::Snapshot { participants } => {
+                for client_id in participants {
+                    self.add_remote(remote_client_id(client_id, origin), peer)
+                        .await;
+                }
+                // Snapshots describe the link they were sent on and are not forwarded
+                return Ok(());
+            }
+            FederationEvent::ParticipantJoined { client_id } => {
+                self.add_remote(remote_client_id(client_id, origin), peer)
+                    .await;
+            }
+            FederationEvent::ParticipantLeft { client_id } => {
+                self.remove_remote(&remote_client_id(client_id, origin))
+                    .await;
+            }
+            FederationEvent::Chat {
+                client_id,
+                content,
+                timestamp,
+            } => {
+                let sender = remote_client_id(client_id, origin);
+                // Messages can arrive before the snapshot of a newly linked peer
+                self.add_remote(sender.clone(), peer).await;
+                self.inject_message(sender, content, *timestamp).await;
+            }
+        }
+
+        envelope.via.push(self.server_id.clone());
+        let frame = seal(&self.secret, &envelope);
+        for (id, link) in self.links.lock().await.iter() {
+            if !envelope.via.contains(id) {
+                let _ = link.send(frame.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Connect a participant of a peer to the local room
+    async fn add_remote(&self, id: String, link: &str) {
+        let mut participants = self.participants.lock().await;
+        if participants.contains_key(&id) {
+            return;
+        }
+        let Ok(client_id) = ClientId::try_from(id.clone()) else {
+            tracing::warn!("Ignoring invalid remote participant '{}'", id);
+            return;
+        };
+
+        // Registered first so that the pusher does not send the join back
+        self.remote.insert(&id);
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Arc<str>>();
+        let connected_at = match self
+            .state
+            .connect_participant_usecase
+            .execute(client_id.clone(), sender)
+            .await
+        {
+            Ok(connected_at) => connected_at,
+            Err(e) => {
+                self.remote.remove(&id);
+                tracing::warn!("Failed to add remote participant '{}': {:?}", id, e);
+                return;
+            }
+        };
+        let drain = engawa_shared::task::spawn("federation-drain", async move {
+            while receiver.recv().await.is_some() {}
+        });
+        participants.insert(
+            id.clone(),
+            RemoteParticipant {
+                link: link.to_string(),
+                drain,
+            },
+        );
+        drop(participants);
+        tracing::info!("Remote participant '{}' joined via '{}'", id, link);
+
+        let joined_msg = participant_joined(
+            Participant::new(client_id.clone(), connected_at),
+            self.state.locale,
+        );
+        let joined_json = serde_json::to_string(&joined_msg).unwrap();
+        if let Err(e) = self
+            .state
+            .connect_participant_usecase
+            .broadcast_participant_joined(&client_id, &joined_json)
+            .await
+        {
+            tracing::warn!("Failed to broadcast participant-joined: {}", e);
+        }
+    }
+
+    /// Disconnect a participant of a peer from the local room
+    async fn remove_remote(&self, id: &str) {
+        let Some(participant) = self.participants.lock().await.remove(id) else {
+            return;
+        };
+        participant.drain.abort();
+        tracing::info!("Remote participant '{}' left", id);
+
+        let Ok(client_id) = ClientId::try_from(id.to_string()) else {
+            return;
+        };
+        if let Ok(notify_targets) = self
+            .state
+            .disconnect_participant_usecase
+            .execute(client_id.clone())
+            .await
+        {
+            let left_msg = participant_left(
+                &client_id,
+                Timestamp::new(get_jst_timestamp()),
+                self.state.locale,
+            );
+            let left_json = serde_json::to_string(&left_msg).unwrap();
+            if let Err(e) = self
+                .state
+                .disconnect_participant_usecase
+                .broadcast_participant_left(notify_targets, &left_json)
+                .await
+            {
+                tracing::warn!("Failed to broadcast participant-left: {}", e);
+            }
+        }
+        // Removed last so that the pusher does not send the leave back
+        self.remote.remove(id);
+    }
+
+    /// Disconnect the remote participants introduced by a link
+    async fn remove_link_participants(&self, peer: &str) {
+        let ids: Vec<String> = self
+            .participants
+            .lock()
+            .await
+            .iter()
+            .filter(|(_, participant)| participant.link == peer)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in ids {
+            self.remove_remote(&id).await;
+        }
+    }
+
+    /// Inject a chat message of a remote participant into the local room
+    async fn inject_message(&self, sender: String, content: &str, timestamp: i64) {
+        let content = self.state.sanitize_profile.apply(content);
+        let (Ok(client_id), Ok(content_vo)) = (
+            ClientId::try_from(sender.clone()),
+            MessageContent::try_from(content.to_string()),
+        ) else {
+            tracing::warn!("Ignoring invalid federated message from '{}'", sender);
+            return;
+        };
+
+        let chat_message = ChatMessage {
+            r#type: MessageType::Chat,
+            client_id: sender,
+            content: content.to_string(),
+            timestamp,
+            seq: None,
+            poll: None,
+            forwarded_from: None,
+        };
+        tracing::info!(
+            "Relaying federated message from '{}': {}",
+            chat_message.client_id,
+            chat_message.content
+        );
+
+        if let Err(e) = self
+            .state
+            .send_message_usecase
+            .execute(client_id, content_vo, move |seq| {
+                chat_message.to_json_with_seq(seq.value())
+            })
+            .await
+        {
+            tracing::warn!("Failed to relay federated message: {:?}", e);
+        }
+    }
+}
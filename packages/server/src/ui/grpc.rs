@@ -0,0 +1,129 @@
+//! gRPC health checking protocol (`grpc.health.v1.Health`).
+//!
+//! Served on a dedicated port so that gRPC-aware load balancers (Envoy, GKE, Kubernetes
+//! `grpc` probes) can route around unhealthy instances.
+//!
+//! | Service name       | Status source                                              |
+//! |--------------------|------------------------------------------------------------|
+//! | `""` (overall)     | Same as `engawa.Chat`                                      |
+//! | `engawa.Chat`      | Dependency health probed through `CheckHealthUseCase`      |
+//!
+//! The probe is the same as `/api/v1/health`: the repository and the message pusher must
+//! both answer within the timeout. Every service turns `NOT_SERVING` once shutdown
+//! is requested, so load balancers drain the instance before it stops.
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use tonic::transport::server::TcpIncoming;
+use tonic_health::{ServingStatus, server::HealthReporter};
+
+use super::{signal::ShutdownToken, state::AppState};
+use crate::usecase::DependencyStatus;
+
+/// Service name reported for the chat service
+pub const CHAT_SERVICE_NAME: &str = "engawa.Chat";
+
+/// Interval between readiness probes
+const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Compute the serving status from the readiness of the server's dependencies
+///
+/// # Arguments
+///
+/// * `dependencies_ready` - Whether every dependency answered the readiness probe
+/// * `shutting_down` - Whether shutdown has been requested
+pub fn serving_status(dependencies_ready: bool, shutting_down: bool) -> ServingStatus {
+    if dependencies_ready && !shutting_down {
+        ServingStatus::Serving
+    } else {
+        ServingStatus::NotServing
+    }
+}
+
+/// Serve the gRPC health service until shutdown is requested
+///
+/// # Arguments
+///
+/// * `state` - Application state used to probe readiness
+/// * `addr` - Address to bind the gRPC listener to
+/// * `shutdown` - Token stopping the service together with the server
+pub async fn serve_health(
+    state: Arc<AppState>,
+    addr: SocketAddr,
+    shutdown: ShutdownToken,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!(
+        "gRPC health service listening on {}",
+        listener.local_addr()?
+    );
+
+    let (reporter, service) = tonic_health::server::health_reporter();
+    let probe = engawa_shared::task::spawn(
+        "grpc-health-probe",
+        probe_readiness(state, reporter.clone()),
+    );
+
+    // Report NOT_SERVING before the listener goes away so that in-flight `Watch` streams see it
+    let signal = async move {
+        shutdown.cancelled().await;
+        probe.abort();
+        set_status(&reporter, serving_status(true, true)).await;
+    };
+    tonic::transport::Server::builder()
+        .add_service(service)
+        .serve_with_incoming_shutdown(TcpIncoming::from(listener), signal)
+        .await?;
+    Ok(())
+}
+
+/// Periodically probe readiness and publish it to the health reporter
+async fn probe_readiness(state: Arc<AppState>, reporter: HealthReporter) {
+    let mut interval = tokio::time::interval(PROBE_INTERVAL);
+    loop {
+        interval.tick().await;
+        let Some(usecase) = &state.check_health_usecase else {
+            set_status(&reporter, serving_status(true, false)).await;
+            continue;
+        };
+        let report = usecase.execute().await;
+        for dependency in &report.dependencies {
+            if let DependencyStatus::Down(reason) = &dependency.status {
+                tracing::warn!(
+                    "Readiness probe failed: {} is unavailable: {}",
+                    dependency.name,
+                    reason
+                );
+            }
+        }
+        set_status(&reporter, serving_status(report.is_healthy(), false)).await;
+    }
+}
+
+async fn set_status(reporter: &HealthReporter, status: ServingStatus) {
+    reporter.set_service_status("", status).await;
+    reporter.set_service_status(CHAT_SERVICE_NAME, status).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serving_status_when_ready() {
+        // テスト項目: 依存先が応答し、シャットダウン中でなければ SERVING になる
+        // when (操作):
+        let status = serving_status(true, false);
+
+        // then (期待する結果):
+        assert_eq!(status, ServingStatus::Serving);
+    }
+
+    #[test]
+    fn test_serving_status_when_not_ready() {
+        // テスト項目: 依存先が応答しない、またはシャットダウン中は NOT_SERVING になる
+        // then (期待する結果):
+        assert_eq!(serving_status(false, false), ServingStatus::NotServing);
+        assert_eq!(serving_status(true, true), ServingStatus::NotServing);
+    }
+}
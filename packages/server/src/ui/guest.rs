@@ -0,0 +1,153 @@
+//! Guests: clients connecting without a client ID.
+//!
+//! The server assigns guests a readable ID such as `guest-7f3a` and marks them as guests in
+//! participant DTOs. Depending on the [`GuestMode`], guests may only read, or may post at a
+//! limited rate.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use super::config::GuestMode;
+use crate::{domain::ClientId, infrastructure::error::InboundMessageError};
+
+/// Number of times a guest ID is drawn again if it collides with a connected guest
+pub const MAX_GUEST_ID_ATTEMPTS: usize = 8;
+
+/// Window over which the messages of a guest are counted
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// What guests may do
+#[derive(Default)]
+pub struct GuestPolicy {
+    mode: GuestMode,
+    /// Cap on the messages a guest may post per minute (unlimited if `None`)
+    messages_per_minute: Option<u32>,
+    /// Start of the current window and the messages posted in it, by guest
+    windows: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl GuestPolicy {
+    /// Create a policy
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - Whether guests may connect and post
+    /// * `messages_per_minute` - Cap on the messages a guest may post per minute
+    pub fn new(mode: GuestMode, messages_per_minute: Option<u32>) -> Self {
+        Self {
+            mode,
+            messages_per_minute,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether clients may connect without a client ID
+    pub fn allows_guests(&self) -> bool {
+        self.mode != GuestMode::Disabled
+    }
+
+    /// Check whether the client may announce that it is typing
+    ///
+    /// Read-only guests cannot post, so their typing indicators are rejected too. Typing
+    /// indicators do not count toward the message rate.
+    pub fn check_typing(&self, client_id: &ClientId) -> Result<(), InboundMessageError> {
+        if client_id.is_guest() && self.mode == GuestMode::ReadOnly {
+            return Err(InboundMessageError::ReadOnly);
+        }
+        Ok(())
+    }
+
+    /// Check whether the client may post a message at `now`
+    ///
+    /// Clients that are not guests may always post.
+    pub fn check_post(
+        &self,
+        client_id: &ClientId,
+        now: Instant,
+    ) -> Result<(), InboundMessageError> {
+        if !client_id.is_guest() {
+            return Ok(());
+        }
+        if self.mode == GuestMode::ReadOnly {
+            return Err(InboundMessageError::ReadOnly);
+        }
+        let Some(limit) = self.messages_per_minute else {
+            return Ok(());
+        };
+
+        let mut windows = self.windows.lock().unwrap();
+        let (start, count) = windows
+            .entry(client_id.as_str().to_string())
+            .or_insert((now, 0));
+        if now.duration_since(*start) >= RATE_WINDOW {
+            *start = now;
+            *count = 0;
+        }
+        if *count >= limit {
+            let retry_after = RATE_WINDOW.saturating_sub(now.duration_since(*start));
+            return Err(InboundMessageError::RateLimited {
+                retry_after_ms: retry_after.as_millis() as u64,
+            });
+        }
+        *count += 1;
+        Ok(())
+    }
+
+    /// Forget the messages counted for a client that disconnected
+    pub fn forget(&self, client_id: &str) {
+        self.windows.lock().unwrap().remove(client_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guest() -> ClientId {
+        ClientId::new("guest-7f3a".to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_read_only_guests_cannot_post() {
+        // テスト項目: 読み取り専用モードではゲストは投稿できず、ゲスト以外は投稿できる
+        // given (前提条件):
+        let policy = GuestPolicy::new(GuestMode::ReadOnly, None);
+        let now = Instant::now();
+
+        // when (操作):
+        let guest_result = policy.check_post(&guest(), now);
+        let member_result = policy.check_post(&ClientId::new("alice".to_string()).unwrap(), now);
+
+        // then (期待する結果):
+        assert_eq!(guest_result, Err(InboundMessageError::ReadOnly));
+        assert_eq!(member_result, Ok(()));
+    }
+
+    #[test]
+    fn test_guest_posts_are_rate_limited_per_minute() {
+        // テスト項目: 1 分あたりの上限を超えた投稿は拒否され、次の 1 分で再び投稿できる
+        // given (前提条件):
+        let policy = GuestPolicy::new(GuestMode::Allowed, Some(2));
+        let start = Instant::now();
+
+        // when (操作):
+        let first = policy.check_post(&guest(), start);
+        let second = policy.check_post(&guest(), start + Duration::from_secs(10));
+        let third = policy.check_post(&guest(), start + Duration::from_secs(20));
+        let next_minute = policy.check_post(&guest(), start + Duration::from_secs(60));
+
+        // then (期待する結果):
+        assert_eq!(first, Ok(()));
+        assert_eq!(second, Ok(()));
+        assert_eq!(
+            third,
+            Err(InboundMessageError::RateLimited {
+                retry_after_ms: 40_000
+            })
+        );
+        assert_eq!(next_minute, Ok(()));
+    }
+}
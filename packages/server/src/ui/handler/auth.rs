@@ -0,0 +1,41 @@
+//! Login endpoint handler.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::State,
+    http::{StatusCode, header::CACHE_CONTROL},
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    infrastructure::dto::http::{AccessTokenDto, LoginRequestDto},
+    ui::{http_cache::NO_STORE, state::AppState},
+};
+
+/// Log in with a client ID and password and get an access token
+///
+/// Responds with 404 if authentication is not enabled and 401 if the credentials are wrong.
+/// Connect with `Authorization: Bearer <access_token>` (or `/ws?access_token=...`) as the
+/// client ID logged in.
+pub async fn login(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<LoginRequestDto>,
+) -> Result<Response, StatusCode> {
+    let auth = state.auth.as_ref().ok_or(StatusCode::NOT_FOUND)?;
+    let access_token = match auth.login(&request.client_id, &request.password) {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::warn!("Login of '{}' failed: {}", request.client_id, e);
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+    };
+    tracing::info!("Client '{}' logged in", request.client_id);
+    let token = AccessTokenDto {
+        access_token,
+        token_type: "Bearer".to_string(),
+        expires_in_secs: auth.token_ttl_secs(),
+    };
+    Ok(([(CACHE_CONTROL, NO_STORE)], Json(token)).into_response())
+}
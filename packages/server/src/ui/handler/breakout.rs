@@ -0,0 +1,111 @@
+//! Breakout room endpoint handlers.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{StatusCode, header::CACHE_CONTROL},
+    response::{IntoResponse, Response},
+};
+
+use engawa_shared::time::get_jst_timestamp;
+
+use crate::{
+    domain::{ClientId, Timestamp},
+    infrastructure::{
+        dto::http::{BreakoutDto, BreakoutListDto, CreateBreakoutRequestDto},
+        i18n::SystemText,
+    },
+    ui::{breakout::announce, http_cache::NO_STORE, state::AppState},
+    usecase::{ManageBreakoutsError, ManageBreakoutsUseCase},
+};
+
+/// List the breakouts spawned from a room, oldest first (including archived ones)
+///
+/// Responds with 404 if breakouts are not enabled or the room does not exist.
+pub async fn list_breakouts(
+    State(state): State<Arc<AppState>>,
+    Path(room_id): Path<String>,
+) -> Result<Response, StatusCode> {
+    let usecase = breakouts_usecase(&state)?;
+    let breakouts = usecase.list(&room_id).await.map_err(error_status)?;
+    let breakouts = BreakoutListDto {
+        breakouts: breakouts.into_iter().map(Into::into).collect(),
+    };
+    Ok(([(CACHE_CONTROL, NO_STORE)], Json(breakouts)).into_response())
+}
+
+/// Spawn a breakout room from a room
+///
+/// The breakout inherits the class and capacities of the room; if `members` lists
+/// participants of the room who opted in, only they may join it. The room receives a chat
+/// message with the breakout's room ID. Responds with 201 and the breakout, 400 if the name
+/// is empty or too long, a member is not in the room, or the room is itself a breakout, and
+/// 503 if the room limit is reached.
+pub async fn create_breakout(
+    State(state): State<Arc<AppState>>,
+    Path(room_id): Path<String>,
+    Json(request): Json<CreateBreakoutRequestDto>,
+) -> Result<Response, StatusCode> {
+    let usecase = breakouts_usecase(&state)?;
+    let members = request
+        .members
+        .into_iter()
+        .map(ClientId::new)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let breakout = usecase
+        .create(
+            &room_id,
+            &request.name,
+            members,
+            Timestamp::new(get_jst_timestamp()),
+        )
+        .await
+        .map_err(error_status)?;
+    tracing::info!(
+        "Breakout {} spawned from room {}",
+        breakout.room_id,
+        breakout.parent_id
+    );
+
+    let text = SystemText::BreakoutOpened {
+        name: &breakout.name,
+        room_id: breakout.room_id.as_str(),
+    };
+    announce(&state, &breakout, text).await;
+
+    let breakout = BreakoutDto::from(breakout);
+    Ok((
+        StatusCode::CREATED,
+        [(CACHE_CONTROL, NO_STORE)],
+        Json(breakout),
+    )
+        .into_response())
+}
+
+fn breakouts_usecase(state: &AppState) -> Result<&ManageBreakoutsUseCase, StatusCode> {
+    state
+        .manage_breakouts_usecase
+        .as_deref()
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+fn error_status(error: ManageBreakoutsError) -> StatusCode {
+    match error {
+        ManageBreakoutsError::RoomNotFound => StatusCode::NOT_FOUND,
+        ManageBreakoutsError::NestedBreakout | ManageBreakoutsError::InvalidName => {
+            StatusCode::BAD_REQUEST
+        }
+        ManageBreakoutsError::NotInParent(client_id) => {
+            tracing::warn!("Breakout member '{}' is not in the room", client_id);
+            StatusCode::BAD_REQUEST
+        }
+        ManageBreakoutsError::TooManyRooms => {
+            tracing::warn!("Room limit reached; rejecting breakout creation");
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        ManageBreakoutsError::RepositoryError => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
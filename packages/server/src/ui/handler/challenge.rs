@@ -0,0 +1,80 @@
+//! Connect challenge endpoint handlers.
+
+use std::{sync::Arc, time::Instant};
+
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode, header::CACHE_CONTROL},
+    response::{IntoResponse, Response},
+};
+
+use super::http::authorize_admin;
+use crate::{
+    infrastructure::{
+        dto::http::{ChallengeModeDto, ChallengeModeRequestDto, ConnectChallengeDto},
+        proof_of_work::CHALLENGE_TTL,
+    },
+    ui::{http_cache::NO_STORE, state::AppState},
+};
+
+/// Get a proof-of-work challenge to solve before connecting (404 while the challenge is
+/// disabled)
+///
+/// The solution is a nonce such that `SHA-256("<challenge>:<nonce>")` starts with `difficulty`
+/// zero bits; connect with `/ws?pow=<challenge>:<nonce>` before the challenge expires.
+pub async fn get_connect_challenge(
+    State(state): State<Arc<AppState>>,
+) -> Result<Response, StatusCode> {
+    let challenge = state
+        .connect_challenge
+        .issue(Instant::now())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let challenge = ConnectChallengeDto {
+        challenge,
+        difficulty: state.connect_challenge.difficulty(),
+        expires_in_secs: CHALLENGE_TTL.as_secs(),
+    };
+    Ok(([(CACHE_CONTROL, NO_STORE)], Json(challenge)).into_response())
+}
+
+/// Get whether connecting clients must solve a proof-of-work
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if no admin token is configured).
+pub async fn get_challenge_mode(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    authorize_admin(&state, &headers)?;
+    Ok(challenge_mode(&state))
+}
+
+/// Require (or stop requiring) a proof-of-work from connecting clients
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if no admin token is configured).
+/// Connected clients are not affected.
+pub async fn set_challenge_mode(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<ChallengeModeRequestDto>,
+) -> Result<Response, StatusCode> {
+    authorize_admin(&state, &headers)?;
+    state.connect_challenge.set_enabled(request.enabled);
+    tracing::warn!(
+        "Connect challenge {} by an admin",
+        if request.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+    Ok(challenge_mode(&state))
+}
+
+fn challenge_mode(state: &AppState) -> Response {
+    let mode = ChallengeModeDto {
+        enabled: state.connect_challenge.is_enabled(),
+        difficulty: state.connect_challenge.difficulty(),
+    };
+    ([(CACHE_CONTROL, NO_STORE)], Json(mode)).into_response()
+}
@@ -1,85 +1,686 @@
 //! HTTP API endpoint handlers.
 
-use std::sync::Arc;
+use std::{convert::Infallible, sync::Arc, time::Instant};
 
 use axum::{
-    Json,
-    extract::{Path, State},
-    http::StatusCode,
+    Extension, Json,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE},
+    },
+    response::{Html, IntoResponse, Response},
 };
 
+use serde::Deserialize;
+
+use engawa_shared::time::get_jst_timestamp;
+
+use super::moderation::end_sessions;
 use crate::{
-    domain::Room,
-    infrastructure::dto::http::{ParticipantDetailDto, RoomDetailDto, RoomSummaryDto},
-    ui::state::AppState,
+    domain::{ClientId, ClientIdentity, RoomClass, RoomSlug, SequenceNumber, Timestamp},
+    infrastructure::{
+        auth::secrets_match,
+        cluster::NodeStatus,
+        dto::http::{
+            ClusterDto, ClusterNodeDto, ErasedClientDataDto, HealthDto, MessageSearchDto,
+            RoomDetailDto, RoomMessagesDto, RoomStateDto, RoomStatsDto, RoomSummaryDto,
+            RoomsPageDto, StarredMessagesDto,
+        },
+        dto::schema::protocol_schemas,
+        dto::websocket::{MessageDeletedMessage, MessageType},
+        filter::{MaxLinksFilter, ProfanityFilter, RegexBlocklistFilter},
+        metrics,
+    },
+    ui::{
+        http_cache::{NO_STORE, revalidatable_json},
+        openapi::openapi_document,
+        presenter::export::ExportFormat,
+        session::SessionEnd,
+        state::AppState,
+    },
+    usecase::{
+        CreateRoomError, DEFAULT_EXPORT_LIMIT, DEFAULT_MESSAGE_LIMIT, DEFAULT_ROOMS_LIMIT,
+        DEFAULT_SEARCH_LIMIT, DependencyStatus, ExportMessagesError, ExportQuery,
+        GetRoomDetailError, GetRoomStatsError, HealthReport, NewRoom, RoomDetail, RoomDetailQuery,
+        RoomListing, RoomSort, RoomsQuery, SearchMessagesError,
+    },
 };
-use engawa_shared::time::timestamp_to_jst_rfc3339;
+
+/// Header giving the `before_timestamp` of the next page of a message history export
+pub const NEXT_BEFORE_TIMESTAMP_HEADER: &str = "x-next-before-timestamp";
 
 /// Debug endpoint to get current room state (for testing purposes)
-pub async fn debug_room_state(State(state): State<Arc<AppState>>) -> Json<Room> {
+pub async fn debug_room_state(State(state): State<Arc<AppState>>) -> Json<RoomStateDto> {
     let room = state
         .get_room_state_usecase
         .execute()
         .await
         .expect("Failed to get room state");
-    Json(room)
+    Json(room.into())
 }
 
 /// Health check endpoint
-pub async fn health_check() -> Json<serde_json::Value> {
-    Json(serde_json::json!({"status": "ok"}))
+///
+/// Pings the repository and the message pusher (each with a timeout) and reports their status
+/// and latency. Responds with `503 Service Unavailable` if any of them is down.
+pub async fn health_check(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let report = match &state.check_health_usecase {
+        Some(usecase) => usecase.execute().await,
+        None => HealthReport {
+            dependencies: Vec::new(),
+        },
+    };
+
+    let status = if report.is_healthy() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    for dependency in &report.dependencies {
+        if let DependencyStatus::Down(reason) = &dependency.status {
+            tracing::warn!("Health check: {} is down: {}", dependency.name, reason);
+        }
+    }
+    // Domain Model から DTO への変換
+    let health = HealthDto::from(report);
+
+    (status, [(CACHE_CONTROL, NO_STORE)], Json(health))
+}
+
+/// JSON Schemas of the WebSocket protocol and REST API messages
+pub async fn get_schema(headers: HeaderMap) -> Response {
+    revalidatable_json(&headers, &protocol_schemas(), None)
+}
+
+/// OpenAPI document of the REST API
+pub async fn get_openapi(headers: HeaderMap) -> Response {
+    revalidatable_json(&headers, &openapi_document(), None)
+}
+
+/// Swagger UI page browsing the OpenAPI document (the UI itself is loaded from a CDN)
+pub async fn get_api_docs() -> Html<&'static str> {
+    Html(API_DOCS_PAGE)
+}
+
+const API_DOCS_PAGE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>engawa REST API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js" crossorigin></script>
+  <script>
+    window.onload = () => {
+      window.ui = SwaggerUIBundle({ url: "openapi.json", dom_id: "#swagger-ui" });
+    };
+  </script>
+</body>
+</html>
+"##;
+
+/// Metrics endpoint (Prometheus text format)
+pub async fn get_metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [
+            (CACHE_CONTROL, NO_STORE),
+            (CONTENT_TYPE, metrics::CONTENT_TYPE),
+        ],
+        state.metrics.render(),
+    )
+}
+
+/// Sort order of the room list
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoomsSortParam {
+    CreatedAt,
+    Participants,
+}
+
+/// Query parameters for the room list endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct RoomsQueryParams {
+    /// Rooms per page (capped at 200, defaults to 50)
+    pub limit: Option<usize>,
+    /// Rooms to skip
+    #[serde(default)]
+    pub offset: usize,
+    /// `created_at` (oldest first, default) or `participants` (most first)
+    pub sort: Option<RoomsSortParam>,
+    /// Case-insensitive substring of the room ID or name (slug)
+    pub q: Option<String>,
 }
 
 /// Get list of rooms
-pub async fn get_rooms(State(state): State<Arc<AppState>>) -> Json<Vec<RoomSummaryDto>> {
-    let rooms = state
+pub async fn get_rooms(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<RoomsQueryParams>,
+    headers: HeaderMap,
+) -> Response {
+    let query = RoomsQuery {
+        limit: params.limit.unwrap_or(DEFAULT_ROOMS_LIMIT),
+        offset: params.offset,
+        sort: match params.sort {
+            Some(RoomsSortParam::CreatedAt) | None => RoomSort::CreatedAt,
+            Some(RoomsSortParam::Participants) => RoomSort::Participants,
+        },
+        q: params.q.filter(|q| !q.is_empty()),
+    };
+    let page = state
         .get_rooms_usecase
-        .execute()
+        .execute(&query)
         .await
         .expect("Failed to get rooms");
 
     // Domain Model から DTO への変換
-    let room_summaries: Vec<RoomSummaryDto> = rooms
-        .into_iter()
-        .map(|room| RoomSummaryDto {
-            id: room.id.as_str().to_string(),
-            participants: room
-                .participants
-                .iter()
-                .map(|p| p.id.as_str().to_string())
-                .collect(),
-            created_at: timestamp_to_jst_rfc3339(room.created_at.value()),
-        })
-        .collect();
+    let room_summaries: Vec<RoomSummaryDto> = page.rooms.into_iter().map(Into::into).collect();
+    let rooms_page = RoomsPageDto {
+        rooms: room_summaries,
+        total: page.total,
+        limit: page.limit,
+        offset: query.offset,
+    };
+
+    revalidatable_json(
+        &headers,
+        &rooms_page,
+        page.last_activity_at.map(|timestamp| timestamp.value()),
+    )
+}
+
+/// Query parameters for the room creation endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateRoomParams {
+    /// Room class selecting its storage backend: `ephemeral` (default) or `persistent`
+    pub class: Option<String>,
+    /// Maximum number of participants (defaults to the server's `--room-capacity`)
+    pub capacity: Option<usize>,
+    /// Maximum number of messages kept in history (defaults to the server's `--message-capacity`)
+    pub message_capacity: Option<usize>,
+    /// Slug naming the room (e.g. `design`), shown as its name in room listings
+    pub slug: Option<String>,
+    /// Maximum length of a message in bytes (defaults to the server's `--max-message-length`)
+    pub max_message_length: Option<usize>,
+    /// Comma-separated names of the server's message filters applied in the room (e.g.
+    /// `max-links`; empty for none, defaults to all of them)
+    pub filters: Option<String>,
+}
+
+/// Create a room (404 if room creation is not enabled)
+///
+/// Responds with 201 and the new room; clients join it with `/ws?room_id=...`.
+/// Responds with 400 if `class` names an unknown room class, `slug` is not a valid slug, a
+/// capacity or the maximum message length is 0 or larger than the server's, or `filters` names
+/// an unknown filter, and with 409 if another room has the slug.
+pub async fn create_room(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<CreateRoomParams>,
+) -> Result<Response, StatusCode> {
+    let usecase = state
+        .create_room_usecase
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let class = match params.class.as_deref() {
+        Some(class) => class.parse().map_err(|e| {
+            tracing::warn!("Rejecting room creation: {}", e);
+            StatusCode::BAD_REQUEST
+        })?,
+        None => RoomClass::default(),
+    };
+    let slug = match params.slug {
+        Some(slug) => Some(RoomSlug::new(slug).map_err(|e| {
+            tracing::warn!("Rejecting room creation: {}", e);
+            StatusCode::BAD_REQUEST
+        })?),
+        None => None,
+    };
+    let message_filters = match params.filters {
+        Some(filters) => Some(message_filter_names(&filters).ok_or_else(|| {
+            tracing::warn!("Rejecting room creation: unknown filter in '{}'", filters);
+            StatusCode::BAD_REQUEST
+        })?),
+        None => None,
+    };
+    match usecase
+        .execute(
+            Timestamp::new(get_jst_timestamp()),
+            NewRoom {
+                class,
+                participant_capacity: params.capacity,
+                message_capacity: params.message_capacity,
+                slug,
+                max_message_length: params.max_message_length,
+                message_filters,
+            },
+        )
+        .await
+    {
+        Ok(room) => {
+            // Domain Model から DTO への変換
+            let room = RoomSummaryDto::from(RoomListing {
+                metadata: room.metadata(),
+                participants: Vec::new(),
+            });
+            Ok((StatusCode::CREATED, Json(room)).into_response())
+        }
+        Err(CreateRoomError::TooManyRooms) => {
+            tracing::warn!("Room limit reached; rejecting room creation");
+            Err(StatusCode::SERVICE_UNAVAILABLE)
+        }
+        Err(CreateRoomError::InvalidCapacity { kind, max }) => {
+            tracing::warn!(
+                "Rejecting room creation: {} capacity must be between 1 and {}",
+                kind,
+                max
+            );
+            Err(StatusCode::BAD_REQUEST)
+        }
+        Err(CreateRoomError::InvalidMessageLength { max }) => {
+            tracing::warn!(
+                "Rejecting room creation: maximum message length must be between 1 and {}",
+                max
+            );
+            Err(StatusCode::BAD_REQUEST)
+        }
+        Err(CreateRoomError::SlugTaken(slug)) => {
+            tracing::warn!("Rejecting room creation: slug '{}' is taken", slug);
+            Err(StatusCode::CONFLICT)
+        }
+        Err(CreateRoomError::RepositoryError) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
 
-    Json(room_summaries)
+/// Parse comma-separated message filter names (`None` if one is not a known filter)
+fn message_filter_names(filters: &str) -> Option<Vec<String>> {
+    const KNOWN: [&str; 3] = [
+        ProfanityFilter::NAME,
+        MaxLinksFilter::NAME,
+        RegexBlocklistFilter::NAME,
+    ];
+    filters
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| KNOWN.contains(&name).then(|| name.to_string()))
+        .collect()
+}
+
+/// Query parameters for the room detail endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct RoomDetailParams {
+    /// Comma-separated fields to include: `participants`, `messages` (defaults to `participants`)
+    pub include: Option<String>,
+    /// Latest messages to include (capped at 200, defaults to 50)
+    pub message_limit: Option<usize>,
+}
+
+impl RoomDetailParams {
+    /// Convert to the use case query, or `None` if `include` names an unknown field
+    fn to_query(&self) -> Option<RoomDetailQuery> {
+        let mut query = RoomDetailQuery {
+            message_limit: self.message_limit.unwrap_or(DEFAULT_MESSAGE_LIMIT),
+            ..RoomDetailQuery::default()
+        };
+        if let Some(include) = &self.include {
+            query.include_participants = false;
+            for field in include.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+                match field {
+                    "participants" => query.include_participants = true,
+                    "messages" => query.include_messages = true,
+                    _ => return None,
+                }
+            }
+        }
+        Some(query)
+    }
 }
 
 /// Get room detail by ID
 pub async fn get_room_detail(
     State(state): State<Arc<AppState>>,
     Path(room_id): Path<String>,
-) -> Result<Json<RoomDetailDto>, StatusCode> {
-    match state.get_room_detail_usecase.execute(room_id).await {
-        Ok(room) => {
+    Query(params): Query<RoomDetailParams>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let query = params.to_query().ok_or(StatusCode::BAD_REQUEST)?;
+    let detail = state.get_room_detail_usecase.execute(&room_id, query).await;
+    room_detail_response(&headers, detail)
+}
+
+/// Get room detail by slug
+pub async fn get_room_detail_by_slug(
+    State(state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+    Query(params): Query<RoomDetailParams>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let query = params.to_query().ok_or(StatusCode::BAD_REQUEST)?;
+    let detail = state
+        .get_room_detail_usecase
+        .execute_by_slug(&slug, query)
+        .await;
+    room_detail_response(&headers, detail)
+}
+
+fn room_detail_response(
+    headers: &HeaderMap,
+    detail: Result<RoomDetail, GetRoomDetailError>,
+) -> Result<Response, StatusCode> {
+    match detail {
+        Ok(detail) => {
             // Domain Model から DTO への変換
-            let room_detail = RoomDetailDto {
-                id: room.id.as_str().to_string(),
-                participants: room
-                    .participants
-                    .iter()
-                    .map(|p| ParticipantDetailDto {
-                        client_id: p.id.as_str().to_string(),
-                        connected_at: timestamp_to_jst_rfc3339(p.connected_at.value()),
-                    })
-                    .collect(),
-                created_at: timestamp_to_jst_rfc3339(room.created_at.value()),
+            let last_activity_at = detail.metadata.last_activity_at;
+            let room_detail = RoomDetailDto::from(detail);
+            Ok(revalidatable_json(
+                headers,
+                &room_detail,
+                Some(last_activity_at.value()),
+            ))
+        }
+        Err(GetRoomDetailError::RoomNotFound) => Err(StatusCode::NOT_FOUND),
+        Err(GetRoomDetailError::RepositoryError) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Query parameters for the room messages endpoint
+#[derive(Debug, Deserialize)]
+pub struct MessagesQuery {
+    /// Latest sequence number the client has; only later messages are returned
+    pub since_seq: Option<u64>,
+    /// Export format (`json`, `csv` or `ndjson`)
+    pub format: Option<String>,
+    /// Number of messages in an export page
+    pub limit: Option<usize>,
+    /// Only messages sent before this time (Unix milliseconds) are exported
+    pub before_timestamp: Option<i64>,
+}
+
+/// Get the messages of a room after `since_seq` (backfill), or export a page of its history
+///
+/// Given `format`, `limit` or `before_timestamp`, the latest `limit` messages sent before
+/// `before_timestamp` are streamed in `format` (JSON by default), oldest first. If older
+/// messages may remain, `X-Next-Before-Timestamp` gives the `before_timestamp` of the next
+/// page. An export cannot be combined with `since_seq` (400).
+pub async fn get_room_messages(
+    State(state): State<Arc<AppState>>,
+    Path(room_id): Path<String>,
+    Query(query): Query<MessagesQuery>,
+) -> Result<Response, StatusCode> {
+    if query.format.is_some() || query.limit.is_some() || query.before_timestamp.is_some() {
+        if query.since_seq.is_some() {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        return export_room_messages(&state, &room_id, query).await;
+    }
+    let since = SequenceNumber::new(query.since_seq.unwrap_or_default());
+    match state
+        .get_room_messages_usecase
+        .execute(&room_id, since)
+        .await
+    {
+        Ok((messages, last_seq)) => {
+            // Domain Model から DTO への変換
+            let room_messages = RoomMessagesDto {
+                room_id,
+                last_seq: last_seq.value(),
+                messages: messages.into_iter().map(Into::into).collect(),
             };
-            Ok(Json(room_detail))
+            Ok(([(CACHE_CONTROL, NO_STORE)], Json(room_messages)).into_response())
         }
-        Err(crate::usecase::GetRoomDetailError::RoomNotFound) => Err(StatusCode::NOT_FOUND),
-        Err(crate::usecase::GetRoomDetailError::RepositoryError) => {
+        Err(crate::usecase::GetRoomMessagesError::RoomNotFound) => Err(StatusCode::NOT_FOUND),
+        Err(crate::usecase::GetRoomMessagesError::RepositoryError) => {
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
+
+/// Stream a page of the message history of a room (404 if exports are not enabled)
+async fn export_room_messages(
+    state: &AppState,
+    room_id: &str,
+    query: MessagesQuery,
+) -> Result<Response, StatusCode> {
+    let usecase = state
+        .export_messages_usecase
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let format = match query.format.as_deref() {
+        Some(format) => format
+            .parse::<ExportFormat>()
+            .map_err(|_| StatusCode::BAD_REQUEST)?,
+        None => ExportFormat::default(),
+    };
+    let export_query = ExportQuery {
+        limit: query.limit.unwrap_or(DEFAULT_EXPORT_LIMIT),
+        before: query.before_timestamp.map(Timestamp::new),
+    };
+    let page = match usecase.execute(room_id, export_query).await {
+        Ok(page) => page,
+        Err(ExportMessagesError::RoomNotFound) => return Err(StatusCode::NOT_FOUND),
+        Err(ExportMessagesError::RepositoryError) => {
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    // Domain Model から DTO への変換
+    let messages = page.messages.into_iter().map(Into::into).collect();
+    let chunks = format.encode(messages).map(Ok::<_, Infallible>);
+    let mut response = (
+        [
+            (CONTENT_TYPE, format.content_type()),
+            (CACHE_CONTROL, NO_STORE),
+        ],
+        Body::from_stream(futures_util::stream::iter(chunks)),
+    )
+        .into_response();
+    if let Some(next_before) = page.next_before {
+        response.headers_mut().insert(
+            NEXT_BEFORE_TIMESTAMP_HEADER,
+            HeaderValue::from(next_before.value()),
+        );
+    }
+    Ok(response)
+}
+
+/// Query parameters for the message search endpoint
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    /// Text the messages contain (case-insensitive)
+    pub q: String,
+    /// Number of messages to return
+    pub limit: Option<usize>,
+}
+
+/// Search the message history of a room, newest first (404 if search is not enabled)
+///
+/// Returns the messages whose content contains `q`, ignoring case (400 if `q` is empty).
+pub async fn search_room_messages(
+    State(state): State<Arc<AppState>>,
+    Path(room_id): Path<String>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Response, StatusCode> {
+    let usecase = state
+        .search_messages_usecase
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    match usecase.execute(&room_id, &query.q, limit).await {
+        Ok(messages) => {
+            // Domain Model から DTO への変換
+            let search = MessageSearchDto {
+                room_id,
+                query: query.q,
+                messages: messages.into_iter().map(Into::into).collect(),
+            };
+            Ok(([(CACHE_CONTROL, NO_STORE)], Json(search)).into_response())
+        }
+        Err(SearchMessagesError::EmptyQuery) => Err(StatusCode::BAD_REQUEST),
+        Err(SearchMessagesError::RoomNotFound) => Err(StatusCode::NOT_FOUND),
+        Err(SearchMessagesError::RepositoryError) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Get the message analytics of a room (404 if the stats are not enabled)
+pub async fn get_room_stats(
+    State(state): State<Arc<AppState>>,
+    Path(room_id): Path<String>,
+) -> Result<Response, StatusCode> {
+    let usecase = state
+        .get_room_stats_usecase
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    match usecase.execute(&room_id).await {
+        // Domain Model から DTO への変換
+        Ok(stats) => {
+            Ok(([(CACHE_CONTROL, NO_STORE)], Json(RoomStatsDto::from(stats))).into_response())
+        }
+        Err(GetRoomStatsError::RoomNotFound) => Err(StatusCode::NOT_FOUND),
+        Err(GetRoomStatsError::RepositoryError) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Get the messages a client starred, in the order they were starred (404 if stars are not
+/// enabled)
+///
+/// While authentication is enabled, clients may only list their own stars (403 otherwise).
+/// Messages no longer in the history of their room are left out.
+pub async fn get_starred_messages(
+    State(state): State<Arc<AppState>>,
+    Path(client_id): Path<String>,
+    identity: Option<Extension<ClientIdentity>>,
+) -> Result<Response, StatusCode> {
+    let usecase = state
+        .star_messages_usecase
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let client_id = ClientId::new(client_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    if let Some(Extension(identity)) = &identity
+        && identity.client_id() != &client_id
+    {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    match usecase.list(&client_id).await {
+        Ok(messages) => {
+            // Domain Model から DTO への変換
+            let starred = StarredMessagesDto {
+                client_id: client_id.into_string(),
+                messages: messages.into_iter().map(Into::into).collect(),
+            };
+            Ok(([(CACHE_CONTROL, NO_STORE)], Json(starred)).into_response())
+        }
+        Err(e) => {
+            tracing::warn!("Failed to list the stars of '{}': {:?}", client_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Erase the participant record and all messages of a client in every room (GDPR-style data
+/// deletion)
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if erasure is not enabled). The client's
+/// open connections are closed first; connected participants of each room receive a
+/// `message-deleted` event for each message deleted from it.
+pub async fn erase_client_data(
+    State(state): State<Arc<AppState>>,
+    Path(client_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let usecase = state
+        .erase_client_data_usecase
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    authorize_admin(&state, &headers)?;
+    let client_id = ClientId::new(client_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    // Close the client's connections so that they leave their rooms before the data is erased
+    let rooms = usecase.connected_rooms(&client_id).await.map_err(|e| {
+        tracing::error!("Failed to find the rooms of '{}': {}", client_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    end_sessions(&state, &client_id, &rooms, SessionEnd::Replaced).await;
+
+    let render = |seq: SequenceNumber| {
+        serde_json::to_string(&MessageDeletedMessage {
+            r#type: MessageType::MessageDeleted,
+            seq: seq.value(),
+        })
+        .unwrap()
+    };
+    match usecase.execute(&client_id, render).await {
+        Ok(erased) => {
+            tracing::info!(
+                "Erased the data of '{}' ({} messages in {} rooms)",
+                client_id,
+                erased.iter().map(|room| room.seqs.len()).sum::<usize>(),
+                erased.len()
+            );
+            let erased = ErasedClientDataDto {
+                client_id: client_id.into_string(),
+                rooms: erased.into_iter().map(Into::into).collect(),
+            };
+            Ok(([(CACHE_CONTROL, NO_STORE)], Json(erased)).into_response())
+        }
+        Err(e) => {
+            tracing::error!("Failed to erase the data of '{}': {}", client_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Check that the request carries `Authorization: Bearer <admin token>`
+///
+/// Responds with 404 if no admin token is configured and 401 if the token does not match.
+pub(super) fn authorize_admin(state: &AppState, headers: &HeaderMap) -> Result<(), StatusCode> {
+    let admin_token = state.admin_token.as_ref().ok_or(StatusCode::NOT_FOUND)?;
+    let bearer = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if !bearer.is_some_and(|bearer| secrets_match(admin_token, bearer)) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(())
+}
+
+/// Get the cluster topology (404 if the server is not part of a cluster)
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if no admin token is configured), since
+/// it exposes the addresses of the nodes.
+pub async fn get_cluster(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let membership = state.cluster.as_ref().ok_or(StatusCode::NOT_FOUND)?;
+    authorize_admin(&state, &headers)?;
+    let membership = membership.lock().await;
+    let nodes = membership.nodes(Instant::now());
+
+    let total_participants = nodes
+        .iter()
+        .filter(|view| view.status != NodeStatus::Dead)
+        .map(|view| view.node.participant_count)
+        .sum();
+    let cluster = ClusterDto {
+        node_id: membership.node_id().to_string(),
+        total_participants,
+        nodes: nodes
+            .into_iter()
+            .map(|view| ClusterNodeDto {
+                node_id: view.node.node_id,
+                gossip_addr: view.node.gossip_addr,
+                http_addr: view.node.http_addr,
+                participant_count: view.node.participant_count,
+                status: view.status.as_str().to_string(),
+                last_seen_ms_ago: view.last_seen.as_millis() as u64,
+            })
+            .collect(),
+    };
+
+    Ok(([(CACHE_CONTROL, NO_STORE)], Json(cluster)).into_response())
+}
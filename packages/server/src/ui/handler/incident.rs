@@ -0,0 +1,165 @@
+//! Incident response (room freeze and disconnect-all) endpoint handlers.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header::CACHE_CONTROL},
+    response::{IntoResponse, Response},
+};
+
+use super::http::authorize_admin;
+use crate::{
+    domain::ClientId,
+    infrastructure::dto::{
+        http::{DisconnectRoomRequestDto, DisconnectedRoomDto, RoomFreezeDto},
+        websocket::{DisconnectedMessage, MessageType, RoomFreezeMessage},
+    },
+    ui::{
+        http_cache::NO_STORE,
+        session::{SessionEnd, TAKEOVER_TIMEOUT},
+        state::AppState,
+    },
+    usecase::{JoinRoomError, MAX_DISCONNECT_REASON_CHARS, RespondToIncidentUseCase, RoomUseCases},
+};
+
+/// Freeze a room: messages sent to it are rejected with `room_frozen` until it is unfrozen
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if no admin token is configured or the
+/// room does not exist). Connected participants receive a `room-frozen` event.
+pub async fn freeze_room(
+    State(state): State<Arc<AppState>>,
+    Path(room_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    set_frozen(&state, &headers, room_id, true).await
+}
+
+/// Unfreeze a room so that it accepts messages again
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if no admin token is configured or the
+/// room does not exist). Connected participants receive a `room-unfrozen` event.
+pub async fn unfreeze_room(
+    State(state): State<Arc<AppState>>,
+    Path(room_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    set_frozen(&state, &headers, room_id, false).await
+}
+
+/// Close the connections of every participant of a room, telling them why
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if no admin token is configured or the
+/// room does not exist, 400 if the reason is empty or too long). Participants receive a
+/// `disconnected` event with the reason before their connection is closed with
+/// `DISCONNECTED_CLOSE_CODE`; they may connect again, so freeze the room to also stop messages.
+pub async fn disconnect_room(
+    State(state): State<Arc<AppState>>,
+    Path(room_id): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<DisconnectRoomRequestDto>,
+) -> Result<Response, StatusCode> {
+    let (room, usecase) = incident_usecase(&state, &headers, &room_id).await?;
+    let reason = request.reason.trim();
+    if reason.is_empty() || reason.chars().count() > MAX_DISCONNECT_REASON_CHARS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let notice = serde_json::to_string(&DisconnectedMessage {
+        r#type: MessageType::Disconnected,
+        reason: reason.to_string(),
+    })
+    .unwrap();
+    let clients = usecase.disconnect_all(&notice).await;
+    let closed = end_sessions(&state, &room, &clients).await;
+    tracing::warn!(
+        "Disconnected everyone in room {} by an admin ({} connections closed): {}",
+        room.room_id,
+        closed,
+        reason
+    );
+
+    let disconnected = DisconnectedRoomDto {
+        room_id: room.room_id.as_str().to_string(),
+        reason: reason.to_string(),
+        closed_connections: closed,
+    };
+    Ok(([(CACHE_CONTROL, NO_STORE)], Json(disconnected)).into_response())
+}
+
+async fn set_frozen(
+    state: &AppState,
+    headers: &HeaderMap,
+    room_id: String,
+    frozen: bool,
+) -> Result<Response, StatusCode> {
+    let (room, usecase) = incident_usecase(state, headers, &room_id).await?;
+    let notice = serde_json::to_string(&RoomFreezeMessage {
+        r#type: if frozen {
+            MessageType::RoomFrozen
+        } else {
+            MessageType::RoomUnfrozen
+        },
+        room_id: room.room_id.as_str().to_string(),
+    })
+    .unwrap();
+    usecase.set_frozen(frozen, &notice).await.map_err(|e| {
+        tracing::error!("Failed to freeze room {}: {}", room.room_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    tracing::warn!(
+        "Room {} {} by an admin",
+        room.room_id,
+        if frozen { "frozen" } else { "unfrozen" }
+    );
+
+    let freeze = RoomFreezeDto {
+        room_id: room.room_id.as_str().to_string(),
+        frozen,
+    };
+    Ok(([(CACHE_CONTROL, NO_STORE)], Json(freeze)).into_response())
+}
+
+/// Get the room and its use case after checking the admin token
+async fn incident_usecase(
+    state: &AppState,
+    headers: &HeaderMap,
+    room_id: &str,
+) -> Result<(Arc<RoomUseCases>, Arc<RespondToIncidentUseCase>), StatusCode> {
+    authorize_admin(state, headers)?;
+    let room = state.join_room(Some(room_id)).await.map_err(|e| match e {
+        JoinRoomError::RepositoryError => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::NOT_FOUND,
+    })?;
+    let usecase = room
+        .respond_to_incident
+        .clone()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok((room, usecase))
+}
+
+/// End the sessions of the clients in the room, wait for them to leave, and return how many
+/// ended
+async fn end_sessions(state: &AppState, room: &RoomUseCases, clients: &[ClientId]) -> usize {
+    let closing: Vec<_> = clients
+        .iter()
+        .filter_map(|client_id| {
+            let key = state.session_key(room, client_id.as_str());
+            state.sessions.end(&key, SessionEnd::Disconnected)
+        })
+        .collect();
+    let ended = closing.len();
+    let deadline = tokio::time::Instant::now() + TAKEOVER_TIMEOUT;
+    for closed in closing {
+        if tokio::time::timeout_at(deadline, closed).await.is_err() {
+            tracing::warn!(
+                "Connections of room {} did not close within {:?}",
+                room.room_id,
+                TAKEOVER_TIMEOUT
+            );
+            break;
+        }
+    }
+    ended
+}
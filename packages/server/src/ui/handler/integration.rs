@@ -0,0 +1,213 @@
+//! Per-room integration (webhooks, bridge settings and join approval) endpoint handlers.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header::CACHE_CONTROL},
+    response::{IntoResponse, Response},
+};
+
+use engawa_shared::time::{get_jst_timestamp, timestamp_to_jst_rfc3339};
+
+use super::http::authorize_admin;
+use crate::{
+    domain::{ClientId, IntegrationKind, RoomId, Timestamp},
+    infrastructure::dto::http::{
+        IntegrationDto, IntegrationListDto, IntegrationSettingsDto, PendingJoinDto,
+        PendingJoinsDto, ResolveJoinRequestDto,
+    },
+    ui::{approval::JoinDecision, http_cache::NO_STORE, state::AppState},
+    usecase::{ManageIntegrationsError, ManageIntegrationsUseCase},
+};
+
+/// List the integrations of a room
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if integrations are not enabled or the
+/// room does not exist).
+pub async fn list_integrations(
+    State(state): State<Arc<AppState>>,
+    Path(room_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let usecase = integrations_usecase(&state, &headers)?;
+    let integrations = usecase.list(&room_id).await.map_err(error_status)?;
+    let integrations = IntegrationListDto {
+        integrations: integrations.into_iter().map(Into::into).collect(),
+    };
+    Ok(([(CACHE_CONTROL, NO_STORE)], Json(integrations)).into_response())
+}
+
+/// Add an integration to a room
+///
+/// Responds with 201 and the integration (including the generated token of an incoming webhook
+/// created without one), 400 if the settings are invalid, and 409 if the incoming webhook token
+/// is used by another integration.
+pub async fn create_integration(
+    State(state): State<Arc<AppState>>,
+    Path(room_id): Path<String>,
+    headers: HeaderMap,
+    Json(settings): Json<IntegrationSettingsDto>,
+) -> Result<Response, StatusCode> {
+    let usecase = integrations_usecase(&state, &headers)?;
+    let kind = integration_kind(settings)?;
+    let integration = usecase
+        .create(&room_id, kind, Timestamp::new(get_jst_timestamp()))
+        .await
+        .map_err(error_status)?;
+    tracing::info!(
+        "Added integration {} to room '{}'",
+        integration.id,
+        integration.room_id
+    );
+    let integration = IntegrationDto::from(integration);
+    Ok((
+        StatusCode::CREATED,
+        [(CACHE_CONTROL, NO_STORE)],
+        Json(integration),
+    )
+        .into_response())
+}
+
+/// Get an integration of a room
+pub async fn get_integration(
+    State(state): State<Arc<AppState>>,
+    Path((room_id, id)): Path<(String, u64)>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let usecase = integrations_usecase(&state, &headers)?;
+    let integration = usecase.get(&room_id, id).await.map_err(error_status)?;
+    let integration = IntegrationDto::from(integration);
+    Ok(([(CACHE_CONTROL, NO_STORE)], Json(integration)).into_response())
+}
+
+/// Replace the settings of an integration of a room
+///
+/// The type of the integration may change; an incoming webhook updated without a token gets a
+/// new generated one.
+pub async fn update_integration(
+    State(state): State<Arc<AppState>>,
+    Path((room_id, id)): Path<(String, u64)>,
+    headers: HeaderMap,
+    Json(settings): Json<IntegrationSettingsDto>,
+) -> Result<Response, StatusCode> {
+    let usecase = integrations_usecase(&state, &headers)?;
+    let kind = integration_kind(settings)?;
+    let integration = usecase
+        .update(&room_id, id, kind)
+        .await
+        .map_err(error_status)?;
+    tracing::info!("Updated integration {} of room '{}'", id, room_id);
+    let integration = IntegrationDto::from(integration);
+    Ok(([(CACHE_CONTROL, NO_STORE)], Json(integration)).into_response())
+}
+
+/// Remove an integration from a room (responds with 204)
+pub async fn delete_integration(
+    State(state): State<Arc<AppState>>,
+    Path((room_id, id)): Path<(String, u64)>,
+    headers: HeaderMap,
+) -> Result<StatusCode, StatusCode> {
+    let usecase = integrations_usecase(&state, &headers)?;
+    usecase.delete(&room_id, id).await.map_err(error_status)?;
+    tracing::info!("Removed integration {} from room '{}'", id, room_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the clients waiting for approval to join a room, oldest request first
+pub async fn get_pending_joins(
+    State(state): State<Arc<AppState>>,
+    Path(room_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let room_id = existing_room(&state, &headers, room_id).await?;
+    let pending = state
+        .pending_joins
+        .list(&room_id)
+        .into_iter()
+        .map(|(client_id, requested_at)| PendingJoinDto {
+            client_id: client_id.into_string(),
+            requested_at: timestamp_to_jst_rfc3339(requested_at.value()),
+        })
+        .collect();
+    let pending = PendingJoinsDto {
+        room_id: room_id.as_str().to_string(),
+        pending,
+    };
+    Ok(([(CACHE_CONTROL, NO_STORE)], Json(pending)).into_response())
+}
+
+/// Approve or reject a client waiting to join a room (responds with 204)
+///
+/// An approved client joins the room on its connection; a rejected client's connection is
+/// closed. Responds with 404 if the client is not waiting to join the room.
+pub async fn resolve_pending_join(
+    State(state): State<Arc<AppState>>,
+    Path((room_id, client_id)): Path<(String, String)>,
+    headers: HeaderMap,
+    Json(request): Json<ResolveJoinRequestDto>,
+) -> Result<StatusCode, StatusCode> {
+    let room_id = existing_room(&state, &headers, room_id).await?;
+    let client_id = ClientId::new(client_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let decision = JoinDecision::from(request.action);
+    if !state.pending_joins.decide(&room_id, &client_id, decision) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    tracing::info!(
+        "Resolved the join request of '{}' to room '{}' ({:?})",
+        client_id,
+        room_id,
+        decision
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Check the admin token and that the room exists (404 otherwise)
+async fn existing_room(
+    state: &AppState,
+    headers: &HeaderMap,
+    room_id: String,
+) -> Result<RoomId, StatusCode> {
+    let usecase = integrations_usecase(state, headers)?;
+    usecase.list(&room_id).await.map_err(error_status)?;
+    RoomId::new(room_id).map_err(|_| StatusCode::NOT_FOUND)
+}
+
+/// Get the use case after checking the admin token
+fn integrations_usecase<'a>(
+    state: &'a AppState,
+    headers: &HeaderMap,
+) -> Result<&'a ManageIntegrationsUseCase, StatusCode> {
+    let usecase = state
+        .manage_integrations_usecase
+        .as_deref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    authorize_admin(state, headers)?;
+    Ok(usecase)
+}
+
+/// Convert the request settings to the domain model (400 on an unknown bridge, an empty or
+/// too long welcome message or announcement, or an invalid announcement schedule)
+fn integration_kind(settings: IntegrationSettingsDto) -> Result<IntegrationKind, StatusCode> {
+    IntegrationKind::try_from(settings).map_err(|e| {
+        tracing::warn!("Rejecting integration settings: {}", e);
+        StatusCode::BAD_REQUEST
+    })
+}
+
+fn error_status(error: ManageIntegrationsError) -> StatusCode {
+    match error {
+        ManageIntegrationsError::RoomNotFound | ManageIntegrationsError::IntegrationNotFound => {
+            StatusCode::NOT_FOUND
+        }
+        ManageIntegrationsError::InvalidUrl
+        | ManageIntegrationsError::InvalidToken
+        | ManageIntegrationsError::InvalidTarget => StatusCode::BAD_REQUEST,
+        ManageIntegrationsError::TokenInUse => StatusCode::CONFLICT,
+        ManageIntegrationsError::RepositoryError(e) => {
+            tracing::error!("Failed to manage integrations: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Network allow/deny list endpoint handlers.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode, header::CACHE_CONTROL},
+    response::{IntoResponse, Response},
+};
+
+use super::http::authorize_admin;
+use crate::{
+    infrastructure::dto::http::IpRulesDto,
+    ui::{
+        client_ip::IpNetwork,
+        http_cache::NO_STORE,
+        ip_filter::{IpFilter, IpRules},
+        state::AppState,
+    },
+};
+
+/// Get the networks allowed and denied
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if no admin token is configured).
+pub async fn get_ip_rules(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    authorize_admin(&state, &headers)?;
+    Ok(ip_rules(&state.ip_filter))
+}
+
+/// Replace the networks allowed and denied (CIDR notation or bare addresses)
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if no admin token is configured).
+/// Responds with `400` and keeps the current lists if any entry is not a valid network.
+/// Open connections from newly denied networks are not closed.
+pub async fn set_ip_rules(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<IpRulesDto>,
+) -> Result<Response, StatusCode> {
+    authorize_admin(&state, &headers)?;
+
+    // DTO から UI 層の設定への変換
+    let parse = |networks: &[String]| {
+        networks
+            .iter()
+            .map(|network| network.parse::<IpNetwork>())
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let rules = match (parse(&request.allow), parse(&request.deny)) {
+        (Ok(allow), Ok(deny)) => IpRules { allow, deny },
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::warn!("Rejecting IP rules: {}", e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+    tracing::warn!(
+        "IP rules replaced by an admin: {} allowed and {} denied networks",
+        rules.allow.len(),
+        rules.deny.len()
+    );
+    state.ip_filter.replace(rules);
+    Ok(ip_rules(&state.ip_filter))
+}
+
+fn ip_rules(filter: &IpFilter) -> Response {
+    let rules = filter.rules();
+    let to_strings = |networks: Vec<IpNetwork>| networks.iter().map(ToString::to_string).collect();
+    let rules = IpRulesDto {
+        allow: to_strings(rules.allow),
+        deny: to_strings(rules.deny),
+    };
+    ([(CACHE_CONTROL, NO_STORE)], Json(rules)).into_response()
+}
@@ -1,10 +1,52 @@
 //! Handler modules for HTTP and WebSocket endpoints.
 
+pub mod auth;
+pub mod breakout;
+pub mod challenge;
 pub mod http;
+pub mod incident;
+pub mod integration;
+pub mod ip_rules;
+pub mod moderation;
+pub mod webhook;
 pub mod websocket;
 
+// Re-export authentication handlers
+pub use auth::login;
+
+// Re-export breakout handlers
+pub use breakout::{create_breakout, list_breakouts};
+
+// Re-export connect challenge handlers
+pub use challenge::{get_challenge_mode, get_connect_challenge, set_challenge_mode};
+
 // Re-export HTTP handlers
-pub use http::{debug_room_state, get_room_detail, get_rooms, health_check};
+pub use http::{
+    create_room, debug_room_state, erase_client_data, get_api_docs, get_cluster, get_metrics,
+    get_openapi, get_room_detail, get_room_detail_by_slug, get_room_messages, get_room_stats,
+    get_rooms, get_schema, get_starred_messages, health_check, search_room_messages,
+};
+
+// Re-export incident response handlers
+pub use incident::{disconnect_room, freeze_room, unfreeze_room};
+
+// Re-export integration handlers
+pub use integration::{
+    create_integration, delete_integration, get_integration, get_pending_joins, list_integrations,
+    resolve_pending_join, update_integration,
+};
+
+// Re-export IP rule handlers
+pub use ip_rules::{get_ip_rules, set_ip_rules};
+
+// Re-export moderation handlers
+pub use moderation::{
+    ban_client, get_moderation_queue, kick_client, report_message, resolve_report,
+};
+
+// Re-export webhook handlers
+pub use webhook::incoming_webhook;
 
 // Re-export WebSocket handlers
-pub use websocket::websocket_handler;
+pub use websocket::{ConnectQuery, websocket_handler};
+pub(crate) use websocket::{JoinedClient, connect, connect_error_message, on_text_frame};
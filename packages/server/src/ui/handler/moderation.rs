@@ -0,0 +1,261 @@
+//! Message report, moderation, and kick/ban endpoint handlers.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header::CACHE_CONTROL},
+    response::{IntoResponse, Response},
+};
+
+use engawa_shared::time::get_jst_timestamp;
+
+use super::http::authorize_admin;
+use crate::{
+    domain::{ClientId, RoomId, SequenceNumber, Timestamp},
+    infrastructure::dto::{
+        http::{
+            KickedClientDto, ModerationQueueDto, ReportAcceptedDto, ReportMessageRequestDto,
+            ReportResolutionDto, ResolveReportRequestDto,
+        },
+        websocket::{MessageDeletedMessage, MessageType},
+    },
+    ui::{
+        http_cache::NO_STORE,
+        session::{SessionEnd, TAKEOVER_TIMEOUT},
+        state::AppState,
+    },
+    usecase::{KickParticipantError, ModerationAction, ReportMessageError, ResolveReportError},
+};
+
+/// Report a message of a room for moderation (404 if moderation is not enabled)
+///
+/// Responds with `202 Accepted` and the report ID, `404` if the room does not exist or the
+/// message is no longer in the history, and `503` if the moderation queue is full.
+pub async fn report_message(
+    State(state): State<Arc<AppState>>,
+    Path((room_id, seq)): Path<(String, u64)>,
+    Json(request): Json<ReportMessageRequestDto>,
+) -> Result<Response, StatusCode> {
+    let usecase = state
+        .moderate_messages_usecase
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let reporter = ClientId::new(request.reporter).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let reason = request.reason.filter(|reason| !reason.trim().is_empty());
+
+    let now = Timestamp::new(get_jst_timestamp());
+    match usecase
+        .report(&room_id, SequenceNumber::new(seq), reporter, reason, now)
+        .await
+    {
+        Ok(report_id) => {
+            tracing::info!(
+                "Message {} of room {} reported ({})",
+                seq,
+                room_id,
+                report_id
+            );
+            let accepted = ReportAcceptedDto { report_id };
+            Ok((StatusCode::ACCEPTED, Json(accepted)).into_response())
+        }
+        Err(ReportMessageError::RoomNotFound | ReportMessageError::MessageNotFound) => {
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(ReportMessageError::ReasonTooLong) => Err(StatusCode::BAD_REQUEST),
+        Err(ReportMessageError::QueueFull) => {
+            tracing::warn!(
+                "Moderation queue is full; rejecting a report of message {}",
+                seq
+            );
+            Err(StatusCode::SERVICE_UNAVAILABLE)
+        }
+        Err(ReportMessageError::RepositoryError) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Get the reported messages awaiting moderation, with their surrounding messages
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if moderation is not enabled).
+pub async fn get_moderation_queue(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let usecase = state
+        .moderate_messages_usecase
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    authorize_admin(&state, &headers)?;
+
+    match usecase.queue().await {
+        // Domain Model から DTO への変換
+        Ok(items) => {
+            let queue = ModerationQueueDto {
+                items: items.into_iter().map(Into::into).collect(),
+            };
+            Ok(([(CACHE_CONTROL, NO_STORE)], Json(queue)).into_response())
+        }
+        Err(e) => {
+            tracing::error!("Failed to get the moderation queue: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Resolve the reports of a message by dismissing them, deleting the message, or banning its
+/// sender
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if moderation is not enabled or the
+/// report is unknown or already resolved). Connected participants of the message's room receive
+/// a `message-deleted` event for a deleted message; a banned sender's connections are closed in
+/// every room.
+pub async fn resolve_report(
+    State(state): State<Arc<AppState>>,
+    Path(report_id): Path<u64>,
+    headers: HeaderMap,
+    Json(request): Json<ResolveReportRequestDto>,
+) -> Result<Response, StatusCode> {
+    let usecase = state
+        .moderate_messages_usecase
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    authorize_admin(&state, &headers)?;
+
+    let render = |seq: SequenceNumber| {
+        serde_json::to_string(&MessageDeletedMessage {
+            r#type: MessageType::MessageDeleted,
+            seq: seq.value(),
+        })
+        .unwrap()
+    };
+    let resolution = match usecase
+        .resolve(report_id, ModerationAction::from(request.action), render)
+        .await
+    {
+        Ok(resolution) => resolution,
+        Err(ResolveReportError::ReportNotFound) => return Err(StatusCode::NOT_FOUND),
+        Err(ResolveReportError::RepositoryError(e)) => {
+            tracing::error!("Failed to resolve report {}: {}", report_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    tracing::info!(
+        "Resolved the reports of message {} of room {} from '{}' ({})",
+        resolution.seq,
+        resolution.room_id,
+        resolution.sender,
+        resolution.action.as_str()
+    );
+
+    // Close the banned client's connections (reconnections are rejected by the usecase)
+    end_sessions(
+        &state,
+        &resolution.sender,
+        &resolution.connected_rooms,
+        SessionEnd::Banned,
+    )
+    .await;
+
+    let resolution = ReportResolutionDto::from(resolution);
+    Ok(([(CACHE_CONTROL, NO_STORE)], Json(resolution)).into_response())
+}
+
+/// Close the connections of a client in every room
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if kicking is not enabled or the client
+/// has no open connection). The client may connect again; use [`ban_client`] to reject it.
+pub async fn kick_client(
+    State(state): State<Arc<AppState>>,
+    Path(client_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let usecase = state
+        .kick_participant_usecase
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    authorize_admin(&state, &headers)?;
+    let client_id = ClientId::new(client_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let rooms = match usecase.kick(&client_id).await {
+        Ok(rooms) => rooms,
+        Err(KickParticipantError::NotConnected) => return Err(StatusCode::NOT_FOUND),
+        Err(KickParticipantError::RepositoryError(e)) => {
+            tracing::error!("Failed to kick '{}': {}", client_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    // Bridged participants (e.g. Discord users) have no connection to close
+    let closed = end_sessions(&state, &client_id, &rooms, SessionEnd::Kicked).await;
+    if closed == 0 {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    tracing::info!("Kicked '{}' ({} connections closed)", client_id, closed);
+
+    let kicked = KickedClientDto {
+        client_id: client_id.into_string(),
+        banned: false,
+        closed_connections: closed,
+    };
+    Ok(([(CACHE_CONTROL, NO_STORE)], Json(kicked)).into_response())
+}
+
+/// Reject future connections of a client and close its open connections
+///
+/// Requires `Authorization: Bearer <admin token>` (404 if banning is not enabled). Clients can
+/// be banned before they connect; the ban lasts until the server restarts.
+pub async fn ban_client(
+    State(state): State<Arc<AppState>>,
+    Path(client_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let usecase = state
+        .kick_participant_usecase
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    authorize_admin(&state, &headers)?;
+    let client_id = ClientId::new(client_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let rooms = usecase.ban(&client_id).await.map_err(|e| {
+        tracing::error!("Failed to ban '{}': {}", client_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let closed = end_sessions(&state, &client_id, &rooms, SessionEnd::Banned).await;
+    tracing::info!("Banned '{}' ({} connections closed)", client_id, closed);
+
+    let banned = KickedClientDto {
+        client_id: client_id.into_string(),
+        banned: true,
+        closed_connections: closed,
+    };
+    Ok(([(CACHE_CONTROL, NO_STORE)], Json(banned)).into_response())
+}
+
+/// End the sessions of a client in `rooms`, wait for them to leave, and return how many ended
+pub(super) async fn end_sessions(
+    state: &AppState,
+    client_id: &ClientId,
+    rooms: &[RoomId],
+    reason: SessionEnd,
+) -> usize {
+    let closing: Vec<_> = rooms
+        .iter()
+        .filter_map(|room_id| {
+            let key = state.room_session_key(room_id, client_id.as_str());
+            state.sessions.end(&key, reason)
+        })
+        .collect();
+    let ended = closing.len();
+    let deadline = tokio::time::Instant::now() + TAKEOVER_TIMEOUT;
+    for closed in closing {
+        if tokio::time::timeout_at(deadline, closed).await.is_err() {
+            tracing::warn!(
+                "Connection of '{}' did not close within {:?}",
+                client_id,
+                TAKEOVER_TIMEOUT
+            );
+            break;
+        }
+    }
+    ended
+}
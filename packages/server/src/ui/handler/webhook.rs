@@ -0,0 +1,164 @@
+//! Incoming webhook endpoint handlers.
+
+use std::sync::Arc;
+
+use axum::{
+    Form,
+    body::Bytes,
+    extract::{FromRequest, Path, Request, State},
+    http::{StatusCode, header::CONTENT_TYPE},
+};
+
+use crate::{
+    domain::{ClientId, MessageContent},
+    infrastructure::{
+        auth::secrets_match,
+        dto::{
+            webhook::{SlackWebhookForm, SlackWebhookPayload},
+            websocket::{ChatMessage, MessageType},
+        },
+    },
+    ui::state::AppState,
+    usecase::{RoomUseCases, SendMessageError},
+};
+use engawa_shared::time::get_jst_timestamp;
+
+/// Sender name used when the payload does not specify `username`
+const DEFAULT_WEBHOOK_SENDER: &str = "webhook";
+
+/// Post a message through the incoming webhook (Slack-compatible payload)
+///
+/// The global token posts into the default room and the token of a room's incoming webhook
+/// integration into that room. Accepts a JSON body (regardless of `Content-Type`, as Slack
+/// does) or a form-encoded `payload=<json>` body. Responds like Slack: `ok` on success,
+/// `no_text` / `invalid_payload` (400) on bad payloads, and `404` for unknown tokens.
+pub async fn incoming_webhook(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+    request: Request,
+) -> (StatusCode, &'static str) {
+    let Some(room) = target_room(&state, &token).await else {
+        return (StatusCode::NOT_FOUND, "no_service");
+    };
+
+    let Some(payload) = read_payload(request, &state).await else {
+        return (StatusCode::BAD_REQUEST, "invalid_payload");
+    };
+
+    let Some(text) = payload.message_text() else {
+        return (StatusCode::BAD_REQUEST, "no_text");
+    };
+    let sender = payload
+        .username
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_WEBHOOK_SENDER.to_string());
+    let text = state.sanitize_profile.apply(&text).into_owned();
+
+    let (Ok(client_id), Ok(content)) = (
+        ClientId::try_from(sender.clone()),
+        MessageContent::try_from(text.clone()),
+    ) else {
+        return (StatusCode::BAD_REQUEST, "invalid_payload");
+    };
+
+    let message = ChatMessage {
+        r#type: MessageType::Chat,
+        client_id: sender,
+        content: text,
+        timestamp: get_jst_timestamp(),
+        seq: None,
+        poll: None,
+        forwarded_from: None,
+    };
+    tracing::info!(
+        "Posting message from incoming webhook to room '{}' as '{}': {}",
+        room.room_id,
+        message.client_id,
+        message.content
+    );
+
+    match room
+        .send_message
+        .execute(client_id, content, move |seq| {
+            message.to_json_with_seq(seq.value())
+        })
+        .await
+    {
+        Ok(_) => (StatusCode::OK, "ok"),
+        Err(SendMessageError::RateLimited { .. }) => {
+            (StatusCode::TOO_MANY_REQUESTS, "rate_limited")
+        }
+        Err(SendMessageError::MessageCapacityExceeded { .. }) => {
+            (StatusCode::SERVICE_UNAVAILABLE, "room_history_full")
+        }
+        Err(SendMessageError::RoomFrozen) => (StatusCode::CONFLICT, "room_frozen"),
+        Err(SendMessageError::MessageTooLong { .. }) => {
+            (StatusCode::PAYLOAD_TOO_LARGE, "message_too_long")
+        }
+        Err(e) => {
+            tracing::warn!("Failed to post webhook message: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "internal_error")
+        }
+    }
+}
+
+/// Find the room a token posts into (`None` for unknown tokens)
+///
+/// Tokens are compared on the whole string; without the global token and integrations, every
+/// token is unknown.
+async fn target_room(state: &AppState, token: &str) -> Option<Arc<RoomUseCases>> {
+    if state
+        .incoming_webhook_token
+        .as_deref()
+        .is_some_and(|expected| secrets_match(expected, token))
+    {
+        return Some(state.default_room.clone());
+    }
+    let usecase = state.manage_integrations_usecase.as_ref()?;
+    let room_id = match usecase.resolve_incoming_webhook(token).await {
+        Ok(room_id) => room_id?,
+        Err(e) => {
+            tracing::error!("Failed to look up the incoming webhook token: {}", e);
+            return None;
+        }
+    };
+    match state.join_room(Some(room_id.as_str())).await {
+        Ok(room) => Some(room),
+        Err(e) => {
+            tracing::warn!(
+                "Room '{}' of the incoming webhook is unavailable: {:?}",
+                room_id,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Read the payload from a JSON or form-encoded body
+///
+/// Bodies that look like JSON are parsed as JSON even when sent as a form, since tools such as
+/// `curl -d '{...}'` post JSON with the form content type.
+async fn read_payload(request: Request, state: &Arc<AppState>) -> Option<SlackWebhookPayload> {
+    let (parts, body) = request.into_parts();
+    let body = Bytes::from_request(Request::from_parts(parts.clone(), body), state)
+        .await
+        .ok()?;
+    if body.trim_ascii_start().starts_with(b"{") {
+        return serde_json::from_slice(&body).ok();
+    }
+
+    let is_form = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/x-www-form-urlencoded"));
+    if !is_form {
+        return None;
+    }
+    let Form(form) =
+        Form::<SlackWebhookForm>::from_request(Request::from_parts(parts, body.into()), state)
+            .await
+            .ok()?;
+    serde_json::from_str(&form.payload).ok()
+}
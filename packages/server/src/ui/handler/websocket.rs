@@ -1,320 +1,1091 @@
 //! WebSocket connection handlers.
 
-use std::sync::Arc;
+use std::{net::IpAddr, sync::Arc, time::Instant};
 
 use axum::{
-    extract::{
-        Query, State,
-        ws::{Message, WebSocket, WebSocketUpgrade},
-    },
-    http::StatusCode,
-    response::IntoResponse,
+    Extension, Json,
+    extract::{Query, RawQuery, State, ws::WebSocketUpgrade},
+    http::{HeaderMap, StatusCode, header::LOCATION},
+    response::{IntoResponse, Response},
 };
-use futures_util::{sink::SinkExt, stream::StreamExt};
 use tokio::sync::mpsc;
+use tracing::Instrument;
+
+use engawa_shared::time::get_jst_timestamp;
 
 use crate::{
-    domain::{ClientId, MessageContent, Timestamp},
-    infrastructure::dto::websocket::{
-        ChatMessage, MessageType, ParticipantJoinedMessage, ParticipantLeftMessage,
-        RoomConnectedMessage,
+    domain::{
+        Activity, ClientId, ClientIdentity, DisplayName, GUEST_ID_PREFIX, GuestIdFactory,
+        MessageContent, PollOption, PusherChannel, SequenceNumber, Timestamp, ValueObjectError,
+    },
+    infrastructure::{
+        dto::websocket::{
+            ChatMessage, ClientMessage, ErrorMessage, MessageType, PollInfo, PollOptionInfo,
+            RoomInfo, RoomListMessage, StarUpdatedMessage, TypingMessage,
+        },
+        error::InboundMessageError,
+        message_pusher::WebSocketMessagePusher,
+    },
+    ui::{
+        approval::wait_for_approval,
+        challenge::POW_HEADER,
+        client_ip::ClientIp,
+        config::DuplicatePolicy,
+        connection::Connection,
+        guest::MAX_GUEST_ID_ATTEMPTS,
+        presenter::websocket::{activity_updated, participant_renamed, poll_updated},
+        session::TAKEOVER_TIMEOUT,
+        state::AppState,
+    },
+    usecase::{
+        ConnectError, ForwardMessageError, GetRoomDetailError, JoinRoomError, MAX_STARS_PER_CLIENT,
+        MultiplexedConnection, RenameParticipantError, RoomUseCases, RoomsQuery, SendMessageError,
+        SetActivityError, StarMessageError, VotePollError,
     },
-    ui::state::AppState,
 };
-use engawa_shared::time::get_jst_timestamp;
 
 use serde::Deserialize;
 
 /// Query parameters for WebSocket connection
 #[derive(Debug, Deserialize)]
 pub struct ConnectQuery {
-    pub client_id: String,
+    /// Client ID; guests connect without one and are given one by the server
+    #[serde(default)]
+    pub client_id: Option<String>,
+    /// ID of the room to join (the default room if omitted)
+    #[serde(default)]
+    pub room_id: Option<String>,
+    /// Room key used to pick the owning node when clustering
+    #[serde(default)]
+    pub room: Option<String>,
+    /// Slug of the room to join, as an alternative to its ID (giving both is rejected)
+    #[serde(default)]
+    pub room_slug: Option<String>,
+    /// Resume token received in `room-connected` before reconnecting
+    #[serde(default)]
+    pub resume_token: Option<String>,
+    /// Sequence number of the latest message the client has received
+    #[serde(default)]
+    pub last_seq: Option<u64>,
+    /// Solution of the connect challenge (`<challenge>:<nonce>`) while it is enabled
+    #[serde(default)]
+    pub pow: Option<String>,
+    /// Name to show the participant as, instead of its client ID
+    #[serde(default)]
+    pub display_name: Option<String>,
 }
 
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
-    Query(query): Query<ConnectQuery>,
-) -> Result<impl IntoResponse, StatusCode> {
-    let client_id_str = query.client_id;
-
-    // Convert String -> ClientId (Domain Model)
-    let client_id = match ClientId::try_from(client_id_str.clone()) {
-        Ok(id) => id,
-        Err(_) => {
-            tracing::warn!("Invalid client_id format: '{}'", client_id_str);
+    ClientIp(client_ip): ClientIp,
+    Query(mut query): Query<ConnectQuery>,
+    RawQuery(raw_query): RawQuery,
+    headers: HeaderMap,
+    identity: Option<Extension<ClientIdentity>>,
+) -> Result<Response, StatusCode> {
+    // An authenticated client connects as the client ID it logged in with
+    if let Some(Extension(identity)) = &identity {
+        let authenticated = identity.client_id().as_str();
+        if query
+            .client_id
+            .as_deref()
+            .is_some_and(|requested| requested != authenticated)
+        {
+            tracing::warn!(
+                "Client logged in as '{}' asked for client_id '{}' from {}",
+                authenticated,
+                query.client_id.as_deref().unwrap_or_default(),
+                client_ip
+            );
+            return Err(StatusCode::FORBIDDEN);
+        }
+        query.client_id = Some(authenticated.to_string());
+    }
+    let client_id_str = query
+        .client_id
+        .clone()
+        .unwrap_or_else(|| "(guest)".to_string());
+
+    // Redirect to the node owning the room (the query is kept as is)
+    if let (Some(shards), Some(room)) = (&state.room_shards, &query.room)
+        && let Some(owner) = shards.remote_owner(room).await
+    {
+        tracing::info!(
+            "Redirecting '{}' to node '{}' owning room '{}'",
+            client_id_str,
+            owner.node_id,
+            room
+        );
+        let location = format!(
+            "{}://{}/ws?{}",
+            state.ws_scheme,
+            owner.http_addr,
+            raw_query.unwrap_or_default()
+        );
+        return Ok((StatusCode::TEMPORARY_REDIRECT, [(LOCATION, location)]).into_response());
+    }
+
+    // Require a solved proof-of-work from anonymous connections while the connect challenge is
+    // enabled (join floods); authenticated clients have already proven who they are
+    if identity.is_none() && state.connect_challenge.is_enabled() {
+        let solution = query.pow.as_deref().or_else(|| {
+            headers
+                .get(POW_HEADER)
+                .and_then(|value| value.to_str().ok())
+        });
+        let Some(solution) = solution else {
+            tracing::debug!(
+                "Connection of '{}' from {} has no connect challenge solution",
+                client_id_str,
+                client_ip
+            );
+            return Err(StatusCode::PRECONDITION_REQUIRED);
+        };
+        if let Err(e) = state.connect_challenge.verify(solution, Instant::now()) {
+            tracing::warn!(
+                "Rejecting connection of '{}' from {}: {}",
+                client_id_str,
+                client_ip,
+                e
+            );
+            return Err(StatusCode::FORBIDDEN);
+        }
+    }
+
+    // Convert String -> ClientId (Domain Model); guests are given one when connecting
+    let requested = match query.client_id.clone() {
+        Some(id) => match ClientId::try_from(id) {
+            Ok(id) if id.is_guest() => {
+                tracing::warn!(
+                    "client_id '{}' uses the prefix reserved for guests",
+                    client_id_str
+                );
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            Ok(id) => Some(id),
+            Err(_) => {
+                tracing::warn!("Invalid client_id format: '{}'", client_id_str);
+                return Err(StatusCode::BAD_REQUEST);
+            }
+        },
+        None if state.guests.allows_guests() => None,
+        None => {
+            tracing::warn!(
+                "Connection from {} has no client_id and guests are disabled",
+                client_ip
+            );
             return Err(StatusCode::BAD_REQUEST);
         }
     };
 
-    // Create a channel for this client to receive messages
-    let (tx, rx) = mpsc::unbounded_channel();
+    // The display name is set after joining, but a malformed one rejects the connection
+    if let Some(name) = &query.display_name
+        && let Err(e) = DisplayName::new(name.clone())
+    {
+        tracing::warn!("Invalid display_name for '{}': {}", client_id_str, e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
 
-    // Use ConnectParticipantUseCase to handle connection
-    // (register_client is called inside the UseCase)
-    let client_id_for_handle = client_id.clone();
-    match state
-        .connect_participant_usecase
-        .execute(client_id, tx)
-        .await
+    // Reject clients banned by an admin or a moderator
+    if let Some(client_id) = &requested
+        && state.is_banned(client_id).await.map_err(|e| {
+            tracing::error!("Failed to check the ban of '{}': {}", client_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+    {
+        tracing::warn!(
+            "Rejecting connection of banned client '{}' from {}",
+            client_id,
+            client_ip
+        );
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    // A room is picked either by ID or by slug, not both
+    if query.room_id.is_some() && query.room_slug.is_some() {
+        tracing::warn!(
+            "Both room_id and room_slug given by '{}'; rejecting connection from {}",
+            client_id_str,
+            client_ip
+        );
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    // Resolve the room the client asked for by slug
+    let room_id = match &query.room_slug {
+        Some(slug) => match state.get_room_detail_usecase.resolve_slug(slug).await {
+            Ok(room_id) => {
+                tracing::debug!("Room slug '{}' resolved to {}", slug, room_id);
+                Some(room_id.as_str().to_string())
+            }
+            Err(GetRoomDetailError::RoomNotFound) => {
+                tracing::warn!("No room with slug '{}' for '{}'", slug, client_id_str);
+                return Err(StatusCode::NOT_FOUND);
+            }
+            Err(GetRoomDetailError::RepositoryError) => {
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        },
+        None => query.room_id.clone(),
+    };
+
+    // Join the room the client asked for (the default room otherwise)
+    let room = match state.join_room(room_id.as_deref()).await {
+        Ok(room) => room,
+        Err(JoinRoomError::RoomNotFound) => {
+            tracing::warn!(
+                "No room with ID '{}' for '{}'",
+                room_id.as_deref().unwrap_or_default(),
+                client_id_str
+            );
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    // Only the members of a breakout who opted in may join it
+    if !state.admits(&room, requested.as_ref()).await {
+        tracing::warn!(
+            "'{}' is not a member of breakout {}; rejecting connection from {}",
+            client_id_str,
+            room.room_id,
+            client_ip
+        );
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    // Bound the number of rooms a client is in at the same time
+    if let Some(client_id) = &requested
+        && let Err(JoinRoomError::TooManyRooms { limit }) = state.enter_room(client_id, &room).await
     {
-        Ok(connected_at) => {
-            tracing::info!("Client '{}' connected and registered", client_id_str);
-            Ok(ws.on_upgrade(move |socket| {
-                handle_socket(
-                    socket,
-                    state,
-                    client_id_str,
-                    rx,
+        tracing::warn!(
+            "Client '{}' is already in {} rooms. Rejecting connection from {}.",
+            client_id_str,
+            limit,
+            client_ip
+        );
+        let error = ErrorMessage {
+            r#type: MessageType::Error,
+            code: "too_many_rooms".to_string(),
+            message: format!(
+                "A client may be in at most {} rooms at the same time",
+                limit
+            ),
+        };
+        return Ok((StatusCode::TOO_MANY_REQUESTS, Json(error)).into_response());
+    }
+
+    // Hold the client until a moderator approves it in a protected room
+    let requires_approval = match state.requires_approval(&room).await {
+        Ok(requires_approval) => requires_approval,
+        Err(e) => {
+            tracing::error!(
+                "Failed to check the integrations of {}: {}",
+                room.room_id,
+                e
+            );
+            // The client entered the room above but never connects to it
+            if let Some(client_id) = &requested {
+                state.leave_room(client_id, &room).await;
+            }
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    if requires_approval {
+        // Moderators cannot tell guests apart before they are given an ID
+        let Some(client_id) = requested else {
+            tracing::warn!(
+                "Guest from {} cannot join room {} requiring approval",
+                client_ip,
+                room.room_id
+            );
+            return Err(StatusCode::FORBIDDEN);
+        };
+        return Ok(ws
+            .on_upgrade(move |socket| async move {
+                let wait = wait_for_approval(state, room, client_id, socket, query, client_ip);
+                let _ = engawa_shared::task::spawn("ws-pending", wait).await;
+            })
+            .into_response());
+    }
+
+    match connect(&state, &room, requested, client_ip).await {
+        Ok(JoinedClient {
+            client_id,
+            connected:
+                MultiplexedConnection {
                     connected_at,
-                    client_id_for_handle,
-                )
-            }))
+                    joined,
+                },
+            outbox,
+            rx,
+        }) => {
+            tracing::info!(
+                "Client '{}' connected and registered from {}",
+                client_id,
+                client_ip
+            );
+            let connection = Connection::new(state, room, client_id, joined);
+            Ok(ws
+                .on_upgrade(move |socket| async move {
+                    let run = connection.run(socket, outbox, rx, query, connected_at);
+                    let _ = engawa_shared::task::spawn("ws-connection", run).await;
+                })
+                .into_response())
         }
-        Err(crate::usecase::ConnectError::DuplicateClientId(_)) => {
+        Err(ConnectError::DuplicateClientId(_)) => {
             tracing::warn!(
-                "Client with ID '{}' is already connected. Rejecting connection.",
-                client_id_str
+                "Client with ID '{}' is already connected. Rejecting connection from {}.",
+                client_id_str,
+                client_ip
             );
             Err(StatusCode::CONFLICT)
         }
-        Err(crate::usecase::ConnectError::RoomCapacityExceeded) => {
+        Err(e @ ConnectError::RoomCapacityExceeded { .. }) => {
             tracing::warn!(
                 "Room capacity exceeded. Cannot add participant '{}'",
                 client_id_str
             );
-            Err(StatusCode::SERVICE_UNAVAILABLE)
+            Ok((
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(connect_error_message(&e)),
+            )
+                .into_response())
+        }
+        Err(ConnectError::PersistFailed(reason)) => {
+            tracing::error!("Failed to add participant '{}': {}", client_id_str, reason);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
 
-/// Spawns a task that receives messages from the rx channel and pushes them to the WebSocket sender.
+/// Client added to a room, with the send queue of its connection
+pub(crate) struct JoinedClient {
+    /// Client of the connection (assigned by the server for guests)
+    pub client_id: ClientId,
+    /// When the client joined and whether this connection joined the room
+    pub connected: MultiplexedConnection,
+    /// Send queue of the connection (backfilled messages and errors)
+    pub outbox: PusherChannel,
+    /// Messages to write to the connection
+    pub rx: mpsc::UnboundedReceiver<Arc<str>>,
+}
+
+/// Add the client to the room according to the duplicate policy
 ///
-/// This function handles the outbound message flow: messages from other clients (via rx channel)
-/// are sent to this client's WebSocket connection.
+/// A client that does not join the room after all is no longer counted in it.
 ///
 /// # Arguments
 ///
-/// * `rx` - Channel receiver for messages from other clients
-/// * `sender` - WebSocket sink to send messages to this client
-///
-/// # Returns
+/// * `state` - Application state
+/// * `room` - Room the client joins
+/// * `requested` - Client ID the client asked for (`None` for guests)
+/// * `client_ip` - Address of the client (for logging)
+pub(crate) async fn connect(
+    state: &AppState,
+    room: &RoomUseCases,
+    requested: Option<ClientId>,
+    client_ip: IpAddr,
+) -> Result<JoinedClient, ConnectError> {
+    // Create a channel for this client to receive messages
+    let (tx, rx) = WebSocketMessagePusher::channel();
+    // Backfilled messages and errors go through the same channel so that the dedup window applies
+    let outbox = tx.clone();
+
+    // Use ConnectParticipantUseCase to handle connection
+    // (register_client is called inside the UseCase)
+    let connected = match requested.clone() {
+        None => connect_guest(room, tx).await,
+        // Add the connection to the participant if it is already in the room
+        Some(client_id) if state.duplicate_policy == DuplicatePolicy::Multiplex => room
+            .connect_participant
+            .execute_multiplexed(client_id.clone(), tx)
+            .await
+            .map(|connected| (client_id, connected)),
+        Some(client_id) => connect_or_take_over(state, room, client_id.clone(), tx, client_ip)
+            .await
+            .map(|connected_at| {
+                (
+                    client_id,
+                    MultiplexedConnection {
+                        connected_at,
+                        joined: true,
+                    },
+                )
+            }),
+    };
+
+    // The connection did not join the room after all
+    if connected.is_err()
+        && let Some(client_id) = &requested
+    {
+        state.leave_room(client_id, room).await;
+    }
+
+    connected.map(|(client_id, connected)| JoinedClient {
+        client_id,
+        connected,
+        outbox,
+        rx,
+    })
+}
+
+/// `error` message telling the client why it could not join the room
+pub(crate) fn connect_error_message(error: &ConnectError) -> ErrorMessage {
+    let (code, message) = match error {
+        ConnectError::DuplicateClientId(_) => (
+            "duplicate_client_id",
+            "A client with this ID is already connected".to_string(),
+        ),
+        ConnectError::RoomCapacityExceeded { capacity } => (
+            "room_full",
+            format!("The room is full (capacity: {})", capacity),
+        ),
+        ConnectError::PersistFailed(_) => ("internal_error", "Failed to join the room".to_string()),
+    };
+    ErrorMessage {
+        r#type: MessageType::Error,
+        code: code.to_string(),
+        message,
+    }
+}
+
+/// Add a guest to the room with a server-assigned client ID
 ///
-/// A `JoinHandle` for the spawned task
-fn pusher_loop(
-    mut rx: mpsc::UnboundedReceiver<String>,
-    mut sender: futures_util::stream::SplitSink<WebSocket, Message>,
-) -> tokio::task::JoinHandle<()> {
-    tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            // Send the message to this client
-            if sender.send(Message::Text(msg.into())).await.is_err() {
-                break;
+/// Another ID is drawn if the assigned one is taken by a connected guest.
+async fn connect_guest(
+    room: &RoomUseCases,
+    tx: PusherChannel,
+) -> Result<(ClientId, MultiplexedConnection), ConnectError> {
+    let mut result = Err(ConnectError::DuplicateClientId(GUEST_ID_PREFIX.to_string()));
+    for _ in 0..MAX_GUEST_ID_ATTEMPTS {
+        let Ok(client_id) = GuestIdFactory::generate() else {
+            continue;
+        };
+        match room
+            .connect_participant
+            .execute(client_id.clone(), tx.clone())
+            .await
+        {
+            Ok(connected_at) => {
+                let connected = MultiplexedConnection {
+                    connected_at,
+                    joined: true,
+                };
+                return Ok((client_id, connected));
             }
+            Err(e @ ConnectError::DuplicateClientId(_)) => result = Err(e),
+            Err(e) => return Err(e),
         }
-    })
+    }
+    result
 }
 
-async fn handle_socket(
-    socket: WebSocket,
-    state: Arc<AppState>,
-    client_id_str: String,
-    rx: mpsc::UnboundedReceiver<String>,
-    connected_at: Timestamp,
+/// Add the client to the room, replacing its previous session with the takeover policy
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `room` - Room the client joins
+/// * `client_id` - Client of the new connection
+/// * `tx` - Send queue of the new connection
+/// * `client_ip` - Address of the client (for logging)
+async fn connect_or_take_over(
+    state: &AppState,
+    room: &RoomUseCases,
     client_id: ClientId,
-) {
-    let (mut sender, mut receiver) = socket.split();
+    tx: PusherChannel,
+    client_ip: IpAddr,
+) -> Result<Timestamp, ConnectError> {
+    let client_id_str = client_id.as_str().to_string();
+    let mut connected = room
+        .connect_participant
+        .execute(client_id.clone(), tx.clone())
+        .await;
 
-    // Send current room participants to the newly connected client
+    // Replace the previous session of the client instead of rejecting the connection
+    if matches!(connected, Err(ConnectError::DuplicateClientId(_)))
+        && state.duplicate_policy == DuplicatePolicy::Takeover
+        && let Some(closed) = state
+            .sessions
+            .replace(&state.session_key(room, &client_id_str))
     {
-        // Use ConnectParticipantUseCase to build participant list
-        let participants = state
-            .connect_participant_usecase
-            .build_participant_list()
-            .await;
-
-        // Domain Model から DTO への変換
-        let participant_infos: Vec<crate::infrastructure::dto::websocket::ParticipantInfo> =
-            participants
-                .into_iter()
-                .map(|p| crate::infrastructure::dto::websocket::ParticipantInfo {
-                    client_id: p.id.as_str().to_string(),
-                    connected_at: p.connected_at.value(),
-                })
-                .collect();
-
-        let room_msg = RoomConnectedMessage {
-            r#type: MessageType::RoomConnected,
-            participants: participant_infos,
-        };
-
-        let room_json = serde_json::to_string(&room_msg).unwrap();
-        if let Err(e) = sender.send(Message::Text(room_json.into())).await {
-            tracing::error!(
-                "Failed to send room connected to '{}': {}",
+        tracing::info!(
+            "Client '{}' is already connected; replacing the previous session with the connection from {}",
+            client_id_str,
+            client_ip
+        );
+        // Wait for the previous session to leave the room
+        if tokio::time::timeout(TAKEOVER_TIMEOUT, closed)
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                "Previous session of '{}' did not close in {:?}",
                 client_id_str,
-                e
+                TAKEOVER_TIMEOUT
             );
-            return;
         }
-        tracing::info!("Sent room connected list to '{}'", client_id_str);
+        connected = room.connect_participant.execute(client_id, tx).await;
     }
 
-    // Broadcast participant-joined to all other clients
-    {
-        let joined_msg = ParticipantJoinedMessage {
-            r#type: MessageType::ParticipantJoined,
-            client_id: client_id_str.clone(),
-            connected_at: connected_at.value(),
-        };
+    connected
+}
 
-        let joined_json = serde_json::to_string(&joined_msg).unwrap();
-        if let Err(e) = state
-            .connect_participant_usecase
-            .broadcast_participant_joined(&client_id, &joined_json)
-            .await
-        {
-            tracing::warn!("Failed to broadcast participant-joined: {}", e);
-        } else {
-            tracing::info!("Broadcasted participant-joined for '{}'", client_id_str);
+/// Handle a text frame received from the client
+///
+/// Each frame is handled in its own `message` span, followed through the sequencer. Invalid
+/// frames are rejected with an `error` message.
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `room` - Room of the connection (messages are sent to its participants)
+/// * `client_id` - Client of the connection (guests may be restricted from posting)
+/// * `room_id` - Room of the connection (for backfill requests)
+/// * `text` - Text frame received from the client
+/// * `outbox` - Channel to the client (for backfilled messages and errors)
+pub(crate) async fn on_text_frame(
+    state: &AppState,
+    room: &RoomUseCases,
+    client_id: &ClientId,
+    room_id: Option<&str>,
+    text: &str,
+    outbox: &mpsc::UnboundedSender<Arc<str>>,
+) {
+    let span = tracing::info_span!(
+        "message",
+        room_id = room_id.unwrap_or_default(),
+        client_id = %client_id,
+        message_id = tracing::field::Empty,
+    );
+    let handled = handle_text_frame(state, room, client_id, text, room_id, outbox)
+        .instrument(span)
+        .await;
+    // Reject invalid messages with a structured error
+    if let Err(e) = handled {
+        tracing::warn!("Rejected message from '{}': {}", client_id, e);
+        let error = serde_json::to_string(&ErrorMessage::from(&e)).unwrap();
+        let _ = outbox.send(error.into());
+    }
+}
+
+/// Parse a text frame received from the client and handle it
+///
+/// Runs inside the message span. Invalid frames are returned as an error so that the caller
+/// can reply with an `error` message.
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `room` - Room of the connection (messages are sent to its participants)
+/// * `sender` - Client of the connection (guests may be restricted from posting)
+/// * `text` - Text frame received from the client
+/// * `room_id` - Room of the connection (for backfill requests)
+/// * `outbox` - Channel to the client (for backfilled messages and room lists)
+async fn handle_text_frame(
+    state: &AppState,
+    room: &RoomUseCases,
+    sender: &ClientId,
+    text: &str,
+    room_id: Option<&str>,
+    outbox: &mpsc::UnboundedSender<Arc<str>>,
+) -> Result<(), InboundMessageError> {
+    let message = tracing::info_span!("parse").in_scope(|| ClientMessage::parse(text))?;
+    match message {
+        // Send the messages the client missed while disconnected
+        ClientMessage::BackfillRequest { since_seq } => {
+            if let Some(room_id) = room_id {
+                backfill(state, room_id, since_seq, outbox).await;
+            }
+            Ok(())
+        }
+        ClientMessage::ListRooms => {
+            list_rooms(state, outbox).await;
+            Ok(())
         }
+        ClientMessage::TypingStarted => {
+            state.guests.check_typing(sender)?;
+            relay_typing(room, sender, true).await;
+            Ok(())
+        }
+        ClientMessage::TypingStopped => {
+            state.guests.check_typing(sender)?;
+            relay_typing(room, sender, false).await;
+            Ok(())
+        }
+        // The sender is the client of the connection, whatever `client_id` the payload names
+        ClientMessage::Chat {
+            content, timestamp, ..
+        } => {
+            let now = Instant::now();
+            state.guests.check_post(sender, now)?;
+            // Participants clear the indicator when the message arrives
+            if let Some(typing) = &room.broadcast_typing {
+                typing.clear(sender);
+            }
+            relay_chat_message(state, room, sender, content, None, timestamp, now).await
+        }
+        ClientMessage::Poll {
+            content,
+            options,
+            timestamp,
+            ..
+        } => {
+            let now = Instant::now();
+            state.guests.check_post(sender, now)?;
+            if let Some(typing) = &room.broadcast_typing {
+                typing.clear(sender);
+            }
+            relay_chat_message(state, room, sender, content, Some(options), timestamp, now).await
+        }
+        ClientMessage::Vote { seq, option } => {
+            state.guests.check_post(sender, Instant::now())?;
+            vote(room, sender, seq, option).await
+        }
+        ClientMessage::Forward { seq, room_id } => {
+            state.guests.check_post(sender, Instant::now())?;
+            forward(state, room, sender, seq, &room_id).await
+        }
+        ClientMessage::Star { seq } => star(state, room, sender, seq, true, outbox).await,
+        ClientMessage::Unstar { seq } => star(state, room, sender, seq, false, outbox).await,
+        ClientMessage::SetActivity { activity } => {
+            state.guests.check_post(sender, Instant::now())?;
+            set_activity(room, sender, activity).await
+        }
+        ClientMessage::SetDisplayName { display_name } => {
+            state.guests.check_post(sender, Instant::now())?;
+            rename(room, sender, display_name).await
+        }
+        // The protocol is negotiated before the room state is sent
+        ClientMessage::Hello { .. } => Err(InboundMessageError::InvalidMessage(
+            "hello must be the first message of the connection".to_string(),
+        )),
     }
+}
 
-    let client_id_str_clone = client_id_str.clone();
-    let state_clone = state.clone();
+/// Copy a message of the client's room into another room it belongs to
+///
+/// The copy is broadcast to everyone in the target room, including the client, with the
+/// room, author and timestamp of the original message in `forwarded_from`.
+async fn forward(
+    state: &AppState,
+    room: &RoomUseCases,
+    sender: &ClientId,
+    seq: u64,
+    target: &str,
+) -> Result<(), InboundMessageError> {
+    let Some(usecase) = &state.forward_message_usecase else {
+        return Err(InboundMessageError::InvalidForward(
+            "forwarding is disabled".to_string(),
+        ));
+    };
+    let seq = SequenceNumber::new(seq);
+    let render = |message: &_, seq: SequenceNumber| {
+        ChatMessage::from(Clone::clone(message)).to_json_with_seq(seq.value())
+    };
+    match usecase
+        .execute(sender.clone(), &room.room_id, seq, target, render)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(ForwardMessageError::RoomNotFound) => Err(InboundMessageError::InvalidForward(
+            format!("room '{}' not found", target),
+        )),
+        Err(ForwardMessageError::SameRoom) => Err(InboundMessageError::InvalidForward(
+            "the message is already in this room".to_string(),
+        )),
+        Err(ForwardMessageError::NotAMember) => Err(InboundMessageError::InvalidForward(format!(
+            "you are not in room '{}'",
+            target
+        ))),
+        Err(ForwardMessageError::MessageNotFound) => Err(InboundMessageError::InvalidForward(
+            format!("message {} is not in the room history", seq),
+        )),
+        Err(ForwardMessageError::SendFailed(SendMessageError::RateLimited { retry_after_ms })) => {
+            Err(InboundMessageError::RateLimited { retry_after_ms })
+        }
+        Err(ForwardMessageError::SendFailed(SendMessageError::MessageCapacityExceeded {
+            capacity,
+        })) => Err(InboundMessageError::RoomHistoryFull { capacity }),
+        Err(ForwardMessageError::SendFailed(SendMessageError::RoomFrozen)) => {
+            Err(InboundMessageError::RoomFrozen)
+        }
+        Err(ForwardMessageError::SendFailed(SendMessageError::Rejected(rejection))) => {
+            Err(rejection.into())
+        }
+        Err(ForwardMessageError::SendFailed(SendMessageError::MessageTooLong { max, .. })) => {
+            Err(InboundMessageError::InvalidForward(format!(
+                "the message is too long for room '{}' ({} bytes at most)",
+                target, max
+            )))
+        }
+        Err(e) => {
+            tracing::warn!("Failed to forward message {} of '{}': {:?}", seq, sender, e);
+            Ok(())
+        }
+    }
+}
 
-    // Spawn a task to receive messages from this client
-    let mut recv_task = tokio::spawn(async move {
-        while let Some(msg) = receiver.next().await {
-            let msg = match msg {
-                Ok(msg) => msg,
-                Err(e) => {
-                    tracing::error!("WebSocket error: {}", e);
-                    break;
-                }
+/// Star or unstar a message of the client's room and answer with `star-updated`
+///
+/// Only the client is told; other participants do not see who starred what.
+async fn star(
+    state: &AppState,
+    room: &RoomUseCases,
+    sender: &ClientId,
+    seq: u64,
+    starred: bool,
+    outbox: &mpsc::UnboundedSender<Arc<str>>,
+) -> Result<(), InboundMessageError> {
+    let Some(usecase) = &state.star_messages_usecase else {
+        return Err(InboundMessageError::InvalidStar(
+            "stars are disabled".to_string(),
+        ));
+    };
+    let seq = SequenceNumber::new(seq);
+    let result = if starred {
+        let now = Timestamp::new(get_jst_timestamp());
+        usecase.star(sender.clone(), &room.room_id, seq, now).await
+    } else {
+        usecase.unstar(sender, &room.room_id, seq).await
+    };
+    match result {
+        Ok(_) => {
+            let updated = StarUpdatedMessage {
+                r#type: MessageType::StarUpdated,
+                room_id: room.room_id.as_str().to_string(),
+                seq: seq.value(),
+                starred,
             };
+            let _ = outbox.send(serde_json::to_string(&updated).unwrap().into());
+            Ok(())
+        }
+        Err(StarMessageError::MessageNotFound) => Err(InboundMessageError::InvalidStar(format!(
+            "message {} is not in the room history",
+            seq
+        ))),
+        Err(StarMessageError::TooManyStars) => Err(InboundMessageError::InvalidStar(format!(
+            "you cannot star more than {} messages",
+            MAX_STARS_PER_CLIENT
+        ))),
+        Err(StarMessageError::RepositoryError) => {
+            tracing::warn!("Failed to update the stars of '{}'", sender);
+            Ok(())
+        }
+    }
+}
 
-            match msg {
-                Message::Text(text) => {
-                    tracing::info!("Received text: {}", text);
-
-                    // Parse the incoming message
-                    let chat_msg = match serde_json::from_str::<ChatMessage>(&text) {
-                        Ok(msg) => msg,
-                        Err(e) => {
-                            tracing::warn!("Failed to parse message as JSON: {}", e);
-                            // If not JSON, treat as plain text and wrap it
-                            ChatMessage {
-                                r#type: MessageType::Chat,
-                                client_id: "unknown".to_string(),
-                                content: text.to_string(),
-                                timestamp: 0,
-                            }
-                        }
-                    };
-
-                    // Create response with type "chat" and preserve client_id
-                    let response = ChatMessage {
-                        r#type: MessageType::Chat,
-                        client_id: chat_msg.client_id.clone(),
-                        content: chat_msg.content.clone(),
-                        timestamp: chat_msg.timestamp,
-                    };
-
-                    let response_json = serde_json::to_string(&response).unwrap();
-                    tracing::info!(
-                        "Broadcasting message from '{}' to other clients: {}",
-                        response.client_id,
-                        response.content
-                    );
-
-                    // Use SendMessageUseCase to handle message sending
-                    // Convert String -> Domain Models
-                    let client_id_result = ClientId::try_from(response.client_id.clone());
-                    let content_result = MessageContent::try_from(response.content.clone());
-
-                    match (client_id_result, content_result) {
-                        (Ok(client_id_vo), Ok(content_vo)) => {
-                            match state_clone
-                                .send_message_usecase
-                                .execute(client_id_vo, content_vo, response_json)
-                                .await
-                            {
-                                Ok(_broadcast_targets) => {
-                                    // Broadcast is handled by UseCase
-                                }
-                                Err(e) => {
-                                    tracing::warn!("Failed to send message: {:?}", e);
-                                }
-                            }
-                        }
-                        (Err(_), _) => {
-                            tracing::warn!("Invalid client_id format: '{}'", response.client_id);
-                        }
-                        (_, Err(_)) => {
-                            tracing::warn!(
-                                "Invalid message content (length: {})",
-                                response.content.len()
-                            );
-                        }
-                    }
-                }
-                Message::Ping(_) => {
-                    tracing::debug!("Received ping");
-                    // Ping/pong is handled automatically by the WebSocket protocol
-                }
-                Message::Close(_) => {
-                    tracing::info!("Client '{}' requested close", client_id_str_clone);
-                    break;
-                }
-                _ => {}
-            }
+/// Record the client's vote on a poll and broadcast the new tally as `poll-updated`
+async fn vote(
+    room: &RoomUseCases,
+    sender: &ClientId,
+    seq: u64,
+    option: usize,
+) -> Result<(), InboundMessageError> {
+    let Some(usecase) = &room.vote_poll else {
+        return Err(InboundMessageError::InvalidVote(
+            "polls are disabled in this room".to_string(),
+        ));
+    };
+    let seq = SequenceNumber::new(seq);
+    let render = |poll: &_| serde_json::to_string(&poll_updated(seq, poll)).unwrap();
+    match usecase.execute(seq, sender.clone(), option, render).await {
+        Ok(_) => Ok(()),
+        Err(VotePollError::MessageNotFound) => Err(InboundMessageError::InvalidVote(format!(
+            "message {} is not in the room history",
+            seq
+        ))),
+        Err(VotePollError::NotAPoll) => Err(InboundMessageError::InvalidVote(format!(
+            "message {} is not a poll",
+            seq
+        ))),
+        Err(VotePollError::UnknownOption) => Err(InboundMessageError::InvalidVote(format!(
+            "poll {} has no option {}",
+            seq, option
+        ))),
+        Err(VotePollError::RepositoryError(e)) => {
+            tracing::warn!("Failed to record the vote of '{}': {}", sender, e);
+            Ok(())
+        }
+    }
+}
+
+/// Set or clear the client's activity and broadcast it as `activity-updated`
+///
+/// A blank activity clears it, like a missing one.
+async fn set_activity(
+    room: &RoomUseCases,
+    sender: &ClientId,
+    activity: Option<String>,
+) -> Result<(), InboundMessageError> {
+    let Some(usecase) = &room.set_activity else {
+        return Err(InboundMessageError::InvalidActivity(
+            "activities are disabled in this room".to_string(),
+        ));
+    };
+    let activity = match activity.filter(|activity| !activity.trim().is_empty()) {
+        Some(activity) => Some(
+            Activity::new(activity)
+                .map_err(|e| InboundMessageError::InvalidActivity(e.to_string()))?,
+        ),
+        None => None,
+    };
+    let render =
+        |activity: Option<&_>| serde_json::to_string(&activity_updated(sender, activity)).unwrap();
+    match usecase.execute(sender, activity, render).await {
+        Ok(()) => Ok(()),
+        Err(SetActivityError::NotInRoom) => Err(InboundMessageError::InvalidActivity(
+            "you are not in the room".to_string(),
+        )),
+        Err(SetActivityError::RepositoryError(e)) => {
+            tracing::warn!("Failed to set the activity of '{}': {}", sender, e);
+            Ok(())
         }
+    }
+}
+
+/// Set or clear the client's display name and broadcast it as `participant-renamed`
+///
+/// A blank name clears it, like a missing one.
+async fn rename(
+    room: &RoomUseCases,
+    sender: &ClientId,
+    display_name: Option<String>,
+) -> Result<(), InboundMessageError> {
+    let Some(usecase) = &room.rename_participant else {
+        return Err(InboundMessageError::InvalidDisplayName(
+            "display names are disabled in this room".to_string(),
+        ));
+    };
+    let display_name = match display_name.filter(|name| !name.trim().is_empty()) {
+        Some(name) => Some(
+            DisplayName::new(name)
+                .map_err(|e| InboundMessageError::InvalidDisplayName(e.to_string()))?,
+        ),
+        None => None,
+    };
+    let render = |display_name: Option<&_>| {
+        serde_json::to_string(&participant_renamed(sender, display_name)).unwrap()
+    };
+    match usecase.execute(sender, display_name, render).await {
+        Ok(()) => Ok(()),
+        Err(RenameParticipantError::NotInRoom) => Err(InboundMessageError::InvalidDisplayName(
+            "you are not in the room".to_string(),
+        )),
+        Err(RenameParticipantError::RepositoryError(e)) => {
+            tracing::warn!("Failed to set the display name of '{}': {}", sender, e);
+            Ok(())
+        }
+    }
+}
+
+/// Relay that the client started or stopped typing to the other participants
+///
+/// Ignored if the room does not relay typing indicators.
+async fn relay_typing(room: &RoomUseCases, sender: &ClientId, typing: bool) {
+    let Some(usecase) = &room.broadcast_typing else {
+        return;
+    };
+    let message = TypingMessage {
+        r#type: if typing {
+            MessageType::TypingStarted
+        } else {
+            MessageType::TypingStopped
+        },
+        client_id: sender.as_str().to_string(),
+    };
+    let json = serde_json::to_string(&message).unwrap();
+    if let Err(e) = usecase.execute(sender, typing, Instant::now(), &json).await {
+        tracing::warn!("Failed to broadcast typing of '{}': {}", sender, e);
+    }
+}
+
+/// Validate and send a chat message (or a poll, if `options` is given) received from the client
+///
+/// Persisting and broadcasting are recorded as child spans by the sequencer, which also
+/// records the assigned sequence number as `message_id`.
+///
+/// # Arguments
+///
+/// * `state` - Application state
+/// * `room` - Room the message is sent to
+/// * `sender` - Client of the connection, to which the message is attributed
+/// * `content` - Message content (the question of a poll)
+/// * `options` - Options of the poll (`None` for a chat message)
+/// * `timestamp` - When the client sent the message
+/// * `received_at` - When the frame was received (for the broadcast latency histogram)
+async fn relay_chat_message(
+    state: &AppState,
+    room: &RoomUseCases,
+    sender: &ClientId,
+    content: String,
+    options: Option<Vec<String>>,
+    timestamp: i64,
+    received_at: Instant,
+) -> Result<(), InboundMessageError> {
+    // Create response with type "chat" (or "poll") from the client of the connection
+    let poll = options.map(|options| PollInfo {
+        options: options
+            .iter()
+            .map(|label| PollOptionInfo {
+                label: state.sanitize_profile.apply(label).into_owned(),
+                votes: 0,
+            })
+            .collect(),
     });
+    let response = ChatMessage {
+        r#type: if poll.is_some() {
+            MessageType::Poll
+        } else {
+            MessageType::Chat
+        },
+        client_id: sender.as_str().to_string(),
+        content: state.sanitize_profile.apply(&content).into_owned(),
+        timestamp,
+        seq: None,
+        poll,
+        forwarded_from: None,
+    };
+
+    tracing::info!(
+        "Broadcasting message from '{}' to other clients: {}",
+        response.client_id,
+        response.content
+    );
 
-    // Spawn a task to receive messages from other clients and send to this client
-    let mut send_task = pusher_loop(rx, sender);
+    // Convert String -> Domain Models
+    let (content, options) = tracing::info_span!("validate").in_scope(|| {
+        // The room's policy (checked by SendMessageUseCase) may allow shorter messages only
+        let content = MessageContent::try_from(response.content.clone()).map_err(|e| match e {
+            ValueObjectError::MessageContentTooLong { max, actual } => {
+                message_too_long(max, actual)
+            }
+            _ => InboundMessageError::InvalidMessage(format!(
+                "invalid content (length: {})",
+                response.content.len()
+            )),
+        })?;
+        let options = response
+            .poll
+            .as_ref()
+            .map(|poll| {
+                poll.options
+                    .iter()
+                    .map(|option| {
+                        PollOption::try_from(option.label.clone()).map_err(|_| {
+                            InboundMessageError::InvalidMessage(format!(
+                                "invalid poll option (length: {})",
+                                option.label.len()
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        Ok::<_, InboundMessageError>((content, options))
+    })?;
 
-    // If any one of the tasks completes, abort the other
-    tokio::select! {
-        _ = &mut recv_task => send_task.abort(),
-        _ = &mut send_task => recv_task.abort(),
+    // Use SendMessageUseCase to handle message sending
+    let render = move |seq: SequenceNumber| response.to_json_with_seq(seq.value());
+    let sent = match options {
+        Some(options) => {
+            room.send_message
+                .execute_poll(sender.clone(), content, options, render)
+                .await
+        }
+        None => {
+            room.send_message
+                .execute(sender.clone(), content, render)
+                .await
+        }
     };
+    match sent {
+        Ok(_broadcast_targets) => {
+            // Broadcast is handled by UseCase; it replies once the message has been pushed
+            // to every recipient
+            state.metrics.observe_broadcast(received_at.elapsed());
+        }
+        Err(SendMessageError::RateLimited { retry_after_ms }) => {
+            return Err(InboundMessageError::RateLimited { retry_after_ms });
+        }
+        Err(SendMessageError::MessageCapacityExceeded { capacity }) => {
+            return Err(InboundMessageError::RoomHistoryFull { capacity });
+        }
+        Err(SendMessageError::RoomFrozen) => {
+            return Err(InboundMessageError::RoomFrozen);
+        }
+        Err(SendMessageError::Rejected(rejection)) => {
+            return Err(rejection.into());
+        }
+        Err(SendMessageError::MessageTooLong { max, actual }) => {
+            return Err(message_too_long(max, actual));
+        }
+        Err(SendMessageError::InvalidPoll { min, max }) => {
+            return Err(InboundMessageError::InvalidMessage(format!(
+                "a poll needs {} to {} options",
+                min, max
+            )));
+        }
+        Err(e) => {
+            tracing::warn!("Failed to send message: {:?}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Error for a message longer than the room allows
+fn message_too_long(max: usize, actual: usize) -> InboundMessageError {
+    InboundMessageError::InvalidMessage(format!(
+        "the message is too long ({} bytes, {} at most)",
+        actual, max
+    ))
+}
 
-    // Use DisconnectParticipantUseCase to handle disconnection
-    // (client_id is already a ClientId Domain Model)
+/// Queue the messages after `since_seq` for the client
+///
+/// Messages already delivered on the connection are dropped by its dedup window.
+async fn backfill(
+    state: &AppState,
+    room_id: &str,
+    since_seq: u64,
+    outbox: &mpsc::UnboundedSender<Arc<str>>,
+) {
+    let since = SequenceNumber::new(since_seq);
     match state
-        .disconnect_participant_usecase
-        .execute(client_id.clone())
+        .get_room_messages_usecase
+        .execute(room_id, since)
         .await
     {
-        Ok(notify_targets) => {
-            tracing::info!(
-                "Client '{}' disconnected and removed from registry",
-                client_id_str
-            );
-
-            // Broadcast participant-left to all remaining clients
-            let disconnected_at = get_jst_timestamp();
-            let left_msg = ParticipantLeftMessage {
-                r#type: MessageType::ParticipantLeft,
-                client_id: client_id_str.clone(),
-                disconnected_at,
-            };
-
-            let left_json = serde_json::to_string(&left_msg).unwrap();
-            if let Err(e) = state
-                .disconnect_participant_usecase
-                .broadcast_participant_left(notify_targets, &left_json)
-                .await
-            {
-                tracing::warn!("Failed to broadcast participant-left: {}", e);
-            } else {
-                tracing::info!("Broadcasted participant-left for '{}'", client_id_str);
+        Ok((messages, _last_seq)) => {
+            tracing::info!("Backfilling {} messages after {}", messages.len(), since);
+            for message in messages {
+                let json = serde_json::to_string(&ChatMessage::from(message)).unwrap();
+                if outbox.send(json.into()).is_err() {
+                    break;
+                }
             }
         }
-        Err(_) => {
-            tracing::warn!("Failed to disconnect participant '{}'", client_id_str);
+        Err(e) => tracing::warn!("Failed to backfill messages: {:?}", e),
+    }
+}
+
+/// Send the rooms the client can join
+async fn list_rooms(state: &AppState, outbox: &mpsc::UnboundedSender<Arc<str>>) {
+    match state
+        .get_rooms_usecase
+        .execute(&RoomsQuery::default())
+        .await
+    {
+        Ok(page) => {
+            // Domain Model から DTO への変換
+            let room_list = RoomListMessage {
+                r#type: MessageType::RoomList,
+                rooms: page.rooms.into_iter().map(RoomInfo::from).collect(),
+            };
+            let _ = outbox.send(serde_json::to_string(&room_list).unwrap().into());
         }
+        Err(e) => tracing::warn!("Failed to list rooms: {:?}", e),
     }
 }
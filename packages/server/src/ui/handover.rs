@@ -0,0 +1,357 @@
+//! Zero-downtime restart by handing the listening socket over to a new process.
+//!
+//! On SIGUSR2 the server re-executes its own binary with the same arguments and passes the
+//! listening socket to the new process, which reports back once it is accepting connections:
+//!
+//! 1. The WAL (if any) is sealed so that the new process replays a log nobody else appends to
+//! 2. The new process is started with the listener as fd 3 and a readiness socket as fd 4
+//! 3. Once the new process writes `READY=1`, this process stops accepting connections and
+//!    sends every client a `server-shutdown` notice followed by a Close frame (1012)
+//! 4. Clients reconnect after a staggered delay and land on the new process; this process
+//!    exits when its connections are gone or the drain timeout expires
+//!
+//! If the new process fails to start, the WAL is unsealed and this process keeps serving.
+//!
+//! Only the HTTP/WebSocket listener is handed over. Other sockets (cluster gossip, gRPC health)
+//! are bound by the new process and fail while this process still holds them.
+
+use std::{hash::BuildHasher, sync::Arc, time::Duration};
+
+use tokio::sync::watch;
+
+use crate::infrastructure::repository::WriteAheadLog;
+
+#[cfg(unix)]
+use super::signal::ShutdownToken;
+
+/// WebSocket close code sent to clients when the server restarts (Service Restart)
+pub const SERVICE_RESTART_CLOSE_CODE: u16 = 1012;
+
+/// Default time to wait for connections to close after handing over the listener
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default window over which client reconnections are spread
+pub const DEFAULT_RECONNECT_STAGGER: Duration = Duration::from_secs(5);
+
+/// Environment variable telling the new process which fd is the handed-over listener
+#[cfg(unix)]
+const LISTENER_FD_ENV: &str = "ENGAWA_HANDOVER_FD";
+
+/// Environment variable telling the new process where to report readiness
+#[cfg(unix)]
+const READY_FD_ENV: &str = "ENGAWA_HANDOVER_READY_FD";
+
+/// File descriptor of the listener in the new process
+#[cfg(unix)]
+const LISTENER_FD: i32 = 3;
+
+/// File descriptor of the readiness socket in the new process
+#[cfg(unix)]
+const READY_FD: i32 = 4;
+
+/// Time allowed for the new process to start accepting connections
+#[cfg(unix)]
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Listener handover configuration
+#[derive(Clone)]
+pub struct Handover {
+    /// Time to wait for connections to close before exiting
+    pub drain_timeout: Duration,
+    /// Window over which client reconnections are spread
+    pub reconnect_stagger: Duration,
+    /// WAL sealed while the new process takes over (if the room is persisted)
+    wal: Option<Arc<WriteAheadLog>>,
+}
+
+impl Handover {
+    /// Create a handover configuration with the default timeouts
+    pub fn new() -> Self {
+        Self {
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+            reconnect_stagger: DEFAULT_RECONNECT_STAGGER,
+            wal: None,
+        }
+    }
+
+    /// Set the time to wait for connections to close before exiting
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
+
+    /// Set the window over which client reconnections are spread
+    pub fn with_reconnect_stagger(mut self, stagger: Duration) -> Self {
+        self.reconnect_stagger = stagger;
+        self
+    }
+
+    /// Seal the WAL while the new process takes over
+    pub fn with_wal(mut self, wal: Arc<WriteAheadLog>) -> Self {
+        self.wal = Some(wal);
+        self
+    }
+}
+
+impl Default for Handover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Delay before a client should reconnect, spread randomly across `stagger`
+///
+/// Spreading the reconnections keeps the new process from being hit by every participant
+/// at the same moment.
+pub fn reconnect_delay(client_id: &str, stagger: Duration) -> Duration {
+    let stagger_ms = stagger.as_millis() as u64;
+    if stagger_ms == 0 {
+        return Duration::ZERO;
+    }
+    // RandomState is seeded randomly, so the same client gets a different delay each time
+    let hash = std::collections::hash_map::RandomState::new().hash_one(client_id);
+    Duration::from_millis(hash % stagger_ms)
+}
+
+/// Counts open WebSocket connections so that a draining server knows when it can exit
+#[derive(Debug, Clone)]
+pub struct ConnectionTracker {
+    count: Arc<watch::Sender<usize>>,
+}
+
+impl ConnectionTracker {
+    /// Create a tracker with no connections
+    pub fn new() -> Self {
+        let (count, _receiver) = watch::channel(0);
+        Self {
+            count: Arc::new(count),
+        }
+    }
+
+    /// Register a connection; it is counted until the guard is dropped
+    pub fn track(&self) -> ConnectionGuard {
+        self.count.send_modify(|count| *count += 1);
+        ConnectionGuard {
+            count: self.count.clone(),
+        }
+    }
+
+    /// Number of open connections
+    pub fn count(&self) -> usize {
+        *self.count.borrow()
+    }
+
+    /// Wait until every connection is closed
+    pub async fn wait_idle(&self) {
+        let mut receiver = self.count.subscribe();
+        // The sender lives as long as `self`, so this cannot fail
+        let _ = receiver.wait_for(|count| *count == 0).await;
+    }
+}
+
+impl Default for ConnectionTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Guard keeping a connection counted by [`ConnectionTracker`]
+#[derive(Debug)]
+pub struct ConnectionGuard {
+    count: Arc<watch::Sender<usize>>,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.count.send_modify(|count| *count -= 1);
+    }
+}
+
+/// Take the listener handed over by the previous process, if started by a handover
+#[cfg(unix)]
+pub fn take_handed_over_listener() -> std::io::Result<Option<std::net::TcpListener>> {
+    use std::os::fd::FromRawFd;
+
+    if std::env::var(LISTENER_FD_ENV).ok().as_deref() != Some(&LISTENER_FD.to_string()) {
+        return Ok(None);
+    }
+    // SAFETY: the previous process placed its listening socket at this fd right before exec,
+    // and nothing else in this process has claimed it.
+    let listener = unsafe { std::net::TcpListener::from_raw_fd(LISTENER_FD) };
+    listener.set_nonblocking(true)?;
+    Ok(Some(listener))
+}
+
+#[cfg(not(unix))]
+pub fn take_handed_over_listener() -> std::io::Result<Option<std::net::TcpListener>> {
+    Ok(None)
+}
+
+/// Tell the previous process that this process is accepting connections
+///
+/// Does nothing if the process was not started by a handover.
+#[cfg(unix)]
+pub fn notify_ready() {
+    use std::{io::Write, os::fd::FromRawFd, os::unix::net::UnixStream};
+
+    if std::env::var(READY_FD_ENV).ok().as_deref() != Some(&READY_FD.to_string()) {
+        return;
+    }
+    // SAFETY: the previous process placed the readiness socket at this fd right before exec;
+    // it is only used here and closed when the stream is dropped.
+    let mut stream = unsafe { UnixStream::from_raw_fd(READY_FD) };
+    if let Err(e) = stream.write_all(b"READY=1\n") {
+        tracing::warn!("Failed to report readiness to the previous process: {}", e);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn notify_ready() {}
+
+/// Wait for SIGUSR2 and hand the listener over to a new process
+///
+/// Returns `true` once the new process is accepting connections and `draining` has been
+/// triggered, or `false` when the server shuts down without a handover.
+#[cfg(unix)]
+pub async fn run_handover(
+    handover: Handover,
+    listener_fd: std::os::fd::RawFd,
+    draining: ShutdownToken,
+    shutdown: ShutdownToken,
+) -> bool {
+    let mut sigusr2 =
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::user_defined2()) {
+            Ok(signal) => signal,
+            Err(e) => {
+                tracing::warn!("Failed to install SIGUSR2 handler: {}", e);
+                return false;
+            }
+        };
+
+    loop {
+        tokio::select! {
+            _ = sigusr2.recv() => {}
+            _ = shutdown.cancelled() => return false,
+        }
+        tracing::info!("Received SIGUSR2, handing the listener over to a new process...");
+
+        if let Some(wal) = &handover.wal {
+            wal.seal().await;
+        }
+        match start_successor(listener_fd).await {
+            Ok(pid) => {
+                tracing::info!(
+                    "Process {} is accepting connections; draining existing connections",
+                    pid
+                );
+                draining.trigger();
+                shutdown.trigger();
+                return true;
+            }
+            Err(e) => {
+                tracing::error!("Handover failed, continuing to serve: {}", e);
+                if let Some(wal) = &handover.wal {
+                    wal.unseal();
+                }
+            }
+        }
+    }
+}
+
+/// Start a new process with the listener and wait until it is accepting connections
+///
+/// Returns the PID of the new process.
+#[cfg(unix)]
+async fn start_successor(listener_fd: std::os::fd::RawFd) -> std::io::Result<u32> {
+    use std::os::{fd::AsRawFd, unix::net::UnixStream};
+    use tokio::io::AsyncReadExt;
+
+    let (parent_end, child_end) = UnixStream::pair()?;
+    let ready_fd = child_end.as_raw_fd();
+
+    let mut command = tokio::process::Command::new(std::env::current_exe()?);
+    command
+        .args(std::env::args_os().skip(1))
+        .env(LISTENER_FD_ENV, LISTENER_FD.to_string())
+        .env(READY_FD_ENV, READY_FD.to_string());
+    // SAFETY: only async-signal-safe functions (fcntl, dup2) are called between fork and exec.
+    unsafe {
+        command.pre_exec(move || {
+            // Duplicate both fds above the targets first so that moving one into place
+            // cannot overwrite the other; the copies are closed on exec
+            let listener = libc::fcntl(listener_fd, libc::F_DUPFD_CLOEXEC, 10);
+            let ready = libc::fcntl(ready_fd, libc::F_DUPFD_CLOEXEC, 10);
+            if listener < 0
+                || ready < 0
+                || libc::dup2(listener, LISTENER_FD) < 0
+                || libc::dup2(ready, READY_FD) < 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let mut child = command.spawn()?;
+    let pid = child.id().unwrap_or_default();
+    // Only the new process keeps its end open, so its exit is seen as EOF
+    drop(child_end);
+
+    parent_end.set_nonblocking(true)?;
+    let mut parent_end = tokio::net::UnixStream::from_std(parent_end)?;
+    let mut report = String::new();
+    let ready = tokio::time::timeout(READY_TIMEOUT, parent_end.read_to_string(&mut report)).await;
+    match ready {
+        Ok(Ok(_)) if report.trim() == "READY=1" => Ok(pid),
+        Ok(Ok(_)) => Err(std::io::Error::other(format!(
+            "process {} exited before accepting connections",
+            pid
+        ))),
+        Ok(Err(e)) => Err(e),
+        Err(_) => {
+            let _ = child.kill().await;
+            Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("process {} did not start accepting connections", pid),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_delay_within_stagger() {
+        // テスト項目: 再接続までの待ち時間は分散させる幅に収まる
+        // given (前提条件):
+        let stagger = Duration::from_millis(500);
+
+        // then (期待する結果):
+        for client_id in ["alice", "bob", "carol"] {
+            assert!(reconnect_delay(client_id, stagger) < stagger);
+        }
+        assert_eq!(reconnect_delay("alice", Duration::ZERO), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_connection_tracker_waits_for_connections() {
+        // テスト項目: 全ての接続が閉じるまで待機し、閉じると待機が終わる
+        // given (前提条件):
+        let tracker = ConnectionTracker::new();
+        let first = tracker.track();
+        let second = tracker.track();
+        assert_eq!(tracker.count(), 2);
+
+        // when (操作):
+        drop(first);
+        let still_open = tokio::time::timeout(Duration::from_millis(50), tracker.wait_idle()).await;
+        drop(second);
+        let idle = tokio::time::timeout(Duration::from_secs(1), tracker.wait_idle()).await;
+
+        // then (期待する結果):
+        assert!(still_open.is_err());
+        assert!(idle.is_ok());
+        assert_eq!(tracker.count(), 0);
+    }
+}
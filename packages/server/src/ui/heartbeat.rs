@@ -0,0 +1,177 @@
+//! Keepalive of WebSocket connections and reaping of idle connections.
+//!
+//! The send pump of each connection sends a WebSocket Ping every ping interval, which clients
+//! answer with a Pong (browsers and tungstenite do so automatically). Every frame received from
+//! the client, Pongs included, counts as a sign of life. A background task closes connections
+//! that stayed silent for longer than the idle timeout (e.g. a laptop that went to sleep or a
+//! network that dropped without closing the TCP connection); the connection then leaves the
+//! room as usual, announcing `participant-left` to the other participants.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::sync::oneshot;
+
+use super::signal::ShutdownToken;
+
+/// Default interval between the Pings sent to each connection
+pub const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Default time without any frame from a client after which its connection is closed
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Ping interval and idle timeout of the connections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Keepalive {
+    /// Interval between the Pings sent to each connection
+    pub ping_interval: Duration,
+    /// Time without any frame from the client after which the connection is closed
+    pub idle_timeout: Duration,
+}
+
+impl Default for Keepalive {
+    fn default() -> Self {
+        Self {
+            ping_interval: DEFAULT_PING_INTERVAL,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+        }
+    }
+}
+
+struct Heartbeat {
+    client_id: String,
+    /// When the last frame was received from the client
+    last_seen: Instant,
+    /// Tells the connection it has been idle for too long
+    reap: oneshot::Sender<()>,
+}
+
+/// Last frame received on each joined connection
+#[derive(Default)]
+pub struct HeartbeatRegistry {
+    next_id: AtomicU64,
+    connections: Mutex<HashMap<u64, Heartbeat>>,
+}
+
+impl HeartbeatRegistry {
+    /// Create a registry with no connections
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a connection that joined at `now`
+    ///
+    /// Returns the ID of the connection in the registry, and a receiver completing when the
+    /// connection is reaped.
+    pub fn register(&self, client_id: &str, now: Instant) -> (u64, oneshot::Receiver<()>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (reap, reaped) = oneshot::channel();
+        self.connections.lock().unwrap().insert(
+            id,
+            Heartbeat {
+                client_id: client_id.to_string(),
+                last_seen: now,
+                reap,
+            },
+        );
+        (id, reaped)
+    }
+
+    /// Record that a frame was received on the connection at `now`
+    pub fn touch(&self, id: u64, now: Instant) {
+        if let Some(heartbeat) = self.connections.lock().unwrap().get_mut(&id) {
+            heartbeat.last_seen = now;
+        }
+    }
+
+    /// Forget a connection that is closing
+    pub fn unregister(&self, id: u64) {
+        self.connections.lock().unwrap().remove(&id);
+    }
+
+    /// Ask the connections silent for longer than `idle_timeout` at `now` to close
+    ///
+    /// Returns the client IDs of the reaped connections.
+    pub fn reap_idle(&self, now: Instant, idle_timeout: Duration) -> Vec<String> {
+        let mut connections = self.connections.lock().unwrap();
+        let idle: Vec<u64> = connections
+            .iter()
+            .filter(|(_, heartbeat)| now.duration_since(heartbeat.last_seen) > idle_timeout)
+            .map(|(id, _)| *id)
+            .collect();
+        idle.into_iter()
+            .filter_map(|id| connections.remove(&id))
+            .map(|heartbeat| {
+                // The connection may be closing already; it leaves the room either way
+                let _ = heartbeat.reap.send(());
+                heartbeat.client_id
+            })
+            .collect()
+    }
+}
+
+/// Close idle connections until the server shuts down
+///
+/// Checks the connections every ping interval, so a silent client is disconnected between the
+/// idle timeout and one ping interval after it.
+pub async fn run_reaper(
+    registry: Arc<HeartbeatRegistry>,
+    keepalive: Keepalive,
+    shutdown: ShutdownToken,
+) {
+    let mut ticker = tokio::time::interval(keepalive.ping_interval);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = shutdown.cancelled() => return,
+        }
+
+        for client_id in registry.reap_idle(Instant::now(), keepalive.idle_timeout) {
+            tracing::info!(
+                "Closing the connection of '{}': no frame for more than {:?}",
+                client_id,
+                keepalive.idle_timeout
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reap_idle_closes_only_silent_connections() {
+        // テスト項目: タイムアウトを超えて受信の無い接続だけが閉じるよう通知され、登録から外れる
+        // given (前提条件):
+        let registry = HeartbeatRegistry::new();
+        let start = Instant::now();
+        let (alice, mut alice_reaped) = registry.register("alice", start);
+        let (_bob, mut bob_reaped) = registry.register("bob", start);
+        let (carol, mut carol_reaped) = registry.register("carol", start);
+        registry.touch(alice, start + Duration::from_secs(60));
+        registry.unregister(carol);
+
+        // when (操作):
+        let reaped = registry.reap_idle(start + Duration::from_secs(100), DEFAULT_IDLE_TIMEOUT);
+        let reaped_again =
+            registry.reap_idle(start + Duration::from_secs(100), DEFAULT_IDLE_TIMEOUT);
+
+        // then (期待する結果):
+        assert_eq!(reaped, vec!["bob".to_string()]);
+        assert!(reaped_again.is_empty());
+        assert!(bob_reaped.try_recv().is_ok());
+        assert!(alice_reaped.try_recv().is_err());
+        // 登録を外した接続には通知しない（送信側が破棄される）
+        assert!(matches!(
+            carol_reaped.try_recv(),
+            Err(oneshot::error::TryRecvError::Closed)
+        ));
+    }
+}
@@ -0,0 +1,150 @@
+//! HTTP caching helpers for REST API responses.
+//!
+//! Room listings change whenever participants join/leave or messages are posted, so
+//! responses are served with `Cache-Control: no-cache` and an `ETag`: clients (and the
+//! dashboard's polling) always revalidate, but unchanged data costs only a `304 Not Modified`.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use axum::{
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH, LAST_MODIFIED},
+    },
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use engawa_shared::time::timestamp_to_http_date;
+
+/// `Cache-Control` for data that may be cached but must be revalidated
+const REVALIDATE: &str = "no-cache";
+
+/// `Cache-Control` for data that must never be cached (e.g. health checks)
+pub const NO_STORE: &str = "no-store";
+
+/// Compute a weak entity tag for a response body
+pub fn etag_for(body: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    format!("W/\"{:016x}\"", hasher.finish())
+}
+
+/// Check whether an `If-None-Match` header value matches the entity tag (weak comparison)
+pub fn if_none_match(header: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    header
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == opaque(etag))
+}
+
+/// Build a revalidatable JSON response.
+///
+/// Returns `304 Not Modified` when the request's `If-None-Match` matches the body.
+///
+/// # Arguments
+///
+/// * `request_headers` - Headers of the incoming request
+/// * `body` - Response body to serialize
+/// * `last_modified` - Unix timestamp (milliseconds) of the latest change, if known
+pub fn revalidatable_json<T: Serialize>(
+    request_headers: &HeaderMap,
+    body: &T,
+    last_modified: Option<i64>,
+) -> Response {
+    let json = match serde_json::to_vec(body) {
+        Ok(json) => json,
+        Err(e) => {
+            tracing::error!("Failed to serialize response: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let etag = etag_for(&json);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static(REVALIDATE));
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        headers.insert(ETAG, value);
+    }
+    if let Some(last_modified) = last_modified
+        && let Ok(value) = HeaderValue::from_str(&timestamp_to_http_date(last_modified))
+    {
+        headers.insert(LAST_MODIFIED, value);
+    }
+
+    let not_modified = request_headers
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| if_none_match(value, &etag));
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    (StatusCode::OK, headers, json).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_etag_is_stable_for_same_body() {
+        // テスト項目: 同じ body からは同じ ETag が生成され、異なる body では異なる
+        // when (操作):
+        let etag1 = etag_for(b"[1,2,3]");
+        let etag2 = etag_for(b"[1,2,3]");
+        let etag3 = etag_for(b"[1,2]");
+
+        // then (期待する結果):
+        assert_eq!(etag1, etag2);
+        assert_ne!(etag1, etag3);
+        assert!(etag1.starts_with("W/\""));
+    }
+
+    #[test]
+    fn test_if_none_match_weak_comparison() {
+        // テスト項目: If-None-Match は弱い比較でリスト中のいずれかと一致すれば真になる
+        // given (前提条件):
+        let etag = "W/\"abc\"";
+
+        // then (期待する結果):
+        assert!(if_none_match("\"abc\"", etag));
+        assert!(if_none_match("\"xyz\", W/\"abc\"", etag));
+        assert!(if_none_match("*", etag));
+        assert!(!if_none_match("\"xyz\"", etag));
+    }
+
+    #[test]
+    fn test_revalidatable_json_returns_not_modified() {
+        // テスト項目: If-None-Match が一致する場合は 304 が返される
+        // given (前提条件):
+        let body = vec!["room"];
+        let etag = etag_for(&serde_json::to_vec(&body).unwrap());
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert(IF_NONE_MATCH, HeaderValue::from_str(&etag).unwrap());
+
+        // when (操作):
+        let response = revalidatable_json(&request_headers, &body, Some(0));
+
+        // then (期待する結果):
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[ETAG], etag.as_str());
+    }
+
+    #[test]
+    fn test_revalidatable_json_sets_cache_headers() {
+        // テスト項目: 通常のレスポンスに Cache-Control / ETag / Last-Modified が付与される
+        // when (操作):
+        let response = revalidatable_json(&HeaderMap::new(), &vec!["room"], Some(0));
+
+        // then (期待する結果):
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CACHE_CONTROL], "no-cache");
+        assert!(response.headers().contains_key(ETAG));
+        assert_eq!(
+            response.headers()[LAST_MODIFIED],
+            "Thu, 01 Jan 1970 00:00:00 GMT"
+        );
+    }
+}
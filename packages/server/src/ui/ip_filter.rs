@@ -0,0 +1,148 @@
+//! Network allow/deny lists checked before a request is handled.
+//!
+//! Every request, including the WebSocket upgrade, is checked against the real client IP
+//! (honoring trusted proxies), so abusive networks can be blocked without touching the
+//! reverse proxy. Admins replace the lists at runtime at `PUT /api/v1/admin/ip-rules`; that
+//! endpoint is exempt from the lists so that a mistaken allowlist cannot lock admins out.
+//! Lists changed at runtime are lost on restart.
+
+use std::{
+    net::IpAddr,
+    sync::{Arc, RwLock},
+};
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use super::{
+    client_ip::{ClientIp, IpNetwork},
+    state::AppState,
+};
+
+/// Path (under the API prefix) of the admin endpoint managing the lists
+pub const IP_RULES_PATH: &str = "/admin/ip-rules";
+
+/// Allowed and denied networks
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpRules {
+    /// Networks allowed to connect (any network if empty)
+    pub allow: Vec<IpNetwork>,
+    /// Networks refused even if allowed
+    pub deny: Vec<IpNetwork>,
+}
+
+impl IpRules {
+    /// Check whether a client at the address may send requests
+    ///
+    /// The denylist takes precedence; a non-empty allowlist admits only its networks.
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        if self.deny.iter().any(|network| network.contains(ip)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|network| network.contains(ip))
+    }
+}
+
+/// Allow/deny lists that can be replaced at runtime
+#[derive(Debug, Default)]
+pub struct IpFilter {
+    rules: RwLock<IpRules>,
+}
+
+impl IpFilter {
+    /// Create a filter with the lists given at startup
+    pub fn new(rules: IpRules) -> Self {
+        Self {
+            rules: RwLock::new(rules),
+        }
+    }
+
+    /// Get the current lists
+    pub fn rules(&self) -> IpRules {
+        self.rules.read().unwrap().clone()
+    }
+
+    /// Replace both lists
+    pub fn replace(&self, rules: IpRules) {
+        *self.rules.write().unwrap() = rules;
+    }
+
+    /// Check whether a client at the address may send requests
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        self.rules.read().unwrap().allows(ip)
+    }
+}
+
+/// Middleware refusing requests from networks not allowed by the lists with `403 Forbidden`
+pub async fn enforce(
+    State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    let exempt = path.starts_with("/api/") && path.ends_with(IP_RULES_PATH);
+    if !exempt && !state.ip_filter.allows(client_ip) {
+        tracing::debug!("Refusing {} from blocked address {}", path, client_ip);
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn networks(networks: &[&str]) -> Vec<IpNetwork> {
+        networks.iter().map(|n| n.parse().unwrap()).collect()
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_rules_deny_takes_precedence_over_allow() {
+        // テスト項目: 拒否リストが許可リストより優先され、許可リストがあればそれ以外は拒否される
+        // given (前提条件):
+        let rules = IpRules {
+            allow: networks(&["10.0.0.0/8"]),
+            deny: networks(&["10.0.0.0/24"]),
+        };
+        let open = IpRules {
+            allow: Vec::new(),
+            deny: networks(&["192.0.2.1"]),
+        };
+
+        // then (期待する結果):
+        assert!(rules.allows(ip("10.1.0.1")));
+        assert!(!rules.allows(ip("10.0.0.5")));
+        assert!(!rules.allows(ip("192.0.2.10")));
+        assert!(open.allows(ip("198.51.100.1")));
+        assert!(!open.allows(ip("192.0.2.1")));
+    }
+
+    #[test]
+    fn test_filter_replaces_rules() {
+        // テスト項目: 実行中にリストを置き換えると以降の判定に反映される
+        // given (前提条件):
+        let filter = IpFilter::default();
+        let blocked = ip("203.0.113.7");
+        let allowed_before = filter.allows(blocked);
+
+        // when (操作):
+        filter.replace(IpRules {
+            allow: Vec::new(),
+            deny: networks(&["203.0.113.0/24"]),
+        });
+
+        // then (期待する結果):
+        assert!(allowed_before);
+        assert!(!filter.allows(blocked));
+        assert_eq!(filter.rules().deny, networks(&["203.0.113.0/24"]));
+    }
+}
@@ -0,0 +1,55 @@
+//! Runtime memory guardrails.
+//!
+//! A background task periodically adds up the approximate memory held by the room history
+//! and the per-client send queues, publishes it to `/metrics`, and evicts the oldest history
+//! when the total exceeds the configured cap.
+
+use std::{sync::Arc, time::Duration};
+
+use crate::{infrastructure::metrics::Metrics, usecase::EnforceMemoryLimitUseCase};
+
+use super::signal::ShutdownToken;
+
+/// How often memory usage is checked
+pub const MEMORY_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Check memory usage until the server shuts down
+pub async fn run_memory_guard(
+    usecase: Arc<EnforceMemoryLimitUseCase>,
+    metrics: Arc<Metrics>,
+    shutdown: ShutdownToken,
+) {
+    metrics.set_memory_limit(usecase.limit_bytes());
+    let mut ticker = tokio::time::interval(MEMORY_CHECK_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = shutdown.cancelled() => return,
+        }
+
+        let usage = usecase.execute(metrics.queue_bytes()).await;
+        metrics.record_history(usage.history_bytes, usage.evicted_messages);
+
+        let Some(limit) = usecase.limit_bytes() else {
+            continue;
+        };
+        if usage.evicted_messages > 0 {
+            tracing::warn!(
+                "Memory limit of {} bytes exceeded; evicted {} messages from history (now {} bytes in history, {} bytes queued)",
+                limit,
+                usage.evicted_messages,
+                usage.history_bytes,
+                usage.queue_bytes
+            );
+        }
+        if usage.total_bytes() > limit {
+            // Queues only shrink as clients read them, so evicting history is not enough
+            tracing::warn!(
+                "Memory usage ({} bytes) is still over the limit of {} bytes; {} bytes are queued for slow clients",
+                usage.total_bytes(),
+                limit,
+                usage.queue_bytes
+            );
+        }
+    }
+}
@@ -1,8 +1,89 @@
 //! WebSocket chat server implementation.
 
+mod announcement;
+mod api_version;
+mod approval;
+mod auth;
+mod breakout;
+mod challenge;
+mod client_ip;
+mod cluster;
+mod config;
+mod config_file;
+mod connection;
+mod digest;
+#[cfg(feature = "discord")]
+mod discord;
+pub mod error;
+#[cfg(feature = "federation")]
+mod federation;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod guest;
 mod handler;
+mod handover;
+mod heartbeat;
+mod http_cache;
+mod ip_filter;
+mod memory;
+#[cfg(feature = "mqtt")]
+mod mqtt;
+mod openapi;
+#[cfg(feature = "webhooks")]
+mod outgoing_webhook;
+mod presenter;
+mod seed;
 mod server;
+mod session;
 mod signal;
 pub mod state; // UseCase 層からアクセスするため public に変更
+mod systemd;
+#[cfg(feature = "tls")]
+mod tls;
+#[cfg(feature = "xmpp")]
+mod xmpp;
 
-pub use server::Server;
+pub use announcement::ANNOUNCEMENT_SENDER;
+pub use approval::JOIN_REJECTED_CLOSE_CODE;
+pub use auth::{ACCESS_TOKEN_QUERY, Authentication};
+pub use breakout::BREAKOUT_SENDER;
+pub use challenge::ConnectChallenge;
+pub use client_ip::{IpNetwork, TrustedProxies};
+pub use cluster::{ClusterNode, RoomShards};
+#[cfg(feature = "discord")]
+pub use config::DiscordConfig;
+#[cfg(feature = "federation")]
+pub use config::FederationConfig;
+#[cfg(feature = "mqtt")]
+pub use config::MqttConfig;
+#[cfg(feature = "tls")]
+pub use config::TlsConfig;
+#[cfg(feature = "xmpp")]
+pub use config::XmppConfig;
+pub use config::{
+    ClusterConfig, DuplicatePolicy, GuestMode, MessageFilterSpec, RoomStorage, SeedProfile,
+    ServerConfig, StorageBackend,
+};
+pub use config_file::ConfigFile;
+pub use digest::DIGEST_SENDER;
+#[cfg(feature = "discord")]
+pub use discord::DiscordRelay;
+#[cfg(feature = "federation")]
+pub use federation::Federation;
+pub use guest::GuestPolicy;
+pub use handover::Handover;
+pub use heartbeat::{DEFAULT_IDLE_TIMEOUT, DEFAULT_PING_INTERVAL, Keepalive};
+pub use ip_filter::{IpFilter, IpRules};
+#[cfg(feature = "mqtt")]
+pub use mqtt::MqttBridge;
+#[cfg(feature = "webhooks")]
+pub use outgoing_webhook::OutgoingWebhooks;
+pub use server::{Server, run_server};
+pub use session::{
+    BANNED_CLOSE_CODE, DISCONNECTED_CLOSE_CODE, KICKED_CLOSE_CODE, SESSION_REPLACED_CLOSE_CODE,
+};
+pub use signal::{ReloadHandle, ShutdownToken};
+#[cfg(feature = "tls")]
+pub use tls::TlsTermination;
+#[cfg(feature = "xmpp")]
+pub use xmpp::XmppGateway;
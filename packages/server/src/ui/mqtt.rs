@@ -0,0 +1,186 @@
+//! MQTT bridge for devices that cannot use WebSockets.
+//!
+//! | Direction        | Topic                           | Payload                                   |
+//! |------------------|---------------------------------|-------------------------------------------|
+//! | Server → devices | `chat/rooms/{room_id}`          | Same JSON as WebSocket broadcasts         |
+//! | Devices → server | `chat/rooms/{room_id}/commands` | `{"client_id": "...", "content": "..."}`  |
+//!
+//! Outgoing messages are published by [`MqttMirrorPusher`]; this module drives the MQTT event
+//! loop and injects commands into the room through `SendMessageUseCase`, exactly as if they
+//! had been sent over WebSocket.
+//!
+//! [`MqttMirrorPusher`]: crate::infrastructure::message_pusher::MqttMirrorPusher
+
+use std::{sync::Arc, time::Duration};
+
+use rumqttc::{AsyncClient, Event, EventLoop, Packet, QoS};
+
+use crate::{
+    domain::{ClientId, MessageContent, RoomId},
+    infrastructure::{
+        dto::{
+            mqtt::MqttCommandMessage,
+            websocket::{ChatMessage, MessageType},
+        },
+        message_pusher::mqtt::command_topic,
+    },
+};
+use engawa_shared::time::get_jst_timestamp;
+
+use super::{error::MqttCommandError, signal::ShutdownToken, state::AppState};
+
+/// Delay before polling again after a connection error (the event loop reconnects on poll)
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Connection to an MQTT broker bridged to a room
+pub struct MqttBridge {
+    client: AsyncClient,
+    eventloop: EventLoop,
+    room_id: RoomId,
+}
+
+impl MqttBridge {
+    /// Create a new bridge
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - MQTT client (shared with `MqttMirrorPusher`)
+    /// * `eventloop` - Event loop paired with `client`
+    /// * `room_id` - Room whose command topic is subscribed
+    pub fn new(client: AsyncClient, eventloop: EventLoop, room_id: RoomId) -> Self {
+        Self {
+            client,
+            eventloop,
+            room_id,
+        }
+    }
+}
+
+/// Parse a command payload into domain models
+pub fn parse_command(payload: &[u8]) -> Result<(ClientId, MessageContent), MqttCommandError> {
+    let command: MqttCommandMessage = serde_json::from_slice(payload)
+        .map_err(|e| MqttCommandError::InvalidPayload(e.to_string()))?;
+    let client_id = ClientId::new(command.client_id)?;
+    let content = MessageContent::new(command.content)?;
+    Ok((client_id, content))
+}
+
+/// Run the bridge until shutdown is requested
+///
+/// Drives the MQTT event loop (which also delivers messages published by the mirror pusher)
+/// and injects commands received on the room's command topic.
+pub async fn run_bridge(bridge: MqttBridge, state: Arc<AppState>, shutdown: ShutdownToken) {
+    let MqttBridge {
+        client,
+        mut eventloop,
+        room_id,
+    } = bridge;
+    let topic = command_topic(&room_id);
+
+    loop {
+        let event = tokio::select! {
+            event = eventloop.poll() => event,
+            _ = shutdown.cancelled() => break,
+        };
+
+        match event {
+            // (Re)subscribe on every connection since the session is not persistent
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                tracing::info!("Connected to MQTT broker, subscribing to '{}'", topic);
+                if let Err(e) = client.subscribe(&topic, QoS::AtLeastOnce).await {
+                    tracing::warn!("Failed to subscribe to '{}': {}", topic, e);
+                }
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) if publish.topic == topic => {
+                inject_command(&state, &publish.payload).await;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!("MQTT connection error: {}", e);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+    }
+
+    let _ = client.disconnect().await;
+    tracing::info!("MQTT bridge stopped");
+}
+
+/// Inject a command into the room as a chat message
+async fn inject_command(state: &AppState, payload: &[u8]) {
+    let (client_id, content) = match parse_command(payload) {
+        Ok(command) => command,
+        Err(e) => {
+            tracing::warn!("Ignoring MQTT command: {}", e);
+            return;
+        }
+    };
+    let Ok(content) =
+        MessageContent::try_from(state.sanitize_profile.apply(content.as_str()).into_owned())
+    else {
+        tracing::warn!("Ignoring MQTT command with no content left after sanitizing");
+        return;
+    };
+
+    let message = ChatMessage {
+        r#type: MessageType::Chat,
+        client_id: client_id.as_str().to_string(),
+        content: content.as_str().to_string(),
+        timestamp: get_jst_timestamp(),
+        seq: None,
+        poll: None,
+        forwarded_from: None,
+    };
+    tracing::info!(
+        "Injecting message from MQTT client '{}': {}",
+        message.client_id,
+        message.content
+    );
+
+    if let Err(e) = state
+        .send_message_usecase
+        .execute(client_id, content, move |seq| {
+            message.to_json_with_seq(seq.value())
+        })
+        .await
+    {
+        tracing::warn!("Failed to inject MQTT command: {:?}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        // テスト項目: コマンド JSON から ClientId と MessageContent が得られる
+        // when (操作):
+        let result = parse_command(br#"{"client_id": "sensor-1", "content": "temp=21.5"}"#);
+
+        // then (期待する結果):
+        let (client_id, content) = result.unwrap();
+        assert_eq!(client_id.as_str(), "sensor-1");
+        assert_eq!(content.as_str(), "temp=21.5");
+    }
+
+    #[test]
+    fn test_parse_command_invalid_payload() {
+        // テスト項目: JSON でないペイロードはエラーになる
+        // when (操作):
+        let result = parse_command(b"temp=21.5");
+
+        // then (期待する結果):
+        assert!(matches!(result, Err(MqttCommandError::InvalidPayload(_))));
+    }
+
+    #[test]
+    fn test_parse_command_invalid_value() {
+        // テスト項目: 空のメッセージ内容はバリデーションエラーになる
+        // when (操作):
+        let result = parse_command(br#"{"client_id": "sensor-1", "content": ""}"#);
+
+        // then (期待する結果):
+        assert!(matches!(result, Err(MqttCommandError::InvalidValue(_))));
+    }
+}
@@ -0,0 +1,875 @@
+//! OpenAPI document of the REST API (`/api/v1/openapi.json`).
+//!
+//! The operations are listed in [`OPERATIONS`], next to the routes in `server.rs`: add an
+//! entry there when adding an endpoint. The schemas of the request and response bodies are
+//! generated from the DTOs with `schemars`, like `/api/schema`, so that the document matches
+//! what the handlers send and accept.
+
+use axum::http::StatusCode;
+use schemars::{JsonSchema, Schema, SchemaGenerator, generate::SchemaSettings};
+use serde_json::{Map, Value, json};
+
+use crate::infrastructure::dto::{http, webhook};
+
+/// Who may call an operation
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Access {
+    /// Anyone
+    Public,
+    /// `Authorization: Bearer <access token>` while authentication is enabled
+    Token,
+    /// `Authorization: Bearer <admin token>`
+    Admin,
+}
+
+/// Body of a request or a response
+#[derive(Clone, Copy)]
+enum Body {
+    /// JSON body described by a DTO
+    Json(fn(&mut SchemaGenerator) -> Schema),
+    /// Plain text body
+    Text,
+    /// HTML page
+    Html,
+    /// No body
+    Empty,
+}
+
+/// Query parameter of an operation
+struct Param {
+    name: &'static str,
+    /// `string` or `integer`
+    r#type: &'static str,
+    required: bool,
+    description: &'static str,
+}
+
+/// Endpoint of the REST API
+struct Operation {
+    method: &'static str,
+    /// Path relative to `/api/v1`, with the path parameters in braces
+    path: &'static str,
+    tag: &'static str,
+    summary: &'static str,
+    access: Access,
+    query: &'static [Param],
+    request: Option<Body>,
+    /// Status of a successful response and its body
+    response: (StatusCode, Body),
+    /// Statuses of the error responses (without a body)
+    errors: &'static [StatusCode],
+}
+
+/// Schema reference of a DTO, whose schema is added to the components
+fn dto<T: JsonSchema>(generator: &mut SchemaGenerator) -> Schema {
+    generator.subschema_for::<T>()
+}
+
+const fn param(
+    name: &'static str,
+    r#type: &'static str,
+    required: bool,
+    description: &'static str,
+) -> Param {
+    Param {
+        name,
+        r#type,
+        required,
+        description,
+    }
+}
+
+const OK: StatusCode = StatusCode::OK;
+const CREATED: StatusCode = StatusCode::CREATED;
+const BAD_REQUEST: StatusCode = StatusCode::BAD_REQUEST;
+const UNAUTHORIZED: StatusCode = StatusCode::UNAUTHORIZED;
+const FORBIDDEN: StatusCode = StatusCode::FORBIDDEN;
+const NOT_FOUND: StatusCode = StatusCode::NOT_FOUND;
+const CONFLICT: StatusCode = StatusCode::CONFLICT;
+const UNAVAILABLE: StatusCode = StatusCode::SERVICE_UNAVAILABLE;
+
+/// Operations of the REST API, in the order of the routes
+const OPERATIONS: &[Operation] = &[
+    Operation {
+        method: "get",
+        path: "/health",
+        tag: "health",
+        summary: "Status and latency of the repository and the message pusher (503 if any is down)",
+        access: Access::Public,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::HealthDto>)),
+        errors: &[UNAVAILABLE],
+    },
+    Operation {
+        method: "get",
+        path: "/schema",
+        tag: "health",
+        summary: "JSON Schemas of the WebSocket protocol and REST API messages",
+        access: Access::Public,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(|_| Schema::from(true))),
+        errors: &[],
+    },
+    Operation {
+        method: "get",
+        path: "/openapi.json",
+        tag: "health",
+        summary: "This OpenAPI document",
+        access: Access::Public,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(|_| Schema::from(true))),
+        errors: &[],
+    },
+    Operation {
+        method: "get",
+        path: "/docs",
+        tag: "health",
+        summary: "Swagger UI page browsing this OpenAPI document",
+        access: Access::Public,
+        query: &[],
+        request: None,
+        response: (OK, Body::Html),
+        errors: &[],
+    },
+    Operation {
+        method: "post",
+        path: "/auth/login",
+        tag: "auth",
+        summary: "Log in with a password and get an access token (404 if authentication is disabled)",
+        access: Access::Public,
+        query: &[],
+        request: Some(Body::Json(dto::<http::LoginRequestDto>)),
+        response: (OK, Body::Json(dto::<http::AccessTokenDto>)),
+        errors: &[UNAUTHORIZED, NOT_FOUND],
+    },
+    Operation {
+        method: "get",
+        path: "/rooms",
+        tag: "rooms",
+        summary: "List the rooms",
+        access: Access::Token,
+        query: &[
+            param(
+                "limit",
+                "integer",
+                false,
+                "Rooms per page (capped at 200, defaults to 50)",
+            ),
+            param("offset", "integer", false, "Rooms to skip"),
+            param(
+                "sort",
+                "string",
+                false,
+                "`created_at` (oldest first, default) or `participants` (most first)",
+            ),
+            param(
+                "q",
+                "string",
+                false,
+                "Case-insensitive substring of the room ID or name (slug)",
+            ),
+        ],
+        request: None,
+        response: (OK, Body::Json(dto::<http::RoomsPageDto>)),
+        errors: &[BAD_REQUEST],
+    },
+    Operation {
+        method: "post",
+        path: "/rooms",
+        tag: "rooms",
+        summary: "Create a room (404 if room creation is not enabled)",
+        access: Access::Token,
+        query: &[
+            param(
+                "class",
+                "string",
+                false,
+                "`ephemeral` (default) or `persistent`",
+            ),
+            param(
+                "capacity",
+                "integer",
+                false,
+                "Maximum number of participants",
+            ),
+            param(
+                "message_capacity",
+                "integer",
+                false,
+                "Maximum number of messages kept in history",
+            ),
+            param(
+                "slug",
+                "string",
+                false,
+                "Slug naming the room (e.g. `design`)",
+            ),
+            param(
+                "max_message_length",
+                "integer",
+                false,
+                "Maximum length of a message in bytes",
+            ),
+            param(
+                "filters",
+                "string",
+                false,
+                "Comma-separated names of the message filters applied in the room",
+            ),
+        ],
+        request: None,
+        response: (CREATED, Body::Json(dto::<http::RoomSummaryDto>)),
+        errors: &[BAD_REQUEST, NOT_FOUND, CONFLICT, UNAVAILABLE],
+    },
+    Operation {
+        method: "get",
+        path: "/rooms/{room_id}",
+        tag: "rooms",
+        summary: "Get a room with its participants and, optionally, its latest messages",
+        access: Access::Token,
+        query: ROOM_DETAIL_PARAMS,
+        request: None,
+        response: (OK, Body::Json(dto::<http::RoomDetailDto>)),
+        errors: &[BAD_REQUEST, NOT_FOUND],
+    },
+    Operation {
+        method: "get",
+        path: "/rooms/by-slug/{slug}",
+        tag: "rooms",
+        summary: "Get a room by its slug",
+        access: Access::Token,
+        query: ROOM_DETAIL_PARAMS,
+        request: None,
+        response: (OK, Body::Json(dto::<http::RoomDetailDto>)),
+        errors: &[BAD_REQUEST, NOT_FOUND],
+    },
+    Operation {
+        method: "get",
+        path: "/rooms/{room_id}/messages",
+        tag: "rooms",
+        summary: "Get the messages after `since_seq`, or export a page of the history (`format`, \
+                  `limit`, `before_timestamp`)",
+        access: Access::Token,
+        query: &[
+            param(
+                "since_seq",
+                "integer",
+                false,
+                "Latest sequence number the client has",
+            ),
+            param(
+                "format",
+                "string",
+                false,
+                "Export format (`json`, `csv` or `ndjson`)",
+            ),
+            param(
+                "limit",
+                "integer",
+                false,
+                "Number of messages in an export page",
+            ),
+            param(
+                "before_timestamp",
+                "integer",
+                false,
+                "Only messages sent before this time (Unix milliseconds) are exported",
+            ),
+        ],
+        request: None,
+        response: (OK, Body::Json(dto::<http::RoomMessagesDto>)),
+        errors: &[BAD_REQUEST, NOT_FOUND],
+    },
+    Operation {
+        method: "get",
+        path: "/rooms/{room_id}/stats",
+        tag: "rooms",
+        summary: "Get the message analytics of a room (404 if the stats are not enabled)",
+        access: Access::Token,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::RoomStatsDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "get",
+        path: "/rooms/{room_id}/search",
+        tag: "rooms",
+        summary: "Search the messages of a room, newest first (404 if search is not enabled)",
+        access: Access::Token,
+        query: &[
+            param(
+                "q",
+                "string",
+                true,
+                "Text the messages contain (case-insensitive)",
+            ),
+            param(
+                "limit",
+                "integer",
+                false,
+                "Number of messages to return (up to 50)",
+            ),
+        ],
+        request: None,
+        response: (OK, Body::Json(dto::<http::MessageSearchDto>)),
+        errors: &[BAD_REQUEST, NOT_FOUND],
+    },
+    Operation {
+        method: "post",
+        path: "/rooms/{room_id}/messages/{seq}/report",
+        tag: "moderation",
+        summary: "Report a message to the moderators (404 if moderation is not enabled)",
+        access: Access::Token,
+        query: &[],
+        request: Some(Body::Json(dto::<http::ReportMessageRequestDto>)),
+        response: (
+            StatusCode::ACCEPTED,
+            Body::Json(dto::<http::ReportAcceptedDto>),
+        ),
+        errors: &[BAD_REQUEST, NOT_FOUND, UNAVAILABLE],
+    },
+    Operation {
+        method: "get",
+        path: "/users/{client_id}/starred",
+        tag: "rooms",
+        summary: "Get the messages a client starred (404 if stars are not enabled)",
+        access: Access::Token,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::StarredMessagesDto>)),
+        errors: &[BAD_REQUEST, FORBIDDEN, NOT_FOUND],
+    },
+    Operation {
+        method: "post",
+        path: "/hooks/{token}",
+        tag: "integrations",
+        summary: "Post a message through an incoming webhook (Slack-compatible payload, JSON or \
+                  form-encoded `payload=<json>`)",
+        access: Access::Public,
+        query: &[],
+        request: Some(Body::Json(dto::<webhook::SlackWebhookPayload>)),
+        response: (OK, Body::Text),
+        errors: &[
+            BAD_REQUEST,
+            NOT_FOUND,
+            CONFLICT,
+            StatusCode::TOO_MANY_REQUESTS,
+        ],
+    },
+    Operation {
+        method: "get",
+        path: "/challenge",
+        tag: "auth",
+        summary: "Get a proof-of-work challenge to connect with (404 if challenges are disabled)",
+        access: Access::Public,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::ConnectChallengeDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "get",
+        path: "/admin/cluster",
+        tag: "admin",
+        summary: "Get the cluster topology (404 if the server is not part of a cluster)",
+        access: Access::Admin,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::ClusterDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "delete",
+        path: "/admin/users/{client_id}/data",
+        tag: "admin",
+        summary: "Erase the messages and data of a client (404 if erasure is not enabled)",
+        access: Access::Admin,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::ErasedClientDataDto>)),
+        errors: &[BAD_REQUEST, NOT_FOUND],
+    },
+    Operation {
+        method: "get",
+        path: "/admin/reports",
+        tag: "moderation",
+        summary: "Get the reported messages waiting for a moderator",
+        access: Access::Admin,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::ModerationQueueDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "post",
+        path: "/admin/reports/{report_id}/resolve",
+        tag: "moderation",
+        summary: "Resolve the reports of a message (dismiss, delete the message or ban its sender)",
+        access: Access::Admin,
+        query: &[],
+        request: Some(Body::Json(dto::<http::ResolveReportRequestDto>)),
+        response: (OK, Body::Json(dto::<http::ReportResolutionDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "post",
+        path: "/admin/kick/{client_id}",
+        tag: "moderation",
+        summary: "Close the connections of a client in every room",
+        access: Access::Admin,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::KickedClientDto>)),
+        errors: &[BAD_REQUEST, NOT_FOUND],
+    },
+    Operation {
+        method: "post",
+        path: "/admin/ban/{client_id}",
+        tag: "moderation",
+        summary: "Close the connections of a client and reject its reconnections",
+        access: Access::Admin,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::KickedClientDto>)),
+        errors: &[BAD_REQUEST, NOT_FOUND],
+    },
+    Operation {
+        method: "post",
+        path: "/admin/rooms/{room_id}/freeze",
+        tag: "admin",
+        summary: "Freeze a room: messages sent to it are rejected until it is unfrozen",
+        access: Access::Admin,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::RoomFreezeDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "delete",
+        path: "/admin/rooms/{room_id}/freeze",
+        tag: "admin",
+        summary: "Unfreeze a room",
+        access: Access::Admin,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::RoomFreezeDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "post",
+        path: "/admin/rooms/{room_id}/disconnect",
+        tag: "admin",
+        summary: "Close the connections of every participant of a room, telling them why",
+        access: Access::Admin,
+        query: &[],
+        request: Some(Body::Json(dto::<http::DisconnectRoomRequestDto>)),
+        response: (OK, Body::Json(dto::<http::DisconnectedRoomDto>)),
+        errors: &[BAD_REQUEST, NOT_FOUND],
+    },
+    Operation {
+        method: "get",
+        path: "/rooms/{room_id}/breakouts",
+        tag: "breakouts",
+        summary: "List the breakouts spawned from a room (404 if breakouts are not enabled)",
+        access: Access::Token,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::BreakoutListDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "post",
+        path: "/rooms/{room_id}/breakouts",
+        tag: "breakouts",
+        summary: "Spawn a breakout room from a room",
+        access: Access::Token,
+        query: &[],
+        request: Some(Body::Json(dto::<http::CreateBreakoutRequestDto>)),
+        response: (CREATED, Body::Json(dto::<http::BreakoutDto>)),
+        errors: &[BAD_REQUEST, NOT_FOUND, UNAVAILABLE],
+    },
+    Operation {
+        method: "get",
+        path: "/rooms/{room_id}/integrations",
+        tag: "integrations",
+        summary: "List the integrations of a room (404 if integrations are not enabled)",
+        access: Access::Admin,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::IntegrationListDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "post",
+        path: "/rooms/{room_id}/integrations",
+        tag: "integrations",
+        summary: "Add an integration to a room",
+        access: Access::Admin,
+        query: &[],
+        request: Some(Body::Json(dto::<http::IntegrationSettingsDto>)),
+        response: (CREATED, Body::Json(dto::<http::IntegrationDto>)),
+        errors: &[BAD_REQUEST, NOT_FOUND, CONFLICT],
+    },
+    Operation {
+        method: "get",
+        path: "/rooms/{room_id}/integrations/{id}",
+        tag: "integrations",
+        summary: "Get an integration of a room",
+        access: Access::Admin,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::IntegrationDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "put",
+        path: "/rooms/{room_id}/integrations/{id}",
+        tag: "integrations",
+        summary: "Replace the settings of an integration of a room",
+        access: Access::Admin,
+        query: &[],
+        request: Some(Body::Json(dto::<http::IntegrationSettingsDto>)),
+        response: (OK, Body::Json(dto::<http::IntegrationDto>)),
+        errors: &[BAD_REQUEST, NOT_FOUND, CONFLICT],
+    },
+    Operation {
+        method: "delete",
+        path: "/rooms/{room_id}/integrations/{id}",
+        tag: "integrations",
+        summary: "Remove an integration from a room",
+        access: Access::Admin,
+        query: &[],
+        request: None,
+        response: (StatusCode::NO_CONTENT, Body::Empty),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "get",
+        path: "/admin/rooms/{room_id}/pending",
+        tag: "moderation",
+        summary: "List the clients waiting for approval to join a room, oldest request first",
+        access: Access::Admin,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::PendingJoinsDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "post",
+        path: "/admin/rooms/{room_id}/pending/{client_id}/resolve",
+        tag: "moderation",
+        summary: "Approve or reject the request of a client to join a room",
+        access: Access::Admin,
+        query: &[],
+        request: Some(Body::Json(dto::<http::ResolveJoinRequestDto>)),
+        response: (StatusCode::NO_CONTENT, Body::Empty),
+        errors: &[BAD_REQUEST, NOT_FOUND],
+    },
+    Operation {
+        method: "get",
+        path: "/admin/challenge",
+        tag: "admin",
+        summary: "Get whether connecting clients must solve a proof-of-work",
+        access: Access::Admin,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::ChallengeModeDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "put",
+        path: "/admin/challenge",
+        tag: "admin",
+        summary: "Require (or stop requiring) a proof-of-work from connecting clients",
+        access: Access::Admin,
+        query: &[],
+        request: Some(Body::Json(dto::<http::ChallengeModeRequestDto>)),
+        response: (OK, Body::Json(dto::<http::ChallengeModeDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "get",
+        path: "/admin/ip-rules",
+        tag: "admin",
+        summary: "Get the IP allow and deny lists",
+        access: Access::Admin,
+        query: &[],
+        request: None,
+        response: (OK, Body::Json(dto::<http::IpRulesDto>)),
+        errors: &[NOT_FOUND],
+    },
+    Operation {
+        method: "put",
+        path: "/admin/ip-rules",
+        tag: "admin",
+        summary: "Replace the IP allow and deny lists",
+        access: Access::Admin,
+        query: &[],
+        request: Some(Body::Json(dto::<http::IpRulesDto>)),
+        response: (OK, Body::Json(dto::<http::IpRulesDto>)),
+        errors: &[BAD_REQUEST, NOT_FOUND],
+    },
+];
+
+/// Query parameters of the room detail endpoints
+const ROOM_DETAIL_PARAMS: &[Param] = &[
+    param(
+        "include",
+        "string",
+        false,
+        "Comma-separated fields to include: `participants`, `messages` (defaults to \
+         `participants`)",
+    ),
+    param(
+        "message_limit",
+        "integer",
+        false,
+        "Latest messages to include (capped at 200, defaults to 50)",
+    ),
+];
+
+/// Build the OpenAPI 3.1 document served at `/api/v1/openapi.json`
+pub fn openapi_document() -> Value {
+    let mut generator = SchemaSettings::draft2020_12()
+        .with(|settings| {
+            settings.definitions_path = "/components/schemas".into();
+            settings.meta_schema = None;
+        })
+        .into_generator();
+
+    let mut paths = Map::new();
+    for operation in OPERATIONS {
+        let item = paths
+            .entry(operation.path)
+            .or_insert_with(|| Value::Object(Map::new()));
+        item[operation.method] = operation_object(operation, &mut generator);
+    }
+
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "engawa REST API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "REST API of the engawa chat server. Connect to the chat rooms with \
+                            WebSocket at `/ws`; the messages are described in `/api/v1/schema`.",
+        },
+        "servers": [{"url": "/api/v1"}],
+        "paths": paths,
+        "components": {
+            "schemas": generator.take_definitions(true),
+            "securitySchemes": {
+                "accessToken": {
+                    "type": "http",
+                    "scheme": "bearer",
+                    "description": "Access token from `/auth/login`, required while \
+                                    authentication is enabled",
+                },
+                "adminToken": {
+                    "type": "http",
+                    "scheme": "bearer",
+                    "description": "Admin token of the server (`ADMIN_TOKEN`)",
+                },
+            },
+        },
+    })
+}
+
+fn operation_object(operation: &Operation, generator: &mut SchemaGenerator) -> Value {
+    let mut parameters: Vec<Value> = path_params(operation.path)
+        .map(|name| {
+            json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": {"type": "string"},
+            })
+        })
+        .collect();
+    parameters.extend(operation.query.iter().map(|param| {
+        json!({
+            "name": param.name,
+            "in": "query",
+            "required": param.required,
+            "description": param.description,
+            "schema": {"type": param.r#type},
+        })
+    }));
+
+    let (status, body) = operation.response;
+    let mut responses = Map::new();
+    responses.insert(
+        status.as_str().to_string(),
+        response(status, body, generator),
+    );
+    // Every protected operation rejects a missing or wrong token
+    let unauthorized = match operation.access {
+        Access::Public => None,
+        Access::Token | Access::Admin => Some(UNAUTHORIZED),
+    };
+    for &status in operation.errors.iter().chain(&unauthorized) {
+        responses.insert(
+            status.as_str().to_string(),
+            response(status, Body::Empty, generator),
+        );
+    }
+
+    let mut object = json!({
+        "tags": [operation.tag],
+        "summary": operation.summary,
+        "operationId": operation_id(operation),
+        "parameters": parameters,
+        "responses": responses,
+    });
+    if let Some(body) = operation.request
+        && let Some(content) = content(body, generator)
+    {
+        object["requestBody"] = json!({"required": true, "content": content});
+    }
+    match operation.access {
+        Access::Public => {}
+        // Required only while authentication is enabled
+        Access::Token => object["security"] = json!([{}, {"accessToken": []}]),
+        Access::Admin => object["security"] = json!([{"adminToken": []}]),
+    }
+    object
+}
+
+fn response(status: StatusCode, body: Body, generator: &mut SchemaGenerator) -> Value {
+    let mut response = json!({"description": status.canonical_reason().unwrap_or_default()});
+    if let Some(content) = content(body, generator) {
+        response["content"] = content;
+    }
+    response
+}
+
+fn content(body: Body, generator: &mut SchemaGenerator) -> Option<Value> {
+    match body {
+        Body::Json(schema) => Some(json!({"application/json": {"schema": schema(generator)}})),
+        Body::Text => Some(json!({"text/plain": {"schema": {"type": "string"}}})),
+        Body::Html => Some(json!({"text/html": {"schema": {"type": "string"}}})),
+        Body::Empty => None,
+    }
+}
+
+/// Names of the path parameters (e.g. `room_id` in `/rooms/{room_id}`)
+fn path_params(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+}
+
+/// Unique name of an operation, e.g. `get_rooms_room_id_messages`
+fn operation_id(operation: &Operation) -> String {
+    let path: Vec<&str> = operation
+        .path
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|part| !part.is_empty())
+        .collect();
+    format!("{}_{}", operation.method, path.join("_"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::ui::ip_filter::IP_RULES_PATH;
+
+    /// `(method, path)` of every route registered under `/api/v1` in `server.rs`
+    fn registered_routes() -> HashSet<(String, String)> {
+        let source = include_str!("server.rs");
+        let start = source.find("let rooms = Router::new()").unwrap();
+        let end = start + source[start..].find("let http = Router::new()").unwrap();
+
+        let mut routes = HashSet::new();
+        for call in source[start..end].split(".route(").skip(1) {
+            // The arguments end at the parenthesis closing `.route(`
+            let mut depth = 1;
+            let arguments_end = call
+                .char_indices()
+                .find(|&(_, c)| {
+                    depth += match c {
+                        '(' => 1,
+                        ')' => -1,
+                        _ => 0,
+                    };
+                    depth == 0
+                })
+                .unwrap()
+                .0;
+            let (path, handlers) = call[..arguments_end].split_once(',').unwrap();
+            let path = match path.trim() {
+                "IP_RULES_PATH" => IP_RULES_PATH,
+                literal => literal.trim_matches('"'),
+            };
+            for method in ["get", "post", "put", "delete", "patch"] {
+                let call = format!("{}(", method);
+                let registered = handlers.match_indices(&call).any(|(index, _)| {
+                    !handlers[..index].ends_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+                });
+                if registered {
+                    routes.insert((method.to_string(), path.to_string()));
+                }
+            }
+        }
+        routes
+    }
+
+    #[test]
+    fn test_openapi_document_covers_every_route() {
+        // テスト項目: /api/v1 に登録された全てのルートが OPERATIONS に記載されている
+        // given (前提条件):
+        let documented: HashSet<(String, String)> = OPERATIONS
+            .iter()
+            .map(|operation| (operation.method.to_string(), operation.path.to_string()))
+            .collect();
+
+        // when (操作):
+        let registered = registered_routes();
+
+        // then (期待する結果):
+        assert!(registered.len() > 30, "{:?}", registered);
+        let mut undocumented: Vec<_> = registered.difference(&documented).collect();
+        undocumented.sort();
+        assert!(undocumented.is_empty(), "undocumented: {:?}", undocumented);
+        let mut unregistered: Vec<_> = documented.difference(&registered).collect();
+        unregistered.sort();
+        assert!(unregistered.is_empty(), "unregistered: {:?}", unregistered);
+    }
+
+    #[test]
+    fn test_openapi_document_resolves_every_schema() {
+        // テスト項目: 全ての操作が一意な ID を持ち、参照しているスキーマが全て components に含まれる
+        // when (操作):
+        let document = openapi_document();
+
+        // then (期待する結果):
+        let ids: HashSet<String> = OPERATIONS.iter().map(operation_id).collect();
+        assert_eq!(ids.len(), OPERATIONS.len());
+
+        let text = document.to_string();
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+        for reference in text.split("\"$ref\":\"").skip(1) {
+            let name = reference
+                .split('"')
+                .next()
+                .unwrap()
+                .strip_prefix("#/components/schemas/")
+                .unwrap();
+            assert!(schemas.contains_key(name), "{}", name);
+        }
+        let search = &document["paths"]["/rooms/{room_id}/search"]["get"];
+        assert_eq!(search["parameters"][0]["name"], "room_id");
+        assert_eq!(search["parameters"][1]["name"], "q");
+        assert_eq!(
+            search["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/MessageSearchDto"
+        );
+        assert!(search["responses"]["401"].is_object());
+        assert!(document["paths"]["/admin/reports"]["get"]["responses"]["401"].is_object());
+        assert!(document["paths"]["/health"]["get"]["responses"]["401"].is_null());
+    }
+}
@@ -0,0 +1,80 @@
+//! Outgoing webhook delivery.
+//!
+//! Chat messages queued by [`OutgoingWebhookPusher`] are posted as JSON to every outgoing
+//! webhook configured for their room at the time of delivery. Messages are delivered one at a
+//! time in the order they were sent; failed deliveries are logged and not retried.
+//!
+//! [`OutgoingWebhookPusher`]: crate::infrastructure::message_pusher::OutgoingWebhookPusher
+
+use std::{sync::Arc, time::Duration};
+
+use tokio::sync::mpsc;
+
+use crate::infrastructure::message_pusher::webhook::OutgoingWebhookPost;
+
+use super::{signal::ShutdownToken, state::AppState};
+
+/// Time allowed for an endpoint to answer a delivery
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delivery of the chat messages of every room to their outgoing webhooks
+pub struct OutgoingWebhooks {
+    http: reqwest::Client,
+    outbound: mpsc::Receiver<OutgoingWebhookPost>,
+}
+
+impl OutgoingWebhooks {
+    /// Create a new delivery from the queue filled by `OutgoingWebhookPusher`
+    pub fn new(outbound: mpsc::Receiver<OutgoingWebhookPost>) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .build()
+            .expect("HTTP client should be built");
+        Self { http, outbound }
+    }
+}
+
+/// Run the delivery until shutdown is requested
+pub async fn run_delivery(
+    webhooks: OutgoingWebhooks,
+    state: Arc<AppState>,
+    shutdown: ShutdownToken,
+) {
+    let OutgoingWebhooks { http, mut outbound } = webhooks;
+    loop {
+        let post = tokio::select! {
+            post = outbound.recv() => match post {
+                Some(post) => post,
+                None => break,
+            },
+            _ = shutdown.cancelled() => break,
+        };
+        // Integrations are disabled without the admin token; the queue is drained regardless
+        let Some(usecase) = &state.manage_integrations_usecase else {
+            continue;
+        };
+        let urls = match usecase.outgoing_webhook_urls(&post.room_id).await {
+            Ok(urls) => urls,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to get the outgoing webhooks of room '{}': {}",
+                    post.room_id,
+                    e
+                );
+                continue;
+            }
+        };
+        for url in urls {
+            let result = http
+                .post(&url)
+                .json(&post.payload)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(e) = result {
+                tracing::warn!("Failed to deliver message to outgoing webhook: {}", e);
+            }
+        }
+    }
+    tracing::info!("Outgoing webhook delivery stopped");
+}
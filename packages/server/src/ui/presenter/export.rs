@@ -0,0 +1,142 @@
+//! Message history exports in JSON, CSV and NDJSON.
+//!
+//! Each message is encoded on its own so that the response body is streamed chunk by chunk
+//! instead of being serialized at once. The messages are the same [`MessageDto`] as the other
+//! HTTP endpoints; CSV joins the tags with `;`.
+
+use std::str::FromStr;
+
+use crate::infrastructure::dto::http::MessageDto;
+
+/// Header row of the CSV export
+const CSV_HEADER: &str = "seq,client_id,content,timestamp,tags\r\n";
+
+/// Format of a message history export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// One JSON array of messages
+    #[default]
+    Json,
+    /// Comma-separated values with a header row (RFC 4180)
+    Csv,
+    /// One JSON message per line
+    Ndjson,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "ndjson" => Ok(Self::Ndjson),
+            _ => Err(format!("unknown export format '{}'", s)),
+        }
+    }
+}
+
+impl ExportFormat {
+    /// `Content-Type` of the response body
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    /// Encode the messages as chunks of the response body, in order
+    pub fn encode(self, messages: Vec<MessageDto>) -> impl Iterator<Item = String> + Send {
+        let header = match self {
+            Self::Json => Some("[".to_string()),
+            Self::Csv => Some(CSV_HEADER.to_string()),
+            Self::Ndjson => None,
+        };
+        let footer = match self {
+            Self::Json => Some("]".to_string()),
+            Self::Csv | Self::Ndjson => None,
+        };
+        let rows = messages
+            .into_iter()
+            .enumerate()
+            .map(move |(i, message)| match self {
+                Self::Json => {
+                    let separator = if i == 0 { "" } else { "," };
+                    format!("{}{}", separator, serde_json::to_string(&message).unwrap())
+                }
+                Self::Csv => csv_row(&message),
+                Self::Ndjson => format!("{}\n", serde_json::to_string(&message).unwrap()),
+            });
+        header.into_iter().chain(rows).chain(footer)
+    }
+}
+
+/// One CSV row of a message
+fn csv_row(message: &MessageDto) -> String {
+    format!(
+        "{},{},{},{},{}\r\n",
+        message.seq,
+        csv_field(&message.client_id),
+        csv_field(&message.content),
+        csv_field(&message.timestamp),
+        csv_field(&message.tags.join(";"))
+    )
+}
+
+/// Quote a CSV field if it contains a separator, a quote or a line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(seq: u64, content: &str) -> MessageDto {
+        MessageDto {
+            seq,
+            client_id: "alice".to_string(),
+            content: content.to_string(),
+            timestamp: "2023-01-01T00:00:00.000+09:00".to_string(),
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_encode_each_format() {
+        // テスト項目: JSON は配列、NDJSON は 1 行 1 メッセージ、CSV は見出しの後に区切り文字と引用符をエスケープした行になる
+        // given (前提条件):
+        let messages = || vec![message(1, "hi"), message(2, "a, \"quoted\"\nline")];
+
+        // when (操作):
+        let json: String = ExportFormat::Json.encode(messages()).collect();
+        let ndjson: String = ExportFormat::Ndjson.encode(messages()).collect();
+        let csv: String = ExportFormat::Csv.encode(messages()).collect();
+        let empty: String = ExportFormat::Json.encode(Vec::new()).collect();
+
+        // then (期待する結果):
+        let parsed: Vec<MessageDto> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].content, "a, \"quoted\"\nline");
+        assert_eq!(ndjson.lines().count(), 2);
+        assert!(
+            ndjson
+                .lines()
+                .all(|line| serde_json::from_str::<MessageDto>(line).is_ok())
+        );
+        assert_eq!(
+            csv,
+            "seq,client_id,content,timestamp,tags\r\n\
+             1,alice,hi,2023-01-01T00:00:00.000+09:00,\r\n\
+             2,alice,\"a, \"\"quoted\"\"\nline\",2023-01-01T00:00:00.000+09:00,\r\n"
+        );
+        assert_eq!(empty, "[]");
+        assert_eq!("ndjson".parse(), Ok(ExportFormat::Ndjson));
+        assert!("xml".parse::<ExportFormat>().is_err());
+    }
+}
@@ -0,0 +1,396 @@
+//! Conversions from domain models and use case results to HTTP API response DTOs.
+
+use engawa_shared::time::timestamp_to_jst_rfc3339;
+
+use crate::{
+    domain::{
+        Activity, Breakout, ChatMessage, ClientId, CronSchedule, DisplayName, Integration,
+        IntegrationKind, MessageContent, MessageTag, Participant, Room, RoomSlug, ValueObjectError,
+    },
+    infrastructure::dto::http::{
+        BreakoutDto, DependencyHealthDto, ErasedRoomMessagesDto, HealthDto, IntegrationDto,
+        IntegrationSettingsDto, JoinDecisionDto, MessageDto, MessageReportDto, ModerationActionDto,
+        ModerationItemDto, ParticipantDetailDto, ReportResolutionDto, RoomDetailDto, RoomStateDto,
+        RoomStatsDto, RoomSummaryDto, StarredMessageDto, TagCountDto,
+    },
+    ui::approval::JoinDecision,
+    usecase::{
+        DependencyHealth, DependencyStatus, ErasedMessages, HealthReport, MessageReport,
+        ModerationAction, ModerationItem, ReportResolution, RoomDetail, RoomListing, RoomStats,
+        StarredMessage,
+    },
+};
+
+impl From<Participant> for ParticipantDetailDto {
+    fn from(participant: Participant) -> Self {
+        Self {
+            guest: participant.id.is_guest(),
+            client_id: participant.id.into_string(),
+            connected_at: timestamp_to_jst_rfc3339(participant.connected_at.value()),
+            display_name: participant.display_name.map(DisplayName::into_string),
+            activity: participant.activity.map(Activity::into_string),
+        }
+    }
+}
+
+impl From<ChatMessage> for MessageDto {
+    fn from(message: ChatMessage) -> Self {
+        Self {
+            seq: message.seq.value(),
+            client_id: message.from.into_string(),
+            content: message.content.into_string(),
+            timestamp: timestamp_to_jst_rfc3339(message.timestamp.value()),
+            tags: message
+                .tags
+                .into_iter()
+                .map(MessageTag::into_string)
+                .collect(),
+        }
+    }
+}
+
+impl From<StarredMessage> for StarredMessageDto {
+    fn from(starred: StarredMessage) -> Self {
+        Self {
+            room_id: starred.room_id.into_string(),
+            message: starred.message.into(),
+            starred_at: timestamp_to_jst_rfc3339(starred.starred_at.value()),
+        }
+    }
+}
+
+impl From<ErasedMessages> for ErasedRoomMessagesDto {
+    fn from(erased: ErasedMessages) -> Self {
+        Self {
+            room_id: erased.room_id.into_string(),
+            deleted_messages: erased.seqs.into_iter().map(|seq| seq.value()).collect(),
+        }
+    }
+}
+
+impl From<MessageReport> for MessageReportDto {
+    fn from(report: MessageReport) -> Self {
+        Self {
+            id: report.id,
+            reporter: report.reporter.into_string(),
+            reason: report.reason,
+            reported_at: timestamp_to_jst_rfc3339(report.reported_at.value()),
+        }
+    }
+}
+
+impl From<ModerationItem> for ModerationItemDto {
+    fn from(item: ModerationItem) -> Self {
+        Self {
+            room_id: item.room_id.as_str().to_string(),
+            message: item.message.into(),
+            context: item.context.into_iter().map(Into::into).collect(),
+            reports: item.reports.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<Breakout> for BreakoutDto {
+    fn from(breakout: Breakout) -> Self {
+        Self {
+            room_id: breakout.room_id.as_str().to_string(),
+            parent_id: breakout.parent_id.as_str().to_string(),
+            name: breakout.name,
+            members: breakout
+                .members
+                .into_iter()
+                .map(ClientId::into_string)
+                .collect(),
+            created_at: timestamp_to_jst_rfc3339(breakout.created_at.value()),
+            archived_at: breakout
+                .archived_at
+                .map(|archived_at| timestamp_to_jst_rfc3339(archived_at.value())),
+        }
+    }
+}
+
+impl From<JoinDecisionDto> for JoinDecision {
+    fn from(decision: JoinDecisionDto) -> Self {
+        match decision {
+            JoinDecisionDto::Approve => Self::Approve,
+            JoinDecisionDto::Reject => Self::Reject,
+        }
+    }
+}
+
+impl From<ModerationActionDto> for ModerationAction {
+    fn from(action: ModerationActionDto) -> Self {
+        match action {
+            ModerationActionDto::Dismiss => Self::Dismiss,
+            ModerationActionDto::Delete => Self::Delete,
+            ModerationActionDto::Ban => Self::Ban,
+        }
+    }
+}
+
+impl From<ModerationAction> for ModerationActionDto {
+    fn from(action: ModerationAction) -> Self {
+        match action {
+            ModerationAction::Dismiss => Self::Dismiss,
+            ModerationAction::Delete => Self::Delete,
+            ModerationAction::Ban => Self::Ban,
+        }
+    }
+}
+
+impl From<ReportResolution> for ReportResolutionDto {
+    fn from(resolution: ReportResolution) -> Self {
+        Self {
+            action: resolution.action.into(),
+            room_id: resolution.room_id.into_string(),
+            seq: resolution.seq.value(),
+            client_id: resolution.sender.into_string(),
+            resolved_reports: resolution.report_ids,
+        }
+    }
+}
+
+impl From<Integration> for IntegrationDto {
+    fn from(integration: Integration) -> Self {
+        Self {
+            id: integration.id,
+            room_id: integration.room_id.as_str().to_string(),
+            settings: integration.kind.into(),
+            created_at: timestamp_to_jst_rfc3339(integration.created_at.value()),
+        }
+    }
+}
+
+impl From<IntegrationKind> for IntegrationSettingsDto {
+    fn from(kind: IntegrationKind) -> Self {
+        match kind {
+            IntegrationKind::OutgoingWebhook { url } => Self::OutgoingWebhook { url },
+            IntegrationKind::IncomingWebhook { token } => {
+                Self::IncomingWebhook { token: Some(token) }
+            }
+            IntegrationKind::Bridge { bridge, target } => Self::Bridge {
+                bridge: bridge.to_string(),
+                target,
+            },
+            IntegrationKind::WelcomeMessage { text } => Self::WelcomeMessage {
+                text: text.into_string(),
+            },
+            IntegrationKind::JoinApproval => Self::JoinApproval,
+            IntegrationKind::RecurringAnnouncement { schedule, text } => {
+                Self::RecurringAnnouncement {
+                    schedule: schedule.into_string(),
+                    text: text.into_string(),
+                }
+            }
+        }
+    }
+}
+
+impl TryFrom<IntegrationSettingsDto> for IntegrationKind {
+    type Error = ValueObjectError;
+
+    /// An omitted incoming webhook token becomes empty, which the use case replaces with a
+    /// generated one.
+    fn try_from(settings: IntegrationSettingsDto) -> Result<Self, Self::Error> {
+        Ok(match settings {
+            IntegrationSettingsDto::OutgoingWebhook { url } => Self::OutgoingWebhook { url },
+            IntegrationSettingsDto::IncomingWebhook { token } => Self::IncomingWebhook {
+                token: token.unwrap_or_default(),
+            },
+            IntegrationSettingsDto::Bridge { bridge, target } => Self::Bridge {
+                bridge: bridge.parse()?,
+                target,
+            },
+            IntegrationSettingsDto::WelcomeMessage { text } => Self::WelcomeMessage {
+                text: MessageContent::new(text)?,
+            },
+            IntegrationSettingsDto::JoinApproval => Self::JoinApproval,
+            IntegrationSettingsDto::RecurringAnnouncement { schedule, text } => {
+                Self::RecurringAnnouncement {
+                    schedule: CronSchedule::new(schedule)?,
+                    text: MessageContent::new(text)?,
+                }
+            }
+        })
+    }
+}
+
+impl From<RoomStats> for RoomStatsDto {
+    fn from(stats: RoomStats) -> Self {
+        Self {
+            room_id: stats.room_id.as_str().to_string(),
+            message_count: stats.message_count,
+            tagged_message_count: stats.tagged_message_count,
+            tags: stats
+                .tags
+                .into_iter()
+                .map(|(tag, count)| TagCountDto {
+                    tag: tag.into_string(),
+                    count,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<RoomListing> for RoomSummaryDto {
+    fn from(room: RoomListing) -> Self {
+        let metadata = room.metadata;
+        Self {
+            id: metadata.id.as_str().to_string(),
+            slug: metadata.slug.map(RoomSlug::into_string),
+            class: metadata.class.to_string(),
+            participants: room
+                .participants
+                .into_iter()
+                .map(|id| id.into_string())
+                .collect(),
+            created_at: timestamp_to_jst_rfc3339(metadata.created_at.value()),
+            participant_count: metadata.participant_count,
+            capacity: metadata.participant_capacity,
+            max_message_length: metadata.max_message_length,
+            frozen: metadata.frozen,
+            message_count: metadata.message_count,
+            last_activity_at: timestamp_to_jst_rfc3339(metadata.last_activity_at.value()),
+        }
+    }
+}
+
+impl From<RoomDetail> for RoomDetailDto {
+    fn from(detail: RoomDetail) -> Self {
+        let metadata = detail.metadata;
+        Self {
+            id: metadata.id.as_str().to_string(),
+            slug: metadata.slug.map(RoomSlug::into_string),
+            locale: metadata.locale.to_string(),
+            created_at: timestamp_to_jst_rfc3339(metadata.created_at.value()),
+            participant_count: metadata.participant_count,
+            last_seq: metadata.last_seq.value(),
+            participants: detail
+                .participants
+                .map(|participants| participants.into_iter().map(Into::into).collect()),
+            messages: detail
+                .messages
+                .map(|messages| messages.into_iter().map(Into::into).collect()),
+        }
+    }
+}
+
+impl From<Room> for RoomStateDto {
+    fn from(room: Room) -> Self {
+        Self {
+            id: room.id.as_str().to_string(),
+            created_at: timestamp_to_jst_rfc3339(room.created_at.value()),
+            participant_capacity: room.participant_capacity,
+            message_capacity: room.message_capacity,
+            last_seq: room.last_seq.value(),
+            participants: room.participants.into_iter().map(Into::into).collect(),
+            messages: room.messages.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<DependencyHealth> for DependencyHealthDto {
+    fn from(dependency: DependencyHealth) -> Self {
+        let (status, error) = match dependency.status {
+            DependencyStatus::Up => ("up", None),
+            DependencyStatus::Down(reason) => ("down", Some(reason)),
+        };
+        Self {
+            name: dependency.name.to_string(),
+            status: status.to_string(),
+            latency_ms: dependency.latency.as_secs_f64() * 1000.0,
+            error,
+        }
+    }
+}
+
+impl From<HealthReport> for HealthDto {
+    fn from(report: HealthReport) -> Self {
+        let status = if report.is_healthy() {
+            "ok"
+        } else {
+            "unavailable"
+        };
+        Self {
+            status: status.to_string(),
+            dependencies: report.dependencies.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::domain::{ClientId, MessageContent, RoomId, Timestamp};
+
+    #[test]
+    fn test_room_to_state_dto() {
+        // テスト項目: Room の全ての状態が文字列の日時と DTO のフィールド名で変換される
+        // given (前提条件):
+        let mut room = Room::new(
+            RoomId::new("aaaaaaaa-0000-4000-8000-000000000000".to_string()).unwrap(),
+            Timestamp::new(0),
+        );
+        room.add_participant(Participant::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            Timestamp::new(1000),
+        ))
+        .unwrap();
+        room.add_message(ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(2000),
+        ))
+        .unwrap();
+
+        // when (操作):
+        let dto = RoomStateDto::from(room);
+
+        // then (期待する結果):
+        assert_eq!(dto.id, "aaaaaaaa-0000-4000-8000-000000000000");
+        assert_eq!(dto.created_at, "1970-01-01T09:00:00+09:00");
+        assert_eq!(dto.last_seq, 1);
+        assert_eq!(dto.participants[0].client_id, "alice");
+        assert_eq!(
+            dto.participants[0].connected_at,
+            "1970-01-01T09:00:01+09:00"
+        );
+        assert_eq!(dto.messages[0].seq, 1);
+        assert_eq!(dto.messages[0].client_id, "alice");
+        assert_eq!(dto.messages[0].content, "Hello!");
+    }
+
+    #[test]
+    fn test_health_report_to_dto() {
+        // テスト項目: 停止中の依存先があれば unavailable になり、停止理由が含まれる
+        // given (前提条件):
+        let report = HealthReport {
+            dependencies: vec![
+                DependencyHealth {
+                    name: "repository",
+                    status: DependencyStatus::Up,
+                    latency: Duration::from_millis(2),
+                },
+                DependencyHealth {
+                    name: "message_pusher",
+                    status: DependencyStatus::Down("timed out".to_string()),
+                    latency: Duration::from_millis(500),
+                },
+            ],
+        };
+
+        // when (操作):
+        let dto = HealthDto::from(report);
+
+        // then (期待する結果):
+        assert_eq!(dto.status, "unavailable");
+        assert_eq!(dto.dependencies[0].status, "up");
+        assert_eq!(dto.dependencies[0].error, None);
+        assert_eq!(dto.dependencies[1].status, "down");
+        assert_eq!(dto.dependencies[1].error.as_deref(), Some("timed out"));
+        assert_eq!(dto.dependencies[1].latency_ms, 500.0);
+    }
+}
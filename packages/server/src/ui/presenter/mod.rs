@@ -0,0 +1,13 @@
+//! Presenters converting domain models and use case results into response DTOs.
+//!
+//! Handlers build responses only through these `From` conversions, so the wire formats
+//! (timestamp formatting, field names, casing) are defined by the DTOs in
+//! `infrastructure::dto` and do not change when a domain struct does.
+//!
+//! - `export`: message history exports of the HTTP API (JSON, CSV and NDJSON)
+//! - `http`: HTTP API responses (timestamps as JST RFC 3339 strings)
+//! - `websocket`: WebSocket messages (timestamps as Unix milliseconds)
+
+pub mod export;
+pub mod http;
+pub mod websocket;
@@ -0,0 +1,367 @@
+//! Conversions from domain entities to WebSocket message DTOs.
+
+use crate::{
+    domain::{
+        Activity, ChatMessage, ClientId, DisplayName, ForwardedFrom, Locale, MessageContent,
+        MessageRejection, Participant, Poll, RoomId, RoomSlug, SequenceNumber, Timestamp,
+    },
+    infrastructure::{dto::websocket as dto, error::InboundMessageError, i18n::SystemText},
+    usecase::RoomListing,
+};
+
+impl From<ChatMessage> for dto::ChatMessage {
+    fn from(model: ChatMessage) -> Self {
+        Self {
+            r#type: if model.poll.is_some() {
+                dto::MessageType::Poll
+            } else {
+                dto::MessageType::Chat
+            },
+            client_id: model.from.into_string(),
+            content: model.content.into_string(),
+            timestamp: model.timestamp.value(),
+            seq: Some(model.seq.value()),
+            poll: model.poll.as_ref().map(dto::PollInfo::from),
+            forwarded_from: model.forwarded_from.map(dto::ForwardedFromInfo::from),
+        }
+    }
+}
+
+impl From<ForwardedFrom> for dto::ForwardedFromInfo {
+    fn from(model: ForwardedFrom) -> Self {
+        Self {
+            room_id: model.room_id.as_str().to_string(),
+            client_id: model.from.into_string(),
+            timestamp: model.timestamp.value(),
+        }
+    }
+}
+
+impl From<&Poll> for dto::PollInfo {
+    fn from(poll: &Poll) -> Self {
+        Self {
+            options: poll_options(poll),
+        }
+    }
+}
+
+/// Options of a poll with the number of votes for each
+fn poll_options(poll: &Poll) -> Vec<dto::PollOptionInfo> {
+    poll.options
+        .iter()
+        .zip(poll.tally())
+        .map(|(option, votes)| dto::PollOptionInfo {
+            label: option.as_str().to_string(),
+            votes,
+        })
+        .collect()
+}
+
+/// `poll-updated` message carrying the tally of a poll after a vote
+pub fn poll_updated(seq: SequenceNumber, poll: &Poll) -> dto::PollUpdatedMessage {
+    dto::PollUpdatedMessage {
+        r#type: dto::MessageType::PollUpdated,
+        seq: seq.value(),
+        options: poll_options(poll),
+    }
+}
+
+/// `participant-renamed` message announcing the display name a participant set (or cleared)
+pub fn participant_renamed(
+    client_id: &ClientId,
+    display_name: Option<&DisplayName>,
+) -> dto::ParticipantRenamedMessage {
+    dto::ParticipantRenamedMessage {
+        r#type: dto::MessageType::ParticipantRenamed,
+        client_id: client_id.as_str().to_string(),
+        display_name: display_name.map(|name| name.as_str().to_string()),
+    }
+}
+
+/// `activity-updated` message announcing the activity a participant set (or cleared)
+pub fn activity_updated(
+    client_id: &ClientId,
+    activity: Option<&Activity>,
+) -> dto::ActivityUpdatedMessage {
+    dto::ActivityUpdatedMessage {
+        r#type: dto::MessageType::ActivityUpdated,
+        client_id: client_id.as_str().to_string(),
+        activity: activity.map(|activity| activity.as_str().to_string()),
+    }
+}
+
+impl From<Participant> for dto::ParticipantInfo {
+    fn from(model: Participant) -> Self {
+        Self {
+            guest: model.id.is_guest(),
+            client_id: model.id.into_string(),
+            connected_at: model.connected_at.value(),
+            display_name: model.display_name.map(DisplayName::into_string),
+            activity: model.activity.map(Activity::into_string),
+        }
+    }
+}
+
+impl From<Participant> for dto::ParticipantJoinedMessage {
+    fn from(model: Participant) -> Self {
+        Self {
+            r#type: dto::MessageType::ParticipantJoined,
+            guest: model.id.is_guest(),
+            client_id: model.id.into_string(),
+            connected_at: model.connected_at.value(),
+            display_name: model.display_name.map(DisplayName::into_string),
+            notice: None,
+        }
+    }
+}
+
+/// Participant-joined notification with the notice in the room's locale
+pub fn participant_joined(
+    participant: Participant,
+    locale: Locale,
+) -> dto::ParticipantJoinedMessage {
+    let notice = SystemText::ParticipantJoined {
+        client_id: participant.id.as_str(),
+    }
+    .localize(locale);
+    dto::ParticipantJoinedMessage {
+        notice: Some(notice),
+        ..participant.into()
+    }
+}
+
+/// Welcome message of the room for a participant that has just joined
+pub fn welcome(room_id: &RoomId, text: MessageContent) -> dto::WelcomeMessage {
+    dto::WelcomeMessage {
+        r#type: dto::MessageType::Welcome,
+        room_id: room_id.as_str().to_string(),
+        content: text.into_string(),
+    }
+}
+
+/// Waiting notice for a client joining a room that requires approval
+pub fn join_pending(room_id: &RoomId, locale: Locale) -> dto::JoinPendingMessage {
+    dto::JoinPendingMessage {
+        r#type: dto::MessageType::JoinPending,
+        room_id: room_id.as_str().to_string(),
+        message: SystemText::JoinPending.localize(locale),
+    }
+}
+
+/// Request to join a room that requires approval, for its participants
+pub fn join_requested(
+    room_id: &RoomId,
+    client_id: &ClientId,
+    requested_at: Timestamp,
+) -> dto::JoinRequestedMessage {
+    dto::JoinRequestedMessage {
+        r#type: dto::MessageType::JoinRequested,
+        room_id: room_id.as_str().to_string(),
+        client_id: client_id.as_str().to_string(),
+        requested_at: requested_at.value(),
+    }
+}
+
+/// Participant-left notification with the notice in the room's locale
+pub fn participant_left(
+    client_id: &ClientId,
+    disconnected_at: Timestamp,
+    locale: Locale,
+) -> dto::ParticipantLeftMessage {
+    dto::ParticipantLeftMessage {
+        r#type: dto::MessageType::ParticipantLeft,
+        client_id: client_id.as_str().to_string(),
+        disconnected_at: disconnected_at.value(),
+        notice: Some(
+            SystemText::ParticipantLeft {
+                client_id: client_id.as_str(),
+            }
+            .localize(locale),
+        ),
+    }
+}
+
+impl From<RoomListing> for dto::RoomInfo {
+    fn from(listing: RoomListing) -> Self {
+        let metadata = listing.metadata;
+        Self {
+            room_id: metadata.id.as_str().to_string(),
+            name: metadata
+                .slug
+                .map(RoomSlug::into_string)
+                .unwrap_or_else(|| metadata.id.into_string()),
+            topic: None,
+            participant_count: metadata.participant_count,
+        }
+    }
+}
+
+impl From<&InboundMessageError> for dto::ErrorMessage {
+    fn from(error: &InboundMessageError) -> Self {
+        Self {
+            r#type: dto::MessageType::Error,
+            code: error.code().to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<MessageRejection> for InboundMessageError {
+    fn from(rejection: MessageRejection) -> Self {
+        Self::MessageRejected {
+            filter: rejection.filter.to_string(),
+            reason: rejection.reason,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{MessageContent, Room, RoomId, RoomIdFactory, SequenceNumber};
+
+    #[test]
+    fn test_domain_chat_message_to_dto() {
+        // テスト項目: ドメインエンティティの ChatMessage が DTO に変換される
+        // given (前提条件):
+        let domain_msg = ChatMessage {
+            seq: SequenceNumber::new(4),
+            from: ClientId::new("bob".to_string()).unwrap(),
+            content: MessageContent::new("Hi!".to_string()).unwrap(),
+            timestamp: Timestamp::new(2000),
+            tags: Vec::new(),
+            poll: None,
+            forwarded_from: None,
+        };
+
+        // when (操作):
+        let dto_msg: dto::ChatMessage = domain_msg.into();
+
+        // then (期待する結果):
+        assert_eq!(dto_msg.client_id, "bob");
+        assert_eq!(dto_msg.content, "Hi!");
+        assert_eq!(dto_msg.timestamp, 2000);
+        assert_eq!(dto_msg.seq, Some(4));
+        assert!(matches!(dto_msg.r#type, dto::MessageType::Chat));
+    }
+
+    #[test]
+    fn test_guest_participant_to_dto() {
+        // テスト項目: サーバが割り当てた ID の参加者はゲストとして変換される
+        // given (前提条件):
+        let guest = Participant {
+            id: ClientId::new("guest-7f3a".to_string()).unwrap(),
+            connected_at: Timestamp::new(2000),
+            display_name: None,
+            activity: None,
+        };
+
+        // when (操作):
+        let info: dto::ParticipantInfo = guest.clone().into();
+        let joined: dto::ParticipantJoinedMessage = guest.into();
+
+        // then (期待する結果):
+        assert!(info.guest);
+        assert!(joined.guest);
+    }
+
+    #[test]
+    fn test_room_listing_to_room_info() {
+        // テスト項目: ルーム一覧の 1 件がロビーのルーム情報に変換される
+        // given (前提条件):
+        let room_id = "550e8400-e29b-41d4-a716-446655440000";
+        let mut room = Room::new(
+            RoomId::new(room_id.to_string()).unwrap(),
+            Timestamp::new(1000),
+        );
+        room.participants.push(Participant {
+            id: ClientId::new("alice".to_string()).unwrap(),
+            connected_at: Timestamp::new(2000),
+            display_name: None,
+            activity: None,
+        });
+        let listing = RoomListing {
+            metadata: room.metadata(),
+            participants: vec![ClientId::new("alice".to_string()).unwrap()],
+        };
+
+        // when (操作):
+        let info: dto::RoomInfo = listing.into();
+
+        // then (期待する結果):
+        assert_eq!(info.room_id, room_id);
+        assert_eq!(info.name, room_id);
+        assert_eq!(info.topic, None);
+        assert_eq!(info.participant_count, 1);
+    }
+
+    #[test]
+    fn test_room_listing_with_slug_to_room_info() {
+        // テスト項目: スラッグが設定されたルームはスラッグを名前として変換される
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        room.slug = Some(RoomSlug::new("general".to_string()).unwrap());
+        let listing = RoomListing {
+            metadata: room.metadata(),
+            participants: vec![],
+        };
+
+        // when (操作):
+        let info: dto::RoomInfo = listing.into();
+
+        // then (期待する結果):
+        assert_eq!(info.name, "general");
+        assert_eq!(info.room_id, room.id.as_str());
+    }
+
+    #[test]
+    fn test_participant_notices_in_room_locale() {
+        // テスト項目: 入室・退室の通知にルームのロケールの通知文が付く
+        // given (前提条件):
+        let alice = Participant {
+            id: ClientId::new("alice".to_string()).unwrap(),
+            connected_at: Timestamp::new(2000),
+            display_name: None,
+            activity: None,
+        };
+
+        // when (操作):
+        let joined = participant_joined(alice.clone(), Locale::Ja);
+        let left = participant_left(&alice.id, Timestamp::new(3000), Locale::En);
+
+        // then (期待する結果):
+        assert_eq!(joined.client_id, "alice");
+        assert_eq!(joined.notice.as_deref(), Some("alice さんが入室しました"));
+        assert_eq!(left.disconnected_at, 3000);
+        assert_eq!(left.notice.as_deref(), Some("alice left the room"));
+    }
+
+    #[test]
+    fn test_domain_participant_to_dto() {
+        // テスト項目: ドメインエンティティの Participant が DTO に変換される
+        // given (前提条件):
+        let domain_participant = Participant {
+            id: ClientId::new("bob".to_string()).unwrap(),
+            connected_at: Timestamp::new(2000),
+            display_name: Some(DisplayName::new("Bob".to_string()).unwrap()),
+            activity: Some(Activity::new("reviewing PR #42".to_string()).unwrap()),
+        };
+
+        // when (操作):
+        let dto_participant: dto::ParticipantInfo = domain_participant.clone().into();
+        let joined: dto::ParticipantJoinedMessage = domain_participant.into();
+
+        // then (期待する結果):
+        assert_eq!(dto_participant.client_id, "bob");
+        assert_eq!(dto_participant.connected_at, 2000);
+        assert_eq!(
+            dto_participant.activity.as_deref(),
+            Some("reviewing PR #42")
+        );
+        assert_eq!(joined.client_id, "bob");
+        assert_eq!(joined.connected_at, 2000);
+        assert_eq!(joined.display_name.as_deref(), Some("Bob"));
+        assert!(!joined.guest);
+        assert!(matches!(joined.r#type, dto::MessageType::ParticipantJoined));
+    }
+}
@@ -0,0 +1,24 @@
+//! Demo data seeded at startup (`--seed demo`).
+//!
+//! The seeded bot participants have no WebSocket connection; a background task reads and
+//! discards whatever is broadcast to them until the server shuts down.
+
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use super::signal::ShutdownToken;
+
+/// Discard the messages delivered to the demo bots until the server shuts down
+pub async fn run_demo_bots(mut inbox: mpsc::UnboundedReceiver<Arc<str>>, shutdown: ShutdownToken) {
+    loop {
+        tokio::select! {
+            message = inbox.recv() => {
+                if message.is_none() {
+                    return;
+                }
+            }
+            _ = shutdown.cancelled() => return,
+        }
+    }
+}
@@ -1,18 +1,79 @@
 //! Server execution logic.
 
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
-use axum::{Router, routing::get};
+use axum::{
+    Router, middleware,
+    routing::{delete, get, post},
+};
+use chrono::NaiveTime;
+use tokio::{net::TcpListener, task::JoinHandle};
+use tower_http::compression::CompressionLayer;
+
+use engawa_shared::time::get_jst_timestamp;
 
-use crate::usecase::{
-    ConnectParticipantUseCase, DisconnectParticipantUseCase, GetRoomDetailUseCase,
-    GetRoomStateUseCase, GetRoomsUseCase, SendMessageUseCase,
+use crate::{
+    domain::{Locale, Timestamp},
+    infrastructure::{
+        dedup::DEFAULT_DEDUP_WINDOW, metrics::Metrics, sanitize::SanitizeProfile, wire_log::WireLog,
+    },
+    usecase::{
+        BroadcastTypingUseCase, CheckHealthUseCase, ComposeDailyDigestUseCase,
+        ConnectParticipantUseCase, CreateRoomUseCase, DisconnectParticipantUseCase,
+        EnforceMemoryLimitUseCase, EraseClientDataUseCase, ExportMessagesUseCase,
+        ForwardMessageUseCase, GetMessageHistoryUseCase, GetRoomDetailUseCase,
+        GetRoomMessagesUseCase, GetRoomStateUseCase, GetRoomStatsUseCase, GetRoomsUseCase,
+        JoinRoomUseCase, KickParticipantUseCase, ManageBreakoutsUseCase, ManageIntegrationsUseCase,
+        ModerateMessagesUseCase, RenameParticipantUseCase, RespondToIncidentUseCase, RoomUseCases,
+        SearchMessagesUseCase, SeedDemoDataUseCase, SendMessageUseCase, SetActivityUseCase,
+        StarMessagesUseCase, VotePollUseCase,
+    },
 };
 
+#[cfg(feature = "discord")]
+use super::discord::{self, DiscordRelay};
+#[cfg(feature = "federation")]
+use super::federation::{self, Federation};
+#[cfg(feature = "grpc")]
+use super::grpc;
+#[cfg(feature = "mqtt")]
+use super::mqtt::{self, MqttBridge};
+#[cfg(feature = "webhooks")]
+use super::outgoing_webhook::{self, OutgoingWebhooks};
+#[cfg(feature = "tls")]
+use super::tls::{self, TlsTermination};
+#[cfg(feature = "xmpp")]
+use super::xmpp::{self, XmppGateway};
 use super::{
-    handler::{debug_room_state, get_room_detail, get_rooms, health_check, websocket_handler},
-    signal::shutdown_signal,
+    announcement, api_version,
+    approval::PendingJoins,
+    auth::{self, Authentication},
+    breakout,
+    challenge::ConnectChallenge,
+    client_ip::TrustedProxies,
+    cluster::{self, ClusterNode},
+    config::DuplicatePolicy,
+    digest,
+    guest::GuestPolicy,
+    handler::{
+        ban_client, create_breakout, create_integration, create_room, debug_room_state,
+        delete_integration, disconnect_room, erase_client_data, freeze_room, get_api_docs,
+        get_challenge_mode, get_cluster, get_connect_challenge, get_integration, get_ip_rules,
+        get_metrics, get_moderation_queue, get_openapi, get_pending_joins, get_room_detail,
+        get_room_detail_by_slug, get_room_messages, get_room_stats, get_rooms, get_schema,
+        get_starred_messages, health_check, incoming_webhook, kick_client, list_breakouts,
+        list_integrations, login, report_message, resolve_pending_join, resolve_report,
+        search_room_messages, set_challenge_mode, set_ip_rules, unfreeze_room, update_integration,
+        websocket_handler,
+    },
+    handover::{self, ConnectionTracker, Handover},
+    heartbeat::{self, HeartbeatRegistry, Keepalive},
+    ip_filter::{self, IP_RULES_PATH, IpFilter, IpRules},
+    memory, seed,
+    session::SessionRegistry,
+    signal::{DEFAULT_SHUTDOWN_TIMEOUT, ReloadHandle, ShutdownToken, listen_signals},
     state::AppState,
+    systemd,
 };
 
 /// WebSocket chat server
@@ -38,10 +99,118 @@ pub struct Server {
     send_message_usecase: Arc<SendMessageUseCase>,
     /// GetRoomStateUseCase（ルーム状態取得のユースケース）
     get_room_state_usecase: Arc<GetRoomStateUseCase>,
+    /// Typing indicators of the default room (disabled if `None`)
+    broadcast_typing: Option<Arc<BroadcastTypingUseCase>>,
+    /// Votes on polls in the default room (disabled if `None`)
+    vote_poll: Option<Arc<VotePollUseCase>>,
+    /// Activities of the participants of the default room (disabled if `None`)
+    set_activity: Option<Arc<SetActivityUseCase>>,
+    /// Display names of the participants of the default room (disabled if `None`)
+    rename_participant: Option<Arc<RenameParticipantUseCase>>,
+    /// Freezing and disconnecting everyone in the default room (disabled if `None`)
+    respond_to_incident: Option<Arc<RespondToIncidentUseCase>>,
     /// GetRoomsUseCase（ルーム一覧取得のユースケース）
     get_rooms_usecase: Arc<GetRoomsUseCase>,
     /// GetRoomDetailUseCase（ルーム詳細取得のユースケース）
     get_room_detail_usecase: Arc<GetRoomDetailUseCase>,
+    /// GetRoomMessagesUseCase（メッセージ取得のユースケース）
+    get_room_messages_usecase: Arc<GetRoomMessagesUseCase>,
+    /// Proxies whose forwarding headers are trusted
+    trusted_proxies: TrustedProxies,
+    /// Secret token of the incoming webhook URL (disabled if `None`)
+    incoming_webhook_token: Option<String>,
+    /// Networks allowed and denied (every network is allowed by default)
+    ip_filter: IpFilter,
+    /// Login and access tokens required by `/ws` and the room endpoints (disabled if `None`)
+    auth: Option<Arc<Authentication>>,
+    /// Cluster membership gossip (disabled if `None`)
+    cluster_node: Option<ClusterNode>,
+    /// Number of sequence numbers remembered per connection to skip duplicate deliveries
+    dedup_window: usize,
+    /// Window in which messages to a client are batched into one frame (disabled if `None`)
+    batch_window: Option<Duration>,
+    /// Messages in a client's send queue at which it is asked to slow down (disabled if `None`)
+    slow_down_threshold: Option<usize>,
+    /// Pings sent to the connections and idle timeout after which they are closed
+    /// (disabled if `None`)
+    keepalive: Option<Keepalive>,
+    /// Log of the frames sent and received on the connections (disabled if `None`)
+    wire_log: Option<Arc<WireLog>>,
+    /// What to do when a client connects with a client ID that is already connected
+    duplicate_policy: DuplicatePolicy,
+    sanitize_profile: SanitizeProfile,
+    /// Clients connecting without a client ID (disabled by default)
+    guests: GuestPolicy,
+    /// Proof-of-work required before the WebSocket upgrade (disabled by default)
+    connect_challenge: ConnectChallenge,
+    /// Language of the system messages of the room
+    locale: Locale,
+    /// Listener handover to a new process (SIGUSR2)
+    handover: Handover,
+    /// Time to wait for connections to close on shutdown
+    shutdown_timeout: Duration,
+    /// Dependency checks of the health endpoints (only liveness is reported if `None`)
+    health_check: Option<Arc<CheckHealthUseCase>>,
+    /// Message analytics of `/api/v1/rooms/{room_id}/stats` (404 if `None`)
+    room_stats: Option<Arc<GetRoomStatsUseCase>>,
+    /// Message search of `/api/v1/rooms/{room_id}/search` (404 if `None`)
+    message_search: Option<Arc<SearchMessagesUseCase>>,
+    /// Message history exports of `/api/v1/rooms/{room_id}/messages?format=...` (404 if `None`)
+    message_export: Option<Arc<ExportMessagesUseCase>>,
+    /// Room creation at `POST /api/v1/rooms` and joining with `/ws?room_id=...` (only the
+    /// default room if `None`)
+    rooms: Option<(Arc<CreateRoomUseCase>, Arc<JoinRoomUseCase>)>,
+    /// Copies of messages into other rooms with `forward` (rejected if `None`)
+    message_forwarding: Option<Arc<ForwardMessageUseCase>>,
+    /// Stars with `star`/`unstar` listed at `/api/v1/users/{client_id}/starred` (rejected and
+    /// 404 if `None`)
+    starred_messages: Option<Arc<StarMessagesUseCase>>,
+    /// Breakout rooms at `/api/v1/rooms/{room_id}/breakouts` (404 if `None`)
+    breakouts: Option<Arc<ManageBreakoutsUseCase>>,
+    /// Latest messages sent to newly connected clients (none if `None`)
+    message_history: Option<Arc<GetMessageHistoryUseCase>>,
+    /// Data erasure of `/api/v1/admin/users/{client_id}/data` with its bearer token (disabled if `None`)
+    client_data_erasure: Option<(String, Arc<EraseClientDataUseCase>)>,
+    /// Message reports and the moderation queue of `/api/v1/admin/reports` with its bearer
+    /// token (disabled if `None`)
+    moderation: Option<(String, Arc<ModerateMessagesUseCase>)>,
+    /// Kicking and banning clients at `/api/v1/admin/{kick,ban}/{client_id}` with its bearer
+    /// token (disabled if `None`)
+    participant_kick: Option<(String, Arc<KickParticipantUseCase>)>,
+    /// Per-room integrations at `/api/v1/rooms/{room_id}/integrations` with its bearer token
+    /// (disabled if `None`)
+    integrations: Option<(String, Arc<ManageIntegrationsUseCase>)>,
+    /// Demo data seeded at startup (disabled if `None`)
+    demo_seed: Option<SeedDemoDataUseCase>,
+    /// Memory usage tracking and cap (history eviction)
+    memory_guard: Option<Arc<EnforceMemoryLimitUseCase>>,
+    /// Daily digest posted at the given time (JST) (disabled if `None`)
+    daily_digest: Option<(NaiveTime, Arc<ComposeDailyDigestUseCase>)>,
+    /// Shutdown token shared with background tasks
+    shutdown: ShutdownToken,
+    /// Configuration reload requests (SIGHUP)
+    reload: ReloadHandle,
+    /// TLS termination (plain HTTP if `None`)
+    #[cfg(feature = "tls")]
+    tls: Option<TlsTermination>,
+    /// Address of the gRPC health service (disabled if `None`)
+    #[cfg(feature = "grpc")]
+    grpc_health_addr: Option<SocketAddr>,
+    /// Discord relay (disabled if `None`)
+    #[cfg(feature = "discord")]
+    discord_relay: Option<DiscordRelay>,
+    /// MQTT bridge (disabled if `None`)
+    #[cfg(feature = "mqtt")]
+    mqtt_bridge: Option<MqttBridge>,
+    /// Federation with peer servers (disabled if `None`)
+    #[cfg(feature = "federation")]
+    federation: Option<Federation>,
+    /// XMPP gateway (disabled if `None`)
+    #[cfg(feature = "xmpp")]
+    xmpp_gateway: Option<XmppGateway>,
+    /// Delivery to the outgoing webhooks of the rooms (disabled if `None`)
+    #[cfg(feature = "webhooks")]
+    outgoing_webhooks: Option<OutgoingWebhooks>,
 }
 
 impl Server {
@@ -55,6 +224,7 @@ impl Server {
     /// * `get_room_state_usecase` - UseCase for getting room state
     /// * `get_rooms_usecase` - UseCase for getting rooms list
     /// * `get_room_detail_usecase` - UseCase for getting room detail
+    /// * `get_room_messages_usecase` - UseCase for getting room messages (backfill)
     pub fn new(
         connect_participant_usecase: Arc<ConnectParticipantUseCase>,
         disconnect_participant_usecase: Arc<DisconnectParticipantUseCase>,
@@ -62,6 +232,7 @@ impl Server {
         get_room_state_usecase: Arc<GetRoomStateUseCase>,
         get_rooms_usecase: Arc<GetRoomsUseCase>,
         get_room_detail_usecase: Arc<GetRoomDetailUseCase>,
+        get_room_messages_usecase: Arc<GetRoomMessagesUseCase>,
     ) -> Self {
         Self {
             connect_participant_usecase,
@@ -70,9 +241,486 @@ impl Server {
             get_room_state_usecase,
             get_rooms_usecase,
             get_room_detail_usecase,
+            get_room_messages_usecase,
+            broadcast_typing: None,
+            vote_poll: None,
+            set_activity: None,
+            rename_participant: None,
+            respond_to_incident: None,
+            health_check: None,
+            room_stats: None,
+            message_search: None,
+            message_export: None,
+            message_forwarding: None,
+            starred_messages: None,
+            rooms: None,
+            breakouts: None,
+            message_history: None,
+            client_data_erasure: None,
+            moderation: None,
+            participant_kick: None,
+            integrations: None,
+            trusted_proxies: TrustedProxies::default(),
+            ip_filter: IpFilter::default(),
+            auth: None,
+            incoming_webhook_token: None,
+            cluster_node: None,
+            dedup_window: DEFAULT_DEDUP_WINDOW,
+            batch_window: None,
+            slow_down_threshold: None,
+            keepalive: None,
+            wire_log: None,
+            duplicate_policy: DuplicatePolicy::default(),
+            sanitize_profile: SanitizeProfile::default(),
+            guests: GuestPolicy::default(),
+            connect_challenge: ConnectChallenge::default(),
+            locale: Locale::default(),
+            handover: Handover::default(),
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            demo_seed: None,
+            memory_guard: None,
+            daily_digest: None,
+            shutdown: ShutdownToken::new(),
+            reload: ReloadHandle::new(),
+            #[cfg(feature = "tls")]
+            tls: None,
+            #[cfg(feature = "grpc")]
+            grpc_health_addr: None,
+            #[cfg(feature = "discord")]
+            discord_relay: None,
+            #[cfg(feature = "mqtt")]
+            mqtt_bridge: None,
+            #[cfg(feature = "federation")]
+            federation: None,
+            #[cfg(feature = "xmpp")]
+            xmpp_gateway: None,
+            #[cfg(feature = "webhooks")]
+            outgoing_webhooks: None,
         }
     }
 
+    /// Trust forwarding headers (`Forwarded` / `X-Forwarded-For`) sent by the given proxies
+    pub fn with_trusted_proxies(mut self, trusted_proxies: TrustedProxies) -> Self {
+        self.trusted_proxies = trusted_proxies;
+        self
+    }
+
+    /// Refuse requests from networks not allowed by the lists with `403 Forbidden`
+    ///
+    /// Admins replace the lists at runtime at `PUT /api/v1/admin/ip-rules` (with the admin
+    /// token); that endpoint itself is not filtered.
+    pub fn with_ip_filter(mut self, rules: IpRules) -> Self {
+        self.ip_filter = IpFilter::new(rules);
+        self
+    }
+
+    /// Require an access token on `/ws` and `/api/v1/rooms/...`
+    ///
+    /// Clients log in at `POST /api/v1/auth/login` and may only connect as the client ID they
+    /// logged in with.
+    pub fn with_auth(mut self, auth: Authentication) -> Self {
+        self.auth = Some(Arc::new(auth));
+        self
+    }
+
+    /// Terminate TLS on the listening socket and serve `https://` and `wss://`
+    #[cfg(feature = "tls")]
+    pub fn with_tls(mut self, tls: TlsTermination) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Serve the gRPC health checking protocol (`grpc.health.v1.Health`) on the given address
+    #[cfg(feature = "grpc")]
+    pub fn with_grpc_health(mut self, addr: SocketAddr) -> Self {
+        self.grpc_health_addr = Some(addr);
+        self
+    }
+
+    /// Bridge the room to an MQTT broker
+    ///
+    /// The message pusher passed to the usecases should be an `MqttMirrorPusher` sharing the
+    /// bridge's client so that room messages are mirrored to the broker.
+    #[cfg(feature = "mqtt")]
+    pub fn with_mqtt_bridge(mut self, bridge: MqttBridge) -> Self {
+        self.mqtt_bridge = Some(bridge);
+        self
+    }
+
+    /// Enable the incoming webhook at `/api/v1/hooks/{token}` (Slack-compatible payload)
+    pub fn with_incoming_webhook_token(mut self, token: String) -> Self {
+        self.incoming_webhook_token = Some(token);
+        self
+    }
+
+    /// Relay messages between a Discord channel and the room
+    ///
+    /// The message pusher passed to the usecases should be a `DiscordRelayPusher` feeding the
+    /// relay's outbound queue so that room messages are posted to Discord.
+    #[cfg(feature = "discord")]
+    pub fn with_discord_relay(mut self, relay: DiscordRelay) -> Self {
+        self.discord_relay = Some(relay);
+        self
+    }
+
+    /// Deliver chat messages to the outgoing webhooks configured with
+    /// [`Server::with_integrations`]
+    #[cfg(feature = "webhooks")]
+    pub fn with_outgoing_webhooks(mut self, webhooks: OutgoingWebhooks) -> Self {
+        self.outgoing_webhooks = Some(webhooks);
+        self
+    }
+
+    /// Expose the room as a multi-user chat on an XMPP server (as an external component)
+    #[cfg(feature = "xmpp")]
+    pub fn with_xmpp_gateway(mut self, gateway: XmppGateway) -> Self {
+        self.xmpp_gateway = Some(gateway);
+        self
+    }
+
+    /// Mirror the room with peer servers and accept their links at `/federation`
+    ///
+    /// The message pusher passed to the usecases should be a `FederationPusher` feeding the
+    /// federation's event queue so that local events are sent to the peers.
+    #[cfg(feature = "federation")]
+    pub fn with_federation(mut self, federation: Federation) -> Self {
+        self.federation = Some(federation);
+        self
+    }
+
+    /// Join a cluster of server nodes and expose its topology at `/api/v1/admin/cluster`
+    ///
+    /// WebSocket connections to a room (`/ws?room=...`) are redirected to the node owning it.
+    pub fn with_cluster(mut self, node: ClusterNode) -> Self {
+        self.cluster_node = Some(node);
+        self
+    }
+
+    /// Set the number of sequence numbers remembered per connection
+    ///
+    /// Messages re-sent to a connection (e.g. after resuming with `last_seq`) are skipped if
+    /// their sequence number is within the window.
+    pub fn with_dedup_window(mut self, size: usize) -> Self {
+        self.dedup_window = size;
+        self
+    }
+
+    /// Batch the messages sent to a client within the window into one frame
+    ///
+    /// The first message waits up to `window` for more; a batch of several messages is sent as
+    /// a JSON array (`[{...},{...}]`), so clients must accept array frames. Trades a little
+    /// latency for fewer frames in very chatty rooms.
+    pub fn with_batch_window(mut self, window: Duration) -> Self {
+        self.batch_window = Some(window);
+        self
+    }
+
+    /// Ask clients to slow down when `threshold` messages are waiting in their send queue
+    ///
+    /// A `slow-down` message asking to space out sends is written ahead of the queued messages,
+    /// and lifted with another one once the queue has drained to a quarter of the threshold.
+    pub fn with_slow_down_threshold(mut self, threshold: usize) -> Self {
+        self.slow_down_threshold = Some(threshold);
+        self
+    }
+
+    /// Ping the connections and close the ones that stay silent for too long
+    ///
+    /// Every connection is sent a WebSocket Ping each `ping_interval`. A connection from which
+    /// no frame (Pongs included) was received for longer than `idle_timeout` is closed, and
+    /// its participant leaves the room with a `participant-left` announcement.
+    pub fn with_keepalive(mut self, keepalive: Keepalive) -> Self {
+        self.keepalive = Some(keepalive);
+        self
+    }
+
+    /// Record every WebSocket frame sent and received on the connections in `wire_log`
+    pub fn with_wire_log(mut self, wire_log: Arc<WireLog>) -> Self {
+        self.wire_log = Some(wire_log);
+        self
+    }
+
+    /// Set what to do when a client connects with a client ID that is already connected
+    ///
+    /// With [`DuplicatePolicy::Takeover`], the previous session is closed with a
+    /// `session-replaced` close reason instead of rejecting the new connection with 409.
+    /// With [`DuplicatePolicy::Multiplex`], the client keeps every connection (e.g. phone and
+    /// laptop): messages are pushed to all of them and the participant leaves the room when
+    /// the last one closes.
+    pub fn with_duplicate_policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_policy = policy;
+        self
+    }
+
+    /// Set how HTML in received message content is sanitized before storage and broadcast
+    ///
+    /// Applies to messages from WebSocket clients, the incoming webhook and the bridges.
+    pub fn with_sanitize_profile(mut self, profile: SanitizeProfile) -> Self {
+        self.sanitize_profile = profile;
+        self
+    }
+
+    /// Set the language of the system messages (join/leave notices etc.) sent to clients
+    ///
+    /// Use the locale of the room so that the notices match the room settings.
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Allow clients to connect without a client ID
+    ///
+    /// Guests are given a server-assigned ID (e.g. `guest-7f3a`) and are marked as guests in
+    /// participant lists; the policy decides whether they may post, and how often.
+    pub fn with_guests(mut self, policy: GuestPolicy) -> Self {
+        self.guests = policy;
+        self
+    }
+
+    /// Configure the proof-of-work connecting clients solve while the challenge is enabled
+    ///
+    /// Clients fetch a challenge from `GET /api/v1/challenge` and connect with
+    /// `/ws?pow=<challenge>:<nonce>`. Admins switch the challenge on and off at
+    /// `PUT /api/v1/admin/challenge` (with the admin token) to mitigate join floods.
+    pub fn with_connect_challenge(mut self, challenge: ConnectChallenge) -> Self {
+        self.connect_challenge = challenge;
+        self
+    }
+
+    /// Configure the listener handover performed on SIGUSR2
+    ///
+    /// The new process takes over the listening socket; existing connections are asked to
+    /// reconnect with a `server-shutdown` notice and this process exits once they are gone.
+    pub fn with_handover(mut self, handover: Handover) -> Self {
+        self.handover = handover;
+        self
+    }
+
+    /// Set how long to wait for connections to close on shutdown
+    ///
+    /// Connections are sent a `server-shutdown` notice and a Close frame, after the messages
+    /// still queued for them; the server exits once they are closed or the timeout elapses.
+    pub fn with_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    /// Seed bot participants and message history at startup (skipped if the room has history)
+    pub fn with_demo_seed(mut self, usecase: SeedDemoDataUseCase) -> Self {
+        self.demo_seed = Some(usecase);
+        self
+    }
+
+    /// Check the repository and the message pusher in `/api/v1/health` and the gRPC health service
+    pub fn with_health_check(mut self, usecase: CheckHealthUseCase) -> Self {
+        self.health_check = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Serve the message analytics of the room at `/api/v1/rooms/{room_id}/stats`
+    ///
+    /// The stats count the tags added by the message analyzer of the `SendMessageUseCase`.
+    pub fn with_room_stats(mut self, usecase: GetRoomStatsUseCase) -> Self {
+        self.room_stats = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Let clients search the message history of a room at `/api/v1/rooms/{room_id}/search?q=...`
+    pub fn with_message_search(mut self, usecase: SearchMessagesUseCase) -> Self {
+        self.message_search = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Serve exports of the message history at `/api/v1/rooms/{room_id}/messages?format=...`
+    ///
+    /// Without this, the endpoint only serves backfills (`since_seq`).
+    pub fn with_message_export(mut self, usecase: ExportMessagesUseCase) -> Self {
+        self.message_export = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Let clients create rooms at `POST /api/v1/rooms` and join them with `/ws?room_id=...`
+    ///
+    /// Each room has its own message pusher, so broadcasts reach only the clients of the
+    /// room. The default room keeps the usecases passed to [`Server::new`].
+    pub fn with_rooms(mut self, create: CreateRoomUseCase, join: Arc<JoinRoomUseCase>) -> Self {
+        self.rooms = Some((Arc::new(create), join));
+        self
+    }
+
+    /// Let clients forward a message of their room into another room they belong to
+    ///
+    /// The copy is posted by the forwarding client and carries where the message was first
+    /// posted; without this, `forward` is rejected with `invalid_forward`.
+    pub fn with_message_forwarding(mut self, usecase: ForwardMessageUseCase) -> Self {
+        self.message_forwarding = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Let clients star messages of their room and list them at
+    /// `GET /api/v1/users/{client_id}/starred`
+    ///
+    /// Without this, `star` and `unstar` are rejected with `invalid_star`.
+    pub fn with_starred_messages(mut self, usecase: StarMessagesUseCase) -> Self {
+        self.starred_messages = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Let clients spawn breakout rooms from a room at `POST /api/v1/rooms/{room_id}/breakouts`
+    ///
+    /// The parent room is told about each breakout in a chat message; breakouts left unused
+    /// for the usecase's idle timeout are archived (removed) and the parent room is told again.
+    pub fn with_breakouts(mut self, usecase: ManageBreakoutsUseCase) -> Self {
+        self.breakouts = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Send the latest messages of the room to newly connected clients in `room-history`
+    ///
+    /// Clients resuming with `resume_token` and `last_seq` backfill instead.
+    pub fn with_message_history(mut self, usecase: GetMessageHistoryUseCase) -> Self {
+        self.message_history = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Accept requests to erase a client's data at `DELETE /api/v1/admin/users/{client_id}/data`
+    ///
+    /// Requests must carry `Authorization: Bearer <admin_token>`. The client's connection is
+    /// closed before its participant record and messages are erased.
+    pub fn with_client_data_erasure(
+        mut self,
+        admin_token: String,
+        usecase: EraseClientDataUseCase,
+    ) -> Self {
+        self.client_data_erasure = Some((admin_token, Arc::new(usecase)));
+        self
+    }
+
+    /// Accept message reports at `POST /api/v1/rooms/{room_id}/messages/{seq}/report` and let
+    /// admins review them at `GET /api/v1/admin/reports`
+    ///
+    /// Admin requests must carry `Authorization: Bearer <admin_token>` (the same token as
+    /// [`Server::with_client_data_erasure`] when both are enabled). Banned clients are
+    /// disconnected and cannot connect again until the server restarts.
+    pub fn with_moderation(
+        mut self,
+        admin_token: String,
+        usecase: ModerateMessagesUseCase,
+    ) -> Self {
+        self.moderation = Some((admin_token, Arc::new(usecase)));
+        self
+    }
+
+    /// Let admins close a client's connections at `POST /api/v1/admin/kick/{client_id}` and
+    /// also reject its future connections at `POST /api/v1/admin/ban/{client_id}`
+    ///
+    /// Requests must carry `Authorization: Bearer <admin_token>` (the same token as the other
+    /// admin endpoints when they are enabled). Kicked connections are closed with
+    /// `KICKED_CLOSE_CODE`, banned ones with `BANNED_CLOSE_CODE`.
+    pub fn with_participant_kick(
+        mut self,
+        admin_token: String,
+        usecase: KickParticipantUseCase,
+    ) -> Self {
+        self.participant_kick = Some((admin_token, Arc::new(usecase)));
+        self
+    }
+
+    /// Let admins configure outgoing webhooks, incoming webhook tokens, and bridge settings of
+    /// each room at `/api/v1/rooms/{room_id}/integrations`
+    ///
+    /// Requests must carry `Authorization: Bearer <admin_token>` (the same token as the other
+    /// admin endpoints when they are enabled). Incoming webhook tokens of a room post into that
+    /// room at `POST /api/v1/hooks/{token}`, alongside the global token. Recurring announcements
+    /// are posted to their room when their schedule fires. Clients joining a room
+    /// with join approval wait at `GET /api/v1/admin/rooms/{room_id}/pending` until they are
+    /// approved or rejected at `POST /api/v1/admin/rooms/{room_id}/pending/{client_id}/resolve`.
+    pub fn with_integrations(
+        mut self,
+        admin_token: String,
+        usecase: ManageIntegrationsUseCase,
+    ) -> Self {
+        self.integrations = Some((admin_token, Arc::new(usecase)));
+        self
+    }
+
+    /// Accept votes on polls in the default room
+    ///
+    /// Rooms created at runtime always accept them; without this, `vote` sent in the default
+    /// room is rejected with `invalid_vote`.
+    pub fn with_poll_votes(mut self, usecase: VotePollUseCase) -> Self {
+        self.vote_poll = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Accept activities of the participants of the default room
+    ///
+    /// Rooms created at runtime always accept them; without this, `set-activity` sent in the
+    /// default room is rejected with `invalid_activity`.
+    pub fn with_activities(mut self, usecase: SetActivityUseCase) -> Self {
+        self.set_activity = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Accept display names of the participants of the default room
+    ///
+    /// Rooms created at runtime always accept them; without this, `set-display-name` sent in
+    /// the default room is rejected with `invalid_display_name` and `?display_name=` is ignored.
+    pub fn with_display_names(mut self, usecase: RenameParticipantUseCase) -> Self {
+        self.rename_participant = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Let admins freeze the default room and disconnect all of its participants at
+    /// `/api/v1/admin/rooms/{room_id}/freeze` and `/api/v1/admin/rooms/{room_id}/disconnect`
+    ///
+    /// Requests must carry `Authorization: Bearer <admin token>`, so the endpoints answer 404
+    /// without an admin token. Rooms created at runtime always support them.
+    pub fn with_incident_response(mut self, usecase: RespondToIncidentUseCase) -> Self {
+        self.respond_to_incident = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Relay typing indicators in the default room
+    ///
+    /// Rooms created at runtime always relay them; without this, `typing-started` and
+    /// `typing-stopped` sent in the default room are ignored.
+    pub fn with_typing_indicators(mut self, usecase: BroadcastTypingUseCase) -> Self {
+        self.broadcast_typing = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Track the memory held by the room history and send queues, and enforce its cap
+    ///
+    /// Usage is checked every second and published at `/metrics`; when the usecase has a
+    /// limit and the total exceeds it, the oldest history is evicted with a warning.
+    pub fn with_memory_guard(mut self, usecase: EnforceMemoryLimitUseCase) -> Self {
+        self.memory_guard = Some(Arc::new(usecase));
+        self
+    }
+
+    /// Post a digest of the last 24 hours to the room every day at `at` (JST)
+    ///
+    /// The digest lists the message count and the most active participants, in the room's
+    /// locale; it is skipped for days without messages.
+    pub fn with_daily_digest(mut self, at: NaiveTime, usecase: ComposeDailyDigestUseCase) -> Self {
+        self.daily_digest = Some((at, Arc::new(usecase)));
+        self
+    }
+
+    /// Get the shutdown token
+    ///
+    /// Background tasks should stop when the token is triggered. Triggering it
+    /// also shuts the server down gracefully.
+    pub fn shutdown_token(&self) -> ShutdownToken {
+        self.shutdown.clone()
+    }
+
+    /// Get the handle notified on configuration reload requests (SIGHUP)
+    pub fn reload_handle(&self) -> ReloadHandle {
+        self.reload.clone()
+    }
+
     /// Run the WebSocket chat server
     ///
     /// # Arguments
@@ -85,6 +733,65 @@ impl Server {
     /// Returns an error if the server fails to bind to the specified address or
     /// if there's an error during server execution.
     pub async fn run(self, host: String, port: u16) -> Result<(), Box<dyn std::error::Error>> {
+        // Use the socket handed over by the previous process or passed by systemd socket
+        // activation, or bind the host and port
+        let bind_addr = format!("{}:{}", host, port);
+        let handed_over = handover::take_handed_over_listener()?;
+        let is_handed_over = handed_over.is_some();
+        let listener = match handed_over {
+            Some(listener) => {
+                tracing::info!("Using listening socket handed over by the previous process");
+                TcpListener::from_std(listener)?
+            }
+            None => match systemd::take_activated_listener()? {
+                Some(listener) => {
+                    tracing::info!("Using listening socket passed by systemd");
+                    TcpListener::from_std(listener)?
+                }
+                None => TcpListener::bind(&bind_addr).await?,
+            },
+        };
+        self.serve(listener, is_handed_over).await
+    }
+
+    /// Serve the chat server on a bound listener until it shuts down
+    ///
+    /// `is_handed_over` tells whether the listener was handed over by the previous process.
+    async fn serve(
+        self,
+        listener: TcpListener,
+        is_handed_over: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Connections without a room ID join the default room
+        let default_room = match self.get_room_state_usecase.execute().await {
+            Ok(room) => room,
+            Err(_) => return Err("Failed to get the default room".into()),
+        };
+        let default_room = Arc::new(RoomUseCases {
+            room_id: default_room.id,
+            // The filters and the maximum message length of the default room are given with its
+            // SendMessageUseCase
+            message_policy: default_room.message_policy,
+            connect_participant: self.connect_participant_usecase.clone(),
+            disconnect_participant: self.disconnect_participant_usecase.clone(),
+            send_message: self.send_message_usecase.clone(),
+            broadcast_typing: self.broadcast_typing,
+            vote_poll: self.vote_poll,
+            set_activity: self.set_activity,
+            rename_participant: self.rename_participant,
+            respond_to_incident: self.respond_to_incident,
+            get_room_state: self.get_room_state_usecase.clone(),
+        });
+        if let Some((_, join)) = &self.rooms {
+            join.register(default_room.clone()).await;
+        }
+
+        // Nodes of a cluster terminate TLS alike, so redirects keep the scheme of this node
+        #[cfg(feature = "tls")]
+        let ws_scheme = if self.tls.is_some() { "wss" } else { "ws" };
+        #[cfg(not(feature = "tls"))]
+        let ws_scheme = "ws";
+
         let app_state = Arc::new(AppState {
             connect_participant_usecase: self.connect_participant_usecase,
             disconnect_participant_usecase: self.disconnect_participant_usecase,
@@ -92,38 +799,415 @@ impl Server {
             get_room_state_usecase: self.get_room_state_usecase,
             get_rooms_usecase: self.get_rooms_usecase,
             get_room_detail_usecase: self.get_room_detail_usecase,
+            get_room_messages_usecase: self.get_room_messages_usecase,
+            get_message_history_usecase: self.message_history,
+            default_room,
+            create_room_usecase: self.rooms.as_ref().map(|(create, _)| create.clone()),
+            join_room_usecase: self.rooms.map(|(_, join)| join),
+            check_health_usecase: self.health_check,
+            get_room_stats_usecase: self.room_stats,
+            search_messages_usecase: self.message_search,
+            export_messages_usecase: self.message_export,
+            forward_message_usecase: self.message_forwarding,
+            star_messages_usecase: self.starred_messages,
+            erase_client_data_usecase: self
+                .client_data_erasure
+                .as_ref()
+                .map(|(_, usecase)| usecase.clone()),
+            moderate_messages_usecase: self.moderation.as_ref().map(|(_, usecase)| usecase.clone()),
+            kick_participant_usecase: self
+                .participant_kick
+                .as_ref()
+                .map(|(_, usecase)| usecase.clone()),
+            manage_breakouts_usecase: self.breakouts.clone(),
+            manage_integrations_usecase: self
+                .integrations
+                .as_ref()
+                .map(|(_, usecase)| usecase.clone()),
+            admin_token: self
+                .client_data_erasure
+                .map(|(token, _)| token)
+                .or(self.moderation.map(|(token, _)| token))
+                .or(self.participant_kick.map(|(token, _)| token))
+                .or(self.integrations.map(|(token, _)| token)),
+            trusted_proxies: self.trusted_proxies,
+            ip_filter: self.ip_filter,
+            auth: self.auth,
+            incoming_webhook_token: self.incoming_webhook_token,
+            cluster: self.cluster_node.as_ref().map(ClusterNode::membership),
+            room_shards: self.cluster_node.as_ref().map(ClusterNode::room_shards),
+            ws_scheme,
+            dedup_window: self.dedup_window,
+            batch_window: self.batch_window,
+            slow_down_threshold: self.slow_down_threshold,
+            keepalive: self.keepalive,
+            heartbeats: Arc::new(HeartbeatRegistry::new()),
+            wire_log: self.wire_log,
+            duplicate_policy: self.duplicate_policy,
+            sanitize_profile: self.sanitize_profile,
+            sessions: SessionRegistry::new(),
+            pending_joins: PendingJoins::new(),
+            guests: self.guests,
+            connect_challenge: self.connect_challenge,
+            locale: self.locale,
+            draining: ShutdownToken::new(),
+            stopping: self.shutdown.clone(),
+            reconnect_stagger: self.handover.reconnect_stagger,
+            connections: ConnectionTracker::new(),
+            metrics: Arc::new(Metrics::new()),
+        });
+
+        // Demo bots stay in the room until the server stops
+        if let Some(usecase) = self.demo_seed {
+            match usecase.execute(Timestamp::new(get_jst_timestamp())).await {
+                Ok(Some(demo)) => {
+                    tracing::info!(
+                        "Seeded demo data: {} bot participants and {} messages",
+                        demo.bots,
+                        demo.messages
+                    );
+                    engawa_shared::task::spawn(
+                        "demo-bots",
+                        seed::run_demo_bots(demo.inbox, self.shutdown.clone()),
+                    );
+                }
+                Ok(None) => tracing::info!("Room already has history; skipping demo data"),
+                Err(e) => return Err(format!("Failed to seed demo data: {:?}", e).into()),
+            }
+        }
+
+        // Memory guard stops with the server
+        if let Some(usecase) = self.memory_guard {
+            engawa_shared::task::spawn(
+                "memory-guard",
+                memory::run_memory_guard(usecase, app_state.metrics.clone(), self.shutdown.clone()),
+            );
+        }
+
+        // Idle connections are closed until the server stops
+        if let Some(keepalive) = self.keepalive {
+            engawa_shared::task::spawn(
+                "heartbeat-reaper",
+                heartbeat::run_reaper(
+                    app_state.heartbeats.clone(),
+                    keepalive,
+                    self.shutdown.clone(),
+                ),
+            );
+        }
+
+        // Unused breakouts are archived until the server stops
+        if let Some(usecase) = self.breakouts {
+            engawa_shared::task::spawn(
+                "breakout-archiver",
+                breakout::run_breakout_archiver(usecase, app_state.clone(), self.shutdown.clone()),
+            );
+        }
+
+        // Recurring announcements of the rooms stop with the server
+        if let Some(usecase) = app_state.manage_integrations_usecase.clone() {
+            engawa_shared::task::spawn(
+                "recurring-announcements",
+                announcement::run_recurring_announcements(
+                    usecase,
+                    app_state.clone(),
+                    self.shutdown.clone(),
+                ),
+            );
+        }
+
+        // Daily digest stops with the server
+        if let Some((at, usecase)) = self.daily_digest {
+            engawa_shared::task::spawn(
+                "daily-digest",
+                digest::run_daily_digest(at, usecase, app_state.clone(), self.shutdown.clone()),
+            );
+        }
+
+        // Cluster gossip stops with the server
+        if let Some(node) = self.cluster_node {
+            engawa_shared::task::spawn(
+                "cluster-gossip",
+                cluster::run_gossip(node, app_state.clone(), self.shutdown.clone()),
+            );
+        }
+
+        // gRPC health service runs on its own port and stops with the server
+        #[cfg(feature = "grpc")]
+        if let Some(addr) = self.grpc_health_addr {
+            let grpc_health = grpc::serve_health(app_state.clone(), addr, self.shutdown.clone());
+            engawa_shared::task::spawn("grpc-health", async move {
+                if let Err(e) = grpc_health.await {
+                    tracing::error!("gRPC health service error: {}", e);
+                }
+            });
+        }
+
+        // MQTT bridge injects device commands into the room and stops with the server
+        #[cfg(feature = "mqtt")]
+        if let Some(bridge) = self.mqtt_bridge {
+            engawa_shared::task::spawn(
+                "mqtt-bridge",
+                mqtt::run_bridge(bridge, app_state.clone(), self.shutdown.clone()),
+            );
+        }
+
+        // Discord relay stops with the server
+        #[cfg(feature = "discord")]
+        if let Some(relay) = self.discord_relay {
+            engawa_shared::task::spawn(
+                "discord-relay",
+                discord::run_relay(relay, app_state.clone(), self.shutdown.clone()),
+            );
+        }
+
+        // Outgoing webhook delivery stops with the server
+        #[cfg(feature = "webhooks")]
+        if let Some(webhooks) = self.outgoing_webhooks {
+            engawa_shared::task::spawn(
+                "outgoing-webhooks",
+                outgoing_webhook::run_delivery(webhooks, app_state.clone(), self.shutdown.clone()),
+            );
+        }
+
+        // XMPP gateway removes its occupants from the room and stops with the server
+        #[cfg(feature = "xmpp")]
+        if let Some(gateway) = self.xmpp_gateway {
+            engawa_shared::task::spawn(
+                "xmpp-gateway",
+                xmpp::run_gateway(gateway, app_state.clone(), self.shutdown.clone()),
+            );
+        }
+
+        // Federation links stop with the server; inbound links are accepted at /federation
+        #[cfg(feature = "federation")]
+        let federation_routes = self.federation.map(|federation| {
+            federation::start(federation, app_state.clone(), self.shutdown.clone())
         });
 
         // Define handlers
+        // REST API v1（/api/v1/...）
+        // ルームのエンドポイント（認証が有効な場合はアクセストークンが必要）
+        let rooms = Router::new()
+            .route("/rooms", get(get_rooms).post(create_room))
+            .route("/rooms/{room_id}", get(get_room_detail))
+            .route("/rooms/by-slug/{slug}", get(get_room_detail_by_slug))
+            .route("/rooms/{room_id}/messages", get(get_room_messages))
+            .route("/rooms/{room_id}/stats", get(get_room_stats))
+            .route("/rooms/{room_id}/search", get(search_room_messages))
+            .route(
+                "/rooms/{room_id}/messages/{seq}/report",
+                post(report_message),
+            )
+            .route(
+                "/rooms/{room_id}/breakouts",
+                get(list_breakouts).post(create_breakout),
+            )
+            .route("/users/{client_id}/starred", get(get_starred_messages))
+            .route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                auth::require_token,
+            ));
+        let api_v1 = Router::new()
+            .route("/health", get(health_check))
+            .route("/schema", get(get_schema))
+            .route("/openapi.json", get(get_openapi))
+            .route("/docs", get(get_api_docs))
+            .route("/auth/login", post(login))
+            .merge(rooms)
+            .route("/hooks/{token}", post(incoming_webhook))
+            .route("/challenge", get(get_connect_challenge))
+            .route("/admin/cluster", get(get_cluster))
+            .route("/admin/users/{client_id}/data", delete(erase_client_data))
+            .route("/admin/reports", get(get_moderation_queue))
+            .route("/admin/reports/{report_id}/resolve", post(resolve_report))
+            .route("/admin/kick/{client_id}", post(kick_client))
+            .route("/admin/ban/{client_id}", post(ban_client))
+            .route(
+                "/admin/rooms/{room_id}/freeze",
+                post(freeze_room).delete(unfreeze_room),
+            )
+            .route("/admin/rooms/{room_id}/disconnect", post(disconnect_room))
+            .route(
+                "/rooms/{room_id}/integrations",
+                get(list_integrations).post(create_integration),
+            )
+            .route(
+                "/rooms/{room_id}/integrations/{id}",
+                get(get_integration)
+                    .put(update_integration)
+                    .delete(delete_integration),
+            )
+            .route("/admin/rooms/{room_id}/pending", get(get_pending_joins))
+            .route(
+                "/admin/rooms/{room_id}/pending/{client_id}/resolve",
+                post(resolve_pending_join),
+            )
+            .route(
+                "/admin/challenge",
+                get(get_challenge_mode).put(set_challenge_mode),
+            )
+            .route(IP_RULES_PATH, get(get_ip_rules).put(set_ip_rules));
+
+        // HTTP エンドポイント（JSON レスポンスは Accept-Encoding に応じて圧縮）
+        let http = Router::new()
+            .route("/debug/room", get(debug_room_state))
+            .route("/metrics", get(get_metrics))
+            .nest(
+                "/api/v1",
+                api_v1.clone().layer(middleware::from_fn(api_version::v1)),
+            )
+            // 旧パス（/api/...）は v1 の互換エイリアスとして提供（Deprecation ヘッダー付き）
+            .nest(
+                "/api",
+                api_v1.layer(middleware::from_fn(api_version::deprecated_alias)),
+            )
+            .layer(CompressionLayer::new());
         let app = Router::new()
             // WebSocket エンドポイント
             .route("/ws", get(websocket_handler))
-            // HTTP エンドポイント
-            .route("/debug/room", get(debug_room_state))
-            .route("/api/health", get(health_check))
-            .route("/api/rooms", get(get_rooms))
-            .route("/api/rooms/{room_id}", get(get_room_detail))
-            .with_state(app_state);
+            .route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                auth::require_token,
+            ))
+            .merge(http)
+            .with_state(app_state.clone());
+        #[cfg(feature = "federation")]
+        let app = match federation_routes {
+            Some(routes) => app.merge(routes),
+            None => app,
+        };
+        // Every request (including the WebSocket upgrade) is checked against the IP rules
+        let app = app.layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            ip_filter::enforce,
+        ));
 
-        // Bind the server to the host and port
-        let bind_addr = format!("{}:{}", host, port);
-        let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
+        let local_addr = listener.local_addr()?;
 
         // Start the server
-        tracing::info!(
-            "WebSocket chat server listening on {}",
-            listener.local_addr()?
-        );
-        tracing::info!("Connect to: ws://{}/ws", bind_addr);
+        tracing::info!("WebSocket chat server listening on {}", local_addr);
+        tracing::info!("Connect to: {}://{}/ws", app_state.ws_scheme, local_addr);
         tracing::info!("Press Ctrl+C to shutdown gracefully");
 
+        // Tell systemd (and the previous process, after a handover) that the server is ready
+        // to accept connections. systemd needs NotifyAccess=all to accept the new main PID.
+        if is_handed_over {
+            systemd::notify(&format!("MAINPID={}", std::process::id()));
+        }
+        systemd::notify("READY=1");
+        handover::notify_ready();
+        let watchdog = systemd::spawn_watchdog();
+
+        // Hand the listener over to a new process on SIGUSR2
+        #[cfg(unix)]
+        let handover_task = {
+            use std::os::fd::AsRawFd;
+            engawa_shared::task::spawn(
+                "handover",
+                handover::run_handover(
+                    self.handover.clone(),
+                    listener.as_raw_fd(),
+                    app_state.draining.clone(),
+                    self.shutdown.clone(),
+                ),
+            )
+        };
+
         // Set up graceful shutdown signal handler
-        axum::serve(listener, app)
-            .with_graceful_shutdown(shutdown_signal())
+        let signals = engawa_shared::task::spawn(
+            "signals",
+            listen_signals(self.shutdown.clone(), self.reload.clone()),
+        );
+        let shutdown = self.shutdown.clone();
+        let draining = app_state.draining.clone();
+        let stopping = async move {
+            shutdown.cancelled().await;
+            // The service keeps running in the new process after a handover
+            if !draining.is_triggered() {
+                systemd::notify("STOPPING=1");
+            }
+        };
+        #[cfg(feature = "tls")]
+        if let Some(termination) = self.tls {
+            tls::serve(listener.into_std()?, app, termination, stopping).await?;
+        } else {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(stopping)
             .await?;
+        }
+        #[cfg(not(feature = "tls"))]
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(stopping)
+        .await?;
+
+        // Wait for the connections to flush their queued messages and close (after a handover,
+        // for the clients to reconnect to the new process)
+        let drain_timeout = if app_state.draining.is_triggered() {
+            self.handover.drain_timeout
+        } else {
+            self.shutdown_timeout
+        };
+        let connections = app_state.connections.count();
+        if connections > 0 {
+            tracing::info!("Waiting for {} connections to close", connections);
+            let drained =
+                tokio::time::timeout(drain_timeout, app_state.connections.wait_idle()).await;
+            if drained.is_err() {
+                tracing::warn!(
+                    "{} connections still open after {:?}; exiting anyway",
+                    app_state.connections.count(),
+                    drain_timeout
+                );
+            }
+        }
+
+        // Make sure background tasks stop even if the server exited on its own
+        self.shutdown.trigger();
+        signals.abort();
+        #[cfg(unix)]
+        handover_task.abort();
+
+        if let Some(watchdog) = watchdog {
+            watchdog.abort();
+        }
 
         tracing::info!("Server shutdown complete");
 
         Ok(())
     }
 }
+
+/// Start the server in the background on `host:port`, without taking over an inherited socket
+///
+/// Port `0` binds an ephemeral port, so that integration tests can run several servers
+/// in-process. The returned task ends once the server's shutdown token is triggered and the
+/// connections are drained.
+///
+/// # Returns
+///
+/// The address the server listens on and the task running it
+///
+/// # Errors
+///
+/// Returns an error if the address cannot be bound.
+pub async fn run_server(
+    server: Server,
+    host: &str,
+    port: u16,
+) -> std::io::Result<(SocketAddr, JoinHandle<()>)> {
+    let listener = TcpListener::bind((host, port)).await?;
+    let local_addr = listener.local_addr()?;
+    let task = engawa_shared::task::spawn("server", async move {
+        if let Err(e) = server.serve(listener, false).await {
+            tracing::error!("Server error: {}", e);
+        }
+    });
+    Ok((local_addr, task))
+}
@@ -0,0 +1,242 @@
+//! Sessions of connected WebSocket clients.
+//!
+//! With `--duplicate-policy takeover`, a client connecting with a client ID that is already
+//! connected replaces the previous session (e.g. a zombie connection left behind by a laptop
+//! going to sleep): the previous connection is closed with [`SESSION_REPLACED_CLOSE_CODE`] and
+//! the new connection joins once it has left the room.
+//!
+//! Admins can also end the session of a client (`POST /api/v1/admin/kick/{client_id}` and
+//! `POST /api/v1/admin/ban/{client_id}`), which closes the connection with
+//! [`KICKED_CLOSE_CODE`] or [`BANNED_CLOSE_CODE`], and disconnect everyone in a room
+//! (`POST /api/v1/admin/rooms/{room_id}/disconnect`), which closes the connections with
+//! [`DISCONNECTED_CLOSE_CODE`].
+//!
+//! With `--duplicate-policy multiplex`, every device of a client has its own session under the
+//! same key; ending the key ends all of them.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use tokio::sync::oneshot;
+
+/// Close code sent to a connection replaced by a newer connection of the same client
+pub const SESSION_REPLACED_CLOSE_CODE: u16 = 4011;
+
+/// Close reason sent to a connection replaced by a newer connection of the same client
+pub const SESSION_REPLACED_REASON: &str = "session-replaced";
+
+/// Close code sent to a connection kicked by an admin
+pub const KICKED_CLOSE_CODE: u16 = 4012;
+
+/// Close code sent to a connection of a client banned by an admin or a moderator
+pub const BANNED_CLOSE_CODE: u16 = 4013;
+
+/// Close code sent to the connections of a room an admin disconnected everyone from
+pub const DISCONNECTED_CLOSE_CODE: u16 = 4015;
+
+/// Time to wait for a replaced session to leave the room
+pub const TAKEOVER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Why a session was ended from outside its connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionEnd {
+    /// A newer connection of the same client took over
+    Replaced,
+    /// An admin kicked the client
+    Kicked,
+    /// The client was banned
+    Banned,
+    /// An admin disconnected everyone in the room
+    Disconnected,
+}
+
+impl SessionEnd {
+    /// Close code and reason sent to the connection
+    pub fn close_frame(self) -> (u16, &'static str) {
+        match self {
+            Self::Replaced => (SESSION_REPLACED_CLOSE_CODE, SESSION_REPLACED_REASON),
+            Self::Kicked => (KICKED_CLOSE_CODE, "kicked"),
+            Self::Banned => (BANNED_CLOSE_CODE, "banned"),
+            Self::Disconnected => (DISCONNECTED_CLOSE_CODE, "disconnected"),
+        }
+    }
+}
+
+/// Identifies one session among the sessions registered under the same key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionId(u64);
+
+struct Session {
+    id: SessionId,
+    /// Tells the connection why it has to close
+    end: oneshot::Sender<SessionEnd>,
+    /// Completes when the connection has left the room
+    closed: oneshot::Receiver<()>,
+}
+
+/// Open sessions by client ID (several for a client connected from multiple devices)
+#[derive(Default)]
+pub struct SessionRegistry {
+    sessions: Mutex<HashMap<String, Vec<Session>>>,
+    next_id: AtomicU64,
+}
+
+impl SessionRegistry {
+    /// Create a registry with no sessions
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the session of a joined connection
+    ///
+    /// Returns the ID to unregister the session with, a receiver completing when the session
+    /// is ended, and a sender the connection drops once it has left the room.
+    pub fn register(
+        &self,
+        client_id: &str,
+    ) -> (
+        SessionId,
+        oneshot::Receiver<SessionEnd>,
+        oneshot::Sender<()>,
+    ) {
+        let id = SessionId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let (end, ended) = oneshot::channel();
+        let (closed_tx, closed) = oneshot::channel();
+        self.sessions
+            .lock()
+            .unwrap()
+            .entry(client_id.to_string())
+            .or_default()
+            .push(Session { id, end, closed });
+        (id, ended, closed_tx)
+    }
+
+    /// Forget a session that is closing, keeping the other sessions of the client
+    pub fn unregister(&self, client_id: &str, id: SessionId) {
+        let mut sessions = self.sessions.lock().unwrap();
+        if let Some(registered) = sessions.get_mut(client_id) {
+            registered.retain(|session| session.id != id);
+            if registered.is_empty() {
+                sessions.remove(client_id);
+            }
+        }
+    }
+
+    /// Ask the sessions of the client to close in favour of a new connection
+    ///
+    /// Returns a future completing once the previous connections have left the room, or
+    /// `None` if the client has no session here (e.g. a bridged participant).
+    pub fn replace(&self, client_id: &str) -> Option<impl Future<Output = ()> + use<>> {
+        self.end(client_id, SessionEnd::Replaced)
+    }
+
+    /// Ask every session registered under `key` to close for `reason`
+    ///
+    /// Returns a future completing once the connections have left the room, or `None` if
+    /// there is no such session.
+    pub fn end(&self, key: &str, reason: SessionEnd) -> Option<impl Future<Output = ()> + use<>> {
+        let sessions = self.sessions.lock().unwrap().remove(key)?;
+        let closing: Vec<_> = sessions
+            .into_iter()
+            .map(|session| {
+                // The connection may be closing already; it leaves the room either way
+                let _ = session.end.send(reason);
+                session.closed
+            })
+            .collect();
+        Some(async move {
+            for closed in closing {
+                let _ = closed.await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_replace_notifies_session_and_waits_for_close() {
+        // テスト項目: セッションを置き換えると接続に通知され、接続が閉じると待機が終わる
+        // given (前提条件):
+        let registry = SessionRegistry::new();
+        let (_, mut replaced, closed) = registry.register("alice");
+
+        // when (操作):
+        let wait = registry.replace("alice").unwrap();
+
+        // then (期待する結果):
+        assert_eq!(replaced.try_recv(), Ok(SessionEnd::Replaced));
+        drop(closed);
+        wait.await;
+        // 置き換えたセッションは登録から外れる
+        assert!(registry.replace("alice").is_none());
+    }
+
+    #[test]
+    fn test_replace_unknown_client() {
+        // テスト項目: セッションを持たないクライアントは置き換えられない
+        // given (前提条件):
+        let registry = SessionRegistry::new();
+        let (id, _replaced, _closed) = registry.register("alice");
+        registry.unregister("alice", id);
+
+        // when (操作):
+        let result = registry.replace("alice");
+
+        // then (期待する結果):
+        assert!(result.is_none());
+        assert!(registry.replace("bob").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_kick_ends_every_device_of_multiplexed_client() {
+        // テスト項目: 複数の端末から接続したクライアントをキックすると、すべての接続が閉じるまで待つ
+        // given (前提条件):
+        let registry = SessionRegistry::new();
+        let (_, mut laptop, laptop_closed) = registry.register("alice");
+        let (_, mut phone, phone_closed) = registry.register("alice");
+
+        // when (操作):
+        let wait = registry.end("alice", SessionEnd::Kicked).unwrap();
+        tokio::pin!(wait);
+
+        // then (期待する結果):
+        assert_eq!(laptop.try_recv(), Ok(SessionEnd::Kicked));
+        assert_eq!(phone.try_recv(), Ok(SessionEnd::Kicked));
+        drop(laptop_closed);
+        // 電話の接続が閉じるまでは待機が終わらない
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), &mut wait)
+                .await
+                .is_err()
+        );
+        drop(phone_closed);
+        wait.await;
+        assert!(registry.end("alice", SessionEnd::Kicked).is_none());
+    }
+
+    #[test]
+    fn test_unregister_keeps_other_devices() {
+        // テスト項目: 閉じた接続のセッションだけが登録から外れ、同じクライアントの他の接続は残る
+        // given (前提条件):
+        let registry = SessionRegistry::new();
+        let (laptop_id, _laptop, _laptop_closed) = registry.register("alice");
+        let (_, mut phone, _phone_closed) = registry.register("alice");
+
+        // when (操作):
+        registry.unregister("alice", laptop_id);
+        let result = registry.end("alice", SessionEnd::Kicked);
+
+        // then (期待する結果):
+        assert!(result.is_some());
+        assert_eq!(phone.try_recv(), Ok(SessionEnd::Kicked));
+    }
+}
@@ -1,30 +1,231 @@
 //! Graceful shutdown signal handling.
+//!
+//! | Signal                    | Action                         |
+//! |---------------------------|--------------------------------|
+//! | Ctrl+C                    | Graceful shutdown              |
+//! | SIGTERM (Unix)            | Graceful shutdown              |
+//! | Ctrl+Break (Windows)      | Graceful shutdown              |
+//! | SIGHUP (Unix)             | Configuration reload request   |
+//! | SIGUSR2 (Unix)            | Listener handover (`handover`) |
+//!
+//! Background tasks receive a [`ShutdownToken`] so that they terminate together with the server.
+//! On shutdown, open WebSocket connections are sent a `server-shutdown` notice and a Close frame
+//! with [`SERVER_SHUTDOWN_CLOSE_CODE`], and the server waits up to the shutdown timeout for them
+//! to flush their queued messages and close.
 
-/// Signal handler for graceful shutdown
-pub async fn shutdown_signal() {
-    let ctrl_c = async {
-        tokio::signal::ctrl_c()
-            .await
-            .expect("Failed to install Ctrl+C handler");
-    };
+use std::{sync::Arc, time::Duration};
+
+use tokio::sync::watch;
+
+/// WebSocket close code sent to clients when the server shuts down (Going Away)
+pub const SERVER_SHUTDOWN_CLOSE_CODE: u16 = 1001;
+
+/// Default time to wait for connections to close on shutdown
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Token shared between the server and its background tasks to propagate shutdown.
+///
+/// Cloning the token is cheap; all clones observe the same shutdown state.
+#[derive(Debug, Clone)]
+pub struct ShutdownToken {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl ShutdownToken {
+    /// Create a new token in the "running" state
+    pub fn new() -> Self {
+        let (sender, _receiver) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+        }
+    }
+
+    /// Request shutdown of every task holding this token
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// Check whether shutdown has been requested
+    pub fn is_triggered(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Wait until shutdown is requested
+    pub async fn cancelled(&self) {
+        let mut receiver = self.sender.subscribe();
+        // The sender lives as long as `self`, so this cannot fail
+        let _ = receiver.wait_for(|triggered| *triggered).await;
+    }
+}
+
+impl Default for ShutdownToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Handle used to request a configuration reload (SIGHUP).
+///
+/// Subscribers receive the reload generation, incremented on every request.
+#[derive(Debug, Clone)]
+pub struct ReloadHandle {
+    sender: Arc<watch::Sender<u64>>,
+}
+
+impl ReloadHandle {
+    /// Create a new reload handle
+    pub fn new() -> Self {
+        let (sender, _receiver) = watch::channel(0);
+        Self {
+            sender: Arc::new(sender),
+        }
+    }
+
+    /// Request a reload
+    pub fn trigger(&self) {
+        self.sender.send_modify(|generation| *generation += 1);
+    }
+
+    /// Subscribe to reload requests
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.sender.subscribe()
+    }
+
+    /// Check whether anything is listening for reload requests
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+}
 
+impl Default for ReloadHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Listen for OS signals until shutdown is requested.
+///
+/// Shutdown signals trigger `shutdown`; SIGHUP triggers `reload` and keeps listening.
+/// Returns immediately if `shutdown` is triggered by someone else.
+pub async fn listen_signals(shutdown: ShutdownToken, reload: ReloadHandle) {
+    #[cfg(unix)]
+    let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+        .expect("Failed to install SIGTERM handler");
     #[cfg(unix)]
-    let terminate = async {
-        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
-            .expect("Failed to install SIGTERM handler")
-            .recv()
-            .await;
-    };
-
-    #[cfg(not(unix))]
-    let terminate = std::future::pending::<()>();
-
-    tokio::select! {
-        _ = ctrl_c => {
-            tracing::info!("Received Ctrl+C, initiating graceful shutdown...");
-        },
-        _ = terminate => {
-            tracing::info!("Received SIGTERM, initiating graceful shutdown...");
-        },
+    let mut sighup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        .expect("Failed to install SIGHUP handler");
+    #[cfg(windows)]
+    let mut ctrl_break =
+        tokio::signal::windows::ctrl_break().expect("Failed to install Ctrl+Break handler");
+
+    loop {
+        #[cfg(unix)]
+        let terminate = sigterm.recv();
+        #[cfg(windows)]
+        let terminate = ctrl_break.recv();
+        #[cfg(not(any(unix, windows)))]
+        let terminate = std::future::pending::<Option<()>>();
+
+        #[cfg(unix)]
+        let hangup = sighup.recv();
+        #[cfg(not(unix))]
+        let hangup = std::future::pending::<Option<()>>();
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                tracing::info!("Received Ctrl+C, initiating graceful shutdown...");
+                break;
+            },
+            _ = terminate => {
+                #[cfg(windows)]
+                tracing::info!("Received Ctrl+Break, initiating graceful shutdown...");
+                #[cfg(not(windows))]
+                tracing::info!("Received SIGTERM, initiating graceful shutdown...");
+                break;
+            },
+            _ = hangup => {
+                if reload.has_subscribers() {
+                    tracing::info!("Received SIGHUP, reloading configuration...");
+                } else {
+                    tracing::info!("Received SIGHUP, but there is no reloadable configuration");
+                }
+                reload.trigger();
+            },
+            _ = shutdown.cancelled() => return,
+        }
+    }
+
+    shutdown.trigger();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_token_trigger() {
+        // テスト項目: トリガーするとクローンされた全てのトークンがシャットダウンを検知する
+        // given (前提条件):
+        let token = ShutdownToken::new();
+        let cloned = token.clone();
+        let waiter = tokio::spawn(async move { cloned.cancelled().await });
+
+        // when (操作):
+        token.trigger();
+
+        // then (期待する結果):
+        assert!(token.is_triggered());
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+            .await
+            .expect("cancelled() should resolve after trigger")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_token_cancelled_after_trigger() {
+        // テスト項目: トリガー後に待機を開始しても即座に完了する
+        // given (前提条件):
+        let token = ShutdownToken::new();
+        token.trigger();
+
+        // when (操作):
+        let result =
+            tokio::time::timeout(std::time::Duration::from_millis(100), token.cancelled()).await;
+
+        // then (期待する結果):
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_reload_handle_increments_generation() {
+        // テスト項目: リロード要求ごとに世代番号が増加する
+        // given (前提条件):
+        let reload = ReloadHandle::new();
+        let receiver = reload.subscribe();
+
+        // when (操作):
+        reload.trigger();
+        reload.trigger();
+
+        // then (期待する結果):
+        assert!(reload.has_subscribers());
+        assert_eq!(*receiver.borrow(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_listen_signals_returns_when_token_triggered() {
+        // テスト項目: 外部からシャットダウンが要求されるとシグナル待機が終了する
+        // given (前提条件):
+        let token = ShutdownToken::new();
+        let listener = tokio::spawn(listen_signals(token.clone(), ReloadHandle::new()));
+
+        // when (操作):
+        token.trigger();
+
+        // then (期待する結果):
+        tokio::time::timeout(std::time::Duration::from_secs(1), listener)
+            .await
+            .expect("listen_signals should return after trigger")
+            .unwrap();
     }
 }
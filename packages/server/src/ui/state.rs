@@ -1,15 +1,42 @@
 //! Server state and connection management.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use crate::usecase::{
-    ConnectParticipantUseCase, DisconnectParticipantUseCase, GetRoomDetailUseCase,
-    GetRoomStateUseCase, GetRoomsUseCase, SendMessageUseCase,
+use tokio::sync::Mutex;
+
+use super::{
+    approval::PendingJoins,
+    auth::Authentication,
+    challenge::ConnectChallenge,
+    client_ip::TrustedProxies,
+    cluster::RoomShards,
+    config::DuplicatePolicy,
+    guest::GuestPolicy,
+    handover::ConnectionTracker,
+    heartbeat::{HeartbeatRegistry, Keepalive},
+    ip_filter::IpFilter,
+    session::SessionRegistry,
+    signal::ShutdownToken,
+};
+use crate::{
+    domain::{ClientId, Locale, RepositoryError, RoomId},
+    infrastructure::{
+        cluster::ClusterMembership, metrics::Metrics, sanitize::SanitizeProfile, wire_log::WireLog,
+    },
+    usecase::{
+        CheckHealthUseCase, ConnectParticipantUseCase, CreateRoomUseCase,
+        DisconnectParticipantUseCase, EraseClientDataUseCase, ExportMessagesUseCase,
+        ForwardMessageUseCase, GetMessageHistoryUseCase, GetRoomDetailUseCase,
+        GetRoomMessagesUseCase, GetRoomStateUseCase, GetRoomStatsUseCase, GetRoomsUseCase,
+        JoinRoomError, JoinRoomUseCase, KickParticipantUseCase, ManageBreakoutsUseCase,
+        ManageIntegrationsUseCase, ModerateMessagesUseCase, RoomUseCases, SearchMessagesUseCase,
+        SendMessageUseCase, StarMessagesUseCase,
+    },
 };
 
 /// Shared application state
 ///
-/// AppState は UseCase のみを保持します。
+/// AppState は UseCase と UI 層の設定のみを保持します。
 /// Repository や MessagePusher は UseCase が内部で保持しており、
 /// ハンドラーからは UseCase を通じてのみアクセスします。
 pub struct AppState {
@@ -25,4 +52,171 @@ pub struct AppState {
     pub get_rooms_usecase: Arc<GetRoomsUseCase>,
     /// GetRoomDetailUseCase（ルーム詳細取得のユースケース）
     pub get_room_detail_usecase: Arc<GetRoomDetailUseCase>,
+    /// GetRoomMessagesUseCase（メッセージ取得のユースケース）
+    pub get_room_messages_usecase: Arc<GetRoomMessagesUseCase>,
+    /// GetMessageHistoryUseCase（接続時の履歴取得のユースケース、`None` の場合は接続時に履歴を送らない）
+    pub get_message_history_usecase: Option<Arc<GetMessageHistoryUseCase>>,
+    /// 既定のルームの UseCase（上の UseCase と同じもの、`room_id` を指定しない接続が参加する）
+    pub default_room: Arc<RoomUseCases>,
+    /// CreateRoomUseCase（ルーム作成のユースケース、`None` の場合はルームを作成できない）
+    pub create_room_usecase: Option<Arc<CreateRoomUseCase>>,
+    /// JoinRoomUseCase（ルーム参加のユースケース、`None` の場合は既定のルームにのみ参加できる）
+    pub join_room_usecase: Option<Arc<JoinRoomUseCase>>,
+    /// CheckHealthUseCase（依存先のヘルスチェックのユースケース、`None` の場合は依存先を確認しない）
+    pub check_health_usecase: Option<Arc<CheckHealthUseCase>>,
+    /// GetRoomStatsUseCase（ルームの統計取得のユースケース、`None` の場合は統計を提供しない）
+    pub get_room_stats_usecase: Option<Arc<GetRoomStatsUseCase>>,
+    /// SearchMessagesUseCase（メッセージ検索のユースケース、`None` の場合は検索を提供しない）
+    pub search_messages_usecase: Option<Arc<SearchMessagesUseCase>>,
+    /// ExportMessagesUseCase（メッセージ履歴のエクスポートのユースケース、`None` の場合はエクスポートを提供しない）
+    pub export_messages_usecase: Option<Arc<ExportMessagesUseCase>>,
+    /// ForwardMessageUseCase（メッセージ転送のユースケース、`None` の場合は転送を受け付けない）
+    pub forward_message_usecase: Option<Arc<ForwardMessageUseCase>>,
+    /// StarMessagesUseCase（メッセージのスターのユースケース、`None` の場合はスターを受け付けない）
+    pub star_messages_usecase: Option<Arc<StarMessagesUseCase>>,
+    /// EraseClientDataUseCase（クライアントのデータ削除のユースケース、`None` の場合は削除を受け付けない）
+    pub erase_client_data_usecase: Option<Arc<EraseClientDataUseCase>>,
+    /// ModerateMessagesUseCase（通報とモデレーションのユースケース、`None` の場合は通報を受け付けない）
+    pub moderate_messages_usecase: Option<Arc<ModerateMessagesUseCase>>,
+    /// KickParticipantUseCase（参加者のキックと BAN のユースケース、`None` の場合は受け付けない）
+    pub kick_participant_usecase: Option<Arc<KickParticipantUseCase>>,
+    /// ManageBreakoutsUseCase（ブレイクアウトルームの管理のユースケース、`None` の場合は受け付けない）
+    pub manage_breakouts_usecase: Option<Arc<ManageBreakoutsUseCase>>,
+    /// ManageIntegrationsUseCase（ルームの連携設定の管理のユースケース、`None` の場合は受け付けない）
+    pub manage_integrations_usecase: Option<Arc<ManageIntegrationsUseCase>>,
+    /// 管理用エンドポイントの Bearer トークン（未設定の場合はデータ削除・モデレーション・キック・連携設定を受け付けない）
+    pub admin_token: Option<String>,
+    /// 転送ヘッダーを信頼するプロキシ
+    pub trusted_proxies: TrustedProxies,
+    /// ログインとアクセストークンの検証（認証が無効な場合は `None`）
+    pub auth: Option<Arc<Authentication>>,
+    /// リクエストを許可・拒否するネットワーク（管理用エンドポイントから置き換える）
+    pub ip_filter: IpFilter,
+    /// Incoming webhook のトークン（未設定の場合は無効）
+    pub incoming_webhook_token: Option<String>,
+    /// クラスタのメンバーシップ（クラスタ構成でない場合は `None`）
+    pub cluster: Option<Arc<Mutex<ClusterMembership>>>,
+    /// ルームのシャーディング（クラスタ構成でない場合は `None`）
+    pub room_shards: Option<Arc<RoomShards>>,
+    /// WebSocket の URL スキーム（TLS を終端する場合は `wss`、リダイレクト先の URL に使う）
+    pub ws_scheme: &'static str,
+    /// 接続ごとの重複排除ウィンドウのサイズ
+    pub dedup_window: usize,
+    /// 送信するメッセージを 1 つのフレームにまとめる時間（バッチ送信しない場合は `None`）
+    pub batch_window: Option<Duration>,
+    /// 減速（slow-down）を要請する送信キューのメッセージ数（要請しない場合は `None`）
+    pub slow_down_threshold: Option<usize>,
+    /// 接続の死活監視（Ping の間隔とアイドルタイムアウト、監視しない場合は `None`）
+    pub keepalive: Option<Keepalive>,
+    /// 参加中の接続ごとの最後の受信時刻
+    pub heartbeats: Arc<HeartbeatRegistry>,
+    /// 送受信したフレームを記録するワイヤーログ（記録しない場合は `None`）
+    pub wire_log: Option<Arc<WireLog>>,
+    /// 接続済みの client_id で接続された場合の扱い
+    pub duplicate_policy: DuplicatePolicy,
+    /// 受信したメッセージ内容のサニタイズ（保存・ブロードキャストの前に適用する）
+    pub sanitize_profile: SanitizeProfile,
+    /// 接続中のクライアントのセッション（takeover 時に以前の接続を閉じる）
+    pub sessions: SessionRegistry,
+    /// 参加の承認を待っているクライアント（承認が必要なルームに接続した）
+    pub pending_joins: PendingJoins,
+    /// client_id なしで接続するゲストの扱い
+    pub guests: GuestPolicy,
+    /// 接続前に解かせる proof-of-work（管理用エンドポイントから有効・無効を切り替える）
+    pub connect_challenge: ConnectChallenge,
+    /// ルームのシステムメッセージ（入室・退室の通知など）の言語
+    pub locale: Locale,
+    /// リスナーを新しいプロセスに引き継いだ時にトリガーされる（接続に再接続を促す）
+    pub draining: ShutdownToken,
+    /// サーバーの停止時にトリガーされる（接続に停止を伝えて閉じる）
+    pub stopping: ShutdownToken,
+    /// 引き継ぎ時にクライアントの再接続を分散させる幅
+    pub reconnect_stagger: Duration,
+    /// 開いている WebSocket 接続
+    pub connections: ConnectionTracker,
+    /// ブロードキャストのレイテンシと送信キューの深さ（`/metrics` で公開）
+    pub metrics: Arc<Metrics>,
+}
+
+impl AppState {
+    /// 接続先のルームの UseCase を取得（`room_id` を指定しない場合は既定のルーム）
+    pub async fn join_room(
+        &self,
+        room_id: Option<&str>,
+    ) -> Result<Arc<RoomUseCases>, JoinRoomError> {
+        match (room_id, &self.join_room_usecase) {
+            (None, _) => Ok(self.default_room.clone()),
+            (Some(room_id), _) if room_id == self.default_room.room_id.as_str() => {
+                Ok(self.default_room.clone())
+            }
+            (Some(room_id), Some(usecase)) => usecase.execute(room_id).await,
+            (Some(_), None) => Err(JoinRoomError::RoomNotFound),
+        }
+    }
+
+    /// クライアントの接続がルームに参加したことを記録（ゲストは接続ごとに別の ID のため数えない）
+    ///
+    /// 成功した場合、接続を閉じる時に `leave_room` を呼び出す。
+    pub async fn enter_room(
+        &self,
+        client_id: &ClientId,
+        room: &RoomUseCases,
+    ) -> Result<(), JoinRoomError> {
+        match &self.join_room_usecase {
+            Some(usecase) if !client_id.is_guest() => usecase.enter(client_id, &room.room_id).await,
+            _ => Ok(()),
+        }
+    }
+
+    /// クライアントの接続がルームから退出したことを記録
+    pub async fn leave_room(&self, client_id: &ClientId, room: &RoomUseCases) {
+        if let Some(usecase) = &self.join_room_usecase
+            && !client_id.is_guest()
+        {
+            usecase.leave(client_id, &room.room_id).await;
+        }
+    }
+
+    /// セッションのキー（同じクライアントでもルームごとに別のセッションになる）
+    pub fn session_key(&self, room: &RoomUseCases, client_id: &str) -> String {
+        self.room_session_key(&room.room_id, client_id)
+    }
+
+    /// ルームの ID からセッションのキーを取得
+    pub fn room_session_key(&self, room_id: &RoomId, client_id: &str) -> String {
+        if *room_id == self.default_room.room_id {
+            client_id.to_string()
+        } else {
+            format!("{}@{}", client_id, room_id)
+        }
+    }
+
+    /// クライアントが管理者・モデレーターに BAN されているか
+    pub async fn is_banned(&self, client_id: &ClientId) -> Result<bool, RepositoryError> {
+        if let Some(usecase) = &self.kick_participant_usecase
+            && usecase.is_banned(client_id).await?
+        {
+            return Ok(true);
+        }
+        match &self.moderate_messages_usecase {
+            Some(usecase) => usecase.is_banned(client_id).await,
+            None => Ok(false),
+        }
+    }
+
+    /// クライアントがルームに参加できるか（メンバーを指定したブレイクアウトにはメンバーだけが参加できる）
+    pub async fn admits(&self, room: &RoomUseCases, client_id: Option<&ClientId>) -> bool {
+        match &self.manage_breakouts_usecase {
+            Some(usecase) => usecase.admits(&room.room_id, client_id).await,
+            None => true,
+        }
+    }
+
+    /// ルームへの参加にモデレーターの承認が必要か（連携設定を管理しない場合は不要）
+    pub async fn requires_approval(&self, room: &RoomUseCases) -> Result<bool, RepositoryError> {
+        match &self.manage_integrations_usecase {
+            Some(usecase) => usecase.requires_approval(&room.room_id).await,
+            None => Ok(false),
+        }
+    }
 }
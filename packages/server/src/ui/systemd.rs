@@ -0,0 +1,218 @@
+//! systemd integration (socket activation and sd_notify).
+//!
+//! - Socket activation: when started by a `.socket` unit, systemd passes the listening
+//!   socket as file descriptor 3 and sets `LISTEN_PID` / `LISTEN_FDS`.
+//! - Readiness notification: `READY=1` / `STOPPING=1` / `WATCHDOG=1` are sent as datagrams
+//!   to the socket given in `NOTIFY_SOCKET`.
+//!
+//! All functions are no-ops when the server is not running under systemd.
+
+use std::time::Duration;
+
+/// First file descriptor passed by systemd socket activation (`SD_LISTEN_FDS_START`)
+#[cfg(unix)]
+const SD_LISTEN_FDS_START: i32 = 3;
+
+/// Parse the number of sockets passed via socket activation.
+///
+/// # Arguments
+///
+/// * `listen_pid` - Value of `LISTEN_PID`
+/// * `listen_fds` - Value of `LISTEN_FDS`
+/// * `pid` - PID of the current process
+///
+/// # Returns
+///
+/// The number of passed sockets, or 0 if the sockets are not meant for this process
+pub fn parse_listen_fds(listen_pid: Option<&str>, listen_fds: Option<&str>, pid: u32) -> usize {
+    let Some(listen_pid) = listen_pid.and_then(|v| v.parse::<u32>().ok()) else {
+        return 0;
+    };
+    if listen_pid != pid {
+        return 0;
+    }
+    listen_fds.and_then(|v| v.parse().ok()).unwrap_or(0)
+}
+
+/// Parse the watchdog ping interval from `WATCHDOG_USEC` / `WATCHDOG_PID`.
+///
+/// The watchdog is pinged at half the configured timeout, as recommended by sd_watchdog_enabled(3).
+///
+/// # Returns
+///
+/// The ping interval, or `None` if the watchdog is not enabled for this process
+pub fn parse_watchdog_interval(
+    watchdog_usec: Option<&str>,
+    watchdog_pid: Option<&str>,
+    pid: u32,
+) -> Option<Duration> {
+    if let Some(watchdog_pid) = watchdog_pid
+        && watchdog_pid.parse::<u32>().ok() != Some(pid)
+    {
+        return None;
+    }
+    let usec = watchdog_usec?.parse::<u64>().ok()?;
+    if usec == 0 {
+        return None;
+    }
+    Some(Duration::from_micros(usec / 2))
+}
+
+/// Take the listening socket passed by systemd socket activation.
+///
+/// Returns `None` if the process was not socket-activated.
+#[cfg(unix)]
+pub fn take_activated_listener() -> std::io::Result<Option<std::net::TcpListener>> {
+    use std::os::fd::FromRawFd;
+
+    let count = parse_listen_fds(
+        std::env::var("LISTEN_PID").ok().as_deref(),
+        std::env::var("LISTEN_FDS").ok().as_deref(),
+        std::process::id(),
+    );
+    if count == 0 {
+        return Ok(None);
+    }
+    if count > 1 {
+        tracing::warn!(
+            "systemd passed {} sockets; only the first one is used",
+            count
+        );
+    }
+
+    // SAFETY: systemd guarantees that fd 3 is an open listening socket owned by this process
+    // when LISTEN_PID matches our PID, and nothing else in the process has claimed it.
+    let listener = unsafe { std::net::TcpListener::from_raw_fd(SD_LISTEN_FDS_START) };
+    listener.set_nonblocking(true)?;
+    Ok(Some(listener))
+}
+
+#[cfg(not(unix))]
+pub fn take_activated_listener() -> std::io::Result<Option<std::net::TcpListener>> {
+    Ok(None)
+}
+
+/// Send a state notification to systemd (e.g. `READY=1`).
+///
+/// Does nothing if `NOTIFY_SOCKET` is not set.
+#[cfg(unix)]
+pub fn notify(state: &str) {
+    use std::os::unix::net::UnixDatagram;
+
+    let Ok(socket_path) = std::env::var("NOTIFY_SOCKET") else {
+        return;
+    };
+
+    let result = UnixDatagram::unbound().and_then(|socket| {
+        if let Some(abstract_name) = socket_path.strip_prefix('@') {
+            send_to_abstract(&socket, abstract_name, state)
+        } else {
+            socket.send_to(state.as_bytes(), &socket_path).map(|_| ())
+        }
+    });
+
+    match result {
+        Ok(()) => tracing::debug!("Sent '{}' to systemd", state),
+        Err(e) => tracing::warn!("Failed to notify systemd ({}): {}", state, e),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn send_to_abstract(
+    socket: &std::os::unix::net::UnixDatagram,
+    name: &str,
+    state: &str,
+) -> std::io::Result<()> {
+    use std::os::linux::net::SocketAddrExt;
+
+    let addr = std::os::unix::net::SocketAddr::from_abstract_name(name.as_bytes())?;
+    socket.send_to_addr(state.as_bytes(), &addr).map(|_| ())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn send_to_abstract(
+    _socket: &std::os::unix::net::UnixDatagram,
+    _name: &str,
+    _state: &str,
+) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "abstract notify sockets are only supported on Linux",
+    ))
+}
+
+#[cfg(not(unix))]
+pub fn notify(_state: &str) {}
+
+/// Spawn a task that pings the systemd watchdog, if enabled for this process.
+pub fn spawn_watchdog() -> Option<tokio::task::JoinHandle<()>> {
+    let interval = parse_watchdog_interval(
+        std::env::var("WATCHDOG_USEC").ok().as_deref(),
+        std::env::var("WATCHDOG_PID").ok().as_deref(),
+        std::process::id(),
+    )?;
+
+    tracing::info!("systemd watchdog enabled (ping every {:?})", interval);
+    Some(engawa_shared::task::spawn("systemd-watchdog", async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            notify("WATCHDOG=1");
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listen_fds_matching_pid() {
+        // テスト項目: LISTEN_PID が自プロセスと一致する場合、ソケット数が返される
+        // when (操作):
+        let count = parse_listen_fds(Some("42"), Some("1"), 42);
+
+        // then (期待する結果):
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_parse_listen_fds_other_pid() {
+        // テスト項目: LISTEN_PID が他プロセスの場合、ソケットは使用しない
+        // when (操作):
+        let count = parse_listen_fds(Some("41"), Some("1"), 42);
+
+        // then (期待する結果):
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_parse_listen_fds_not_activated() {
+        // テスト項目: 環境変数が無い場合、ソケットアクティベーションではないと判定される
+        // when (操作):
+        let count = parse_listen_fds(None, None, 42);
+
+        // then (期待する結果):
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_parse_watchdog_interval_is_half_timeout() {
+        // テスト項目: watchdog の ping 間隔はタイムアウトの半分になる
+        // when (操作):
+        let interval = parse_watchdog_interval(Some("10000000"), Some("42"), 42);
+
+        // then (期待する結果):
+        assert_eq!(interval, Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_parse_watchdog_interval_other_pid() {
+        // テスト項目: WATCHDOG_PID が他プロセスの場合、watchdog は無効になる
+        // when (操作):
+        let interval = parse_watchdog_interval(Some("10000000"), Some("41"), 42);
+
+        // then (期待する結果):
+        assert_eq!(interval, None);
+    }
+}
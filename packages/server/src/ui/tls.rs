@@ -0,0 +1,206 @@
+//! TLS termination.
+//!
+//! With `--tls-cert` and `--tls-key`, the server accepts `https://` and `wss://` connections
+//! itself instead of leaving TLS to a reverse proxy. Only HTTP/1.1 is offered through ALPN,
+//! as WebSocket upgrades need it.
+
+use std::{future::Future, net::SocketAddr, path::Path, sync::Arc};
+
+use axum::Router;
+use axum_server::{Handle, tls_rustls::RustlsConfig};
+use rustls::{
+    ServerConfig,
+    pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+};
+
+use super::error::TlsError;
+
+/// Certificate chain and private key the server terminates TLS with
+#[derive(Clone)]
+pub struct TlsTermination {
+    config: RustlsConfig,
+}
+
+impl TlsTermination {
+    /// Load a PEM certificate chain and private key (PKCS#8, PKCS#1 or SEC1)
+    ///
+    /// # Arguments
+    ///
+    /// * `cert` - File holding the server certificate followed by its intermediates
+    /// * `key` - File holding the private key of the server certificate
+    pub fn load(cert: &Path, key: &Path) -> Result<Self, TlsError> {
+        let pem_error = |what: &'static str, path: &Path| {
+            let path = path.display().to_string();
+            move |e: rustls::pki_types::pem::Error| TlsError::Pem {
+                what,
+                path,
+                reason: e.to_string(),
+            }
+        };
+        let certs = CertificateDer::pem_file_iter(cert)
+            .map_err(pem_error("certificate", cert))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(pem_error("certificate", cert))?;
+        if certs.is_empty() {
+            return Err(TlsError::NoCertificate {
+                path: cert.display().to_string(),
+            });
+        }
+        let key = PrivateKeyDer::from_pem_file(key).map_err(pem_error("private key", key))?;
+
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let mut config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()?
+            .with_no_client_auth()
+            .with_single_cert(certs, key)?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        Ok(Self {
+            config: RustlsConfig::from_config(Arc::new(config)),
+        })
+    }
+}
+
+/// Serve the app over TLS until `shutdown` completes
+///
+/// Like `axum::serve`, in-flight requests and upgraded connections are waited for after
+/// shutdown is requested.
+///
+/// # Arguments
+///
+/// * `listener` - Listening socket (non-blocking)
+/// * `app` - Router to serve
+/// * `tls` - Certificate and key to terminate TLS with
+/// * `shutdown` - Future completing when the server should stop accepting connections
+pub async fn serve(
+    listener: std::net::TcpListener,
+    app: Router,
+    tls: TlsTermination,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    let handle = Handle::new();
+    let trigger = handle.clone();
+    let stop = engawa_shared::task::spawn("tls-shutdown", async move {
+        shutdown.await;
+        trigger.graceful_shutdown(None);
+    });
+    let result = axum_server::from_tcp_rustls(listener, tls.config)
+        .handle(handle)
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .await;
+    stop.abort();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use rustls::{ClientConfig, RootCertStore, pki_types::ServerName};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Self-signed certificate for `localhost` written to temporary PEM files
+    struct SelfSigned {
+        cert: std::path::PathBuf,
+        key: std::path::PathBuf,
+        der: CertificateDer<'static>,
+    }
+
+    impl SelfSigned {
+        fn generate() -> Self {
+            let rcgen::CertifiedKey { cert, key_pair } =
+                rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+            let dir = std::env::temp_dir();
+            let id = uuid::Uuid::new_v4();
+            let cert_path = dir.join(format!("engawa-tls-{}.crt", id));
+            let key_path = dir.join(format!("engawa-tls-{}.key", id));
+            std::fs::write(&cert_path, cert.pem()).unwrap();
+            std::fs::write(&key_path, key_pair.serialize_pem()).unwrap();
+            Self {
+                cert: cert_path,
+                key: key_path,
+                der: cert.der().clone(),
+            }
+        }
+    }
+
+    impl Drop for SelfSigned {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.cert);
+            let _ = std::fs::remove_file(&self.key);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serve_over_tls_with_self_signed_certificate() {
+        // テスト項目: 自己署名証明書で TLS を終端し、証明書を信頼したクライアントが HTTP/1.1 で応答を受け取れる
+        // given (前提条件):
+        let certificate = SelfSigned::generate();
+        let tls = TlsTermination::load(&certificate.cert, &certificate.key).unwrap();
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let app = Router::new().route("/", get(|| async { "hello over tls" }));
+        let server = tokio::spawn(serve(listener, app, tls, async move {
+            let _ = stopped.await;
+        }));
+
+        let mut roots = RootCertStore::empty();
+        roots.add(certificate.der.clone()).unwrap();
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let mut config = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+
+        // when (操作):
+        let tcp = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let mut stream = connector
+            .connect(ServerName::try_from("localhost").unwrap(), tcp)
+            .await
+            .unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let alpn = stream.get_ref().1.alpn_protocol().map(<[u8]>::to_vec);
+
+        // then (期待する結果):
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.ends_with("hello over tls"), "{}", response);
+        assert_eq!(alpn.as_deref(), Some(&b"http/1.1"[..]));
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_load_rejects_missing_files_and_mismatched_key() {
+        // テスト項目: 存在しないファイル、証明書の無いファイル、証明書と一致しない鍵はエラーになる
+        // given (前提条件):
+        let certificate = SelfSigned::generate();
+        let other = SelfSigned::generate();
+        let missing = std::env::temp_dir().join("engawa-tls-missing.crt");
+
+        // when (操作):
+        let missing_cert = TlsTermination::load(&missing, &certificate.key);
+        let key_as_cert = TlsTermination::load(&certificate.key, &certificate.key);
+        let mismatched = TlsTermination::load(&certificate.cert, &other.key);
+
+        // then (期待する結果):
+        assert!(matches!(
+            missing_cert,
+            Err(TlsError::Pem {
+                what: "certificate",
+                ..
+            })
+        ));
+        assert!(matches!(key_as_cert, Err(TlsError::NoCertificate { .. })));
+        assert!(matches!(mismatched, Err(TlsError::Config(_))));
+    }
+}
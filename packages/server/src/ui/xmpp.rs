@@ -0,0 +1,788 @@
+//! XMPP gateway exposing the room as a multi-user chat (XEP-0045).
+//!
+//! The server connects to an existing XMPP server as an external component (XEP-0114) and
+//! serves the room as the MUC `{room_id}@{component domain}`:
+//!
+//! - Joining the MUC (presence to `room@domain/nick`) connects a participant `{prefix}{nick}`
+//!   (e.g. `xmpp:alice`) through `ConnectParticipantUseCase`, so XMPP occupants are ordinary
+//!   room participants visible to WebSocket clients.
+//! - Room broadcasts (the WebSocket protocol in [`dto::websocket`]) delivered to an occupant
+//!   are translated into groupchat messages and occupant presences.
+//! - Groupchat messages from an occupant are sent through `SendMessageUseCase` and reflected
+//!   back to the sender as MUC requires.
+//!
+//! Only the basic MUC protocol is supported: no history, private messages, nickname changes
+//! or moderation.
+//!
+//! [`dto::websocket`]: crate::infrastructure::dto::websocket
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use serde::Deserialize;
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use crate::{
+    domain::{ClientId, MessageContent, Participant, RoomId, Timestamp},
+    infrastructure::{
+        dto::websocket::{
+            ChatMessage, MessageType, ParticipantJoinedMessage, ParticipantLeftMessage,
+        },
+        xmpp::{self, Element, StanzaReader, StanzaWriter, escape},
+    },
+    usecase::{ConnectError, SendMessageError},
+};
+use engawa_shared::time::get_jst_timestamp;
+
+use super::{
+    presenter::websocket::{participant_joined, participant_left},
+    signal::ShutdownToken,
+    state::AppState,
+};
+
+/// Delay before reconnecting to the XMPP server
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+const NS_MUC: &str = "http://jabber.org/protocol/muc";
+const NS_MUC_USER: &str = "http://jabber.org/protocol/muc#user";
+const NS_DISCO_INFO: &str = "http://jabber.org/protocol/disco#info";
+const NS_STANZAS: &str = "urn:ietf:params:xml:ns:xmpp-stanzas";
+
+/// Connection settings of the XMPP gateway
+pub struct XmppGateway {
+    server_addr: String,
+    domain: String,
+    secret: String,
+    room_id: RoomId,
+    nick_prefix: String,
+}
+
+impl XmppGateway {
+    /// Create a new gateway
+    ///
+    /// # Arguments
+    ///
+    /// * `server_addr` - Component port of the XMPP server (e.g. `localhost:5347`)
+    /// * `domain` - Component domain configured on the XMPP server (e.g. `chat.example.org`)
+    /// * `secret` - Shared secret configured on the XMPP server
+    /// * `room_id` - Room served as the MUC `{room_id}@{domain}`
+    /// * `nick_prefix` - Prefix of the client IDs of XMPP occupants in the room
+    pub fn new(
+        server_addr: String,
+        domain: String,
+        secret: String,
+        room_id: RoomId,
+        nick_prefix: String,
+    ) -> Self {
+        Self {
+            server_addr,
+            domain,
+            secret,
+            room_id,
+            nick_prefix,
+        }
+    }
+
+    /// JID of the MUC serving the room
+    pub fn room_jid(&self) -> String {
+        format!("{}@{}", self.room_id.as_str(), self.domain)
+    }
+}
+
+/// Split a JID into its bare part and resource
+pub fn split_jid(jid: &str) -> (&str, Option<&str>) {
+    match jid.split_once('/') {
+        Some((bare, resource)) => (bare, Some(resource)),
+        None => (jid, None),
+    }
+}
+
+/// Nickname of a room participant in the MUC
+fn nick_of<'a>(nick_prefix: &str, client_id: &'a str) -> &'a str {
+    client_id.strip_prefix(nick_prefix).unwrap_or(client_id)
+}
+
+/// Occupant presence (`available == false` for leaving occupants)
+fn occupant_presence(from: &str, to: &str, available: bool, status_codes: &[u16]) -> String {
+    let (kind, role) = if available {
+        ("", "participant")
+    } else {
+        (" type='unavailable'", "none")
+    };
+    let statuses: String = status_codes
+        .iter()
+        .map(|code| format!("<status code='{}'/>", code))
+        .collect();
+    format!(
+        "<presence from='{}' to='{}'{}><x xmlns='{}'><item affiliation='none' role='{}'/>{}</x></presence>",
+        escape(from),
+        escape(to),
+        kind,
+        NS_MUC_USER,
+        role,
+        statuses
+    )
+}
+
+/// Groupchat message
+fn groupchat_message(from: &str, to: &str, id: Option<&str>, body: &str) -> String {
+    let id = id
+        .map(|id| format!(" id='{}'", escape(id)))
+        .unwrap_or_default();
+    format!(
+        "<message type='groupchat' from='{}' to='{}'{}><body>{}</body></message>",
+        escape(from),
+        escape(to),
+        id,
+        escape(body)
+    )
+}
+
+/// Error reply to a stanza (RFC 6120 §8.3)
+fn error_reply(request: &Element, error_type: &str, condition: &str) -> Option<String> {
+    let from = request.attr("from")?;
+    let to = request.attr("to")?;
+    let id = request
+        .attr("id")
+        .map(|id| format!(" id='{}'", escape(id)))
+        .unwrap_or_default();
+    Some(format!(
+        "<{kind} type='error' from='{}' to='{}'{}><error type='{}'><{} xmlns='{}'/></error></{kind}>",
+        escape(to),
+        escape(from),
+        id,
+        error_type,
+        condition,
+        NS_STANZAS,
+        kind = request.name
+    ))
+}
+
+#[derive(Deserialize)]
+struct Envelope {
+    r#type: MessageType,
+}
+
+/// Translate a room broadcast into a stanza for an occupant (`None` if not relayed)
+///
+/// # Arguments
+///
+/// * `room_jid` - JID of the MUC
+/// * `nick_prefix` - Prefix of the client IDs of XMPP occupants
+/// * `to` - Full JID of the occupant
+/// * `json` - Broadcast message (WebSocket protocol)
+pub fn translate(room_jid: &str, nick_prefix: &str, to: &str, json: &str) -> Option<String> {
+    let occupant = |client_id: &str| format!("{}/{}", room_jid, nick_of(nick_prefix, client_id));
+    match serde_json::from_str::<Envelope>(json).ok()?.r#type {
+        MessageType::Chat => {
+            let message: ChatMessage = serde_json::from_str(json).ok()?;
+            Some(groupchat_message(
+                &occupant(&message.client_id),
+                to,
+                None,
+                &message.content,
+            ))
+        }
+        MessageType::ParticipantJoined => {
+            let message: ParticipantJoinedMessage = serde_json::from_str(json).ok()?;
+            Some(occupant_presence(
+                &occupant(&message.client_id),
+                to,
+                true,
+                &[],
+            ))
+        }
+        MessageType::ParticipantLeft => {
+            let message: ParticipantLeftMessage = serde_json::from_str(json).ok()?;
+            Some(occupant_presence(
+                &occupant(&message.client_id),
+                to,
+                false,
+                &[],
+            ))
+        }
+        MessageType::RoomConnected
+        | MessageType::RoomHistory
+        | MessageType::ServerShutdown
+        | MessageType::BackfillRequest
+        | MessageType::ListRooms
+        | MessageType::Forward
+        | MessageType::Star
+        | MessageType::Unstar
+        | MessageType::StarUpdated
+        | MessageType::Hello
+        | MessageType::HelloAck
+        | MessageType::SetActivity
+        | MessageType::ActivityUpdated
+        | MessageType::SetDisplayName
+        | MessageType::ParticipantRenamed
+        | MessageType::RoomFrozen
+        | MessageType::RoomUnfrozen
+        | MessageType::Disconnected
+        | MessageType::RoomList
+        | MessageType::MessageDeleted
+        | MessageType::TypingStarted
+        | MessageType::TypingStopped
+        | MessageType::SlowDown
+        | MessageType::Heartbeat
+        | MessageType::Welcome
+        | MessageType::JoinPending
+        | MessageType::JoinRequested
+        | MessageType::Poll
+        | MessageType::Vote
+        | MessageType::PollUpdated
+        | MessageType::Error => None,
+    }
+}
+
+/// Run the gateway until shutdown is requested, reconnecting when the stream is lost
+pub async fn run_gateway(gateway: XmppGateway, state: Arc<AppState>, shutdown: ShutdownToken) {
+    while !shutdown.is_triggered() {
+        let connection = tokio::select! {
+            connection = xmpp::connect(&gateway.server_addr, &gateway.domain, &gateway.secret) => connection,
+            _ = shutdown.cancelled() => break,
+        };
+        match connection {
+            Ok((reader, writer)) => {
+                tracing::info!(
+                    "Connected to XMPP server as '{}', serving MUC {}",
+                    gateway.domain,
+                    gateway.room_jid()
+                );
+                serve(&gateway, reader, writer, &state, &shutdown).await;
+            }
+            Err(e) => tracing::warn!("Failed to connect to XMPP server: {}", e),
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(RECONNECT_DELAY) => {},
+            _ = shutdown.cancelled() => break,
+        }
+    }
+    tracing::info!("XMPP gateway stopped");
+}
+
+/// Serve the MUC over an established component stream until it is lost or shutdown is requested
+async fn serve(
+    gateway: &XmppGateway,
+    mut reader: StanzaReader,
+    mut writer: StanzaWriter,
+    state: &Arc<AppState>,
+    shutdown: &ShutdownToken,
+) {
+    // Reading is not cancel-safe, so stanzas are read on a dedicated task
+    let (stanza_sender, mut stanzas) = mpsc::unbounded_channel();
+    let reader_task = engawa_shared::task::spawn("xmpp-reader", async move {
+        loop {
+            match reader.read_stanza().await {
+                Ok(stanza) => {
+                    if stanza_sender.send(stanza).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("XMPP stream lost: {}", e);
+                    break;
+                }
+            }
+        }
+    });
+
+    let (outbound, mut outbound_receiver) = mpsc::unbounded_channel::<String>();
+    let writer_task = engawa_shared::task::spawn("xmpp-writer", async move {
+        while let Some(stanza) = outbound_receiver.recv().await {
+            if let Err(e) = writer.send(&stanza).await {
+                tracing::warn!("Failed to write to XMPP stream: {}", e);
+                return;
+            }
+        }
+        writer.close().await;
+    });
+
+    let mut session = Session {
+        state: state.clone(),
+        domain: gateway.domain.clone(),
+        room_jid: gateway.room_jid(),
+        room_id: gateway.room_id.as_str().to_string(),
+        nick_prefix: gateway.nick_prefix.clone(),
+        outbound,
+        occupants: HashMap::new(),
+    };
+
+    let shutting_down = loop {
+        tokio::select! {
+            stanza = stanzas.recv() => match stanza {
+                Some(stanza) => session.handle_stanza(stanza).await,
+                None => break false,
+            },
+            _ = shutdown.cancelled() => break true,
+        }
+    };
+
+    session.close(shutting_down).await;
+    reader_task.abort();
+    let _ = writer_task.await;
+}
+
+/// XMPP user joined to the MUC
+struct Occupant {
+    nick: String,
+    client_id: ClientId,
+    /// Task translating room broadcasts into stanzas for this occupant
+    task: JoinHandle<()>,
+}
+
+/// State of a component stream
+struct Session {
+    state: Arc<AppState>,
+    domain: String,
+    room_jid: String,
+    room_id: String,
+    nick_prefix: String,
+    outbound: mpsc::UnboundedSender<String>,
+    /// Occupants keyed by full JID
+    occupants: HashMap<String, Occupant>,
+}
+
+impl Session {
+    fn send(&self, stanza: String) {
+        // The writer task only stops when the stream is lost, which also ends the session
+        let _ = self.outbound.send(stanza);
+    }
+
+    fn reply_error(&self, request: &Element, error_type: &str, condition: &str) {
+        if request.attr("type") == Some("error") {
+            return;
+        }
+        if let Some(reply) = error_reply(request, error_type, condition) {
+            self.send(reply);
+        }
+    }
+
+    fn is_room(&self, jid: &str) -> bool {
+        jid.eq_ignore_ascii_case(&self.room_jid)
+    }
+
+    async fn handle_stanza(&mut self, stanza: Element) {
+        match stanza.name.as_str() {
+            "presence" => self.handle_presence(stanza).await,
+            "message" => self.handle_message(stanza).await,
+            "iq" => self.handle_iq(stanza),
+            _ => {}
+        }
+    }
+
+    async fn handle_presence(&mut self, presence: Element) {
+        let (Some(from), Some(to)) = (presence.attr("from"), presence.attr("to")) else {
+            return;
+        };
+        let (room, nick) = split_jid(to);
+        if !self.is_room(room) {
+            if presence.attr("type").is_none() {
+                self.reply_error(&presence, "cancel", "item-not-found");
+            }
+            return;
+        }
+
+        match (presence.attr("type"), nick) {
+            (Some("unavailable"), _) => self.leave(from).await,
+            (None, Some(nick)) if !nick.is_empty() => {
+                let (from, nick) = (from.to_string(), nick.to_string());
+                self.join(&presence, from, nick).await;
+            }
+            (None, _) => self.reply_error(&presence, "modify", "jid-malformed"),
+            _ => {}
+        }
+    }
+
+    async fn join(&mut self, presence: &Element, jid: String, nick: String) {
+        if let Some(occupant) = self.occupants.get(&jid) {
+            // Presence updates are accepted, nickname changes are not supported
+            if occupant.nick != nick {
+                self.reply_error(presence, "modify", "not-acceptable");
+            }
+            return;
+        }
+        if self
+            .occupants
+            .values()
+            .any(|occupant| occupant.nick == nick)
+        {
+            self.reply_error(presence, "cancel", "conflict");
+            return;
+        }
+
+        let client_id_str = format!("{}{}", self.nick_prefix, nick);
+        let Ok(client_id) = ClientId::try_from(client_id_str.clone()) else {
+            self.reply_error(presence, "modify", "jid-malformed");
+            return;
+        };
+
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Arc<str>>();
+        let connected_at = match self
+            .state
+            .connect_participant_usecase
+            .execute(client_id.clone(), sender)
+            .await
+        {
+            Ok(connected_at) => connected_at,
+            Err(ConnectError::DuplicateClientId(_)) => {
+                self.reply_error(presence, "cancel", "conflict");
+                return;
+            }
+            Err(ConnectError::RoomCapacityExceeded { .. }) => {
+                self.reply_error(presence, "wait", "service-unavailable");
+                return;
+            }
+            Err(ConnectError::PersistFailed(reason)) => {
+                tracing::error!("Failed to add XMPP user '{}': {}", jid, reason);
+                self.reply_error(presence, "wait", "internal-server-error");
+                return;
+            }
+        };
+        tracing::info!("XMPP user '{}' joined as '{}'", jid, client_id_str);
+
+        // Existing occupants, then the user's own presence and the room subject
+        let participants = self
+            .state
+            .connect_participant_usecase
+            .build_participant_list()
+            .await;
+        for participant in participants.iter().filter(|p| p.id != client_id) {
+            let from = format!(
+                "{}/{}",
+                self.room_jid,
+                nick_of(&self.nick_prefix, participant.id.as_str())
+            );
+            self.send(occupant_presence(&from, &jid, true, &[]));
+        }
+        let own = format!("{}/{}", self.room_jid, nick);
+        self.send(occupant_presence(&own, &jid, true, &[110]));
+        self.send(format!(
+            "<message type='groupchat' from='{}' to='{}'><subject>Engawa room {}</subject></message>",
+            escape(&self.room_jid),
+            escape(&jid),
+            escape(&self.room_id)
+        ));
+
+        let joined_msg = participant_joined(
+            Participant::new(client_id.clone(), connected_at),
+            self.state.locale,
+        );
+        let joined_json = serde_json::to_string(&joined_msg).unwrap();
+        if let Err(e) = self
+            .state
+            .connect_participant_usecase
+            .broadcast_participant_joined(&client_id, &joined_json)
+            .await
+        {
+            tracing::warn!("Failed to broadcast participant-joined: {}", e);
+        }
+
+        let (room_jid, nick_prefix, to) =
+            (self.room_jid.clone(), self.nick_prefix.clone(), jid.clone());
+        let outbound = self.outbound.clone();
+        let task = engawa_shared::task::spawn("xmpp-occupant", async move {
+            while let Some(json) = receiver.recv().await {
+                if let Some(stanza) = translate(&room_jid, &nick_prefix, &to, &json)
+                    && outbound.send(stanza).is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        self.occupants.insert(
+            jid,
+            Occupant {
+                nick,
+                client_id,
+                task,
+            },
+        );
+    }
+
+    async fn leave(&mut self, jid: &str) {
+        let Some(occupant) = self.occupants.remove(jid) else {
+            return;
+        };
+        let own = format!("{}/{}", self.room_jid, occupant.nick);
+        self.send(occupant_presence(&own, jid, false, &[110]));
+        tracing::info!("XMPP user '{}' left", jid);
+        self.disconnect(occupant).await;
+    }
+
+    /// Remove an occupant from the room and notify the remaining participants
+    async fn disconnect(&self, occupant: Occupant) {
+        occupant.task.abort();
+        let Ok(notify_targets) = self
+            .state
+            .disconnect_participant_usecase
+            .execute(occupant.client_id.clone())
+            .await
+        else {
+            tracing::warn!(
+                "Failed to disconnect participant '{}'",
+                occupant.client_id.as_str()
+            );
+            return;
+        };
+
+        let left_msg = participant_left(
+            &occupant.client_id,
+            Timestamp::new(get_jst_timestamp()),
+            self.state.locale,
+        );
+        let left_json = serde_json::to_string(&left_msg).unwrap();
+        if let Err(e) = self
+            .state
+            .disconnect_participant_usecase
+            .broadcast_participant_left(notify_targets, &left_json)
+            .await
+        {
+            tracing::warn!("Failed to broadcast participant-left: {}", e);
+        }
+    }
+
+    async fn handle_message(&mut self, message: Element) {
+        let (Some(from), Some(to)) = (message.attr("from"), message.attr("to")) else {
+            return;
+        };
+        if message.attr("type") == Some("error") {
+            return;
+        }
+        let (room, nick) = split_jid(to);
+        if !self.is_room(room) {
+            self.reply_error(&message, "cancel", "item-not-found");
+            return;
+        }
+        if message.attr("type") != Some("groupchat") || nick.is_some() {
+            // Private messages are not supported
+            self.reply_error(&message, "cancel", "feature-not-implemented");
+            return;
+        }
+        let Some(occupant) = self.occupants.get(from) else {
+            self.reply_error(&message, "modify", "not-acceptable");
+            return;
+        };
+        // Chat states, subject changes etc. carry no body
+        let Some(body) = message.child("body") else {
+            return;
+        };
+        let text = self.state.sanitize_profile.apply(&body.text).into_owned();
+        let Ok(content) = MessageContent::try_from(text.clone()) else {
+            self.reply_error(&message, "modify", "not-acceptable");
+            return;
+        };
+
+        let chat_message = ChatMessage {
+            r#type: MessageType::Chat,
+            client_id: occupant.client_id.as_str().to_string(),
+            content: text,
+            timestamp: get_jst_timestamp(),
+            seq: None,
+            poll: None,
+            forwarded_from: None,
+        };
+        tracing::info!(
+            "Relaying message from XMPP user '{}': {}",
+            chat_message.client_id,
+            chat_message.content
+        );
+
+        match self
+            .state
+            .send_message_usecase
+            .execute(occupant.client_id.clone(), content, move |seq| {
+                chat_message.to_json_with_seq(seq.value())
+            })
+            .await
+        {
+            Ok(_) => {
+                // MUC reflects messages to the sender
+                let own = format!("{}/{}", self.room_jid, occupant.nick);
+                self.send(groupchat_message(
+                    &own,
+                    from,
+                    message.attr("id"),
+                    &body.text,
+                ));
+            }
+            Err(SendMessageError::MessageCapacityExceeded { .. }) => {
+                self.reply_error(&message, "wait", "resource-constraint");
+            }
+            Err(SendMessageError::RoomFrozen) => {
+                self.reply_error(&message, "cancel", "forbidden");
+            }
+            Err(SendMessageError::RateLimited { .. }) => {
+                self.reply_error(&message, "wait", "policy-violation");
+            }
+            Err(SendMessageError::MessageTooLong { .. }) => {
+                self.reply_error(&message, "modify", "not-acceptable");
+            }
+            Err(e) => {
+                tracing::warn!("Failed to relay XMPP message: {:?}", e);
+                self.reply_error(&message, "wait", "internal-server-error");
+            }
+        }
+    }
+
+    fn handle_iq(&self, iq: Element) {
+        if !matches!(iq.attr("type"), Some("get") | Some("set")) {
+            return;
+        }
+        let (Some(from), Some(to)) = (iq.attr("from"), iq.attr("to")) else {
+            return;
+        };
+        let is_disco_info = iq.attr("type") == Some("get")
+            && iq
+                .child("query")
+                .is_some_and(|query| query.attr("xmlns") == Some(NS_DISCO_INFO));
+        if !is_disco_info {
+            self.reply_error(&iq, "cancel", "service-unavailable");
+            return;
+        }
+
+        let (identity, features) = if self.is_room(to) {
+            (
+                format!(
+                    "<identity category='conference' type='text' name='Engawa room {}'/>",
+                    escape(&self.room_id)
+                ),
+                vec![
+                    NS_MUC,
+                    NS_DISCO_INFO,
+                    "muc_public",
+                    "muc_open",
+                    "muc_temporary",
+                ],
+            )
+        } else if to.eq_ignore_ascii_case(&self.domain) {
+            (
+                "<identity category='conference' type='text' name='Engawa'/>".to_string(),
+                vec![NS_MUC, NS_DISCO_INFO],
+            )
+        } else {
+            self.reply_error(&iq, "cancel", "item-not-found");
+            return;
+        };
+        let features: String = features
+            .iter()
+            .map(|feature| format!("<feature var='{}'/>", feature))
+            .collect();
+        self.send(format!(
+            "<iq type='result' from='{}' to='{}' id='{}'><query xmlns='{}'>{}{}</query></iq>",
+            escape(to),
+            escape(from),
+            escape(iq.attr("id").unwrap_or_default()),
+            NS_DISCO_INFO,
+            identity,
+            features
+        ));
+    }
+
+    /// Remove all occupants from the room (notifying them if the server is shutting down)
+    async fn close(mut self, shutting_down: bool) {
+        for (jid, occupant) in std::mem::take(&mut self.occupants) {
+            if shutting_down {
+                // 332: removed from the room because of a system shutdown
+                let own = format!("{}/{}", self.room_jid, occupant.nick);
+                self.send(occupant_presence(&own, &jid, false, &[110, 332]));
+            }
+            self.disconnect(occupant).await;
+        }
+        // Dropping the session closes the outbound queue, which ends the stream
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOM: &str = "room@chat.example.org";
+
+    #[test]
+    fn test_split_jid() {
+        // テスト項目: JID が bare JID とリソースに分割される
+        // then (期待する結果):
+        assert_eq!(
+            split_jid("room@chat.example.org/alice"),
+            ("room@chat.example.org", Some("alice"))
+        );
+        assert_eq!(
+            split_jid("room@chat.example.org/a/b"),
+            ("room@chat.example.org", Some("a/b"))
+        );
+        assert_eq!(split_jid("chat.example.org"), ("chat.example.org", None));
+    }
+
+    #[test]
+    fn test_translate_chat_message() {
+        // テスト項目: チャットメッセージが送信者のニックネームからの groupchat メッセージに変換される
+        // given (前提条件):
+        let json = r#"{"type":"chat","client_id":"xmpp:bob","content":"1 < 2","timestamp":0}"#;
+
+        // when (操作):
+        let stanza = translate(ROOM, "xmpp:", "alice@example.org/phone", json);
+
+        // then (期待する結果): 接頭辞は取り除かれ、本文はエスケープされる
+        assert_eq!(
+            stanza.as_deref(),
+            Some(
+                "<message type='groupchat' from='room@chat.example.org/bob' \
+                 to='alice@example.org/phone'><body>1 &lt; 2</body></message>"
+            )
+        );
+    }
+
+    #[test]
+    fn test_translate_participant_events() {
+        // テスト項目: 参加・退出通知が在室者のプレゼンスに変換され、ルーム接続通知は変換されない
+        // given (前提条件):
+        let joined = r#"{"type":"participant-joined","client_id":"carol","connected_at":0}"#;
+        let left = r#"{"type":"participant-left","client_id":"carol","disconnected_at":0}"#;
+        let connected = r#"{"type":"room-connected","participants":[]}"#;
+
+        // when (操作):
+        let joined = translate(ROOM, "xmpp:", "alice@example.org", joined).unwrap();
+        let left = translate(ROOM, "xmpp:", "alice@example.org", left).unwrap();
+
+        // then (期待する結果):
+        assert!(joined.starts_with("<presence from='room@chat.example.org/carol'"));
+        assert!(joined.contains("role='participant'"));
+        assert!(left.contains("type='unavailable'"));
+        assert!(left.contains("role='none'"));
+        assert_eq!(
+            translate(ROOM, "xmpp:", "alice@example.org", connected),
+            None
+        );
+    }
+
+    #[test]
+    fn test_error_reply() {
+        // テスト項目: エラー応答は送信元と宛先を入れ替え、ID を引き継ぐ
+        // given (前提条件):
+        let request = Element {
+            name: "iq".to_string(),
+            attrs: vec![
+                ("from".to_string(), "alice@example.org/phone".to_string()),
+                ("to".to_string(), ROOM.to_string()),
+                ("id".to_string(), "q1".to_string()),
+                ("type".to_string(), "get".to_string()),
+            ],
+            ..Default::default()
+        };
+
+        // when (操作):
+        let reply = error_reply(&request, "cancel", "service-unavailable");
+
+        // then (期待する結果):
+        assert_eq!(
+            reply.as_deref(),
+            Some(
+                "<iq type='error' from='room@chat.example.org' to='alice@example.org/phone' \
+                 id='q1'><error type='cancel'><service-unavailable \
+                 xmlns='urn:ietf:params:xml:ns:xmpp-stanzas'/></error></iq>"
+            )
+        );
+    }
+}
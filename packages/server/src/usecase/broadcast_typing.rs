@@ -0,0 +1,162 @@
+//! UseCase: 入力中の通知のブロードキャスト
+//!
+//! クライアントが入力を始めた・やめたことを、ルームの他の参加者に知らせます。
+//!
+//! ## 設計ノート
+//!
+//! クライアントは入力中に `typing-started` を繰り返し送るため、同じクライアントの開始の通知は
+//! デバウンス間隔に 1 回だけ転送します（間隔を過ぎて届いたものは、入力が続いている知らせとして
+//! 転送する）。入力中でないクライアントの `typing-stopped` は転送しません。
+//! 入力中の状態はメッセージの送信と切断で消えます（受信側も同じ契機で表示を消す）。
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::domain::{ClientId, MessagePusher, RoomRepository};
+
+/// 同じクライアントの `typing-started` を転送する最短の間隔の既定値
+pub const DEFAULT_TYPING_DEBOUNCE: Duration = Duration::from_secs(3);
+
+/// 入力中の通知のブロードキャストのユースケース
+pub struct BroadcastTypingUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+    /// 同じクライアントの開始の通知を転送する最短の間隔
+    debounce: Duration,
+    /// 入力中のクライアントと、最後に開始の通知を転送した時刻
+    typing: Mutex<HashMap<ClientId, Instant>>,
+}
+
+impl BroadcastTypingUseCase {
+    /// 新しい BroadcastTypingUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+        debounce: Duration,
+    ) -> Self {
+        Self {
+            repository,
+            message_pusher,
+            debounce,
+            typing: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 入力の開始・終了を他の参加者にブロードキャスト
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - 入力しているクライアントの ID（Domain Model）
+    /// * `typing` - 入力を始めた場合は `true`、やめた場合は `false`
+    /// * `now` - 通知を受け取った時刻
+    /// * `message` - ブロードキャストするメッセージ（JSON）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - ブロードキャストした
+    /// * `Ok(false)` - デバウンスのため転送しなかった
+    /// * `Err(String)` - ブロードキャスト失敗
+    pub async fn execute(
+        &self,
+        client_id: &ClientId,
+        typing: bool,
+        now: Instant,
+        message: &str,
+    ) -> Result<bool, String> {
+        if !self.should_forward(client_id, typing, now) {
+            return Ok(false);
+        }
+        let targets: Vec<ClientId> = self
+            .repository
+            .get_all_connected_client_ids()
+            .await
+            .into_iter()
+            .filter(|id| id != client_id)
+            .collect();
+        self.message_pusher
+            .broadcast(targets, message)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+
+    /// クライアントの入力中の状態を消す（メッセージを送信した・切断した場合）
+    pub fn clear(&self, client_id: &ClientId) {
+        self.typing.lock().unwrap().remove(client_id);
+    }
+
+    /// 通知を転送するかを判定し、入力中の状態を更新する
+    fn should_forward(&self, client_id: &ClientId, typing: bool, now: Instant) -> bool {
+        let mut clients = self.typing.lock().unwrap();
+        if !typing {
+            return clients.remove(client_id).is_some();
+        }
+        match clients.get(client_id) {
+            Some(forwarded_at) if now.duration_since(*forwarded_at) < self.debounce => false,
+            _ => {
+                clients.insert(client_id.clone(), now);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{Room, RoomIdFactory, Timestamp},
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_execute_debounces_typing_notifications() {
+        // テスト項目: 開始の通知はデバウンス間隔に 1 回だけ転送され、終了の通知は入力中の場合のみ転送される
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Default::default()));
+        let usecase =
+            BroadcastTypingUseCase::new(repository, message_pusher, Duration::from_secs(3));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let start = Instant::now();
+
+        // when (操作):
+        let first = usecase.execute(&alice, true, start, "{}").await;
+        let repeated = usecase
+            .execute(&alice, true, start + Duration::from_secs(1), "{}")
+            .await;
+        let refreshed = usecase
+            .execute(&alice, true, start + Duration::from_secs(4), "{}")
+            .await;
+        let stopped = usecase
+            .execute(&alice, false, start + Duration::from_secs(5), "{}")
+            .await;
+        let stopped_again = usecase
+            .execute(&alice, false, start + Duration::from_secs(6), "{}")
+            .await;
+        usecase
+            .execute(&alice, true, start + Duration::from_secs(7), "{}")
+            .await
+            .unwrap();
+        usecase.clear(&alice);
+        let after_clear = usecase
+            .execute(&alice, true, start + Duration::from_secs(8), "{}")
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(first, Ok(true));
+        assert_eq!(repeated, Ok(false));
+        assert_eq!(refreshed, Ok(true));
+        assert_eq!(stopped, Ok(true));
+        assert_eq!(stopped_again, Ok(false));
+        assert_eq!(after_clear, Ok(true));
+    }
+}
@@ -0,0 +1,229 @@
+//! UseCase: 依存先のヘルスチェック
+//!
+//! Repository と MessagePusher にタイムアウト付きで応答を確認し、依存先ごとの状態と
+//! 応答時間を返します。
+//!
+//! ## 設計ノート
+//!
+//! 依存先は並行して確認するため、全体の所要時間はおおよそ最も遅い依存先の応答時間
+//! （最大でタイムアウト）になります。
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::domain::{MessagePusher, RoomRepository};
+
+/// 依存先ごとの応答を待つ時間の既定値
+pub const DEFAULT_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 依存先の状態
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyStatus {
+    /// 応答した
+    Up,
+    /// 応答しなかった（理由）
+    Down(String),
+}
+
+/// 依存先ごとのヘルスチェックの結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyHealth {
+    /// 依存先の名前（`repository`, `message_pusher`）
+    pub name: &'static str,
+    /// 状態
+    pub status: DependencyStatus,
+    /// 応答（またはタイムアウト）までの時間
+    pub latency: Duration,
+}
+
+/// ヘルスチェックの結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    /// 依存先ごとの結果
+    pub dependencies: Vec<DependencyHealth>,
+}
+
+impl HealthReport {
+    /// 全ての依存先が応答したか
+    pub fn is_healthy(&self) -> bool {
+        self.dependencies
+            .iter()
+            .all(|dependency| dependency.status == DependencyStatus::Up)
+    }
+}
+
+/// 依存先のヘルスチェックのユースケース
+pub struct CheckHealthUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+    /// 依存先ごとの応答を待つ時間
+    timeout: Duration,
+}
+
+impl CheckHealthUseCase {
+    /// 新しい CheckHealthUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            repository,
+            message_pusher,
+            timeout,
+        }
+    }
+
+    /// 全ての依存先の応答を確認する
+    pub async fn execute(&self) -> HealthReport {
+        let (repository, message_pusher) = tokio::join!(
+            self.check("repository", async {
+                self.repository.ping().await.map_err(|e| e.to_string())
+            }),
+            self.check("message_pusher", async {
+                self.message_pusher.ping().await.map_err(|e| e.to_string())
+            }),
+        );
+        HealthReport {
+            dependencies: vec![repository, message_pusher],
+        }
+    }
+
+    async fn check(
+        &self,
+        name: &'static str,
+        ping: impl Future<Output = Result<(), String>>,
+    ) -> DependencyHealth {
+        let started_at = Instant::now();
+        let status = match tokio::time::timeout(self.timeout, ping).await {
+            Ok(Ok(())) => DependencyStatus::Up,
+            Ok(Err(reason)) => DependencyStatus::Down(reason),
+            Err(_) => DependencyStatus::Down(format!(
+                "no response within {} ms",
+                self.timeout.as_millis()
+            )),
+        };
+        DependencyHealth {
+            name,
+            status,
+            latency: started_at.elapsed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{ClientId, MessagePushError, PusherChannel, Room, RoomIdFactory, Timestamp},
+        infrastructure::repository::InMemoryRoomRepository,
+    };
+
+    // ping の結果だけを返す MessagePusher（`None` の場合は ping に応答しない）
+    struct PingPusher {
+        result: Option<Result<(), String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl MessagePusher for PingPusher {
+        async fn register_client(&self, _client_id: ClientId, _sender: PusherChannel) {}
+
+        async fn unregister_client(&self, _client_id: &ClientId) {}
+        async fn unregister_connection(
+            &self,
+            _client_id: &ClientId,
+            _sender: &PusherChannel,
+        ) -> usize {
+            0
+        }
+
+        async fn push_to(
+            &self,
+            _client_id: &ClientId,
+            _content: &str,
+        ) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+
+        async fn broadcast(
+            &self,
+            _targets: Vec<ClientId>,
+            _content: &str,
+        ) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+
+        async fn ping(&self) -> Result<(), MessagePushError> {
+            match &self.result {
+                Some(result) => result.clone().map_err(MessagePushError::PushFailed),
+                None => std::future::pending().await,
+            }
+        }
+    }
+
+    fn usecase(pusher_result: Option<Result<(), String>>) -> CheckHealthUseCase {
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        CheckHealthUseCase::new(
+            Arc::new(InMemoryRoomRepository::new(room)),
+            Arc::new(PingPusher {
+                result: pusher_result,
+            }),
+            Duration::from_millis(50),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_execute_all_dependencies_up() {
+        // テスト項目: 全ての依存先が応答すれば healthy になる
+        // given (前提条件):
+        let usecase = usecase(Some(Ok(())));
+
+        // when (操作):
+        let report = usecase.execute().await;
+
+        // then (期待する結果):
+        assert!(report.is_healthy());
+        let names: Vec<_> = report.dependencies.iter().map(|d| d.name).collect();
+        assert_eq!(names, vec!["repository", "message_pusher"]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_reports_failing_dependency() {
+        // テスト項目: 依存先がエラーを返した場合はその依存先だけが理由付きで down になる
+        // given (前提条件):
+        let usecase = usecase(Some(Err("broker unreachable".to_string())));
+
+        // when (操作):
+        let report = usecase.execute().await;
+
+        // then (期待する結果):
+        assert!(!report.is_healthy());
+        assert_eq!(report.dependencies[0].status, DependencyStatus::Up);
+        assert_eq!(
+            report.dependencies[1].status,
+            DependencyStatus::Down("Push failed: broker unreachable".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_times_out_unresponsive_dependency() {
+        // テスト項目: 応答しない依存先はタイムアウトで down になり、他の依存先の確認は妨げない
+        // given (前提条件): MessagePusher が ping に応答しない
+        let usecase = usecase(None);
+
+        // when (操作):
+        let report = usecase.execute().await;
+
+        // then (期待する結果):
+        assert_eq!(report.dependencies[0].status, DependencyStatus::Up);
+        assert_eq!(
+            report.dependencies[1].status,
+            DependencyStatus::Down("no response within 50 ms".to_string())
+        );
+        assert!(report.dependencies[1].latency >= Duration::from_millis(50));
+    }
+}
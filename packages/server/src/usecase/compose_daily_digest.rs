@@ -0,0 +1,200 @@
+//! UseCase: ルームのデイリーダイジェストの作成
+//!
+//! 指定した期間のメッセージ数と、よく発言した参加者を集計します。
+//! 作成したダイジェストは UI 層のバックグラウンドタスクが毎日決まった時刻にルームへ投稿します。
+
+use std::{cmp::Reverse, collections::HashMap, sync::Arc};
+
+use crate::domain::{ClientId, RepositoryError, RoomId, RoomRepository, Timestamp};
+
+/// ダイジェストに載せる、よく発言した参加者の人数
+pub const DIGEST_MOST_ACTIVE_LIMIT: usize = 3;
+
+/// 参加者ごとの発言数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SenderActivity {
+    /// 送信者のクライアント ID
+    pub client_id: ClientId,
+    /// 期間中のメッセージ数
+    pub messages: usize,
+}
+
+/// ルームのデイリーダイジェスト
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyDigest {
+    /// ルームの ID
+    pub room_id: RoomId,
+    /// 集計期間の開始（含む）
+    pub since: Timestamp,
+    /// 集計期間の終了（含まない）
+    pub until: Timestamp,
+    /// 期間中のメッセージ数
+    pub message_count: usize,
+    /// よく発言した参加者（発言数の多い順、同数の場合はクライアント ID 順）
+    pub most_active: Vec<SenderActivity>,
+}
+
+/// デイリーダイジェスト作成のユースケース
+pub struct ComposeDailyDigestUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// ダイジェストの送信者（集計から除く）
+    sender: ClientId,
+}
+
+impl ComposeDailyDigestUseCase {
+    /// 新しい ComposeDailyDigestUseCase を作成
+    ///
+    /// `sender` はダイジェストを投稿するクライアント ID で、前回のダイジェストを集計に含めないために使う。
+    pub fn new(repository: Arc<dyn RoomRepository>, sender: ClientId) -> Self {
+        Self { repository, sender }
+    }
+
+    /// ダイジェストの送信者
+    pub fn sender(&self) -> &ClientId {
+        &self.sender
+    }
+
+    /// 期間中のメッセージを集計してダイジェストを作成
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - 集計期間の開始（含む）
+    /// * `until` - 集計期間の終了（含まない）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(DailyDigest)` - 作成したダイジェスト
+    /// * `Err(RepositoryError)` - ルームの取得に失敗
+    pub async fn execute(
+        &self,
+        since: Timestamp,
+        until: Timestamp,
+    ) -> Result<DailyDigest, RepositoryError> {
+        let room = self.repository.get_room().await?;
+
+        let mut counts: HashMap<&ClientId, usize> = HashMap::new();
+        let mut message_count = 0;
+        for message in room
+            .messages
+            .iter()
+            .filter(|m| since <= m.timestamp && m.timestamp < until && m.from != self.sender)
+        {
+            message_count += 1;
+            *counts.entry(&message.from).or_default() += 1;
+        }
+
+        let mut most_active: Vec<SenderActivity> = counts
+            .into_iter()
+            .map(|(client_id, messages)| SenderActivity {
+                client_id: client_id.clone(),
+                messages,
+            })
+            .collect();
+        most_active.sort_by(|a, b| {
+            Reverse(a.messages)
+                .cmp(&Reverse(b.messages))
+                .then_with(|| a.client_id.as_str().cmp(b.client_id.as_str()))
+        });
+        most_active.truncate(DIGEST_MOST_ACTIVE_LIMIT);
+
+        Ok(DailyDigest {
+            room_id: room.id,
+            since,
+            until,
+            message_count,
+            most_active,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{MessageContent, Room, RoomIdFactory},
+        infrastructure::repository::InMemoryRoomRepository,
+    };
+
+    fn client_id(id: &str) -> ClientId {
+        ClientId::new(id.to_string()).unwrap()
+    }
+
+    async fn repository_with_messages(messages: &[(&str, i64)]) -> Arc<dyn RoomRepository> {
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        for (from, timestamp) in messages {
+            repository
+                .add_message(
+                    client_id(from),
+                    MessageContent::new("hello".to_string()).unwrap(),
+                    Timestamp::new(*timestamp),
+                )
+                .await
+                .unwrap();
+        }
+        repository
+    }
+
+    #[tokio::test]
+    async fn test_execute_counts_messages_in_period() {
+        // テスト項目: 期間内のメッセージ数と発言数の多い参加者が集計される（終了時刻は含まない）
+        // given (前提条件):
+        let repository = repository_with_messages(&[
+            ("alice", 500),
+            ("bob", 1000),
+            ("alice", 1500),
+            ("carol", 1600),
+            ("bob", 1700),
+            ("dave", 1800),
+            ("alice", 1900),
+            ("bob", 2000),
+        ])
+        .await;
+        let usecase = ComposeDailyDigestUseCase::new(repository, client_id("digest"));
+
+        // when (操作):
+        let digest = usecase
+            .execute(Timestamp::new(1000), Timestamp::new(2000))
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(digest.message_count, 6);
+        assert_eq!(
+            digest.most_active,
+            vec![
+                SenderActivity {
+                    client_id: client_id("alice"),
+                    messages: 2,
+                },
+                SenderActivity {
+                    client_id: client_id("bob"),
+                    messages: 2,
+                },
+                SenderActivity {
+                    client_id: client_id("carol"),
+                    messages: 1,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_excludes_previous_digests() {
+        // テスト項目: ダイジェストの送信者のメッセージは集計されない
+        // given (前提条件):
+        let repository = repository_with_messages(&[("digest", 1000), ("alice", 1100)]).await;
+        let usecase = ComposeDailyDigestUseCase::new(repository, client_id("digest"));
+
+        // when (操作):
+        let digest = usecase
+            .execute(Timestamp::new(0), Timestamp::new(2000))
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(digest.message_count, 1);
+        assert_eq!(digest.most_active[0].client_id, client_id("alice"));
+    }
+}
@@ -19,17 +19,29 @@
 use std::sync::Arc;
 
 use crate::domain::{
-    ClientId, MessagePusher, Participant, PusherChannel, RoomRepository, Timestamp,
+    ClientId, MessagePusher, Participant, PendingMessageStore, PusherChannel, RepositoryError,
+    RoomError, RoomFeed, RoomRepository, Timestamp,
 };
 
 use super::error::ConnectError;
 
+/// 多重接続を許可した接続の結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultiplexedConnection {
+    /// 参加者の接続時刻（既に参加している場合は最初の接続の時刻）
+    pub connected_at: Timestamp,
+    /// 参加者として新たに追加されたか（`false` の場合は既存の参加者の接続が増えただけ）
+    pub joined: bool,
+}
+
 /// 参加者接続のユースケース
 pub struct ConnectParticipantUseCase {
     /// Repository（データアクセス層の抽象化）
     repository: Arc<dyn RoomRepository>,
     /// MessagePusher（メッセージ通知の抽象化）
     message_pusher: Arc<dyn MessagePusher>,
+    /// 切断中に届いたメッセージのメールボックス（参加者を持続させない場合は `None`）
+    pending_messages: Option<Arc<dyn PendingMessageStore>>,
 }
 
 impl ConnectParticipantUseCase {
@@ -41,9 +53,18 @@ impl ConnectParticipantUseCase {
         Self {
             repository,
             message_pusher,
+            pending_messages: None,
         }
     }
 
+    /// 再接続した参加者に切断中に届いたメッセージを送る
+    ///
+    /// メールボックスは DisconnectParticipantUseCase が切断時に開く（同じ store を渡す）。
+    pub fn with_pending_messages(mut self, store: Arc<dyn PendingMessageStore>) -> Self {
+        self.pending_messages = Some(store);
+        self
+    }
+
     /// 参加者接続を実行
     ///
     /// # Arguments
@@ -78,14 +99,104 @@ impl ConnectParticipantUseCase {
         self.repository
             .add_participant(client_id.clone(), connected_at)
             .await
-            .map_err(|_| ConnectError::RoomCapacityExceeded)?;
+            .map_err(|e| match e {
+                RepositoryError::Room(RoomError::CapacityExceeded { capacity, .. }) => {
+                    ConnectError::RoomCapacityExceeded { capacity }
+                }
+                e => ConnectError::PersistFailed(e.to_string()),
+            })?;
 
         // 3. MessagePusher にクライアントを登録（Domain Model を渡す）
-        self.message_pusher.register_client(client_id, sender).await;
+        self.message_pusher
+            .register_client(client_id.clone(), sender)
+            .await;
+
+        // 4. 切断中に届いたメッセージを送る（送信キューに入るため、ルームの状態の後に届く）
+        self.deliver_pending_messages(&client_id, connected_at)
+            .await;
 
         Ok(connected_at)
     }
 
+    /// メールボックスに溜まったメッセージを再接続したクライアントに送る
+    async fn deliver_pending_messages(&self, client_id: &ClientId, now: Timestamp) {
+        let Some(store) = &self.pending_messages else {
+            return;
+        };
+        let messages = match store.take(client_id, now).await {
+            Ok(messages) => messages,
+            Err(e) => {
+                tracing::warn!("Failed to take pending messages of '{}': {}", client_id, e);
+                return;
+            }
+        };
+        if !messages.is_empty() {
+            tracing::info!(
+                "Delivering {} pending messages to '{}'",
+                messages.len(),
+                client_id
+            );
+        }
+        for message in messages {
+            if let Err(e) = self.message_pusher.push_to(client_id, &message).await {
+                tracing::warn!(
+                    "Failed to deliver a pending message to '{}': {}",
+                    client_id,
+                    e
+                );
+                return;
+            }
+        }
+    }
+
+    /// 同じ参加者の複数の接続を許可して接続を実行
+    ///
+    /// 既に参加しているクライアントの場合は、参加者を追加せずに接続だけを MessagePusher に
+    /// 登録します。送信は参加者の全ての接続に行われます。
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - 接続するクライアントの ID（Domain Model）
+    /// * `sender` - この接続へのメッセージ送信用チャンネル
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(MultiplexedConnection)` - 接続成功
+    /// * `Err(ConnectError)` - 接続失敗（容量超過・永続化の失敗）
+    pub async fn execute_multiplexed(
+        &self,
+        client_id: ClientId,
+        sender: PusherChannel,
+    ) -> Result<MultiplexedConnection, ConnectError> {
+        let participants = self.repository.get_participants().await;
+        if let Some(participant) = participants.iter().find(|p| p.id == client_id) {
+            let connected_at = participant.connected_at;
+            self.message_pusher.register_client(client_id, sender).await;
+            return Ok(MultiplexedConnection {
+                connected_at,
+                joined: false,
+            });
+        }
+
+        let connected_at = self.execute(client_id, sender).await?;
+        Ok(MultiplexedConnection {
+            connected_at,
+            joined: true,
+        })
+    }
+
+    /// 接続へのブロードキャストをルームの全員に配るメッセージの購読に切り替える
+    ///
+    /// 接続への書き込みを始める時に呼ぶ。MessagePusher が購読に対応しない場合は `None`
+    /// （全てのメッセージが `sender` に届く）。
+    pub async fn subscribe(
+        &self,
+        client_id: &ClientId,
+        sender: &PusherChannel,
+    ) -> Option<RoomFeed> {
+        self.message_pusher.subscribe(client_id, sender).await
+    }
+
     /// 参加者リストを構築
     ///
     /// # Returns
@@ -129,6 +240,46 @@ impl ConnectParticipantUseCase {
             .await
             .map_err(|e| e.to_string())
     }
+
+    /// ルームの全ての参加者にメッセージをブロードキャスト（参加の承認待ちの通知など）
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - ブロードキャストするメッセージ（JSON）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - ブロードキャスト成功
+    /// * `Err(String)` - ブロードキャスト失敗
+    pub async fn broadcast_to_participants(&self, message: &str) -> Result<(), String> {
+        let target_ids = self.repository.get_all_connected_client_ids().await;
+        self.message_pusher
+            .broadcast(target_ids, message)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// 参加したクライアントだけにメッセージを送信（ウェルカムメッセージなど）
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - 送信先のクライアントの ID（Domain Model）
+    /// * `message` - 送信するメッセージ（JSON）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - 送信成功
+    /// * `Err(String)` - 送信失敗
+    pub async fn send_to_participant(
+        &self,
+        client_id: &ClientId,
+        message: &str,
+    ) -> Result<(), String> {
+        self.message_pusher
+            .push_to(client_id, message)
+            .await
+            .map_err(|e| e.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -137,36 +288,35 @@ mod tests {
     use crate::{
         domain::{Room, RoomIdFactory, Timestamp},
         infrastructure::{
-            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+            message_pusher::WebSocketMessagePusher,
+            repository::{InMemoryPendingMessageStore, InMemoryRoomRepository},
         },
     };
     use engawa_shared::time::get_jst_timestamp;
-    use std::{collections::HashMap, sync::Arc};
-    use tokio::sync::Mutex;
+    use std::{sync::Arc, time::Duration};
 
     fn create_test_repository() -> Arc<InMemoryRoomRepository> {
-        let room = Arc::new(Mutex::new(Room::new(
+        let room = Room::new(
             RoomIdFactory::generate().unwrap(),
             Timestamp::new(get_jst_timestamp()),
-        )));
+        );
         Arc::new(InMemoryRoomRepository::new(room))
     }
 
     fn create_test_repository_with_capacity(
         participant_capacity: usize,
     ) -> Arc<InMemoryRoomRepository> {
-        let room = Arc::new(Mutex::new(Room::with_capacity(
+        let room = Room::with_capacity(
             RoomIdFactory::generate().unwrap(),
             Timestamp::new(get_jst_timestamp()),
             participant_capacity,
             100,
-        )));
+        );
         Arc::new(InMemoryRoomRepository::new(room))
     }
 
     fn create_test_message_pusher() -> Arc<WebSocketMessagePusher> {
-        let clients = Arc::new(Mutex::new(HashMap::new()));
-        Arc::new(WebSocketMessagePusher::new(clients))
+        Arc::new(WebSocketMessagePusher::new(Default::default()))
     }
 
     #[tokio::test]
@@ -192,6 +342,35 @@ mod tests {
         assert_eq!(participants[0].id, client_id);
     }
 
+    #[tokio::test]
+    async fn test_reconnected_participant_receives_pending_messages() {
+        // テスト項目: 切断中に溜まったメッセージが再接続したクライアントに古い順に送られる
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let store = Arc::new(InMemoryPendingMessageStore::new(
+            Duration::from_secs(60),
+            100,
+        ));
+        let usecase = ConnectParticipantUseCase::new(repository.clone(), message_pusher)
+            .with_pending_messages(store.clone());
+        let client_id = ClientId::new("alice".to_string()).unwrap();
+        let now = Timestamp::new(get_jst_timestamp());
+        store.open(client_id.clone(), now).await.unwrap();
+        store.push(r#"{"seq":1}"#, now).await.unwrap();
+        store.push(r#"{"seq":2}"#, now).await.unwrap();
+
+        // when (操作):
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        usecase.execute(client_id.clone(), tx).await.unwrap();
+
+        // then (期待する結果):
+        assert_eq!(rx.try_recv().unwrap().as_ref(), r#"{"seq":1}"#);
+        assert_eq!(rx.try_recv().unwrap().as_ref(), r#"{"seq":2}"#);
+        assert!(rx.try_recv().is_err());
+        assert!(store.take(&client_id, now).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_connect_participant_duplicate_error() {
         // テスト項目: 重複した client_id での接続試行がエラーになる
@@ -243,12 +422,43 @@ mod tests {
         let result = usecase.execute(charlie.clone(), tx3).await;
 
         // then (期待する結果): 容量超過エラーが返される
-        assert_eq!(result, Err(ConnectError::RoomCapacityExceeded));
+        assert_eq!(result, Err(ConnectError::RoomCapacityExceeded { capacity }));
 
         // Repository には2人だけ
         assert_eq!(repository.count_connected_clients().await, 2);
     }
 
+    #[tokio::test]
+    async fn test_connect_multiplexed_adds_connection_to_existing_participant() {
+        // テスト項目: 多重接続では既存の参加者に接続が追加され、参加者は増えない
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = ConnectParticipantUseCase::new(repository.clone(), message_pusher.clone());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let (phone, mut phone_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (laptop, mut laptop_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        // when (操作):
+        let first = usecase
+            .execute_multiplexed(alice.clone(), phone)
+            .await
+            .unwrap();
+        let second = usecase
+            .execute_multiplexed(alice.clone(), laptop)
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert!(first.joined);
+        assert!(!second.joined);
+        assert_eq!(second.connected_at, first.connected_at);
+        assert_eq!(repository.count_connected_clients().await, 1);
+        message_pusher.push_to(&alice, "Hello").await.unwrap();
+        assert_eq!(phone_rx.recv().await.unwrap().as_ref(), "Hello");
+        assert_eq!(laptop_rx.recv().await.unwrap().as_ref(), "Hello");
+    }
+
     #[tokio::test]
     async fn test_build_participant_list() {
         // テスト項目: 参加者リストが正しく構築される
@@ -0,0 +1,382 @@
+//! UseCase: ルーム作成処理
+//!
+//! 新しい ID のルームを作成し、Repository に追加します。
+//! 作成したルームには `JoinRoomUseCase` で参加します。
+//!
+//! ## 設計ノート
+//!
+//! ルームはクライアントの要求で作成できるため、既定のルームを含むルーム数に上限を設けます。
+//! ルームのクラスは Repository がルームの保存先を選ぶために使います（`RoomRepositoryRouter`）。
+//! ルームごとに参加者数とメッセージ履歴の容量を指定できますが、サーバーの容量を超えることはできません。
+//! スラッグを指定した場合はルーム一覧での名前になるため、他のルームと重複するスラッグは拒否します。
+//! メッセージのポリシー（最大の長さと適用するフィルター）もルームごとに指定できます。最大の長さは
+//! 容量と同じくサーバーの上限を超えられず、フィルターはサーバーのフィルターから名前で選びます。
+
+use std::sync::Arc;
+
+use crate::domain::{
+    DEFAULT_MAX_MESSAGE_LENGTH, MessagePolicy, Room, RoomClass, RoomId, RoomIdFactory,
+    RoomRepository, RoomSlug, Timestamp,
+    entity::{DEFAULT_MESSAGE_CAPACITY, DEFAULT_PARTICIPANT_CAPACITY},
+};
+
+/// ルーム数の上限の既定値（既定のルームを含む）
+pub const DEFAULT_MAX_ROOMS: usize = 100;
+
+/// ルーム作成のユースケース
+pub struct CreateRoomUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// ルーム数の上限（既定のルームを含む）
+    max_rooms: usize,
+    /// ルームの参加者数の上限
+    participant_capacity: usize,
+    /// ルームのメッセージ履歴の上限
+    message_capacity: usize,
+    /// ルームのメッセージの最大の長さ（バイト数）
+    max_message_length: usize,
+}
+
+/// 作成するルームの設定
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NewRoom {
+    /// ルームのクラス
+    pub class: RoomClass,
+    /// 参加者数の上限（`None` の場合はサーバーの上限）
+    pub participant_capacity: Option<usize>,
+    /// メッセージ履歴の上限（`None` の場合はサーバーの上限）
+    pub message_capacity: Option<usize>,
+    /// ルームのスラッグ（`None` の場合はスラッグ無し）
+    pub slug: Option<RoomSlug>,
+    /// メッセージの最大の長さ（`None` の場合はサーバーの上限）
+    pub max_message_length: Option<usize>,
+    /// 適用するサーバーのメッセージフィルターの名前（`None` の場合は全て）
+    pub message_filters: Option<Vec<String>>,
+}
+
+/// ルーム作成エラー
+#[derive(Debug, PartialEq)]
+pub enum CreateRoomError {
+    /// ルーム数が上限に達している
+    TooManyRooms,
+    /// 指定された容量が 0 またはサーバーの上限を超えている
+    InvalidCapacity {
+        /// 容量の種類（`participant` または `message`）
+        kind: &'static str,
+        /// サーバーの上限
+        max: usize,
+    },
+    /// 指定されたメッセージの最大の長さが 0 またはサーバーの上限を超えている
+    InvalidMessageLength {
+        /// サーバーの上限
+        max: usize,
+    },
+    /// 指定されたスラッグが他のルームで使われている
+    SlugTaken(RoomSlug),
+    /// Repository エラー
+    RepositoryError,
+}
+
+impl CreateRoomUseCase {
+    /// 新しい CreateRoomUseCase を作成
+    ///
+    /// # Arguments
+    ///
+    /// * `repository` - 既定のルームの Repository
+    /// * `max_rooms` - ルーム数の上限（既定のルームを含む）
+    pub fn new(repository: Arc<dyn RoomRepository>, max_rooms: usize) -> Self {
+        Self {
+            repository,
+            max_rooms,
+            participant_capacity: DEFAULT_PARTICIPANT_CAPACITY,
+            message_capacity: DEFAULT_MESSAGE_CAPACITY,
+            max_message_length: DEFAULT_MAX_MESSAGE_LENGTH,
+        }
+    }
+
+    /// 作成するルームの容量の上限（指定が無い場合の容量）を設定
+    ///
+    /// # Arguments
+    ///
+    /// * `participant_capacity` - ルームの参加者数の上限
+    /// * `message_capacity` - ルームのメッセージ履歴の上限
+    pub fn with_capacity(mut self, participant_capacity: usize, message_capacity: usize) -> Self {
+        self.participant_capacity = participant_capacity;
+        self.message_capacity = message_capacity;
+        self
+    }
+
+    /// 作成するルームのメッセージの最大の長さ（指定が無い場合の長さ）を設定
+    pub fn with_max_message_length(mut self, max_message_length: usize) -> Self {
+        self.max_message_length = max_message_length;
+        self
+    }
+
+    /// ルームを作成
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - 作成日時
+    /// * `settings` - 作成するルームの設定
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Room)` - 作成したルーム
+    /// * `Err(CreateRoomError)` - 作成失敗
+    pub async fn execute(
+        &self,
+        now: Timestamp,
+        settings: NewRoom,
+    ) -> Result<Room, CreateRoomError> {
+        let participant_capacity = Self::capacity(
+            "participant",
+            settings.participant_capacity,
+            self.participant_capacity,
+        )?;
+        let message_capacity =
+            Self::capacity("message", settings.message_capacity, self.message_capacity)?;
+        let max_message_length = match settings.max_message_length {
+            None => self.max_message_length,
+            Some(length) if (1..=self.max_message_length).contains(&length) => length,
+            Some(_) => {
+                return Err(CreateRoomError::InvalidMessageLength {
+                    max: self.max_message_length,
+                });
+            }
+        };
+        let room_ids = self.repository.get_room_ids().await;
+        if room_ids.len() >= self.max_rooms {
+            return Err(CreateRoomError::TooManyRooms);
+        }
+        if let Some(slug) = &settings.slug {
+            for room_id in &room_ids {
+                let room = self
+                    .repository
+                    .get_room_snapshot(room_id)
+                    .await
+                    .map_err(|_| CreateRoomError::RepositoryError)?;
+                if room.slug.as_ref() == Some(slug) {
+                    return Err(CreateRoomError::SlugTaken(slug.clone()));
+                }
+            }
+        }
+
+        let room_id: RoomId =
+            RoomIdFactory::generate().map_err(|_| CreateRoomError::RepositoryError)?;
+        let mut room = Room::with_capacity(room_id, now, participant_capacity, message_capacity);
+        room.class = settings.class;
+        room.slug = settings.slug;
+        room.message_policy = MessagePolicy {
+            max_length: max_message_length,
+            filters: settings.message_filters,
+        };
+        self.repository
+            .create_room(room.clone())
+            .await
+            .map_err(|_| CreateRoomError::RepositoryError)?;
+
+        tracing::info!("Room {} ({}) created", room.id, room.class);
+        Ok(room)
+    }
+
+    /// 指定された容量を検証し、指定が無ければサーバーの上限を返す
+    fn capacity(
+        kind: &'static str,
+        requested: Option<usize>,
+        max: usize,
+    ) -> Result<usize, CreateRoomError> {
+        match requested {
+            None => Ok(max),
+            Some(capacity) if (1..=max).contains(&capacity) => Ok(capacity),
+            Some(_) => Err(CreateRoomError::InvalidCapacity { kind, max }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::repository::InMemoryRoomRepository;
+
+    #[tokio::test]
+    async fn test_execute_creates_rooms_up_to_the_limit() {
+        // テスト項目: 既定のルームを含めて上限までルームを作成でき、上限を超えると拒否される
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let usecase = CreateRoomUseCase::new(repository.clone(), 2);
+
+        // when (操作):
+        let created = usecase
+            .execute(
+                Timestamp::new(2000),
+                NewRoom {
+                    class: RoomClass::Persistent,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let rejected = usecase
+            .execute(Timestamp::new(3000), NewRoom::default())
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(created.created_at, Timestamp::new(2000));
+        assert_eq!(rejected.err(), Some(CreateRoomError::TooManyRooms));
+        let stored = repository.get_room_by_id(&created.id).await.unwrap();
+        assert_eq!(stored.id, created.id);
+        assert_eq!(stored.class, RoomClass::Persistent);
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_slug_of_another_room() {
+        // テスト項目: 指定したスラッグでルームが作成され、既に使われているスラッグは拒否される
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let usecase = CreateRoomUseCase::new(repository, 10);
+        let slug = RoomSlug::new("design".to_string()).unwrap();
+        let settings = NewRoom {
+            slug: Some(slug.clone()),
+            ..Default::default()
+        };
+
+        // when (操作):
+        let created = usecase
+            .execute(Timestamp::new(2000), settings.clone())
+            .await
+            .unwrap();
+        let duplicate = usecase.execute(Timestamp::new(3000), settings).await;
+
+        // then (期待する結果):
+        assert_eq!(created.slug, Some(slug.clone()));
+        assert_eq!(duplicate.err(), Some(CreateRoomError::SlugTaken(slug)));
+    }
+
+    #[tokio::test]
+    async fn test_execute_applies_capacity_within_server_limits() {
+        // テスト項目: 指定した容量でルームが作成され、指定が無ければサーバーの上限、0 や上限超過は拒否される
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let usecase = CreateRoomUseCase::new(repository, 10).with_capacity(5, 50);
+
+        // when (操作):
+        let custom = usecase
+            .execute(
+                Timestamp::new(2000),
+                NewRoom {
+                    participant_capacity: Some(2),
+                    message_capacity: Some(20),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let unspecified = usecase
+            .execute(Timestamp::new(3000), NewRoom::default())
+            .await
+            .unwrap();
+        let zero = usecase
+            .execute(
+                Timestamp::new(4000),
+                NewRoom {
+                    participant_capacity: Some(0),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let too_large = usecase
+            .execute(
+                Timestamp::new(5000),
+                NewRoom {
+                    message_capacity: Some(51),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(
+            (custom.participant_capacity, custom.message_capacity),
+            (2, 20)
+        );
+        assert_eq!(
+            (
+                unspecified.participant_capacity,
+                unspecified.message_capacity
+            ),
+            (5, 50)
+        );
+        assert_eq!(
+            zero.err(),
+            Some(CreateRoomError::InvalidCapacity {
+                kind: "participant",
+                max: 5
+            })
+        );
+        assert_eq!(
+            too_large.err(),
+            Some(CreateRoomError::InvalidCapacity {
+                kind: "message",
+                max: 50
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_applies_message_policy() {
+        // テスト項目: 指定したメッセージのポリシーでルームが作成され、指定が無ければサーバーの最大の長さ、上限超過は拒否される
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let usecase = CreateRoomUseCase::new(repository, 10).with_max_message_length(500);
+
+        // when (操作):
+        let custom = usecase
+            .execute(
+                Timestamp::new(2000),
+                NewRoom {
+                    max_message_length: Some(140),
+                    message_filters: Some(vec!["max-links".to_string()]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let unspecified = usecase
+            .execute(Timestamp::new(3000), NewRoom::default())
+            .await
+            .unwrap();
+        let too_long = usecase
+            .execute(
+                Timestamp::new(4000),
+                NewRoom {
+                    max_message_length: Some(501),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(
+            custom.message_policy,
+            MessagePolicy {
+                max_length: 140,
+                filters: Some(vec!["max-links".to_string()]),
+            }
+        );
+        assert_eq!(
+            unspecified.message_policy,
+            MessagePolicy {
+                max_length: 500,
+                filters: None,
+            }
+        );
+        assert_eq!(
+            too_long.err(),
+            Some(CreateRoomError::InvalidMessageLength { max: 500 })
+        );
+    }
+}
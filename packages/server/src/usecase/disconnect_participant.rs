@@ -18,7 +18,9 @@
 
 use std::sync::Arc;
 
-use crate::domain::{ClientId, MessagePusher, RoomRepository};
+use crate::domain::{
+    ClientId, MessagePusher, PendingMessageStore, PusherChannel, RoomRepository, Timestamp,
+};
 
 /// 参加者切断のユースケース
 pub struct DisconnectParticipantUseCase {
@@ -26,6 +28,8 @@ pub struct DisconnectParticipantUseCase {
     repository: Arc<dyn RoomRepository>,
     /// MessagePusher（メッセージ通知の抽象化）
     message_pusher: Arc<dyn MessagePusher>,
+    /// 切断中に届いたメッセージのメールボックス（参加者を持続させない場合は `None`）
+    pending_messages: Option<Arc<dyn PendingMessageStore>>,
 }
 
 impl DisconnectParticipantUseCase {
@@ -37,9 +41,16 @@ impl DisconnectParticipantUseCase {
         Self {
             repository,
             message_pusher,
+            pending_messages: None,
         }
     }
 
+    /// 切断した参加者のメールボックスを開き、再接続までのメッセージを溜める
+    pub fn with_pending_messages(mut self, store: Arc<dyn PendingMessageStore>) -> Self {
+        self.pending_messages = Some(store);
+        self
+    }
+
     /// 参加者切断を実行
     ///
     /// # Arguments
@@ -69,9 +80,47 @@ impl DisconnectParticipantUseCase {
         // 4. MessagePusher からクライアントを登録解除（Domain Model を渡す）
         self.message_pusher.unregister_client(&client_id).await;
 
+        // 5. 再接続まで届いたメッセージを溜めるメールボックスを開く
+        if let Some(store) = &self.pending_messages {
+            let now = Timestamp::new(engawa_shared::time::get_jst_timestamp());
+            if let Err(e) = store.open(client_id.clone(), now).await {
+                tracing::warn!("Failed to open the mailbox of '{}': {}", client_id, e);
+            }
+        }
+
         Ok(notify_targets)
     }
 
+    /// 参加者の接続を 1 つ切断
+    ///
+    /// 参加者が他にも接続を持っている場合は参加者を残し、最後の接続が閉じた時にだけ
+    /// 参加者を削除します（[`Self::execute`] と同じ処理）。
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - 切断するクライアントの ID（Domain Model）
+    /// * `sender` - 閉じた接続のメッセージ送信用チャンネル
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(Vec<ClientId>))` - 参加者を削除した（通知対象のクライアント ID リスト）
+    /// * `Ok(None)` - 参加者の他の接続が残っている
+    /// * `Err(())` - 切断失敗（参加者が存在しない場合）
+    pub async fn execute_connection(
+        &self,
+        client_id: ClientId,
+        sender: &PusherChannel,
+    ) -> Result<Option<Vec<ClientId>>, ()> {
+        let remaining = self
+            .message_pusher
+            .unregister_connection(&client_id, sender)
+            .await;
+        if remaining > 0 {
+            return Ok(None);
+        }
+        self.execute(client_id).await.map(Some)
+    }
+
     /// 通知対象のクライアント ID リストを取得
     ///
     /// 切断するクライアント以外の全てのクライアント ID を返す（Domain Model）
@@ -121,20 +170,18 @@ mod tests {
         },
     };
     use engawa_shared::time::get_jst_timestamp;
-    use std::{collections::HashMap, sync::Arc};
-    use tokio::sync::Mutex;
+    use std::sync::Arc;
 
     fn create_test_repository() -> Arc<InMemoryRoomRepository> {
-        let room = Arc::new(Mutex::new(Room::new(
+        let room = Room::new(
             RoomIdFactory::generate().unwrap(),
             Timestamp::new(get_jst_timestamp()),
-        )));
+        );
         Arc::new(InMemoryRoomRepository::new(room))
     }
 
     fn create_test_message_pusher() -> Arc<WebSocketMessagePusher> {
-        let clients = Arc::new(Mutex::new(HashMap::new()));
-        Arc::new(WebSocketMessagePusher::new(clients))
+        Arc::new(WebSocketMessagePusher::new(Default::default()))
     }
 
     #[tokio::test]
@@ -182,6 +229,39 @@ mod tests {
         assert_eq!(repository.count_connected_clients().await, 2);
     }
 
+    #[tokio::test]
+    async fn test_disconnect_connection_keeps_participant_until_last() {
+        // テスト項目: 参加者の接続が残っている間は参加者を削除せず、最後の接続で削除する
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = DisconnectParticipantUseCase::new(repository.clone(), message_pusher.clone());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let (phone, _phone_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (laptop, _laptop_rx) = tokio::sync::mpsc::unbounded_channel();
+        repository
+            .add_participant(alice.clone(), Timestamp::new(get_jst_timestamp()))
+            .await
+            .unwrap();
+        message_pusher
+            .register_client(alice.clone(), phone.clone())
+            .await;
+        message_pusher
+            .register_client(alice.clone(), laptop.clone())
+            .await;
+
+        // when (操作):
+        let first = usecase.execute_connection(alice.clone(), &phone).await;
+        let count_after_first = repository.count_connected_clients().await;
+        let last = usecase.execute_connection(alice.clone(), &laptop).await;
+
+        // then (期待する結果):
+        assert_eq!(first, Ok(None));
+        assert_eq!(count_after_first, 1);
+        assert_eq!(last, Ok(Some(vec![])));
+        assert_eq!(repository.count_connected_clients().await, 0);
+    }
+
     #[tokio::test]
     async fn test_disconnect_last_participant() {
         // テスト項目: 最後の参加者が切断した場合、通知対象は空
@@ -0,0 +1,163 @@
+//! UseCase: メモリ使用量の上限の適用
+//!
+//! ルームのメッセージ履歴とクライアントごとの送信キューのおおよそのメモリ使用量を集計し、
+//! 上限を超えていれば古い履歴から削除します。
+//!
+//! ## 設計ノート
+//!
+//! 送信キューは接続の送信ループが消化するまで減らせないため、削除対象は履歴のみです。
+//! 上限ちょうどまで削除すると次のメッセージですぐに超えてしまうため、
+//! 上限の [`EVICTION_TARGET_PERCENT`] % まで削除します。
+
+use std::sync::Arc;
+
+use crate::domain::RoomRepository;
+
+/// 上限を超えた時に削除後の目標とする使用量（上限に対する割合）
+pub const EVICTION_TARGET_PERCENT: usize = 90;
+
+/// メモリ使用量（バイト）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryUsage {
+    /// メッセージ履歴
+    pub history_bytes: usize,
+    /// クライアントの送信キュー
+    pub queue_bytes: usize,
+    /// 上限を超えたために削除したメッセージ数
+    pub evicted_messages: usize,
+}
+
+impl MemoryUsage {
+    /// 合計の使用量
+    pub fn total_bytes(&self) -> usize {
+        self.history_bytes + self.queue_bytes
+    }
+}
+
+/// メモリ使用量の上限を適用するユースケース
+pub struct EnforceMemoryLimitUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// 全体の上限（`None` の場合は集計のみ）
+    limit_bytes: Option<usize>,
+}
+
+impl EnforceMemoryLimitUseCase {
+    /// 新しい EnforceMemoryLimitUseCase を作成
+    pub fn new(repository: Arc<dyn RoomRepository>, limit_bytes: Option<usize>) -> Self {
+        Self {
+            repository,
+            limit_bytes,
+        }
+    }
+
+    /// 全体の上限
+    pub fn limit_bytes(&self) -> Option<usize> {
+        self.limit_bytes
+    }
+
+    /// メモリ使用量を集計し、上限を超えていれば古い履歴を削除する
+    ///
+    /// # Arguments
+    ///
+    /// * `queue_bytes` - クライアントの送信キューのおおよそのメモリ使用量
+    ///
+    /// # Returns
+    ///
+    /// 削除後のメモリ使用量
+    pub async fn execute(&self, queue_bytes: usize) -> MemoryUsage {
+        let mut usage = MemoryUsage {
+            history_bytes: self.repository.history_bytes().await,
+            queue_bytes,
+            evicted_messages: 0,
+        };
+
+        if let Some(limit) = self.limit_bytes
+            && usage.total_bytes() > limit
+        {
+            let target = (limit / 100 * EVICTION_TARGET_PERCENT).saturating_sub(queue_bytes);
+            usage.evicted_messages = self.repository.evict_history(target).await;
+            usage.history_bytes = self.repository.history_bytes().await;
+        }
+
+        usage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{ClientId, MessageContent, Room, RoomIdFactory, Timestamp},
+        infrastructure::repository::InMemoryRoomRepository,
+    };
+
+    async fn repository_with_messages(count: usize) -> Arc<dyn RoomRepository> {
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        for i in 0..count {
+            repository
+                .add_message(
+                    ClientId::new("alice".to_string()).unwrap(),
+                    MessageContent::new(format!("message {}", i)).unwrap(),
+                    Timestamp::new(1000),
+                )
+                .await
+                .unwrap();
+        }
+        repository
+    }
+
+    #[tokio::test]
+    async fn test_execute_within_limit() {
+        // テスト項目: 上限以下の場合は使用量を集計するだけで履歴を削除しない
+        // given (前提条件):
+        let repository = repository_with_messages(10).await;
+        let history_bytes = repository.history_bytes().await;
+        let usecase = EnforceMemoryLimitUseCase::new(repository, Some(history_bytes + 100));
+
+        // when (操作):
+        let usage = usecase.execute(100).await;
+
+        // then (期待する結果):
+        assert_eq!(usage.history_bytes, history_bytes);
+        assert_eq!(usage.queue_bytes, 100);
+        assert_eq!(usage.evicted_messages, 0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_evicts_history_over_limit() {
+        // テスト項目: 上限を超えた場合は送信キューの分も考慮して上限の 90% まで履歴を削除する
+        // given (前提条件):
+        let repository = repository_with_messages(100).await;
+        let history_bytes = repository.history_bytes().await;
+        let limit = history_bytes / 2;
+        let queue_bytes = limit / 4;
+        let usecase = EnforceMemoryLimitUseCase::new(repository.clone(), Some(limit));
+
+        // when (操作):
+        let usage = usecase.execute(queue_bytes).await;
+
+        // then (期待する結果):
+        assert!(usage.evicted_messages > 0);
+        assert!(usage.total_bytes() <= limit / 100 * EVICTION_TARGET_PERCENT);
+        assert_eq!(usage.history_bytes, repository.history_bytes().await);
+        let room = repository.get_room().await.unwrap();
+        assert_eq!(room.messages.len(), 100 - usage.evicted_messages);
+        assert_eq!(room.messages.last().unwrap().seq.value(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_execute_without_limit() {
+        // テスト項目: 上限が設定されていない場合は履歴を削除しない
+        // given (前提条件):
+        let repository = repository_with_messages(10).await;
+        let usecase = EnforceMemoryLimitUseCase::new(repository, None);
+
+        // when (操作):
+        let usage = usecase.execute(usize::MAX / 2).await;
+
+        // then (期待する結果):
+        assert_eq!(usage.evicted_messages, 0);
+    }
+}
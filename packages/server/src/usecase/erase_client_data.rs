@@ -0,0 +1,336 @@
+//! UseCase: クライアントのデータ削除処理
+//!
+//! 個人データの削除要求に応じて、全てのルームからクライアントの参加者情報と送信した全ての
+//! メッセージを Repository から削除し、各ルームの接続中の参加者に削除したメッセージを通知します。
+//! StarRepository を設定した場合は、クライアントが付けたスターも外します。
+//!
+//! ## 設計ノート
+//!
+//! 削除したメッセージのシーケンス番号は再利用しません（バックフィルには欠番として現れる）。
+//! 接続中のクライアントのデータを削除する場合、呼び出し元が先に全てのルームの接続を閉じます
+//! （`connected_rooms`）。閉じずに削除すると、接続は開いたままルームの参加者ではなくなります。
+//!
+//! 作成されたルームへの通知には、`JoinRoomUseCase` がルームごとに作成した MessagePusher を
+//! 使います。起動してから誰も参加していないルームには接続が無いため、通知しません。
+
+use std::sync::Arc;
+
+use crate::domain::{
+    ClientId, MessagePusher, RepositoryError, RoomId, RoomRepository, SequenceNumber,
+    StarRepository,
+};
+
+use super::{JoinRoomUseCase, kick_participant::connected_rooms};
+
+/// 1 つのルームから削除したメッセージ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErasedMessages {
+    /// メッセージを削除したルームの ID
+    pub room_id: RoomId,
+    /// 削除したメッセージのシーケンス番号（古い順）
+    pub seqs: Vec<SequenceNumber>,
+}
+
+/// クライアントのデータ削除のユースケース
+pub struct EraseClientDataUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// 既定のルームの MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+    /// StarRepository（`None` の場合はスターを外さない）
+    stars: Option<Arc<dyn StarRepository>>,
+    /// JoinRoomUseCase（作成されたルームの MessagePusher、`None` の場合は既定のルームにのみ通知する）
+    join_room: Option<Arc<JoinRoomUseCase>>,
+}
+
+impl EraseClientDataUseCase {
+    /// 新しい EraseClientDataUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+    ) -> Self {
+        Self {
+            repository,
+            message_pusher,
+            stars: None,
+            join_room: None,
+        }
+    }
+
+    /// クライアントのデータとともにスターも外す
+    pub fn with_stars(mut self, stars: Arc<dyn StarRepository>) -> Self {
+        self.stars = Some(stars);
+        self
+    }
+
+    /// 作成されたルームの参加者にも削除したメッセージを通知する
+    pub fn with_rooms(mut self, join_room: Arc<JoinRoomUseCase>) -> Self {
+        self.join_room = Some(join_room);
+        self
+    }
+
+    /// クライアントが接続しているルームを取得（既定のルームが先頭）
+    ///
+    /// 呼び出し元は、データを削除する前にこれらのルームの接続を閉じる。
+    pub async fn connected_rooms(
+        &self,
+        client_id: &ClientId,
+    ) -> Result<Vec<RoomId>, RepositoryError> {
+        connected_rooms(self.repository.as_ref(), client_id).await
+    }
+
+    /// 全てのルームからクライアントのデータを削除
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - データを削除するクライアントの ID（Domain Model）
+    /// * `render` - 削除したメッセージのシーケンス番号から、通知する JSON メッセージを作成する関数
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ErasedMessages>)` - メッセージを削除したルームごとのシーケンス番号（既定のルームが先頭）
+    /// * `Err(RepositoryError)` - 削除失敗
+    pub async fn execute(
+        &self,
+        client_id: &ClientId,
+        render: impl Fn(SequenceNumber) -> String,
+    ) -> Result<Vec<ErasedMessages>, RepositoryError> {
+        let default_room = self.repository.get_room_metadata().await?.id;
+        let mut erased = Vec::new();
+        for room_id in self.repository.get_room_ids().await {
+            let repository = match self.repository.for_room(&room_id).await {
+                Ok(repository) => repository,
+                // 削除している間に削除されたルーム
+                Err(RepositoryError::RoomNotFound) => continue,
+                Err(e) => return Err(e),
+            };
+
+            // 1. Repository 経由で参加者情報とメッセージを削除
+            let seqs = repository.erase_client(client_id).await?;
+
+            // 2. 接続中の参加者に削除したメッセージを通知し、送信先に残っているクライアントを登録解除
+            let message_pusher = match &self.join_room {
+                _ if room_id == default_room => Some(self.message_pusher.clone()),
+                Some(join_room) => join_room.message_pusher(&room_id).await,
+                None => None,
+            };
+            if let Some(message_pusher) = message_pusher {
+                let targets = repository.get_all_connected_client_ids().await;
+                if !targets.is_empty() {
+                    for seq in &seqs {
+                        if let Err(e) = message_pusher
+                            .broadcast(targets.clone(), &render(*seq))
+                            .await
+                        {
+                            tracing::warn!(
+                                "Failed to notify deletion of message {} in {}: {}",
+                                seq,
+                                room_id,
+                                e
+                            );
+                        }
+                    }
+                }
+                message_pusher.unregister_client(client_id).await;
+            }
+
+            if !seqs.is_empty() {
+                erased.push(ErasedMessages { room_id, seqs });
+            }
+        }
+
+        // 3. クライアントが付けたスターを外す
+        if let Some(stars) = &self.stars {
+            stars.remove_all(client_id).await?;
+        }
+
+        Ok(erased)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{
+            MessageContent, MessagePushError, PusherChannel, Room, RoomIdFactory, Star, Timestamp,
+        },
+        infrastructure::repository::{InMemoryRoomRepository, InMemoryStarRepository},
+    };
+
+    // ブロードキャストの宛先と内容を記録する MessagePusher
+    #[derive(Default)]
+    struct RecordingMessagePusher {
+        broadcasts: std::sync::Mutex<Vec<(Vec<ClientId>, String)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl MessagePusher for RecordingMessagePusher {
+        async fn register_client(&self, _client_id: ClientId, _sender: PusherChannel) {}
+
+        async fn unregister_client(&self, _client_id: &ClientId) {}
+
+        async fn unregister_connection(
+            &self,
+            _client_id: &ClientId,
+            _sender: &PusherChannel,
+        ) -> usize {
+            0
+        }
+
+        async fn push_to(
+            &self,
+            _client_id: &ClientId,
+            _content: &str,
+        ) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+
+        async fn broadcast(
+            &self,
+            targets: Vec<ClientId>,
+            content: &str,
+        ) -> Result<(), MessagePushError> {
+            self.broadcasts
+                .lock()
+                .unwrap()
+                .push((targets, content.to_string()));
+            Ok(())
+        }
+
+        async fn ping(&self) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_erases_messages_and_notifies_participants() {
+        // テスト項目: クライアントのメッセージとスターが削除され、残りの参加者に削除が 1 件ずつ通知される
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let room_id = room.id.clone();
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        for id in [&alice, &bob] {
+            repository
+                .add_participant(id.clone(), Timestamp::new(1500))
+                .await
+                .unwrap();
+        }
+        for from in [&alice, &bob, &alice] {
+            repository
+                .add_message(
+                    from.clone(),
+                    MessageContent::new("hi".to_string()).unwrap(),
+                    Timestamp::new(2000),
+                )
+                .await
+                .unwrap();
+        }
+        let pusher = Arc::new(RecordingMessagePusher::default());
+        let stars = Arc::new(InMemoryStarRepository::new());
+        stars
+            .add(Star {
+                client_id: alice.clone(),
+                room_id: room_id.clone(),
+                seq: SequenceNumber::new(2),
+                starred_at: Timestamp::new(2500),
+            })
+            .await
+            .unwrap();
+        let usecase = EraseClientDataUseCase::new(repository.clone(), pusher.clone())
+            .with_stars(stars.clone());
+
+        // when (操作):
+        let erased = usecase
+            .execute(&alice, |seq| format!("deleted {}", seq))
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(
+            erased,
+            vec![ErasedMessages {
+                room_id: room_id.clone(),
+                seqs: vec![SequenceNumber::new(1), SequenceNumber::new(3)],
+            }]
+        );
+        assert_eq!(
+            *pusher.broadcasts.lock().unwrap(),
+            vec![
+                (vec![bob.clone()], "deleted 1".to_string()),
+                (vec![bob.clone()], "deleted 3".to_string()),
+            ]
+        );
+        let room = repository.get_room().await.unwrap();
+        assert_eq!(room.participants.len(), 1);
+        assert!(room.messages.iter().all(|message| message.from == bob));
+        assert!(stars.list(&alice).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_erases_messages_in_created_rooms() {
+        // テスト項目: 作成されたルームのメッセージも削除され、そのルームの参加者にだけ削除が通知される
+        // given (前提条件):
+        let lobby = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository = Arc::new(InMemoryRoomRepository::new(lobby));
+        let other = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let other_id = other.id.clone();
+        repository.create_room(other).await.unwrap();
+        let other_repository = repository.for_room(&other_id).await.unwrap();
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        for id in [&alice, &bob] {
+            other_repository
+                .add_participant(id.clone(), Timestamp::new(1500))
+                .await
+                .unwrap();
+        }
+        other_repository
+            .add_message(
+                alice.clone(),
+                MessageContent::new("hi".to_string()).unwrap(),
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap();
+        let lobby_pusher = Arc::new(RecordingMessagePusher::default());
+        let other_pusher = Arc::new(RecordingMessagePusher::default());
+        let join_room = {
+            let other_pusher = other_pusher.clone();
+            Arc::new(JoinRoomUseCase::new(repository.clone(), 10, move |_| {
+                other_pusher.clone() as Arc<dyn MessagePusher>
+            }))
+        };
+        join_room.execute(other_id.as_str()).await.unwrap();
+        let usecase =
+            EraseClientDataUseCase::new(repository, lobby_pusher.clone()).with_rooms(join_room);
+
+        // when (操作):
+        let connected = usecase.connected_rooms(&alice).await.unwrap();
+        let erased = usecase
+            .execute(&alice, |seq| format!("deleted {}", seq))
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(connected, vec![other_id.clone()]);
+        assert_eq!(
+            erased,
+            vec![ErasedMessages {
+                room_id: other_id,
+                seqs: vec![SequenceNumber::new(1)],
+            }]
+        );
+        assert_eq!(
+            *other_pusher.broadcasts.lock().unwrap(),
+            vec![(vec![bob.clone()], "deleted 1".to_string())]
+        );
+        assert!(lobby_pusher.broadcasts.lock().unwrap().is_empty());
+        assert_eq!(
+            other_repository.get_all_connected_client_ids().await,
+            vec![bob]
+        );
+    }
+}
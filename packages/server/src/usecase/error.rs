@@ -1,19 +1,65 @@
 //! UseCase layer error definitions.
 
+use crate::domain::MessageRejection;
+
 /// Errors related to participant connection
 #[derive(Debug, PartialEq, Eq)]
 pub enum ConnectError {
     /// クライアント ID が既に接続している
     DuplicateClientId(String),
     /// Room の容量超過
-    RoomCapacityExceeded,
+    RoomCapacityExceeded {
+        /// Room の参加者数の上限
+        capacity: usize,
+    },
+    /// 永続化（WAL への書き込みなど）に失敗
+    PersistFailed(String),
 }
 
 /// Errors related to message sending
 #[derive(Debug, PartialEq, Eq)]
 pub enum SendMessageError {
+    /// メッセージフィルターが拒否した
+    Rejected(MessageRejection),
+    /// メッセージがルームのポリシーの最大長を超えている
+    MessageTooLong {
+        /// ルームのメッセージの最大長（バイト）
+        max: usize,
+        /// メッセージの長さ（バイト）
+        actual: usize,
+    },
     /// メッセージ容量超過
-    MessageCapacityExceeded,
+    MessageCapacityExceeded {
+        /// Room のメッセージ数の上限
+        capacity: usize,
+    },
+    /// ルームが管理者に凍結されている
+    RoomFrozen,
+    /// 永続化（WAL への書き込みなど）に失敗
+    PersistFailed(String),
     /// ブロードキャスト失敗
     BroadcastFailed(String),
+    /// シーケンサーのタスクが停止している
+    SequencerStopped,
+    /// 送信が多すぎる（レート制限）
+    RateLimited {
+        /// 次に送信できるまでの時間（ミリ秒）
+        retry_after_ms: u64,
+    },
+    /// 投票の選択肢の数が範囲外
+    InvalidPoll {
+        /// 選択肢の数の下限
+        min: usize,
+        /// 選択肢の数の上限
+        max: usize,
+    },
+}
+
+/// Errors related to seeding demo data
+#[derive(Debug, PartialEq, Eq)]
+pub enum SeedError {
+    /// Room の容量超過（ボットを追加できない）
+    RoomCapacityExceeded,
+    /// 永続化（WAL への書き込みなど）に失敗
+    PersistFailed(String),
 }
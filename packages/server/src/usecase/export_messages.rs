@@ -0,0 +1,167 @@
+//! UseCase: ルームのメッセージ履歴のエクスポート処理
+//!
+//! ルームのメッセージ履歴を送信日時でページに分けて Repository から取得します。
+//! エクスポートの形式（JSON・CSV・NDJSON）への変換は UI 層が行います。
+//!
+//! ## 設計ノート
+//!
+//! ページは新しい方から辿ります。1 ページ目は最新の `limit` 件で、次のページは
+//! 前のページの最も古いメッセージの送信日時を `before` に指定して取得します。
+//! 送信日時はクライアントが付けるため、同じ送信日時のメッセージがページの境目にあると
+//! 次のページに含まれない場合があります。
+
+use std::sync::Arc;
+
+use crate::domain::{ChatMessage, RepositoryError, RoomId, RoomRepository, Timestamp};
+
+/// 1 ページのメッセージ数の既定値
+pub const DEFAULT_EXPORT_LIMIT: usize = 100;
+
+/// 1 ページのメッセージ数の上限
+pub const MAX_EXPORT_LIMIT: usize = 1000;
+
+/// エクスポートするページ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportQuery {
+    /// メッセージ数（[`MAX_EXPORT_LIMIT`] を超える場合は切り詰める）
+    pub limit: usize,
+    /// この送信日時より前のメッセージを取得する（`None` の場合は最新から）
+    pub before: Option<Timestamp>,
+}
+
+impl Default for ExportQuery {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_EXPORT_LIMIT,
+            before: None,
+        }
+    }
+}
+
+/// エクスポートしたメッセージのページ
+#[derive(Debug, Clone)]
+pub struct ExportPage {
+    /// メッセージ（古い順）
+    pub messages: Vec<ChatMessage>,
+    /// 次のページを取得する `before`（最後のページの場合は `None`）
+    pub next_before: Option<Timestamp>,
+}
+
+/// メッセージ履歴のエクスポートのエラー
+#[derive(Debug, PartialEq)]
+pub enum ExportMessagesError {
+    /// ルームが見つからない
+    RoomNotFound,
+    /// Repository エラー
+    RepositoryError,
+}
+
+impl From<RepositoryError> for ExportMessagesError {
+    fn from(e: RepositoryError) -> Self {
+        match e {
+            RepositoryError::RoomNotFound => ExportMessagesError::RoomNotFound,
+            _ => ExportMessagesError::RepositoryError,
+        }
+    }
+}
+
+/// メッセージ履歴のエクスポートのユースケース
+pub struct ExportMessagesUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+}
+
+impl ExportMessagesUseCase {
+    /// 新しい ExportMessagesUseCase を作成
+    pub fn new(repository: Arc<dyn RoomRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// メッセージ履歴の 1 ページを取得
+    ///
+    /// # Arguments
+    ///
+    /// * `room_id` - エクスポートするルームの ID
+    /// * `query` - 取得するページ
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ExportPage)` - メッセージのページ
+    /// * `Err(ExportMessagesError)` - 取得失敗
+    pub async fn execute(
+        &self,
+        room_id: &str,
+        query: ExportQuery,
+    ) -> Result<ExportPage, ExportMessagesError> {
+        let room_id =
+            RoomId::new(room_id.to_string()).map_err(|_| ExportMessagesError::RoomNotFound)?;
+        let repository = self.repository.for_room(&room_id).await?;
+        let limit = query.limit.clamp(1, MAX_EXPORT_LIMIT);
+        let messages = repository.get_messages_before(query.before, limit).await?;
+
+        // ページが埋まっている場合は、より古いメッセージが残っている可能性がある
+        let next_before = if messages.len() == limit {
+            messages.iter().map(|message| message.timestamp).min()
+        } else {
+            None
+        };
+        Ok(ExportPage {
+            messages,
+            next_before,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{ClientId, MessageContent, Room, RoomIdFactory},
+        infrastructure::repository::InMemoryRoomRepository,
+    };
+
+    #[tokio::test]
+    async fn test_execute_pages_back_through_history() {
+        // テスト項目: 最新のページから次のページの日時を辿って全てのメッセージを取得でき、最後のページには次のページが無い
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let room_id = room.id.clone();
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        for timestamp in [2000, 3000, 4000] {
+            repository
+                .add_message(
+                    ClientId::new("alice".to_string()).unwrap(),
+                    MessageContent::new("hi".to_string()).unwrap(),
+                    Timestamp::new(timestamp),
+                )
+                .await
+                .unwrap();
+        }
+        let usecase = ExportMessagesUseCase::new(repository);
+        let query = |before| ExportQuery { limit: 2, before };
+
+        // when (操作):
+        let first = usecase
+            .execute(room_id.as_str(), query(None))
+            .await
+            .unwrap();
+        let second = usecase
+            .execute(room_id.as_str(), query(first.next_before))
+            .await
+            .unwrap();
+        let unknown = usecase.execute("no-such-room", query(None)).await;
+
+        // then (期待する結果):
+        let seqs = |page: &ExportPage| {
+            page.messages
+                .iter()
+                .map(|message| message.seq.value())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(seqs(&first), vec![2, 3]);
+        assert_eq!(first.next_before, Some(Timestamp::new(3000)));
+        assert_eq!(seqs(&second), vec![1]);
+        assert_eq!(second.next_before, None);
+        assert_eq!(unknown.err(), Some(ExportMessagesError::RoomNotFound));
+    }
+}
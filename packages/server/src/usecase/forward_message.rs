@@ -0,0 +1,216 @@
+//! UseCase: メッセージの転送処理
+//!
+//! ルームのメッセージを、転送するクライアントが参加中の別のルームにコピーします。
+//! コピーには転送元（元のルーム・送信者・送信日時）を記録し、転送先のルームの
+//! シーケンサーを通して他のメッセージと同じように採番・永続化・ブロードキャストします。
+//!
+//! ## 設計ノート
+//!
+//! 転送先に参加中かどうかは接続ごとの参加の記録（`JoinRoomUseCase::enter`）で判定します。
+//! 転送済みのメッセージをさらに転送した場合は、最初に投稿されたルームを転送元のまま残します。
+//! 投票を転送した場合は質問のみをコピーします（票は元のルームにのみ残る）。
+
+use std::sync::Arc;
+
+use engawa_shared::time::get_jst_timestamp;
+
+use crate::domain::{
+    ChatMessage, ClientId, ForwardedFrom, RoomId, RoomRepository, SequenceNumber, Timestamp,
+};
+
+use super::{JoinRoomError, JoinRoomUseCase, SendMessageError};
+
+/// メッセージ転送のエラー
+#[derive(Debug, PartialEq)]
+pub enum ForwardMessageError {
+    /// 転送先のルームが見つからない
+    RoomNotFound,
+    /// 転送先が転送元と同じルーム
+    SameRoom,
+    /// 転送先のルームに参加していない
+    NotAMember,
+    /// メッセージが履歴に無い
+    MessageNotFound,
+    /// 転送先のルームへの送信失敗
+    SendFailed(SendMessageError),
+    /// Repository エラー
+    RepositoryError,
+}
+
+impl From<JoinRoomError> for ForwardMessageError {
+    fn from(e: JoinRoomError) -> Self {
+        match e {
+            JoinRoomError::RoomNotFound => ForwardMessageError::RoomNotFound,
+            _ => ForwardMessageError::RepositoryError,
+        }
+    }
+}
+
+/// メッセージ転送のユースケース
+pub struct ForwardMessageUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// JoinRoomUseCase（転送先のルームの UseCase と参加の記録）
+    join_room: Arc<JoinRoomUseCase>,
+}
+
+impl ForwardMessageUseCase {
+    /// 新しい ForwardMessageUseCase を作成
+    pub fn new(repository: Arc<dyn RoomRepository>, join_room: Arc<JoinRoomUseCase>) -> Self {
+        Self {
+            repository,
+            join_room,
+        }
+    }
+
+    /// メッセージ転送を実行
+    ///
+    /// # Arguments
+    ///
+    /// * `from_client_id` - 転送するクライアントの ID（Domain Model）
+    /// * `source` - 転送するメッセージのルームの ID（Domain Model）
+    /// * `seq` - 転送するメッセージのシーケンス番号（Domain Model）
+    /// * `target` - 転送先のルームの ID
+    /// * `render` - 転送したメッセージと振られたシーケンス番号からブロードキャストする
+    ///   JSON メッセージを作成する関数
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ChatMessage)` - 転送先のルームに送信したメッセージ（転送元を含む）
+    /// * `Err(ForwardMessageError)` - 転送失敗
+    pub async fn execute(
+        &self,
+        from_client_id: ClientId,
+        source: &RoomId,
+        seq: SequenceNumber,
+        target: &str,
+        render: impl FnOnce(&ChatMessage, SequenceNumber) -> String + Send + 'static,
+    ) -> Result<ChatMessage, ForwardMessageError> {
+        let target_id =
+            RoomId::new(target.to_string()).map_err(|_| ForwardMessageError::RoomNotFound)?;
+        if &target_id == source {
+            return Err(ForwardMessageError::SameRoom);
+        }
+
+        let room = self
+            .repository
+            .get_room_snapshot(source)
+            .await
+            .map_err(|_| ForwardMessageError::RepositoryError)?;
+        let original = room
+            .message(seq)
+            .ok_or(ForwardMessageError::MessageNotFound)?;
+        // 転送済みのメッセージは最初に投稿されたルームを転送元のまま残す
+        let origin = original.forwarded_from.clone().unwrap_or(ForwardedFrom {
+            room_id: source.clone(),
+            from: original.from.clone(),
+            timestamp: original.timestamp,
+        });
+
+        let target_room = self.join_room.execute(target_id.as_str()).await?;
+        if !self.join_room.is_member(&from_client_id, &target_id).await {
+            return Err(ForwardMessageError::NotAMember);
+        }
+
+        let mut message = ChatMessage::new(
+            from_client_id.clone(),
+            original.content.clone(),
+            Timestamp::new(get_jst_timestamp()),
+        );
+        message.forwarded_from = Some(origin.clone());
+        let rendered = message.clone();
+        target_room
+            .send_message
+            .execute_forward(
+                from_client_id,
+                message.content.clone(),
+                origin,
+                move |seq| render(&rendered, seq),
+            )
+            .await
+            .map_err(ForwardMessageError::SendFailed)?;
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{MessageContent, MessagePusher, Room, RoomIdFactory},
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+        },
+    };
+
+    fn client_id(name: &str) -> ClientId {
+        ClientId::new(name.to_string()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_execute_copies_message_with_origin() {
+        // テスト項目: 参加中の別のルームに転送元付きでコピーされ、参加していないルーム・同じルーム・無いメッセージには転送できない
+        // given (前提条件):
+        let lobby = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let lobby_id = lobby.id.clone();
+        let repository = Arc::new(InMemoryRoomRepository::new(lobby));
+        let other = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let other_id = other.id.clone();
+        repository.create_room(other).await.unwrap();
+        let join_room = Arc::new(JoinRoomUseCase::new(repository.clone(), 10, |_| {
+            Arc::new(WebSocketMessagePusher::new(Default::default())) as Arc<dyn MessagePusher>
+        }));
+        let usecase = ForwardMessageUseCase::new(repository.clone(), join_room.clone());
+        let seq = repository
+            .add_message(
+                client_id("bob"),
+                MessageContent::new("Hello".to_string()).unwrap(),
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap();
+        join_room
+            .enter(&client_id("alice"), &other_id)
+            .await
+            .unwrap();
+
+        // when (操作):
+        let (usecase, source) = (&usecase, &lobby_id);
+        let forward = |from: &str, seq: u64, target: &RoomId| {
+            let (from, target) = (client_id(from), target.clone());
+            async move {
+                usecase
+                    .execute(
+                        from,
+                        source,
+                        SequenceNumber::new(seq),
+                        target.as_str(),
+                        |_, seq| format!("message {}", seq),
+                    )
+                    .await
+            }
+        };
+        let forwarded = forward("alice", seq.value(), &other_id).await.unwrap();
+        let not_a_member = forward("carol", seq.value(), &other_id).await;
+        let same_room = forward("alice", seq.value(), &lobby_id).await;
+        let missing = forward("alice", 99, &other_id).await;
+        let unknown = forward("alice", seq.value(), &RoomIdFactory::generate().unwrap()).await;
+
+        // then (期待する結果):
+        let origin = ForwardedFrom {
+            room_id: lobby_id.clone(),
+            from: client_id("bob"),
+            timestamp: Timestamp::new(2000),
+        };
+        assert_eq!(forwarded.forwarded_from, Some(origin.clone()));
+        assert_eq!(forwarded.from, client_id("alice"));
+        let copied = repository.get_room_by_id(&other_id).await.unwrap();
+        assert_eq!(copied.messages.len(), 1);
+        assert_eq!(copied.messages[0].content.as_str(), "Hello");
+        assert_eq!(copied.messages[0].forwarded_from, Some(origin));
+        assert_eq!(not_a_member.err(), Some(ForwardMessageError::NotAMember));
+        assert_eq!(same_room.err(), Some(ForwardMessageError::SameRoom));
+        assert_eq!(missing.err(), Some(ForwardMessageError::MessageNotFound));
+        assert_eq!(unknown.err(), Some(ForwardMessageError::RoomNotFound));
+    }
+}
@@ -0,0 +1,99 @@
+//! UseCase: 接続時のメッセージ履歴の取得処理
+//!
+//! 新しく接続したクライアントに、ルームの直近のメッセージを送るために使います。
+//! 再接続したクライアントは `GetRoomMessagesUseCase` で切断中のメッセージを取得します。
+
+use std::sync::Arc;
+
+use super::GetRoomMessagesError;
+use crate::domain::{ChatMessage, RepositoryError, RoomId, RoomRepository};
+
+/// 接続時に送るメッセージ履歴の既定の件数
+pub const DEFAULT_HISTORY_REPLAY: usize = 20;
+
+/// メッセージ履歴の取得のユースケース
+pub struct GetMessageHistoryUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// 取得するメッセージの最大件数
+    limit: usize,
+}
+
+impl GetMessageHistoryUseCase {
+    /// 新しい GetMessageHistoryUseCase を作成
+    ///
+    /// # Arguments
+    ///
+    /// * `repository` - ルームの Repository
+    /// * `limit` - 取得するメッセージの最大件数
+    pub fn new(repository: Arc<dyn RoomRepository>, limit: usize) -> Self {
+        Self { repository, limit }
+    }
+
+    /// ルームの直近のメッセージを取得
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ChatMessage>)` - 直近の最大 `limit` 件のメッセージ（古い順）
+    /// * `Err(GetRoomMessagesError)` - 取得失敗
+    pub async fn execute(&self, room_id: &str) -> Result<Vec<ChatMessage>, GetRoomMessagesError> {
+        let room_id =
+            RoomId::new(room_id.to_string()).map_err(|_| GetRoomMessagesError::RoomNotFound)?;
+        let room = self
+            .repository
+            .get_room_snapshot(&room_id)
+            .await
+            .map_err(|e| match e {
+                RepositoryError::RoomNotFound => GetRoomMessagesError::RoomNotFound,
+                _ => GetRoomMessagesError::RepositoryError,
+            })?;
+
+        Ok(room.recent_messages(self.limit).to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{ClientId, MessageContent, Room, RoomIdFactory, SequenceNumber, Timestamp},
+        infrastructure::repository::InMemoryRoomRepository,
+    };
+
+    #[tokio::test]
+    async fn test_execute_returns_recent_messages() {
+        // テスト項目: 直近の limit 件のメッセージが古い順に返り、存在しないルームはエラーになる
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let room_id = room.id.as_str().to_string();
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        for i in 0..5 {
+            repository
+                .add_message(
+                    alice.clone(),
+                    MessageContent::new(format!("message {}", i)).unwrap(),
+                    Timestamp::new(2000 + i),
+                )
+                .await
+                .unwrap();
+        }
+        let usecase = GetMessageHistoryUseCase::new(repository, 3);
+
+        // when (操作):
+        let history = usecase.execute(&room_id).await.unwrap();
+        let missing = usecase.execute("unknown").await;
+
+        // then (期待する結果):
+        let seqs: Vec<_> = history.iter().map(|message| message.seq).collect();
+        assert_eq!(
+            seqs,
+            vec![
+                SequenceNumber::new(3),
+                SequenceNumber::new(4),
+                SequenceNumber::new(5)
+            ]
+        );
+        assert!(matches!(missing, Err(GetRoomMessagesError::RoomNotFound)));
+    }
+}
@@ -1,8 +1,53 @@
 //! UseCase: ルーム詳細取得処理
+//!
+//! ルームのメタデータに加えて、呼び出し側が指定した項目（参加者・最新のメッセージ）だけを
+//! Repository の射影クエリで取得します。メタデータだけが必要な場合に、
+//! メッセージ履歴全体を読み出さずに済みます。
 
 use std::sync::Arc;
 
-use crate::domain::{Room, RoomRepository};
+use crate::domain::{
+    ChatMessage, Participant, RepositoryError, RoomId, RoomMetadata, RoomRepository,
+};
+
+/// 取得するメッセージ数の既定値
+pub const DEFAULT_MESSAGE_LIMIT: usize = 50;
+
+/// 取得するメッセージ数の上限
+pub const MAX_MESSAGE_LIMIT: usize = 200;
+
+/// ルーム詳細に含める項目
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoomDetailQuery {
+    /// 参加者リストを含めるか
+    pub include_participants: bool,
+    /// 最新のメッセージを含めるか
+    pub include_messages: bool,
+    /// 含めるメッセージ数（[`MAX_MESSAGE_LIMIT`] を超える場合は切り詰める）
+    pub message_limit: usize,
+}
+
+impl Default for RoomDetailQuery {
+    /// 従来のレスポンスと同じく、参加者リストのみを含める
+    fn default() -> Self {
+        Self {
+            include_participants: true,
+            include_messages: false,
+            message_limit: DEFAULT_MESSAGE_LIMIT,
+        }
+    }
+}
+
+/// ルーム詳細
+#[derive(Debug, Clone)]
+pub struct RoomDetail {
+    /// ルームのメタデータ（Domain Model）
+    pub metadata: RoomMetadata,
+    /// 参加者リスト（含めない場合は `None`）
+    pub participants: Option<Vec<Participant>>,
+    /// 最新のメッセージ（古い順、含めない場合は `None`）
+    pub messages: Option<Vec<ChatMessage>>,
+}
 
 /// ルーム詳細取得のユースケース
 pub struct GetRoomDetailUseCase {
@@ -30,23 +75,234 @@ impl GetRoomDetailUseCase {
     /// # Arguments
     ///
     /// * `room_id` - 取得するルームの ID
+    /// * `query` - 含める項目
     ///
     /// # Returns
     ///
-    /// * `Ok(Room)` - ルームの詳細情報（Domain Model）
+    /// * `Ok(RoomDetail)` - ルームの詳細情報
     /// * `Err(GetRoomDetailError)` - 取得失敗
-    pub async fn execute(&self, room_id: String) -> Result<Room, GetRoomDetailError> {
-        let room = self
+    pub async fn execute(
+        &self,
+        room_id: &str,
+        query: RoomDetailQuery,
+    ) -> Result<RoomDetail, GetRoomDetailError> {
+        let room_id =
+            RoomId::new(room_id.to_string()).map_err(|_| GetRoomDetailError::RoomNotFound)?;
+        let repository = self
             .repository
-            .get_room()
+            .for_room(&room_id)
+            .await
+            .map_err(|e| match e {
+                RepositoryError::RoomNotFound => GetRoomDetailError::RoomNotFound,
+                _ => GetRoomDetailError::RepositoryError,
+            })?;
+        let metadata = repository
+            .get_room_metadata()
             .await
             .map_err(|_| GetRoomDetailError::RepositoryError)?;
 
-        // Check if the requested room_id matches
-        if room.id.as_str() != room_id {
-            return Err(GetRoomDetailError::RoomNotFound);
+        let participants = if query.include_participants {
+            Some(repository.get_participants().await)
+        } else {
+            None
+        };
+        let messages = if query.include_messages {
+            let limit = query.message_limit.min(MAX_MESSAGE_LIMIT);
+            Some(
+                repository
+                    .get_recent_messages(limit)
+                    .await
+                    .map_err(|_| GetRoomDetailError::RepositoryError)?,
+            )
+        } else {
+            None
+        };
+
+        Ok(RoomDetail {
+            metadata,
+            participants,
+            messages,
+        })
+    }
+
+    /// スラッグを指定してルーム詳細を取得
+    ///
+    /// # Arguments
+    ///
+    /// * `slug` - 取得するルームのスラッグ
+    /// * `query` - 含める項目
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RoomDetail)` - ルームの詳細情報
+    /// * `Err(GetRoomDetailError)` - 取得失敗（スラッグに対応するルームが無い場合は `RoomNotFound`）
+    pub async fn execute_by_slug(
+        &self,
+        slug: &str,
+        query: RoomDetailQuery,
+    ) -> Result<RoomDetail, GetRoomDetailError> {
+        let room_id = self.resolve_slug(slug).await?;
+        self.execute(room_id.as_str(), query).await
+    }
+
+    /// スラッグに対応するルーム ID を取得
+    ///
+    /// 既定のルームに限らず、作成されたすべてのルームからスラッグを探す。
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RoomId)` - スラッグが設定されたルームの ID
+    /// * `Err(GetRoomDetailError)` - スラッグに対応するルームが無い場合は `RoomNotFound`
+    pub async fn resolve_slug(&self, slug: &str) -> Result<RoomId, GetRoomDetailError> {
+        for room_id in self.repository.get_room_ids().await {
+            let room = match self.repository.get_room_snapshot(&room_id).await {
+                Ok(room) => room,
+                // 一覧の取得後に削除されたルーム
+                Err(RepositoryError::RoomNotFound) => continue,
+                Err(_) => return Err(GetRoomDetailError::RepositoryError),
+            };
+            if room.slug.as_ref().is_some_and(|s| s.as_str() == slug) {
+                return Ok(room_id);
+            }
         }
+        Err(GetRoomDetailError::RoomNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{
+        domain::{ClientId, MessageContent, Room, RoomIdFactory, RoomSlug, Timestamp},
+        infrastructure::repository::InMemoryRoomRepository,
+    };
+
+    async fn create_repository(messages: usize) -> (Arc<dyn RoomRepository>, String) {
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let room_id = room.id.as_str().to_string();
+        let repository: Arc<dyn RoomRepository> = Arc::new(InMemoryRoomRepository::new(room));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        repository
+            .add_participant(alice.clone(), Timestamp::new(2000))
+            .await
+            .unwrap();
+        for i in 0..messages {
+            repository
+                .add_message(
+                    alice.clone(),
+                    MessageContent::new(format!("message {}", i)).unwrap(),
+                    Timestamp::new(3000 + i as i64),
+                )
+                .await
+                .unwrap();
+        }
+        (repository, room_id)
+    }
+
+    #[tokio::test]
+    async fn test_default_query_includes_participants_only() {
+        // テスト項目: 既定では参加者リストのみを含め、メッセージは含めない
+        // given (前提条件):
+        let (repository, room_id) = create_repository(3).await;
+        let usecase = GetRoomDetailUseCase::new(repository);
+
+        // when (操作):
+        let detail = usecase
+            .execute(&room_id, RoomDetailQuery::default())
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(detail.metadata.participant_count, 1);
+        assert_eq!(detail.metadata.message_count, 3);
+        assert_eq!(detail.metadata.last_seq.value(), 3);
+        assert_eq!(detail.participants.unwrap().len(), 1);
+        assert!(detail.messages.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_include_messages_returns_latest_messages() {
+        // テスト項目: メッセージを含める場合、最新の message_limit 件を古い順に返す
+        // given (前提条件):
+        let (repository, room_id) = create_repository(5).await;
+        let usecase = GetRoomDetailUseCase::new(repository);
+        let query = RoomDetailQuery {
+            include_participants: false,
+            include_messages: true,
+            message_limit: 2,
+        };
+
+        // when (操作):
+        let detail = usecase.execute(&room_id, query).await.unwrap();
+
+        // then (期待する結果):
+        assert!(detail.participants.is_none());
+        let seqs: Vec<_> = detail
+            .messages
+            .unwrap()
+            .iter()
+            .map(|m| m.seq.value())
+            .collect();
+        assert_eq!(seqs, vec![4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_room_is_not_found() {
+        // テスト項目: 存在しないルーム ID は RoomNotFound になる
+        // given (前提条件):
+        let (repository, _) = create_repository(0).await;
+        let usecase = GetRoomDetailUseCase::new(repository);
+
+        // when (操作):
+        let result = usecase
+            .execute("unknown-room", RoomDetailQuery::default())
+            .await;
+
+        // then (期待する結果):
+        assert!(matches!(result, Err(GetRoomDetailError::RoomNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_execute_by_slug() {
+        // テスト項目: スラッグでルーム詳細を取得でき、一致しないスラッグは RoomNotFound になる
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        room.slug = Some(RoomSlug::new("general".to_string()).unwrap());
+        let room_id = room.id.clone();
+        let repository: Arc<dyn RoomRepository> = Arc::new(InMemoryRoomRepository::new(room));
+        let usecase = GetRoomDetailUseCase::new(repository);
+
+        // when (操作):
+        let detail = usecase
+            .execute_by_slug("general", RoomDetailQuery::default())
+            .await;
+        let unknown = usecase
+            .execute_by_slug("random", RoomDetailQuery::default())
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(detail.unwrap().metadata.id, room_id);
+        assert!(matches!(unknown, Err(GetRoomDetailError::RoomNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_slug_of_created_room() {
+        // テスト項目: 作成したルームのスラッグも解決できる
+        // given (前提条件):
+        let (repository, _) = create_repository(0).await;
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        room.slug = Some(RoomSlug::new("random".to_string()).unwrap());
+        let room_id = room.id.clone();
+        repository.create_room(room).await.unwrap();
+        let usecase = GetRoomDetailUseCase::new(repository);
+
+        // when (操作):
+        let resolved = usecase.resolve_slug("random").await;
+        let unknown = usecase.resolve_slug("general").await;
 
-        Ok(room)
+        // then (期待する結果):
+        assert_eq!(resolved, Ok(room_id));
+        assert_eq!(unknown, Err(GetRoomDetailError::RoomNotFound));
     }
 }
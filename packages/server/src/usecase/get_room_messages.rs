@@ -0,0 +1,60 @@
+//! UseCase: ルームのメッセージ取得処理（バックフィル）
+//!
+//! 再接続したクライアントが、切断中に受け取れなかったメッセージを
+//! シーケンス番号を起点に取得するために使います。
+
+use std::sync::Arc;
+
+use crate::domain::{ChatMessage, RepositoryError, RoomId, RoomRepository, SequenceNumber};
+
+/// ルームのメッセージ取得のユースケース
+pub struct GetRoomMessagesUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+}
+
+/// ルームのメッセージ取得エラー
+#[derive(Debug, PartialEq)]
+pub enum GetRoomMessagesError {
+    /// ルームが見つからない
+    RoomNotFound,
+    /// Repository エラー
+    RepositoryError,
+}
+
+impl GetRoomMessagesUseCase {
+    /// 新しい GetRoomMessagesUseCase を作成
+    pub fn new(repository: Arc<dyn RoomRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// 指定したシーケンス番号より後のメッセージを取得
+    ///
+    /// # Arguments
+    ///
+    /// * `room_id` - 取得するルームの ID
+    /// * `since` - 受信済みの最新のシーケンス番号（これより後のメッセージを返す）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((Vec<ChatMessage>, SequenceNumber))` - メッセージ（古い順）とルームの最新のシーケンス番号
+    /// * `Err(GetRoomMessagesError)` - 取得失敗
+    pub async fn execute(
+        &self,
+        room_id: &str,
+        since: SequenceNumber,
+    ) -> Result<(Vec<ChatMessage>, SequenceNumber), GetRoomMessagesError> {
+        let room_id =
+            RoomId::new(room_id.to_string()).map_err(|_| GetRoomMessagesError::RoomNotFound)?;
+        let room = self
+            .repository
+            .get_room_snapshot(&room_id)
+            .await
+            .map_err(|e| match e {
+                RepositoryError::RoomNotFound => GetRoomMessagesError::RoomNotFound,
+                _ => GetRoomMessagesError::RepositoryError,
+            })?;
+
+        Ok((room.messages_since(since).to_vec(), room.last_seq))
+    }
+}
@@ -0,0 +1,143 @@
+//! UseCase: ルームの統計取得処理
+//!
+//! MessageAnalyzer がメッセージに付けたタグを集計し、タグごとのメッセージ数を返します。
+//! 集計の対象は保持しているメッセージ履歴のみです（容量を超えて削除されたメッセージは含まない）。
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::domain::{MessageTag, RepositoryError, RoomId, RoomRepository};
+
+/// ルームの統計取得のユースケース
+pub struct GetRoomStatsUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+}
+
+/// ルームの統計取得エラー
+#[derive(Debug, PartialEq)]
+pub enum GetRoomStatsError {
+    /// ルームが見つからない
+    RoomNotFound,
+    /// Repository エラー
+    RepositoryError,
+}
+
+/// ルームの統計
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomStats {
+    pub room_id: RoomId,
+    /// 保持しているメッセージの数
+    pub message_count: usize,
+    /// タグが 1 つ以上付いたメッセージの数
+    pub tagged_message_count: usize,
+    /// タグごとのメッセージ数（多い順、同数はタグの辞書順）
+    pub tags: Vec<(MessageTag, usize)>,
+}
+
+impl GetRoomStatsUseCase {
+    /// 新しい GetRoomStatsUseCase を作成
+    pub fn new(repository: Arc<dyn RoomRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// ルームの統計を取得
+    ///
+    /// # Arguments
+    ///
+    /// * `room_id` - 取得するルームの ID
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RoomStats)` - ルームの統計
+    /// * `Err(GetRoomStatsError)` - 取得失敗
+    pub async fn execute(&self, room_id: &str) -> Result<RoomStats, GetRoomStatsError> {
+        let room_id =
+            RoomId::new(room_id.to_string()).map_err(|_| GetRoomStatsError::RoomNotFound)?;
+        let room = self
+            .repository
+            .get_room_snapshot(&room_id)
+            .await
+            .map_err(|e| match e {
+                RepositoryError::RoomNotFound => GetRoomStatsError::RoomNotFound,
+                _ => GetRoomStatsError::RepositoryError,
+            })?;
+
+        let mut counts: HashMap<&MessageTag, usize> = HashMap::new();
+        let mut tagged_message_count = 0;
+        for message in &room.messages {
+            if !message.tags.is_empty() {
+                tagged_message_count += 1;
+            }
+            for tag in &message.tags {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        let mut tags: Vec<(MessageTag, usize)> = counts
+            .into_iter()
+            .map(|(tag, count)| (tag.clone(), count))
+            .collect();
+        tags.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+
+        Ok(RoomStats {
+            room_id: room.id.clone(),
+            message_count: room.messages.len(),
+            tagged_message_count,
+            tags,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{ClientId, MessageContent, Room, RoomIdFactory, Timestamp},
+        infrastructure::repository::InMemoryRoomRepository,
+    };
+
+    fn tag(value: &str) -> MessageTag {
+        MessageTag::new(value.to_string()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_execute_counts_tags() {
+        // テスト項目: タグごとのメッセージ数が多い順に集計される
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let room_id = room.id.as_str().to_string();
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let tagged = [
+            vec![tag("keyword:deploy")],
+            vec![tag("keyword:deploy"), tag("keyword:incident")],
+            vec![],
+        ];
+        for tags in tagged {
+            let seq = repository
+                .add_message(
+                    alice.clone(),
+                    MessageContent::new("message".to_string()).unwrap(),
+                    Timestamp::new(2000),
+                )
+                .await
+                .unwrap();
+            if !tags.is_empty() {
+                repository.tag_message(seq, tags).await.unwrap();
+            }
+        }
+        let usecase = GetRoomStatsUseCase::new(repository);
+
+        // when (操作):
+        let stats = usecase.execute(&room_id).await.unwrap();
+        let missing = usecase.execute("unknown").await;
+
+        // then (期待する結果):
+        assert_eq!(stats.message_count, 3);
+        assert_eq!(stats.tagged_message_count, 2);
+        assert_eq!(
+            stats.tags,
+            vec![(tag("keyword:deploy"), 2), (tag("keyword:incident"), 1)]
+        );
+        assert_eq!(missing, Err(GetRoomStatsError::RoomNotFound));
+    }
+}
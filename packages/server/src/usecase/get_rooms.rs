@@ -1,8 +1,73 @@
 //! UseCase: ルーム一覧取得処理
+//!
+//! ルームを絞り込み・並べ替えた上で、指定した範囲（ページ）を返します。
+//! 一覧にはメッセージ履歴は不要なため、Repository のメタデータの射影から組み立てます。
 
 use std::sync::Arc;
 
-use crate::domain::{Room, RoomRepository};
+use crate::domain::{ClientId, RoomMetadata, RoomRepository, Timestamp};
+
+/// 1 ページのルーム数の既定値
+pub const DEFAULT_ROOMS_LIMIT: usize = 50;
+
+/// 1 ページのルーム数の上限
+pub const MAX_ROOMS_LIMIT: usize = 200;
+
+/// ルーム一覧の並び順
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoomSort {
+    /// 作成日時の古い順
+    #[default]
+    CreatedAt,
+    /// 参加者の多い順（同数の場合は作成日時の古い順）
+    Participants,
+}
+
+/// ルーム一覧の取得条件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomsQuery {
+    /// 1 ページのルーム数（[`MAX_ROOMS_LIMIT`] を超える場合は切り詰める）
+    pub limit: usize,
+    /// 読み飛ばすルーム数
+    pub offset: usize,
+    /// 並び順
+    pub sort: RoomSort,
+    /// ルーム ID または名前（スラッグ）に含まれる文字列（大文字・小文字を区別しない）
+    pub q: Option<String>,
+}
+
+impl Default for RoomsQuery {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_ROOMS_LIMIT,
+            offset: 0,
+            sort: RoomSort::default(),
+            q: None,
+        }
+    }
+}
+
+/// ルーム一覧の 1 件
+#[derive(Debug, Clone)]
+pub struct RoomListing {
+    /// ルームのメタデータ（参加者数・メッセージ数・最終アクティビティ日時を含む）
+    pub metadata: RoomMetadata,
+    /// 接続中の参加者の ID
+    pub participants: Vec<ClientId>,
+}
+
+/// ルーム一覧の 1 ページ
+#[derive(Debug, Clone)]
+pub struct RoomsPage {
+    /// このページのルーム
+    pub rooms: Vec<RoomListing>,
+    /// 絞り込み後の全ルーム数
+    pub total: usize,
+    /// 実際に適用した 1 ページのルーム数
+    pub limit: usize,
+    /// 絞り込み後の全ルームの最終アクティビティ日時（ルームが無い場合は `None`）
+    pub last_activity_at: Option<Timestamp>,
+}
 
 /// ルーム一覧取得のユースケース
 pub struct GetRoomsUseCase {
@@ -18,12 +83,214 @@ impl GetRoomsUseCase {
 
     /// ルーム一覧を取得
     ///
+    /// # Arguments
+    ///
+    /// * `query` - 絞り込み・並び順・ページの指定
+    ///
     /// # Returns
     ///
-    /// * `Ok(Vec<Room>)` - ルーム一覧（Domain Model）
+    /// * `Ok(RoomsPage)` - 指定したページのルームと、絞り込み後の全ルーム数
     /// * `Err(())` - 取得失敗
-    pub async fn execute(&self) -> Result<Vec<Room>, ()> {
-        let room = self.repository.get_room().await.map_err(|_| ())?;
-        Ok(vec![room])
+    pub async fn execute(&self, query: &RoomsQuery) -> Result<RoomsPage, ()> {
+        let mut listings = Vec::new();
+        for room_id in self.repository.get_room_ids().await {
+            // 一覧の取得中に削除されたルームは含めない
+            let Ok(repository) = self.repository.for_room(&room_id).await else {
+                continue;
+            };
+            let metadata = repository.get_room_metadata().await.map_err(|_| ())?;
+            let participants = repository.get_all_connected_client_ids().await;
+            listings.push(RoomListing {
+                metadata,
+                participants,
+            });
+        }
+        Ok(paginate(listings, query))
+    }
+}
+
+/// ルームを絞り込み・並べ替え、指定した範囲を切り出す
+fn paginate(rooms: Vec<RoomListing>, query: &RoomsQuery) -> RoomsPage {
+    let needle = query.q.as_deref().map(str::to_lowercase);
+    let mut rooms: Vec<RoomListing> = rooms
+        .into_iter()
+        .filter(|room| match &needle {
+            Some(needle) => {
+                let metadata = &room.metadata;
+                metadata.id.as_str().to_lowercase().contains(needle)
+                    || metadata
+                        .slug
+                        .as_ref()
+                        .is_some_and(|slug| slug.as_str().to_lowercase().contains(needle))
+            }
+            None => true,
+        })
+        .collect();
+
+    match query.sort {
+        RoomSort::CreatedAt => rooms.sort_by(|a, b| {
+            let (a, b) = (&a.metadata, &b.metadata);
+            (a.created_at.value(), a.id.as_str()).cmp(&(b.created_at.value(), b.id.as_str()))
+        }),
+        RoomSort::Participants => rooms.sort_by(|a, b| {
+            let (a, b) = (&a.metadata, &b.metadata);
+            b.participant_count
+                .cmp(&a.participant_count)
+                .then_with(|| a.created_at.value().cmp(&b.created_at.value()))
+                .then_with(|| a.id.as_str().cmp(b.id.as_str()))
+        }),
+    }
+
+    let total = rooms.len();
+    let last_activity_at = rooms
+        .iter()
+        .map(|room| room.metadata.last_activity_at)
+        .max();
+    let limit = query.limit.min(MAX_ROOMS_LIMIT);
+    let rooms = rooms.into_iter().skip(query.offset).take(limit).collect();
+    RoomsPage {
+        rooms,
+        total,
+        limit,
+        last_activity_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Participant, Room, RoomId, RoomSlug};
+
+    fn room(id: &str, created_at: i64, participants: usize) -> RoomListing {
+        let mut room = Room::new(
+            RoomId::new(id.to_string()).unwrap(),
+            Timestamp::new(created_at),
+        );
+        for i in 0..participants {
+            room.add_participant(Participant::new(
+                ClientId::new(format!("client-{}", i)).unwrap(),
+                Timestamp::new(created_at),
+            ))
+            .unwrap();
+        }
+        RoomListing {
+            metadata: room.metadata(),
+            participants: room.participants.iter().map(|p| p.id.clone()).collect(),
+        }
+    }
+
+    fn rooms() -> Vec<RoomListing> {
+        vec![
+            room("cccccccc-0000-4000-8000-000000000000", 3000, 1),
+            room("aaaaaaaa-0000-4000-8000-000000000000", 1000, 0),
+            room("bbbbbbbb-0000-4000-8000-000000000000", 2000, 2),
+        ]
+    }
+
+    fn ids(page: &RoomsPage) -> Vec<&str> {
+        page.rooms
+            .iter()
+            .map(|room| &room.metadata.id.as_str()[..8])
+            .collect()
+    }
+
+    #[test]
+    fn test_paginate_sorts_rooms() {
+        // テスト項目: 作成日時の古い順（既定）と参加者の多い順に並べ替えられる
+        // when (操作):
+        let by_created_at = paginate(rooms(), &RoomsQuery::default());
+        let by_participants = paginate(
+            rooms(),
+            &RoomsQuery {
+                sort: RoomSort::Participants,
+                ..RoomsQuery::default()
+            },
+        );
+
+        // then (期待する結果):
+        assert_eq!(
+            ids(&by_created_at),
+            vec!["aaaaaaaa", "bbbbbbbb", "cccccccc"]
+        );
+        assert_eq!(
+            ids(&by_participants),
+            vec!["bbbbbbbb", "cccccccc", "aaaaaaaa"]
+        );
+    }
+
+    #[test]
+    fn test_paginate_limit_and_offset() {
+        // テスト項目: limit と offset で範囲を切り出し、total は切り出す前の件数になる
+        // when (操作):
+        let page = paginate(
+            rooms(),
+            &RoomsQuery {
+                limit: 1,
+                offset: 1,
+                ..RoomsQuery::default()
+            },
+        );
+        let past_end = paginate(
+            rooms(),
+            &RoomsQuery {
+                offset: 10,
+                ..RoomsQuery::default()
+            },
+        );
+
+        // then (期待する結果):
+        assert_eq!(ids(&page), vec!["bbbbbbbb"]);
+        assert_eq!(page.total, 3);
+        assert!(past_end.rooms.is_empty());
+        assert_eq!(past_end.total, 3);
+    }
+
+    #[test]
+    fn test_paginate_filters_and_clamps_limit() {
+        // テスト項目: q は大文字・小文字を区別せずに絞り込み、limit は上限で切り詰める
+        // when (操作):
+        let page = paginate(
+            rooms(),
+            &RoomsQuery {
+                limit: MAX_ROOMS_LIMIT + 1,
+                q: Some("BBBB".to_string()),
+                ..RoomsQuery::default()
+            },
+        );
+
+        // then (期待する結果):
+        assert_eq!(ids(&page), vec!["bbbbbbbb"]);
+        assert_eq!(page.total, 1);
+        assert_eq!(page.limit, MAX_ROOMS_LIMIT);
+        assert_eq!(page.last_activity_at, Some(Timestamp::new(2000)));
+    }
+
+    #[test]
+    fn test_paginate_filters_by_name() {
+        // テスト項目: q はルームの名前（スラッグ）にも大文字・小文字を区別せずに一致する
+        // given (前提条件):
+        let mut rooms = rooms();
+        let mut named = Room::new(
+            RoomId::new("dddddddd-0000-4000-8000-000000000000".to_string()).unwrap(),
+            Timestamp::new(4000),
+        );
+        named.slug = Some(RoomSlug::new("team-standup".to_string()).unwrap());
+        rooms.push(RoomListing {
+            metadata: named.metadata(),
+            participants: Vec::new(),
+        });
+
+        // when (操作):
+        let page = paginate(
+            rooms,
+            &RoomsQuery {
+                q: Some("StandUp".to_string()),
+                ..RoomsQuery::default()
+            },
+        );
+
+        // then (期待する結果):
+        assert_eq!(ids(&page), vec!["dddddddd"]);
+        assert_eq!(page.total, 1);
     }
 }
@@ -0,0 +1,354 @@
+//! UseCase: ルーム参加処理
+//!
+//! 接続先のルームを ID で選び、そのルームを対象に操作する UseCase（参加者の接続・切断、
+//! メッセージ送信、入力中の通知、ルーム状態取得）を返します。
+//!
+//! ## 設計ノート
+//!
+//! ブロードキャストをルームごとに分けるため、ルームごとに MessagePusher を作成します
+//! （送信先はそのルームに接続したクライアントのみ）。ルームの UseCase は最初に参加した時に
+//! 作成してキャッシュし、ルームが削除されていれば破棄します（シーケンサーも停止する）。
+//! 既定のルームの UseCase は `register` で登録し、ブリッジ（MQTT・Discord など）を挟んだ
+//! MessagePusher をそのまま使います。
+//!
+//! ルームのメッセージのポリシー（`MessagePolicy`）は UseCase の作成時にルームから読み込み、
+//! 送信するメッセージの検証とメッセージフィルターの選択に使います。
+//!
+//! クライアントごとのサーバ資源（送信キューやセッション）を抑えるため、1 つのクライアントが
+//! 同時に参加できるルーム数に上限を設けます（ドメインのポリシー `RoomMemberships`）。
+//! 接続ごとに `enter` で参加を記録し、切断時に `leave` で取り消します（既定のルームを含む）。
+
+use std::{collections::HashMap, sync::Arc};
+
+use tokio::sync::Mutex;
+
+use crate::domain::{
+    ClientId, MembershipError, MessageFilterChain, MessagePolicy, MessagePusher, RepositoryError,
+    RoomId, RoomMemberships, RoomRepository,
+};
+
+use super::{
+    BroadcastTypingUseCase, ConnectParticipantUseCase, DEFAULT_TYPING_DEBOUNCE,
+    DisconnectParticipantUseCase, GetRoomStateUseCase, RateLimiter, RenameParticipantUseCase,
+    RespondToIncidentUseCase, SendMessageUseCase, SetActivityUseCase, VotePollUseCase,
+};
+
+/// 1 つのルームを対象に操作する UseCase
+pub struct RoomUseCases {
+    /// 対象のルームの ID
+    pub room_id: RoomId,
+    /// 対象のルームに送信するメッセージのポリシー
+    pub message_policy: MessagePolicy,
+    /// ConnectParticipantUseCase（参加者接続のユースケース）
+    pub connect_participant: Arc<ConnectParticipantUseCase>,
+    /// DisconnectParticipantUseCase（参加者切断のユースケース）
+    pub disconnect_participant: Arc<DisconnectParticipantUseCase>,
+    /// SendMessageUseCase（メッセージ送信のユースケース）
+    pub send_message: Arc<SendMessageUseCase>,
+    /// BroadcastTypingUseCase（入力中の通知のユースケース、無効な場合は `None`）
+    pub broadcast_typing: Option<Arc<BroadcastTypingUseCase>>,
+    /// GetRoomStateUseCase（ルーム状態取得のユースケース）
+    pub get_room_state: Arc<GetRoomStateUseCase>,
+    /// VotePollUseCase（投票への投票のユースケース、無効な場合は `None`）
+    pub vote_poll: Option<Arc<VotePollUseCase>>,
+    /// SetActivityUseCase（アクティビティ設定のユースケース、無効な場合は `None`）
+    pub set_activity: Option<Arc<SetActivityUseCase>>,
+    /// RenameParticipantUseCase（表示名設定のユースケース、無効な場合は `None`）
+    pub rename_participant: Option<Arc<RenameParticipantUseCase>>,
+    /// RespondToIncidentUseCase（凍結と全参加者の切断のユースケース、無効な場合は `None`）
+    pub respond_to_incident: Option<Arc<RespondToIncidentUseCase>>,
+}
+
+impl RoomUseCases {
+    /// ルームの Repository と MessagePusher から UseCase を作成
+    ///
+    /// メッセージ送信のシーケンサータスクを起動するため、Tokio ランタイム上で呼び出す必要がある。
+    /// `rate_limiter` を渡した場合、メッセージ送信のレートをクライアントごとに制限する。
+    /// `filters` を渡した場合、送信するメッセージにメッセージフィルター（そのうち
+    /// `message_policy` が選んだもの）を適用する。
+    pub fn new(
+        room_id: RoomId,
+        message_policy: MessagePolicy,
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+        rate_limiter: Option<Arc<RateLimiter>>,
+        filters: Option<Arc<MessageFilterChain>>,
+    ) -> Self {
+        let send_message = SendMessageUseCase::new(repository.clone(), message_pusher.clone())
+            .with_max_message_length(message_policy.max_length);
+        let send_message = match rate_limiter {
+            Some(rate_limiter) => send_message.with_rate_limiter(rate_limiter),
+            None => send_message,
+        };
+        let filters = match (filters, &message_policy.filters) {
+            (Some(filters), Some(_)) => Some(Arc::new(filters.for_policy(&message_policy))),
+            (filters, _) => filters,
+        };
+        let send_message = match filters {
+            Some(filters) => send_message.with_filters(filters),
+            None => send_message,
+        };
+        Self {
+            room_id,
+            message_policy,
+            connect_participant: Arc::new(ConnectParticipantUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+            )),
+            disconnect_participant: Arc::new(DisconnectParticipantUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+            )),
+            broadcast_typing: Some(Arc::new(BroadcastTypingUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+                DEFAULT_TYPING_DEBOUNCE,
+            ))),
+            send_message: Arc::new(send_message),
+            vote_poll: Some(Arc::new(VotePollUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+            ))),
+            set_activity: Some(Arc::new(SetActivityUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+            ))),
+            rename_participant: Some(Arc::new(RenameParticipantUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+            ))),
+            respond_to_incident: Some(Arc::new(RespondToIncidentUseCase::new(
+                repository.clone(),
+                message_pusher,
+            ))),
+            get_room_state: Arc::new(GetRoomStateUseCase::new(repository)),
+        }
+    }
+}
+
+/// ルーム参加エラー
+#[derive(Debug, PartialEq)]
+pub enum JoinRoomError {
+    /// ルームが見つからない
+    RoomNotFound,
+    /// クライアントが参加できるルーム数の上限に達している
+    TooManyRooms {
+        /// クライアントごとのルーム数の上限
+        limit: usize,
+    },
+    /// Repository エラー
+    RepositoryError,
+}
+
+impl From<MembershipError> for JoinRoomError {
+    fn from(e: MembershipError) -> Self {
+        match e {
+            MembershipError::TooManyRooms { limit } => JoinRoomError::TooManyRooms { limit },
+        }
+    }
+}
+
+/// ルームごとの MessagePusher を作成する関数（引数はルームの ID）
+type NewMessagePusher = Box<dyn Fn(&RoomId) -> Arc<dyn MessagePusher> + Send + Sync>;
+
+/// ルーム参加のユースケース
+pub struct JoinRoomUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// ルームごとの MessagePusher を作成する関数
+    new_message_pusher: NewMessagePusher,
+    /// 参加したことのあるルームの UseCase
+    rooms: Mutex<HashMap<RoomId, Arc<RoomUseCases>>>,
+    /// 参加したことのあるルームの MessagePusher（ルームを横断する UseCase が通知に使う）
+    message_pushers: Mutex<HashMap<RoomId, Arc<dyn MessagePusher>>>,
+    /// クライアントごとの参加中のルーム
+    memberships: Mutex<RoomMemberships>,
+    /// 作成するルームのメッセージ送信のレート制限（制限しない場合は `None`）
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// 作成するルームのメッセージフィルター（適用しない場合は `None`）
+    filters: Option<Arc<MessageFilterChain>>,
+}
+
+impl JoinRoomUseCase {
+    /// 新しい JoinRoomUseCase を作成
+    ///
+    /// # Arguments
+    ///
+    /// * `repository` - 既定のルームの Repository（他のルームは `for_room` で取得する）
+    /// * `max_rooms_per_client` - 1 つのクライアントが同時に参加できるルーム数の上限
+    /// * `new_message_pusher` - ルームの ID からそのルームの MessagePusher を作成する関数
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        max_rooms_per_client: usize,
+        new_message_pusher: impl Fn(&RoomId) -> Arc<dyn MessagePusher> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            repository,
+            new_message_pusher: Box::new(new_message_pusher),
+            rooms: Mutex::new(HashMap::new()),
+            message_pushers: Mutex::new(HashMap::new()),
+            memberships: Mutex::new(RoomMemberships::new(max_rooms_per_client)),
+            rate_limiter: None,
+            filters: None,
+        }
+    }
+
+    /// 作成するルームのメッセージ送信のレートを RateLimiter で制限する
+    ///
+    /// 既定のルームと同じ RateLimiter を渡すと、クライアントの送信レートを全てのルームで共有する。
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// 作成するルームで送信するメッセージにメッセージフィルターを適用する
+    pub fn with_filters(mut self, filters: Arc<MessageFilterChain>) -> Self {
+        self.filters = Some(filters);
+        self
+    }
+
+    /// 作成済みのルームの UseCase を登録（既定のルームなど）
+    pub async fn register(&self, room: Arc<RoomUseCases>) {
+        let mut rooms = self.rooms.lock().await;
+        rooms.insert(room.room_id.clone(), room);
+    }
+
+    /// ルームに参加
+    ///
+    /// # Arguments
+    ///
+    /// * `room_id` - 参加するルームの ID
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Arc<RoomUseCases>)` - ルームを対象に操作する UseCase
+    /// * `Err(JoinRoomError)` - 参加失敗
+    pub async fn execute(&self, room_id: &str) -> Result<Arc<RoomUseCases>, JoinRoomError> {
+        let room_id = RoomId::new(room_id.to_string()).map_err(|_| JoinRoomError::RoomNotFound)?;
+
+        // 削除されたルームの UseCase は破棄する
+        let repository = match self.repository.for_room(&room_id).await {
+            Ok(repository) => repository,
+            Err(RepositoryError::RoomNotFound) => {
+                self.rooms.lock().await.remove(&room_id);
+                self.message_pushers.lock().await.remove(&room_id);
+                return Err(JoinRoomError::RoomNotFound);
+            }
+            Err(_) => return Err(JoinRoomError::RepositoryError),
+        };
+
+        let mut rooms = self.rooms.lock().await;
+        if let Some(room) = rooms.get(&room_id) {
+            return Ok(room.clone());
+        }
+        let message_policy = repository
+            .get_room()
+            .await
+            .map_err(|_| JoinRoomError::RepositoryError)?
+            .message_policy;
+        let message_pusher = (self.new_message_pusher)(&room_id);
+        self.message_pushers
+            .lock()
+            .await
+            .insert(room_id.clone(), message_pusher.clone());
+        let room = Arc::new(RoomUseCases::new(
+            room_id.clone(),
+            message_policy,
+            repository,
+            message_pusher,
+            self.rate_limiter.clone(),
+            self.filters.clone(),
+        ));
+        rooms.insert(room_id, room.clone());
+        Ok(room)
+    }
+
+    /// 参加したことのある作成済みのルームの MessagePusher を取得
+    ///
+    /// 既定のルームと、起動してから誰も参加していないルーム（接続が無い）は `None`。
+    pub async fn message_pusher(&self, room_id: &RoomId) -> Option<Arc<dyn MessagePusher>> {
+        self.message_pushers.lock().await.get(room_id).cloned()
+    }
+
+    /// クライアントの接続がルームに参加したことを記録
+    ///
+    /// 同じルームへの別の接続（マルチプレックスや引き継ぎ中の接続）はルーム数に数えない。
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - 参加を記録した（切断時に `leave` を呼び出す）
+    /// * `Err(JoinRoomError::TooManyRooms)` - 参加中のルーム数が上限に達している
+    pub async fn enter(&self, client_id: &ClientId, room_id: &RoomId) -> Result<(), JoinRoomError> {
+        let mut memberships = self.memberships.lock().await;
+        memberships.join(client_id, room_id)?;
+        Ok(())
+    }
+
+    /// クライアントの接続がルームから退出したことを記録
+    pub async fn leave(&self, client_id: &ClientId, room_id: &RoomId) {
+        self.memberships.lock().await.leave(client_id, room_id);
+    }
+
+    /// クライアントがルームに参加中（接続がある）かどうか
+    pub async fn is_member(&self, client_id: &ClientId, room_id: &RoomId) -> bool {
+        self.memberships.lock().await.contains(client_id, room_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{ClientId, MessageContent, Room, RoomIdFactory, Timestamp},
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_broadcasts_are_scoped_per_room() {
+        // テスト項目: 参加したルームの参加者にだけメッセージがブロードキャストされる
+        // given (前提条件):
+        let lobby = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository = Arc::new(InMemoryRoomRepository::new(lobby));
+        let other = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        repository.create_room(other.clone()).await.unwrap();
+        let usecase = JoinRoomUseCase::new(repository.clone(), 10, |_| {
+            Arc::new(WebSocketMessagePusher::new(Default::default())) as Arc<dyn MessagePusher>
+        });
+
+        let room = usecase.execute(other.id.as_str()).await.unwrap();
+        let again = usecase.execute(other.id.as_str()).await.unwrap();
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let (alice_tx, _alice_rx) = WebSocketMessagePusher::channel();
+        let (bob_tx, mut bob_rx) = WebSocketMessagePusher::channel();
+        room.connect_participant
+            .execute(alice.clone(), alice_tx)
+            .await
+            .unwrap();
+        room.connect_participant.execute(bob, bob_tx).await.unwrap();
+
+        // when (操作):
+        let targets = room
+            .send_message
+            .execute(
+                alice,
+                MessageContent::new("hello".to_string()).unwrap(),
+                |seq| format!("message {}", seq),
+            )
+            .await
+            .unwrap();
+        let missing = usecase
+            .execute(RoomIdFactory::generate().unwrap().as_str())
+            .await;
+
+        // then (期待する結果):
+        assert!(Arc::ptr_eq(&room, &again));
+        assert_eq!(targets.len(), 1);
+        assert_eq!(bob_rx.recv().await.unwrap().as_ref(), "message 1");
+        assert_eq!(repository.count_connected_clients().await, 0);
+        assert!(repository.get_room().await.unwrap().messages.is_empty());
+        assert_eq!(missing.err(), Some(JoinRoomError::RoomNotFound));
+    }
+}
@@ -0,0 +1,180 @@
+//! UseCase: 参加者のキックと BAN の処理
+//!
+//! 管理者の操作で接続中のクライアントを全てのルームから退出させ（キック）、必要に応じて
+//! 以降の接続を拒否する対象として `BanList` に加えます（BAN）。
+//!
+//! ## 設計ノート
+//!
+//! このユースケースはクライアントが接続しているルームを調べるだけで、接続は閉じません。
+//! 呼び出し元（UI 層）が返したルームのセッションを閉じ、参加者は通常の切断処理でルームから
+//! 退出します。キックしただけのクライアントは再び接続できます。
+//! BAN は接続していないクライアントにも行え、BAN の解除はサーバーの再起動まで行えません
+//! （`InMemoryBanList` の場合）。
+
+use std::sync::Arc;
+
+use crate::domain::{BanList, ClientId, RepositoryError, RoomId, RoomRepository};
+
+/// 参加者のキックのエラー
+#[derive(Debug)]
+pub enum KickParticipantError {
+    /// クライアントがどのルームにも接続していない
+    NotConnected,
+    /// Repository エラー
+    RepositoryError(RepositoryError),
+}
+
+/// 参加者のキックと BAN のユースケース
+pub struct KickParticipantUseCase {
+    /// Repository（データアクセス層の抽象化、全てのルームを対象に調べる）
+    repository: Arc<dyn RoomRepository>,
+    /// 接続を拒否するクライアントの一覧
+    ban_list: Arc<dyn BanList>,
+}
+
+impl KickParticipantUseCase {
+    /// 新しい KickParticipantUseCase を作成
+    pub fn new(repository: Arc<dyn RoomRepository>, ban_list: Arc<dyn BanList>) -> Self {
+        Self {
+            repository,
+            ban_list,
+        }
+    }
+
+    /// クライアントをキック
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - キックするクライアントの ID（Domain Model）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<RoomId>)` - クライアントが接続しているルーム（呼び出し元がセッションを閉じる）
+    /// * `Err(KickParticipantError)` - キック失敗
+    pub async fn kick(&self, client_id: &ClientId) -> Result<Vec<RoomId>, KickParticipantError> {
+        let rooms = self
+            .connected_rooms(client_id)
+            .await
+            .map_err(KickParticipantError::RepositoryError)?;
+        if rooms.is_empty() {
+            return Err(KickParticipantError::NotConnected);
+        }
+        Ok(rooms)
+    }
+
+    /// クライアントを BAN
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - BAN するクライアントの ID（Domain Model）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<RoomId>)` - クライアントが接続しているルーム（接続していなければ空）
+    /// * `Err(RepositoryError)` - BAN 失敗
+    pub async fn ban(&self, client_id: &ClientId) -> Result<Vec<RoomId>, RepositoryError> {
+        if !self.ban_list.ban(client_id.clone()).await? {
+            tracing::debug!("Client '{}' is already banned", client_id);
+        }
+        self.connected_rooms(client_id).await
+    }
+
+    /// クライアントが BAN されているか
+    pub async fn is_banned(&self, client_id: &ClientId) -> Result<bool, RepositoryError> {
+        self.ban_list.is_banned(client_id).await
+    }
+
+    /// クライアントが接続しているルームを取得（既定のルームが先頭）
+    async fn connected_rooms(&self, client_id: &ClientId) -> Result<Vec<RoomId>, RepositoryError> {
+        connected_rooms(self.repository.as_ref(), client_id).await
+    }
+}
+
+/// クライアントが接続しているルームを全てのルームから探す（既定のルームが先頭）
+pub(super) async fn connected_rooms(
+    repository: &dyn RoomRepository,
+    client_id: &ClientId,
+) -> Result<Vec<RoomId>, RepositoryError> {
+    let mut rooms = Vec::new();
+    for room_id in repository.get_room_ids().await {
+        let room = match repository.for_room(&room_id).await {
+            Ok(room) => room,
+            // 調べている間に削除されたルーム
+            Err(RepositoryError::RoomNotFound) => continue,
+            Err(e) => return Err(e),
+        };
+        if room
+            .get_all_connected_client_ids()
+            .await
+            .contains(client_id)
+        {
+            rooms.push(room_id);
+        }
+    }
+    Ok(rooms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{Room, RoomIdFactory, Timestamp},
+        infrastructure::repository::{InMemoryBanList, InMemoryRoomRepository},
+    };
+
+    fn client_id(name: &str) -> ClientId {
+        ClientId::new(name.to_string()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_kick_finds_rooms_of_connected_client() {
+        // テスト項目: キックは接続している全てのルームを返し、接続していなければ NotConnected になる
+        // given (前提条件):
+        let default_room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository: Arc<dyn RoomRepository> =
+            Arc::new(InMemoryRoomRepository::new(default_room.clone()));
+        let other = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        repository.create_room(other.clone()).await.unwrap();
+        repository
+            .add_participant(client_id("mallory"), Timestamp::new(1500))
+            .await
+            .unwrap();
+        repository
+            .for_room(&other.id)
+            .await
+            .unwrap()
+            .add_participant(client_id("mallory"), Timestamp::new(1500))
+            .await
+            .unwrap();
+        let usecase = KickParticipantUseCase::new(repository, Arc::new(InMemoryBanList::new()));
+
+        // when (操作):
+        let rooms = usecase.kick(&client_id("mallory")).await.unwrap();
+        let absent = usecase.kick(&client_id("alice")).await;
+
+        // then (期待する結果):
+        assert_eq!(rooms, vec![default_room.id, other.id]);
+        assert!(matches!(absent, Err(KickParticipantError::NotConnected)));
+        // キックしただけでは BAN されない
+        assert!(!usecase.is_banned(&client_id("mallory")).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_ban_records_client_even_if_not_connected() {
+        // テスト項目: 接続していないクライアントも BAN でき、BAN したクライアントだけが BAN 済みになる
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository: Arc<dyn RoomRepository> = Arc::new(InMemoryRoomRepository::new(room));
+        let ban_list = Arc::new(InMemoryBanList::new());
+        let usecase = KickParticipantUseCase::new(repository, ban_list.clone());
+
+        // when (操作):
+        let rooms = usecase.ban(&client_id("mallory")).await.unwrap();
+
+        // then (期待する結果):
+        assert!(rooms.is_empty());
+        assert!(usecase.is_banned(&client_id("mallory")).await.unwrap());
+        assert!(ban_list.is_banned(&client_id("mallory")).await.unwrap());
+        assert!(!usecase.is_banned(&client_id("alice")).await.unwrap());
+    }
+}
@@ -0,0 +1,373 @@
+//! UseCase: ブレイクアウトルームの管理処理
+//!
+//! 親のルームから一時的な子のルーム（ブレイクアウト）を作成し、一定時間使われなかった
+//! ブレイクアウトをアーカイブします。
+//!
+//! ## 設計ノート
+//!
+//! ブレイクアウトは親のルームのクラスと容量、メッセージのポリシーを引き継いで `CreateRoomUseCase` で作成するため、
+//! ルーム数の上限に数えます。参加を希望した親のルームの参加者（メンバー）を指定した場合、
+//! ブレイクアウトにはメンバーだけが参加できます。ブレイクアウトから更にブレイクアウトは作成できません。
+//!
+//! 参加者がいない状態で最後の活動（メッセージ・入室・作成）から `idle_timeout` が経過した
+//! ブレイクアウトはアーカイブし、ルームを削除します（履歴も失われる）。
+//! ブレイクアウトの記録はメモリ上に保持し、アーカイブ後も親のルームの一覧に残ります。
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use tokio::sync::Mutex;
+
+use crate::domain::{Breakout, ClientId, RepositoryError, RoomId, RoomRepository, Timestamp};
+
+use super::{CreateRoomError, CreateRoomUseCase, NewRoom};
+
+/// ブレイクアウトの名前の最大の文字数
+pub const MAX_BREAKOUT_NAME_CHARS: usize = 64;
+
+/// 使われていないブレイクアウトをアーカイブするまでの時間の既定値
+pub const DEFAULT_BREAKOUT_IDLE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// ブレイクアウトの管理のエラー
+#[derive(Debug, PartialEq)]
+pub enum ManageBreakoutsError {
+    /// 親のルームが見つからない
+    RoomNotFound,
+    /// 親のルームがブレイクアウト
+    NestedBreakout,
+    /// 名前が空または長すぎる
+    InvalidName,
+    /// メンバーが親のルームに参加していない
+    NotInParent(ClientId),
+    /// ルーム数が上限に達している
+    TooManyRooms,
+    /// Repository エラー
+    RepositoryError,
+}
+
+impl From<RepositoryError> for ManageBreakoutsError {
+    fn from(e: RepositoryError) -> Self {
+        match e {
+            RepositoryError::RoomNotFound => ManageBreakoutsError::RoomNotFound,
+            _ => ManageBreakoutsError::RepositoryError,
+        }
+    }
+}
+
+/// ブレイクアウトルームの管理のユースケース
+pub struct ManageBreakoutsUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// ブレイクアウトのルームを作成するユースケース
+    create_room: CreateRoomUseCase,
+    /// ブレイクアウト（キーはブレイクアウトのルームの ID）
+    breakouts: Mutex<HashMap<RoomId, Breakout>>,
+    /// 使われていないブレイクアウトをアーカイブするまでの時間
+    idle_timeout: Duration,
+}
+
+impl ManageBreakoutsUseCase {
+    /// 新しい ManageBreakoutsUseCase を作成
+    ///
+    /// # Arguments
+    ///
+    /// * `repository` - 既定のルームの Repository
+    /// * `create_room` - ブレイクアウトのルームを作成するユースケース
+    pub fn new(repository: Arc<dyn RoomRepository>, create_room: CreateRoomUseCase) -> Self {
+        Self {
+            repository,
+            create_room,
+            breakouts: Mutex::new(HashMap::new()),
+            idle_timeout: DEFAULT_BREAKOUT_IDLE_TIMEOUT,
+        }
+    }
+
+    /// 使われていないブレイクアウトをアーカイブするまでの時間を設定
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// 使われていないブレイクアウトをアーカイブするまでの時間
+    pub fn idle_timeout(&self) -> Duration {
+        self.idle_timeout
+    }
+
+    /// ブレイクアウトを作成
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_id` - 親のルームの ID
+    /// * `name` - 親のルームに表示する名前
+    /// * `members` - 参加を希望した親のルームの参加者（空の場合は誰でも参加できる）
+    /// * `now` - 作成日時
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Breakout)` - 作成したブレイクアウト
+    /// * `Err(ManageBreakoutsError)` - 作成失敗
+    pub async fn create(
+        &self,
+        parent_id: &str,
+        name: &str,
+        members: Vec<ClientId>,
+        now: Timestamp,
+    ) -> Result<Breakout, ManageBreakoutsError> {
+        let parent_id =
+            RoomId::new(parent_id.to_string()).map_err(|_| ManageBreakoutsError::RoomNotFound)?;
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_BREAKOUT_NAME_CHARS {
+            return Err(ManageBreakoutsError::InvalidName);
+        }
+        if self.breakouts.lock().await.contains_key(&parent_id) {
+            return Err(ManageBreakoutsError::NestedBreakout);
+        }
+        let parent = self.repository.get_room_by_id(&parent_id).await?;
+        if let Some(outsider) = members
+            .iter()
+            .find(|member| !parent.participants.iter().any(|p| &p.id == *member))
+        {
+            return Err(ManageBreakoutsError::NotInParent(outsider.clone()));
+        }
+
+        // 親のルームのクラスと容量を引き継ぐ
+        let room = self
+            .create_room
+            .execute(
+                now,
+                NewRoom {
+                    class: parent.class,
+                    participant_capacity: Some(parent.participant_capacity),
+                    message_capacity: Some(parent.message_capacity),
+                    slug: None,
+                    max_message_length: Some(parent.message_policy.max_length),
+                    message_filters: parent.message_policy.filters.clone(),
+                },
+            )
+            .await
+            .map_err(|e| match e {
+                CreateRoomError::TooManyRooms => ManageBreakoutsError::TooManyRooms,
+                _ => ManageBreakoutsError::RepositoryError,
+            })?;
+
+        let mut unique: Vec<ClientId> = Vec::new();
+        for member in members {
+            if !unique.contains(&member) {
+                unique.push(member);
+            }
+        }
+        let breakout = Breakout {
+            room_id: room.id,
+            parent_id,
+            name: name.to_string(),
+            members: unique,
+            created_at: now,
+            archived_at: None,
+        };
+        self.breakouts
+            .lock()
+            .await
+            .insert(breakout.room_id.clone(), breakout.clone());
+        Ok(breakout)
+    }
+
+    /// 親のルームのブレイクアウトを作成順に取得（アーカイブ済みを含む）
+    pub async fn list(&self, parent_id: &str) -> Result<Vec<Breakout>, ManageBreakoutsError> {
+        let parent_id =
+            RoomId::new(parent_id.to_string()).map_err(|_| ManageBreakoutsError::RoomNotFound)?;
+        let mut breakouts: Vec<Breakout> = self
+            .breakouts
+            .lock()
+            .await
+            .values()
+            .filter(|breakout| breakout.parent_id == parent_id)
+            .cloned()
+            .collect();
+        if breakouts.is_empty() {
+            // 親のルームが存在するか確認する
+            self.repository.get_room_by_id(&parent_id).await?;
+        }
+        breakouts.sort_by_key(|breakout| breakout.created_at);
+        Ok(breakouts)
+    }
+
+    /// クライアントがルームに参加できるか（ブレイクアウトでないルームには誰でも参加できる）
+    pub async fn admits(&self, room_id: &RoomId, client_id: Option<&ClientId>) -> bool {
+        match self.breakouts.lock().await.get(room_id) {
+            Some(breakout) => client_id.is_some_and(|client_id| breakout.admits(client_id)),
+            None => true,
+        }
+    }
+
+    /// 使われていないブレイクアウトをアーカイブ
+    ///
+    /// 参加者がいない状態で最後の活動から `idle_timeout` が経過したブレイクアウトのルームを
+    /// 削除します。既に削除されていたルームのブレイクアウトもアーカイブします。
+    ///
+    /// # Returns
+    ///
+    /// アーカイブしたブレイクアウト
+    pub async fn archive_idle(&self, now: Timestamp) -> Vec<Breakout> {
+        let idle_timeout = self.idle_timeout.as_millis() as i64;
+        let mut breakouts = self.breakouts.lock().await;
+        let mut archived = Vec::new();
+        for breakout in breakouts
+            .values_mut()
+            .filter(|breakout| breakout.archived_at.is_none())
+        {
+            let idle = match self.repository.get_room_by_id(&breakout.room_id).await {
+                Ok(room) => {
+                    room.participants.is_empty()
+                        && now.value() - room.last_activity_at().value() >= idle_timeout
+                }
+                Err(RepositoryError::RoomNotFound) => true,
+                Err(e) => {
+                    tracing::warn!("Failed to check breakout {}: {}", breakout.room_id, e);
+                    false
+                }
+            };
+            if !idle {
+                continue;
+            }
+            match self.repository.delete_room(&breakout.room_id).await {
+                Ok(()) | Err(RepositoryError::RoomNotFound) => {}
+                Err(e) => {
+                    tracing::warn!("Failed to delete breakout {}: {}", breakout.room_id, e);
+                    continue;
+                }
+            }
+            breakout.archived_at = Some(now);
+            tracing::info!(
+                "Breakout {} of room {} archived",
+                breakout.room_id,
+                breakout.parent_id
+            );
+            archived.push(breakout.clone());
+        }
+        archived
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{Room, RoomIdFactory},
+        infrastructure::repository::InMemoryRoomRepository,
+    };
+
+    fn client_id(name: &str) -> ClientId {
+        ClientId::new(name.to_string()).unwrap()
+    }
+
+    async fn usecase_with_parent() -> (ManageBreakoutsUseCase, Arc<InMemoryRoomRepository>, Room) {
+        let parent = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository = Arc::new(InMemoryRoomRepository::new(parent.clone()));
+        repository
+            .add_participant(client_id("alice"), Timestamp::new(1000))
+            .await
+            .unwrap();
+        let usecase = ManageBreakoutsUseCase::new(
+            repository.clone(),
+            CreateRoomUseCase::new(repository.clone(), 10),
+        )
+        .with_idle_timeout(Duration::from_secs(60));
+        (usecase, repository, parent)
+    }
+
+    #[tokio::test]
+    async fn test_create_admits_only_opted_in_members() {
+        // テスト項目: 親のルームの参加者をメンバーにしてブレイクアウトを作成でき、メンバー以外は参加できない
+        // given (前提条件):
+        let (usecase, repository, parent) = usecase_with_parent().await;
+
+        // when (操作):
+        let breakout = usecase
+            .create(
+                parent.id.as_str(),
+                "Design review",
+                vec![client_id("alice")],
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap();
+        let outsider = usecase
+            .create(
+                parent.id.as_str(),
+                "Other",
+                vec![client_id("mallory")],
+                Timestamp::new(2000),
+            )
+            .await;
+        let nested = usecase
+            .create(
+                breakout.room_id.as_str(),
+                "Nested",
+                Vec::new(),
+                Timestamp::new(2000),
+            )
+            .await;
+        let unnamed = usecase
+            .create(parent.id.as_str(), "  ", Vec::new(), Timestamp::new(2000))
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(breakout.parent_id, parent.id);
+        assert!(repository.get_room_by_id(&breakout.room_id).await.is_ok());
+        assert!(
+            usecase
+                .admits(&breakout.room_id, Some(&client_id("alice")))
+                .await
+        );
+        assert!(
+            !usecase
+                .admits(&breakout.room_id, Some(&client_id("bob")))
+                .await
+        );
+        assert!(usecase.admits(&parent.id, None).await);
+        assert_eq!(
+            outsider.err(),
+            Some(ManageBreakoutsError::NotInParent(client_id("mallory")))
+        );
+        assert_eq!(nested.err(), Some(ManageBreakoutsError::NestedBreakout));
+        assert_eq!(unnamed.err(), Some(ManageBreakoutsError::InvalidName));
+    }
+
+    #[tokio::test]
+    async fn test_archive_idle_deletes_unused_breakouts() {
+        // テスト項目: 参加者がいない状態でアイドル時間が経過したブレイクアウトだけがアーカイブされ、一覧には残る
+        // given (前提条件):
+        let (usecase, repository, parent) = usecase_with_parent().await;
+        let idle = usecase
+            .create(parent.id.as_str(), "Idle", Vec::new(), Timestamp::new(2000))
+            .await
+            .unwrap();
+        let busy = usecase
+            .create(parent.id.as_str(), "Busy", Vec::new(), Timestamp::new(3000))
+            .await
+            .unwrap();
+        repository
+            .for_room(&busy.room_id)
+            .await
+            .unwrap()
+            .add_participant(client_id("alice"), Timestamp::new(3000))
+            .await
+            .unwrap();
+
+        // when (操作):
+        let early = usecase.archive_idle(Timestamp::new(30_000)).await;
+        let archived = usecase.archive_idle(Timestamp::new(62_000)).await;
+
+        // then (期待する結果):
+        assert!(early.is_empty());
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].room_id, idle.room_id);
+        assert!(matches!(
+            repository.get_room_by_id(&idle.room_id).await,
+            Err(RepositoryError::RoomNotFound)
+        ));
+        let listed = usecase.list(parent.id.as_str()).await.unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].archived_at, Some(Timestamp::new(62_000)));
+        assert_eq!(listed[1].archived_at, None);
+    }
+}
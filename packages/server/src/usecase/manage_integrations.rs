@@ -0,0 +1,593 @@
+//! UseCase: ルームの連携設定の管理処理
+//!
+//! ルームごとの送信 Webhook・受信 Webhook のトークン・ブリッジ・ウェルカムメッセージ・参加の承認・定期的なお知らせの設定を作成・取得・更新・削除します。
+//! 設定ファイルの連携（サーバー全体で 1 つ）とは別に、ルームごとに複数の連携を設定できます。
+//!
+//! ## 設計ノート
+//!
+//! 受信 Webhook のトークンはサーバー全体で一意で、トークンから投稿先のルームを決めます。
+//! トークンを指定しなかった場合はサーバーが生成します。
+//!
+//! 定期的なお知らせはスケジュール（cron 式）を記録するだけで、投稿は UI 層のスケジューラーが
+//! 毎分 [`ManageIntegrationsUseCase::recurring_announcements`] を取得して行います。
+
+use std::sync::Arc;
+
+use crate::domain::{
+    CronSchedule, Integration, IntegrationKind, IntegrationRepository, MessageContent,
+    RepositoryError, RoomId, RoomRepository, Timestamp, WebhookTokenFactory,
+};
+
+/// 受信 Webhook のトークンの最小の長さ（推測されにくくするため）
+pub const MIN_WEBHOOK_TOKEN_LEN: usize = 16;
+
+/// 受信 Webhook のトークンの最大の長さ
+pub const MAX_WEBHOOK_TOKEN_LEN: usize = 128;
+
+/// ルームの定期的なお知らせ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurringAnnouncement {
+    /// 投稿先のルーム
+    pub room_id: RoomId,
+    /// 投稿するスケジュール（JST）
+    pub schedule: CronSchedule,
+    /// 投稿する内容
+    pub text: MessageContent,
+}
+
+/// 連携設定の管理のエラー
+#[derive(Debug)]
+pub enum ManageIntegrationsError {
+    /// ルームが見つからない
+    RoomNotFound,
+    /// ルームに該当する連携設定が無い
+    IntegrationNotFound,
+    /// 送信 Webhook の URL が http(s) の URL ではない
+    InvalidUrl,
+    /// 受信 Webhook のトークンの長さや文字が不正
+    InvalidToken,
+    /// 受信 Webhook のトークンが他の連携設定で使われている
+    TokenInUse,
+    /// ブリッジの接続先が空
+    InvalidTarget,
+    /// Repository エラー
+    RepositoryError(RepositoryError),
+}
+
+impl From<RepositoryError> for ManageIntegrationsError {
+    fn from(e: RepositoryError) -> Self {
+        match e {
+            RepositoryError::RoomNotFound => ManageIntegrationsError::RoomNotFound,
+            e => ManageIntegrationsError::RepositoryError(e),
+        }
+    }
+}
+
+/// ルームの連携設定の管理のユースケース
+pub struct ManageIntegrationsUseCase {
+    /// Repository（データアクセス層の抽象化、ルームの存在確認に使用）
+    repository: Arc<dyn RoomRepository>,
+    /// 連携設定の Repository
+    integrations: Arc<dyn IntegrationRepository>,
+}
+
+impl ManageIntegrationsUseCase {
+    /// 新しい ManageIntegrationsUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        integrations: Arc<dyn IntegrationRepository>,
+    ) -> Self {
+        Self {
+            repository,
+            integrations,
+        }
+    }
+
+    /// ルームの連携設定を作成順に取得
+    pub async fn list(&self, room_id: &str) -> Result<Vec<Integration>, ManageIntegrationsError> {
+        let room_id = self.existing_room(room_id).await?;
+        Ok(self.integrations.list(&room_id).await?)
+    }
+
+    /// ルームの連携設定を 1 件取得
+    pub async fn get(
+        &self,
+        room_id: &str,
+        id: u64,
+    ) -> Result<Integration, ManageIntegrationsError> {
+        self.list(room_id)
+            .await?
+            .into_iter()
+            .find(|integration| integration.id == id)
+            .ok_or(ManageIntegrationsError::IntegrationNotFound)
+    }
+
+    /// 連携設定を作成
+    ///
+    /// # Arguments
+    ///
+    /// * `room_id` - 連携設定を作成するルームの ID
+    /// * `kind` - 連携設定の内容（受信 Webhook のトークンが空の場合はトークンを生成）
+    /// * `created_at` - 作成日時
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Integration)` - 作成した連携設定
+    /// * `Err(ManageIntegrationsError)` - 作成失敗
+    pub async fn create(
+        &self,
+        room_id: &str,
+        kind: IntegrationKind,
+        created_at: Timestamp,
+    ) -> Result<Integration, ManageIntegrationsError> {
+        let room_id = self.existing_room(room_id).await?;
+        let kind = self.validate(kind, None).await?;
+        Ok(self.integrations.create(room_id, kind, created_at).await?)
+    }
+
+    /// 連携設定の内容を置き換える
+    ///
+    /// 受信 Webhook のトークンが空の場合は新しいトークンを生成します。
+    pub async fn update(
+        &self,
+        room_id: &str,
+        id: u64,
+        kind: IntegrationKind,
+    ) -> Result<Integration, ManageIntegrationsError> {
+        let room_id = self.existing_room(room_id).await?;
+        let kind = self.validate(kind, Some(id)).await?;
+        self.integrations
+            .update(&room_id, id, kind)
+            .await?
+            .ok_or(ManageIntegrationsError::IntegrationNotFound)
+    }
+
+    /// 連携設定を削除
+    pub async fn delete(&self, room_id: &str, id: u64) -> Result<(), ManageIntegrationsError> {
+        let room_id = self.existing_room(room_id).await?;
+        if self.integrations.delete(&room_id, id).await? {
+            Ok(())
+        } else {
+            Err(ManageIntegrationsError::IntegrationNotFound)
+        }
+    }
+
+    /// 受信 Webhook のトークンから投稿先のルームを取得
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(RoomId))` - トークンが一致する受信 Webhook のルーム
+    /// * `Ok(None)` - 一致する受信 Webhook が無い
+    pub async fn resolve_incoming_webhook(
+        &self,
+        token: &str,
+    ) -> Result<Option<RoomId>, RepositoryError> {
+        Ok(self
+            .integrations
+            .find_incoming_webhook(token)
+            .await?
+            .map(|integration| integration.room_id))
+    }
+
+    /// ルームの送信 Webhook の URL を取得（メッセージの配信時に使用）
+    pub async fn outgoing_webhook_urls(
+        &self,
+        room_id: &RoomId,
+    ) -> Result<Vec<String>, RepositoryError> {
+        Ok(self
+            .integrations
+            .list(room_id)
+            .await?
+            .into_iter()
+            .filter_map(|integration| match integration.kind {
+                IntegrationKind::OutgoingWebhook { url } => Some(url),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// ルームのウェルカムメッセージを作成順に取得（参加者の参加時に使用）
+    pub async fn welcome_messages(
+        &self,
+        room_id: &RoomId,
+    ) -> Result<Vec<MessageContent>, RepositoryError> {
+        Ok(self
+            .integrations
+            .list(room_id)
+            .await?
+            .into_iter()
+            .filter_map(|integration| match integration.kind {
+                IntegrationKind::WelcomeMessage { text } => Some(text),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// 全てのルームの定期的なお知らせを作成順に取得（スケジューラーが毎分使用）
+    pub async fn recurring_announcements(
+        &self,
+    ) -> Result<Vec<RecurringAnnouncement>, RepositoryError> {
+        Ok(self
+            .integrations
+            .list_all()
+            .await?
+            .into_iter()
+            .filter_map(|integration| match integration.kind {
+                IntegrationKind::RecurringAnnouncement { schedule, text } => {
+                    Some(RecurringAnnouncement {
+                        room_id: integration.room_id,
+                        schedule,
+                        text,
+                    })
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// ルームへの参加にモデレーターの承認が必要か（参加の承認の連携設定がある場合）
+    pub async fn requires_approval(&self, room_id: &RoomId) -> Result<bool, RepositoryError> {
+        Ok(self
+            .integrations
+            .list(room_id)
+            .await?
+            .iter()
+            .any(|integration| integration.kind == IntegrationKind::JoinApproval))
+    }
+
+    /// ルームが存在することを確認
+    async fn existing_room(&self, room_id: &str) -> Result<RoomId, ManageIntegrationsError> {
+        let room_id =
+            RoomId::new(room_id.to_string()).map_err(|_| ManageIntegrationsError::RoomNotFound)?;
+        self.repository.for_room(&room_id).await?;
+        Ok(room_id)
+    }
+
+    /// 連携設定の内容を検証（`id` は更新する連携設定の ID）
+    async fn validate(
+        &self,
+        kind: IntegrationKind,
+        id: Option<u64>,
+    ) -> Result<IntegrationKind, ManageIntegrationsError> {
+        match kind {
+            IntegrationKind::OutgoingWebhook { url } => {
+                let rest = url
+                    .strip_prefix("https://")
+                    .or_else(|| url.strip_prefix("http://"))
+                    .ok_or(ManageIntegrationsError::InvalidUrl)?;
+                if rest.is_empty() || rest.starts_with('/') || url.contains(char::is_whitespace) {
+                    return Err(ManageIntegrationsError::InvalidUrl);
+                }
+                Ok(IntegrationKind::OutgoingWebhook { url })
+            }
+            IntegrationKind::IncomingWebhook { token } => {
+                if token.is_empty() {
+                    return Ok(IntegrationKind::IncomingWebhook {
+                        token: WebhookTokenFactory::generate(),
+                    });
+                }
+                if !(MIN_WEBHOOK_TOKEN_LEN..=MAX_WEBHOOK_TOKEN_LEN).contains(&token.len())
+                    || !token.chars().all(|c| c.is_ascii_graphic())
+                {
+                    return Err(ManageIntegrationsError::InvalidToken);
+                }
+                match self.integrations.find_incoming_webhook(&token).await? {
+                    Some(existing) if Some(existing.id) != id => {
+                        Err(ManageIntegrationsError::TokenInUse)
+                    }
+                    _ => Ok(IntegrationKind::IncomingWebhook { token }),
+                }
+            }
+            IntegrationKind::Bridge { bridge, target } => {
+                let target = target.trim().to_string();
+                if target.is_empty() {
+                    return Err(ManageIntegrationsError::InvalidTarget);
+                }
+                Ok(IntegrationKind::Bridge { bridge, target })
+            }
+            // 内容は MessageContent として検証済み
+            kind @ IntegrationKind::WelcomeMessage { .. } => Ok(kind),
+            // スケジュールは CronSchedule、内容は MessageContent として検証済み
+            kind @ IntegrationKind::RecurringAnnouncement { .. } => Ok(kind),
+            IntegrationKind::JoinApproval => Ok(IntegrationKind::JoinApproval),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{BridgeKind, Room, RoomIdFactory},
+        infrastructure::repository::{InMemoryIntegrationRepository, InMemoryRoomRepository},
+    };
+
+    fn setup() -> (ManageIntegrationsUseCase, RoomId) {
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let room_id = room.id.clone();
+        let usecase = ManageIntegrationsUseCase::new(
+            Arc::new(InMemoryRoomRepository::new(room)),
+            Arc::new(InMemoryIntegrationRepository::new()),
+        );
+        (usecase, room_id)
+    }
+
+    #[tokio::test]
+    async fn test_create_update_and_delete_integrations() {
+        // テスト項目: 連携設定を作成・更新・削除でき、送信 Webhook の URL と受信 Webhook のルームを引ける
+        // given (前提条件):
+        let (usecase, room_id) = setup();
+        let webhook = usecase
+            .create(
+                room_id.as_str(),
+                IntegrationKind::OutgoingWebhook {
+                    url: "https://example.com/hook".to_string(),
+                },
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap();
+        let incoming = usecase
+            .create(
+                room_id.as_str(),
+                IntegrationKind::IncomingWebhook {
+                    token: String::new(),
+                },
+                Timestamp::new(3000),
+            )
+            .await
+            .unwrap();
+
+        // when (操作):
+        let bridge = usecase
+            .update(
+                room_id.as_str(),
+                webhook.id,
+                IntegrationKind::Bridge {
+                    bridge: BridgeKind::Mqtt,
+                    target: " engawa/lobby ".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        usecase.delete(room_id.as_str(), incoming.id).await.unwrap();
+        let missing = usecase.delete(room_id.as_str(), incoming.id).await;
+
+        // then (期待する結果):
+        // トークンを指定しなければ生成される
+        let IntegrationKind::IncomingWebhook { token } = &incoming.kind else {
+            panic!("expected an incoming webhook: {:?}", incoming.kind);
+        };
+        assert_eq!(token.len(), 32);
+        assert_eq!(
+            bridge.kind,
+            IntegrationKind::Bridge {
+                bridge: BridgeKind::Mqtt,
+                target: "engawa/lobby".to_string(),
+            }
+        );
+        assert_eq!(bridge.created_at, Timestamp::new(2000));
+        assert_eq!(usecase.list(room_id.as_str()).await.unwrap(), vec![bridge]);
+        assert!(matches!(
+            missing,
+            Err(ManageIntegrationsError::IntegrationNotFound)
+        ));
+        assert_eq!(usecase.resolve_incoming_webhook(token).await.unwrap(), None);
+        assert!(
+            usecase
+                .outgoing_webhook_urls(&room_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_settings_are_rejected() {
+        // テスト項目: 不正な URL・トークン・接続先、存在しないルーム、使用中のトークンはエラーになる
+        // given (前提条件):
+        let (usecase, room_id) = setup();
+        let token = "0123456789abcdef".to_string();
+        let incoming = usecase
+            .create(
+                room_id.as_str(),
+                IntegrationKind::IncomingWebhook {
+                    token: token.clone(),
+                },
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap();
+        let create = |kind| usecase.create(room_id.as_str(), kind, Timestamp::new(3000));
+
+        // when (操作):
+        let invalid_url = create(IntegrationKind::OutgoingWebhook {
+            url: "ftp://example.com".to_string(),
+        })
+        .await;
+        let short_token = create(IntegrationKind::IncomingWebhook {
+            token: "short".to_string(),
+        })
+        .await;
+        let token_in_use = create(IntegrationKind::IncomingWebhook {
+            token: token.clone(),
+        })
+        .await;
+        let empty_target = create(IntegrationKind::Bridge {
+            bridge: BridgeKind::Discord,
+            target: "  ".to_string(),
+        })
+        .await;
+        let unknown_room = usecase
+            .list(RoomIdFactory::generate().unwrap().as_str())
+            .await;
+        // 同じ連携設定のトークンは更新で変えなくてもよい
+        let same_token = usecase
+            .update(
+                room_id.as_str(),
+                incoming.id,
+                IntegrationKind::IncomingWebhook {
+                    token: token.clone(),
+                },
+            )
+            .await;
+
+        // then (期待する結果):
+        assert!(matches!(
+            invalid_url,
+            Err(ManageIntegrationsError::InvalidUrl)
+        ));
+        assert!(matches!(
+            short_token,
+            Err(ManageIntegrationsError::InvalidToken)
+        ));
+        assert!(matches!(
+            token_in_use,
+            Err(ManageIntegrationsError::TokenInUse)
+        ));
+        assert!(matches!(
+            empty_target,
+            Err(ManageIntegrationsError::InvalidTarget)
+        ));
+        assert!(matches!(
+            unknown_room,
+            Err(ManageIntegrationsError::RoomNotFound)
+        ));
+        assert_eq!(same_token.unwrap(), incoming);
+        assert_eq!(
+            usecase.resolve_incoming_webhook(&token).await.unwrap(),
+            Some(room_id)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_welcome_messages_in_creation_order() {
+        // テスト項目: ルームのウェルカムメッセージだけが作成順に取得され、他のルームのものは含まれない
+        // given (前提条件):
+        let (usecase, room_id) = setup();
+        let welcome = |text: &str| IntegrationKind::WelcomeMessage {
+            text: MessageContent::new(text.to_string()).unwrap(),
+        };
+        for (kind, created_at) in [
+            (welcome("Welcome!"), 2000),
+            (
+                IntegrationKind::OutgoingWebhook {
+                    url: "https://example.com/hook".to_string(),
+                },
+                3000,
+            ),
+            (welcome("Please read the rules."), 4000),
+        ] {
+            usecase
+                .create(room_id.as_str(), kind, Timestamp::new(created_at))
+                .await
+                .unwrap();
+        }
+
+        // when (操作):
+        let messages = usecase.welcome_messages(&room_id).await.unwrap();
+        let elsewhere = usecase
+            .welcome_messages(&RoomIdFactory::generate().unwrap())
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        let texts: Vec<&str> = messages.iter().map(MessageContent::as_str).collect();
+        assert_eq!(texts, vec!["Welcome!", "Please read the rules."]);
+        assert!(elsewhere.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_requires_approval_while_join_approval_is_configured() {
+        // テスト項目: 参加の承認の連携設定がある間だけ、ルームへの参加に承認が必要になる
+        // given (前提条件):
+        let (usecase, room_id) = setup();
+        let before = usecase.requires_approval(&room_id).await.unwrap();
+        let approval = usecase
+            .create(
+                room_id.as_str(),
+                IntegrationKind::JoinApproval,
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap();
+
+        // when (操作):
+        let required = usecase.requires_approval(&room_id).await.unwrap();
+        usecase.delete(room_id.as_str(), approval.id).await.unwrap();
+        let after = usecase.requires_approval(&room_id).await.unwrap();
+
+        // then (期待する結果):
+        assert!(!before);
+        assert!(required);
+        assert!(!after);
+    }
+
+    #[tokio::test]
+    async fn test_recurring_announcements_of_all_rooms() {
+        // テスト項目: 全てのルームの定期的なお知らせだけがルームとスケジュール付きで作成順に取得される
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let other = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let (room_id, other_id) = (room.id.clone(), other.id.clone());
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        repository.create_room(other).await.unwrap();
+        let usecase = ManageIntegrationsUseCase::new(
+            repository,
+            Arc::new(InMemoryIntegrationRepository::new()),
+        );
+        let standup = CronSchedule::new("0 10 * * 1-5".to_string()).unwrap();
+        let announcement =
+            |schedule: &CronSchedule, text: &str| IntegrationKind::RecurringAnnouncement {
+                schedule: schedule.clone(),
+                text: MessageContent::new(text.to_string()).unwrap(),
+            };
+        usecase
+            .create(
+                room_id.as_str(),
+                announcement(&standup, "Standup in the huddle!"),
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap();
+        usecase
+            .create(
+                room_id.as_str(),
+                IntegrationKind::WelcomeMessage {
+                    text: MessageContent::new("Welcome!".to_string()).unwrap(),
+                },
+                Timestamp::new(3000),
+            )
+            .await
+            .unwrap();
+        usecase
+            .create(
+                other_id.as_str(),
+                announcement(&standup, "Ship it!"),
+                Timestamp::new(4000),
+            )
+            .await
+            .unwrap();
+
+        // when (操作):
+        let announcements = usecase.recurring_announcements().await.unwrap();
+
+        // then (期待する結果):
+        let rooms: Vec<(&RoomId, &str)> = announcements
+            .iter()
+            .map(|announcement| (&announcement.room_id, announcement.text.as_str()))
+            .collect();
+        assert_eq!(
+            rooms,
+            vec![
+                (&room_id, "Standup in the huddle!"),
+                (&other_id, "Ship it!")
+            ]
+        );
+        assert!(
+            announcements
+                .iter()
+                .all(|announcement| announcement.schedule == standup)
+        );
+    }
+}
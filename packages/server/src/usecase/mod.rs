@@ -3,18 +3,94 @@
 //! ビジネスロジックを実装するレイヤー。
 //! UI 層から呼び出され、Domain 層を操作します。
 
+pub mod broadcast_typing;
+pub mod check_health;
+pub mod compose_daily_digest;
 pub mod connect_participant;
+pub mod create_room;
 pub mod disconnect_participant;
+pub mod enforce_memory_limit;
+pub mod erase_client_data;
 pub mod error;
+pub mod export_messages;
+pub mod forward_message;
+pub mod get_message_history;
 pub mod get_room_detail;
+pub mod get_room_messages;
 pub mod get_room_state;
+pub mod get_room_stats;
 pub mod get_rooms;
+pub mod join_room;
+pub mod kick_participant;
+pub mod manage_breakouts;
+pub mod manage_integrations;
+pub mod moderate_messages;
+pub mod rate_limiter;
+pub mod rename_participant;
+pub mod respond_to_incident;
+pub mod search_messages;
+pub mod seed_demo_data;
 pub mod send_message;
+pub mod set_activity;
+pub mod star_messages;
+pub mod vote_poll;
 
-pub use connect_participant::ConnectParticipantUseCase;
+pub use broadcast_typing::{BroadcastTypingUseCase, DEFAULT_TYPING_DEBOUNCE};
+pub use check_health::{
+    CheckHealthUseCase, DEFAULT_HEALTH_CHECK_TIMEOUT, DependencyHealth, DependencyStatus,
+    HealthReport,
+};
+pub use compose_daily_digest::{
+    ComposeDailyDigestUseCase, DIGEST_MOST_ACTIVE_LIMIT, DailyDigest, SenderActivity,
+};
+pub use connect_participant::{ConnectParticipantUseCase, MultiplexedConnection};
+pub use create_room::{CreateRoomError, CreateRoomUseCase, DEFAULT_MAX_ROOMS, NewRoom};
 pub use disconnect_participant::DisconnectParticipantUseCase;
-pub use error::{ConnectError, SendMessageError};
-pub use get_room_detail::{GetRoomDetailError, GetRoomDetailUseCase};
+pub use enforce_memory_limit::{EnforceMemoryLimitUseCase, MemoryUsage};
+pub use erase_client_data::{EraseClientDataUseCase, ErasedMessages};
+pub use error::{ConnectError, SeedError, SendMessageError};
+pub use export_messages::{
+    DEFAULT_EXPORT_LIMIT, ExportMessagesError, ExportMessagesUseCase, ExportPage, ExportQuery,
+    MAX_EXPORT_LIMIT,
+};
+pub use forward_message::{ForwardMessageError, ForwardMessageUseCase};
+pub use get_message_history::{DEFAULT_HISTORY_REPLAY, GetMessageHistoryUseCase};
+pub use get_room_detail::{
+    DEFAULT_MESSAGE_LIMIT, GetRoomDetailError, GetRoomDetailUseCase, MAX_MESSAGE_LIMIT, RoomDetail,
+    RoomDetailQuery,
+};
+pub use get_room_messages::{GetRoomMessagesError, GetRoomMessagesUseCase};
 pub use get_room_state::GetRoomStateUseCase;
-pub use get_rooms::GetRoomsUseCase;
+pub use get_room_stats::{GetRoomStatsError, GetRoomStatsUseCase, RoomStats};
+pub use get_rooms::{
+    DEFAULT_ROOMS_LIMIT, GetRoomsUseCase, MAX_ROOMS_LIMIT, RoomListing, RoomSort, RoomsPage,
+    RoomsQuery,
+};
+pub use join_room::{JoinRoomError, JoinRoomUseCase, RoomUseCases};
+pub use kick_participant::{KickParticipantError, KickParticipantUseCase};
+pub use manage_breakouts::{
+    DEFAULT_BREAKOUT_IDLE_TIMEOUT, MAX_BREAKOUT_NAME_CHARS, ManageBreakoutsError,
+    ManageBreakoutsUseCase,
+};
+pub use manage_integrations::{
+    MAX_WEBHOOK_TOKEN_LEN, MIN_WEBHOOK_TOKEN_LEN, ManageIntegrationsError,
+    ManageIntegrationsUseCase, RecurringAnnouncement,
+};
+pub use moderate_messages::{
+    MAX_OPEN_REPORTS, MAX_REPORT_REASON_CHARS, MessageReport, ModerateMessagesUseCase,
+    ModerationAction, ModerationItem, REPORT_CONTEXT_MESSAGES, ReportMessageError,
+    ReportResolution, ResolveReportError,
+};
+pub use rate_limiter::{DEFAULT_MESSAGE_BURST, RateLimiter};
+pub use rename_participant::{RenameParticipantError, RenameParticipantUseCase};
+pub use respond_to_incident::{MAX_DISCONNECT_REASON_CHARS, RespondToIncidentUseCase};
+pub use search_messages::{
+    DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT, SearchMessagesError, SearchMessagesUseCase,
+};
+pub use seed_demo_data::{DEMO_BOTS, DemoSeed, SeedDemoDataUseCase};
 pub use send_message::SendMessageUseCase;
+pub use set_activity::{SetActivityError, SetActivityUseCase};
+pub use star_messages::{
+    MAX_STARS_PER_CLIENT, StarMessageError, StarMessagesUseCase, StarredMessage,
+};
+pub use vote_poll::{VotePollError, VotePollUseCase};
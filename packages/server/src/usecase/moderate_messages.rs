@@ -0,0 +1,668 @@
+//! UseCase: メッセージの通報とモデレーション処理
+//!
+//! 参加者からのメッセージの通報を受け付けてモデレーションキューに積み、管理者がキューを確認して
+//! 通報を解決（却下・メッセージの削除・送信者の BAN）できるようにします。
+//!
+//! ## 設計ノート
+//!
+//! 全てのルームのメッセージを対象とし、通報はルームとシーケンス番号の組で区別します。
+//! 通報とキューはメモリ上に保持し、サーバーを再起動すると失われます。同じメッセージへの通報は 1 つのキュー項目にまとめ、
+//! どの通報 ID で解決しても項目ごと解決します。削除・容量超過などで履歴から消えたメッセージの
+//! 項目は、キューを取得した時に取り除きます（削除されたルームの項目も同様）。
+//!
+//! メッセージを削除した場合、`EraseClientDataUseCase` と同じくそのルームの接続中の参加者に
+//! 削除を通知します。BAN は送信者のメッセージを削除し、以降の接続を拒否する対象として
+//! `BanList` に加えます（`KickParticipantUseCase` と同じ BanList を共有できる）。
+//! 接続中のセッションは呼び出し元が全てのルームで閉じます。
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use tokio::sync::Mutex;
+
+use crate::domain::{
+    BanList, ChatMessage, ClientId, MessagePusher, RepositoryError, Room, RoomId, RoomRepository,
+    SequenceNumber, Timestamp,
+};
+
+use super::{JoinRoomUseCase, kick_participant::connected_rooms};
+
+/// キューに積める未解決の通報の上限（超えた通報は受け付けない）
+pub const MAX_OPEN_REPORTS: usize = 1000;
+/// 通報理由の最大文字数
+pub const MAX_REPORT_REASON_CHARS: usize = 500;
+/// キュー項目に含める前後のメッセージの数（それぞれ）
+pub const REPORT_CONTEXT_MESSAGES: usize = 2;
+
+/// メッセージの通報
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageReport {
+    /// 通報 ID（1 から連番）
+    pub id: u64,
+    /// 通報したクライアント
+    pub reporter: ClientId,
+    /// 通報理由（任意）
+    pub reason: Option<String>,
+    pub reported_at: Timestamp,
+}
+
+/// モデレーションキューの項目（通報されたメッセージ）
+#[derive(Debug, Clone)]
+pub struct ModerationItem {
+    pub room_id: RoomId,
+    /// 通報されたメッセージ
+    pub message: ChatMessage,
+    /// 前後のメッセージ（古い順、通報されたメッセージは含まない）
+    pub context: Vec<ChatMessage>,
+    /// メッセージへの通報（古い順）
+    pub reports: Vec<MessageReport>,
+}
+
+/// 通報の解決方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModerationAction {
+    /// 通報を却下する（メッセージは残す）
+    Dismiss,
+    /// メッセージを削除する
+    Delete,
+    /// メッセージを削除し、送信者の以降の接続を拒否する
+    Ban,
+}
+
+impl ModerationAction {
+    /// アクションの名前
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dismiss => "dismiss",
+            Self::Delete => "delete",
+            Self::Ban => "ban",
+        }
+    }
+}
+
+/// 通報の解決結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportResolution {
+    pub action: ModerationAction,
+    /// 通報されたメッセージのルームの ID
+    pub room_id: RoomId,
+    /// 通報されたメッセージのシーケンス番号
+    pub seq: SequenceNumber,
+    /// 通報されたメッセージの送信者
+    pub sender: ClientId,
+    /// 解決した通報の ID（古い順）
+    pub report_ids: Vec<u64>,
+    /// BAN した送信者が接続しているルーム（呼び出し元がセッションを閉じる、BAN 以外は空）
+    pub connected_rooms: Vec<RoomId>,
+}
+
+/// メッセージの通報エラー
+#[derive(Debug, PartialEq)]
+pub enum ReportMessageError {
+    /// ルームが見つからない
+    RoomNotFound,
+    /// メッセージが履歴に無い
+    MessageNotFound,
+    /// 通報理由が長すぎる
+    ReasonTooLong,
+    /// 未解決の通報が上限に達している
+    QueueFull,
+    /// Repository エラー
+    RepositoryError,
+}
+
+/// 通報の解決エラー
+#[derive(Debug)]
+pub enum ResolveReportError {
+    /// 通報が見つからない（解決済みを含む）
+    ReportNotFound,
+    /// Repository エラー
+    RepositoryError(RepositoryError),
+}
+
+/// 通報されたメッセージごとの未解決の通報
+struct OpenReports {
+    sender: ClientId,
+    reports: Vec<MessageReport>,
+}
+
+/// モデレーションキューの状態
+#[derive(Default)]
+struct Queue {
+    /// 最後に採番した通報 ID
+    last_id: u64,
+    /// 通報されたメッセージ（ルームとシーケンス番号）ごとの通報
+    open: BTreeMap<(RoomId, SequenceNumber), OpenReports>,
+}
+
+impl Queue {
+    fn open_report_count(&self) -> usize {
+        self.open.values().map(|open| open.reports.len()).sum()
+    }
+}
+
+/// メッセージの通報とモデレーションのユースケース
+pub struct ModerateMessagesUseCase {
+    /// Repository（データアクセス層の抽象化、他のルームは `for_room` で取得する）
+    repository: Arc<dyn RoomRepository>,
+    /// 既定のルームの MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+    /// JoinRoomUseCase（作成されたルームの MessagePusher、`None` の場合は既定のルームにのみ通知する）
+    join_room: Option<Arc<JoinRoomUseCase>>,
+    /// モデレーションキュー
+    queue: Mutex<Queue>,
+    /// 接続を拒否するクライアントの一覧
+    ban_list: Arc<dyn BanList>,
+}
+
+impl ModerateMessagesUseCase {
+    /// 新しい ModerateMessagesUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+        ban_list: Arc<dyn BanList>,
+    ) -> Self {
+        Self {
+            repository,
+            message_pusher,
+            join_room: None,
+            queue: Mutex::new(Queue::default()),
+            ban_list,
+        }
+    }
+
+    /// 作成されたルームの参加者にも削除したメッセージを通知する
+    pub fn with_rooms(mut self, join_room: Arc<JoinRoomUseCase>) -> Self {
+        self.join_room = Some(join_room);
+        self
+    }
+
+    /// メッセージを通報
+    ///
+    /// 同じクライアントが同じメッセージを再び通報した場合は、最初の通報の ID を返す。
+    ///
+    /// # Arguments
+    ///
+    /// * `room_id` - メッセージのルームの ID
+    /// * `seq` - 通報するメッセージのシーケンス番号
+    /// * `reporter` - 通報したクライアント
+    /// * `reason` - 通報理由（任意）
+    /// * `now` - 通報日時
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - 通報 ID
+    /// * `Err(ReportMessageError)` - 通報失敗
+    pub async fn report(
+        &self,
+        room_id: &str,
+        seq: SequenceNumber,
+        reporter: ClientId,
+        reason: Option<String>,
+        now: Timestamp,
+    ) -> Result<u64, ReportMessageError> {
+        if reason
+            .as_ref()
+            .is_some_and(|reason| reason.chars().count() > MAX_REPORT_REASON_CHARS)
+        {
+            return Err(ReportMessageError::ReasonTooLong);
+        }
+        let room_id =
+            RoomId::new(room_id.to_string()).map_err(|_| ReportMessageError::RoomNotFound)?;
+        let room = self
+            .repository
+            .get_room_snapshot(&room_id)
+            .await
+            .map_err(|e| match e {
+                RepositoryError::RoomNotFound => ReportMessageError::RoomNotFound,
+                _ => ReportMessageError::RepositoryError,
+            })?;
+        let message = room
+            .messages
+            .iter()
+            .find(|message| message.seq == seq)
+            .ok_or(ReportMessageError::MessageNotFound)?;
+
+        let key = (room_id, seq);
+        let mut queue = self.queue.lock().await;
+        if let Some(report) = queue.open.get(&key).and_then(|open| {
+            open.reports
+                .iter()
+                .find(|report| report.reporter == reporter)
+        }) {
+            return Ok(report.id);
+        }
+        if queue.open_report_count() >= MAX_OPEN_REPORTS {
+            return Err(ReportMessageError::QueueFull);
+        }
+        queue.last_id += 1;
+        let report = MessageReport {
+            id: queue.last_id,
+            reporter,
+            reason,
+            reported_at: now,
+        };
+        queue
+            .open
+            .entry(key)
+            .or_insert_with(|| OpenReports {
+                sender: message.from.clone(),
+                reports: Vec::new(),
+            })
+            .reports
+            .push(report);
+        Ok(queue.last_id)
+    }
+
+    /// モデレーションキューを取得（ルームごとに、通報されたメッセージの古い順）
+    pub async fn queue(&self) -> Result<Vec<ModerationItem>, RepositoryError> {
+        let mut queue = self.queue.lock().await;
+
+        // 通報されたメッセージのあるルームの履歴（削除されたルームは `None`）
+        let mut rooms: BTreeMap<RoomId, Option<Arc<Room>>> = BTreeMap::new();
+        for (room_id, _) in queue.open.keys() {
+            if rooms.contains_key(room_id) {
+                continue;
+            }
+            let room = match self.repository.get_room_snapshot(room_id).await {
+                Ok(room) => Some(room),
+                Err(RepositoryError::RoomNotFound) => None,
+                Err(e) => return Err(e),
+            };
+            rooms.insert(room_id.clone(), room);
+        }
+        let position = |room_id: &RoomId, seq: &SequenceNumber| {
+            let room = rooms.get(room_id)?.as_ref()?;
+            let index = room
+                .messages
+                .binary_search_by_key(seq, |message| message.seq)
+                .ok()?;
+            Some((room, index))
+        };
+
+        // 履歴から消えたメッセージの通報は取り除く
+        queue
+            .open
+            .retain(|(room_id, seq), _| position(room_id, seq).is_some());
+
+        let items = queue
+            .open
+            .iter()
+            .filter_map(|((room_id, seq), open)| {
+                let (room, index) = position(room_id, seq)?;
+                let start = index.saturating_sub(REPORT_CONTEXT_MESSAGES);
+                let end = (index + 1 + REPORT_CONTEXT_MESSAGES).min(room.messages.len());
+                let context = room.messages[start..index]
+                    .iter()
+                    .chain(&room.messages[index + 1..end])
+                    .cloned()
+                    .collect();
+                Some(ModerationItem {
+                    room_id: room_id.clone(),
+                    message: room.messages[index].clone(),
+                    context,
+                    reports: open.reports.clone(),
+                })
+            })
+            .collect();
+        Ok(items)
+    }
+
+    /// 通報を解決（通報されたメッセージへの全ての通報を解決する）
+    ///
+    /// # Arguments
+    ///
+    /// * `report_id` - 解決する通報の ID
+    /// * `action` - 解決方法
+    /// * `render` - 削除したメッセージのシーケンス番号から、通知する JSON メッセージを作成する関数
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ReportResolution)` - 解決結果（BAN の場合、呼び出し元が送信者の接続を閉じる）
+    /// * `Err(ResolveReportError)` - 解決失敗（通報は未解決のまま残る）
+    pub async fn resolve(
+        &self,
+        report_id: u64,
+        action: ModerationAction,
+        render: impl Fn(SequenceNumber) -> String,
+    ) -> Result<ReportResolution, ResolveReportError> {
+        let mut queue = self.queue.lock().await;
+        let (key, sender) = queue
+            .open
+            .iter()
+            .find(|(_, open)| open.reports.iter().any(|report| report.id == report_id))
+            .map(|(key, open)| (key.clone(), open.sender.clone()))
+            .ok_or(ResolveReportError::ReportNotFound)?;
+        let (room_id, seq) = &key;
+
+        if action != ModerationAction::Dismiss {
+            self.delete_message(room_id, *seq, render).await?;
+        }
+        let connected_rooms = if action == ModerationAction::Ban {
+            self.ban_list
+                .ban(sender.clone())
+                .await
+                .map_err(ResolveReportError::RepositoryError)?;
+            connected_rooms(self.repository.as_ref(), &sender)
+                .await
+                .map_err(ResolveReportError::RepositoryError)?
+        } else {
+            Vec::new()
+        };
+
+        let open = queue.open.remove(&key).expect("the report was found above");
+        let (room_id, seq) = key;
+        Ok(ReportResolution {
+            action,
+            room_id,
+            seq,
+            sender,
+            report_ids: open.reports.iter().map(|report| report.id).collect(),
+            connected_rooms,
+        })
+    }
+
+    /// クライアントが BAN されているか
+    pub async fn is_banned(&self, client_id: &ClientId) -> Result<bool, RepositoryError> {
+        self.ban_list.is_banned(client_id).await
+    }
+
+    /// メッセージを削除し、ルームの接続中の参加者に通知する（既に履歴やルームが無い場合は何もしない）
+    async fn delete_message(
+        &self,
+        room_id: &RoomId,
+        seq: SequenceNumber,
+        render: impl Fn(SequenceNumber) -> String,
+    ) -> Result<(), ResolveReportError> {
+        let repository = match self.repository.for_room(room_id).await {
+            Ok(repository) => repository,
+            Err(RepositoryError::RoomNotFound) => return Ok(()),
+            Err(e) => return Err(ResolveReportError::RepositoryError(e)),
+        };
+        match repository.delete_message(seq).await {
+            Ok(()) => {}
+            Err(RepositoryError::MessageNotFound(_)) => return Ok(()),
+            Err(e) => return Err(ResolveReportError::RepositoryError(e)),
+        }
+
+        let default_room = self
+            .repository
+            .get_room_metadata()
+            .await
+            .map_err(ResolveReportError::RepositoryError)?
+            .id;
+        let message_pusher = match &self.join_room {
+            _ if *room_id == default_room => Some(self.message_pusher.clone()),
+            Some(join_room) => join_room.message_pusher(room_id).await,
+            None => None,
+        };
+        let targets = repository.get_all_connected_client_ids().await;
+        if let Some(message_pusher) = message_pusher
+            && !targets.is_empty()
+            && let Err(e) = message_pusher.broadcast(targets, &render(seq)).await
+        {
+            tracing::warn!(
+                "Failed to notify deletion of message {} in {}: {}",
+                seq,
+                room_id,
+                e
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{MessageContent, Room, RoomIdFactory},
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher,
+            repository::{InMemoryBanList, InMemoryRoomRepository},
+        },
+    };
+
+    fn client_id(name: &str) -> ClientId {
+        ClientId::new(name.to_string()).unwrap()
+    }
+
+    /// 既定のルームに `messages`（送信者と内容）を追加した UseCase を作成
+    async fn setup(
+        messages: &[(&str, &str)],
+    ) -> (ModerateMessagesUseCase, Arc<dyn RoomRepository>) {
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository: Arc<dyn RoomRepository> = Arc::new(InMemoryRoomRepository::new(room));
+        for (from, content) in messages {
+            repository
+                .add_message(
+                    client_id(from),
+                    MessageContent::new(content.to_string()).unwrap(),
+                    Timestamp::new(2000),
+                )
+                .await
+                .unwrap();
+        }
+        let pusher = Arc::new(WebSocketMessagePusher::new(Default::default()));
+        (
+            ModerateMessagesUseCase::new(
+                repository.clone(),
+                pusher,
+                Arc::new(InMemoryBanList::new()),
+            ),
+            repository,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_reports_are_grouped_per_message_with_context() {
+        // テスト項目: 同じメッセージへの通報が 1 つの項目にまとまり、前後のメッセージが付く
+        // given (前提条件):
+        let messages = [
+            ("alice", "one"),
+            ("bob", "two"),
+            ("mallory", "spam"),
+            ("alice", "four"),
+        ];
+        let (usecase, repository) = setup(&messages).await;
+        let room_id = repository.get_room().await.unwrap().id;
+        let seq = SequenceNumber::new(3);
+
+        // when (操作):
+        let first = usecase
+            .report(
+                room_id.as_str(),
+                seq,
+                client_id("alice"),
+                None,
+                Timestamp::new(3000),
+            )
+            .await
+            .unwrap();
+        let again = usecase
+            .report(
+                room_id.as_str(),
+                seq,
+                client_id("alice"),
+                None,
+                Timestamp::new(3001),
+            )
+            .await
+            .unwrap();
+        let second = usecase
+            .report(
+                room_id.as_str(),
+                seq,
+                client_id("bob"),
+                Some("spam".to_string()),
+                Timestamp::new(3002),
+            )
+            .await
+            .unwrap();
+        let missing = usecase
+            .report(
+                room_id.as_str(),
+                SequenceNumber::new(99),
+                client_id("bob"),
+                None,
+                Timestamp::new(3003),
+            )
+            .await;
+        let other_room = usecase
+            .report(
+                RoomIdFactory::generate().unwrap().as_str(),
+                seq,
+                client_id("bob"),
+                None,
+                Timestamp::new(3003),
+            )
+            .await;
+        let queue = usecase.queue().await.unwrap();
+
+        // then (期待する結果):
+        assert_eq!((first, again, second), (1, 1, 2));
+        assert_eq!(missing, Err(ReportMessageError::MessageNotFound));
+        assert_eq!(other_room, Err(ReportMessageError::RoomNotFound));
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].message.content.as_str(), "spam");
+        let context: Vec<_> = queue[0].context.iter().map(|m| m.seq.value()).collect();
+        assert_eq!(context, vec![1, 2, 4]);
+        let reporters: Vec<_> = queue[0]
+            .reports
+            .iter()
+            .map(|r| r.reporter.as_str())
+            .collect();
+        assert_eq!(reporters, vec!["alice", "bob"]);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_actions() {
+        // テスト項目: 却下はメッセージを残し、削除はメッセージを消し、BAN は送信者を BAN する
+        // given (前提条件):
+        let messages = [("alice", "one"), ("mallory", "two"), ("mallory", "three")];
+        let (usecase, repository) = setup(&messages).await;
+        let room_id = repository.get_room().await.unwrap().id;
+        let mut report_ids = Vec::new();
+        for seq in 1..=3 {
+            let id = usecase
+                .report(
+                    room_id.as_str(),
+                    SequenceNumber::new(seq),
+                    client_id("bob"),
+                    None,
+                    Timestamp::new(3000),
+                )
+                .await
+                .unwrap();
+            report_ids.push(id);
+        }
+        let render = |seq: SequenceNumber| format!("deleted {}", seq);
+
+        // when (操作):
+        let dismissed = usecase
+            .resolve(report_ids[0], ModerationAction::Dismiss, render)
+            .await
+            .unwrap();
+        let deleted = usecase
+            .resolve(report_ids[1], ModerationAction::Delete, render)
+            .await
+            .unwrap();
+        let banned = usecase
+            .resolve(report_ids[2], ModerationAction::Ban, render)
+            .await
+            .unwrap();
+        let resolved_again = usecase
+            .resolve(report_ids[0], ModerationAction::Delete, render)
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(dismissed.seq, SequenceNumber::new(1));
+        assert_eq!(deleted.seq, SequenceNumber::new(2));
+        assert_eq!(banned.sender, client_id("mallory"));
+        assert!(matches!(
+            resolved_again,
+            Err(ResolveReportError::ReportNotFound)
+        ));
+        let seqs: Vec<_> = repository
+            .get_room()
+            .await
+            .unwrap()
+            .messages
+            .iter()
+            .map(|m| m.seq.value())
+            .collect();
+        assert_eq!(seqs, vec![1]);
+        assert!(usecase.is_banned(&client_id("mallory")).await.unwrap());
+        assert!(!usecase.is_banned(&client_id("alice")).await.unwrap());
+        assert!(usecase.queue().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reports_are_kept_per_room() {
+        // テスト項目: 別のルームの同じシーケンス番号のメッセージは別の項目になり、削除はそのルームにだけ行う
+        // given (前提条件):
+        let (usecase, repository) = setup(&[("alice", "hello")]).await;
+        let lobby_id = repository.get_room().await.unwrap().id;
+        let other = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let other_id = other.id.clone();
+        repository.create_room(other).await.unwrap();
+        let other_repository = repository.for_room(&other_id).await.unwrap();
+        other_repository
+            .add_message(
+                client_id("mallory"),
+                MessageContent::new("spam".to_string()).unwrap(),
+                Timestamp::new(2000),
+            )
+            .await
+            .unwrap();
+        let seq = SequenceNumber::new(1);
+        let mut report_ids = Vec::new();
+        for room_id in [&lobby_id, &other_id] {
+            let id = usecase
+                .report(
+                    room_id.as_str(),
+                    seq,
+                    client_id("bob"),
+                    None,
+                    Timestamp::new(3000),
+                )
+                .await
+                .unwrap();
+            report_ids.push(id);
+        }
+        let queued: Vec<_> = usecase
+            .queue()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|item| (item.room_id, item.message.content.into_string()))
+            .collect();
+
+        // when (操作):
+        let resolution = usecase
+            .resolve(report_ids[1], ModerationAction::Delete, |seq| {
+                format!("deleted {}", seq)
+            })
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(report_ids, vec![1, 2]);
+        assert_eq!(queued.len(), 2);
+        assert!(queued.contains(&(lobby_id.clone(), "hello".to_string())));
+        assert!(queued.contains(&(other_id.clone(), "spam".to_string())));
+        assert_eq!(resolution.room_id, other_id);
+        assert_eq!(resolution.sender, client_id("mallory"));
+        assert!(
+            other_repository
+                .get_room()
+                .await
+                .unwrap()
+                .messages
+                .is_empty()
+        );
+        assert_eq!(repository.get_room().await.unwrap().messages.len(), 1);
+        let remaining = usecase.queue().await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].room_id, lobby_id);
+    }
+}
@@ -0,0 +1,116 @@
+//! クライアントごとのメッセージ送信のレート制限（トークンバケット）
+//!
+//! ## 設計ノート
+//!
+//! クライアント（`ClientId`）ごとに容量 `burst` のバケットを持ち、送信のたびにトークンを
+//! 1 つ消費します。トークンは毎秒 `per_second` 個ずつ容量まで補充されるため、
+//! 短時間に `burst` 件まで続けて送信でき、それ以降は平均 `per_second` 件/秒に制限されます。
+//!
+//! バケットはクライアントの全てのルームで共有します（ルームを分けて送信しても制限は同じ）。
+//! 切断したクライアントのバケットは `forget` で破棄します。
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::domain::ClientId;
+
+/// 続けて送信できるメッセージ数の既定値
+pub const DEFAULT_MESSAGE_BURST: u32 = 10;
+
+/// クライアントのトークンバケット
+#[derive(Debug)]
+struct Bucket {
+    /// 残っているトークン
+    tokens: f64,
+    /// トークンを最後に補充した時刻
+    refilled_at: Instant,
+}
+
+/// クライアントごとのメッセージ送信のレート制限
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// 1 秒あたりに補充するトークン
+    per_second: f64,
+    /// バケットの容量（続けて送信できるメッセージ数）
+    burst: f64,
+    buckets: Mutex<HashMap<ClientId, Bucket>>,
+}
+
+impl RateLimiter {
+    /// 新しい RateLimiter を作成
+    ///
+    /// # Arguments
+    ///
+    /// * `per_second` - 1 秒あたりに送信できるメッセージ数（1 以上）
+    /// * `burst` - 続けて送信できるメッセージ数（1 以上）
+    pub fn new(per_second: u32, burst: u32) -> Self {
+        Self {
+            per_second: f64::from(per_second.max(1)),
+            burst: f64::from(burst.max(1)),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// `now` にクライアントがメッセージを送信できるか判定し、送信できる場合はトークンを消費する
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - 送信できる
+    /// * `Err(Duration)` - 送信できない（次のトークンが補充されるまでの時間）
+    pub fn check(&self, client_id: &ClientId, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(client_id.clone()).or_insert(Bucket {
+            tokens: self.burst,
+            refilled_at: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * self.per_second).min(self.burst);
+        bucket.refilled_at = now;
+        if bucket.tokens < 1.0 {
+            let wait = (1.0 - bucket.tokens) / self.per_second;
+            return Err(Duration::from_secs_f64(wait));
+        }
+        bucket.tokens -= 1.0;
+        Ok(())
+    }
+
+    /// 切断したクライアントのバケットを破棄する
+    pub fn forget(&self, client_id: &ClientId) {
+        self.buckets.lock().unwrap().remove(client_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_allows_bursts_then_refills_at_the_rate() {
+        // テスト項目: burst 件まで続けて送信でき、それ以降は補充された分だけ送信できる（クライアントごと）
+        // given (前提条件):
+        let limiter = RateLimiter::new(2, 3);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let start = Instant::now();
+
+        // when (操作):
+        let burst: Vec<_> = (0..3).map(|_| limiter.check(&alice, start)).collect();
+        let limited = limiter.check(&alice, start);
+        let other_client = limiter.check(&bob, start);
+        let refilled = limiter.check(&alice, start + Duration::from_millis(500));
+        let limited_again = limiter.check(&alice, start + Duration::from_millis(500));
+        limiter.forget(&alice);
+        let after_forget = limiter.check(&alice, start + Duration::from_millis(500));
+
+        // then (期待する結果):
+        assert!(burst.iter().all(Result::is_ok));
+        assert_eq!(limited, Err(Duration::from_millis(500)));
+        assert!(other_client.is_ok());
+        assert!(refilled.is_ok());
+        assert_eq!(limited_again, Err(Duration::from_millis(500)));
+        assert!(after_forget.is_ok());
+    }
+}
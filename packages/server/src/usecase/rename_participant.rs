@@ -0,0 +1,153 @@
+//! UseCase: 表示名設定処理
+//!
+//! 参加者の表示名（例: `Alice Smith`）を記録し、送信者を含むルームの全ての参加者に
+//! ブロードキャストします。
+//!
+//! ## 設計ノート
+//!
+//! 表示名は ClientId とは別に参加者のエンティティに記録し、参加者一覧（`room-connected` と
+//! ルーム詳細 API）と `participant-joined` にも含めます。ClientId は接続の識別子のまま変わらず、
+//! 表示名が重複しても構いません。長さと制御文字の検証は値オブジェクト `DisplayName` が行います。
+
+use std::sync::Arc;
+
+use crate::domain::{ClientId, DisplayName, MessagePusher, RepositoryError, RoomRepository};
+
+/// 表示名設定のエラー
+#[derive(Debug)]
+pub enum RenameParticipantError {
+    /// クライアントがルームにいない
+    NotInRoom,
+    /// Repository エラー
+    RepositoryError(RepositoryError),
+}
+
+impl From<RepositoryError> for RenameParticipantError {
+    fn from(e: RepositoryError) -> Self {
+        match e {
+            RepositoryError::ParticipantNotFound(_) => RenameParticipantError::NotInRoom,
+            e => RenameParticipantError::RepositoryError(e),
+        }
+    }
+}
+
+/// 表示名設定のユースケース
+pub struct RenameParticipantUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+}
+
+impl RenameParticipantUseCase {
+    /// 新しい RenameParticipantUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+    ) -> Self {
+        Self {
+            repository,
+            message_pusher,
+        }
+    }
+
+    /// 表示名の設定を実行
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - 表示名を設定するクライアントの ID（Domain Model）
+    /// * `display_name` - 新しい表示名（`None` の場合は消去）
+    /// * `render` - 設定した表示名からブロードキャストする JSON メッセージを作成する関数
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - 設定成功
+    /// * `Err(RenameParticipantError)` - 設定失敗
+    pub async fn execute(
+        &self,
+        client_id: &ClientId,
+        display_name: Option<DisplayName>,
+        render: impl FnOnce(Option<&DisplayName>) -> String,
+    ) -> Result<(), RenameParticipantError> {
+        let message = render(display_name.as_ref());
+        self.repository
+            .rename_participant(client_id, display_name)
+            .await?;
+
+        let targets = self.repository.get_all_connected_client_ids().await;
+        if let Err(e) = self.message_pusher.broadcast(targets, &message).await {
+            // 参加者一覧には記録済みの表示名が含まれる
+            tracing::warn!(
+                "Failed to broadcast the display name of '{}': {}",
+                client_id,
+                e
+            );
+        }
+        Ok(())
+    }
+
+    /// 接続時に指定された表示名を記録
+    ///
+    /// 表示名は `participant-joined` と参加者一覧に含まれるため、ブロードキャストしない。
+    pub async fn record(
+        &self,
+        client_id: &ClientId,
+        display_name: DisplayName,
+    ) -> Result<(), RenameParticipantError> {
+        self.repository
+            .rename_participant(client_id, Some(display_name))
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{Room, RoomIdFactory, Timestamp},
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+        },
+    };
+
+    fn client_id(name: &str) -> ClientId {
+        ClientId::new(name.to_string()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_execute_sets_and_clears_display_name() {
+        // テスト項目: 参加者の表示名を設定・消去でき、ルームにいないクライアントは設定できない
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Default::default()));
+        let usecase = RenameParticipantUseCase::new(repository.clone(), message_pusher);
+        repository
+            .add_participant(client_id("alice"), Timestamp::new(2000))
+            .await
+            .unwrap();
+        let name = DisplayName::new("Alice Smith".to_string()).unwrap();
+        let render = |_: Option<&DisplayName>| "{}".to_string();
+
+        // when (操作):
+        usecase
+            .execute(&client_id("alice"), Some(name.clone()), render)
+            .await
+            .unwrap();
+        let set = repository.get_participants().await[0].display_name.clone();
+        usecase
+            .execute(&client_id("alice"), None, render)
+            .await
+            .unwrap();
+        let cleared = repository.get_participants().await[0].display_name.clone();
+        let stranger = usecase
+            .execute(&client_id("bob"), Some(name.clone()), render)
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(set, Some(name));
+        assert_eq!(cleared, None);
+        assert!(matches!(stranger, Err(RenameParticipantError::NotInRoom)));
+    }
+}
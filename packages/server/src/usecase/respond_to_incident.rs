@@ -0,0 +1,170 @@
+//! UseCase: インシデント対応（ルームの凍結と全参加者の切断）
+//!
+//! 荒らしや障害の対応として、管理者の操作でルームを凍結（メッセージの送信を拒否し、履歴の
+//! 読み取りは続けられる）したり、ルームの全ての参加者を理由を添えて一度に切断したりします。
+//!
+//! ## 設計ノート
+//!
+//! 凍結はルームのエンティティに記録し、メッセージの追加そのものを拒否します（チャット・投票・
+//! 転送のいずれも同じ）。凍結の開始と解除は接続中の参加者に通知します。
+//!
+//! 切断は `KickParticipantUseCase` と同じく接続を閉じません。理由を含む通知を MessagePusher で
+//! 参加者に送ってから接続中のクライアントを返し、呼び出し元（UI 層）がそのセッションを閉じて
+//! 参加者は通常の切断処理でルームから退出します。切断した参加者は再び接続できるため、
+//! 送信も止める場合は凍結と組み合わせます。
+
+use std::sync::Arc;
+
+use crate::domain::{ClientId, MessagePusher, RepositoryError, RoomRepository};
+
+/// 切断の理由の最大文字数
+pub const MAX_DISCONNECT_REASON_CHARS: usize = 200;
+
+/// インシデント対応のユースケース
+pub struct RespondToIncidentUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+}
+
+impl RespondToIncidentUseCase {
+    /// 新しい RespondToIncidentUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+    ) -> Self {
+        Self {
+            repository,
+            message_pusher,
+        }
+    }
+
+    /// ルームを凍結、または凍結を解除
+    ///
+    /// # Arguments
+    ///
+    /// * `frozen` - 凍結する場合は `true`、解除する場合は `false`
+    /// * `notice` - 接続中の参加者にブロードキャストする JSON メッセージ
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - 凍結・解除成功
+    /// * `Err(RepositoryError)` - 凍結・解除失敗
+    pub async fn set_frozen(&self, frozen: bool, notice: &str) -> Result<(), RepositoryError> {
+        self.repository.set_frozen(frozen).await?;
+
+        let targets = self.repository.get_all_connected_client_ids().await;
+        if let Err(e) = self.message_pusher.broadcast(targets, notice).await {
+            // 凍結は記録済みで、送信したクライアントには `room_frozen` のエラーで伝わる
+            tracing::warn!("Failed to broadcast the freeze of the room: {}", e);
+        }
+        Ok(())
+    }
+
+    /// ルームが凍結されているか
+    pub async fn is_frozen(&self) -> Result<bool, RepositoryError> {
+        Ok(self.repository.get_room().await?.frozen)
+    }
+
+    /// ルームの全ての参加者に切断を通知
+    ///
+    /// # Arguments
+    ///
+    /// * `notice` - 切断の理由を含む、参加者に送る JSON メッセージ
+    ///
+    /// # Returns
+    ///
+    /// 通知した接続中のクライアント（呼び出し元がセッションを閉じる）
+    pub async fn disconnect_all(&self, notice: &str) -> Vec<ClientId> {
+        let targets = self.repository.get_all_connected_client_ids().await;
+        if let Err(e) = self.message_pusher.broadcast(targets.clone(), notice).await {
+            // 通知が届かなくても接続は閉じる
+            tracing::warn!(
+                "Failed to notify the participants of the disconnection: {}",
+                e
+            );
+        }
+        targets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{
+        domain::{MessageContent, Room, RoomError, RoomIdFactory, Timestamp},
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+        },
+    };
+
+    fn client_id(name: &str) -> ClientId {
+        ClientId::new(name.to_string()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_frozen_room_rejects_messages_and_notifies() {
+        // テスト項目: 凍結したルームはメッセージを拒否して参加者に通知され、解除すると再び受け付ける
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Default::default()));
+        let usecase = RespondToIncidentUseCase::new(repository.clone(), message_pusher.clone());
+        repository
+            .add_participant(client_id("alice"), Timestamp::new(2000))
+            .await
+            .unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        message_pusher.register_client(client_id("alice"), tx).await;
+        let content = MessageContent::new("Hello!".to_string()).unwrap();
+
+        // when (操作):
+        usecase.set_frozen(true, "frozen").await.unwrap();
+        let rejected = repository
+            .add_message(client_id("alice"), content.clone(), Timestamp::new(3000))
+            .await;
+        let frozen = usecase.is_frozen().await.unwrap();
+        usecase.set_frozen(false, "unfrozen").await.unwrap();
+        let accepted = repository
+            .add_message(client_id("alice"), content, Timestamp::new(4000))
+            .await;
+
+        // then (期待する結果):
+        assert!(frozen);
+        assert!(matches!(
+            rejected,
+            Err(RepositoryError::Room(RoomError::RoomFrozen))
+        ));
+        assert!(accepted.is_ok());
+        assert_eq!(rx.recv().await.as_deref(), Some("frozen"));
+        assert_eq!(rx.recv().await.as_deref(), Some("unfrozen"));
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_all_notifies_connected_participants() {
+        // テスト項目: 全ての参加者の切断は接続中の参加者に通知し、そのクライアントを返す
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Default::default()));
+        let usecase = RespondToIncidentUseCase::new(repository.clone(), message_pusher.clone());
+        for name in ["alice", "bob"] {
+            repository
+                .add_participant(client_id(name), Timestamp::new(2000))
+                .await
+                .unwrap();
+        }
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        message_pusher.register_client(client_id("bob"), tx).await;
+
+        // when (操作):
+        let disconnected = usecase.disconnect_all("maintenance").await;
+
+        // then (期待する結果):
+        assert_eq!(disconnected, vec![client_id("alice"), client_id("bob")]);
+        assert_eq!(rx.recv().await.as_deref(), Some("maintenance"));
+    }
+}
@@ -0,0 +1,130 @@
+//! UseCase: ルームのメッセージ検索処理
+//!
+//! 保持しているメッセージ履歴から、本文に検索語を含むメッセージを新しい順に返します。
+//! 大文字と小文字は区別しません。容量を超えて削除されたメッセージは検索の対象外です。
+
+use std::sync::Arc;
+
+use crate::domain::{ChatMessage, RepositoryError, RoomId, RoomRepository};
+
+/// 検索結果に含めるメッセージ数の既定値
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// 検索結果に含めるメッセージ数の上限
+pub const MAX_SEARCH_LIMIT: usize = 50;
+
+/// ルームのメッセージ検索のユースケース
+pub struct SearchMessagesUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+}
+
+/// ルームのメッセージ検索エラー
+#[derive(Debug, PartialEq)]
+pub enum SearchMessagesError {
+    /// ルームが見つからない
+    RoomNotFound,
+    /// 検索語が空
+    EmptyQuery,
+    /// Repository エラー
+    RepositoryError,
+}
+
+impl SearchMessagesUseCase {
+    /// 新しい SearchMessagesUseCase を作成
+    pub fn new(repository: Arc<dyn RoomRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// 本文に検索語を含むメッセージを検索
+    ///
+    /// # Arguments
+    ///
+    /// * `room_id` - 検索するルームの ID
+    /// * `query` - 検索語（前後の空白は無視する）
+    /// * `limit` - 返すメッセージ数（[`MAX_SEARCH_LIMIT`] を超える場合は切り詰める）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ChatMessage>)` - 一致したメッセージ（新しい順）
+    /// * `Err(SearchMessagesError)` - 検索失敗
+    pub async fn execute(
+        &self,
+        room_id: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<ChatMessage>, SearchMessagesError> {
+        let needle = query.trim().to_lowercase();
+        if needle.is_empty() {
+            return Err(SearchMessagesError::EmptyQuery);
+        }
+        let room_id =
+            RoomId::new(room_id.to_string()).map_err(|_| SearchMessagesError::RoomNotFound)?;
+        let room = self
+            .repository
+            .get_room_snapshot(&room_id)
+            .await
+            .map_err(|e| match e {
+                RepositoryError::RoomNotFound => SearchMessagesError::RoomNotFound,
+                _ => SearchMessagesError::RepositoryError,
+            })?;
+
+        Ok(room
+            .messages
+            .iter()
+            .rev()
+            .filter(|message| message.content.as_str().to_lowercase().contains(&needle))
+            .take(limit.min(MAX_SEARCH_LIMIT))
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{ClientId, MessageContent, Room, RoomIdFactory, Timestamp},
+        infrastructure::repository::InMemoryRoomRepository,
+    };
+
+    #[tokio::test]
+    async fn test_execute_finds_messages_newest_first() {
+        // テスト項目: 本文に検索語を含むメッセージが大文字と小文字を区別せずに新しい順で最大 limit 件返る
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let room_id = room.id.as_str().to_string();
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        for content in [
+            "Deploy starts at 10",
+            "lunch?",
+            "deploy done",
+            "The DEPLOY failed",
+        ] {
+            repository
+                .add_message(
+                    alice.clone(),
+                    MessageContent::new(content.to_string()).unwrap(),
+                    Timestamp::new(2000),
+                )
+                .await
+                .unwrap();
+        }
+        let usecase = SearchMessagesUseCase::new(repository);
+
+        // when (操作):
+        let found = usecase.execute(&room_id, " deploy ", 2).await.unwrap();
+        let empty = usecase.execute(&room_id, "  ", 2).await;
+        let missing = usecase.execute("unknown", "deploy", 2).await;
+
+        // then (期待する結果):
+        let contents: Vec<_> = found
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["The DEPLOY failed", "deploy done"]);
+        assert_eq!(empty.err(), Some(SearchMessagesError::EmptyQuery));
+        assert_eq!(missing.err(), Some(SearchMessagesError::RoomNotFound));
+    }
+}
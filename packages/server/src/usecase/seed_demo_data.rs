@@ -0,0 +1,208 @@
+//! UseCase: デモデータの投入
+//!
+//! 起動時にボットの参加者と過去のメッセージ履歴をルームに追加し、手作業で準備しなくても
+//! REST API やクライアントを試せるようにします。
+//!
+//! ## 設計ノート
+//!
+//! ボットは WebSocket 接続を持たないため、全てのボットが 1 つの受信チャネルを共有し、
+//! 呼び出し側が届いたメッセージを読み捨てます（登録しないとブロードキャストのたびに
+//! 送信先が見つからない警告が出るため）。
+//! WAL から復元したルームなど、既に履歴があるルームには投入しません（再起動のたびに
+//! 履歴が重複しないように）。
+
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use crate::domain::{ClientId, MessageContent, MessagePusher, RoomRepository, Timestamp};
+
+use super::error::SeedError;
+
+/// デモ用のボットの参加者
+pub const DEMO_BOTS: [&str; 3] = ["demo-bot-alice", "demo-bot-bob", "demo-bot-carol"];
+
+/// デモ用の会話（送信者のボットのインデックスと内容）
+const DEMO_CONVERSATION: [(usize, &str); 8] = [
+    (
+        0,
+        "Welcome to Engawa! This room was seeded with --seed demo.",
+    ),
+    (
+        1,
+        "Hi Alice! Is the history here available over the REST API?",
+    ),
+    (
+        0,
+        "Yes: GET /api/v1/rooms/{room_id}/messages?since_seq=0 returns all of it.",
+    ),
+    (
+        2,
+        "And /api/v1/rooms lists the room with the three of us in it.",
+    ),
+    (1, "Nice. What happens if my client drops its connection?"),
+    (
+        0,
+        "Reconnect with your resume_token and the server backfills what you missed.",
+    ),
+    (
+        2,
+        "Try it: cargo run --bin engawa-client -- --client-id you",
+    ),
+    (1, "See you in the chat!"),
+];
+
+/// デモの会話の間隔（ミリ秒）
+const DEMO_MESSAGE_INTERVAL_MS: i64 = 5 * 60 * 1000;
+
+/// 投入したデモデータ
+pub struct DemoSeed {
+    /// ボット宛てに届いたメッセージ（呼び出し側が読み捨てる）
+    pub inbox: mpsc::UnboundedReceiver<Arc<str>>,
+    /// 追加したボットの数
+    pub bots: usize,
+    /// 追加したメッセージの数
+    pub messages: usize,
+}
+
+/// デモデータ投入のユースケース
+pub struct SeedDemoDataUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+}
+
+impl SeedDemoDataUseCase {
+    /// 新しい SeedDemoDataUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+    ) -> Self {
+        Self {
+            repository,
+            message_pusher,
+        }
+    }
+
+    /// ボットの参加者と、`now` までの過去のメッセージ履歴を追加する
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(DemoSeed))` - 投入したデモデータ
+    /// * `Ok(None)` - ルームに既に履歴があるため投入しなかった
+    /// * `Err(SeedError)` - 参加者またはメッセージの追加に失敗
+    pub async fn execute(&self, now: Timestamp) -> Result<Option<DemoSeed>, SeedError> {
+        let room = self
+            .repository
+            .get_room()
+            .await
+            .map_err(|e| SeedError::PersistFailed(e.to_string()))?;
+        if !room.messages.is_empty() {
+            return Ok(None);
+        }
+
+        let bots: Vec<ClientId> = DEMO_BOTS
+            .iter()
+            .map(|name| ClientId::new(name.to_string()).expect("demo bot names are valid"))
+            .collect();
+
+        // 会話は `now` の少し前に終わるように時刻を遡らせる
+        let started_at = now.value() - DEMO_MESSAGE_INTERVAL_MS * DEMO_CONVERSATION.len() as i64;
+        for (i, (from, content)) in DEMO_CONVERSATION.iter().enumerate() {
+            self.repository
+                .add_message(
+                    bots[*from].clone(),
+                    MessageContent::new(content.to_string()).expect("demo messages are valid"),
+                    Timestamp::new(started_at + DEMO_MESSAGE_INTERVAL_MS * i as i64),
+                )
+                .await
+                .map_err(|e| SeedError::PersistFailed(e.to_string()))?;
+        }
+
+        let (sender, inbox) = mpsc::unbounded_channel();
+        for bot in &bots {
+            self.repository
+                .add_participant(bot.clone(), now)
+                .await
+                .map_err(|_| SeedError::RoomCapacityExceeded)?;
+            self.message_pusher
+                .register_client(bot.clone(), sender.clone())
+                .await;
+        }
+
+        Ok(Some(DemoSeed {
+            inbox,
+            bots: bots.len(),
+            messages: DEMO_CONVERSATION.len(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{Room, RoomIdFactory},
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+        },
+    };
+
+    fn create_usecase() -> (SeedDemoDataUseCase, Arc<InMemoryRoomRepository>) {
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Default::default()));
+        (
+            SeedDemoDataUseCase::new(repository.clone(), message_pusher),
+            repository,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_execute_seeds_bots_and_history() {
+        // テスト項目: ボットの参加者と、現在時刻より前の時系列順のメッセージ履歴が追加される
+        // given (前提条件):
+        let (usecase, repository) = create_usecase();
+        let now = Timestamp::new(1_700_000_000_000);
+
+        // when (操作):
+        let seed = usecase.execute(now).await.unwrap().unwrap();
+
+        // then (期待する結果):
+        assert_eq!(seed.bots, DEMO_BOTS.len());
+        let room = repository.get_room().await.unwrap();
+        assert_eq!(room.participants.len(), DEMO_BOTS.len());
+        assert_eq!(room.messages.len(), seed.messages);
+        assert!(
+            room.messages
+                .windows(2)
+                .all(|pair| pair[0].timestamp.value() < pair[1].timestamp.value())
+        );
+        assert!(room.messages.last().unwrap().timestamp.value() < now.value());
+    }
+
+    #[tokio::test]
+    async fn test_execute_skips_room_with_history() {
+        // テスト項目: 既に履歴があるルームには投入しない（再起動で重複しない）
+        // given (前提条件):
+        let (usecase, repository) = create_usecase();
+        repository
+            .add_message(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new("hello".to_string()).unwrap(),
+                Timestamp::new(1000),
+            )
+            .await
+            .unwrap();
+
+        // when (操作):
+        let seed = usecase.execute(Timestamp::new(2000)).await.unwrap();
+
+        // then (期待する結果):
+        assert!(seed.is_none());
+        let room = repository.get_room().await.unwrap();
+        assert_eq!(room.messages.len(), 1);
+        assert!(room.participants.is_empty());
+    }
+}
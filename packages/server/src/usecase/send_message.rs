@@ -5,6 +5,7 @@
 //! ### 何をテストしているか
 //! - SendMessageUseCase::execute() メソッド
 //! - メッセージ送信処理（ブロードキャスト対象選定、メッセージ履歴への追加）
+//! - シーケンサーによる順序保証（シーケンス番号順に永続化・ブロードキャストされる）
 //!
 //! ### なぜこのテストが必要か
 //! - ビジネスロジックの検証：送信者以外にメッセージがブロードキャストされる
@@ -15,30 +16,157 @@
 //! - 正常系：メッセージ送信とブロードキャスト
 //! - 異常系：メッセージ容量超過
 //! - エッジケース：送信者のみが接続している場合（ブロードキャスト対象なし）
-
-use std::sync::Arc;
-
-use crate::domain::{ClientId, MessageContent, MessagePusher, RoomRepository, Timestamp};
-
-use super::error::SendMessageError;
+//! - 並行性：複数タスクから同時に送信した場合
+//!
+//! ## 設計ノート
+//!
+//! ルームへの送信は全て 1 つのシーケンサータスクを通ります（mpsc → 採番 → 永続化 →
+//! ブロードキャスト）。ハンドラーのタスクごとに永続化とブロードキャストを行うと、
+//! 並行した送信の間でブロードキャストの順序が永続化の順序と入れ替わることがあるためです。
+//! ブロードキャストする JSON はシーケンス番号が決まってから `render` で作成します。
+//!
+//! ルームのメッセージのポリシーの最大長（`with_max_message_length`）はシーケンサーに渡す前に
+//! 確認し、超えたメッセージは `SendMessageError::MessageTooLong` を返します。WebSocket だけでなく
+//! 転送・Webhook・ブリッジからの送信にも同じポリシーが適用されます。
+//!
+//! RateLimiter を設定した場合、シーケンサーに渡す前にクライアントごとの送信レートを確認し、
+//! 超えた送信は採番・永続化・ブロードキャストせずに `SendMessageError::RateLimited` を返します。
+//! メッセージフィルターも同じくシーケンサーに渡す前に設定した順に適用し、拒否したメッセージは
+//! `SendMessageError::Rejected` を返します（遅いフィルターでも他のクライアントの送信は遅れない）。
+//!
+//! 投票は質問を内容とするメッセージとして採番・永続化し、ブロードキャストの前に選択肢を付けます。
+//! 投票の送信者は振られたシーケンス番号で投票するため、投票は送信者にもブロードキャストします。
+//!
+//! 他のルームから転送したメッセージも同じように採番・永続化し、ブロードキャストの前に転送元を
+//! 記録します。転送した本人は転送先のルームで結果を確認するため、転送も送信者にブロードキャストします。
+//!
+//! 参加者を持続させる場合（PendingMessageStore を設定した場合）は、ブロードキャストした JSON を
+//! 切断中の参加者のメールボックスにも追加します。再接続した参加者には ConnectParticipantUseCase が
+//! 溜まったメッセージを送ります。
+//!
+//! MessageAnalyzer を渡した場合、ブロードキャストの後にメッセージごとの分析タスクを起動し、
+//! 付いたタグを Repository に保存します。分析はシーケンサーを止めないため、遅い分析でも
+//! 後続のメッセージの配信は遅れません。
+
+use std::{sync::Arc, time::Instant};
+
+use tokio::sync::{mpsc, oneshot};
+use tracing::Instrument;
+
+use crate::domain::{
+    ChatMessage, ClientId, ForwardedFrom, MessageAnalyzer, MessageContent, MessageFilterChain,
+    MessagePusher, PendingMessageStore, Poll, PollOption, RepositoryError, RoomError,
+    RoomRepository, SequenceNumber, Timestamp,
+};
+
+use super::{error::SendMessageError, rate_limiter::RateLimiter};
+
+/// シーケンサーの送信キューの容量
+pub const SEQUENCER_QUEUE_CAPACITY: usize = 1024;
+
+/// シーケンス番号からブロードキャストする JSON メッセージを作成する関数
+pub type RenderMessage = Box<dyn FnOnce(SequenceNumber) -> String + Send>;
+
+/// シーケンサーに渡す送信要求
+struct SendRequest {
+    from_client_id: ClientId,
+    content: MessageContent,
+    /// 投票の選択肢（投票でないメッセージは `None`）
+    poll: Option<Vec<PollOption>>,
+    /// 転送元（転送でないメッセージは `None`）
+    forwarded_from: Option<ForwardedFrom>,
+    render: RenderMessage,
+    /// ブロードキャストした JSON を追加するメールボックス（参加者を持続させない場合は `None`）
+    pending_messages: Option<Arc<dyn PendingMessageStore>>,
+    reply: oneshot::Sender<Result<Vec<ClientId>, SendMessageError>>,
+    /// 送信元のスパン（永続化とブロードキャストを同じメッセージのスパンの下に記録する）
+    span: tracing::Span,
+}
 
 /// メッセージ送信のユースケース
 pub struct SendMessageUseCase {
-    /// Repository（データアクセス層の抽象化）
-    repository: Arc<dyn RoomRepository>,
-    /// MessagePusher（メッセージ通知の抽象化）
-    message_pusher: Arc<dyn MessagePusher>,
+    /// シーケンサーへの送信キュー（Repository と MessagePusher はシーケンサーが保持する）
+    requests: mpsc::Sender<SendRequest>,
+    /// クライアントごとの送信レートの制限（制限しない場合は `None`）
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// 送信を受け付ける前に適用するメッセージフィルター（適用しない場合は `None`）
+    filters: Option<Arc<MessageFilterChain>>,
+    /// 送信を受け付けるメッセージの最大長（バイト、`MessageContent` の上限のみの場合は `None`）
+    max_message_length: Option<usize>,
+    /// 切断中の参加者のメールボックス（参加者を持続させない場合は `None`）
+    pending_messages: Option<Arc<dyn PendingMessageStore>>,
 }
 
 impl SendMessageUseCase {
     /// 新しい SendMessageUseCase を作成
+    ///
+    /// ルームのシーケンサータスクを起動するため、Tokio ランタイム上で呼び出す必要がある。
+    /// シーケンサーは UseCase が破棄されると停止する。
     pub fn new(
         repository: Arc<dyn RoomRepository>,
         message_pusher: Arc<dyn MessagePusher>,
     ) -> Self {
+        Self::spawn(repository, message_pusher, None)
+    }
+
+    /// 送信したメッセージを MessageAnalyzer で分析する SendMessageUseCase を作成
+    ///
+    /// 分析で付いたタグは `RoomRepository::tag_message` で保存する。
+    pub fn with_analyzer(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+        analyzer: Arc<dyn MessageAnalyzer>,
+    ) -> Self {
+        Self::spawn(repository, message_pusher, Some(analyzer))
+    }
+
+    fn spawn(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+        analyzer: Option<Arc<dyn MessageAnalyzer>>,
+    ) -> Self {
+        let (requests, receiver) = mpsc::channel(SEQUENCER_QUEUE_CAPACITY);
+        engawa_shared::task::spawn(
+            "sequencer",
+            run_sequencer(repository, message_pusher, analyzer, receiver),
+        );
         Self {
-            repository,
-            message_pusher,
+            requests,
+            rate_limiter: None,
+            filters: None,
+            max_message_length: None,
+            pending_messages: None,
+        }
+    }
+
+    /// クライアントごとの送信レートを RateLimiter で制限する
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// 送信を受け付ける前にメッセージフィルターを適用する
+    pub fn with_filters(mut self, filters: Arc<MessageFilterChain>) -> Self {
+        self.filters = Some(filters);
+        self
+    }
+
+    /// ルームのメッセージのポリシー（`MessagePolicy::max_length`）より長いメッセージを拒否する
+    pub fn with_max_message_length(mut self, max_length: usize) -> Self {
+        self.max_message_length = Some(max_length);
+        self
+    }
+
+    /// ブロードキャストしたメッセージを切断中の参加者のメールボックスにも追加する
+    pub fn with_pending_messages(mut self, store: Arc<dyn PendingMessageStore>) -> Self {
+        self.pending_messages = Some(store);
+        self
+    }
+
+    /// 切断したクライアントの送信レートの記録を破棄する
+    pub fn forget(&self, client_id: &ClientId) {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.forget(client_id);
         }
     }
 
@@ -46,64 +174,317 @@ impl SendMessageUseCase {
     ///
     /// # Arguments
     ///
-    /// * `from_client_id` - メッセージ送信者のクライアント ID（Domain Model）
+    /// * `from_client_id` - メッセージ送信者のクライアント ID（Domain Model）。送信レートの制限の
+    ///   キーにもなるため、メッセージの内容ではなく接続（認証したクライアント）から渡す
     /// * `content` - メッセージ内容（Domain Model）
-    /// * `json_message` - 送信する JSON メッセージ（DTO 層で生成されたもの）
+    /// * `render` - 振られたシーケンス番号からブロードキャストする JSON メッセージを作成する関数
     ///
     /// # Returns
     ///
     /// * `Ok(Vec<ClientId>)` - ブロードキャスト対象のクライアント ID リスト（Domain Model）
-    /// * `Err(SendMessageError)` - 送信失敗
+    /// * `Err(SendMessageError)` - 送信失敗（レート制限を超えた場合は `RateLimited`、
+    ///   フィルターが拒否した場合は `Rejected`、ルームのポリシーより長い場合は `MessageTooLong`）
     pub async fn execute(
         &self,
         from_client_id: ClientId,
         content: MessageContent,
-        json_message: String,
+        render: impl FnOnce(SequenceNumber) -> String + Send + 'static,
     ) -> Result<Vec<ClientId>, SendMessageError> {
-        use engawa_shared::time::get_jst_timestamp;
-
-        let timestamp = Timestamp::new(get_jst_timestamp());
-
-        // 1. Repository 経由でメッセージを Room に追加
-        self.repository
-            .add_message(from_client_id.clone(), content, timestamp)
+        self.submit(from_client_id, content, None, None, Box::new(render))
             .await
-            .map_err(|_| SendMessageError::MessageCapacityExceeded)?;
+    }
 
-        // 2. ブロードキャスト対象を取得（送信者以外の全てのクライアント）
-        let broadcast_targets = self.get_broadcast_targets(&from_client_id).await;
+    /// 投票の送信を実行
+    ///
+    /// 質問を内容とするメッセージを `options` の投票にして、送信者を含む全ての参加者に
+    /// ブロードキャストする。
+    ///
+    /// # Arguments
+    ///
+    /// * `from_client_id` - 投票の送信者のクライアント ID（Domain Model）
+    /// * `question` - 投票の質問（Domain Model）
+    /// * `options` - 投票の選択肢（[`Poll::MIN_OPTIONS`] 〜 [`Poll::MAX_OPTIONS`] 個）
+    /// * `render` - 振られたシーケンス番号からブロードキャストする JSON メッセージを作成する関数
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ClientId>)` - ブロードキャスト対象のクライアント ID リスト（送信者を含む）
+    /// * `Err(SendMessageError)` - 送信失敗（選択肢の数が範囲外の場合は `InvalidPoll`）
+    pub async fn execute_poll(
+        &self,
+        from_client_id: ClientId,
+        question: MessageContent,
+        options: Vec<PollOption>,
+        render: impl FnOnce(SequenceNumber) -> String + Send + 'static,
+    ) -> Result<Vec<ClientId>, SendMessageError> {
+        if !(Poll::MIN_OPTIONS..=Poll::MAX_OPTIONS).contains(&options.len()) {
+            return Err(SendMessageError::InvalidPoll {
+                min: Poll::MIN_OPTIONS,
+                max: Poll::MAX_OPTIONS,
+            });
+        }
+        self.submit(
+            from_client_id,
+            question,
+            Some(options),
+            None,
+            Box::new(render),
+        )
+        .await
+    }
 
-        // 3. MessagePusher を使ってブロードキャスト
-        self.message_pusher
-            .broadcast(broadcast_targets.clone(), &json_message)
+    /// 他のルームから転送したメッセージの送信を実行
+    ///
+    /// 転送元を記録したメッセージを、転送したクライアントを含む全ての参加者にブロードキャストする。
+    ///
+    /// # Arguments
+    ///
+    /// * `from_client_id` - 転送したクライアントの ID（Domain Model）
+    /// * `content` - 転送するメッセージの内容（Domain Model）
+    /// * `origin` - 転送元のルーム・送信者・送信日時（Domain Model）
+    /// * `render` - 振られたシーケンス番号からブロードキャストする JSON メッセージを作成する関数
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ClientId>)` - ブロードキャスト対象のクライアント ID リスト（転送したクライアントを含む）
+    /// * `Err(SendMessageError)` - 送信失敗
+    pub async fn execute_forward(
+        &self,
+        from_client_id: ClientId,
+        content: MessageContent,
+        origin: ForwardedFrom,
+        render: impl FnOnce(SequenceNumber) -> String + Send + 'static,
+    ) -> Result<Vec<ClientId>, SendMessageError> {
+        self.submit(
+            from_client_id,
+            content,
+            None,
+            Some(origin),
+            Box::new(render),
+        )
+        .await
+    }
+
+    /// メッセージの長さ・送信レート・メッセージフィルターを確認し、送信要求をシーケンサーに渡して
+    /// 結果を待つ
+    async fn submit(
+        &self,
+        from_client_id: ClientId,
+        content: MessageContent,
+        poll: Option<Vec<PollOption>>,
+        forwarded_from: Option<ForwardedFrom>,
+        render: RenderMessage,
+    ) -> Result<Vec<ClientId>, SendMessageError> {
+        if let Some(max) = self.max_message_length
+            && content.as_str().len() > max
+        {
+            return Err(SendMessageError::MessageTooLong {
+                max,
+                actual: content.as_str().len(),
+            });
+        }
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter
+                .check(&from_client_id, Instant::now())
+                .map_err(|retry_after| SendMessageError::RateLimited {
+                    retry_after_ms: (retry_after.as_micros() as u64).div_ceil(1000),
+                })?;
+        }
+        if let Some(filters) = &self.filters {
+            filters
+                .check(&from_client_id, &content)
+                .instrument(tracing::info_span!("filter"))
+                .await
+                .map_err(SendMessageError::Rejected)?;
+        }
+        let (reply, result) = oneshot::channel();
+        self.requests
+            .send(SendRequest {
+                from_client_id,
+                content,
+                poll,
+                forwarded_from,
+                render,
+                pending_messages: self.pending_messages.clone(),
+                reply,
+                span: tracing::Span::current(),
+            })
+            .await
+            .map_err(|_| SendMessageError::SequencerStopped)?;
+        result
             .await
-            .map_err(|e| SendMessageError::BroadcastFailed(e.to_string()))?;
+            .map_err(|_| SendMessageError::SequencerStopped)?
+    }
+}
+
+/// 送信要求を 1 件ずつ処理するシーケンサー
+async fn run_sequencer(
+    repository: Arc<dyn RoomRepository>,
+    message_pusher: Arc<dyn MessagePusher>,
+    analyzer: Option<Arc<dyn MessageAnalyzer>>,
+    mut requests: mpsc::Receiver<SendRequest>,
+) {
+    while let Some(request) = requests.recv().await {
+        let result = sequence(
+            repository.as_ref(),
+            message_pusher.as_ref(),
+            request.from_client_id,
+            request.content,
+            request.poll,
+            request.forwarded_from,
+            request.render,
+        )
+        .instrument(request.span.clone())
+        .await;
+        if let (Ok((message, _, rendered)), Some(store)) = (&result, &request.pending_messages)
+            && let Err(e) = store.push(rendered, message.timestamp).await
+        {
+            tracing::warn!(
+                "Failed to keep message {} for mailboxes: {}",
+                message.seq,
+                e
+            );
+        }
+        let result = result.map(|(message, targets, _)| {
+            if let Some(analyzer) = &analyzer {
+                engawa_shared::task::spawn(
+                    "message-analyzer",
+                    analyze(repository.clone(), analyzer.clone(), message).instrument(request.span),
+                );
+            }
+            targets
+        });
+        // 送信元が待機をやめていても処理は完了している
+        let _ = request.reply.send(result);
+    }
+}
 
-        Ok(broadcast_targets)
+/// 保存済みのメッセージを分析し、付いたタグを保存する
+async fn analyze(
+    repository: Arc<dyn RoomRepository>,
+    analyzer: Arc<dyn MessageAnalyzer>,
+    message: ChatMessage,
+) {
+    let tags = analyzer
+        .analyze(&message)
+        .instrument(tracing::info_span!("analyze"))
+        .await;
+    if tags.is_empty() {
+        return;
+    }
+    if let Err(e) = repository.tag_message(message.seq, tags).await {
+        tracing::warn!("Failed to tag message {}: {}", message.seq, e);
     }
+}
 
-    /// ブロードキャスト対象のクライアント ID リストを取得
-    ///
-    /// 送信者以外の全てのクライアント ID を返す（Domain Model）
-    async fn get_broadcast_targets(&self, exclude_client_id: &ClientId) -> Vec<ClientId> {
-        let all_client_ids = self.repository.get_all_connected_client_ids().await;
-        all_client_ids
-            .into_iter()
-            .filter(|id| id != exclude_client_id)
-            .collect()
+/// 採番・永続化・ブロードキャストを行う
+async fn sequence(
+    repository: &dyn RoomRepository,
+    message_pusher: &dyn MessagePusher,
+    from_client_id: ClientId,
+    content: MessageContent,
+    poll: Option<Vec<PollOption>>,
+    forwarded_from: Option<ForwardedFrom>,
+    render: RenderMessage,
+) -> Result<(ChatMessage, Vec<ClientId>, String), SendMessageError> {
+    use engawa_shared::time::get_jst_timestamp;
+
+    let timestamp = Timestamp::new(get_jst_timestamp());
+
+    // 1. Repository 経由でメッセージを Room に追加（シーケンス番号が振られる）
+    let seq = repository
+        .add_message(from_client_id.clone(), content.clone(), timestamp)
+        .instrument(tracing::info_span!("persist"))
+        .await
+        .map_err(|e| match e {
+            RepositoryError::Room(RoomError::MessageCapacityExceeded { capacity, .. }) => {
+                SendMessageError::MessageCapacityExceeded { capacity }
+            }
+            RepositoryError::Room(RoomError::RoomFrozen) => SendMessageError::RoomFrozen,
+            e => SendMessageError::PersistFailed(e.to_string()),
+        })?;
+
+    // 送信元のスパンに `message_id` フィールドがあれば採番結果を記録する
+    tracing::Span::current().record("message_id", seq.value());
+
+    // 投票は選択肢を付けてからブロードキャストする（届いた投票には必ず投票できる）
+    let is_poll = poll.is_some();
+    let poll = match poll {
+        Some(options) => {
+            repository
+                .attach_poll(seq, options.clone())
+                .instrument(tracing::info_span!("persist_poll"))
+                .await
+                .map_err(|e| SendMessageError::PersistFailed(e.to_string()))?;
+            Some(Poll::new(options))
+        }
+        None => None,
+    };
+
+    // 転送は転送元を記録してからブロードキャストする
+    let is_forward = forwarded_from.is_some();
+    if let Some(origin) = &forwarded_from {
+        repository
+            .mark_forwarded(seq, origin.clone())
+            .instrument(tracing::info_span!("persist_forward"))
+            .await
+            .map_err(|e| SendMessageError::PersistFailed(e.to_string()))?;
     }
+
+    // 2. ブロードキャスト対象を取得（送信者以外の全てのクライアント、投票と転送は送信者を含む）
+    let broadcast_targets = if is_poll || is_forward {
+        repository.get_all_connected_client_ids().await
+    } else {
+        broadcast_targets(repository, &from_client_id).await
+    };
+
+    // 3. MessagePusher を使ってブロードキャスト
+    let rendered = render(seq);
+    message_pusher
+        .broadcast(broadcast_targets.clone(), &rendered)
+        .instrument(tracing::info_span!(
+            "broadcast",
+            targets = broadcast_targets.len()
+        ))
+        .await
+        .map_err(|e| SendMessageError::BroadcastFailed(e.to_string()))?;
+    tracing::debug!("Pushed to {} clients", broadcast_targets.len());
+
+    let mut message = ChatMessage::new(from_client_id, content, timestamp);
+    message.seq = seq;
+    message.poll = poll;
+    message.forwarded_from = forwarded_from;
+    Ok((message, broadcast_targets, rendered))
+}
+
+/// ブロードキャスト対象のクライアント ID リストを取得
+///
+/// 送信者以外の全てのクライアント ID を返す（Domain Model）
+async fn broadcast_targets(
+    repository: &dyn RoomRepository,
+    exclude_client_id: &ClientId,
+) -> Vec<ClientId> {
+    let all_client_ids = repository.get_all_connected_client_ids().await;
+    all_client_ids
+        .into_iter()
+        .filter(|id| id != exclude_client_id)
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        domain::{MessagePushError, MessagePusher, PusherChannel, Room, RoomIdFactory, Timestamp},
-        infrastructure::repository::InMemoryRoomRepository,
+        domain::{
+            MessagePushError, MessagePusher, MessageTag, PusherChannel, Room, RoomIdFactory,
+            Timestamp,
+        },
+        infrastructure::{
+            analyzer::KeywordAnalyzer, filter::MaxLinksFilter, repository::InMemoryRoomRepository,
+        },
     };
     use engawa_shared::time::get_jst_timestamp;
     use std::sync::Arc;
-    use tokio::sync::Mutex;
 
     // Mock MessagePusher for testing
     struct MockMessagePusher;
@@ -118,6 +499,14 @@ mod tests {
             // No-op for mock
         }
 
+        async fn unregister_connection(
+            &self,
+            _client_id: &ClientId,
+            _sender: &PusherChannel,
+        ) -> usize {
+            0
+        }
+
         async fn push_to(
             &self,
             _client_id: &ClientId,
@@ -133,25 +522,72 @@ mod tests {
         ) -> Result<(), MessagePushError> {
             Ok(())
         }
+
+        async fn ping(&self) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+    }
+
+    // ブロードキャストした内容を順に記録する MessagePusher
+    struct RecordingMessagePusher {
+        broadcasts: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl MessagePusher for RecordingMessagePusher {
+        async fn register_client(&self, _client_id: ClientId, _sender: PusherChannel) {}
+
+        async fn unregister_client(&self, _client_id: &ClientId) {}
+
+        async fn unregister_connection(
+            &self,
+            _client_id: &ClientId,
+            _sender: &PusherChannel,
+        ) -> usize {
+            0
+        }
+
+        async fn push_to(
+            &self,
+            _client_id: &ClientId,
+            _content: &str,
+        ) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+
+        async fn broadcast(
+            &self,
+            _targets: Vec<ClientId>,
+            content: &str,
+        ) -> Result<(), MessagePushError> {
+            // 処理が遅いブロードキャストでも順序が入れ替わらないことを確認するため譲る
+            tokio::task::yield_now().await;
+            self.broadcasts.lock().unwrap().push(content.to_string());
+            Ok(())
+        }
+
+        async fn ping(&self) -> Result<(), MessagePushError> {
+            Ok(())
+        }
     }
 
     fn create_test_repository() -> Arc<InMemoryRoomRepository> {
-        let room = Arc::new(Mutex::new(Room::new(
+        let room = Room::new(
             RoomIdFactory::generate().unwrap(),
             Timestamp::new(get_jst_timestamp()),
-        )));
+        );
         Arc::new(InMemoryRoomRepository::new(room))
     }
 
     fn create_test_repository_with_capacity(
         message_capacity: usize,
     ) -> Arc<InMemoryRoomRepository> {
-        let room = Arc::new(Mutex::new(Room::with_capacity(
+        let room = Room::with_capacity(
             RoomIdFactory::generate().unwrap(),
             Timestamp::new(get_jst_timestamp()),
             100,
             message_capacity,
-        )));
+        );
         Arc::new(InMemoryRoomRepository::new(room))
     }
 
@@ -184,11 +620,9 @@ mod tests {
         // when (操作): alice がメッセージを送信
         let content = MessageContent::new("Hello!".to_string()).unwrap();
         let result = usecase
-            .execute(
-                alice.clone(),
-                content,
-                r#"{\"type\":\"chat\",\"client_id\":\"alice\",\"content\":\"Hello!\"}"#.to_string(),
-            )
+            .execute(alice.clone(), content, |_| {
+                r#"{"type":"chat","client_id":"alice","content":"Hello!"}"#.to_string()
+            })
             .await;
 
         // then (期待する結果):
@@ -208,6 +642,158 @@ mod tests {
         assert_eq!(room.messages[0].content.as_str(), "Hello!");
     }
 
+    #[tokio::test]
+    async fn test_send_message_rate_limited() {
+        // テスト項目: レート制限を超えた送信は RateLimited になり、メッセージ履歴に追加されない
+        // given (前提条件):
+        let repository = create_test_repository();
+        let usecase = SendMessageUseCase::new(repository.clone(), Arc::new(MockMessagePusher))
+            .with_rate_limiter(Arc::new(RateLimiter::new(1, 2)));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let send = |client_id: &ClientId| {
+            let content = MessageContent::new("Hello!".to_string()).unwrap();
+            usecase.execute(client_id.clone(), content, |_| "{}".to_string())
+        };
+
+        // when (操作): alice が続けて 3 回、bob が 1 回送信
+        let first = send(&alice).await;
+        let second = send(&alice).await;
+        let third = send(&alice).await;
+        let other_client = send(&bob).await;
+
+        // then (期待する結果):
+        assert!(first.is_ok());
+        assert!(second.is_ok());
+        assert!(matches!(
+            third,
+            Err(SendMessageError::RateLimited { retry_after_ms }) if retry_after_ms > 0 && retry_after_ms <= 1000
+        ));
+        assert!(other_client.is_ok());
+        assert_eq!(repository.get_room().await.unwrap().messages.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_rejected_by_filter() {
+        // テスト項目: フィルターが拒否したメッセージは Rejected になり、メッセージ履歴に追加されない
+        // given (前提条件):
+        let repository = create_test_repository();
+        let filters = MessageFilterChain::new(vec![Arc::new(MaxLinksFilter::new(1))]);
+        let usecase = SendMessageUseCase::new(repository.clone(), Arc::new(MockMessagePusher))
+            .with_filters(Arc::new(filters));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let send = |text: &str| {
+            let content = MessageContent::new(text.to_string()).unwrap();
+            usecase.execute(alice.clone(), content, |_| "{}".to_string())
+        };
+
+        // when (操作):
+        let passed = send("see https://example.com").await;
+        let rejected = send("https://a.example https://b.example").await;
+
+        // then (期待する結果):
+        assert!(passed.is_ok());
+        assert!(matches!(
+            rejected,
+            Err(SendMessageError::Rejected(rejection)) if rejection.filter == "max-links"
+        ));
+        assert_eq!(repository.get_room().await.unwrap().messages.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_longer_than_policy() {
+        // テスト項目: ルームのポリシーより長いメッセージは転送でも MessageTooLong になり、メッセージ履歴に追加されない
+        // given (前提条件):
+        let repository = create_test_repository();
+        let usecase = SendMessageUseCase::new(repository.clone(), Arc::new(MockMessagePusher))
+            .with_max_message_length(5);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let origin = ForwardedFrom {
+            room_id: RoomIdFactory::generate().unwrap(),
+            from: alice.clone(),
+            timestamp: Timestamp::new(1000),
+        };
+
+        // when (操作):
+        let short = usecase
+            .execute(
+                alice.clone(),
+                MessageContent::new("Hello".to_string()).unwrap(),
+                |_| "{}".to_string(),
+            )
+            .await;
+        let long = usecase
+            .execute(
+                alice.clone(),
+                MessageContent::new("Hello!".to_string()).unwrap(),
+                |_| "{}".to_string(),
+            )
+            .await;
+        let forwarded = usecase
+            .execute_forward(
+                alice,
+                MessageContent::new("Hello!".to_string()).unwrap(),
+                origin,
+                |_| "{}".to_string(),
+            )
+            .await;
+
+        // then (期待する結果):
+        assert!(short.is_ok());
+        let too_long = Err(SendMessageError::MessageTooLong { max: 5, actual: 6 });
+        assert_eq!(long, too_long);
+        assert_eq!(forwarded, too_long);
+        assert_eq!(repository.get_room().await.unwrap().messages.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_poll_broadcasts_to_sender() {
+        // テスト項目: 投票は選択肢付きで履歴に追加されて送信者にもブロードキャストされ、選択肢が 1 つの投票は送信できない
+        // given (前提条件):
+        let repository = create_test_repository();
+        let usecase = SendMessageUseCase::new(repository.clone(), Arc::new(MockMessagePusher));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        for client_id in [&alice, &bob] {
+            repository
+                .add_participant(client_id.clone(), Timestamp::new(get_jst_timestamp()))
+                .await
+                .unwrap();
+        }
+        let question = || MessageContent::new("Lunch?".to_string()).unwrap();
+        let option = |label: &str| PollOption::new(label.to_string()).unwrap();
+
+        // when (操作):
+        let targets = usecase
+            .execute_poll(
+                alice.clone(),
+                question(),
+                vec![option("Ramen"), option("Sushi")],
+                |_| "{}".to_string(),
+            )
+            .await
+            .unwrap();
+        let single = usecase
+            .execute_poll(alice.clone(), question(), vec![option("Ramen")], |_| {
+                "{}".to_string()
+            })
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(targets.len(), 2);
+        assert!(targets.contains(&alice));
+        assert!(targets.contains(&bob));
+        assert!(matches!(
+            single,
+            Err(SendMessageError::InvalidPoll { min: 2, max: 10 })
+        ));
+        let room = repository.get_room().await.unwrap();
+        assert_eq!(room.messages.len(), 1);
+        let poll = room.messages[0].poll.as_ref().unwrap();
+        assert_eq!(poll.options, vec![option("Ramen"), option("Sushi")]);
+        assert_eq!(poll.tally(), vec![0, 0]);
+    }
+
     #[tokio::test]
     async fn test_send_message_no_broadcast_targets() {
         // テスト項目: 送信者のみが接続している場合、ブロードキャスト対象は空
@@ -226,11 +812,9 @@ mod tests {
         // when (操作): alice がメッセージを送信
         let content = MessageContent::new("Hello!".to_string()).unwrap();
         let result = usecase
-            .execute(
-                alice.clone(),
-                content,
-                r#"{\"type\":\"chat\",\"client_id\":\"alice\",\"content\":\"Hello!\"}"#.to_string(),
-            )
+            .execute(alice.clone(), content, |_| {
+                r#"{"type":"chat","client_id":"alice","content":"Hello!"}"#.to_string()
+            })
             .await;
 
         // then (期待する結果):
@@ -263,36 +847,78 @@ mod tests {
         // 2件のメッセージを送信（容量いっぱい）
         let msg1 = MessageContent::new("Message 1".to_string()).unwrap();
         usecase
-            .execute(alice.clone(), msg1, r#"{"type":"chat"}"#.to_string())
+            .execute(alice.clone(), msg1, |_| r#"{"type":"chat"}"#.to_string())
             .await
             .unwrap();
 
         let msg2 = MessageContent::new("Message 2".to_string()).unwrap();
         usecase
-            .execute(alice.clone(), msg2, r#"{"type":"chat"}"#.to_string())
+            .execute(alice.clone(), msg2, |_| r#"{"type":"chat"}"#.to_string())
             .await
             .unwrap();
 
         // when (操作): 3件目のメッセージを送信
         let msg3 = MessageContent::new("Message 3".to_string()).unwrap();
         let result = usecase
-            .execute(alice.clone(), msg3, r#"{"type":"chat"}"#.to_string())
+            .execute(alice.clone(), msg3, |_| r#"{"type":"chat"}"#.to_string())
             .await;
 
         // then (期待する結果): 容量超過エラーが返される
-        assert_eq!(result, Err(SendMessageError::MessageCapacityExceeded));
+        assert_eq!(
+            result,
+            Err(SendMessageError::MessageCapacityExceeded { capacity: 2 })
+        );
 
         // Room のメッセージ履歴は2件のまま
         let room = repository.get_room().await.unwrap();
         assert_eq!(room.messages.len(), 2);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_sends_are_broadcast_in_sequence_order() {
+        // テスト項目: 複数タスクから同時に送信しても、シーケンス番号順に永続化・ブロードキャストされる
+        // given (前提条件):
+        let repository = create_test_repository();
+        let broadcasts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let usecase = Arc::new(SendMessageUseCase::new(
+            repository.clone(),
+            Arc::new(RecordingMessagePusher {
+                broadcasts: broadcasts.clone(),
+            }),
+        ));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+
+        // when (操作): 50 件を並行に送信
+        let tasks: Vec<_> = (0..50)
+            .map(|i| {
+                let usecase = usecase.clone();
+                let alice = alice.clone();
+                tokio::spawn(async move {
+                    let content = MessageContent::new(format!("Message {}", i)).unwrap();
+                    usecase
+                        .execute(alice, content, |seq| seq.to_string())
+                        .await
+                        .unwrap();
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // then (期待する結果): ブロードキャストの順序と Room のメッセージ履歴の順序が一致する
+        let expected: Vec<String> = (1..=50).map(|seq| seq.to_string()).collect();
+        assert_eq!(*broadcasts.lock().unwrap(), expected);
+        let room = repository.get_room().await.unwrap();
+        let seqs: Vec<String> = room.messages.iter().map(|m| m.seq.to_string()).collect();
+        assert_eq!(seqs, expected);
+    }
+
     #[tokio::test]
-    async fn test_get_broadcast_targets_multiple_clients() {
+    async fn test_broadcast_targets_multiple_clients() {
         // テスト項目: 複数クライアント接続時に正しいブロードキャスト対象が取得できる
         // given (前提条件):
         let repository = create_test_repository();
-        let usecase = SendMessageUseCase::new(repository.clone(), Arc::new(MockMessagePusher));
 
         // 3人のクライアントを接続
         let timestamp = get_jst_timestamp();
@@ -313,7 +939,7 @@ mod tests {
             .unwrap();
 
         // when (操作): bob を除いたブロードキャスト対象を取得
-        let result = usecase.get_broadcast_targets(&bob).await;
+        let result = broadcast_targets(repository.as_ref(), &bob).await;
 
         // then (期待する結果):
         assert_eq!(result.len(), 2);
@@ -321,4 +947,45 @@ mod tests {
         assert!(result.contains(&charlie));
         assert!(!result.contains(&bob));
     }
+
+    #[tokio::test]
+    async fn test_sent_messages_are_tagged_by_analyzer() {
+        // テスト項目: MessageAnalyzer を渡すと、送信したメッセージに分析のタグが保存される
+        // given (前提条件):
+        let repository = create_test_repository();
+        let analyzer = KeywordAnalyzer::new(["deploy".to_string()]).unwrap();
+        let usecase = SendMessageUseCase::with_analyzer(
+            repository.clone(),
+            Arc::new(MockMessagePusher),
+            Arc::new(analyzer),
+        );
+        let alice = ClientId::new("alice".to_string()).unwrap();
+
+        // when (操作):
+        for content in ["Deploy done", "lunch?"] {
+            let content = MessageContent::new(content.to_string()).unwrap();
+            usecase
+                .execute(alice.clone(), content, |_| String::new())
+                .await
+                .unwrap();
+        }
+
+        // then (期待する結果): 分析は別のタスクで行われるため、タグが保存されるまで待つ
+        let tagged = tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            loop {
+                let room = repository.get_room().await.unwrap();
+                if !room.messages[0].tags.is_empty() {
+                    break room;
+                }
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("message should be tagged");
+        assert_eq!(
+            tagged.messages[0].tags,
+            vec![MessageTag::new("keyword:deploy".to_string()).unwrap()]
+        );
+        assert!(tagged.messages[1].tags.is_empty());
+    }
 }
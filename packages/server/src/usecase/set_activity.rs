@@ -0,0 +1,133 @@
+//! UseCase: アクティビティ設定処理
+//!
+//! 参加者が公開するアクティビティ（例: `reviewing PR #42`）を記録し、送信者を含むルームの
+//! 全ての参加者にブロードキャストします。
+//!
+//! ## 設計ノート
+//!
+//! アクティビティは参加者のエンティティに記録し、参加者一覧（`room-connected` と
+//! ルーム詳細 API）にも含めます。参加者と同じく永続化せず、再接続すると消えます。
+//! 長さの制限は値オブジェクト `Activity` が行います。
+
+use std::sync::Arc;
+
+use crate::domain::{Activity, ClientId, MessagePusher, RepositoryError, RoomRepository};
+
+/// アクティビティ設定のエラー
+#[derive(Debug)]
+pub enum SetActivityError {
+    /// クライアントがルームにいない
+    NotInRoom,
+    /// Repository エラー
+    RepositoryError(RepositoryError),
+}
+
+impl From<RepositoryError> for SetActivityError {
+    fn from(e: RepositoryError) -> Self {
+        match e {
+            RepositoryError::ParticipantNotFound(_) => SetActivityError::NotInRoom,
+            e => SetActivityError::RepositoryError(e),
+        }
+    }
+}
+
+/// アクティビティ設定のユースケース
+pub struct SetActivityUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+}
+
+impl SetActivityUseCase {
+    /// 新しい SetActivityUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+    ) -> Self {
+        Self {
+            repository,
+            message_pusher,
+        }
+    }
+
+    /// アクティビティの設定を実行
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - アクティビティを設定するクライアントの ID（Domain Model）
+    /// * `activity` - 新しいアクティビティ（`None` の場合は消去）
+    /// * `render` - 設定したアクティビティからブロードキャストする JSON メッセージを作成する関数
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - 設定成功
+    /// * `Err(SetActivityError)` - 設定失敗
+    pub async fn execute(
+        &self,
+        client_id: &ClientId,
+        activity: Option<Activity>,
+        render: impl FnOnce(Option<&Activity>) -> String,
+    ) -> Result<(), SetActivityError> {
+        let message = render(activity.as_ref());
+        self.repository.set_activity(client_id, activity).await?;
+
+        let targets = self.repository.get_all_connected_client_ids().await;
+        if let Err(e) = self.message_pusher.broadcast(targets, &message).await {
+            // 参加者一覧には記録済みのアクティビティが含まれる
+            tracing::warn!("Failed to broadcast the activity of '{}': {}", client_id, e);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{Room, RoomIdFactory, Timestamp},
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+        },
+    };
+
+    fn client_id(name: &str) -> ClientId {
+        ClientId::new(name.to_string()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_execute_sets_and_clears_activity() {
+        // テスト項目: 参加者のアクティビティを設定・消去でき、ルームにいないクライアントは設定できない
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Default::default()));
+        let usecase = SetActivityUseCase::new(repository.clone(), message_pusher);
+        repository
+            .add_participant(client_id("alice"), Timestamp::new(2000))
+            .await
+            .unwrap();
+        let activity = Activity::new("reviewing PR #42".to_string()).unwrap();
+        let render = |_: Option<&Activity>| "{}".to_string();
+
+        // when (操作):
+        usecase
+            .execute(&client_id("alice"), Some(activity.clone()), render)
+            .await
+            .unwrap();
+        let set = repository.get_participants().await[0].activity.clone();
+        usecase
+            .execute(&client_id("alice"), None, render)
+            .await
+            .unwrap();
+        let cleared = repository.get_participants().await[0].activity.clone();
+        let stranger = usecase
+            .execute(&client_id("bob"), Some(activity.clone()), render)
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(set, Some(activity));
+        assert_eq!(cleared, None);
+        assert!(matches!(stranger, Err(SetActivityError::NotInRoom)));
+    }
+}
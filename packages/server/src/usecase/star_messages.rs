@@ -0,0 +1,224 @@
+//! UseCase: メッセージのスター（保存）処理
+//!
+//! クライアントが後で見返すためにメッセージにスターを付け・外し、スターを付けたメッセージの
+//! 一覧を取得します。スターはクライアントとメッセージ（ルームとシーケンス番号）の組ごとに
+//! StarRepository に保存します。
+//!
+//! ## 設計ノート
+//!
+//! スターを付けられるのは履歴にあるメッセージのみです。スターを付けた後に削除・容量超過などで
+//! 履歴から消えたメッセージ（ルームごと削除された場合を含む）は、一覧を取得した時に除きます
+//! （スター自体は残る）。
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::domain::{
+    ChatMessage, ClientId, RepositoryError, Room, RoomId, RoomRepository, SequenceNumber, Star,
+    StarRepository, Timestamp,
+};
+
+/// 1 つのクライアントが付けられるスターの上限
+pub const MAX_STARS_PER_CLIENT: usize = 1000;
+
+/// スターを付けたメッセージ
+#[derive(Debug, Clone)]
+pub struct StarredMessage {
+    pub room_id: RoomId,
+    pub message: ChatMessage,
+    pub starred_at: Timestamp,
+}
+
+/// メッセージのスターのエラー
+#[derive(Debug, PartialEq)]
+pub enum StarMessageError {
+    /// メッセージが履歴に無い
+    MessageNotFound,
+    /// スターの数が上限に達している
+    TooManyStars,
+    /// Repository エラー
+    RepositoryError,
+}
+
+impl From<RepositoryError> for StarMessageError {
+    fn from(e: RepositoryError) -> Self {
+        match e {
+            RepositoryError::RoomNotFound => StarMessageError::MessageNotFound,
+            _ => StarMessageError::RepositoryError,
+        }
+    }
+}
+
+/// メッセージのスターのユースケース
+pub struct StarMessagesUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// StarRepository（スターの保存先の抽象化）
+    stars: Arc<dyn StarRepository>,
+}
+
+impl StarMessagesUseCase {
+    /// 新しい StarMessagesUseCase を作成
+    pub fn new(repository: Arc<dyn RoomRepository>, stars: Arc<dyn StarRepository>) -> Self {
+        Self { repository, stars }
+    }
+
+    /// メッセージにスターを付ける
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - スターを付けるクライアントの ID（Domain Model）
+    /// * `room_id` - メッセージのルームの ID（Domain Model）
+    /// * `seq` - メッセージのシーケンス番号（Domain Model）
+    /// * `now` - スターを付けた日時
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - スターを付けた場合は `true`、既に付いていた場合は `false`
+    /// * `Err(StarMessageError)` - スターを付けられなかった
+    pub async fn star(
+        &self,
+        client_id: ClientId,
+        room_id: &RoomId,
+        seq: SequenceNumber,
+        now: Timestamp,
+    ) -> Result<bool, StarMessageError> {
+        let room = self.repository.get_room_snapshot(room_id).await?;
+        if room.message(seq).is_none() {
+            return Err(StarMessageError::MessageNotFound);
+        }
+        let stars = self.stars.list(&client_id).await?;
+        if stars
+            .iter()
+            .any(|star| &star.room_id == room_id && star.seq == seq)
+        {
+            return Ok(false);
+        }
+        if stars.len() >= MAX_STARS_PER_CLIENT {
+            return Err(StarMessageError::TooManyStars);
+        }
+        Ok(self
+            .stars
+            .add(Star {
+                client_id,
+                room_id: room_id.clone(),
+                seq,
+                starred_at: now,
+            })
+            .await?)
+    }
+
+    /// メッセージのスターを外す
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - スターを外した場合は `true`、付いていなかった場合は `false`
+    /// * `Err(StarMessageError)` - Repository エラー
+    pub async fn unstar(
+        &self,
+        client_id: &ClientId,
+        room_id: &RoomId,
+        seq: SequenceNumber,
+    ) -> Result<bool, StarMessageError> {
+        Ok(self.stars.remove(client_id, room_id, seq).await?)
+    }
+
+    /// クライアントがスターを付けたメッセージを、スターを付けた順に取得
+    ///
+    /// 履歴から消えたメッセージは含めない。
+    pub async fn list(
+        &self,
+        client_id: &ClientId,
+    ) -> Result<Vec<StarredMessage>, StarMessageError> {
+        let stars = self.stars.list(client_id).await?;
+        let mut rooms: HashMap<RoomId, Option<Arc<Room>>> = HashMap::new();
+        let mut starred = Vec::with_capacity(stars.len());
+        for star in stars {
+            let room = match rooms.get(&star.room_id) {
+                Some(room) => room.clone(),
+                None => {
+                    let room = match self.repository.get_room_snapshot(&star.room_id).await {
+                        Ok(room) => Some(room),
+                        Err(RepositoryError::RoomNotFound) => None,
+                        Err(_) => return Err(StarMessageError::RepositoryError),
+                    };
+                    rooms.insert(star.room_id.clone(), room.clone());
+                    room
+                }
+            };
+            if let Some(message) = room.as_ref().and_then(|room| room.message(star.seq)) {
+                starred.push(StarredMessage {
+                    room_id: star.room_id,
+                    message: message.clone(),
+                    starred_at: star.starred_at,
+                });
+            }
+        }
+        Ok(starred)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{MessageContent, RoomIdFactory},
+        infrastructure::repository::{InMemoryRoomRepository, InMemoryStarRepository},
+    };
+
+    #[tokio::test]
+    async fn test_star_and_list_messages() {
+        // テスト項目: 履歴にあるメッセージにスターを付けて一覧に取得でき、無いメッセージ・消えたメッセージは含まれない
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let room_id = room.id.clone();
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let mut seqs = Vec::new();
+        for text in ["first", "second"] {
+            let seq = repository
+                .add_message(
+                    ClientId::new("bob".to_string()).unwrap(),
+                    MessageContent::new(text.to_string()).unwrap(),
+                    Timestamp::new(2000),
+                )
+                .await
+                .unwrap();
+            seqs.push(seq);
+        }
+        let usecase =
+            StarMessagesUseCase::new(repository.clone(), Arc::new(InMemoryStarRepository::new()));
+
+        // when (操作):
+        let starred = usecase
+            .star(alice.clone(), &room_id, seqs[1], Timestamp::new(3000))
+            .await;
+        let again = usecase
+            .star(alice.clone(), &room_id, seqs[1], Timestamp::new(4000))
+            .await;
+        usecase
+            .star(alice.clone(), &room_id, seqs[0], Timestamp::new(5000))
+            .await
+            .unwrap();
+        let missing = usecase
+            .star(
+                alice.clone(),
+                &room_id,
+                SequenceNumber::new(99),
+                Timestamp::new(5000),
+            )
+            .await;
+        repository.delete_message(seqs[0]).await.unwrap();
+        let list = usecase.list(&alice).await.unwrap();
+
+        // then (期待する結果):
+        assert_eq!(starred, Ok(true));
+        assert_eq!(again, Ok(false));
+        assert_eq!(missing, Err(StarMessageError::MessageNotFound));
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].room_id, room_id);
+        assert_eq!(list[0].message.content.as_str(), "second");
+        assert_eq!(list[0].starred_at, Timestamp::new(3000));
+        assert_eq!(usecase.unstar(&alice, &room_id, seqs[1]).await, Ok(true));
+        assert!(usecase.list(&alice).await.unwrap().is_empty());
+    }
+}
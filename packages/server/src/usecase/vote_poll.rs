@@ -0,0 +1,161 @@
+//! UseCase: 投票への投票処理
+//!
+//! 投票のメッセージにクライアントの票を入れ、集計した投票を送信者を含むルームの全ての参加者に
+//! ブロードキャストします。
+//!
+//! ## 設計ノート
+//!
+//! 票はメッセージのエンティティに記録し、集計はサーバーで行います。1 つの投票に 1 クライアント
+//! 1 票で、投票済みのクライアントの票は新しい選択肢に移ります。
+//! 集計のブロードキャストが票の順と入れ替わらないよう、票の記録とブロードキャストは
+//! ルームごとに 1 つずつ行います（古い集計が新しい集計の後に届かない）。
+
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use crate::domain::{
+    ClientId, MessagePusher, Poll, RepositoryError, RoomError, RoomRepository, SequenceNumber,
+};
+
+/// 投票のエラー
+#[derive(Debug)]
+pub enum VotePollError {
+    /// メッセージが履歴に無い
+    MessageNotFound,
+    /// メッセージが投票でない
+    NotAPoll,
+    /// 投票に無い選択肢
+    UnknownOption,
+    /// Repository エラー
+    RepositoryError(RepositoryError),
+}
+
+impl From<RepositoryError> for VotePollError {
+    fn from(e: RepositoryError) -> Self {
+        match e {
+            RepositoryError::MessageNotFound(_) => VotePollError::MessageNotFound,
+            RepositoryError::Room(RoomError::NotAPoll { .. }) => VotePollError::NotAPoll,
+            RepositoryError::Room(RoomError::UnknownPollOption { .. }) => {
+                VotePollError::UnknownOption
+            }
+            e => VotePollError::RepositoryError(e),
+        }
+    }
+}
+
+/// 投票への投票のユースケース
+pub struct VotePollUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+    /// 票の記録とブロードキャストを 1 つずつ行うためのロック
+    voting: Mutex<()>,
+}
+
+impl VotePollUseCase {
+    /// 新しい VotePollUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+    ) -> Self {
+        Self {
+            repository,
+            message_pusher,
+            voting: Mutex::new(()),
+        }
+    }
+
+    /// 投票を実行
+    ///
+    /// # Arguments
+    ///
+    /// * `seq` - 投票のメッセージのシーケンス番号（Domain Model）
+    /// * `voter` - 投票するクライアントの ID（Domain Model）
+    /// * `option` - 選ぶ選択肢の位置（0 始まり）
+    /// * `render` - 集計した投票からブロードキャストする JSON メッセージを作成する関数
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Poll)` - 票を反映した投票
+    /// * `Err(VotePollError)` - 投票失敗
+    pub async fn execute(
+        &self,
+        seq: SequenceNumber,
+        voter: ClientId,
+        option: usize,
+        render: impl FnOnce(&Poll) -> String,
+    ) -> Result<Poll, VotePollError> {
+        let _voting = self.voting.lock().await;
+        let poll = self.repository.vote(seq, voter, option).await?;
+
+        let targets = self.repository.get_all_connected_client_ids().await;
+        if let Err(e) = self.message_pusher.broadcast(targets, &render(&poll)).await {
+            // 票は記録済みのため、集計は次の票で届く
+            tracing::warn!("Failed to broadcast poll-updated for seq {}: {}", seq, e);
+        }
+        Ok(poll)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{MessageContent, PollOption, Room, RoomIdFactory, Timestamp},
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+        },
+    };
+
+    fn client_id(name: &str) -> ClientId {
+        ClientId::new(name.to_string()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_execute_tallies_votes() {
+        // テスト項目: 票は集計に反映されて投票し直すと移り、投票でないメッセージと無い選択肢には投票できない
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(1000));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Default::default()));
+        let usecase = VotePollUseCase::new(repository.clone(), message_pusher);
+        let message = |content: &str| {
+            repository.add_message(
+                client_id("alice"),
+                MessageContent::new(content.to_string()).unwrap(),
+                Timestamp::new(2000),
+            )
+        };
+        let poll_seq = message("Lunch?").await.unwrap();
+        let chat_seq = message("Hello").await.unwrap();
+        repository
+            .attach_poll(
+                poll_seq,
+                vec![
+                    PollOption::new("Ramen".to_string()).unwrap(),
+                    PollOption::new("Sushi".to_string()).unwrap(),
+                ],
+            )
+            .await
+            .unwrap();
+
+        // when (操作):
+        let vote = |voter: &str, seq: SequenceNumber, option: usize| {
+            usecase.execute(seq, client_id(voter), option, |_| "{}".to_string())
+        };
+        vote("alice", poll_seq, 0).await.unwrap();
+        vote("bob", poll_seq, 0).await.unwrap();
+        let changed = vote("bob", poll_seq, 1).await.unwrap();
+        let not_a_poll = vote("bob", chat_seq, 0).await;
+        let unknown = vote("bob", poll_seq, 2).await;
+        let missing = vote("bob", SequenceNumber::new(99), 0).await;
+
+        // then (期待する結果):
+        assert_eq!(changed.tally(), vec![1, 1]);
+        assert!(matches!(not_a_poll, Err(VotePollError::NotAPoll)));
+        assert!(matches!(unknown, Err(VotePollError::UnknownOption)));
+        assert!(matches!(missing, Err(VotePollError::MessageNotFound)));
+    }
+}
@@ -0,0 +1,292 @@
+//! Test fixtures for integration tests.
+//!
+//! This module provides common test helpers for both WebSocket and HTTP API tests.
+//! Servers run in the test process on an ephemeral port, and clients are WebSocket
+//! connections whose received frames the tests assert on.
+
+#![allow(dead_code)]
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::{net::TcpStream, task::JoinHandle};
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{self, Message},
+};
+
+use engawa_server::{
+    domain::{
+        ClientId, DEFAULT_MAX_ROOMS_PER_CLIENT, MessagePusher, Room, RoomIdFactory, RoomRepository,
+        Timestamp,
+    },
+    infrastructure::{
+        auth::{AccessTokens, Credentials},
+        message_pusher::WebSocketMessagePusher,
+        protocol::{Feature, PROTOCOL_VERSION},
+        repository::InMemoryRoomRepository,
+    },
+    ui::{Authentication, Server, ShutdownToken, run_server},
+    usecase::{
+        CheckHealthUseCase, ConnectParticipantUseCase, CreateRoomUseCase,
+        DEFAULT_HEALTH_CHECK_TIMEOUT, DEFAULT_HISTORY_REPLAY, DEFAULT_MAX_ROOMS,
+        DisconnectParticipantUseCase, GetMessageHistoryUseCase, GetRoomDetailUseCase,
+        GetRoomMessagesUseCase, GetRoomStateUseCase, GetRoomsUseCase, JoinRoomUseCase,
+        RenameParticipantUseCase, SearchMessagesUseCase, SendMessageUseCase, SetActivityUseCase,
+    },
+};
+use engawa_shared::time::get_jst_timestamp;
+
+/// How long to wait for a frame the test expects
+const RECV_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Secret signing the access tokens of servers with authentication
+const JWT_SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+/// Enable authentication on the server (nobody can log in; use [`access_token`])
+pub fn with_auth(server: Server) -> Server {
+    server.with_auth(Authentication::new(Credentials::default(), access_tokens()))
+}
+
+/// Access token of `client_id` accepted by servers with authentication
+pub fn access_token(client_id: &str) -> String {
+    let client_id = ClientId::new(client_id.to_string()).expect("Invalid client_id");
+    access_tokens().issue(&client_id, std::time::SystemTime::now())
+}
+
+fn access_tokens() -> AccessTokens {
+    AccessTokens::new(JWT_SECRET, Duration::from_secs(60))
+}
+
+/// Chat server running in the test process with an in-memory default room
+///
+/// Rooms can be created with `POST /api/v1/rooms`.
+pub struct TestServer {
+    addr: SocketAddr,
+    shutdown: ShutdownToken,
+    task: JoinHandle<()>,
+}
+
+impl TestServer {
+    /// Start a test server on an ephemeral port
+    pub async fn start() -> Self {
+        Self::start_with(|usecase| usecase, |server, _| server).await
+    }
+
+    /// Start a test server with the default room's SendMessageUseCase and the server adjusted
+    ///
+    /// `configure` also receives the repository of the rooms, for the use cases it adds.
+    pub async fn start_with(
+        send_message: impl FnOnce(SendMessageUseCase) -> SendMessageUseCase,
+        configure: impl FnOnce(Server, Arc<dyn RoomRepository>) -> Server,
+    ) -> Self {
+        let room = Room::new(
+            RoomIdFactory::generate().expect("Failed to generate RoomId"),
+            Timestamp::new(get_jst_timestamp()),
+        );
+        let repository: Arc<dyn RoomRepository> = Arc::new(InMemoryRoomRepository::new(room));
+        let message_pusher: Arc<dyn MessagePusher> =
+            Arc::new(WebSocketMessagePusher::new(Default::default()));
+
+        let server = Server::new(
+            Arc::new(ConnectParticipantUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+            )),
+            Arc::new(DisconnectParticipantUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+            )),
+            Arc::new(send_message(SendMessageUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+            ))),
+            Arc::new(GetRoomStateUseCase::new(repository.clone())),
+            Arc::new(GetRoomsUseCase::new(repository.clone())),
+            Arc::new(GetRoomDetailUseCase::new(repository.clone())),
+            Arc::new(GetRoomMessagesUseCase::new(repository.clone())),
+        )
+        .with_health_check(CheckHealthUseCase::new(
+            repository.clone(),
+            message_pusher.clone(),
+            DEFAULT_HEALTH_CHECK_TIMEOUT,
+        ))
+        .with_activities(SetActivityUseCase::new(
+            repository.clone(),
+            message_pusher.clone(),
+        ))
+        .with_display_names(RenameParticipantUseCase::new(
+            repository.clone(),
+            message_pusher.clone(),
+        ))
+        .with_rooms(
+            CreateRoomUseCase::new(repository.clone(), DEFAULT_MAX_ROOMS),
+            Arc::new(JoinRoomUseCase::new(
+                repository.clone(),
+                DEFAULT_MAX_ROOMS_PER_CLIENT,
+                |_| Arc::new(WebSocketMessagePusher::new(Default::default())),
+            )),
+        )
+        .with_message_search(SearchMessagesUseCase::new(repository.clone()))
+        .with_message_history(GetMessageHistoryUseCase::new(
+            repository.clone(),
+            DEFAULT_HISTORY_REPLAY,
+        ));
+        let server = configure(server, repository);
+        let shutdown = server.shutdown_token();
+
+        let (addr, task) = run_server(server, "127.0.0.1", 0)
+            .await
+            .expect("Failed to start server");
+        TestServer {
+            addr,
+            shutdown,
+            task,
+        }
+    }
+
+    /// Get the WebSocket URL for this server
+    pub fn url(&self) -> String {
+        format!("ws://{}/ws", self.addr)
+    }
+
+    /// Get the base HTTP URL for this server
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Create a room with the given slug and return its ID
+    pub async fn create_room(&self, slug: &str) -> String {
+        let response = reqwest::Client::new()
+            .post(format!("{}/api/v1/rooms?slug={}", self.base_url(), slug))
+            .send()
+            .await
+            .expect("Failed to create room");
+        assert_eq!(response.status(), 201);
+        let room: Value = response.json().await.expect("Failed to parse room");
+        room["id"]
+            .as_str()
+            .expect("Room should have an ID")
+            .to_string()
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        // Stop the server when the test ends
+        self.shutdown.trigger();
+        self.task.abort();
+    }
+}
+
+/// WebSocket client connected to a test server
+///
+/// Connecting sends `hello`, like the chat client does, and waits for `hello-ack`.
+pub struct TestWsClient {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    client_id: String,
+}
+
+impl TestWsClient {
+    /// Connect to the server with the given URL and client_id
+    ///
+    /// # Errors
+    ///
+    /// Returns the handshake error if the server rejects the connection (e.g. HTTP 409 for a
+    /// client_id already connected).
+    pub async fn connect(url: &str, client_id: &str) -> Result<Self, tungstenite::Error> {
+        let features: Vec<&str> = Feature::ALL.iter().map(|f| f.as_str()).collect();
+        let (client, _) = Self::connect_with_features(url, client_id, &features).await?;
+        Ok(client)
+    }
+
+    /// Connect announcing only the given features in `hello`
+    ///
+    /// Returns the client and the `hello-ack` of the server.
+    pub async fn connect_with_features(
+        url: &str,
+        client_id: &str,
+        features: &[&str],
+    ) -> Result<(Self, Value), tungstenite::Error> {
+        // The URL may already have query parameters (e.g. `?display_name=`)
+        let separator = if url.contains('?') { '&' } else { '?' };
+        let (stream, _) =
+            connect_async(format!("{}{}client_id={}", url, separator, client_id)).await?;
+        let mut client = TestWsClient {
+            stream,
+            client_id: client_id.to_string(),
+        };
+        client
+            .send_json(&json!({
+                "type": "hello",
+                "protocol_version": PROTOCOL_VERSION,
+                "features": features,
+            }))
+            .await;
+        let ack = client.expect_type("hello-ack").await;
+        Ok((client, ack))
+    }
+
+    /// Send a JSON message to the server
+    pub async fn send_json(&mut self, message: &Value) {
+        self.stream
+            .send(Message::Text(message.to_string().into()))
+            .await
+            .expect("Failed to send message");
+    }
+
+    /// Send a chat message from this client
+    pub async fn send_chat(&mut self, content: &str) {
+        let message = json!({
+            "type": "chat",
+            "client_id": self.client_id,
+            "content": content,
+            "timestamp": get_jst_timestamp(),
+        });
+        self.send_json(&message).await;
+    }
+
+    /// Receive the next JSON message from the server
+    ///
+    /// Panics if no text frame arrives in time or the connection is closed.
+    pub async fn recv_json(&mut self) -> Value {
+        loop {
+            let frame = tokio::time::timeout(RECV_TIMEOUT, self.stream.next())
+                .await
+                .expect("Timed out waiting for a message")
+                .expect("Connection closed")
+                .expect("Failed to read from the connection");
+            match frame {
+                Message::Text(text) => {
+                    return serde_json::from_str(&text).expect("Message is not JSON");
+                }
+                Message::Close(frame) => panic!("Connection closed by the server: {:?}", frame),
+                // Pings are answered by tungstenite
+                _ => continue,
+            }
+        }
+    }
+
+    /// Receive messages until one of the given type arrives, and return it
+    pub async fn expect_type(&mut self, r#type: &str) -> Value {
+        loop {
+            let message = self.recv_json().await;
+            if message["type"] == r#type {
+                return message;
+            }
+        }
+    }
+
+    /// Assert that no message arrives within `wait`
+    pub async fn expect_silence(&mut self, wait: Duration) {
+        if let Ok(Some(Ok(frame))) = tokio::time::timeout(wait, self.stream.next()).await {
+            panic!("Unexpected frame: {:?}", frame);
+        }
+    }
+
+    /// Close the connection
+    pub async fn close(mut self) {
+        let _ = self.stream.close(None).await;
+    }
+}
@@ -0,0 +1,369 @@
+//! HTTP API integration tests.
+//!
+//! Tests for REST API endpoints (health check, room list, room details, search, versioning).
+
+use std::time::Duration;
+
+use engawa_server::usecase::{CreateRoomUseCase, DEFAULT_MAX_ROOMS, ManageBreakoutsUseCase};
+
+mod fixtures;
+use fixtures::{TestServer, TestWsClient, access_token, with_auth};
+
+#[tokio::test]
+async fn test_health_endpoint() {
+    // テスト項目: /api/health エンドポイントが正常に動作する
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let client = reqwest::Client::new();
+
+    // when (操作):
+    let response = client
+        .get(format!("{}/api/health", server.base_url()))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // then (期待する結果):
+    assert_eq!(response.status(), 200);
+
+    let body: serde_json::Value = response.json().await.expect("Failed to parse JSON");
+    assert_eq!(body["status"], "ok");
+}
+
+#[tokio::test]
+async fn test_rooms_list_endpoint() {
+    // テスト項目: /api/rooms エンドポイントがルーム一覧を返す
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let client = reqwest::Client::new();
+
+    // when (操作):
+    let response = client
+        .get(format!("{}/api/rooms", server.base_url()))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // then (期待する結果):
+    assert_eq!(response.status(), 200);
+
+    let body: serde_json::Value = response.json().await.expect("Failed to parse JSON");
+    assert!(
+        body["rooms"].is_array(),
+        "Response should be a page of rooms"
+    );
+
+    // デフォルトでは1つのルームが存在する
+    let rooms = body["rooms"].as_array().unwrap();
+    assert_eq!(rooms.len(), 1);
+    assert_eq!(body["total"], 1);
+
+    // ルームの構造を確認
+    let room = &rooms[0];
+    let room_id = room["id"].as_str().expect("room id should be a string");
+
+    // UUID v4 形式であることを確認（36文字、ハイフン含む）
+    assert_eq!(
+        room_id.len(),
+        36,
+        "Room ID should be UUID format (36 chars)"
+    );
+    assert!(
+        uuid::Uuid::parse_str(room_id).is_ok(),
+        "Room ID should be valid UUID"
+    );
+
+    assert!(room["participants"].is_array());
+    assert!(room["created_at"].is_string());
+    assert_eq!(room["participant_count"], 0);
+    assert_eq!(room["message_count"], 0);
+    assert!(room["last_activity_at"].is_string());
+}
+
+#[tokio::test]
+async fn test_room_detail_endpoint_success() {
+    // テスト項目: /api/rooms/:room_id エンドポイントが正常にルーム詳細を返す
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let client = reqwest::Client::new();
+
+    // 実際の room_id を取得
+    let rooms_response = client
+        .get(format!("{}/api/rooms", server.base_url()))
+        .send()
+        .await
+        .expect("Failed to get rooms");
+    let rooms: serde_json::Value = rooms_response
+        .json()
+        .await
+        .expect("Failed to parse rooms JSON");
+    let room_id = rooms["rooms"][0]["id"]
+        .as_str()
+        .expect("room id should exist");
+
+    // when (操作):
+    let response = client
+        .get(format!("{}/api/rooms/{}", server.base_url(), room_id))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // then (期待する結果):
+    assert_eq!(response.status(), 200);
+
+    let body: serde_json::Value = response.json().await.expect("Failed to parse JSON");
+    assert_eq!(body["id"], room_id);
+    assert!(body["participants"].is_array());
+    assert!(body["created_at"].is_string());
+
+    // participants の各要素が client_id と connected_at を持つ
+    let participants = body["participants"].as_array().unwrap();
+    for participant in participants {
+        assert!(participant["client_id"].is_string());
+        assert!(participant["connected_at"].is_string());
+    }
+}
+
+#[tokio::test]
+async fn test_room_search_endpoint() {
+    // テスト項目: /api/v1/rooms/:room_id/search エンドポイントが検索語を含むメッセージを新しい順に返す
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let client = reqwest::Client::new();
+    let mut alice = TestWsClient::connect(&server.url(), "alice")
+        .await
+        .expect("Failed to connect alice");
+    let connected = alice.expect_type("room-connected").await;
+    let room_id = connected["room_id"].as_str().expect("room id should exist");
+    for content in ["Deploy at 10", "lunch?", "deploy done"] {
+        alice.send_chat(content).await;
+    }
+    alice.expect_silence(Duration::from_millis(200)).await;
+
+    // when (操作):
+    let response = client
+        .get(format!(
+            "{}/api/v1/rooms/{}/search",
+            server.base_url(),
+            room_id
+        ))
+        .query(&[("q", "DEPLOY")])
+        .send()
+        .await
+        .expect("Failed to send request");
+    let empty = client
+        .get(format!(
+            "{}/api/v1/rooms/{}/search?q=",
+            server.base_url(),
+            room_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // then (期待する結果):
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.expect("Failed to parse JSON");
+    assert_eq!(body["query"], "DEPLOY");
+    let found: Vec<_> = body["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| {
+            (
+                message["seq"].as_u64().unwrap(),
+                message["content"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(found, vec![(3, "deploy done"), (1, "Deploy at 10")]);
+    assert_eq!(empty.status(), 400);
+}
+
+#[tokio::test]
+async fn test_room_detail_endpoint_not_found() {
+    // テスト項目: /api/rooms/:room_id エンドポイントが存在しないルームに対して404を返す
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let client = reqwest::Client::new();
+
+    // 存在しない UUID を使用
+    let nonexistent_uuid = "00000000-0000-0000-0000-000000000000";
+
+    // when (操作):
+    let response = client
+        .get(format!(
+            "{}/api/rooms/{}",
+            server.base_url(),
+            nonexistent_uuid
+        ))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // then (期待する結果):
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_openapi_endpoint() {
+    // テスト項目: /api/v1/openapi.json が REST API の OpenAPI ドキュメントを返し、/api/v1/docs が Swagger UI を返す
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let client = reqwest::Client::new();
+
+    // when (操作):
+    let response = client
+        .get(format!("{}/api/v1/openapi.json", server.base_url()))
+        .send()
+        .await
+        .expect("Failed to send request");
+    let docs = client
+        .get(format!("{}/api/v1/docs", server.base_url()))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // then (期待する結果):
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.expect("Failed to parse JSON");
+    assert_eq!(body["openapi"], "3.1.0");
+    assert_eq!(body["servers"][0]["url"], "/api/v1");
+    for path in [
+        "/health",
+        "/rooms",
+        "/rooms/{room_id}",
+        "/admin/rooms/{room_id}/freeze",
+    ] {
+        assert!(body["paths"][path].is_object(), "{}", path);
+    }
+    assert_eq!(
+        body["paths"]["/rooms"]["get"]["responses"]["200"]["content"]["application/json"]["schema"]
+            ["$ref"],
+        "#/components/schemas/RoomsPageDto"
+    );
+    assert!(body["components"]["schemas"]["RoomsPageDto"].is_object());
+    assert_eq!(docs.status(), 200);
+    assert!(
+        docs.text()
+            .await
+            .expect("Failed to read body")
+            .contains("openapi.json")
+    );
+}
+
+#[tokio::test]
+async fn test_versioned_endpoint() {
+    // テスト項目: /api/v1 配下のエンドポイントが API-Version ヘッダー付きで応答する
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let client = reqwest::Client::new();
+
+    // when (操作):
+    let response = client
+        .get(format!("{}/api/v1/rooms", server.base_url()))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // then (期待する結果):
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["api-version"], "1");
+    assert!(response.headers().get("deprecation").is_none());
+}
+
+#[tokio::test]
+async fn test_legacy_endpoint_is_deprecated_alias() {
+    // テスト項目: 旧パスは v1 の互換エイリアスとして Deprecation / Link ヘッダー付きで応答する
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let client = reqwest::Client::new();
+
+    // when (操作):
+    let response = client
+        .get(format!("{}/api/rooms", server.base_url()))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // then (期待する結果):
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["deprecation"], "true");
+    assert_eq!(
+        response.headers()["link"],
+        "</api/v1/rooms>; rel=\"successor-version\""
+    );
+}
+
+#[tokio::test]
+async fn test_unsupported_api_version() {
+    // テスト項目: 提供できないバージョンを要求すると 406 が返される
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let client = reqwest::Client::new();
+
+    // when (操作):
+    let response = client
+        .get(format!("{}/api/v1/rooms", server.base_url()))
+        .header("accept-version", "2")
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    // then (期待する結果):
+    assert_eq!(response.status(), 406);
+    let body: serde_json::Value = response.json().await.expect("Failed to parse JSON");
+    assert_eq!(body["supported_versions"], serde_json::json!([1]));
+}
+
+#[tokio::test]
+async fn test_breakouts_require_access_token() {
+    // テスト項目: 認証が有効な場合、ブレイクアウトの一覧・作成にはアクセストークンが必要
+    // given (前提条件):
+    let server = TestServer::start_with(
+        |usecase| usecase,
+        |server, repository| {
+            let create_room = CreateRoomUseCase::new(repository.clone(), DEFAULT_MAX_ROOMS);
+            with_auth(server).with_breakouts(ManageBreakoutsUseCase::new(repository, create_room))
+        },
+    )
+    .await;
+    let client = reqwest::Client::new();
+    let rooms: serde_json::Value = client
+        .get(format!("{}/api/v1/rooms", server.base_url()))
+        .bearer_auth(access_token("alice"))
+        .send()
+        .await
+        .expect("Failed to send request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    let url = format!(
+        "{}/api/v1/rooms/{}/breakouts",
+        server.base_url(),
+        rooms["rooms"][0]["id"]
+            .as_str()
+            .expect("Room should have an ID")
+    );
+    let breakout = serde_json::json!({"name": "Side", "members": ["alice"]});
+
+    // when (操作):
+    let list = client.get(&url).send().await.expect("Failed to send");
+    let create = client
+        .post(&url)
+        .json(&breakout)
+        .send()
+        .await
+        .expect("Failed to send");
+    let authorized = client
+        .get(&url)
+        .bearer_auth(access_token("alice"))
+        .send()
+        .await
+        .expect("Failed to send");
+
+    // then (期待する結果):
+    assert_eq!(list.status(), 401);
+    assert_eq!(create.status(), 401);
+    assert_eq!(authorized.status(), 200);
+}
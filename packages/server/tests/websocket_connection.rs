@@ -0,0 +1,172 @@
+//! Connection management integration tests.
+//!
+//! Tests for server startup, client connection, and basic connection management.
+
+mod fixtures;
+use engawa_server::ui::ConnectChallenge;
+use fixtures::{TestServer, TestWsClient, access_token, with_auth};
+
+#[tokio::test]
+async fn test_server_starts_successfully() {
+    // テスト項目: サーバーが正常に起動する
+    // given (前提条件):
+    let server = TestServer::start().await;
+
+    // when (操作):
+    let response = reqwest::get(format!("{}/api/health", server.base_url()))
+        .await
+        .expect("Failed to send request");
+
+    // then (期待する結果):
+    assert!(response.status().is_success());
+}
+
+#[tokio::test]
+async fn test_client_connects_to_server() {
+    // テスト項目: クライアントがサーバーに接続し、自分を含む参加者一覧を受け取る
+    // given (前提条件):
+    let server = TestServer::start().await;
+
+    // when (操作):
+    let mut alice = TestWsClient::connect(&server.url(), "alice")
+        .await
+        .expect("Failed to connect");
+
+    // then (期待する結果):
+    let connected = alice.expect_type("room-connected").await;
+    assert_eq!(connected["client_id"], "alice");
+    assert_eq!(connected["participants"][0]["client_id"], "alice");
+}
+
+#[tokio::test]
+async fn test_multiple_different_clients_can_connect() {
+    // テスト項目: 異なる client_id を持つ複数のクライアントが接続できる
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let _alice = TestWsClient::connect(&server.url(), "alice")
+        .await
+        .expect("Failed to connect alice");
+    let _bob = TestWsClient::connect(&server.url(), "bob")
+        .await
+        .expect("Failed to connect bob");
+
+    // when (操作):
+    let mut charlie = TestWsClient::connect(&server.url(), "charlie")
+        .await
+        .expect("Failed to connect charlie");
+
+    // then (期待する結果):
+    let connected = charlie.expect_type("room-connected").await;
+    let participants = connected["participants"].as_array().unwrap();
+    assert_eq!(participants.len(), 3);
+}
+
+#[tokio::test]
+async fn test_duplicate_client_id_is_rejected() {
+    // テスト項目: 重複する client_id での接続が拒否される
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let _alice = TestWsClient::connect(&server.url(), "alice")
+        .await
+        .expect("Failed to connect");
+
+    // when (操作):
+    let duplicate = TestWsClient::connect(&server.url(), "alice").await;
+
+    // then (期待する結果):
+    match duplicate {
+        Err(tokio_tungstenite::tungstenite::Error::Http(response)) => {
+            assert_eq!(response.status(), 409);
+        }
+        Err(e) => panic!("Unexpected error: {}", e),
+        Ok(_) => panic!("The second connection should be rejected"),
+    }
+}
+
+#[tokio::test]
+async fn test_client_joins_room_by_slug() {
+    // テスト項目: room_slug で作成したルームに参加でき、room_id と同時に指定すると拒否される
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let room_id = server.create_room("design").await;
+
+    // when (操作):
+    let mut alice = TestWsClient::connect(&format!("{}?room_slug=design", server.url()), "alice")
+        .await
+        .expect("Failed to connect alice");
+    let both = TestWsClient::connect(
+        &format!("{}?room_slug=design&room_id={}", server.url(), room_id),
+        "bob",
+    )
+    .await;
+
+    // then (期待する結果):
+    assert_eq!(
+        alice.expect_type("room-connected").await["room_id"],
+        room_id
+    );
+    match both {
+        Err(tokio_tungstenite::tungstenite::Error::Http(response)) => {
+            assert_eq!(response.status(), 400);
+        }
+        Err(e) => panic!("Unexpected error: {}", e),
+        Ok(_) => panic!("A connection giving both room_id and room_slug should be rejected"),
+    }
+}
+
+#[tokio::test]
+async fn test_protocol_is_negotiated_with_hello() {
+    // テスト項目: hello で伝えた機能のうちサーバーが対応するものが hello-ack で返され、使わない機能のメッセージは変換される
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let mut alice = TestWsClient::connect(&server.url(), "alice")
+        .await
+        .expect("Failed to connect alice");
+    alice.expect_type("room-connected").await;
+
+    // when (操作):
+    let (mut bob, ack) =
+        TestWsClient::connect_with_features(&server.url(), "bob", &["history", "reactions"])
+            .await
+            .expect("Failed to connect bob");
+    bob.expect_type("room-connected").await;
+    alice
+        .send_json(&serde_json::json!({
+            "type": "poll",
+            "client_id": "alice",
+            "content": "Lunch?",
+            "options": ["Ramen", "Sushi"],
+            "timestamp": 1000,
+        }))
+        .await;
+
+    // then (期待する結果):
+    assert_eq!(ack["protocol_version"], 2);
+    assert_eq!(ack["features"], serde_json::json!(["history"]));
+    // bob does not understand polls and gets the question as a plain chat message
+    let received = bob.expect_type("chat").await;
+    assert_eq!(received["content"], "Lunch?");
+    assert!(received.get("poll").is_none());
+    assert_eq!(alice.expect_type("poll").await["content"], "Lunch?");
+}
+
+#[tokio::test]
+async fn test_authenticated_client_skips_connect_challenge() {
+    // テスト項目: 接続時のチャレンジが有効でも、アクセストークンで認証した接続は解なしで接続できる
+    // given (前提条件):
+    let server = TestServer::start_with(
+        |send_message| send_message,
+        |server, _| with_auth(server).with_connect_challenge(ConnectChallenge::new(true, 18)),
+    )
+    .await;
+
+    // when (操作):
+    let result = TestWsClient::connect(
+        &format!("{}?access_token={}", server.url(), access_token("alice")),
+        "alice",
+    )
+    .await;
+
+    // then (期待する結果):
+    assert!(result.is_ok());
+}
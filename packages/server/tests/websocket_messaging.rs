@@ -0,0 +1,249 @@
+//! WebSocket messaging integration tests.
+//!
+//! Tests for message broadcasting and participant notifications.
+
+use std::{sync::Arc, time::Duration};
+
+use engawa_server::usecase::RateLimiter;
+use engawa_shared::time::get_jst_timestamp;
+use serde_json::json;
+
+mod fixtures;
+use fixtures::{TestServer, TestWsClient};
+
+#[tokio::test]
+async fn test_message_broadcast() {
+    // テスト項目: メッセージが他の参加者に連番付きで配信され、送信者には送り返されない
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let mut alice = TestWsClient::connect(&server.url(), "alice")
+        .await
+        .expect("Failed to connect alice");
+    alice.expect_type("room-connected").await;
+    let mut bob = TestWsClient::connect(&server.url(), "bob")
+        .await
+        .expect("Failed to connect bob");
+    bob.expect_type("room-connected").await;
+    alice.expect_type("participant-joined").await;
+
+    // when (操作):
+    alice.send_chat("Hello from alice!").await;
+
+    // then (期待する結果):
+    let received = bob.expect_type("chat").await;
+    assert_eq!(received["client_id"], "alice");
+    assert_eq!(received["content"], "Hello from alice!");
+    assert_eq!(received["seq"], 1);
+    alice.expect_silence(Duration::from_millis(200)).await;
+
+    // bob replies to alice
+    bob.send_chat("Hello from bob!").await;
+    let reply = alice.expect_type("chat").await;
+    assert_eq!(reply["client_id"], "bob");
+    assert_eq!(reply["content"], "Hello from bob!");
+    assert_eq!(reply["seq"], 2);
+}
+
+#[tokio::test]
+async fn test_message_is_attributed_to_connection() {
+    // テスト項目: ペイロードの client_id に他のクライアントを指定しても、接続のクライアントの発言として配信される
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let mut alice = TestWsClient::connect(&server.url(), "alice")
+        .await
+        .expect("Failed to connect alice");
+    alice.expect_type("room-connected").await;
+    let mut bob = TestWsClient::connect(&server.url(), "bob")
+        .await
+        .expect("Failed to connect bob");
+    bob.expect_type("room-connected").await;
+    alice.expect_type("participant-joined").await;
+
+    // when (操作):
+    alice
+        .send_json(&json!({
+            "type": "chat",
+            "client_id": "bob",
+            "content": "I am bob",
+            "timestamp": get_jst_timestamp(),
+        }))
+        .await;
+    alice
+        .send_json(&json!({
+            "type": "poll",
+            "client_id": "bob",
+            "content": "Lunch?",
+            "options": ["yes", "no"],
+            "timestamp": get_jst_timestamp(),
+        }))
+        .await;
+
+    // then (期待する結果):
+    let chat = bob.expect_type("chat").await;
+    assert_eq!(chat["client_id"], "alice");
+    assert_eq!(chat["content"], "I am bob");
+    let poll = bob.expect_type("poll").await;
+    assert_eq!(poll["client_id"], "alice");
+}
+
+#[tokio::test]
+async fn test_rate_limit_follows_connection() {
+    // テスト項目: ペイロードの client_id を毎回変えても、接続のクライアントの送信レートで制限される
+    // given (前提条件):
+    let rate_limiter = Arc::new(RateLimiter::new(1, 2));
+    let server = TestServer::start_with(
+        |usecase| usecase.with_rate_limiter(rate_limiter),
+        |server, _| server,
+    )
+    .await;
+    let mut alice = TestWsClient::connect(&server.url(), "alice")
+        .await
+        .expect("Failed to connect alice");
+    alice.expect_type("room-connected").await;
+
+    // when (操作):
+    for i in 0..3 {
+        alice
+            .send_json(&json!({
+                "type": "chat",
+                "client_id": format!("spoofed-{}", i),
+                "content": format!("message {}", i),
+                "timestamp": get_jst_timestamp(),
+            }))
+            .await;
+    }
+
+    // then (期待する結果):
+    let error = alice.expect_type("error").await;
+    assert_eq!(error["code"], "rate_limited");
+}
+
+#[tokio::test]
+async fn test_participant_notifications() {
+    // テスト項目: 新規参加者の接続・切断が他の参加者に通知される
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let mut alice = TestWsClient::connect(&server.url(), "alice")
+        .await
+        .expect("Failed to connect alice");
+    alice.expect_type("room-connected").await;
+
+    // when (操作):
+    // bob joins after alice, then leaves
+    let bob = TestWsClient::connect(&server.url(), "bob")
+        .await
+        .expect("Failed to connect bob");
+    let joined = alice.expect_type("participant-joined").await;
+    bob.close().await;
+    let left = alice.expect_type("participant-left").await;
+
+    // then (期待する結果):
+    assert_eq!(joined["client_id"], "bob");
+    assert_eq!(left["client_id"], "bob");
+}
+
+#[tokio::test]
+async fn test_new_client_receives_room_history() {
+    // テスト項目: 新しく接続したクライアントは room-connected の後にルームの直近のメッセージを受け取る
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let mut alice = TestWsClient::connect(&server.url(), "alice")
+        .await
+        .expect("Failed to connect alice");
+    alice.expect_type("room-connected").await;
+    alice.send_chat("first").await;
+    alice.send_chat("second").await;
+    alice.expect_silence(Duration::from_millis(200)).await;
+
+    // when (操作):
+    let mut bob = TestWsClient::connect(&server.url(), "bob")
+        .await
+        .expect("Failed to connect bob");
+
+    // then (期待する結果):
+    assert_eq!(bob.recv_json().await["type"], "room-connected");
+    let history = bob.recv_json().await;
+    assert_eq!(history["type"], "room-history");
+    let contents: Vec<_> = history["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["content"].as_str().unwrap())
+        .collect();
+    assert_eq!(contents, vec!["first", "second"]);
+}
+
+#[tokio::test]
+async fn test_activity_is_broadcast_and_listed() {
+    // テスト項目: 設定したアクティビティが本人を含む参加者に通知され、後から接続したクライアントの参加者一覧に含まれる
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let mut alice = TestWsClient::connect(&server.url(), "alice")
+        .await
+        .expect("Failed to connect alice");
+    alice.expect_type("room-connected").await;
+
+    // when (操作):
+    alice
+        .send_json(&serde_json::json!({
+            "type": "set-activity",
+            "activity": "reviewing PR #42",
+        }))
+        .await;
+    let updated = alice.expect_type("activity-updated").await;
+    let mut bob = TestWsClient::connect(&server.url(), "bob")
+        .await
+        .expect("Failed to connect bob");
+
+    // then (期待する結果):
+    assert_eq!(updated["client_id"], "alice");
+    assert_eq!(updated["activity"], "reviewing PR #42");
+    let connected = bob.expect_type("room-connected").await;
+    let participants = connected["participants"].as_array().unwrap();
+    let listed = participants
+        .iter()
+        .find(|participant| participant["client_id"] == "alice")
+        .unwrap();
+    assert_eq!(listed["activity"], "reviewing PR #42");
+}
+
+#[tokio::test]
+async fn test_display_name_is_announced_and_changed() {
+    // テスト項目: 接続時に指定した表示名が参加の通知と参加者一覧に含まれ、変更すると本人を含む参加者に通知される
+    // given (前提条件):
+    let server = TestServer::start().await;
+    let mut alice = TestWsClient::connect(&server.url(), "alice")
+        .await
+        .expect("Failed to connect alice");
+    alice.expect_type("room-connected").await;
+
+    // when (操作):
+    let mut bob =
+        TestWsClient::connect(&format!("{}?display_name=Bob%20Jones", server.url()), "bob")
+            .await
+            .expect("Failed to connect bob");
+    let joined = alice.expect_type("participant-joined").await;
+    let connected = bob.expect_type("room-connected").await;
+    bob.send_json(&serde_json::json!({
+        "type": "set-display-name",
+        "display_name": "Bobby",
+    }))
+    .await;
+
+    // then (期待する結果):
+    assert_eq!(joined["display_name"], "Bob Jones");
+    let listed = connected["participants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|participant| participant["client_id"] == "bob")
+        .unwrap();
+    assert_eq!(listed["display_name"], "Bob Jones");
+    let renamed = alice.expect_type("participant-renamed").await;
+    assert_eq!(renamed["client_id"], "bob");
+    assert_eq!(renamed["display_name"], "Bobby");
+    assert_eq!(
+        bob.expect_type("participant-renamed").await["display_name"],
+        "Bobby"
+    );
+}
@@ -1,2 +1,3 @@
 pub mod logger;
+pub mod task;
 pub mod time;
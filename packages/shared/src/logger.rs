@@ -1,38 +1,246 @@
 //! Logging setup utilities for the WebSocket chat application.
 
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry, filter::ParseError, fmt::MakeWriter, layer::SubscriberExt, reload,
+    util::SubscriberInitExt,
+};
 
-/// Initialize the tracing subscriber with the specified default log level.
+/// Log levels accepted by `--log-level`
+pub const LOG_LEVELS: [&str; 6] = ["error", "warn", "info", "debug", "trace", "off"];
+
+/// Logging options shared by the binaries (flatten into the clap arguments).
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct LogArgs {
+    /// Log level of this application's crates; overrides RUST_LOG
+    #[arg(long, value_parser = LOG_LEVELS)]
+    pub log_level: Option<String>,
+
+    /// Additional filter directives in EnvFilter syntax, applied on top of the log level
+    /// (e.g. engawa_server::ui::handler=trace,tower_http=debug)
+    #[arg(long)]
+    pub log_filter: Option<String>,
+}
+
+/// Handle replacing the log filter of the installed subscriber (e.g. on SIGHUP).
+#[derive(Debug, Clone)]
+pub struct LogReloader {
+    binary_name: String,
+    default_log_level: String,
+    handle: reload::Handle<EnvFilter, Registry>,
+}
+
+impl LogReloader {
+    /// Replace the log filter with the one built from `args`, the same way as at startup
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError` if `--log-filter` (or `RUST_LOG`) is not valid EnvFilter syntax;
+    /// the current filter is kept then
+    pub fn reload(&self, args: &LogArgs) -> Result<(), ParseError> {
+        let filter = build_filter(&self.binary_name, &self.default_log_level, args)?;
+        // The subscriber is installed globally and never dropped, so this cannot fail
+        let _ = self.handle.reload(filter);
+        Ok(())
+    }
+}
+
+/// Initialize the tracing subscriber.
+///
+/// The application's crates (`engawa_shared` and the binary's crate) log at `--log-level`.
+/// Without `--log-level`, the `RUST_LOG` environment variable is used if set, and
+/// `default_log_level` otherwise. Directives in `--log-filter` are added on top, so a single
+/// module can be made more verbose without enabling debug logs everywhere.
 ///
-/// This function sets up logging for both the application crate and the binary.
-/// The log level can be overridden using the `RUST_LOG` environment variable.
+/// With the `console` feature and `RUSTFLAGS="--cfg tokio_unstable"`, a tokio-console layer
+/// is also installed (listening on `127.0.0.1:6669`, or `TOKIO_CONSOLE_BIND`). It sees every
+/// task and resource regardless of the log filter.
 ///
 /// # Arguments
 ///
-/// * `binary_name` - The name of the binary (e.g., "server", "client")
-/// * `default_level` - The default log level (e.g., "debug", "info", "warn", "error")
+/// * `binary_name` - The name of the binary (e.g., "engawa-server", "engawa-client")
+/// * `default_log_level` - The log level used when neither `--log-level` nor `RUST_LOG` is given
+/// * `args` - The logging options given on the command line
+///
+/// Returns a [`LogReloader`] to change the filter later.
+///
+/// # Errors
+///
+/// Returns `ParseError` if `--log-filter` (or `RUST_LOG`) is not valid EnvFilter syntax
 ///
 /// # Examples
 ///
 /// ```no_run
-/// use engawa_shared::logger::setup_logger;
+/// use engawa_shared::logger::{LogArgs, setup_logger};
 ///
-/// setup_logger("server", "debug");
+/// setup_logger("engawa-server", "debug", &LogArgs::default()).unwrap();
 /// ```
-pub fn setup_logger(binary_name: &str, default_log_level: &str) {
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
-                format!(
-                    "{}={},{}={}",
-                    env!("CARGO_PKG_NAME").replace("-", "_"),
-                    default_log_level,
-                    binary_name,
-                    default_log_level
-                )
-                .into()
-            }),
+pub fn setup_logger(
+    binary_name: &str,
+    default_log_level: &str,
+    args: &LogArgs,
+) -> Result<LogReloader, ParseError> {
+    setup_logger_with_writer(binary_name, default_log_level, args, std::io::stdout)
+}
+
+/// Initialize the tracing subscriber, writing the logs to `writer` instead of stdout.
+///
+/// Used when stdout is taken by a full-screen terminal UI, where log lines would corrupt the
+/// screen. Otherwise the same as [`setup_logger`].
+///
+/// # Errors
+///
+/// Returns `ParseError` if `--log-filter` (or `RUST_LOG`) is not valid EnvFilter syntax
+pub fn setup_logger_with_writer<W>(
+    binary_name: &str,
+    default_log_level: &str,
+    args: &LogArgs,
+    writer: W,
+) -> Result<LogReloader, ParseError>
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let (filter, handle) = reload::Layer::new(build_filter(binary_name, default_log_level, args)?);
+
+    let registry = tracing_subscriber::registry().with(
+        tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_filter(filter),
+    );
+    // console-subscriber panics unless tokio is built with its unstable instrumentation
+    #[cfg(all(feature = "console", tokio_unstable))]
+    let registry = registry.with(console_subscriber::spawn());
+    registry.init();
+    #[cfg(all(feature = "console", not(tokio_unstable)))]
+    tracing::warn!(
+        "tokio-console is disabled: rebuild with RUSTFLAGS=\"--cfg tokio_unstable\" to enable it"
+    );
+    Ok(LogReloader {
+        binary_name: binary_name.to_string(),
+        default_log_level: default_log_level.to_string(),
+        handle,
+    })
+}
+
+/// Build the EnvFilter from the logging options and `RUST_LOG`.
+fn build_filter(
+    binary_name: &str,
+    default_log_level: &str,
+    args: &LogArgs,
+) -> Result<EnvFilter, ParseError> {
+    let rust_log = std::env::var(EnvFilter::DEFAULT_ENV).ok();
+    let directives = filter_directives(binary_name, default_log_level, args, rust_log);
+    EnvFilter::builder().parse(directives)
+}
+
+/// Build the EnvFilter directives from the logging options.
+fn filter_directives(
+    binary_name: &str,
+    default_log_level: &str,
+    args: &LogArgs,
+    rust_log: Option<String>,
+) -> String {
+    // Targets use the crate name, which has underscores instead of hyphens
+    let app_level = |level: &str| {
+        format!(
+            "{}={},{}={}",
+            env!("CARGO_PKG_NAME").replace('-', "_"),
+            level,
+            binary_name.replace('-', "_"),
+            level
         )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    };
+    let base = match (&args.log_level, rust_log) {
+        (Some(level), _) => app_level(level),
+        (None, Some(rust_log)) if !rust_log.trim().is_empty() => rust_log,
+        _ => app_level(default_log_level),
+    };
+    match &args.log_filter {
+        Some(filter) if !filter.trim().is_empty() => format!("{},{}", base, filter),
+        _ => base,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_directives_default_level() {
+        // テスト項目: オプションも RUST_LOG もない場合はアプリケーションのクレートをデフォルトのレベルで出力する
+        // when (操作):
+        let directives = filter_directives("engawa-server", "info", &LogArgs::default(), None);
+
+        // then (期待する結果): バイナリ名のハイフンはクレート名に合わせてアンダースコアになる
+        assert_eq!(directives, "engawa_shared=info,engawa_server=info");
+    }
+
+    #[test]
+    fn test_filter_directives_precedence() {
+        // テスト項目: --log-level は RUST_LOG より優先され、--log-filter はその上に追加される
+        // given (前提条件):
+        let rust_log = Some("warn".to_string());
+        let level = LogArgs {
+            log_level: Some("debug".to_string()),
+            log_filter: None,
+        };
+        let filter = LogArgs {
+            log_level: None,
+            log_filter: Some("engawa_server::ui::handler=trace".to_string()),
+        };
+
+        // when (操作):
+        let with_level = filter_directives("engawa-server", "info", &level, rust_log.clone());
+        let with_filter = filter_directives("engawa-server", "info", &filter, rust_log);
+
+        // then (期待する結果):
+        assert_eq!(with_level, "engawa_shared=debug,engawa_server=debug");
+        assert_eq!(with_filter, "warn,engawa_server::ui::handler=trace");
+    }
+
+    #[test]
+    fn test_invalid_filter_is_rejected() {
+        // テスト項目: EnvFilter の構文として不正なフィルタはエラーになる
+        // given (前提条件):
+        let args = LogArgs {
+            log_level: None,
+            log_filter: Some("engawa_server=loud".to_string()),
+        };
+
+        // when (操作):
+        let directives = filter_directives("engawa-server", "info", &args, None);
+
+        // then (期待する結果):
+        assert!(EnvFilter::builder().parse(directives).is_err());
+    }
+
+    #[test]
+    fn test_reload_replaces_filter() {
+        // テスト項目: リロードでフィルタが置き換わり、不正なフィルタでは現在のフィルタが維持される
+        // given (前提条件):
+        let (_filter, handle) = reload::Layer::<_, Registry>::new(EnvFilter::new("info"));
+        let reloader = LogReloader {
+            binary_name: "engawa-server".to_string(),
+            default_log_level: "debug".to_string(),
+            handle: handle.clone(),
+        };
+        let valid = LogArgs {
+            log_level: Some("warn".to_string()),
+            log_filter: Some("tower_http=debug".to_string()),
+        };
+        let invalid = LogArgs {
+            log_level: None,
+            log_filter: Some("engawa_server=loud".to_string()),
+        };
+
+        // when (操作):
+        let reloaded = reloader.reload(&valid);
+        let rejected = reloader.reload(&invalid);
+
+        // then (期待する結果):
+        assert!(reloaded.is_ok());
+        assert!(rejected.is_err());
+        let current = handle.with_current(|filter| filter.to_string()).unwrap();
+        assert!(current.contains("engawa_server=warn"), "{}", current);
+        assert!(current.contains("tower_http=debug"), "{}", current);
+    }
 }
@@ -0,0 +1,45 @@
+//! Task spawning utilities.
+
+use std::future::Future;
+
+use tokio::task::JoinHandle;
+
+/// Spawn a task with a name shown in tokio-console.
+///
+/// Tasks are only named when built with the `console` feature and
+/// `RUSTFLAGS="--cfg tokio_unstable"`; otherwise this is `tokio::spawn`.
+///
+/// # Arguments
+///
+/// * `name` - The task name (e.g., "sequencer", "ws-pusher")
+/// * `future` - The future to run
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn example() {
+/// use engawa_shared::task::spawn;
+///
+/// let handle = spawn("worker", async { 1 + 1 });
+/// assert_eq!(handle.await.unwrap(), 2);
+/// # }
+/// ```
+#[track_caller]
+pub fn spawn<F>(name: &str, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    #[cfg(all(feature = "console", tokio_unstable))]
+    {
+        tokio::task::Builder::new()
+            .name(name)
+            .spawn(future)
+            .expect("Failed to spawn task")
+    }
+    #[cfg(not(all(feature = "console", tokio_unstable)))]
+    {
+        let _ = name;
+        tokio::spawn(future)
+    }
+}
@@ -1,6 +1,6 @@
 //! Time-related utilities with clock abstraction for testability.
 
-use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use chrono::{DateTime, FixedOffset, NaiveTime, TimeZone, Utc};
 
 /// Clock trait for dependency injection and testing
 pub trait Clock: Send + Sync {
@@ -56,6 +56,37 @@ pub fn timestamp_to_jst_rfc3339(timestamp_millis: i64) -> String {
     dt.to_rfc3339()
 }
 
+/// Convert Unix timestamp (milliseconds) to the JST time of day (e.g. `12:30`)
+pub fn timestamp_to_jst_clock(timestamp_millis: i64) -> String {
+    let jst_offset = FixedOffset::east_opt(9 * 3600).unwrap(); // JST is UTC+9
+    let dt = jst_offset
+        .timestamp_millis_opt(timestamp_millis)
+        .single()
+        .unwrap_or_default();
+    dt.format("%H:%M").to_string()
+}
+
+/// Convert Unix timestamp (milliseconds) to the JST time of day
+pub fn timestamp_to_jst_time(timestamp_millis: i64) -> NaiveTime {
+    let jst_offset = FixedOffset::east_opt(9 * 3600).unwrap(); // JST is UTC+9
+    jst_offset
+        .timestamp_millis_opt(timestamp_millis)
+        .single()
+        .unwrap_or_default()
+        .time()
+}
+
+/// Convert Unix timestamp (milliseconds) to an HTTP-date (IMF-fixdate, always GMT)
+///
+/// Used for headers such as `Last-Modified`. Sub-second precision is truncated.
+pub fn timestamp_to_http_date(timestamp_millis: i64) -> String {
+    let dt = Utc
+        .timestamp_millis_opt(timestamp_millis)
+        .single()
+        .unwrap_or_default();
+    dt.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,6 +151,34 @@ mod tests {
         assert_eq!(timestamp3, fixed_time);
     }
 
+    #[test]
+    fn test_timestamp_to_jst_time() {
+        // テスト項目: タイムスタンプが JST の時刻に変換される（UTC では前日）
+        // given (前提条件):
+        // 2023-01-01 07:15:00 JST (2022-12-31 22:15:00 UTC)
+        let timestamp = 1672498800000 + (7 * 3600 + 15 * 60) * 1000;
+
+        // when (操作):
+        let result = timestamp_to_jst_time(timestamp);
+
+        // then (期待する結果):
+        assert_eq!(result, NaiveTime::from_hms_opt(7, 15, 0).unwrap());
+    }
+
+    #[test]
+    fn test_timestamp_to_jst_clock() {
+        // テスト項目: タイムスタンプが JST の時刻（時:分）に変換される
+        // given (前提条件):
+        // 2023-01-01 12:30:45 JST in milliseconds
+        let timestamp = 1672498800000 + (12 * 3600 + 30 * 60 + 45) * 1000;
+
+        // when (操作):
+        let result = timestamp_to_jst_clock(timestamp);
+
+        // then (期待する結果):
+        assert_eq!(result, "12:30");
+    }
+
     #[test]
     fn test_timestamp_to_jst_rfc3339_format() {
         // テスト項目: タイムスタンプが正しく RFC 3339 形式に変換される
@@ -160,4 +219,18 @@ mod tests {
         // then (期待する結果):
         assert!(timestamp > 0);
     }
+
+    #[test]
+    fn test_timestamp_to_http_date_format() {
+        // テスト項目: タイムスタンプが HTTP-date（IMF-fixdate）形式に変換される
+        // given (前提条件):
+        // 2023-01-01 00:00:00 JST (= 2022-12-31 15:00:00 GMT) in milliseconds
+        let timestamp = 1672498800123;
+
+        // when (操作):
+        let result = timestamp_to_http_date(timestamp);
+
+        // then (期待する結果):
+        assert_eq!(result, "Sat, 31 Dec 2022 15:00:00 GMT");
+    }
 }